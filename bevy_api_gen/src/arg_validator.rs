@@ -1,7 +1,8 @@
+use bevy_mod_scripting_common::arg::NUMERIC_TYPES;
 use rustdoc_types::{GenericArg, GenericArgs, Type};
 
 /// A representation of valid argument types
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ArgType {
     /// The primary identifier of the type
     ///
@@ -17,6 +18,13 @@ pub enum ArgType {
         is_mut: bool,
         ref_: Box<ArgType>,
     },
+    /// `[T]`, only valid behind a reference
+    Slice(Box<ArgType>),
+    /// `[T; N]`, only literal lengths are supported
+    Array {
+        type_: Box<ArgType>,
+        len: usize,
+    },
 }
 
 impl fmt::Display for ArgType {
@@ -39,6 +47,16 @@ impl fmt::Display for ArgType {
                 ref_.fmt(f)
             }
             ArgType::Self_ => f.write_str("self"),
+            ArgType::Slice(inner) => {
+                f.write_str("[")?;
+                inner.fmt(f)?;
+                f.write_str("]")
+            }
+            ArgType::Array { type_, len } => {
+                f.write_str("[")?;
+                type_.fmt(f)?;
+                write!(f, "; {len}]")
+            }
             ArgType::Generic { base, args } => {
                 base.fmt(f)?;
                 f.write_str("<")?;
@@ -54,6 +72,106 @@ impl fmt::Display for ArgType {
     }
 }
 
+/// Parses types in the format they're displayed in, i.e. the types of the API manifest
+impl std::str::FromStr for ArgType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (type_, rest) = Self::parse_prefix(s.trim())?;
+        if rest.trim().is_empty() {
+            Ok(type_)
+        } else {
+            Err(format!("Unexpected `{rest}` after type `{type_}`"))
+        }
+    }
+}
+
+impl ArgType {
+    /// Parses a single type from the start of the string, returning it along with the unparsed remainder
+    fn parse_prefix(s: &str) -> Result<(Self, &str), String> {
+        let s = s.trim_start();
+        if let Some(rest) = s.strip_prefix('&') {
+            let rest = rest.trim_start();
+            let (is_mut, rest) = match rest.strip_prefix("mut ") {
+                Some(rest) => (true, rest),
+                None => (false, rest),
+            };
+            let (ref_, rest) = Self::parse_prefix(rest)?;
+            return Ok((
+                ArgType::Ref {
+                    is_mut,
+                    ref_: Box::new(ref_),
+                },
+                rest,
+            ));
+        }
+
+        if let Some(rest) = s.strip_prefix('[') {
+            let (inner, rest) = Self::parse_prefix(rest)?;
+            let rest = rest.trim_start();
+            if let Some(rest) = rest.strip_prefix(']') {
+                return Ok((ArgType::Slice(Box::new(inner)), rest));
+            }
+            let (len, rest) = rest
+                .strip_prefix(';')
+                .and_then(|rest| rest.split_once(']'))
+                .ok_or_else(|| format!("Unclosed array or slice in `{s}`"))?;
+            let len = len
+                .trim()
+                .parse()
+                .map_err(|_| format!("Array length `{len}` is not a literal"))?;
+            return Ok((
+                ArgType::Array {
+                    type_: Box::new(inner),
+                    len,
+                },
+                rest,
+            ));
+        }
+
+        let end = s
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+            .unwrap_or(s.len());
+        let (ident, mut rest) = s.split_at(end);
+        if ident.is_empty() {
+            return Err(format!("Expected a type in `{s}`"));
+        }
+        let base = if ident == "self" || ident == "Self" {
+            ArgType::Self_
+        } else {
+            ArgType::Base(ident.split("::").last().unwrap().to_owned())
+        };
+
+        match rest.trim_start().strip_prefix('<') {
+            Some(generics) => {
+                let mut args = Vec::default();
+                rest = generics;
+                loop {
+                    let (arg, after) = Self::parse_prefix(rest)?;
+                    args.push(arg);
+                    let after = after.trim_start();
+                    if let Some(after) = after.strip_prefix(',') {
+                        rest = after;
+                    } else if let Some(after) = after.strip_prefix('>') {
+                        rest = after;
+                        break;
+                    } else {
+                        return Err(format!("Unclosed generic arguments in `{s}`"));
+                    }
+                }
+                Ok((
+                    ArgType::Generic {
+                        base: Box::new(base),
+                        args,
+                    },
+                    rest,
+                ))
+            }
+            None => Ok((base, rest)),
+        }
+    }
+}
+
 impl TryFrom<Type> for ArgType {
     type Error = String;
 
@@ -75,6 +193,8 @@ impl TryFrom<&Type> for ArgType {
                         for generic in args {
                             match generic {
                                 GenericArg::Type(type_) => processed_args.push(type_.try_into()?),
+                                // lifetimes make no difference to scripts, i.e. `Cow<'a, str>` is treated as `Cow<str>`
+                                GenericArg::Lifetime(_) => {}
                                 _ => {
                                     return Err(
                                         "Only types are allowed as generic arguments".to_owned()
@@ -114,6 +234,13 @@ impl TryFrom<&Type> for ArgType {
                 is_mut: *mutable,
                 ref_: Box::new(type_.as_ref().try_into()?),
             }),
+            Type::Slice(type_) => Ok(Self::Slice(Box::new(type_.as_ref().try_into()?))),
+            Type::Array { type_, len } => Ok(Self::Array {
+                type_: Box::new(type_.as_ref().try_into()?),
+                len: len
+                    .parse()
+                    .map_err(|_| format!("Array length `{len}` is not a literal"))?,
+            }),
             _ => Err("".to_owned()),
         }
     }
@@ -127,7 +254,9 @@ impl ArgType {
     {
         match self {
             ArgType::Base(b) => f(Ok(b)),
-            ArgType::Ref { is_mut: _, ref_ } => ref_.map_base(f),
+            ArgType::Ref { is_mut: _, ref_ }
+            | ArgType::Slice(ref_)
+            | ArgType::Array { type_: ref_, .. } => ref_.map_base(f),
             ArgType::Self_ => f(Err(())),
             ArgType::Generic { base, .. } => base.map_base(f),
         }
@@ -140,7 +269,9 @@ impl ArgType {
     {
         match self {
             ArgType::Base(b) => f(Ok(b)),
-            ArgType::Ref { is_mut: _, ref_ } => ref_.map_base_mut(f),
+            ArgType::Ref { is_mut: _, ref_ }
+            | ArgType::Slice(ref_)
+            | ArgType::Array { type_: ref_, .. } => ref_.map_base_mut(f),
             ArgType::Self_ => f(Err(())),
             ArgType::Generic { base, .. } => base.map_base_mut(f),
         }
@@ -150,43 +281,302 @@ impl ArgType {
         self.map_base(|b| b.is_err())
     }
 
+    /// The kind of receiver this type describes if it's `self`, `&self` or `&mut self`
+    pub fn receiver(&self) -> Option<Receiver> {
+        match self {
+            ArgType::Self_ => Some(Receiver::Value),
+            ArgType::Ref { is_mut, ref_ } if matches!(ref_.as_ref(), ArgType::Self_) => {
+                Some(if *is_mut {
+                    Receiver::MutRef
+                } else {
+                    Receiver::Ref
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if this is a slice or a reference to one
+    pub fn is_slice(&self) -> bool {
+        match self {
+            ArgType::Slice(_) => true,
+            ArgType::Ref { ref_, .. } => ref_.is_slice(),
+            _ => false,
+        }
+    }
+
+    /// Returns true if this is a `String`, `str` or `Cow<str>`, or a reference to one
+    pub fn is_string(&self) -> bool {
+        match self {
+            ArgType::Base(b) => b == "String" || b == "str",
+            ArgType::Generic { base, args } => {
+                base.base_ident() == Some("Cow")
+                    && matches!(args.as_slice(), [ArgType::Base(b)] if b == "str")
+            }
+            ArgType::Ref { ref_, .. } => ref_.is_string(),
+            _ => false,
+        }
+    }
+
+    /// Returns true if this is a fixed size array
+    pub fn is_array(&self) -> bool {
+        matches!(self, ArgType::Array { .. })
+    }
+
+    /// Returns true if this is a mutable reference to a slice
+    pub fn is_mut_slice(&self) -> bool {
+        matches!(self, ArgType::Ref { is_mut: true, ref_ } if ref_.is_slice())
+    }
+
+    /// Replaces `Self` within generic arguments with the given type, i.e. `Option<Self>` becomes `Option<Vec3>`.
+    ///
+    /// Receivers and plain `Self` types are left as is, since those are printed as `self`
+    pub fn resolve_generic_self(self, self_type: &str) -> Self {
+        match self {
+            ArgType::Generic { base, args } => ArgType::Generic {
+                base,
+                args: args
+                    .into_iter()
+                    .map(|a| match a {
+                        ArgType::Self_ => ArgType::Base(self_type.to_owned()),
+                        a => a.resolve_generic_self(self_type),
+                    })
+                    .collect(),
+            },
+            ArgType::Ref { is_mut, ref_ } => ArgType::Ref {
+                is_mut,
+                ref_: Box::new(ref_.resolve_generic_self(self_type)),
+            },
+            ArgType::Slice(inner) => ArgType::Slice(Box::new(match *inner {
+                ArgType::Self_ => ArgType::Base(self_type.to_owned()),
+                a => a.resolve_generic_self(self_type),
+            })),
+            ArgType::Array { type_, len } => ArgType::Array {
+                type_: Box::new(match *type_ {
+                    ArgType::Self_ => ArgType::Base(self_type.to_owned()),
+                    a => a.resolve_generic_self(self_type),
+                }),
+                len,
+            },
+            a => a,
+        }
+    }
+
+    /// Replaces the generic parameters with the given names by the given types, i.e. `Option<&T>` becomes `Option<&Transform>`
+    pub fn substitute_generics(self, params: &[(&str, ArgType)]) -> Self {
+        match self {
+            ArgType::Base(b) => match params.iter().find(|(param, _)| *param == b) {
+                Some((_, type_)) => type_.clone(),
+                None => ArgType::Base(b),
+            },
+            ArgType::Generic { base, args } => ArgType::Generic {
+                base,
+                args: args
+                    .into_iter()
+                    .map(|a| a.substitute_generics(params))
+                    .collect(),
+            },
+            ArgType::Ref { is_mut, ref_ } => ArgType::Ref {
+                is_mut,
+                ref_: Box::new(ref_.substitute_generics(params)),
+            },
+            ArgType::Slice(inner) => ArgType::Slice(Box::new(inner.substitute_generics(params))),
+            ArgType::Array { type_, len } => ArgType::Array {
+                type_: Box::new(type_.substitute_generics(params)),
+                len,
+            },
+            ArgType::Self_ => ArgType::Self_,
+        }
+    }
+
+    /// Returns true if this is a `Result<T, E>`
+    pub fn is_result(&self) -> bool {
+        matches!(self, ArgType::Generic { base, args } if base.base_ident() == Some("Result") && args.len() == 2)
+    }
+
+    /// Drops the error type of a `Result<T, E>`, leaving `Result<T>`. Other types are left as is.
+    ///
+    /// Errors are only ever formatted into script errors, so only the `Ok` type needs a wrapper
+    pub fn strip_result_error(self) -> Self {
+        match self {
+            ArgType::Generic { base, mut args } if base.base_ident() == Some("Result") => {
+                args.truncate(1);
+                ArgType::Generic { base, args }
+            }
+            a => a,
+        }
+    }
+
+    /// Returns the key and value types if this is a `HashMap<K, V>`
+    pub fn map_types(&self) -> Option<(&ArgType, &ArgType)> {
+        match self {
+            ArgType::Generic { base, args } if base.base_ident() == Some("HashMap") => {
+                match args.as_slice() {
+                    [key, value] => Some((key, value)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
     /// Retrieves the base ident if this type is resolved otherwise returns None (i.e. in the case of a self receiver)
     pub fn base_ident(&self) -> Option<&str> {
         match self {
             ArgType::Base(b) => Some(b),
-            ArgType::Ref { is_mut: _, ref_ } => ref_.base_ident(),
+            ArgType::Ref { is_mut: _, ref_ }
+            | ArgType::Slice(ref_)
+            | ArgType::Array { type_: ref_, .. } => ref_.base_ident(),
             ArgType::Self_ => None,
             ArgType::Generic { base, .. } => base.base_ident(),
         }
     }
 }
 
-#[derive(PartialEq, Eq)]
+/// The way a method takes its `self` receiver
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Receiver {
+    /// `self`, consumes the value
+    Value,
+    /// `&self`
+    Ref,
+    /// `&mut self`
+    MutRef,
+}
+
+impl fmt::Display for Receiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Receiver::Value => f.write_str("self"),
+            Receiver::Ref => f.write_str("&self"),
+            Receiver::MutRef => f.write_str("&mut self"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub enum ArgWrapperType {
     Raw,
     Wrapped,
+    /// unknown types passed around as `ReflectedValue`s
+    Reflected,
     /// in case of `self` argument
     None,
 }
 
 impl ArgWrapperType {
     pub fn with_config(self_type: &str, type_: &ArgType, config: &Config) -> Option<Self> {
+        // strings are received as the script language's string type, borrowed as `&str` and copied only if an owned
+        // string is expected. Mutable references to strings would alias immutable script strings and are not supported
+        if type_.is_string() {
+            return match type_ {
+                ArgType::Ref { is_mut: true, .. } => None,
+                _ => Some(ArgWrapperType::Raw),
+            };
+        }
+
+        // `&[T]` takes on the wrapper of `T`, scripts pass in arrays which are collected into a `Vec<T>` first.
+        // mutable slices are not supported, since they would alias the script side values
+        if let ArgType::Ref { is_mut, ref_ } = type_ {
+            if let ArgType::Slice(inner) = ref_.as_ref() {
+                if *is_mut {
+                    return None;
+                }
+                return match (inner.as_ref(), Self::with_config(self_type, inner, config)?) {
+                    (ArgType::Base(_), w @ (ArgWrapperType::Raw | ArgWrapperType::Wrapped)) => {
+                        Some(w)
+                    }
+                    _ => None,
+                };
+            }
+        }
+
+        if let ArgType::Slice(_) = type_ {
+            return None;
+        }
+
+        // `[T; N]` takes on the wrapper of `T`, the length of script arrays is validated at runtime.
+        // references to arrays are not supported
+        match type_ {
+            ArgType::Array { type_: inner, .. } => {
+                return match (inner.as_ref(), Self::with_config(self_type, inner, config)?) {
+                    (ArgType::Base(_), w @ (ArgWrapperType::Raw | ArgWrapperType::Wrapped)) => {
+                        Some(w)
+                    }
+                    _ => None,
+                };
+            }
+            ArgType::Ref { ref_, .. } if ref_.is_array() => return None,
+            _ => {}
+        }
+
+        // `Option<T>`, `Vec<T>` and `Result<T>` (with the error type stripped) take on the wrapper of `T`,
+        // other generics are not supported
+        if let ArgType::Generic { base, args } = type_ {
+            return match (base.base_ident(), args.as_slice()) {
+                (Some("Option" | "Vec" | "Result"), [inner @ ArgType::Base(_)]) => {
+                    match Self::with_config(self_type, inner, config)? {
+                        w @ (ArgWrapperType::Raw | ArgWrapperType::Wrapped) => Some(w),
+                        _ => None,
+                    }
+                }
+                // `HashMap<K, V>` takes on the wrapper of `V`, keys must be primitives to index script tables with
+                (Some("HashMap"), [key @ ArgType::Base(_), value @ ArgType::Base(_)]) => {
+                    if Self::with_config(self_type, key, config)? != ArgWrapperType::Raw {
+                        return None;
+                    }
+                    match Self::with_config(self_type, value, config)? {
+                        w @ (ArgWrapperType::Raw | ArgWrapperType::Wrapped) => Some(w),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+        }
+
         let base_ident = type_.base_ident().unwrap_or(self_type);
         type_
             .is_self()
             .then_some(ArgWrapperType::None)
-            .or_else(|| {
-                config
-                    .primitives
-                    .contains(base_ident)
-                    .then_some(ArgWrapperType::Raw)
-            })
-            .or_else(|| {
-                config
-                    .types
-                    .contains_key(base_ident)
-                    .then_some(ArgWrapperType::Wrapped)
-            })
+            .or_else(|| Self::for_base_ident(base_ident, config))
+    }
+
+    /// The wrapper of a plain type given its base identifier, shared by method arguments, fields and operators
+    /// so that primitives added in the config behave the same everywhere
+    pub fn for_base_ident(base_ident: &str, config: &Config) -> Option<Self> {
+        if config.is_primitive(base_ident) {
+            Some(ArgWrapperType::Raw)
+        } else if config.types.contains_key(base_ident) {
+            Some(ArgWrapperType::Wrapped)
+        } else {
+            None
+        }
+    }
+
+    /// The reason a `HashMap<K, V>` cannot be wrapped due to its key type, if that's the case
+    pub fn map_key_error(type_: &ArgType, config: &Config) -> Option<String> {
+        let (key, _) = type_.map_types()?;
+        match key {
+            ArgType::Base(b) if config.is_primitive(b) => None,
+            ArgType::Base(b) if config.types.contains_key(b) => Some(format!(
+                "Unsupported map key {key}, wrapped types cannot be used as keys of script tables"
+            )),
+            _ => Some(format!(
+                "Unsupported map key {key}, only primitives can be used as keys of script tables"
+            )),
+        }
+    }
+
+    /// The wrapper to fall back on for types without a proxy, only plain types and shared references to them can be reflected
+    pub fn reflected_fallback(type_: &ArgType) -> Option<Self> {
+        match type_ {
+            ArgType::Base(_) => Some(ArgWrapperType::Reflected),
+            ArgType::Ref {
+                is_mut: false,
+                ref_,
+            } if matches!(ref_.as_ref(), ArgType::Base(_)) => Some(ArgWrapperType::Reflected),
+            _ => None,
+        }
     }
 }
 
@@ -195,19 +585,38 @@ impl fmt::Display for ArgWrapperType {
         match self {
             ArgWrapperType::Raw => f.write_str("Raw"),
             ArgWrapperType::Wrapped => f.write_str("Wrapped"),
+            ArgWrapperType::Reflected => f.write_str("Reflected"),
             ArgWrapperType::None => f.write_str("None"),
         }
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct Arg {
     pub type_: ArgType,
     pub wrapper: ArgWrapperType,
+    /// The parameter name to validate this argument as, only valid for raw numeric arguments
+    pub name: Option<String>,
 }
 
 impl Arg {
     pub fn new(type_: ArgType, wrapper: ArgWrapperType) -> Self {
-        Self { type_, wrapper }
+        Self {
+            type_,
+            wrapper,
+            name: None,
+        }
+    }
+
+    /// Names this argument so that it's validated as a number if it's a raw numeric type,
+    /// does nothing otherwise
+    pub fn with_validated_name(mut self, name: &str) -> Self {
+        let is_numeric =
+            matches!(&self.type_, ArgType::Base(b) if NUMERIC_TYPES.contains(&b.as_str()));
+        if self.wrapper == ArgWrapperType::Raw && is_numeric {
+            self.name = Some(name.to_owned());
+        }
+        self
     }
 }
 
@@ -219,10 +628,216 @@ impl fmt::Display for Arg {
         let inner = self.type_.to_string();
 
         match self.wrapper {
-            ArgWrapperType::Raw | ArgWrapperType::Wrapped => {
-                write!(f, "{}({inner})", self.wrapper)
+            ArgWrapperType::Raw | ArgWrapperType::Wrapped | ArgWrapperType::Reflected => {
+                match &self.name {
+                    Some(name) => write!(f, "{}({inner} as {name})", self.wrapper),
+                    None => write!(f, "{}({inner})", self.wrapper),
+                }
             }
             ArgWrapperType::None => f.write_str(&inner),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reflected_fallback() {
+        let base = ArgType::Base("Foo".to_owned());
+        let arg = Arg::new(
+            ArgType::Ref {
+                is_mut: false,
+                ref_: Box::new(ArgType::Base("Foo".to_owned())),
+            },
+            ArgWrapperType::reflected_fallback(&base).unwrap(),
+        );
+        assert_eq!(arg.to_string(), "Reflected(&Foo)");
+
+        let mut_ref = ArgType::Ref {
+            is_mut: true,
+            ref_: Box::new(base),
+        };
+        assert!(ArgWrapperType::reflected_fallback(&mut_ref).is_none());
+
+        let generic = ArgType::Generic {
+            base: Box::new(ArgType::Base("Vec".to_owned())),
+            args: vec![ArgType::Base("Foo".to_owned())],
+        };
+        assert!(ArgWrapperType::reflected_fallback(&generic).is_none());
+    }
+
+    #[test]
+    fn option_of_self() {
+        let option = ArgType::Generic {
+            base: Box::new(ArgType::Base("Option".to_owned())),
+            args: vec![ArgType::Self_],
+        }
+        .resolve_generic_self("Vec3");
+        let arg = Arg::new(option, ArgWrapperType::Wrapped);
+        assert_eq!(arg.to_string(), "Wrapped(Option<Vec3>)");
+    }
+
+    #[test]
+    fn result_error_is_stripped() {
+        let result = ArgType::Generic {
+            base: Box::new(ArgType::Base("Result".to_owned())),
+            args: vec![ArgType::Self_, ArgType::Base("QueryEntityError".to_owned())],
+        };
+        assert!(result.is_result());
+
+        let result = result.resolve_generic_self("Vec3").strip_result_error();
+        let arg = Arg::new(result, ArgWrapperType::Wrapped);
+        assert_eq!(arg.to_string(), "Wrapped(Result<Vec3>)");
+    }
+
+    #[test]
+    fn array_of_self() {
+        let array = ArgType::Array {
+            type_: Box::new(ArgType::Self_),
+            len: 3,
+        }
+        .resolve_generic_self("Vec3");
+        assert!(array.is_array());
+        let arg = Arg::new(array, ArgWrapperType::Wrapped);
+        assert_eq!(arg.to_string(), "Wrapped([Vec3; 3])");
+    }
+
+    #[test]
+    fn map_keys() {
+        let mut config: Config = toml::from_str(
+            r#"
+            imports = ""
+            other = ""
+            lua_api_defaults = ""
+            primitives = ["String", "f32"]
+            manual_lua_types = []
+            types = [{ type = "Vec3" }]
+            "#,
+        )
+        .unwrap();
+        let vec3 = config.types_.pop().unwrap();
+        config.types.insert(vec3.type_.clone(), vec3);
+
+        let map = |key: &str, value: &str| ArgType::Generic {
+            base: Box::new(ArgType::Base("HashMap".to_owned())),
+            args: vec![
+                ArgType::Base(key.to_owned()),
+                ArgType::Base(value.to_owned()),
+            ],
+        };
+
+        let valid = map("String", "Vec3");
+        assert_eq!(
+            ArgWrapperType::with_config("Foo", &valid, &config),
+            Some(ArgWrapperType::Wrapped)
+        );
+        assert_eq!(ArgWrapperType::map_key_error(&valid, &config), None);
+
+        let wrapped_key = map("Vec3", "f32");
+        assert_eq!(
+            ArgWrapperType::with_config("Foo", &wrapped_key, &config),
+            None
+        );
+        assert!(ArgWrapperType::map_key_error(&wrapped_key, &config)
+            .unwrap()
+            .contains("wrapped types cannot be used as keys"));
+    }
+
+    #[test]
+    fn custom_primitives() {
+        let config: Config = toml::from_str(
+            r#"
+            imports = ""
+            other = ""
+            lua_api_defaults = ""
+            primitives = ["Fixed"]
+            manual_lua_types = []
+            types = []
+            "#,
+        )
+        .unwrap();
+
+        let fixed = ArgType::Base("Fixed".to_owned());
+        assert_eq!(
+            ArgWrapperType::for_base_ident("Fixed", &config),
+            Some(ArgWrapperType::Raw)
+        );
+        assert_eq!(
+            ArgWrapperType::with_config("Foo", &fixed, &config),
+            Some(ArgWrapperType::Raw)
+        );
+        let option = ArgType::Generic {
+            base: Box::new(ArgType::Base("Option".to_owned())),
+            args: vec![fixed],
+        };
+        assert_eq!(
+            ArgWrapperType::with_config("Foo", &option, &config),
+            Some(ArgWrapperType::Raw)
+        );
+    }
+
+    #[test]
+    fn receivers() {
+        let by_ref = ArgType::Ref {
+            is_mut: false,
+            ref_: Box::new(ArgType::Self_),
+        };
+        let by_mut_ref = ArgType::Ref {
+            is_mut: true,
+            ref_: Box::new(ArgType::Self_),
+        };
+        assert_eq!(ArgType::Self_.receiver(), Some(Receiver::Value));
+        assert_eq!(by_ref.receiver(), Some(Receiver::Ref));
+        assert_eq!(by_mut_ref.receiver(), Some(Receiver::MutRef));
+        assert_eq!(by_mut_ref.receiver().unwrap().to_string(), "&mut self");
+        assert_eq!(ArgType::Base("Vec3".to_owned()).receiver(), None);
+    }
+
+    #[test]
+    fn slice_of_self() {
+        let slice = ArgType::Ref {
+            is_mut: false,
+            ref_: Box::new(ArgType::Slice(Box::new(ArgType::Self_))),
+        }
+        .resolve_generic_self("Vec3");
+        assert!(slice.is_slice());
+        assert!(!slice.is_mut_slice());
+        let arg = Arg::new(slice, ArgWrapperType::Wrapped);
+        assert_eq!(arg.to_string(), "Wrapped(&[Vec3])");
+
+        let mut_slice = ArgType::Ref {
+            is_mut: true,
+            ref_: Box::new(ArgType::Slice(Box::new(ArgType::Base("u32".to_owned())))),
+        };
+        assert!(mut_slice.is_mut_slice());
+    }
+
+    #[test]
+    fn parse_displayed_types() {
+        for type_ in [
+            "self",
+            "&mut self",
+            "f32",
+            "&[Vec3]",
+            "[f32; 4]",
+            "Option<Vec3>",
+            "HashMap<String,Vec<&Entity>>",
+        ] {
+            assert_eq!(type_.parse::<ArgType>().unwrap().to_string(), type_);
+        }
+        assert!("Option<Vec3".parse::<ArgType>().is_err());
+        assert!("[f32; N]".parse::<ArgType>().is_err());
+    }
+
+    #[test]
+    fn substitute_generics() {
+        let type_: ArgType = "Option<&[T; 2]>".parse().unwrap();
+        let params = [("T", "Vec<Transform>".parse().unwrap())];
+        assert_eq!(
+            type_.substitute_generics(&params).to_string(),
+            "Option<&[Vec<Transform>; 2]>"
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+};
+
+/// The name of the sidecar file storing the hash of the inputs the output was last generated from
+pub const CACHE_FILE_NAME: &str = ".bevy_api_gen_cache";
+
+/// Tracks whether the generated output is up to date with its inputs,
+/// i.e. the rustdoc json files, the config file and the command line arguments
+pub struct Cache {
+    path: PathBuf,
+    output: PathBuf,
+    hash: String,
+}
+
+impl Cache {
+    /// Hashes the given input files and arguments, the cache is stored next to the given output file
+    pub fn new<'a>(
+        output: &Path,
+        inputs: impl IntoIterator<Item = &'a str>,
+        args: impl IntoIterator<Item = String>,
+    ) -> io::Result<Self> {
+        let mut hasher = DefaultHasher::new();
+        env!("CARGO_PKG_VERSION").hash(&mut hasher);
+
+        for input in inputs {
+            input.hash(&mut hasher);
+            fs::read(input)?.hash(&mut hasher);
+        }
+
+        for arg in args {
+            arg.hash(&mut hasher);
+        }
+
+        let path = output
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(CACHE_FILE_NAME);
+
+        Ok(Self {
+            path,
+            output: output.to_owned(),
+            hash: format!("{:016x}", hasher.finish()),
+        })
+    }
+
+    /// Returns true if the output exists and was generated from the same inputs
+    pub fn is_valid(&self) -> bool {
+        self.output.exists()
+            && fs::read_to_string(&self.path).map_or(false, |hash| hash.trim() == self.hash)
+    }
+
+    /// Records the current inputs as the ones the output was generated from
+    pub fn store(&self) -> io::Result<()> {
+        fs::write(&self.path, &self.hash)
+    }
+}
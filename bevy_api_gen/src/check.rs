@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use crate::Newtype;
+
+/// What became of all overloads and instantiations of one method of a wrapped type
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MethodOutcome {
+    /// True if at least one of them was wrapped
+    pub wrapped: bool,
+    /// The reasons the others were excluded, without duplicates
+    pub reasons: Vec<String>,
+}
+
+impl MethodOutcome {
+    pub fn record(&mut self, errors: &[String]) {
+        if errors.is_empty() {
+            self.wrapped = true;
+        }
+        for e in errors {
+            if !self.reasons.contains(e) {
+                self.reasons.push(e.clone());
+            }
+        }
+    }
+}
+
+/// Checks the methods named in the config of a type against what was found for it, `outcomes` being keyed by method name,
+/// or by `Trait::method` for trait methods colliding with others.
+///
+/// Returns a description of each method which is configured but not found,
+/// and of each method which is asked for but was excluded entirely
+pub fn check_methods(
+    type_name: &str,
+    config: &Newtype,
+    outcomes: &BTreeMap<String, MethodOutcome>,
+) -> Vec<String> {
+    // plain names also refer to colliding trait methods, like they do for overrides
+    let matching = |key: &str| {
+        outcomes
+            .iter()
+            .filter(|(k, _)| {
+                k.as_str() == key || (!key.contains("::") && k.rsplit("::").next() == Some(key))
+            })
+            .map(|(_, outcome)| outcome)
+            .collect::<Vec<_>>()
+    };
+
+    let overrides = config
+        .method_overrides
+        .iter()
+        .map(|(key, o)| (key.as_str(), "method_overrides", !o.skip));
+    let included = config
+        .include_methods
+        .iter()
+        .flatten()
+        .map(|key| (key.as_str(), "include_methods", true));
+    let generic = config
+        .generic_methods
+        .iter()
+        .map(|g| (g.method.as_str(), "generic_methods", true));
+    let since = config
+        .since
+        .keys()
+        .map(|key| (key.as_str(), "since", false));
+
+    let mut problems = Vec::default();
+    for (key, entry, is_wanted) in overrides.chain(included).chain(generic).chain(since) {
+        let outcomes = matching(key);
+        if outcomes.is_empty() {
+            problems.push(format!(
+                "`{type_name}`: `{key}` in `{entry}` does not match any method"
+            ));
+        } else if is_wanted && !outcomes.iter().any(|o| o.wrapped) {
+            let reasons = outcomes
+                .iter()
+                .flat_map(|o| o.reasons.iter().map(String::as_str))
+                .collect::<Vec<_>>();
+            problems.push(format!(
+                "`{type_name}`: `{key}` in `{entry}` is excluded entirely: {}",
+                reasons.join(", ")
+            ));
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use crate::Newtype;
+
+    use super::{check_methods, MethodOutcome};
+
+    #[test]
+    fn configured_methods_are_checked() {
+        let config: Newtype = toml::from_str(
+            r#"
+            type = "Vec3"
+            include_methods = ["dot", "Add::add", "lerp"]
+            since = { length = "0.10" }
+            [method_overrides]
+            cross = { skip = true }
+            "#,
+        )
+        .unwrap();
+
+        let mut outcomes = BTreeMap::default();
+        outcomes
+            .entry("dot".to_owned())
+            .or_insert_with(MethodOutcome::default)
+            .record(&[]);
+        outcomes
+            .entry("Add::add".to_owned())
+            .or_insert_with(MethodOutcome::default)
+            .record(&["Unsupported argument Vec<f32>".to_owned()]);
+        outcomes
+            .entry("cross".to_owned())
+            .or_insert_with(MethodOutcome::default)
+            .record(&["Skipped in the config".to_owned()]);
+
+        let problems = check_methods("Vec3", &config, &outcomes);
+        assert_eq!(
+            problems,
+            vec![
+                "`Vec3`: `Add::add` in `include_methods` is excluded entirely: Unsupported argument Vec<f32>",
+                "`Vec3`: `lerp` in `include_methods` does not match any method",
+                "`Vec3`: `length` in `since` does not match any method",
+            ]
+        );
+
+        // plain names refer to colliding trait methods as well
+        let mut outcomes = BTreeMap::default();
+        outcomes.insert(
+            "Add::add".to_owned(),
+            MethodOutcome {
+                wrapped: true,
+                reasons: Vec::default(),
+            },
+        );
+        let config: Newtype =
+            toml::from_str("type = \"Vec3\"\ninclude_methods = [\"add\"]").unwrap();
+        assert!(check_methods("Vec3", &config, &outcomes).is_empty());
+    }
+}
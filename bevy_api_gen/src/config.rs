@@ -1,10 +1,12 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use indexmap::IndexMap;
 use rustdoc_types::{Crate, Item, ItemEnum, Visibility};
 use serde::Deserialize;
 
+use crate::CoverageFormat;
+
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 pub struct Args {
@@ -16,9 +18,172 @@ pub struct Args {
     #[clap(short, long, value_parser)]
     pub config: String,
 
+    /// The path to write the generated code to, printed to stdout if not given
+    #[clap(short, long, value_parser)]
+    pub output: Option<String>,
+
+    /// if true the generated code is formatted with `rustfmt`, it's left unformatted with a warning if rustfmt is not installed
+    #[clap(long)]
+    pub format: bool,
+
+    /// if true the output is regenerated even if none of the inputs changed since the last run,
+    /// only applies when an output file is given
+    #[clap(long)]
+    pub force: bool,
+
     /// if true the excluded methods will show up as commented out code with reasons for exclusion
     #[clap(long)]
     pub print_errors: bool,
+
+    /// if true the reasons for excluding methods, variant constructors and operators are printed to stderr
+    /// along with the type and item they belong to, independently of `--print-errors`
+    #[clap(long)]
+    pub verbose: bool,
+
+    /// if true numeric method arguments are validated with errors naming the offending parameter
+    #[clap(long)]
+    pub validate_numeric_args: bool,
+
+    /// if true deprecated methods warn scripts calling them once, their deprecation is noted in the docs either way
+    #[clap(long)]
+    pub warn_deprecated: bool,
+
+    /// if true const functions without arguments are evaluated at compile time instead of on every call
+    #[clap(long)]
+    pub evaluate_const_fns: bool,
+
+    /// if true method arguments and return values of unknown types are passed as `ReflectedValue`s instead of excluding the method,
+    /// mismatched types are then only caught at runtime
+    #[clap(long)]
+    pub reflect_unknown_types: bool,
+
+    /// the scripting languages to generate wrappers and API providers for, can be given multiple times
+    #[clap(long = "language", value_enum, default_values_t = vec![Language::Lua])]
+    pub languages: Vec<Language>,
+
+    /// if set, prints how many methods, fields and operators of each type were wrapped to stderr
+    #[clap(long, value_enum)]
+    pub coverage: Option<CoverageFormat>,
+
+    /// if set, no output is written, instead the included and excluded methods of each type along with the grouped reasons
+    /// for their exclusion, as well as the wrapped fields and operators are printed to stdout
+    #[clap(long, value_enum)]
+    pub stats: Option<CoverageFormat>,
+
+    /// if true no output is written, instead the config is checked against the given crates and the program exits with an error
+    /// listing the configured types and methods which were not found, and the configured methods which would be excluded entirely
+    #[clap(long)]
+    pub config_check: bool,
+
+    /// if true the output is treated as a directory, and the wrappers of each source crate are written to their own module,
+    /// with a `mod.rs` re-exporting them and containing the API providers
+    #[clap(long, requires = "output")]
+    pub split_by_crate: bool,
+
+    /// if true a JSON description of every generated type, its methods, fields and operators
+    /// is written next to the output as `<output>.api.json`
+    #[clap(long, requires = "output")]
+    pub manifest: bool,
+
+    /// if true teal type definitions of the generated lua wrappers are written next to the output as `<output>.d.tl`,
+    /// types which teal cannot describe are declared as `any`
+    #[clap(long, requires = "output")]
+    pub teal: bool,
+
+    /// if true a lua stub file with `lua-language-server` annotations of the generated lua wrappers
+    /// is written next to the output as `<output>.lua`
+    #[clap(long, requires = "output")]
+    pub luals: bool,
+}
+
+const LUA_KEYWORDS: &[&str] = &[
+    "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "goto", "if", "in",
+    "local", "nil", "not", "or", "repeat", "return", "then", "true", "until", "while",
+];
+
+const RHAI_KEYWORDS: &[&str] = &[
+    "true", "false", "let", "const", "if", "else", "switch", "do", "while", "until", "loop", "for",
+    "in", "continue", "break", "return", "throw", "try", "catch", "fn", "private", "import",
+    "export", "as", "this", "global", "Fn",
+];
+
+/// Words rhai reserves for future use
+const RHAI_RESERVED: &[&str] = &[
+    "var",
+    "static",
+    "shared",
+    "goto",
+    "exit",
+    "match",
+    "case",
+    "public",
+    "protected",
+    "new",
+    "use",
+    "with",
+    "module",
+    "package",
+    "super",
+    "thread",
+    "spawn",
+    "go",
+    "await",
+    "async",
+    "sync",
+    "yield",
+    "default",
+    "void",
+    "null",
+    "nil",
+];
+
+/// The scripting languages wrappers can be generated for
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    Lua,
+    Rhai,
+}
+
+impl Language {
+    /// The name used in `#[languages(..)]` attributes, impl blocks and feature flags
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::Lua => "lua",
+            Language::Rhai => "rhai",
+        }
+    }
+
+    /// The prefix of generated wrapper types, i.e. `LuaVec3`
+    pub fn wrapper_prefix(self) -> &'static str {
+        match self {
+            Language::Lua => "Lua",
+            Language::Rhai => "Rhai",
+        }
+    }
+
+    /// The attribute selecting the implementors of the given languages, each gated on the language's feature
+    pub fn on_feature_attribute(languages: &[Language]) -> String {
+        let languages = languages
+            .iter()
+            .map(|l| format!("on_feature({})", l.name()))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!("#[languages({languages})]")
+    }
+
+    /// The `cfg` attribute gating code on the language's feature
+    pub fn cfg_attribute(self) -> String {
+        format!("#[cfg(feature=\"{}\")]", self.name())
+    }
+
+    /// Returns true if scripts cannot use the given identifier as a method or field name,
+    /// since it is a keyword of the language or reserved for future use
+    pub fn is_keyword(self, ident: &str) -> bool {
+        match self {
+            Language::Lua => LUA_KEYWORDS.contains(&ident),
+            Language::Rhai => RHAI_KEYWORDS.contains(&ident) || RHAI_RESERVED.contains(&ident),
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -39,6 +204,93 @@ pub struct Config {
     pub primitives: HashSet<String>,
 
     pub manual_lua_types: Vec<ManualLuaType>,
+
+    /// Types whose rhai proxies are implemented by hand, such as the math types passed to rhai as themselves,
+    /// no rhai wrappers are generated for them and they are registered by the hand written `RhaiBevyAPIProvider` instead
+    #[serde(default)]
+    pub manual_rhai_types: HashSet<String>,
+
+    /// Controls annotation of methods which are only available since some version
+    #[serde(default)]
+    pub versioning: Versioning,
+
+    /// If true, generated type and method docstrings end with a link to the original item on docs.rs
+    #[serde(default)]
+    pub emit_docs_rs_links: bool,
+
+    /// If true, methods which cannot be wrapped are replaced with stubs raising an error describing why they are unavailable,
+    /// instead of being absent from the generated API
+    #[serde(default)]
+    pub emit_exclusion_stubs: bool,
+
+    /// If true, types implementing `Serialize` and `Deserialize` get `to_json` and `from_json` methods.
+    ///
+    /// The methods are only generated for Lua, where they use the `serde_json` re-exported by `bevy_mod_scripting_lua`,
+    /// rhai wrappers don't get them
+    #[serde(default)]
+    pub emit_serde_methods: bool,
+}
+
+impl Config {
+    /// Returns true if the type with the given base identifier is passed to and from scripts as is
+    pub fn is_primitive(&self, base_ident: &str) -> bool {
+        self.primitives.contains(base_ident)
+    }
+
+    /// Returns the key and entry of the type configured for the given item of the given crate,
+    /// entries of types sharing a name are told apart by their source crate
+    pub fn newtype_of(&self, item: &Item, source: &Crate) -> Option<(&String, &Newtype)> {
+        let name = item.name.as_deref()?;
+        let candidates: Vec<_> = self.types.iter().filter(|(_, t)| t.type_ == name).collect();
+
+        match candidates.as_slice() {
+            [] => None,
+            [only] => Some(*only),
+            _ => {
+                let crate_name = source.index.get(&source.root)?.name.as_deref()?;
+                candidates
+                    .into_iter()
+                    .find(|(_, t)| t.source.0 == crate_name)
+            }
+        }
+    }
+}
+
+/// Where the versions methods became available in are read from
+#[derive(Deserialize, Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionSource {
+    /// Only the `since` tables of each type are used
+    #[default]
+    Config,
+    /// `since = "..."` attributes found in rustdoc are used as well,
+    /// versions declared in the config still take precedence
+    Rustdoc,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Versioning {
+    #[serde(default)]
+    pub source: VersionSource,
+
+    /// The predicate of the `#[cfg(...)]` attribute emitted on methods with a known version,
+    /// `{version}` is replaced with the version, with dots replaced by underscores.
+    ///
+    /// If empty only documentation notes are emitted
+    #[serde(default)]
+    pub cfg: String,
+}
+
+impl Versioning {
+    /// Returns the `#[cfg(...)]` attribute for the given version if one should be emitted
+    pub fn cfg_attribute(&self, version: &str) -> Option<String> {
+        (!self.cfg.is_empty()).then(|| {
+            format!(
+                "#[cfg({})]",
+                self.cfg.replace("{version}", &version.replace('.', "_"))
+            )
+        })
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -67,6 +319,14 @@ pub struct Newtype {
     #[serde(rename = "type")]
     pub type_: String,
 
+    /// The name of the type in the config, the generated code and scripts if it differs from `type`,
+    /// the type is then imported under this name.
+    ///
+    /// Needed to wrap types of the same name from several source crates,
+    /// entries sharing a `type` only match the type defined in the crate named by their `source`
+    #[serde(default)]
+    pub name: Option<String>,
+
     /// Override type-level docstring
     pub doc: Option<String>,
 
@@ -76,14 +336,110 @@ pub struct Newtype {
     #[serde(default)]
     pub lua_methods: Vec<String>,
 
+    /// Additional derive flags passed on to the macro, apart from `PrimitiveConversions`
+    /// which generates lua constructors and accessors from `From` impls between this type and primitives
     #[serde(default)]
     pub derive_flags: Vec<String>,
 
+    /// The full path of the type, if empty it's computed from rustdoc,
+    /// with a warning if the computed path goes through private modules
     #[serde(default)]
     pub import_path: String,
 
     #[serde(default)]
     pub traits: Vec<TraitMethods>,
+
+    /// Maps method names to the version they are available since
+    #[serde(default)]
+    pub since: BTreeMap<String, String>,
+
+    /// Controls which impl blocks contribute methods
+    #[serde(default)]
+    pub impl_filter: ImplFilter,
+
+    /// If true, the public primitive fields of the target of this type's `Deref` impl are exposed as fields prefixed with `deref_`
+    #[serde(default)]
+    pub expose_deref_fields: bool,
+
+    /// Renames or skips generated methods, keyed by the rust method name
+    #[serde(default)]
+    pub method_overrides: BTreeMap<String, MethodOverride>,
+
+    /// If given, only the listed methods are generated and all others are skipped, even if they could be wrapped.
+    /// Methods are named like the keys of `method_overrides`
+    #[serde(default)]
+    pub include_methods: Option<Vec<String>>,
+
+    /// Instantiations of generic methods to generate, generic methods without one are skipped
+    #[serde(default)]
+    pub generic_methods: Vec<GenericMethod>,
+
+    /// If true, the lua wrapper is written as a direct `TealData` implementation instead of through `impl_script_newtype!`,
+    /// for types used in hot paths. Each method, field and operator is registered with a closure specialized to its types,
+    /// values are still read through their reference on every access.
+    ///
+    /// Only `Clone` types without `lua_methods` are supported. Methods, fields and operators whose types the native fast path doesn't cover
+    /// (i.e. callbacks, collections or reflected values) are excluded, as are index operators, deref fields and derive flags other than `Sequence`.
+    /// Other languages still go through the macro
+    #[serde(default)]
+    pub native: bool,
+}
+
+/// A generic method called with concrete type arguments, i.e. `get::<Transform>` exposed as `get_transform`
+#[derive(Deserialize, Debug, PartialEq, Eq, Hash)]
+pub struct GenericMethod {
+    /// The rust name of the generic method
+    pub method: String,
+    /// The type arguments in the order of the method's type parameters
+    pub type_args: Vec<String>,
+    /// The name the instantiation is exposed as on the script side,
+    /// needs to be unique if there are several instantiations of one method
+    pub script_name: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Hash, Default)]
+pub struct MethodOverride {
+    /// The name the method is exposed as on the script side, the original method is still the one called
+    #[serde(default)]
+    pub rename: Option<String>,
+
+    /// If true the method is excluded even if it's supported.
+    /// Methods whose doc comment contains a `<!-- script:ignore -->` line are always excluded
+    #[serde(default)]
+    pub skip: bool,
+}
+
+/// Selects impl blocks by their attributes (including `#[cfg(...)]` and `#[doc(hidden)]`),
+/// patterns are matched against the attribute text with the `#[` and `]` stripped
+#[derive(Deserialize, Debug, PartialEq, Eq, Hash, Default)]
+pub struct ImplFilter {
+    /// Impl blocks with an attribute containing any of these patterns are skipped
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// If not empty, only inherent impl blocks with an attribute containing one of these patterns contribute methods.
+    /// Trait impls are selected via `traits` instead
+    #[serde(default)]
+    pub include: Vec<String>,
+}
+
+impl ImplFilter {
+    /// Returns true if an impl block with the given attributes should contribute methods
+    pub fn allows(&self, attrs: &[String], is_trait_impl: bool) -> bool {
+        let contains = |pattern: &String| {
+            attrs.iter().any(|attr| {
+                attr.trim_start_matches("#[")
+                    .trim_end_matches(']')
+                    .contains(pattern.as_str())
+            })
+        };
+
+        if self.exclude.iter().any(contains) {
+            return false;
+        }
+
+        is_trait_impl || self.include.is_empty() || self.include.iter().any(contains)
+    }
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq, Hash, Default)]
@@ -102,26 +458,36 @@ impl Default for Source {
 }
 
 impl Newtype {
+    /// The name of this type in the config, the generated code and scripts
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.type_)
+    }
+
     /// Returns true if this Type:
     /// - describes the given item element
     /// - if the element is fully described in the source crate
     /// - if the element is a struct or enum
     /// - if the element has no generics
     pub fn matches_result(&self, item: &Item, source: &Crate) -> bool {
-        match &item.inner {
-            ItemEnum::Struct(s) => {
-                if !s.generics.params.is_empty() {
-                    return false;
-                }
+        is_wrappable_item(item, source)
+    }
+}
+
+/// Returns true if the item is a public non generic struct or enum defined in the given crate
+pub fn is_wrappable_item(item: &Item, source: &Crate) -> bool {
+    match &item.inner {
+        ItemEnum::Struct(s) => {
+            if !s.generics.params.is_empty() {
+                return false;
             }
-            ItemEnum::Enum(_) => {}
-            _ => return false,
-        };
+        }
+        ItemEnum::Enum(_) => {}
+        _ => return false,
+    };
 
-        if source.external_crates.contains_key(&item.crate_id) {
-            return false;
-        };
+    if source.external_crates.contains_key(&item.crate_id) {
+        return false;
+    };
 
-        matches!(item.visibility, Visibility::Public)
-    }
+    matches!(item.visibility, Visibility::Public)
 }
@@ -0,0 +1,233 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// The format of the coverage report
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverageFormat {
+    Table,
+    Json,
+}
+
+/// The number of candidates of one kind, and how many of those were wrapped
+#[derive(Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Count {
+    pub total: usize,
+    pub wrapped: usize,
+}
+
+impl Count {
+    pub fn record(&mut self, wrapped: bool) {
+        self.total += 1;
+        if wrapped {
+            self.wrapped += 1;
+        }
+    }
+
+    /// The percentage of wrapped candidates, nothing to wrap counts as full coverage
+    pub fn percentage(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.wrapped as f64 / self.total as f64 * 100.0
+        }
+    }
+}
+
+impl std::ops::Add for Count {
+    type Output = Count;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Count {
+            total: self.total + rhs.total,
+            wrapped: self.wrapped + rhs.wrapped,
+        }
+    }
+}
+
+impl std::fmt::Display for Count {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.wrapped, self.total)
+    }
+}
+
+/// How much of a type's API made it into the generated bindings
+#[derive(Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Coverage {
+    pub methods: Count,
+    pub fields: Count,
+    /// binary and unary operators
+    pub ops: Count,
+}
+
+impl Coverage {
+    pub fn total(&self) -> Count {
+        self.methods + self.fields + self.ops
+    }
+}
+
+impl std::ops::Add for Coverage {
+    type Output = Coverage;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Coverage {
+            methods: self.methods + rhs.methods,
+            fields: self.fields + rhs.fields,
+            ops: self.ops + rhs.ops,
+        }
+    }
+}
+
+/// Writes the coverage of each type followed by the overall coverage
+pub fn write_coverage_report<'a, W: Write>(
+    types: impl Iterator<Item = (&'a str, Coverage)>,
+    format: CoverageFormat,
+    out: &mut W,
+) -> io::Result<()> {
+    let types: Vec<_> = types.collect();
+    let overall = types
+        .iter()
+        .fold(Coverage::default(), |acc, (_, c)| acc + *c);
+
+    match format {
+        CoverageFormat::Table => {
+            let width = types
+                .iter()
+                .map(|(name, _)| name.len())
+                .max()
+                .unwrap_or_default()
+                .max("Type".len());
+
+            writeln!(
+                out,
+                "{:<width$} | {:>9} | {:>9} | {:>9} | {:>8}",
+                "Type", "Methods", "Fields", "Ops", "Coverage"
+            )?;
+
+            let mut write_row = |name: &str, c: &Coverage| {
+                writeln!(
+                    out,
+                    "{name:<width$} | {:>9} | {:>9} | {:>9} | {:>7.1}%",
+                    c.methods.to_string(),
+                    c.fields.to_string(),
+                    c.ops.to_string(),
+                    c.total().percentage()
+                )
+            };
+
+            for (name, c) in &types {
+                write_row(name, c)?;
+            }
+            write_row("Total", &overall)
+        }
+        CoverageFormat::Json => {
+            let entry = |c: &Coverage| {
+                serde_json::json!({
+                    "methods": c.methods,
+                    "fields": c.fields,
+                    "ops": c.ops,
+                    "percentage": c.total().percentage(),
+                })
+            };
+
+            let report = serde_json::json!({
+                "types": types
+                    .iter()
+                    .map(|(name, c)| (name.to_string(), entry(c)))
+                    .collect::<serde_json::Map<_, _>>(),
+                "total": entry(&overall),
+            });
+
+            serde_json::to_writer_pretty(&mut *out, &report)?;
+            writeln!(out)
+        }
+    }
+}
+
+/// Writes the included and excluded methods of each type with the reasons for their exclusion, along with the wrapped fields and operators.
+///
+/// The reasons are also summed up over all types, most common first
+pub fn write_stats_report<'a, W: Write>(
+    types: impl Iterator<Item = (&'a str, Coverage, &'a BTreeMap<String, usize>)>,
+    format: CoverageFormat,
+    out: &mut W,
+) -> io::Result<()> {
+    let types: Vec<_> = types.collect();
+
+    let mut overall: BTreeMap<&str, usize> = BTreeMap::default();
+    for (reason, count) in types.iter().flat_map(|(_, _, e)| e.iter()) {
+        *overall.entry(reason).or_default() += count;
+    }
+    let mut overall: Vec<_> = overall.into_iter().collect();
+    overall.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    match format {
+        CoverageFormat::Table => {
+            let width = types
+                .iter()
+                .map(|(name, _, _)| name.len())
+                .max()
+                .unwrap_or_default()
+                .max("Type".len());
+
+            writeln!(
+                out,
+                "{:<width$} | {:>8} | {:>8} | {:>9} | {:>9}",
+                "Type", "Included", "Excluded", "Fields", "Ops"
+            )?;
+
+            for (name, c, exclusions) in &types {
+                writeln!(
+                    out,
+                    "{name:<width$} | {:>8} | {:>8} | {:>9} | {:>9}",
+                    c.methods.wrapped,
+                    c.methods.total - c.methods.wrapped,
+                    c.fields.to_string(),
+                    c.ops.to_string(),
+                )?;
+                for (reason, count) in exclusions.iter() {
+                    writeln!(out, "    {count}x {reason}")?;
+                }
+            }
+
+            writeln!(out)?;
+            writeln!(out, "Exclusion reasons:")?;
+            for (reason, count) in overall {
+                writeln!(out, "    {count}x {reason}")?;
+            }
+            Ok(())
+        }
+        CoverageFormat::Json => {
+            let report = serde_json::json!({
+                "types": types
+                    .iter()
+                    .map(|(name, c, exclusions)| {
+                        (
+                            name.to_string(),
+                            serde_json::json!({
+                                "methods": {
+                                    "included": c.methods.wrapped,
+                                    "excluded": c.methods.total - c.methods.wrapped,
+                                    "exclusions": exclusions,
+                                },
+                                "fields": c.fields,
+                                "ops": c.ops,
+                            }),
+                        )
+                    })
+                    .collect::<serde_json::Map<_, _>>(),
+                "exclusions": overall
+                    .into_iter()
+                    .map(|(reason, count)| (reason.to_owned(), count.into()))
+                    .collect::<serde_json::Map<_, _>>(),
+            });
+
+            serde_json::to_writer_pretty(&mut *out, &report)?;
+            writeln!(out)
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use rustdoc_types::{Crate, Id, ItemEnum, Visibility};
+use rustdoc_types::{Crate, Id, Item, ItemEnum, Visibility};
 
 pub(crate) fn get_path(id: &Id, source: &Crate) -> Option<Vec<Id>> {
     match source.paths.get(id) {
@@ -30,6 +30,9 @@ pub(crate) fn get_path(id: &Id, source: &Crate) -> Option<Vec<Id>> {
                 }
                 false
             });
+            // the index is a hash map, so the parents are sorted to always pick the same path
+            let mut parents: Vec<_> = parents.collect();
+            parents.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
 
             for (parent, _) in parents {
                 let path_o = get_path(parent, source);
@@ -74,3 +77,90 @@ pub(crate) fn path_to_import(path: Vec<Id>, source: &Crate) -> Vec<String> {
         })
         .unwrap()
 }
+
+/// Finds a public item with the given name directly inside the given module or re-exported into it,
+/// returns the item and the item it resolves to if it's a re-export of a local item
+fn find_public_child<'a>(
+    module: &'a Item,
+    name: &str,
+    source: &'a Crate,
+) -> Option<(&'a Item, Option<&'a Item>)> {
+    let items = match &module.inner {
+        ItemEnum::Module(m) => &m.items,
+        _ => return None,
+    };
+
+    let children = items
+        .iter()
+        .filter_map(|id| source.index.get(id))
+        .filter(|item| matches!(item.visibility, Visibility::Public));
+
+    for child in children {
+        match &child.inner {
+            ItemEnum::Import(import) if import.glob => {
+                if let Some(found) = import
+                    .id
+                    .as_ref()
+                    .and_then(|id| source.index.get(id))
+                    .filter(|target| target.id != module.id)
+                    .and_then(|target| find_public_child(target, name, source))
+                {
+                    return Some(found);
+                }
+            }
+            ItemEnum::Import(import) if import.name == name => {
+                return Some((
+                    child,
+                    import.id.as_ref().and_then(|id| source.index.get(id)),
+                ))
+            }
+            _ if child.name.as_deref() == Some(name) => return Some((child, Some(child))),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Walks the module tree from the crate root along the given import path (which starts with the crate name)
+/// and returns the first component which is not publicly reachable, if any
+pub(crate) fn first_private_component<'a>(path: &'a [String], source: &Crate) -> Option<&'a str> {
+    let mut current = source.index.get(&source.root)?;
+    for (idx, name) in path.iter().enumerate().skip(1) {
+        match find_public_child(current, name, source) {
+            Some((_, Some(target))) => current = target,
+            // re-exports of items from other crates can only be checked if they're the last component
+            Some((_, None)) if idx == path.len() - 1 => return None,
+            _ => return Some(name),
+        }
+    }
+    None
+}
+
+/// Finds a publicly reachable path through which the given item is re-exported, i.e. via a prelude
+pub(crate) fn public_reexport_path(id: &Id, source: &Crate) -> Option<Vec<String>> {
+    let mut imports: Vec<_> = source
+        .index
+        .values()
+        .filter(|item| matches!(item.visibility, Visibility::Public))
+        .filter_map(|item| match &item.inner {
+            ItemEnum::Import(import) if !import.glob && import.id.as_ref() == Some(id) => {
+                Some((&item.id, &import.name))
+            }
+            _ => None,
+        })
+        .collect();
+    // sorted so that the same re-export is suggested on every run
+    imports.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+
+    imports
+        .into_iter()
+        .filter_map(|(import_id, name)| {
+            let (module_id, _) = source.index.iter().find(|(_, item)| {
+                matches!(&item.inner, ItemEnum::Module(m) if m.items.contains(import_id))
+            })?;
+            let mut path = path_to_import(get_path(module_id, source)?, source);
+            path.push(name.to_owned());
+            Some(path)
+        })
+        .find(|path| first_private_component(path, source).is_none())
+}
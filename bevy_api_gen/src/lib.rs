@@ -1,11 +1,20 @@
 use rustdoc_types::Type;
 
 pub mod arg_validator;
+pub mod cache;
+pub mod check;
 pub mod config;
+pub mod coverage;
+pub mod manifest;
+pub mod native;
+pub mod suggest;
 pub mod wrapper;
 pub mod writer;
 
-pub use {arg_validator::*, config::*, wrapper::*, writer::*};
+pub use {
+    arg_validator::*, cache::*, check::*, config::*, coverage::*, manifest::*, native::*,
+    suggest::*, wrapper::*, writer::*,
+};
 
 /// Currently only used for stringifying simple trait names
 pub fn stringify_type(type_: &Type) -> Option<String> {
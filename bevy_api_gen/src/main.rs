@@ -1,49 +1,121 @@
 pub mod cratepath;
 
-use bevy_api_gen_lib::{Args, Config, PrettyWriter, WrappedItem, WRAPPER_PREFIX};
+use bevy_api_gen_lib::{
+    check_methods, closest_match, is_wrappable_item, write_coverage_report,
+    write_luals_annotations, write_stats_report, write_teal_declarations, ApiManifest, Args, Cache,
+    Config, Language, PrettyWriter, WrappedItem,
+};
 
 use clap::Parser;
-use cratepath::{get_path, path_to_import};
+use cratepath::{first_private_component, get_path, path_to_import, public_reexport_path};
 use indexmap::{IndexMap, IndexSet};
-use rustdoc_types::{Crate, Impl, Item, ItemEnum};
-use serde_json::from_reader;
+use rustdoc_types::{Crate, Impl, Item, ItemEnum, FORMAT_VERSION};
+use serde::Deserialize;
 use std::{
     borrow::Cow,
-    collections::HashSet,
-    fs::{read_to_string, File},
-    io::{self, BufReader},
+    collections::{BTreeSet, HashSet},
+    fs::{self, read_to_string},
+    io::{self, Write},
+    iter::once,
+    path::Path,
+    process::{Command, Stdio},
+    thread,
 };
 
+/// The bevy sub-crates re-exported by the `bevy` crate as modules, i.e. `bevy_ecs` as `bevy::ecs`
+const BEVY_SUB_CRATES: &[&str] = &[
+    "a11y",
+    "animation",
+    "app",
+    "asset",
+    "audio",
+    "core",
+    "core_pipeline",
+    "derive",
+    "diagnostic",
+    "dynamic_plugin",
+    "ecs",
+    "gilrs",
+    "gltf",
+    "hierarchy",
+    "input",
+    "log",
+    "math",
+    "pbr",
+    "ptr",
+    "reflect",
+    "render",
+    "scene",
+    "sprite",
+    "tasks",
+    "text",
+    "time",
+    "transform",
+    "ui",
+    "utils",
+    "window",
+    "winit",
+];
+
+/// The path bevy sub-crates are imported by through the `bevy` crate, i.e. `bevy::ecs` for `bevy_ecs`.
+///
+/// Any other crate, including `bevy` itself, third party crates like `bevy_rapier2d` and crates merely starting with `bevy`
+/// like `bevycustom`, is imported by its own name.
+fn crate_import_path(crate_name: &str) -> Cow<'_, str> {
+    match crate_name.strip_prefix("bevy_") {
+        Some(sub_crate) if BEVY_SUB_CRATES.contains(&sub_crate) => {
+            Cow::Owned(format!("bevy::{sub_crate}"))
+        }
+        _ => Cow::Borrowed(crate_name),
+    }
+}
+
 pub(crate) fn write_use_items_from_path(
     module_name: &str,
     path_components: &[String],
+    alias: Option<&str>,
     writer: &mut PrettyWriter,
 ) {
     // generate imports for each item
     writer.write_no_newline("use ");
 
-    if module_name.starts_with("bevy") && module_name.len() > 5 {
-        writer.write_inline("bevy::");
-        writer.write_inline(&module_name[5..]);
-    } else {
-        writer.write_inline(module_name);
-    }
+    writer.write_inline(&crate_import_path(module_name));
 
     for item in path_components {
         writer.write_inline("::");
         writer.write_inline(item);
     }
+    if let Some(alias) = alias {
+        writer.write_inline(" as ");
+        writer.write_inline(alias);
+    }
     writer.write_inline(";");
     writer.newline();
 }
 
+/// The generated code, split into a root module and a module per source crate if `--split-by-crate` is given
+pub(crate) struct GeneratedApi {
+    /// The whole generated code, or the `mod.rs` declaring the per crate modules and the providers
+    pub root: String,
+    /// The code of each per crate module, keyed by module name
+    pub modules: IndexMap<String, String>,
+    /// The JSON description of the generated API if `--manifest` is given
+    pub manifest: Option<String>,
+    /// The teal type definitions of the generated lua API if `--teal` is given
+    pub teal: Option<String>,
+    /// The LuaLS annotated stubs of the generated lua API if `--luals` is given
+    pub luals: Option<String>,
+    /// The mismatches between the config and the given crates if `--config-check` is given
+    pub problems: Vec<String>,
+}
+
 pub(crate) fn generate_macros(
     crates: &[Crate],
     config: Config,
     args: &Args,
-) -> Result<String, io::Error> {
+) -> Result<GeneratedApi, io::Error> {
     // the items we want to generate macro instantiations for
-    let mut unmatched_types: HashSet<&String> = config.types.iter().map(|(k, _v)| k).collect();
+    let mut unmatched_types: BTreeSet<&String> = config.types.iter().map(|(k, _v)| k).collect();
 
     let mut wrapped_items: Vec<_> = crates
         .iter()
@@ -51,14 +123,13 @@ pub(crate) fn generate_macros(
             source
                 .index
                 .iter()
-                .filter(|(_id, item)| {
-                    item.name
-                        .as_ref()
-                        .and_then(|k| config.types.get(k))
-                        .map(|k| k.matches_result(item, source))
-                        .unwrap_or(false)
+                .filter_map(|(id, item)| {
+                    let (key, newtype) = config.newtype_of(item, source)?;
+                    newtype
+                        .matches_result(item, source)
+                        .then_some((id, item, key, newtype))
                 })
-                .map(|(id, item)| {
+                .map(|(id, item, wrapped_type, config)| {
                     // extract all available associated constants,methods etc available to this item
                     let mut self_impl: Option<&Impl> = None;
                     let mut impl_items: IndexMap<&str, Vec<(&Impl, &Item)>> = Default::default();
@@ -70,14 +141,24 @@ pub(crate) fn generate_macros(
                         _ => panic!("Only structs or enums are allowed!"),
                     };
 
+                    let impl_filter = &config.impl_filter;
+
                     impls.iter().for_each(|id| {
-                        if let ItemEnum::Impl(i) = &source.index.get(id).unwrap().inner {
-                            match &i.trait_ {
-                                Some(t) => {
-                                    implemented_traits.insert(t.name.to_owned());
-                                }
-                                None => self_impl = Some(i),
+                        let impl_item = source.index.get(id).unwrap();
+                        if let ItemEnum::Impl(i) = &impl_item.inner {
+                            if let Some(t) = &i.trait_ {
+                                implemented_traits.insert(t.name.to_owned());
+                            }
+
+                            if !impl_filter.allows(&impl_item.attrs, i.trait_.is_some()) {
+                                return;
                             }
+
+                            // the first inherent impl remaining after filtering is the primary one
+                            if i.trait_.is_none() && self_impl.is_none() {
+                                self_impl = Some(i);
+                            }
+
                             i.items.iter().for_each(|id| {
                                 let it = source.index.get(id).unwrap();
 
@@ -91,8 +172,6 @@ pub(crate) fn generate_macros(
                         }
                     });
 
-                    let config = config.types.get(item.name.as_ref().unwrap()).unwrap();
-
                     //let path_components = &source.paths.get(id).unwrap().path;
                     let path_components = get_path(id, source).unwrap_or_else(|| {
                         panic!("path not found for {:?} in {:?}", id, source.root)
@@ -101,10 +180,26 @@ pub(crate) fn generate_macros(
                     let path_components = path_to_import(path_components, source);
                     //eprintln!("{:?}", path_components);
 
-                    let wrapper_name = format!("{WRAPPER_PREFIX}{}", item.name.as_ref().unwrap());
-                    let wrapped_type = item.name.as_ref().unwrap();
+                    // the computed path is only used if no import path is given, make sure it compiles
+                    if config.import_path.is_empty() {
+                        if let Some(private) = first_private_component(&path_components, source) {
+                            let mut msg = format!(
+                                "Warning: the computed import path `{}` of `{}` goes through the private item `{private}` and will not compile",
+                                path_components.join("::"),
+                                config.type_
+                            );
+                            match public_reexport_path(id, source) {
+                                Some(public) => msg.push_str(&format!(
+                                    ", it is publicly reachable as `{}`, consider setting it as the `import_path`",
+                                    public.join("::")
+                                )),
+                                None => msg.push_str(", consider setting an `import_path`"),
+                            }
+                            eprintln!("{msg}");
+                        }
+                    }
+
                     WrappedItem {
-                        wrapper_name,
                         wrapped_type,
                         path_components: Cow::Owned(path_components),
                         source,
@@ -114,6 +209,16 @@ pub(crate) fn generate_macros(
                         impl_items,
                         crates,
                         has_global_methods: false,
+                        coverage: Default::default(),
+                        exclusions: Default::default(),
+                        primitive_conversions: Default::default(),
+                        serde_methods: Default::default(),
+                        enum_variants: Default::default(),
+                        assoc_consts: Default::default(),
+                        excluded_methods: Default::default(),
+                        manifest: Default::default(),
+                        native: Default::default(),
+                        method_outcomes: Default::default(),
                         implemented_traits,
                     }
                 })
@@ -124,15 +229,250 @@ pub(crate) fn generate_macros(
         unmatched_types.remove(&v.wrapped_type);
     });
 
+    // only collected with `--config-check`, otherwise missing types are fatal
+    let mut problems = Vec::default();
+
     if !unmatched_types.is_empty() {
-        panic!("Some types were not found in the given crates: {unmatched_types:#?}")
+        // sorted, so that equally close suggestions are picked the same way on every run
+        let candidates: BTreeSet<&str> = crates
+            .iter()
+            .flat_map(|source| {
+                source
+                    .index
+                    .values()
+                    .filter(|item| is_wrappable_item(item, source))
+                    .filter_map(|item| item.name.as_deref())
+            })
+            .collect();
+
+        let searched: Vec<&str> = crates
+            .iter()
+            .filter_map(|source| source.index.get(&source.root)?.name.as_deref())
+            .collect();
+
+        let missing: Vec<String> = unmatched_types
+            .into_iter()
+            .map(|key| {
+                let newtype = config.types.get(key).unwrap();
+                let (type_, source) = (&newtype.type_, &newtype.source.0);
+                let mut line = format!("`{type_}` (from `{source}`)");
+                if let Some(suggestion) = closest_match(type_, candidates.iter().copied()) {
+                    line.push_str(&format!(", did you mean `{suggestion}`?"));
+                }
+                line
+            })
+            .collect();
+
+        if args.config_check {
+            problems.extend(missing.into_iter().map(|line| {
+                format!(
+                    "{line} was not found in the given crates ({})",
+                    searched.join(", ")
+                )
+            }));
+        } else {
+            let mut msg = format!(
+                "Some types were not found in the given crates ({}):",
+                searched.join(", ")
+            );
+            missing
+                .iter()
+                .for_each(|line| msg.push_str(&format!("\n - {line}")));
+            panic!("{msg}")
+        }
     }
 
+    // each language only once, in the order given
+    let languages: Vec<Language> = args
+        .languages
+        .iter()
+        .copied()
+        .collect::<IndexSet<_>>()
+        .into_iter()
+        .collect();
+
+    // we want to preserve the original ordering from the config file,
+    // items are found by iterating over the hash map of each crate's index, so ties are broken by the item id
+    wrapped_items.sort_by_cached_key(|f| {
+        (
+            config.types.get_index_of(f.wrapped_type).unwrap(),
+            f.item.id.0.clone(),
+        )
+    });
+
     let mut writer = PrettyWriter::new();
+    let mut modules = IndexMap::default();
+
+    if args.split_by_crate {
+        // one module per source crate, in order of first appearance
+        let sources: IndexSet<&str> = wrapped_items
+            .iter()
+            .map(|i| i.config.source.0.as_str())
+            .collect();
+
+        for source in sources {
+            let mut module_writer = PrettyWriter::new();
+            write_header(
+                wrapped_items.iter().filter(|i| i.config.source.0 == source),
+                &config,
+                &mut module_writer,
+            );
+            wrapped_items
+                .iter_mut()
+                .filter(|i| i.config.source.0 == source)
+                .for_each(|v| v.write_invocation(&config, &languages, &mut module_writer, args));
+
+            modules.insert(module_name(source), module_writer.finish());
+        }
 
-    // we want to preserve the original ordering from the config file
-    wrapped_items.sort_by_cached_key(|f| config.types.get_index_of(f.wrapped_type).unwrap());
+        // the root module re-exports all the wrappers and houses the combined providers
+        write_header(wrapped_items.iter(), &config, &mut writer);
+        for module in modules.keys() {
+            writer.write_line(&format!("mod {module};"));
+            writer.write_line(&format!("pub use {module}::*;"));
+        }
+    } else {
+        write_header(wrapped_items.iter(), &config, &mut writer);
+        wrapped_items
+            .iter_mut()
+            .for_each(|v| v.write_invocation(&config, &languages, &mut writer, args));
+    }
+
+    // write other code
+    for line in config.other.lines() {
+        writer.write_line(line);
+    }
+
+    // now create the BevyAPIProvider for each language, covering the items of all modules
+    for language in &languages {
+        match language {
+            Language::Lua => write_lua_provider(&wrapped_items, &config, &mut writer),
+            Language::Rhai => write_rhai_provider(&wrapped_items, &config, &mut writer),
+        }
+    }
 
+    if let Some(format) = args.coverage {
+        write_coverage_report(
+            wrapped_items
+                .iter()
+                .map(|i| (i.wrapped_type.as_str(), i.coverage)),
+            format,
+            &mut io::stderr(),
+        )?;
+    }
+
+    if let Some(format) = args.stats {
+        write_stats_report(
+            wrapped_items
+                .iter()
+                .map(|i| (i.wrapped_type.as_str(), i.coverage, &i.exclusions)),
+            format,
+            &mut io::stdout(),
+        )?;
+    }
+
+    if args.config_check {
+        problems.extend(
+            wrapped_items
+                .iter()
+                .flat_map(|i| check_methods(i.wrapped_type, i.config, &i.method_outcomes)),
+        );
+    }
+
+    let api_manifest = (args.manifest || args.teal || args.luals).then(|| ApiManifest {
+        types: wrapped_items
+            .iter_mut()
+            .map(|i| std::mem::take(&mut i.manifest))
+            .collect(),
+    });
+
+    let teal = api_manifest.as_ref().filter(|_| args.teal).map(|m| {
+        let mut teal_writer = PrettyWriter::new();
+        write_teal_declarations(m, &mut teal_writer);
+        teal_writer.finish()
+    });
+
+    let luals = api_manifest.as_ref().filter(|_| args.luals).map(|m| {
+        let mut luals_writer = PrettyWriter::new();
+        write_luals_annotations(m, &mut luals_writer);
+        luals_writer.finish()
+    });
+
+    let manifest = api_manifest
+        .filter(|_| args.manifest)
+        .map(|m| serde_json::to_string_pretty(&m))
+        .transpose()?;
+
+    let mut root = writer.finish();
+    if args.format {
+        root = format_code(root);
+        modules
+            .values_mut()
+            .for_each(|code| *code = format_code(std::mem::take(code)));
+    }
+
+    Ok(GeneratedApi {
+        root,
+        modules,
+        manifest,
+        teal,
+        luals,
+        problems,
+    })
+}
+
+/// Formats generated code with `rustfmt`, the code is returned as is with a warning if rustfmt is not installed or fails.
+///
+/// rustfmt leaves the contents of the macro invocations alone, so mostly the imports and providers are affected
+fn format_code(code: String) -> String {
+    let output = Command::new("rustfmt")
+        .args(["--edition", "2021"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            let mut stdin = child.stdin.take().expect("rustfmt stdin is piped");
+            let input = code.as_bytes();
+            thread::scope(|scope| -> io::Result<_> {
+                // the code is written from another thread, since rustfmt may block on a full stdout pipe before reading all of it
+                let writer = scope.spawn(move || stdin.write_all(input));
+                let output = child.wait_with_output()?;
+                writer.join().expect("Thread writing to rustfmt panicked")?;
+                Ok(output)
+            })
+        });
+
+    match output {
+        Ok(output) if output.status.success() => match String::from_utf8(output.stdout) {
+            Ok(formatted) => formatted,
+            Err(_) => {
+                eprintln!("Warning: rustfmt produced invalid UTF-8, the generated code is left unformatted");
+                code
+            }
+        },
+        Ok(output) => {
+            eprintln!(
+                "Warning: rustfmt failed, the generated code is left unformatted:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            code
+        }
+        Err(e) => {
+            eprintln!(
+                "Warning: could not run rustfmt, the generated code is left unformatted: {e}"
+            );
+            code
+        }
+    }
+}
+
+/// Writes the attributes and imports at the top of each generated file, importing the given wrapped items and their traits
+fn write_header<'a, 'b: 'a>(
+    wrapped_items: impl Iterator<Item = &'a WrappedItem<'b>> + Clone,
+    config: &Config,
+    writer: &mut PrettyWriter,
+) {
     writer.write_line("#![allow(clippy::all,unused_imports)]");
     writer.write_line("// This file is generated by `bevy_mod_scripting_derive/main.rs` change the template not this file");
     writer.write_line("extern crate self as bevy_script_api;");
@@ -144,17 +484,24 @@ pub(crate) fn generate_macros(
     });
     // automatic
 
-    wrapped_items.iter().for_each(|item| {
-        write_use_items_from_path(
-            &item.config.source.0,
-            &item.path_components[1..],
-            &mut writer,
-        );
+    wrapped_items.clone().for_each(|item| {
+        // renamed types are imported under their name, by their import path if one is given
+        match item.config.name.as_deref() {
+            Some(name) if !item.config.import_path.is_empty() => {
+                writer.write_line(&format!("use {} as {name};", item.config.import_path))
+            }
+            name => write_use_items_from_path(
+                &item.config.source.0,
+                &item.path_components[1..],
+                name,
+                writer,
+            ),
+        }
     });
 
     let mut imported = HashSet::<String>::default();
 
-    wrapped_items.iter().for_each(|item| {
+    wrapped_items.for_each(|item| {
         item.config.traits.iter().for_each(|trait_methods| {
             if !imported.contains(&trait_methods.name) {
                 writer.write_no_newline("use ");
@@ -165,36 +512,17 @@ pub(crate) fn generate_macros(
             }
         })
     });
+}
 
-    // make macro calls for each wrapped item
-    wrapped_items.iter_mut().for_each(|v| {
-        // macro invocation
-        writer.write_no_newline("impl_script_newtype!");
-        writer.open_brace();
-        writer.write_line("#[languages(on_feature(lua))]");
-
-        v.write_type_docstring(&mut writer, args);
-        writer.write_indentation();
-        v.write_inline_full_path(&mut writer, args);
-        writer.write_inline(" : ");
-        writer.newline();
-
-        v.write_derive_flags_body(&config, &mut writer, args);
-
-        writer.write_line("lua impl");
-        writer.open_brace();
-        v.write_impl_block_body(&mut writer, args);
-        writer.close_brace();
-
-        writer.close_brace();
-    });
-
-    // write other code
-    for line in config.other.lines() {
-        writer.write_line(line);
-    }
+/// The name of the module holding the wrappers of items from the given source crate
+fn module_name(source: &str) -> String {
+    source
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
 
-    // now create the BevyAPIProvider
+fn write_lua_provider(wrapped_items: &[WrappedItem], config: &Config, writer: &mut PrettyWriter) {
     // first the globals
     writer.write_line("#[cfg(feature=\"lua\")]");
     writer.write_line("#[derive(Default)]");
@@ -210,16 +538,18 @@ pub(crate) fn generate_macros(
     for (global_name, type_, dummy_proxy) in wrapped_items
         .iter()
         .filter_map(|i| {
-            i.has_global_methods.then_some((
-                i.wrapped_type.as_str(),
-                i.wrapper_name.as_str(),
-                false,
-            ))
+            i.has_global_methods.then(|| {
+                (
+                    i.wrapped_type.as_str(),
+                    Cow::Owned(i.wrapper_name(Language::Lua)),
+                    false,
+                )
+            })
         })
         .chain(config.manual_lua_types.iter().filter_map(|i| {
             i.include_global_proxy.then_some((
                 i.proxy_name.as_str(),
-                i.name.as_str(),
+                Cow::Borrowed(i.name.as_str()),
                 i.use_dummy_proxy,
             ))
         }))
@@ -232,13 +562,13 @@ pub(crate) fn generate_macros(
         // corresponding proxy
         if dummy_proxy {
             writer.write_inline(", crate::lua::util::DummyTypeName::<");
-            writer.write_inline(type_);
+            writer.write_inline(&type_);
             writer.write_inline(">::new");
             writer.write_inline(")?;");
             writer.newline();
         } else {
             writer.write_inline(", bevy_mod_scripting_lua::tealr::mlu::UserDataProxy::<");
-            writer.write_inline(type_);
+            writer.write_inline(&type_);
             writer.write_inline(">::new)?;");
             writer.newline();
         }
@@ -267,7 +597,7 @@ pub(crate) fn generate_macros(
     );
     writer.open_brace();
     writer.write_line("let ctx = ctx.get_mut().expect(\"Unable to acquire lock on Lua context\");");
-    writer.write_line("bevy_mod_scripting_lua::tealr::mlu::set_global_env(BevyAPIGlobals,ctx).map_err(|e| ScriptError::Other(e.to_string()))");
+    writer.write_line("bevy_mod_scripting_lua::tealr::mlu::set_global_env(BevyAPIGlobals,ctx).map_err(<ScriptError as bevy_mod_scripting_lua::LuaScriptError>::from_lua)");
     writer.close_brace();
     // } attach_api
 
@@ -280,16 +610,20 @@ pub(crate) fn generate_macros(
     writer.write_line(".document_global_instance::<BevyAPIGlobals>().expect(\"Something went wrong documenting globals\")");
 
     // include external types not generated by this file as well
-    for (type_, include_proxy) in
-        wrapped_items
-            .iter()
-            .map(|i| (i.wrapper_name.as_str(), i.has_global_methods))
-            .chain(config.manual_lua_types.iter().filter_map(|i| {
-                (!i.dont_process).then_some((i.name.as_str(), i.include_global_proxy))
-            }))
+    for (type_, include_proxy) in wrapped_items
+        .iter()
+        .map(|i| {
+            (
+                Cow::Owned(i.wrapper_name(Language::Lua)),
+                i.has_global_methods,
+            )
+        })
+        .chain(config.manual_lua_types.iter().filter_map(|i| {
+            (!i.dont_process).then_some((Cow::Borrowed(i.name.as_str()), i.include_global_proxy))
+        }))
     {
         writer.write_no_newline(".process_type::<");
-        writer.write_inline(type_);
+        writer.write_inline(&type_);
         writer.write_inline(">()");
         writer.newline();
 
@@ -297,7 +631,7 @@ pub(crate) fn generate_macros(
             writer.write_no_newline(
                 ".process_type::<bevy_mod_scripting_lua::tealr::mlu::UserDataProxy<",
             );
-            writer.write_inline(type_);
+            writer.write_inline(&type_);
             writer.write_inline(">>()");
             writer.newline();
         }
@@ -317,6 +651,7 @@ pub(crate) fn generate_macros(
     // register_with_app {
     writer.write_no_newline("fn register_with_app(&self, app: &mut App)");
     writer.open_brace();
+    writer.write_line("crate::common::bevy::register_script_commands(app);");
     for item in wrapped_items
         .iter()
         .map(|i| i.wrapped_type)
@@ -332,22 +667,128 @@ pub(crate) fn generate_macros(
 
     writer.close_brace();
     // } end impl
+}
 
-    Ok(writer.finish())
+fn write_rhai_provider(wrapped_items: &[WrappedItem], config: &Config, writer: &mut PrettyWriter) {
+    writer.write_line("#[cfg(feature=\"rhai\")]");
+    writer.write_line("pub struct RhaiBevyAPIProvider;");
+
+    // begin impl {
+    writer.write_line("#[cfg(feature=\"rhai\")]");
+    writer.write_no_newline("impl APIProvider for RhaiBevyAPIProvider");
+    writer.open_brace();
+
+    writer.write_line("type APITarget = bevy_mod_scripting_rhai::rhai::Engine;");
+    writer.write_line("type ScriptContext = bevy_mod_scripting_rhai::RhaiContext;");
+    writer.write_line("type DocTarget = bevy_mod_scripting_rhai::docs::RhaiDocFragment;");
+
+    // attach_api {
+    writer.write_no_newline(
+        "fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError>",
+    );
+    writer.open_brace();
+    for item in wrapped_items.iter().filter(|i| i.has_rhai_wrapper(config)) {
+        writer.write_no_newline("engine.register_rhai_wrapper::<");
+        writer.write_inline(&item.wrapper_name(Language::Rhai));
+        writer
+            .write_inline(">().map_err(|e| ScriptError::new_language_error(\"Rhai\", *e, None))?;");
+        writer.newline();
+    }
+    writer.write_line("Ok(())");
+    writer.close_brace();
+    // } attach_api
+
+    // rhai has no documentation generation yet so get_doc_fragment is left as the default
+
+    // register_with_app {
+    writer.write_no_newline("fn register_with_app(&self, app: &mut App)");
+    writer.open_brace();
+    writer.write_line("crate::common::bevy::register_script_commands(app);");
+    // primitives and the types with hand written proxies are registered by the hand written `rhai::bevy::RhaiBevyAPIProvider`
+    for item in wrapped_items
+        .iter()
+        .filter(|i| i.has_rhai_wrapper(config))
+        .map(|i| i.wrapped_type)
+    {
+        writer.write_no_newline("app.register_foreign_rhai_type::<");
+        writer.write_inline(item);
+        writer.write_inline(">();");
+        writer.newline();
+    }
+    writer.close_brace();
+    // } regiser_with_app
+
+    writer.close_brace();
+    // } end impl
+}
+
+/// The part of a rustdoc json file which is read before the rest of it
+#[derive(Deserialize)]
+struct FormatVersion {
+    format_version: u32,
+}
+
+/// Checks that the rustdoc json was produced in the format `rustdoc_types` expects,
+/// since a mismatch otherwise surfaces as an unrelated error about some missing or unknown field.
+///
+/// Files without a format version are left for the full parse to report on
+fn check_format_version(contents: &str) -> Result<(), String> {
+    match serde_json::from_str::<FormatVersion>(contents) {
+        Ok(FormatVersion { format_version }) if format_version != FORMAT_VERSION => Err(format!(
+            "rustdoc JSON format v{format_version}, expected v{FORMAT_VERSION}; regenerate with matching nightly"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Reads and deserializes a rustdoc json file, errors include the path of the file
+fn parse_crate(json: &str) -> Result<Crate, io::Error> {
+    let contents = read_to_string(json)
+        .map_err(|e| io::Error::new(e.kind(), format!("Could not open {json}: {e}")))?;
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+    check_format_version(&contents).map_err(|e| invalid(format!("Could not parse {json}: {e}")))?;
+    serde_json::from_str(&contents).map_err(|e| invalid(format!("Could not parse {json}: {e}")))
 }
 
 pub fn main() -> Result<(), io::Error> {
     let args = Args::parse();
 
-    let crates: Vec<_> = args
-        .json
-        .iter()
-        .map(|json| {
-            let f = File::open(json).unwrap_or_else(|_| panic!("Could not open {}", &json));
-            let rdr = BufReader::new(f);
-            from_reader(rdr)
-        })
-        .collect::<Result<Vec<_>, _>>()?;
+    // only the hashes of the inputs are compared, so this is much cheaper than parsing them
+    let cache = match &args.output {
+        // dry runs always regenerate and don't touch the output
+        Some(_) if args.stats.is_some() || args.config_check => None,
+        Some(output) => {
+            let inputs = args
+                .json
+                .iter()
+                .chain(once(&args.config))
+                .map(String::as_str);
+            let cli_args = std::env::args().skip(1).filter(|a| a != "--force");
+            Some(Cache::new(Path::new(output), inputs, cli_args)?)
+        }
+        None => None,
+    };
+
+    if let Some(cache) = &cache {
+        if !args.force && cache.is_valid() {
+            eprintln!("Generated API is up to date, skipping");
+            return Ok(());
+        }
+    }
+
+    // each crate is parsed on its own thread, errors are reported for the first failing file in the order given
+    let crates: Vec<Crate> = thread::scope(|scope| {
+        // all threads need to be spawned before any is joined
+        let handles: Vec<_> = args
+            .json
+            .iter()
+            .map(|json| scope.spawn(move || parse_crate(json)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Thread parsing rustdoc json panicked"))
+            .collect::<Result<Vec<_>, _>>()
+    })?;
 
     let f = read_to_string(&args.config)?;
     let mut config: Config = toml::from_str(&f)?;
@@ -356,12 +797,209 @@ pub fn main() -> Result<(), io::Error> {
 
     while !config.types_.is_empty() {
         let t = config.types_.remove(config.types_.len() - 1);
-        config.types.insert(t.type_.to_string(), t);
+        let key = t.name().to_owned();
+        if config.types.insert(key.clone(), t).is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("The type `{key}` is configured more than once, give the entries distinct `name`s to wrap types of the same name from several crates"),
+            ));
+        }
     }
 
     let out = generate_macros(&crates, config, &args)?;
 
-    println!("{}", out);
+    if args.config_check {
+        if out.problems.is_empty() {
+            eprintln!("The config matches the given crates");
+            return Ok(());
+        }
+        eprintln!("The config does not match the given crates:");
+        out.problems
+            .iter()
+            .for_each(|problem| eprintln!(" - {problem}"));
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} problem(s) found in {}", out.problems.len(), args.config),
+        ));
+    }
+
+    if args.stats.is_some() {
+        return Ok(());
+    }
+
+    match &args.output {
+        // the output is a directory of modules with a `mod.rs` at the root
+        Some(output) if args.split_by_crate => {
+            let output = Path::new(output);
+            fs::create_dir_all(output)?;
+            for (module, code) in &out.modules {
+                fs::write(output.join(format!("{module}.rs")), code)?;
+            }
+            fs::write(output.join("mod.rs"), out.root)?
+        }
+        Some(output) => fs::write(output, out.root)?,
+        None => println!("{}", out.root),
+    }
+
+    if let (Some(output), Some(manifest)) = (&args.output, out.manifest) {
+        fs::write(format!("{output}.api.json"), manifest)?;
+    }
+
+    if let (Some(output), Some(teal)) = (&args.output, out.teal) {
+        fs::write(format!("{output}.d.tl"), teal)?;
+    }
+
+    if let (Some(output), Some(luals)) = (&args.output, out.luals) {
+        fs::write(format!("{output}.lua"), luals)?;
+    }
+
+    if let Some(cache) = cache {
+        cache.store()?;
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use bevy_api_gen_lib::{Args, Config, PrettyWriter};
+
+    use clap::Parser;
+    use rustdoc_types::{
+        Crate, Generics, Id, Item, ItemEnum, Struct, StructKind, Visibility, FORMAT_VERSION,
+    };
+
+    use super::{check_format_version, format_code, generate_macros, write_use_items_from_path};
+
+    fn use_item(crate_name: &str) -> String {
+        let mut writer = PrettyWriter::new();
+        write_use_items_from_path(crate_name, &["Item".to_owned()], None, &mut writer);
+        writer.finish()
+    }
+
+    #[test]
+    fn config_check_reports_missing_types() {
+        let vec3 = Item {
+            id: Id("0".to_owned()),
+            crate_id: 0,
+            name: Some("Vec3".to_owned()),
+            span: None,
+            visibility: Visibility::Public,
+            docs: None,
+            links: HashMap::default(),
+            attrs: Vec::default(),
+            deprecation: None,
+            inner: ItemEnum::Struct(Struct {
+                kind: StructKind::Unit,
+                generics: Generics {
+                    params: Vec::default(),
+                    where_predicates: Vec::default(),
+                },
+                impls: Vec::default(),
+            }),
+        };
+        let source = Crate {
+            root: vec3.id.clone(),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::from([(vec3.id.clone(), vec3)]),
+            paths: HashMap::default(),
+            external_crates: HashMap::default(),
+            format_version: FORMAT_VERSION,
+        };
+        let mut config: Config = toml::from_str(
+            r#"
+            imports = ""
+            other = ""
+            lua_api_defaults = ""
+            primitives = []
+            manual_lua_types = []
+            [[types]]
+            type = "Vec2"
+            source = "bevy_math"
+            "#,
+        )
+        .unwrap();
+        config.types = config
+            .types_
+            .drain(..)
+            .map(|t| (t.name().to_owned(), t))
+            .collect();
+        let args = Args::parse_from(["bevy_api_gen", "--config", "config.toml", "--config-check"]);
+
+        let out = generate_macros(&[source], config, &args).unwrap();
+
+        assert_eq!(out.problems.len(), 1, "{:?}", out.problems);
+        assert!(
+            out.problems[0].starts_with("`Vec2` (from `bevy_math`), did you mean `Vec3`?"),
+            "{:?}",
+            out.problems
+        );
+        assert!(
+            out.problems[0].contains("was not found in the given crates (Vec3)"),
+            "{:?}",
+            out.problems
+        );
+    }
+
+    #[test]
+    fn bevy_sub_crates_are_imported_through_bevy() {
+        assert_eq!(use_item("bevy_ecs"), "use bevy::ecs::Item;\n");
+    }
+
+    #[test]
+    fn bevy_itself_is_not_renamed() {
+        assert_eq!(use_item("bevy"), "use bevy::Item;\n");
+        assert_eq!(use_item("bevy_"), "use bevy_::Item;\n");
+    }
+
+    #[test]
+    fn third_party_bevy_crates_are_not_renamed() {
+        assert_eq!(use_item("bevy_rapier2d"), "use bevy_rapier2d::Item;\n");
+        assert_eq!(
+            use_item("bevy_mod_scripting"),
+            "use bevy_mod_scripting::Item;\n"
+        );
+    }
+
+    #[test]
+    fn crates_starting_with_bevy_are_not_renamed() {
+        assert_eq!(use_item("bevycustom"), "use bevycustom::Item;\n");
+        assert_eq!(use_item("bevyish_thing"), "use bevyish_thing::Item;\n");
+    }
+
+    #[test]
+    fn mismatched_format_version() {
+        let json = format!(
+            r#"{{"root": "0:0", "format_version": {}}}"#,
+            FORMAT_VERSION + 1
+        );
+        let err = check_format_version(&json).unwrap_err();
+        assert_eq!(
+            err,
+            format!(
+                "rustdoc JSON format v{}, expected v{FORMAT_VERSION}; regenerate with matching nightly",
+                FORMAT_VERSION + 1
+            )
+        );
+
+        let json = format!(r#"{{"format_version": {FORMAT_VERSION}}}"#);
+        assert!(check_format_version(&json).is_ok());
+        // missing versions are reported by the full parse
+        assert!(check_format_version("{}").is_ok());
+    }
+
+    #[test]
+    fn formatting_keeps_inner_attributes() {
+        let code = "#![allow(clippy::all,unused_imports)]\nuse   bevy::math::Vec3;\n";
+        let formatted = format_code(code.to_owned());
+        // the code is returned as is if rustfmt is not installed
+        assert!(
+            formatted == code
+                || formatted == "#![allow(clippy::all, unused_imports)]\nuse bevy::math::Vec3;\n",
+            "{formatted}"
+        );
+    }
+}
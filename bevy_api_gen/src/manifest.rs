@@ -0,0 +1,60 @@
+use serde::Serialize;
+
+/// A machine readable description of the generated API, for editor tooling which cannot parse the generated code
+#[derive(Serialize, Debug, Default)]
+pub struct ApiManifest {
+    pub types: Vec<TypeManifest>,
+}
+
+/// A wrapped type along with everything exposed on it, types are given as they appear in rust with `self` standing for the type itself
+#[derive(Serialize, Debug, Default, PartialEq, Eq)]
+pub struct TypeManifest {
+    pub name: String,
+    /// The full path of the wrapped type
+    pub path: String,
+    pub methods: Vec<MethodManifest>,
+    pub fields: Vec<FieldManifest>,
+    pub bin_ops: Vec<OpManifest>,
+    pub unary_ops: Vec<OpManifest>,
+    /// `PartialEq` and `PartialOrd`, only ever comparing two values of the type itself
+    pub comparison_ops: Vec<OpManifest>,
+    /// Indexing with `[]`, scripts can only index with a single key type
+    pub index: Option<IndexManifest>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct MethodManifest {
+    /// The name the method is exposed as to scripts, languages the name is a keyword of append an underscore to it
+    pub name: String,
+    /// `self`, `&self` or `&mut self`, static functions have no receiver
+    pub receiver: Option<String>,
+    pub args: Vec<String>,
+    pub output: Option<String>,
+    /// True for static functions returning the type itself, i.e. `new`
+    pub constructor: bool,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct FieldManifest {
+    /// The name the field is exposed as to scripts, languages the name is a keyword of append an underscore to it
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub readonly: bool,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct OpManifest {
+    /// The name of the operator trait, i.e. `Add`
+    pub op: String,
+    pub args: Vec<String>,
+    pub output: String,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct IndexManifest {
+    pub key: String,
+    pub output: String,
+    /// True if scripts can assign to indexed values too, i.e. the type implements `IndexMut`
+    pub mutable: bool,
+}
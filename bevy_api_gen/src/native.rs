@@ -0,0 +1,720 @@
+//! The native fast path of lua wrappers, see [`Newtype::native`](crate::Newtype::native).
+//!
+//! Instead of a `impl_script_newtype!` invocation, the `TealData` implementation of the wrapper is written out directly,
+//! every method, field and operator becoming a single `add_*` call with a closure specialized to its argument types.
+
+use crate::{
+    wrapper::to_string_flag, Arg, ArgType, ArgWrapperType, Args, Config, Language, LuaImplEntry,
+    PrettyWriter, Receiver, WrappedItem,
+};
+
+/// The derive flag of types which convert to and from sequences of their elements
+const SEQUENCE_FLAG: &str = "Sequence";
+const MLUA: &str = "bevy_mod_scripting_lua::tealr::mlu::mlua";
+const TEALR: &str = "bevy_mod_scripting_lua::tealr";
+
+/// A wrapped method, in the form needed to register it with a direct `add_method` or `add_function` call
+#[derive(Debug, Clone)]
+pub struct NativeMethod {
+    /// The rust name of the method
+    pub name: String,
+    pub script_name: String,
+    pub docs: Vec<String>,
+    /// Attributes applying to the whole registration, i.e. `#[cfg(...)]`
+    pub attrs: Vec<String>,
+    /// The trait the method is called through, if it collides with other methods of the same name
+    pub qualified_trait: Option<String>,
+    pub type_args: Vec<String>,
+    pub receiver: Option<Receiver>,
+    pub args: Vec<Arg>,
+    pub output: Option<Arg>,
+}
+
+/// A wrapped field, in the form needed to register its accessors directly
+#[derive(Debug)]
+pub struct NativeField {
+    /// The rust name of the field, or its index for tuple fields
+    pub member: String,
+    pub script_name: String,
+    pub docs: Vec<String>,
+    pub arg: Arg,
+    pub readonly: bool,
+}
+
+/// One implementation of a binary operator trait
+#[derive(Debug)]
+pub struct NativeBinOp {
+    /// The name of the operator trait, i.e. `Add`
+    pub trait_: &'static str,
+    pub lhs: Arg,
+    pub rhs: Arg,
+    pub output: Arg,
+}
+
+/// Everything on the native fast path of a type, filled in along with the derive flags
+#[derive(Debug, Default)]
+pub struct NativeApi {
+    pub methods: Vec<NativeMethod>,
+    pub fields: Vec<NativeField>,
+    pub bin_ops: Vec<NativeBinOp>,
+    /// The names of the implemented unary operator traits, i.e. `Neg`
+    pub unary_ops: Vec<&'static str>,
+}
+
+/// The lua metamethod implementing the given operator trait
+fn metamethod(trait_: &str) -> &'static str {
+    match trait_ {
+        "Add" => "Add",
+        "Sub" => "Sub",
+        "Mul" => "Mul",
+        "Div" => "Div",
+        "Rem" => "Mod",
+        "Neg" => "Unm",
+        _ => panic!("No metamethod for `{trait_}`"),
+    }
+}
+
+/// A method parameter as received from lua and passed on to the rust method
+struct Param {
+    /// The type the parameter is received as
+    type_: String,
+    /// The expression passing the parameter on
+    value: String,
+    /// If `Some`, the call is made inside a `val` (`val_mut` if true) call borrowing the wrapped value
+    borrow: Option<bool>,
+}
+
+impl WrappedItem<'_> {
+    /// Returns true if the lua wrapper of this type is written on the native fast path,
+    /// which requires lua to be generated, the type to be `Clone` and no `lua_methods` in its config
+    pub fn is_native(&self, args: &Args) -> bool {
+        self.config.native
+            && args.languages.contains(&Language::Lua)
+            && self.implemented_traits.contains("Clone")
+            && self.config.lua_methods.is_empty()
+    }
+
+    /// The lua wrapper of the given wrapped type, or of this type for `Self`
+    fn native_wrapper(&self, type_: &ArgType) -> Option<String> {
+        match type_ {
+            ArgType::Self_ => Some(self.wrapper_name(Language::Lua)),
+            ArgType::Base(b) => Some(format!("{}{b}", Language::Lua.wrapper_prefix())),
+            _ => None,
+        }
+    }
+
+    /// How a parameter of the given type is received, or the reason it's not supported on the native fast path
+    fn native_param(&self, arg: &Arg, ident: &str) -> Result<Param, String> {
+        let unsupported = || Err(format!("argument {}", arg.type_));
+        let param = |type_: String, value: String, borrow| {
+            Ok(Param {
+                type_,
+                value,
+                borrow,
+            })
+        };
+
+        match (&arg.type_, &arg.wrapper) {
+            (ArgType::Base(b), ArgWrapperType::Raw) if b != "str" => {
+                param(b.clone(), ident.to_owned(), None)
+            }
+            // strings are received as owned strings and borrowed
+            (
+                ArgType::Ref {
+                    is_mut: false,
+                    ref_,
+                },
+                ArgWrapperType::Raw,
+            ) => match ref_.as_ref() {
+                ArgType::Base(b) if b == "str" => {
+                    param("String".to_owned(), format!("&{ident}"), None)
+                }
+                ArgType::Base(b) => param(b.clone(), format!("&{ident}"), None),
+                _ => unsupported(),
+            },
+            (ArgType::Base(_), ArgWrapperType::Wrapped)
+            | (ArgType::Self_, ArgWrapperType::None) => param(
+                self.native_wrapper(&arg.type_).unwrap(),
+                format!("{ident}.inner()?"),
+                None,
+            ),
+            (ArgType::Ref { is_mut, ref_ }, ArgWrapperType::Wrapped | ArgWrapperType::None) => {
+                match self.native_wrapper(ref_) {
+                    Some(wrapper) => param(wrapper, ident.to_owned(), Some(*is_mut)),
+                    None => unsupported(),
+                }
+            }
+            (ArgType::Generic { base, args }, wrapper) if base.base_ident() == Some("Option") => {
+                match (args.as_slice(), wrapper) {
+                    ([ArgType::Base(b)], ArgWrapperType::Raw) => {
+                        param(format!("Option<{b}>"), ident.to_owned(), None)
+                    }
+                    ([inner @ ArgType::Base(_)], ArgWrapperType::Wrapped) => param(
+                        format!("Option<{}>", self.native_wrapper(inner).unwrap()),
+                        format!("{ident}.map(|v| v.inner()).transpose()?"),
+                        None,
+                    ),
+                    _ => unsupported(),
+                }
+            }
+            _ => unsupported(),
+        }
+    }
+
+    /// Converts the value produced by the given expression into the value returned to lua,
+    /// or gives the reason the output type is not supported on the native fast path
+    fn native_output(
+        &self,
+        type_: &ArgType,
+        wrapper: &ArgWrapperType,
+        value: String,
+    ) -> Result<String, String> {
+        match (type_, wrapper) {
+            // errors are raised as script errors
+            (ArgType::Generic { base, args }, _) if base.base_ident() == Some("Result") => {
+                let value = format!("({value}).map_err(|e| {MLUA}::Error::external(bevy_mod_scripting_core::prelude::ScriptError::Other(format!(\"{{e:?}}\"))))?");
+                match args.as_slice() {
+                    [inner] => self.native_output(inner, wrapper, value),
+                    _ => Err(format!("output {type_}")),
+                }
+            }
+            (ArgType::Generic { base, args }, _) if base.base_ident() == Some("Option") => {
+                match (args.as_slice(), wrapper) {
+                    ([ArgType::Base(_)], ArgWrapperType::Raw) => Ok(value),
+                    ([inner @ ArgType::Base(_)], ArgWrapperType::Wrapped) => Ok(format!(
+                        "({value}).map({}::new)",
+                        self.native_wrapper(inner).unwrap()
+                    )),
+                    _ => Err(format!("output {type_}")),
+                }
+            }
+            // references are returned as owned copies
+            (
+                ArgType::Ref {
+                    is_mut: false,
+                    ref_,
+                },
+                _,
+            ) => self.native_output(ref_, wrapper, format!("({value}).to_owned()")),
+            (ArgType::Base(_), ArgWrapperType::Raw) => Ok(value),
+            (ArgType::Base(_), ArgWrapperType::Wrapped)
+            | (ArgType::Self_, ArgWrapperType::None) => Ok(format!(
+                "{}::new({value})",
+                self.native_wrapper(type_).unwrap()
+            )),
+            _ => Err(format!("output {type_}")),
+        }
+    }
+
+    /// Returns the reason the given method cannot be registered on the native fast path, if that's the case
+    pub fn native_method_support(&self, method: &NativeMethod) -> Result<(), String> {
+        for (idx, arg) in method.args.iter().enumerate() {
+            self.native_param(arg, &format!("a_{idx}"))?;
+        }
+        if let Some(output) = &method.output {
+            self.native_output(&output.type_, &output.wrapper, String::default())?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if the operands and output of the given operator are covered by the native fast path,
+    /// operands have to be primitives or wrapped types, possibly behind references
+    pub fn native_bin_op_support(&self, op: &NativeBinOp) -> bool {
+        let is_supported_operand = |arg: &Arg| {
+            let type_ = match &arg.type_ {
+                ArgType::Ref { ref_, .. } => ref_.as_ref(),
+                type_ => type_,
+            };
+            matches!(
+                (type_, &arg.wrapper),
+                (
+                    ArgType::Base(_),
+                    ArgWrapperType::Raw | ArgWrapperType::Wrapped
+                ) | (ArgType::Self_, ArgWrapperType::None)
+            )
+        };
+        is_supported_operand(&op.lhs)
+            && is_supported_operand(&op.rhs)
+            && self
+                .native_output(&op.output.type_, &op.output.wrapper, String::default())
+                .is_ok()
+    }
+
+    /// Returns true if the given field can be accessed on the native fast path
+    pub fn native_field_support(arg: &Arg) -> bool {
+        matches!(
+            (&arg.type_, &arg.wrapper),
+            (
+                ArgType::Base(_),
+                ArgWrapperType::Raw | ArgWrapperType::Wrapped
+            )
+        )
+    }
+
+    /// Writes the registration of a method on the native fast path
+    ///
+    /// As:
+    /// ```rust,ignore
+    /// methods.document(" Computes the dot product of `self` and `rhs`.");
+    /// methods.add_method("dot", |_, s, (a_0,): (LuaVec3,)| {Ok(s.val(|s| s.dot(a_0.inner()?))?)});
+    /// ```
+    fn write_native_method(&self, method: &NativeMethod, writer: &mut PrettyWriter) {
+        let wrapped_type = self.wrapped_type;
+        let params = method
+            .args
+            .iter()
+            .enumerate()
+            .map(|(idx, arg)| self.native_param(arg, &format!("a_{idx}")).unwrap())
+            .collect::<Vec<_>>();
+
+        let values = params
+            .iter()
+            .map(|p| p.value.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let fn_name = if method.type_args.is_empty() {
+            method.name.clone()
+        } else {
+            format!("{}::<{}>", method.name, method.type_args.join(", "))
+        };
+        let receiver = match method.receiver {
+            Some(Receiver::Value) => Some("s.inner()?"),
+            Some(_) => Some("s"),
+            None => None,
+        };
+        let call = match (receiver, &method.qualified_trait) {
+            (Some(receiver), Some(trait_)) => {
+                let values = if values.is_empty() {
+                    receiver.to_owned()
+                } else {
+                    format!("{receiver}, {values}")
+                };
+                format!("<{wrapped_type} as {trait_}>::{fn_name}({values})")
+            }
+            (Some(receiver), None) => format!("{receiver}.{fn_name}({values})"),
+            (None, Some(trait_)) => format!("<{wrapped_type} as {trait_}>::{fn_name}({values})"),
+            (None, None) => format!("{wrapped_type}::{fn_name}({values})"),
+        };
+
+        let mut body = match &method.output {
+            Some(output) => format!(
+                "Ok({})",
+                self.native_output(&output.type_, &output.wrapper, call)
+                    .unwrap()
+            ),
+            None => format!("Ok({call})"),
+        };
+
+        // referenced wrappers and the receiver are borrowed for the duration of the call
+        let borrows = params
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, p)| Some((format!("a_{idx}"), p.borrow?)))
+            .chain(
+                method
+                    .receiver
+                    .filter(|r| *r != Receiver::Value)
+                    .map(|r| ("s".to_owned(), r == Receiver::MutRef)),
+            );
+        for (ident, is_mut) in borrows {
+            let accessor = if is_mut { "val_mut" } else { "val" };
+            body = format!("{ident}.{accessor}(|{ident}| {body})?");
+        }
+
+        let pattern = params
+            .iter()
+            .enumerate()
+            .map(|(idx, p)| match p.borrow {
+                Some(true) => format!("mut a_{idx},"),
+                _ => format!("a_{idx},"),
+            })
+            .collect::<String>();
+        let types = params
+            .iter()
+            .map(|p| format!("{},", p.type_))
+            .collect::<String>();
+
+        let (add, receiver) = match method.receiver {
+            Some(Receiver::MutRef) => ("add_method_mut", "s, "),
+            Some(_) => ("add_method", "s, "),
+            None => ("add_function", ""),
+        };
+
+        write_registration(&method.attrs, &method.docs, "methods", writer, |writer| {
+            writer.write_line(&format!(
+                "methods.{add}({:?}, |_, {receiver}({pattern}): ({types})| {{{body}}});",
+                method.script_name
+            ));
+        });
+    }
+
+    /// Writes the getter and unless it's readonly the setter of a field on the native fast path,
+    /// primitive fields are accessed without looking up the world
+    ///
+    /// As:
+    /// ```rust,ignore
+    /// fields.add_field_method_get("x", |_, s| Ok(s.val(|s| s.x.clone())?));
+    /// fields.add_field_method_set("x", |_, s, o: f32| Ok(s.val_mut(|s| s.x = o)?));
+    /// ```
+    fn write_native_field(&self, field: &NativeField, writer: &mut PrettyWriter) {
+        let (member, name) = (&field.member, &field.script_name);
+        let world_ptr = format!(
+            "let world_ptr = <{MLUA}::Lua as bevy_script_api::common::bevy::GetWorld>::get_world(lua)?;"
+        );
+        // tuple fields are indexed by position, named fields by name
+        let index = match member.parse::<usize>() {
+            Ok(idx) => format!("{idx}usize"),
+            Err(_) => format!("std::borrow::Cow::Borrowed({member:?})"),
+        };
+        let sub_ref =
+            format!("bevy_script_api::ValueIndex::index(&s.script_ref(world_ptr), {index})");
+
+        let (getter, setter) = match (&field.arg.wrapper, &field.arg.type_) {
+            (ArgWrapperType::Wrapped, type_) => {
+                let wrapper = self.native_wrapper(type_).unwrap();
+                (
+                    format!("|lua, s| {{{world_ptr} Ok({wrapper}::new_ref({sub_ref}))}}"),
+                    format!("|lua, s, o: {wrapper}| {{{world_ptr} Ok(o.apply_self_to_base(&mut {sub_ref})?)}}"),
+                )
+            }
+            (_, type_) => (
+                format!("|_, s| Ok(s.val(|s| s.{member}.clone())?)"),
+                format!("|_, s, o: {type_}| Ok(s.val_mut(|s| s.{member} = o)?)"),
+            ),
+        };
+
+        write_registration(&[], &field.docs, "fields", writer, |writer| {
+            writer.write_line(&format!("fields.add_field_method_get({name:?}, {getter});"));
+            if !field.readonly {
+                writer.write_line(&format!("fields.add_field_method_set({name:?}, {setter});"));
+            }
+        });
+    }
+
+    /// Writes one metamethod per binary operator, trying each implementation in order until both operands convert
+    ///
+    /// As:
+    /// ```rust,ignore
+    /// methods.add_meta_function(MetaMethod::Mul, |lua, (lhs, rhs): (Value, Value)| {
+    ///     if let (Ok(l), Ok(r)) = (<LuaVec3 as FromLua>::from_lua(lhs.clone(), lua), <f32 as FromLua>::from_lua(rhs.clone(), lua)) {
+    ///         return ToLua::to_lua(LuaVec3::new(l.inner()? * r), lua);
+    ///     }
+    ///     Err(..)
+    /// });
+    /// ```
+    fn write_native_bin_ops(&self, writer: &mut PrettyWriter) {
+        let mut traits = self
+            .native
+            .bin_ops
+            .iter()
+            .map(|op| op.trait_)
+            .collect::<Vec<_>>();
+        traits.dedup();
+
+        // operands are only ever primitives or wrapped types, possibly behind references
+        let operand = |arg: &Arg, ident: &str| {
+            let (type_, is_ref) = match &arg.type_ {
+                ArgType::Ref { ref_, .. } => (ref_.as_ref(), true),
+                type_ => (type_, false),
+            };
+            let (lua_type, value) = match &arg.wrapper {
+                ArgWrapperType::Raw => (type_.to_string(), ident.to_owned()),
+                _ => (
+                    self.native_wrapper(type_).unwrap(),
+                    format!("{ident}.inner()?"),
+                ),
+            };
+            (lua_type, if is_ref { format!("&{value}") } else { value })
+        };
+
+        for trait_ in traits {
+            let symbol = match trait_ {
+                "Add" => "+",
+                "Sub" => "-",
+                "Mul" => "*",
+                "Div" => "/",
+                _ => "%",
+            };
+            writer.write_line(&format!(
+                "methods.add_meta_function({MLUA}::MetaMethod::{}, |lua, (lhs, rhs): ({MLUA}::Value, {MLUA}::Value)| {{",
+                metamethod(trait_)
+            ));
+            writer.indent();
+            for op in self.native.bin_ops.iter().filter(|op| op.trait_ == trait_) {
+                let (lhs_type, lhs) = operand(&op.lhs, "l");
+                let (rhs_type, rhs) = operand(&op.rhs, "r");
+                let output = self
+                    .native_output(
+                        &op.output.type_,
+                        &op.output.wrapper,
+                        format!("{lhs} {symbol} {rhs}"),
+                    )
+                    .unwrap();
+                writer.write_line(&format!(
+                    "if let (Ok(l), Ok(r)) = (<{lhs_type} as {MLUA}::FromLua>::from_lua(lhs.clone(), lua), <{rhs_type} as {MLUA}::FromLua>::from_lua(rhs.clone(), lua)) {{"
+                ));
+                writer.indent();
+                writer.write_line(&format!("return {MLUA}::ToLua::to_lua({output}, lua);"));
+                writer.dedent();
+                writer.write_line("}");
+            }
+            writer.write_line(&format!(
+                "Err({MLUA}::Error::RuntimeError(format!(\"unsupported operands for `{trait_}` on `{}`: {{}} and {{}}\", lhs.type_name(), rhs.type_name())))",
+                self.wrapped_type
+            ));
+            writer.dedent();
+            writer.write_line("});");
+        }
+    }
+
+    /// Writes the lua wrapper of this type directly instead of through `impl_script_newtype!`,
+    /// including the functions of its lua impl block given by [`WrappedItem::lua_impl_entries`]
+    pub fn write_native_lua(&self, config: &Config, writer: &mut PrettyWriter, args: &Args) {
+        let wrapper = self.wrapper_name(Language::Lua);
+        let wrapped_type = self.wrapped_type;
+        let cfg = Language::Lua.cfg_attribute();
+
+        let mut type_docs = PrettyWriter::new();
+        self.write_type_docstring(config, &mut type_docs, args);
+        let type_docs = type_docs.finish();
+
+        writer.write_line(&cfg);
+        writer.write_line(&format!(
+            "bevy_script_api::make_script_wrapper!({wrapped_type} as {wrapper} with Clone);"
+        ));
+        writer.write_line(&cfg);
+        writer.write_line(&format!("bevy_script_api::impl_tealr_type!({wrapper});"));
+
+        for format_trait in ["Debug", "Display"] {
+            if self.implemented_traits.contains(format_trait) {
+                writer.write_line(&cfg);
+                writer.write_line(&format!("impl std::fmt::{format_trait} for {wrapper} {{"));
+                writer.indent();
+                writer.write_line(
+                    "fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {",
+                );
+                writer.indent();
+                writer.write_line(&format!("self.val(|s| std::fmt::{format_trait}::fmt(s, f)).unwrap_or_else(|_| f.write_str(\"Error while retrieving reference in `std::fmt::{format_trait}`.\"))"));
+                writer.dedent();
+                writer.write_line("}");
+                writer.dedent();
+                writer.write_line("}");
+            }
+        }
+
+        let entries = self.lua_impl_entries(args);
+        // sequences can be assigned and passed as tables of their elements, like with the macro
+        let is_sequence = self
+            .config
+            .derive_flags
+            .iter()
+            .any(|flag| flag == SEQUENCE_FLAG);
+
+        writer.write_line(&cfg);
+        writer.write_line("#[allow(unused_parens, unused_variables, clippy::all)]");
+        writer.write_line(&format!("impl {TEALR}::mlu::TealData for {wrapper} {{"));
+        writer.indent();
+
+        writer.write_line(&format!(
+            "fn add_methods<'lua, T: {TEALR}::mlu::TealDataMethods<'lua, Self>>(methods: &mut T) {{"
+        ));
+        writer.indent();
+        type_docs
+            .lines()
+            .filter_map(|line| line.trim_start().strip_prefix("///"))
+            .for_each(|line| {
+                writer.write_line(&format!("methods.document_type({line:?});"));
+            });
+
+        let to_string = match to_string_flag(&self.implemented_traits) {
+            "Display" => "|_, s, ()| Ok(format!(\"{}\", s))".to_owned(),
+            "Debug" => "|_, s, ()| Ok(format!(\"{:?}\", s))".to_owned(),
+            _ => format!("|_, _, ()| Ok({:?})", format!("<{wrapped_type}>")),
+        };
+        writer.write_line(&format!(
+            "methods.add_meta_method({MLUA}::MetaMethod::ToString, {to_string});"
+        ));
+        let comparisons = [
+            ("PartialEq", "Eq", "=="),
+            ("PartialOrd", "Lt", "<"),
+            ("PartialOrd", "Le", "<="),
+        ];
+        for (trait_, metamethod, op) in comparisons {
+            if self.implements_with_self(trait_) {
+                writer.write_line(&format!(
+                    "methods.add_meta_method({MLUA}::MetaMethod::{metamethod}, |_, s, o: {wrapper}| Ok(s.val(|s| o.val(|o| s {op} o))??));"
+                ));
+            }
+        }
+
+        self.native
+            .methods
+            .iter()
+            .for_each(|m| self.write_native_method(m, writer));
+        self.write_native_bin_ops(writer);
+        self.native.unary_ops.iter().for_each(|op| {
+            writer.write_line(&format!(
+                "methods.add_meta_method({MLUA}::MetaMethod::{}, |_, s, ()| Ok({wrapper}::new(-s.inner()?)));",
+                metamethod(op)
+            ));
+        });
+        if is_sequence {
+            let docs = [
+                " Converts this value into a sequence of its elements, i.e. `{x, y, z}`".to_owned(),
+            ];
+            write_registration(&[], &docs, "methods", writer, |writer| {
+                writer.write_line("methods.add_method(\"to_array\", |lua, s, ()| bevy_script_api::lua::table::sequence_to_table(lua, &s.inner()?));");
+            });
+        }
+        entries
+            .iter()
+            .filter(|e| !e.is_field)
+            .for_each(|e| write_impl_entry(e, writer));
+        writer.dedent();
+        writer.write_line("}");
+
+        writer.write_line(&format!(
+            "fn add_fields<'lua, T: {TEALR}::mlu::TealDataFields<'lua, Self>>(fields: &mut T) {{"
+        ));
+        writer.indent();
+        self.native
+            .fields
+            .iter()
+            .for_each(|f| self.write_native_field(f, writer));
+        entries
+            .iter()
+            .filter(|e| e.is_field)
+            .for_each(|e| write_impl_entry(e, writer));
+        writer.dedent();
+        writer.write_line("}");
+
+        writer.dedent();
+        writer.write_line("}");
+
+        // the same conversions the macro provides, so other wrappers can take and return this type
+        writer.write_line(&cfg);
+        writer.write_line(&format!(
+            "impl bevy_script_api::lua::LuaProxyable for {wrapped_type} {{"
+        ));
+        writer.indent();
+        writer.write_line(&format!("fn ref_to_lua<'lua>(self_: bevy_script_api::script_ref::ScriptRef, lua: &'lua {MLUA}::Lua) -> {MLUA}::Result<{MLUA}::Value<'lua>> {{"));
+        writer.indent();
+        writer.write_line(&format!(
+            "<{wrapper} as {MLUA}::ToLua>::to_lua({wrapper}::new_ref(self_), lua)"
+        ));
+        writer.dedent();
+        writer.write_line("}");
+        writer.write_line(&format!("fn apply_lua<'lua>(self_: &mut bevy_script_api::script_ref::ScriptRef, _: &'lua {MLUA}::Lua, new_val: {MLUA}::Value<'lua>) -> {MLUA}::Result<()> {{"));
+        writer.indent();
+        writer.write_line(&format!("if let {MLUA}::Value::UserData(v) = new_val {{"));
+        writer.indent();
+        writer.write_line(&format!(
+            "Ok(v.borrow::<{wrapper}>()?.apply_self_to_base(self_)?)"
+        ));
+        writer.dedent();
+        if is_sequence {
+            writer.write_line(&format!(
+                "}} else if let {MLUA}::Value::Table(table) = new_val {{"
+            ));
+            writer.indent();
+            writer.write_line(&format!("self_.set_val(bevy_script_api::lua::table::sequence_from_table::<{wrapped_type}>(table)?)?;"));
+            writer.write_line("Ok(())");
+            writer.dedent();
+        }
+        writer.write_line("} else {");
+        writer.indent();
+        writer.write_line(&format!(
+            "Err({MLUA}::Error::RuntimeError(\"Error in assigning to custom user data\".to_owned()))"
+        ));
+        writer.dedent();
+        writer.write_line("}");
+        writer.dedent();
+        writer.write_line("}");
+        writer.dedent();
+        writer.write_line("}");
+
+        writer.write_line(&cfg);
+        writer.write_line(&format!(
+            "impl bevy_script_api::lua::ToLuaProxy<'_> for {wrapped_type} {{"
+        ));
+        writer.indent();
+        writer.write_line(&format!("fn to_lua_proxy<'lua>(self, lua: &'lua {MLUA}::Lua) -> {MLUA}::Result<{MLUA}::Value<'lua>> {{"));
+        writer.indent();
+        writer.write_line(&format!(
+            "<{wrapper} as {MLUA}::ToLua>::to_lua({wrapper}::new(self), lua)"
+        ));
+        writer.dedent();
+        writer.write_line("}");
+        writer.dedent();
+        writer.write_line("}");
+
+        writer.write_line(&cfg);
+        writer.write_line(&format!(
+            "impl bevy_script_api::lua::FromLuaProxy<'_> for {wrapped_type} {{"
+        ));
+        writer.indent();
+        writer.write_line(&format!("fn from_lua_proxy<'lua>(lua_value: {MLUA}::Value<'lua>, _: &'lua {MLUA}::Lua) -> {MLUA}::Result<Self> {{"));
+        writer.indent();
+        writer.write_line(&format!(
+            "if let {MLUA}::Value::UserData(ud) = lua_value {{"
+        ));
+        writer.indent();
+        writer.write_line(&format!("Ok(ud.borrow::<{wrapper}>()?.inner()?)"));
+        writer.dedent();
+        if is_sequence {
+            writer.write_line(&format!(
+                "}} else if let {MLUA}::Value::Table(table) = lua_value {{"
+            ));
+            writer.indent();
+            writer.write_line("bevy_script_api::lua::table::sequence_from_table(table)");
+            writer.dedent();
+        }
+        writer.write_line("} else {");
+        writer.indent();
+        writer.write_line(&format!(
+            "Err({MLUA}::Error::FromLuaConversionError {{ from: lua_value.type_name(), to: {wrapped_type:?}, message: None }})"
+        ));
+        writer.dedent();
+        writer.write_line("}");
+        writer.dedent();
+        writer.write_line("}");
+        writer.dedent();
+        writer.write_line("}");
+    }
+}
+
+/// Writes the statements registering a method or field, preceded by its documentation and wrapped in a block carrying its attributes
+fn write_registration<F: FnOnce(&mut PrettyWriter)>(
+    attrs: &[String],
+    docs: &[String],
+    receiver: &str,
+    writer: &mut PrettyWriter,
+    register: F,
+) {
+    attrs.iter().for_each(|attr| {
+        writer.write_line(attr);
+    });
+    writer.open_brace();
+    docs.iter().for_each(|line| {
+        writer.write_line(&format!("{receiver}.document({line:?});"));
+    });
+    register(writer);
+    writer.close_brace();
+}
+
+/// Registers a function of the lua impl block, static fields are registered in `add_fields`
+fn write_impl_entry(entry: &LuaImplEntry, writer: &mut PrettyWriter) {
+    let (receiver, add) = if entry.is_field {
+        ("fields", "add_field_function_get")
+    } else if entry.is_static {
+        ("methods", "add_function")
+    } else {
+        ("methods", "add_method")
+    };
+    write_registration(&[], &entry.docs, receiver, writer, |writer| {
+        writer.write_line(&format!(
+            "{receiver}.{add}(\"{}\", {});",
+            entry.name, entry.closure
+        ));
+    });
+}
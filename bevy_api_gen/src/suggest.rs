@@ -0,0 +1,54 @@
+/// The number of single character edits (insertions, deletions or substitutions) needed to turn `a` into `b`
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Finds the candidate closest to the given name, as long as it's close enough to be a plausible typo
+pub fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|c| (levenshtein(name, c), c))
+        .filter(|(d, _)| *d <= max_distance)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("Transform", "Transform"), 0);
+        assert_eq!(levenshtein("Transfrom", "Transform"), 2);
+        assert_eq!(levenshtein("Vec", "Vec3"), 1);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let candidates = ["Transform", "GlobalTransform", "Timer"];
+        assert_eq!(
+            closest_match("Transfrom", candidates.iter().copied()),
+            Some("Transform")
+        );
+        assert_eq!(closest_match("Quat", candidates.iter().copied()), None);
+    }
+}
@@ -1,15 +1,150 @@
-use std::{borrow::Cow, collections::HashSet};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+};
 
 use indexmap::{IndexMap, IndexSet};
-use rustdoc_types::{Crate, Id, Impl, Item, ItemEnum, StructKind};
+use rustdoc_types::{
+    Crate, Deprecation, GenericArg, GenericArgs, GenericBound, GenericParamDefKind, Id, Impl, Item,
+    ItemEnum, Path, StructKind, Type, Variant, Visibility,
+};
 
-use crate::{Arg, ArgType, ArgWrapperType, Args, Config, Newtype, PrettyWriter};
+use crate::{
+    Arg, ArgType, ArgWrapperType, Args, Config, Count, Coverage, FieldManifest, IndexManifest,
+    Language, MethodManifest, MethodOutcome, NativeApi, NativeBinOp, NativeField, NativeMethod,
+    Newtype, OpManifest, PrettyWriter, Receiver, TypeManifest, VersionSource,
+};
 
-pub static WRAPPER_PREFIX: &str = "Lua";
+/// The derive flag which generates script constructors and accessors from `From` impls between the type and primitives,
+/// it's handled by the generator and not passed on to the macro
+pub const PRIMITIVE_CONVERSIONS_FLAG: &str = "PrimitiveConversions";
+
+/// A function of the lua impl block generated for a type,
+/// kept structured so that the native fast path can register it directly
+#[derive(Debug, PartialEq, Eq)]
+pub struct LuaImplEntry {
+    /// The lines of the docstring, without the `///` prefix
+    pub docs: Vec<String>,
+    /// Static functions are called on the global proxy of the type, the others on its values
+    pub is_static: bool,
+    /// The name scripts call the function by
+    pub name: String,
+    /// The closure implementing the function, taking the arguments of `add_function` or `add_method`
+    pub closure: String,
+    /// Static fields are read from the global proxy of the type without calling them, the closure then takes
+    /// the arguments of `add_field_function_get`
+    pub is_field: bool,
+}
+
+impl LuaImplEntry {
+    fn new(doc: String, is_static: bool, name: String, closure: String) -> Self {
+        Self {
+            docs: vec![format!(" {doc}")],
+            is_static,
+            name,
+            closure,
+            is_field: false,
+        }
+    }
+
+    /// Writes the function in the syntax of the lua impl block, i.e. `static fn "from_f32" => |_,v: f32| {...};`
+    /// or `get fn "ZERO" => |_,_| {...};` for static fields
+    fn write(&self, writer: &mut PrettyWriter) {
+        self.docs.iter().for_each(|line| {
+            writer.write_line(&format!("///{line}"));
+        });
+        let static_ = if self.is_field {
+            "get fn "
+        } else if self.is_static {
+            "static fn "
+        } else {
+            ""
+        };
+        writer.write_postfixed_line(
+            &format!("{static_}\"{}\" => {}", self.name, self.closure),
+            ";",
+        );
+    }
+}
+
+/// The path generated rhai code refers to rhai by
+const RHAI: &str = "bevy_mod_scripting_rhai::rhai";
+
+/// The docs of the `type_name` method generated for every wrapper
+const TYPE_NAME_DOCS: &str =
+    "Returns the fully qualified name of this type, as given by `Reflect::type_name`";
+
+/// The name of a function written in the syntax of the lua impl block, i.e. `type_name` for `mut fn "type_name" => |..| {..}`.
+/// `None` for meta methods such as `(MetaMethod::Index) => |..| {..}`
+fn lua_method_name(method: &str) -> Option<&str> {
+    let mut rest = method.trim_start();
+    while let Some(doc) = rest.strip_prefix("///") {
+        rest = doc
+            .split_once('\n')
+            .map_or("", |(_, rest)| rest)
+            .trim_start();
+    }
+    while let Some(keyword) = ["get", "set", "static", "mut", "fn"].into_iter().find(|k| {
+        rest.strip_prefix(k)
+            .map_or(false, |r| r.starts_with(char::is_whitespace))
+    }) {
+        rest = rest[keyword.len()..].trim_start();
+    }
+    rest.strip_prefix('"')?
+        .split_once('"')
+        .map(|(name, _)| name)
+}
+
+/// A conversion between a wrapped type and a primitive
+#[derive(Debug, PartialEq, Eq)]
+pub enum PrimitiveConversion {
+    /// `impl From<primitive> for Type`, exposed as the static `from_<primitive>` function
+    From(String),
+    /// `impl From<Type> for primitive`, exposed as the `into_<primitive>` method
+    Into(String),
+}
+
+/// A method converting between a wrapped type and JSON, generated for Lua if `emit_serde_methods` is set
+#[derive(Debug, PartialEq, Eq)]
+pub enum SerdeMethod {
+    /// `impl Serialize for Type`, exposed as the `to_json` method
+    ToJson,
+    /// `impl Deserialize for Type`, exposed as the static `from_json` function
+    FromJson,
+}
+
+/// A method which could not be wrapped, exposed as a stub raising an error if `emit_exclusion_stubs` is set
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExcludedMethod {
+    /// The name the method would be exposed as to scripts
+    pub name: String,
+    /// True if the method has no `self` receiver
+    pub is_static: bool,
+    /// The number of arguments besides the receiver, rhai functions are looked up by their number of arguments
+    pub arity: usize,
+    pub reasons: Vec<String>,
+}
+
+/// A variant of a wrapped enum
+#[derive(Debug)]
+pub struct EnumVariant {
+    pub name: String,
+    /// The payload of the variant if it can be constructed from scripts (empty for unit variants),
+    /// otherwise the reason it cannot be
+    pub payload: Result<Vec<Arg>, String>,
+}
+
+/// An associated constant of a wrapped type, i.e. `Vec3::ZERO`
+#[derive(Debug)]
+pub struct AssocConst {
+    pub name: String,
+    pub docs: Option<String>,
+    /// The type of the constant, a primitive or a wrapped type
+    pub value: Arg,
+}
 
 #[derive(Debug)]
 pub struct WrappedItem<'a> {
-    pub wrapper_name: String,
     pub wrapped_type: &'a String,
     pub path_components: Cow<'a, [String]>,
     pub source: &'a Crate,
@@ -22,9 +157,45 @@ pub struct WrappedItem<'a> {
     pub crates: &'a [Crate],
     /// If this type has some things which are "static" this is set to true later
     pub has_global_methods: bool,
+    /// How many of the methods, fields and operators were wrapped, filled in later
+    pub coverage: Coverage,
+    /// The number of methods excluded for each reason, filled in later
+    pub exclusions: BTreeMap<String, usize>,
+    /// Conversions to and from primitives, filled in later if the `PrimitiveConversions` flag is present
+    pub primitive_conversions: Vec<PrimitiveConversion>,
+    /// The JSON conversions of this type, filled in later if `emit_serde_methods` is set
+    pub serde_methods: Vec<SerdeMethod>,
+    /// The variants of this type if it's an enum, filled in later
+    pub enum_variants: Vec<EnumVariant>,
+    /// The associated constants of this type exposed to scripts, filled in later
+    pub assoc_consts: Vec<AssocConst>,
+    /// The methods replaced with stubs, filled in later if `emit_exclusion_stubs` is set
+    pub excluded_methods: Vec<ExcludedMethod>,
+    /// The description of everything wrapped on this type, filled in later
+    pub manifest: TypeManifest,
+    /// What is written directly on the native fast path, filled in later if `native` is set in the config
+    pub native: NativeApi,
+    /// Whether each method was wrapped and why not, keyed like `method_overrides`, filled in later
+    pub method_outcomes: BTreeMap<String, MethodOutcome>,
 }
 
 impl WrappedItem<'_> {
+    /// The name of the type wrapping this item in the given language, i.e. `LuaVec3`
+    pub fn wrapper_name(&self, language: Language) -> String {
+        format!("{}{}", language.wrapper_prefix(), self.wrapped_type)
+    }
+
+    /// The fully qualified name of the wrapped type as given by `Reflect::type_name`, i.e. `bevy_transform::components::transform::Transform`.
+    ///
+    /// This is the path the type is defined at, which may differ from the `import_path` it's reexported at
+    pub fn type_name(&self) -> String {
+        if self.path_components.is_empty() {
+            self.config.import_path.clone()
+        } else {
+            self.path_components.join("::")
+        }
+    }
+
     /// Writes full type path inline corresponding to `Reflect::type_name` of each type
     ///
     /// As:
@@ -37,7 +208,10 @@ impl WrappedItem<'_> {
     ///  UnaryOps( ...
     /// ```
     pub fn write_inline_full_path(&self, writer: &mut PrettyWriter, _: &Args) {
-        if self.config.import_path.is_empty() {
+        // renamed types are imported under their name
+        if let Some(name) = &self.config.name {
+            writer.write_inline(name);
+        } else if self.config.import_path.is_empty() {
             writer.write_inline(&self.path_components.join("::"));
         } else {
             writer.write_inline(&self.config.import_path);
@@ -58,7 +232,7 @@ impl WrappedItem<'_> {
     ///  +
     ///  ...
     /// ```
-    pub fn write_type_docstring(&self, writer: &mut PrettyWriter, _: &Args) {
+    pub fn write_type_docstring(&self, config: &Config, writer: &mut PrettyWriter, _: &Args) {
         let strings = if let Some(d) = &self.config.doc {
             d.to_string()
         } else {
@@ -68,9 +242,31 @@ impl WrappedItem<'_> {
         strings.lines().for_each(|l| {
             writer.write_line(l);
         });
+        if let Some(deprecation) = &self.item.deprecation {
+            write_deprecation_note(&strings, deprecation, writer);
+        }
+        if config.emit_docs_rs_links {
+            write_docs_rs_link(&strings, &self.docs_rs_url(), writer);
+        }
         writer.clear_prefix();
     }
 
+    /// The url of the documentation of the wrapped item on docs.rs,
+    /// i.e. `https://docs.rs/bevy_transform/0.10.0/bevy_transform/components/struct.Transform.html`
+    pub fn docs_rs_url(&self) -> String {
+        let (name, modules) = self.path_components.split_last().unwrap();
+        let version = self.source.crate_version.as_deref().unwrap_or("latest");
+        let kind = match &self.item.inner {
+            ItemEnum::Enum(_) => "enum",
+            _ => "struct",
+        };
+        format!(
+            "https://docs.rs/{}/{version}/{}/{kind}.{name}.html",
+            self.path_components[0],
+            modules.join("/")
+        )
+    }
+
     /// Writes the docstring for the given auto method over multiple lines
     ///
     /// As:
@@ -86,385 +282,4028 @@ impl WrappedItem<'_> {
     ///  +
     ///  ...
     /// ```
-    pub fn write_method_docstring(&self, id: &Id, writer: &mut PrettyWriter, _: &Args) {
+    pub fn write_method_docstring(
+        &self,
+        id: &Id,
+        config: &Config,
+        writer: &mut PrettyWriter,
+        _: &Args,
+    ) {
+        let method = self.source.index.get(id).unwrap();
+        let docs = method.docs.as_deref().unwrap_or_default();
         writer.set_prefix("///".into());
-        self.source
-            .index
-            .get(id)
-            .unwrap()
-            .docs
-            .as_ref()
-            .cloned()
-            .unwrap_or_else(|| "".to_owned())
-            .lines()
-            .for_each(|l| {
-                writer.write_line(l);
-            });
+        docs.lines().for_each(|l| {
+            writer.write_line(l);
+        });
+        if let Some(deprecation) = &method.deprecation {
+            write_deprecation_note(docs, deprecation, writer);
+        }
+        if config.emit_docs_rs_links {
+            let url = format!(
+                "{}#method.{}",
+                self.docs_rs_url(),
+                method.name.as_deref().unwrap()
+            );
+            write_docs_rs_link(docs, &url, writer);
+        }
         writer.clear_prefix();
     }
 
-    /// Writes the contents of the impl block for this wrapper
-    ///
-    /// As:
+    /// Returns the version the given method is available since, if known.
     ///
-    /// ```rust,ignore
-    ///     impl {
-    ///     ... // this!
-    ///     }
-    /// ```
-    pub fn write_impl_block_body(&self, writer: &mut PrettyWriter, _: &Args) {
-        self.config.lua_methods.iter().for_each(|v| {
-            writer.write_postfixed_line(v, ";");
+    /// Versions declared in the config take precedence over the ones found in rustdoc.
+    pub fn method_version(&self, method: &Item, config: &Config) -> Option<String> {
+        let name = method.name.as_deref()?;
+
+        self.config.since.get(name).cloned().or_else(|| {
+            (config.versioning.source == VersionSource::Rustdoc)
+                .then(|| method.attrs.iter().find_map(|attr| parse_since_attr(attr)))
+                .flatten()
         })
     }
 
-    /// Generates all derive flags for the type,
-    ///
-    /// Returns additional imports necessary for the generated methods
+    /// Writes the version note and `cfg` attribute for the given method, if its version is known
     ///
+    /// As:
     /// ```rust,ignore
-    /// my_type::Type : Value:
-    /// ... // flags go here
+    ///
+    /// my_macro_key : Value :
+    ///  Methods(
+    ///        /// Available since version `0.10`
+    ///        #[cfg(feature = "bevy_0_10")]
+    ///        my_method(usize) -> u32
+    ///  )
     /// ```
-    pub fn write_derive_flags_body(
+    pub fn write_method_version(&self, method: &Item, config: &Config, writer: &mut PrettyWriter) {
+        if let Some(version) = self.method_version(method, config) {
+            writer.write_line("///");
+            writer.write_line(&format!("/// Available since version `{version}`"));
+            if let Some(attr) = config.versioning.cfg_attribute(&version) {
+                writer.write_line(&attr);
+            }
+        }
+    }
+
+    /// Returns true if a rhai wrapper is generated for this type.
+    ///
+    /// Rhai values are cloned, so only `Clone` types can be wrapped, and types with hand written rhai proxies are left alone
+    pub fn has_rhai_wrapper(&self, config: &Config) -> bool {
+        self.implemented_traits.contains("Clone")
+            && !config.manual_rhai_types.contains(self.wrapped_type)
+    }
+
+    /// Writes the macro invocation generating the wrappers of this item in the given languages,
+    /// followed by the native lua wrapper if this type is on the native fast path
+    pub fn write_invocation(
         &mut self,
         config: &Config,
+        languages: &[Language],
         writer: &mut PrettyWriter,
         args: &Args,
     ) {
-        if self.implemented_traits.contains("Clone") {
-            // this flag requires cloning
-            writer.write_line("Clone +");
+        if self.config.native
+            && !self.is_native(args)
+            && languages.contains(&Language::Lua)
+            && args.verbose
+        {
+            let reason = if self.implemented_traits.contains("Clone") {
+                "it has `lua_methods`"
+            } else {
+                "it is not `Clone`"
+            };
+            eprintln!(
+                "{}: excluded from the native fast path, {reason}, its lua wrapper is generated through the macro instead",
+                self.wrapped_type
+            );
         }
 
-        if self.implemented_traits.contains("Debug") {
-            // this flag requires cloning
-            writer.write_line("Debug +");
+        if languages.contains(&Language::Rhai)
+            && !self.implemented_traits.contains("Clone")
+            && args.verbose
+        {
+            eprintln!(
+                "{}: excluded the rhai wrapper, only `Clone` types can be wrapped for rhai",
+                self.wrapped_type
+            );
         }
 
-        let mut used_method_identifiers: HashSet<&str> = HashSet::default();
-
-        writer.write_line("Methods");
-        writer.open_paren();
-        let mut has_global_methods = false;
-        self.impl_items
+        // the lua wrapper of native types is written out directly after the invocation for the other languages
+        let is_native = self.is_native(args);
+        let languages = languages
             .iter()
-            .flat_map(|(_, items)| items.iter())
-            .for_each(|(impl_, v)| {
-                // only select trait methods are allowed
-                if let Some(trait_) = &impl_.trait_ {
-                    if self
-                        .config
-                        .traits
-                        .iter()
-                        .any(|f| {
-                            trait_.name == f.name
-                        })
-                    {
-                        // keep going
-                    } else {
-                        return;
-                    }
-                };
+            .copied()
+            .filter(|l| match l {
+                Language::Lua => !is_native,
+                Language::Rhai => self.has_rhai_wrapper(config),
+            })
+            .collect::<Vec<_>>();
 
-                let (decl, generics) = match &v.inner {
-                    ItemEnum::Function(f) => (&f.decl, &f.generics),
-                    _ => return,
-                };
+        // the derive flags are always written since they find what's wrapped,
+        // even if no invocation needs them
+        let mut derive_flags = PrettyWriter::new();
+        self.write_derive_flags_body(config, &mut derive_flags, args);
 
-                let mut errors = Vec::default();
+        if !languages.is_empty() {
+            // macro invocation
+            writer.write_no_newline("impl_script_newtype!");
+            writer.open_brace();
+            writer.write_line(&Language::on_feature_attribute(&languages));
 
-                let mut inner_writer = PrettyWriter::new();
+            self.write_type_docstring(config, writer, args);
+            writer.write_indentation();
+            self.write_inline_full_path(writer, args);
+            writer.write_inline(" : ");
+            writer.newline();
 
-                self.write_method_docstring(&v.id, &mut inner_writer, args);
+            writer.extend(derive_flags);
 
-                inner_writer.write_inline(v.name.as_ref().unwrap());
-                inner_writer.write_inline("(");
-                let mut is_global_method = true;
-                decl.inputs
-                    .iter()
-                    .enumerate()
-                    .for_each(|(i, (declaration_name, tp))| {
-                        let arg_type: Result<ArgType, _> = tp.try_into();
+            // languages without any manually written functions get no impl block
+            for language in &languages {
+                let mut impl_block = PrettyWriter::new();
+                self.write_impl_block_body(*language, &mut impl_block, args);
+                if impl_block.is_empty() {
+                    continue;
+                }
+                writer.write_line(&format!("{} impl", language.name()));
+                writer.open_brace();
+                writer.extend(impl_block);
+                writer.close_brace();
+            }
 
-                        if let Ok(arg_type) = arg_type {
-                            // if the underlying ident is self, we shouldn't wrap it when printing it
-                            // if type is unknown no wrapper exists
-                            let wrapper_type: Option<ArgWrapperType> = ArgWrapperType::with_config(self.wrapped_type, &arg_type, config);
+            writer.close_brace();
+        }
 
-                            match wrapper_type {
-                                Some(w) => {
-                                    inner_writer.write_inline(&Arg::new(arg_type, w).to_string())
-                                }
-                                None => {
-                                    inner_writer.write_inline(&format!("<invalid: {arg_type}>"));
-                                    errors.push(format!("Unsupported argument {}, not a wrapped type or primitive", arg_type));
-                                    return;
-                                }
-                            };
+        if is_native {
+            self.write_native_lua(config, writer, args);
+        }
+    }
 
-                            if declaration_name != "self" && i + 1 != decl.inputs.len() {
-                                inner_writer.write_inline(",");
-                            } else if declaration_name == "self" {
-                                is_global_method = false;
-                                // macro needs to recognize the self receiver
-                                inner_writer.write_inline(":");
-                            }
-                        } else {
-                            errors.push(format!("Unsupported argument, Not a simple type: {}.", arg_type.unwrap_err()))
-                        };
-                    });
+    /// Writes the contents of the impl block for this wrapper
+    ///
+    /// As:
+    ///
+    /// ```rust,ignore
+    ///     impl {
+    ///     ... // this!
+    ///     }
+    /// ```
+    pub fn write_impl_block_body(
+        &self,
+        language: Language,
+        writer: &mut PrettyWriter,
+        args: &Args,
+    ) {
+        // manually written methods are only available for lua, rhai only gets the generated `type_name` and exclusion stubs
+        if language == Language::Rhai {
+            if !self.impl_items.contains_key("type_name") {
+                writer.write_line(&format!("/// {TYPE_NAME_DOCS}"));
+                writer.write_postfixed_line(
+                    &format!(
+                        "fn \"type_name\" => |_s: &mut {}| -> Result<{RHAI}::Dynamic, Box<{RHAI}::EvalAltResult>> {{Ok({:?}.into())}}",
+                        self.wrapper_name(Language::Rhai),
+                        self.type_name()
+                    ),
+                    ";",
+                );
+            }
+            self.write_rhai_exclusion_stubs(writer);
+            return;
+        }
 
-                if is_global_method {
-                    has_global_methods = true;
+        self.config.lua_methods.iter().for_each(|v| {
+            writer.write_postfixed_line(v, ";");
+        });
+
+        self.lua_impl_entries(args)
+            .iter()
+            .for_each(|entry| entry.write(writer));
+
+        if args.print_errors {
+            self.enum_variants.iter().for_each(|variant| {
+                if let Err(reason) = &variant.payload {
+                    writer.write_line(&format!(
+                        "// Exclusion reason: constructor of variant `{}`, {reason}",
+                        variant.name
+                    ));
                 }
+            });
+        }
+    }
 
-                inner_writer.write_inline(")");
+    /// The functions of the lua impl block generated for this type, apart from the `lua_methods` of its config
+    pub fn lua_impl_entries(&self, args: &Args) -> Vec<LuaImplEntry> {
+        let mut entries = Vec::default();
+        let wrapper_name = self.wrapper_name(Language::Lua);
+        let wrapped_type = self.wrapped_type;
 
-                if let Some(tp) = &decl.output{
-                    let arg_type: Result<ArgType, _> = tp.try_into();
-                    if let Ok(arg_type) = arg_type {
-                        if let ArgType::Ref { .. } = arg_type {
-                            errors.push("references are not supported as return types".to_owned());
-                            return;
-                        }
+        self.primitive_conversions.iter().for_each(|c| match c {
+            PrimitiveConversion::From(primitive) => entries.push(LuaImplEntry::new(
+                format!("Constructs a `{wrapped_type}` from a `{primitive}`"),
+                true,
+                format!("from_{primitive}"),
+                format!("|_,v: {primitive}| {{Ok({wrapper_name}::new(<{wrapped_type} as From<{primitive}>>::from(v)))}}"),
+            )),
+            PrimitiveConversion::Into(primitive) => entries.push(LuaImplEntry::new(
+                format!("Converts this `{wrapped_type}` into a `{primitive}`"),
+                false,
+                format!("into_{primitive}"),
+                format!("|_,s,()| {{Ok(<{primitive} as From<{wrapped_type}>>::from(s.inner()?))}}"),
+            )),
+        });
 
-                        // if the underlying ident is self, we shouldn't wrap it when printing it
-                        // if type is unknown, no wrapper type exists
-                        let wrapper_type: Option<ArgWrapperType> = ArgWrapperType::with_config(self.wrapped_type, &arg_type, config);
+        self.serde_methods.iter().for_each(|m| match m {
+            SerdeMethod::ToJson => entries.push(LuaImplEntry::new(
+                format!("Serializes this `{wrapped_type}` into a JSON string"),
+                false,
+                "to_json".to_owned(),
+                "|_,s,()| {s.val(|s| bevy_mod_scripting_lua::serde_json::to_string(s))?.map_err(bevy_mod_scripting_lua::tealr::mlu::mlua::Error::external)}".to_owned(),
+            )),
+            SerdeMethod::FromJson => entries.push(LuaImplEntry::new(
+                format!("Deserializes a `{wrapped_type}` from a JSON string"),
+                true,
+                "from_json".to_owned(),
+                format!("|_,json: String| {{bevy_mod_scripting_lua::serde_json::from_str::<{wrapped_type}>(&json).map({wrapper_name}::new).map_err(bevy_mod_scripting_lua::tealr::mlu::mlua::Error::external)}}"),
+            )),
+        });
 
-                        match wrapper_type {
-                            Some(w) => {
-                                inner_writer.write_inline(" -> ");
-                                inner_writer.write_inline(&Arg::new(arg_type, w).to_string());
-                            }
-                            None => {
-                                errors.push(format!("Unsupported argument, not a wrapped type or primitive {arg_type}"));
-                                inner_writer.write_inline(&format!("<invalid: {arg_type}>"));
-                            }
-                        }
-                    } else {
-                        errors.push(format!("Unsupported argument, not a simple type: {}", arg_type.unwrap_err()))
-                    }
-                };
+        // the name is known at generation time, so no reflection is needed to look it up
+        let overridden = self
+            .config
+            .lua_methods
+            .iter()
+            .any(|m| lua_method_name(m) == Some("type_name"));
+        if !self.impl_items.contains_key("type_name") && !overridden {
+            entries.push(LuaImplEntry::new(
+                TYPE_NAME_DOCS.to_owned(),
+                false,
+                "type_name".to_owned(),
+                format!("|_,_s,()| {{Ok({:?})}}", self.type_name()),
+            ));
+        }
 
-                if !generics.params.is_empty() {
-                    errors.push("Generics on the method".to_owned());
-                }
+        self.enum_variant_entries(&mut entries, args);
+        self.assoc_const_entries(&mut entries);
+        self.exclusion_stub_entries(&mut entries);
+        entries
+    }
 
-                if !errors.is_empty() {
-                    if args.print_errors {
-                        writer.set_prefix("// ".into());
-                        writer.write_line(&format!("Exclusion reason: {}", errors.join(",")));
-                        writer.extend(inner_writer);
-                        writer.clear_prefix();
-                        writer.newline();
-                    }
-                } else {
-                    used_method_identifiers.insert(v.name.as_deref().unwrap());
-                    inner_writer.write_inline(",");
-                    writer.extend(inner_writer);
-                    writer.newline();
-                }
+    /// A static field of the global proxy for each associated constant, read as `Vec3.ZERO`
+    ///
+    /// As:
+    /// ```rust,ignore
+    /// /// All zeroes.
+    /// get fn "ZERO" => |_,_| {Ok(LuaVec3::new(Vec3::ZERO))};
+    /// ```
+    fn assoc_const_entries(&self, entries: &mut Vec<LuaImplEntry>) {
+        let wrapped_type = self.wrapped_type;
+
+        self.assoc_consts.iter().for_each(|const_| {
+            let name = &const_.name;
+            let value = match const_.value.wrapper {
+                ArgWrapperType::Wrapped => format!(
+                    "{}{}::new({wrapped_type}::{name})",
+                    Language::Lua.wrapper_prefix(),
+                    const_.value.type_
+                ),
+                _ => format!("{wrapped_type}::{name}"),
+            };
+
+            let docs = match &const_.docs {
+                Some(docs) => docs.lines().map(|line| format!(" {line}")).collect(),
+                None => vec![format!(" The `{name}` constant")],
+            };
+            entries.push(LuaImplEntry {
+                docs,
+                is_static: true,
+                name: name.to_owned(),
+                closure: format!("|_,_| {{Ok({value})}}"),
+                is_field: true,
             });
+        });
+    }
 
-        self.has_global_methods = has_global_methods;
-        writer.close_paren();
+    /// A stub for every method which could not be wrapped, raising an error explaining why it's unavailable
+    ///
+    /// As:
+    /// ```rust,ignore
+    /// /// Unavailable: Unsupported argument Vec<Entity>, not a wrapped type or primitive
+    /// "despawn_all" => |_,_s,_args: bevy_mod_scripting_lua::tealr::mlu::mlua::MultiValue| {...};
+    /// ```
+    fn exclusion_stub_entries(&self, entries: &mut Vec<LuaImplEntry>) {
+        self.excluded_methods.iter().for_each(|method| {
+            let reasons = method.reasons.join(", ");
+            let msg = format!("method `{}` unavailable: {reasons}", method.name);
+            let error = format!(
+                "Err(bevy_mod_scripting_lua::tealr::mlu::mlua::Error::external(ScriptError::Other({msg:?}.to_owned())))"
+            );
+            let receiver = if method.is_static { "" } else { "_s," };
 
-        writer.write_line("+ Fields");
-        writer.open_paren();
+            entries.push(LuaImplEntry::new(
+                format!("Unavailable: {reasons}"),
+                method.is_static,
+                method.name.clone(),
+                format!("|_,{receiver}_args: bevy_mod_scripting_lua::tealr::mlu::mlua::MultiValue| -> bevy_mod_scripting_lua::tealr::mlu::mlua::Result<()> {{{error}}}"),
+            ));
+        });
+    }
 
-        if let ItemEnum::Struct(struct_) = &self.item.inner {
-            if let StructKind::Plain {
-                fields,
-                fields_stripped: _,
-            } = &struct_.kind
-            {
-                fields
-                    .iter()
-                    .map(|field_| self.source.index.get(field_).unwrap())
-                    .filter_map(|field_| match &field_.inner {
-                        ItemEnum::StructField(type_) => {
-                            Some((field_.name.as_ref().unwrap(), type_, field_))
-                        }
-                        _ => None,
-                    })
-                    .filter_map(|(name, type_, field_)| {
-                        let arg_type: ArgType = type_.try_into().ok()?;
-                        let base_ident = arg_type
-                            .base_ident() // resolve self
-                            .unwrap_or(self.wrapped_type.as_str());
+    /// The rhai counterpart of [`Self::exclusion_stub_entries`], taking as many arguments as the excluded method
+    ///
+    /// As:
+    /// ```rust,ignore
+    /// /// Unavailable: Unsupported argument Vec<Entity>, not a wrapped type or primitive
+    /// fn "despawn_all" => |_s: &mut RhaiWorld, _a_0: bevy_mod_scripting_rhai::rhai::Dynamic| -> Result<..> {...};
+    /// ```
+    fn write_rhai_exclusion_stubs(&self, writer: &mut PrettyWriter) {
+        self.excluded_methods.iter().for_each(|method| {
+            let reasons = method.reasons.join(", ");
+            let msg = format!("method `{}` unavailable: {reasons}", method.name);
+            let receiver = (!method.is_static)
+                .then(|| format!("_s: &mut {}", self.wrapper_name(Language::Rhai)));
+            let params = receiver
+                .into_iter()
+                .chain((0..method.arity).map(|idx| format!("_a_{idx}: {RHAI}::Dynamic")))
+                .collect::<Vec<_>>()
+                .join(", ");
 
-                        // if the underlying ident is self, we shouldn't wrap it when printing it
-                        let wrapper: ArgWrapperType = arg_type
-                            .is_self()
-                            .then_some(ArgWrapperType::None)
-                            .or_else(|| {
-                                config
-                                    .primitives
-                                    .contains(base_ident)
-                                    .then_some(ArgWrapperType::Raw)
-                            })
-                            .or_else(|| {
-                                config
-                                    .types
-                                    .contains_key(base_ident)
-                                    .then_some(ArgWrapperType::Wrapped)
-                            })
-                            // we allow this since we later resolve unknown types to be resolved as ReflectedValues
-                            .unwrap_or(ArgWrapperType::None);
+            writer.write_line(&format!("/// Unavailable: {reasons}"));
+            writer.write_postfixed_line(
+                &format!(
+                    "{}fn {:?} => |{params}| -> Result<{RHAI}::Dynamic, Box<{RHAI}::EvalAltResult>> {{Err(Box::new({RHAI}::EvalAltResult::ErrorRuntime({msg:?}.into(), {RHAI}::Position::NONE)))}}",
+                    if method.is_static { "static " } else { "" },
+                    escape_keyword(&method.name, Language::Rhai),
+                ),
+                ";",
+            );
+        });
+    }
+
+    /// A constructor function and an `is_<variant>` predicate for each variant,
+    /// as well as a `variant_name` accessor if this is an enum
+    ///
+    /// As:
+    /// ```rust,ignore
+    /// static fn "Pressed" => |_,()| {Ok(LuaButtonState::new(ButtonState::Pressed))};
+    /// "is_pressed" => |_,s,()| {Ok(s.val(|s| matches!(s, ButtonState::Pressed{..}))?)};
+    /// ```
+    fn enum_variant_entries(&self, entries: &mut Vec<LuaImplEntry>, args: &Args) {
+        if self.enum_variants.is_empty() {
+            return;
+        }
 
-                        let arg = Arg::new(arg_type, wrapper);
-                        let mut reflectable_type = arg.to_string();
+        let wrapper_name = self.wrapper_name(Language::Lua);
+        let wrapped_type = self.wrapped_type;
 
-                        // if we do not have an appropriate wrapper and this is not a primitive or it's not public
-                        // we need to go back to the reflection API
-                        if arg.wrapper == ArgWrapperType::None {
-                            if field_.attrs.iter().any(|attr| attr == "#[reflect(ignore)]") {
-                                return None;
+        self.enum_variants.iter().for_each(|variant| {
+            let name = &variant.name;
+            match &variant.payload {
+                Ok(payload) if !self.impl_items.contains_key(name.as_str()) => {
+                    let params: Vec<_> = (0..payload.len()).map(|idx| format!("a_{idx}")).collect();
+                    let types: Vec<_> = payload
+                        .iter()
+                        .map(|arg| match arg.wrapper {
+                            ArgWrapperType::Wrapped => {
+                                format!("{}{}", Language::Lua.wrapper_prefix(), arg.type_)
                             }
+                            _ => arg.type_.to_string(),
+                        })
+                        .collect();
+                    let values: Vec<_> = payload
+                        .iter()
+                        .zip(&params)
+                        .map(|(arg, param)| match arg.wrapper {
+                            ArgWrapperType::Wrapped => format!("{param}.inner()?"),
+                            _ => param.clone(),
+                        })
+                        .collect();
+                    let value = if payload.is_empty() {
+                        format!("{wrapped_type}::{name}")
+                    } else {
+                        format!("{wrapped_type}::{name}({})", values.join(","))
+                    };
 
-                            reflectable_type = "Raw(ReflectedValue)".to_owned();
-                        }
+                    entries.push(LuaImplEntry::new(
+                        format!("Constructs the `{name}` variant"),
+                        true,
+                        name.to_owned(),
+                        format!(
+                            "|_,({}): ({})| {{Ok({wrapper_name}::new({value}))}}",
+                            params.iter().map(|p| format!("{p},")).collect::<String>(),
+                            types.iter().map(|t| format!("{t},")).collect::<String>(),
+                        ),
+                    ));
+                }
+                Ok(_) => {}
+                Err(reason) => {
+                    if args.verbose {
+                        eprintln!(
+                            "{wrapped_type}::{name}: excluded the variant constructor, {reason}"
+                        );
+                    }
+                }
+            }
 
-                        if let Some(docs) = &field_.docs {
-                            writer.set_prefix("/// ".into());
-                            docs.lines().for_each(|line| {
-                                writer.write_line(line);
-                            });
-                            writer.clear_prefix();
-                        };
+            let predicate = format!("is_{}", to_snake_case(name));
+            if !self.impl_items.contains_key(predicate.as_str()) {
+                entries.push(LuaImplEntry::new(
+                    format!("Returns true if this is the `{name}` variant"),
+                    false,
+                    predicate,
+                    format!(
+                        "|_,s,()| {{Ok(s.val(|s| matches!(s, {wrapped_type}::{name}{{..}}))?)}}"
+                    ),
+                ));
+            }
+        });
 
-                        // add underscore if a method with same name exists
-                        used_method_identifiers
-                            .contains(name.as_str())
-                            .then(|| writer.write_line(&format!("#[rename(\"_{name}\")]")));
-                        writer.write_no_newline(name);
-                        writer.write_inline(": ");
-                        writer.write_inline(&reflectable_type);
-                        writer.write_inline(",");
-                        writer.newline();
+        if !self.impl_items.contains_key("variant_name") {
+            entries.push(LuaImplEntry::new(
+                "Returns the name of the current variant".to_owned(),
+                false,
+                "variant_name".to_owned(),
+                "|_,s,()| {Ok(s.val(|s| bevy::reflect::Enum::variant_name(s).to_owned())?)}"
+                    .to_owned(),
+            ));
+        }
+    }
 
-                        Some(())
-                    })
-                    .for_each(drop);
-            }
+    /// Finds the variants of this type if it's an enum, tuple variants can only be constructed
+    /// if all of their fields are primitives or wrapped types
+    fn find_enum_variants(&self, config: &Config) -> Vec<EnumVariant> {
+        let variants = match &self.item.inner {
+            ItemEnum::Enum(enum_) => &enum_.variants,
+            _ => return Vec::default(),
         };
-        writer.close_paren();
 
-        static BINARY_OPS: [(&str, &str); 5] = [
-            ("add", "Add"),
-            ("sub", "Sub"),
-            ("div", "Div"),
-            ("mul", "Mul"),
-            ("rem", "Rem"),
-        ];
-        writer.write_line("+ BinOps");
-        writer.open_paren();
-        BINARY_OPS.into_iter().for_each(|(op, rep)| {
-            if let Some(items) = self.impl_items.get(op) {
-                items
+        variants
+            .iter()
+            .filter_map(|id| self.source.index.get(id))
+            .filter_map(|item| match &item.inner {
+                ItemEnum::Variant(variant) => Some((item.name.clone()?, variant)),
+                _ => None,
+            })
+            .map(|(name, variant)| {
+                let payload = match variant {
+                    Variant::Plain(_) => Ok(Vec::default()),
+                    Variant::Tuple(fields) => fields
+                        .iter()
+                        .map(|field_| {
+                            let type_ = match field_
+                                .as_ref()
+                                .and_then(|id| self.source.index.get(id))
+                                .map(|f| &f.inner)
+                            {
+                                Some(ItemEnum::StructField(type_)) => type_,
+                                _ => return Err("private fields are not supported".to_owned()),
+                            };
+                            let arg_type: ArgType = type_.try_into().map_err(|e| {
+                                format!("Unsupported field, not a simple type: {e}")
+                            })?;
+                            if !matches!(arg_type, ArgType::Base(_)) {
+                                return Err(format!(
+                                    "Unsupported field {arg_type}, only plain types are supported"
+                                ));
+                            }
+                            match ArgWrapperType::with_config(self.wrapped_type, &arg_type, config)
+                            {
+                                Some(w @ (ArgWrapperType::Raw | ArgWrapperType::Wrapped)) => {
+                                    Ok(Arg::new(arg_type, w))
+                                }
+                                _ => Err(format!(
+                                    "Unsupported field {arg_type}, not a wrapped type or primitive"
+                                )),
+                            }
+                        })
+                        .collect(),
+                    Variant::Struct { .. } => Err("struct variants are not supported".to_owned()),
+                };
+                EnumVariant { name, payload }
+            })
+            .collect()
+    }
+
+    /// Finds the associated constants in the inherent impls of this type which are primitives or wrapped types.
+    /// Constants sharing their name with a method or variant are left out, since they would shadow it
+    fn find_assoc_consts(&self, config: &Config) -> Vec<AssocConst> {
+        self.impl_items
+            .iter()
+            .filter(|(name, _)| !self.enum_variants.iter().any(|v| &v.name == *name))
+            .filter_map(|(name, items)| match items.as_slice() {
+                [(impl_, item)] if impl_.trait_.is_none() => match &item.inner {
+                    ItemEnum::AssocConst { type_, .. } => Some((*name, item, type_)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .filter_map(|(name, item, type_)| {
+                let type_ = self.resolve_paths(type_, config);
+                let value = match ArgType::try_from(type_.as_ref()).ok()? {
+                    ArgType::Self_ => Arg::new(
+                        ArgType::Base(self.wrapped_type.to_owned()),
+                        ArgWrapperType::Wrapped,
+                    ),
+                    arg_type @ ArgType::Base(_) => {
+                        match ArgWrapperType::with_config(self.wrapped_type, &arg_type, config)? {
+                            w @ (ArgWrapperType::Raw | ArgWrapperType::Wrapped) => {
+                                Arg::new(arg_type, w)
+                            }
+                            _ => return None,
+                        }
+                    }
+                    _ => return None,
+                };
+                Some(AssocConst {
+                    name: name.to_owned(),
+                    docs: item.docs.clone(),
+                    value,
+                })
+            })
+            .collect()
+    }
+
+    /// Finds the JSON conversions available through the `Serialize` and `Deserialize` impls of this type,
+    /// conversions whose script name clashes with an existing method are skipped
+    fn find_serde_methods(&self) -> Vec<SerdeMethod> {
+        [
+            ("Serialize", "to_json", SerdeMethod::ToJson),
+            ("Deserialize", "from_json", SerdeMethod::FromJson),
+        ]
+        .into_iter()
+        .filter(|(trait_, name, _)| {
+            self.implemented_traits.contains(*trait_) && !self.impl_items.contains_key(name)
+        })
+        .map(|(_, _, method)| method)
+        .collect()
+    }
+
+    /// The key and output of every `Index` impl of this type as written in the `Index` flag along with their manifest,
+    /// i.e. `mut Raw(usize) -> Raw(f32)` if the type also implements `IndexMut` with the same key,
+    /// or the reason the impl can't be exposed to scripts.
+    ///
+    /// Keys must be checked before indexing, which is only possible for `usize` keys of types with the `Sequence` flag
+    fn find_index_exprs(&self, config: &Config) -> Vec<Result<(String, IndexManifest), String>> {
+        let trait_impls = |method: &str, trait_: &'static str| {
+            self.impl_items
+                .get(method)
+                .into_iter()
+                .flatten()
+                .filter(move |(impl_, _)| impl_.trait_.as_ref().map_or(false, |t| t.name == trait_))
+        };
+        // the key is the argument following the receiver of `index` and `index_mut`
+        fn key_of(item: &Item) -> Option<&Type> {
+            match &item.inner {
+                ItemEnum::Function(f) => f.decl.inputs.get(1).map(|(_, tp)| tp),
+                _ => None,
+            }
+        }
+        let to_arg = |tp: &Type, kind: &str| {
+            let arg_type =
+                ArgType::try_from(tp).map_err(|e| format!("Unsupported {kind} type {e}"))?;
+            match ArgWrapperType::with_config(self.wrapped_type, &arg_type, config) {
+                Some(wrapper @ (ArgWrapperType::Raw | ArgWrapperType::Wrapped)) => {
+                    Ok(Arg::new(arg_type, wrapper))
+                }
+                _ => Err(format!("Unsupported {kind} type {arg_type}")),
+            }
+        };
+
+        // indexing written by hand in the config takes precedence, i.e. to return references into matrices
+        let manual_index = self
+            .config
+            .lua_methods
+            .iter()
+            .any(|m| m.contains("MetaMethod::Index"));
+
+        trait_impls("index", "Index")
+            .map(|(impl_, item)| {
+                if manual_index {
+                    return Err(
+                        "Indexing is implemented by the `lua_methods` of the type".to_owned()
+                    );
+                }
+                let key_type = key_of(item).ok_or_else(|| "Expected a key argument".to_owned())?;
+                let key = to_arg(key_type, "key")?;
+                if !self.config.derive_flags.iter().any(|f| f == "Sequence")
+                    || key.type_.to_string() != "usize"
+                {
+                    return Err(format!(
+                        "Unsupported key {}, only sequences indexed by `usize` have keys which can be checked",
+                        key.type_
+                    ));
+                }
+
+                // the output is an associated type of the impl
+                let output_type = impl_
+                    .items
                     .iter()
-                    .filter_map(|(impl_, item)| Some((impl_, item, (&impl_.for_).try_into().ok()?)))
-                    .filter(|(_, _, self_type): &(&&Impl, &&Item, ArgType)| {
-                        let base_ident =
-                            self_type.base_ident().unwrap_or(self.wrapped_type.as_str());
-                        let is_self_type_the_wrapper = (base_ident == self.wrapped_type)
-                            && config.types.contains_key(base_ident);
-                        let is_primitive = config.primitives.contains(base_ident);
-                        is_self_type_the_wrapper || is_primitive
+                    .find_map(|id| {
+                        let item = self.source.index.get(id)?;
+                        match &item.inner {
+                            ItemEnum::AssocType {
+                                default: Some(default),
+                                ..
+                            } if item.name.as_deref() == Some("Output") => Some(default),
+                            _ => None,
+                        }
                     })
-                    .for_each(|(impl_, item, _self_type)| {
-                        let _ = match &item.inner {
-                            ItemEnum::Function(m) => {
-                                m.decl
-                                    .inputs
-                                    .iter()
-                                    .map(|(_, t)| {
-                                        // check arg is valid
-                                        let arg_type: ArgType = t.try_into()?;
+                    .ok_or_else(|| "Expected an `Output` type".to_owned())?;
+                let output = to_arg(output_type, "output")?;
 
-                                        // if the underlying ident is self, we shouldn't wrap it when printing it
-                                        let wrapper_type = ArgWrapperType::with_config(
-                                            self.wrapped_type,
-                                            &arg_type,
-                                            config,
-                                        )
-                                        .unwrap();
+                let is_mut = trait_impls("index_mut", "IndexMut").any(|(_, item)| {
+                    key_of(item)
+                        .and_then(|tp| ArgType::try_from(tp).ok())
+                        .map_or(false, |tp| tp.to_string() == key.type_.to_string())
+                });
 
-                                        Ok(Arg::new(arg_type, wrapper_type).to_string())
-                                    })
-                                    .collect::<Result<Vec<_>, _>>()
-                                    .map(|v| v.join(&format!(" {} ", rep)))
-                                    .and_then(|expr| {
-                                        // then provide return type
-                                        // for these traits that's on associated types within the impl
-                                        let out_type = impl_
-                                            .items
-                                            .iter()
-                                            .find_map(|v| {
-                                                let item = self.source.index.get(v).unwrap();
-                                                if let ItemEnum::AssocType { default, .. } =
-                                                    &item.inner
-                                                {
-                                                    if let Some("Output") = item.name.as_deref() {
-                                                        return Some(default.as_ref().unwrap());
-                                                    }
-                                                }
-                                                None
-                                            })
-                                            .ok_or_else(|| expr.clone())?;
+                let manifest = IndexManifest {
+                    key: key.type_.to_string(),
+                    output: output.type_.to_string(),
+                    mutable: is_mut,
+                };
+                Ok((
+                    format!("{}{key} -> {output}", if is_mut { "mut " } else { "" }),
+                    manifest,
+                ))
+            })
+            .collect()
+    }
 
-                                        let arg_type: ArgType = out_type.try_into()?;
-                                        // if the underlying ident is self, we shouldn't wrap it when printing it
-                                        let wrapper_type: ArgWrapperType =
-                                            ArgWrapperType::with_config(
-                                                self.wrapped_type,
-                                                &arg_type,
-                                                config,
-                                            )
-                                            .unwrap();
+    /// Finds the `From` impls converting between this type and a primitive.
+    ///
+    /// Conversions whose script name clashes with an existing method are skipped,
+    /// conversions into primitives require the type to be `Clone`
+    fn find_primitive_conversions(&self, config: &Config) -> Vec<PrimitiveConversion> {
+        // the primitive type argument of `From<T>`, if `T` is a primitive
+        let from_primitive = |trait_: &Path| -> Option<String> {
+            if trait_.name != "From" {
+                return None;
+            }
+            match trait_.args.as_deref()? {
+                GenericArgs::AngleBracketed { args, .. } => match args.as_slice() {
+                    [GenericArg::Type(type_)] => {
+                        let arg_type: ArgType = type_.try_into().ok()?;
+                        match arg_type {
+                            ArgType::Base(b) if config.is_primitive(&b) => Some(b),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                },
+                _ => None,
+            }
+        };
 
-                                        if wrapper_type == ArgWrapperType::None {
-                                            return Err(arg_type.to_string());
-                                        }
+        let from = self
+            .impl_items
+            .get("from")
+            .into_iter()
+            .flatten()
+            .filter_map(|(impl_, _)| from_primitive(impl_.trait_.as_ref()?))
+            .map(PrimitiveConversion::From);
 
-                                        let return_string =
-                                            Arg::new(arg_type, wrapper_type).to_string();
+        // impls on primitives are not listed with the type, they can only be found by searching the whole crate
+        let into = self
+            .source
+            .index
+            .values()
+            .filter(|_| self.implemented_traits.contains("Clone"))
+            .filter_map(|item| match &item.inner {
+                ItemEnum::Impl(i) => Some(i),
+                _ => None,
+            })
+            .filter_map(|i| {
+                let trait_ = i.trait_.as_ref()?;
+                let converts_self = matches!(
+                    trait_.args.as_deref()?,
+                    GenericArgs::AngleBracketed { args, .. }
+                        if matches!(args.as_slice(), [GenericArg::Type(Type::ResolvedPath(p))] if p.id == self.item.id)
+                );
+                if trait_.name != "From" || !converts_self {
+                    return None;
+                }
+                let for_: ArgType = (&i.for_).try_into().ok()?;
+                match for_ {
+                    ArgType::Base(b) if config.is_primitive(&b) => Some(b),
+                    _ => None,
+                }
+            })
+            .map(PrimitiveConversion::Into);
 
-                                        writer.write_no_newline(&expr);
-                                        writer.write_inline(" -> ");
-                                        writer.write_inline(&return_string);
-                                        writer.write_inline(",");
-                                        writer.newline();
-                                        Ok(())
-                                    })
-                            }
-                            _ => panic!("Expected method"),
-                        };
-                    })
+        let mut conversions: Vec<PrimitiveConversion> = Vec::default();
+        from.chain(into).for_each(|c| {
+            let script_name = match &c {
+                PrimitiveConversion::From(p) => format!("from_{p}"),
+                PrimitiveConversion::Into(p) => format!("into_{p}"),
+            };
+            if !self.impl_items.contains_key(script_name.as_str()) && !conversions.contains(&c) {
+                conversions.push(c);
             }
         });
-        writer.close_paren();
+        conversions
+    }
 
-        static UNARY_OPS: [(&str, &str); 1] = [("neg", "Neg")];
+    /// Writes the public primitive fields of the `Deref` target of this type as additional fields prefixed with `deref_`.
+    ///
+    /// Only types with a single `Deref` impl are considered, the fields are accessed through auto deref,
+    /// so they are read only unless the type also implements `DerefMut`
+    fn write_deref_fields(
+        &self,
+        config: &Config,
+        used_method_identifiers: &HashSet<String>,
+        writer: &mut PrettyWriter,
+    ) {
+        let deref_impls: Vec<_> = self
+            .impl_items
+            .get("deref")
+            .into_iter()
+            .flatten()
+            .filter(|(impl_, _)| impl_.trait_.as_ref().map_or(false, |t| t.name == "Deref"))
+            .collect();
 
-        writer.write_line("+ UnaryOps");
-        writer.open_paren();
-        UNARY_OPS.into_iter().for_each(|(op, rep)| {
-            if let Some(items) = self.impl_items.get(op) {
-                items.iter().for_each(|(_, _)| {
-                    writer.write_line(&format!("{rep} self -> self"));
-                });
+        let impl_ = match deref_impls.as_slice() {
+            [(impl_, _)] => impl_,
+            _ => return,
+        };
+
+        let target = impl_.items.iter().find_map(|id| {
+            let item = self.source.index.get(id)?;
+            match &item.inner {
+                ItemEnum::AssocType {
+                    default: Some(type_),
+                    ..
+                } if item.name.as_deref() == Some("Target") => Some(type_),
+                _ => None,
             }
         });
-        writer.close_paren();
 
-        self.config.derive_flags.iter().for_each(|flag| {
-            writer.write_inline("+ ");
-            flag.lines().for_each(|line| {
-                writer.write_line(line);
+        // targets from other crates are not in the index
+        let fields = match target
+            .and_then(|t| match t {
+                Type::ResolvedPath(path) => self.source.index.get(&path.id),
+                _ => None,
+            })
+            .map(|t| &t.inner)
+        {
+            Some(ItemEnum::Struct(struct_)) => match &struct_.kind {
+                StructKind::Plain { fields, .. } => fields,
+                _ => return,
+            },
+            _ => return,
+        };
+
+        let readonly = !self.implemented_traits.contains("DerefMut");
+
+        // auto deref finds the fields of this type first, so target fields of the same name cannot be reached
+        let own_fields: HashSet<&str> = match &self.item.inner {
+            ItemEnum::Struct(struct_) => match &struct_.kind {
+                StructKind::Plain { fields, .. } => fields
+                    .iter()
+                    .filter_map(|id| self.source.index.get(id)?.name.as_deref())
+                    .collect(),
+                _ => HashSet::default(),
+            },
+            _ => HashSet::default(),
+        };
+
+        fields
+            .iter()
+            .filter_map(|id| self.source.index.get(id))
+            .filter(|field_| matches!(field_.visibility, Visibility::Public))
+            .filter_map(|field_| match &field_.inner {
+                ItemEnum::StructField(type_) => Some((field_, ArgType::try_from(type_).ok()?)),
+                _ => None,
+            })
+            .for_each(|(field_, arg_type)| {
+                let type_ = match &arg_type {
+                    ArgType::Base(b) if config.is_primitive(b) => b,
+                    _ => return,
+                };
+                let name = field_.name.as_deref().unwrap();
+                let script_name = format!("deref_{name}");
+                if used_method_identifiers.contains(script_name.as_str())
+                    || own_fields.contains(name)
+                    || own_fields.contains(script_name.as_str())
+                {
+                    return;
+                }
+
+                if let Some(docs) = &field_.docs {
+                    writer.set_prefix("/// ".into());
+                    docs.lines().for_each(|line| {
+                        writer.write_line(line);
+                    });
+                    writer.clear_prefix();
+                };
+                writer.write_line(&format!("#[rename(\"{script_name}\")]"));
+                if readonly {
+                    writer.write_line("#[readonly]");
+                }
+                writer.write_line(&format!("{name}: Raw({type_}),"));
             });
-        });
+    }
+
+    /// The callback argument as written in the `Methods` flag, i.e. `Callback(Raw(f32),Wrapped(&Vec3) -> Raw(bool))`,
+    /// together with its description in the manifest, if the `impl Trait` bounds describe a closure
+    /// taking primitives or wrapped types, or shared references to them, and returning nothing or a primitive.
+    ///
+    /// Closures with further bounds such as `'static` or `Send` could be kept around after the method returns,
+    /// when the script function may no longer be called, so only the lone `Fn`, `FnMut` and `FnOnce` bounds are supported
+    fn callback_arg(
+        &self,
+        bounds: &[GenericBound],
+        config: &Config,
+    ) -> Result<(String, String), String> {
+        let (fn_trait, inputs, output) = match bounds {
+            [GenericBound::TraitBound { trait_, .. }] => match (trait_.name.rsplit("::").next(), trait_.args.as_deref()) {
+                (Some(fn_trait @ ("Fn" | "FnMut" | "FnOnce")), Some(GenericArgs::Parenthesized { inputs, output })) => {
+                    (fn_trait, inputs, output)
+                }
+                _ => return Err(format!("Unsupported argument impl {}, not a closure", trait_.name)),
+            },
+            _ => return Err("Unsupported closure argument, closures with more than one bound may outlive the call".to_owned()),
+        };
+
+        let to_arg = |tp: &Type, is_output: bool| -> Result<Arg, String> {
+            let tp = self.resolve_paths(tp, config);
+            let resolve_self = |a: ArgType| match a {
+                ArgType::Self_ => ArgType::Base(self.wrapped_type.to_owned()),
+                a => a,
+            };
+            let arg_type = match ArgType::try_from(tp.as_ref())
+                .map_err(|e| format!("Unsupported closure argument, not a simple type: {e}"))?
+            {
+                ArgType::Ref {
+                    is_mut: false,
+                    ref_,
+                } if !is_output => ArgType::Ref {
+                    is_mut: false,
+                    ref_: Box::new(resolve_self(*ref_)),
+                },
+                a => resolve_self(a),
+            };
+            let base = match &arg_type {
+                ArgType::Ref {
+                    is_mut: false,
+                    ref_,
+                } if !is_output => ref_.as_ref(),
+                a => a,
+            };
+            match (base, ArgWrapperType::with_config(self.wrapped_type, &arg_type, config)) {
+                (ArgType::Base(_), Some(ArgWrapperType::Raw)) => Ok(Arg::new(arg_type, ArgWrapperType::Raw)),
+                (ArgType::Base(_), Some(ArgWrapperType::Wrapped)) if !is_output => Ok(Arg::new(arg_type, ArgWrapperType::Wrapped)),
+                _ if is_output => Err(format!("Unsupported closure output {arg_type}, not a primitive")),
+                _ => Err(format!("Unsupported closure argument {arg_type}, not a wrapped type, primitive or a shared reference to one")),
+            }
+        };
+
+        let args = inputs
+            .iter()
+            .map(|tp| to_arg(tp, false))
+            .collect::<Result<Vec<_>, _>>()?;
+        let output = output.as_ref().map(|tp| to_arg(tp, true)).transpose()?;
+
+        let written = format!(
+            "Callback({}{})",
+            args.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+            output
+                .as_ref()
+                .map(|o| format!(" -> {o}"))
+                .unwrap_or_default()
+        );
+        let described = format!(
+            "impl {fn_trait}({}){}",
+            args.iter()
+                .map(|a| a.type_.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            output
+                .as_ref()
+                .map(|o| format!(" -> {}", o.type_))
+                .unwrap_or_default()
+        );
+        Ok((written, described))
+    }
+
+    /// Returns true if the given referenced type can be cloned into an owned return value,
+    /// only primitives, strings and wrapped types implementing `Clone` qualify
+    fn is_clone_referent(&self, referent: &ArgType, config: &Config) -> bool {
+        let name = match referent {
+            // string slices are returned as owned strings
+            _ if referent.is_string() => return true,
+            ArgType::Self_ => self.wrapped_type.as_str(),
+            ArgType::Base(b) => b.as_str(),
+            _ => return false,
+        };
+
+        match ArgWrapperType::for_base_ident(name, config) {
+            Some(ArgWrapperType::Raw) => true,
+            Some(ArgWrapperType::Wrapped) if name == self.wrapped_type => {
+                self.implemented_traits.contains("Clone")
+            }
+            Some(ArgWrapperType::Wrapped) => self.crates.iter().any(|source| {
+                source
+                    .index
+                    .values()
+                    .filter(|item| item.name.as_deref() == Some(name))
+                    .filter_map(|item| match &item.inner {
+                        ItemEnum::Struct(s) => Some(&s.impls),
+                        ItemEnum::Enum(e) => Some(&e.impls),
+                        _ => None,
+                    })
+                    .flatten()
+                    .filter_map(|id| source.index.get(id))
+                    .any(|impl_| {
+                        matches!(&impl_.inner, ItemEnum::Impl(Impl { trait_: Some(trait_), .. }) if trait_.name == "Clone")
+                    })
+            }),
+            _ => false,
+        }
+    }
+
+    /// Replaces paths to wrapped types with paths to their names in the config, which differ from their names in rustdoc
+    /// if the types are configured with a `name`. Paths behind references, in slices and arrays and in generic arguments,
+    /// i.e. the `T` of `Option<T>`, are replaced as well.
+    ///
+    /// Paths are resolved by id, so the right entry is found even among types of the same name from several crates
+    fn paths_to_wrapped<'t>(&self, tp: &'t Type, config: &Config) -> Cow<'t, Type> {
+        match tp {
+            Type::ResolvedPath(path) => {
+                let key = self
+                    .source
+                    .index
+                    .get(&path.id)
+                    .and_then(|item| config.newtype_of(item, self.source))
+                    .map(|(key, _)| key)
+                    .filter(|key| path.name.rsplit("::").next() != Some(key.as_str()));
+                let args = match path.args.as_deref() {
+                    Some(GenericArgs::AngleBracketed { args, bindings }) => {
+                        let resolved = args
+                            .iter()
+                            .map(|arg| match arg {
+                                GenericArg::Type(type_) => {
+                                    match self.paths_to_wrapped(type_, config) {
+                                        Cow::Owned(type_) => GenericArg::Type(type_),
+                                        Cow::Borrowed(_) => arg.clone(),
+                                    }
+                                }
+                                _ => arg.clone(),
+                            })
+                            .collect::<Vec<_>>();
+                        (resolved != *args).then(|| {
+                            Box::new(GenericArgs::AngleBracketed {
+                                args: resolved,
+                                bindings: bindings.clone(),
+                            })
+                        })
+                    }
+                    _ => None,
+                };
+
+                if key.is_none() && args.is_none() {
+                    return Cow::Borrowed(tp);
+                }
+                Cow::Owned(Type::ResolvedPath(Path {
+                    name: key.cloned().unwrap_or_else(|| path.name.clone()),
+                    id: path.id.clone(),
+                    args: args.or_else(|| path.args.clone()),
+                }))
+            }
+            Type::BorrowedRef {
+                lifetime,
+                mutable,
+                type_,
+            } => match self.paths_to_wrapped(type_, config) {
+                Cow::Owned(type_) => Cow::Owned(Type::BorrowedRef {
+                    lifetime: lifetime.clone(),
+                    mutable: *mutable,
+                    type_: Box::new(type_),
+                }),
+                Cow::Borrowed(_) => Cow::Borrowed(tp),
+            },
+            Type::Slice(type_) => match self.paths_to_wrapped(type_, config) {
+                Cow::Owned(type_) => Cow::Owned(Type::Slice(Box::new(type_))),
+                Cow::Borrowed(_) => Cow::Borrowed(tp),
+            },
+            Type::Array { type_, len } => match self.paths_to_wrapped(type_, config) {
+                Cow::Owned(type_) => Cow::Owned(Type::Array {
+                    type_: Box::new(type_),
+                    len: len.clone(),
+                }),
+                Cow::Borrowed(_) => Cow::Borrowed(tp),
+            },
+            _ => Cow::Borrowed(tp),
+        }
+    }
+
+    /// Resolves the paths of a type in the API of this type, paths to this type become `Self` if it's renamed
+    /// and paths to other wrapped types are replaced as in [`Self::paths_to_wrapped`]
+    fn resolve_paths<'t>(&self, tp: &'t Type, config: &Config) -> Cow<'t, Type> {
+        match self.config.name {
+            Some(_) => match paths_to_self(tp, &self.item.id) {
+                Cow::Borrowed(tp) => self.paths_to_wrapped(tp, config),
+                self_ => self_,
+            },
+            None => self.paths_to_wrapped(tp, config),
+        }
+    }
+
+    /// Returns true if the given trait is implemented with this type as the only generic argument (explicit or default),
+    /// i.e. `impl PartialOrd for Type` but not `impl PartialOrd<f32> for Type`
+    pub(crate) fn implements_with_self(&self, trait_name: &str) -> bool {
+        let impls = match &self.item.inner {
+            ItemEnum::Struct(s) => &s.impls,
+            ItemEnum::Enum(e) => &e.impls,
+            _ => return false,
+        };
+
+        impls
+            .iter()
+            .filter_map(|id| self.source.index.get(id))
+            .any(|item| match &item.inner {
+                ItemEnum::Impl(Impl {
+                    trait_: Some(trait_),
+                    ..
+                }) if trait_.name == trait_name => match trait_.args.as_deref() {
+                    None => true,
+                    Some(GenericArgs::AngleBracketed { args, .. }) => match args.as_slice() {
+                        [] => true,
+                        [GenericArg::Type(type_)] => ArgType::try_from(type_).map_or(false, |t| {
+                            t.is_self() || t.base_ident() == Some(self.wrapped_type.as_str())
+                        }),
+                        _ => false,
+                    },
+                    _ => false,
+                },
+                _ => false,
+            })
+    }
+
+    /// Generates all derive flags for the type,
+    ///
+    /// Returns additional imports necessary for the generated methods
+    ///
+    /// ```rust,ignore
+    /// my_type::Type : Value:
+    /// ... // flags go here
+    /// ```
+    pub fn write_derive_flags_body(
+        &mut self,
+        config: &Config,
+        writer: &mut PrettyWriter,
+        args: &Args,
+    ) {
+        if self.implemented_traits.contains("Clone") {
+            // this flag requires cloning
+            writer.write_line("Clone +");
+        }
+
+        // `Display` is preferred for strings, the `Debug` flag is still needed for the wrapper to implement `Debug`
+        if self.implemented_traits.contains("Debug") && self.implemented_traits.contains("Display")
+        {
+            writer.write_line("Debug +");
+        }
+        writer.write_line(&format!("{} +", to_string_flag(&self.implemented_traits)));
+
+        let mut used_method_identifiers: HashSet<String> = HashSet::default();
+        let mut manifest = TypeManifest {
+            name: self.wrapped_type.to_owned(),
+            path: if self.config.import_path.is_empty() {
+                self.path_components.join("::")
+            } else {
+                self.config.import_path.clone()
+            },
+            ..Default::default()
+        };
+
+        // comparison metamethods are only ever called with two values of the same type
+        for trait_ in ["PartialEq", "PartialOrd"] {
+            if self.implements_with_self(trait_) {
+                writer.write_line(&format!("{trait_} +"));
+                manifest.comparison_ops.push(OpManifest {
+                    op: trait_.to_owned(),
+                    args: vec!["self".to_owned(), "self".to_owned()],
+                    output: "bool".to_owned(),
+                });
+            }
+        }
+
+        writer.write_line("Methods");
+        writer.open_paren();
+        let mut has_global_methods = false;
+        // the first wrapped constructor, it is also exposed as `new` which is where scripts look for one
+        let mut constructor: Option<(String, String, MethodManifest, Option<NativeMethod>)> = None;
+        let mut methods = Count::default();
+        let mut exclusions: BTreeMap<String, usize> = BTreeMap::default();
+        let mut excluded_methods: Vec<ExcludedMethod> = Vec::default();
+        let mut native = NativeApi::default();
+        let mut method_outcomes: BTreeMap<String, MethodOutcome> = BTreeMap::default();
+
+        // only select trait methods are allowed
+        let is_allowed_impl = |impl_: &Impl| {
+            impl_.trait_.as_ref().map_or(true, |trait_| {
+                self.config.traits.iter().any(|f| trait_.name == f.name)
+            })
+        };
+
+        // methods with the same name coming from several traits (or a trait and the type itself) would clash,
+        // so the trait methods are called through `<Type as Trait>::method` and exposed under the name `trait_method`
+        let colliding: HashSet<&str> = self
+            .impl_items
+            .iter()
+            .filter(|(_, items)| {
+                items
+                    .iter()
+                    .filter(|(impl_, _)| is_allowed_impl(impl_))
+                    .count()
+                    > 1
+            })
+            .map(|(name, _)| *name)
+            .collect();
+
+        self.impl_items
+            .iter()
+            .flat_map(|(_, items)| items.iter())
+            // methods with instantiations in the config are generated once per instantiation
+            .flat_map(|(impl_, v)| {
+                let instantiations: Vec<_> = self
+                    .config
+                    .generic_methods
+                    .iter()
+                    .filter(|g| v.name.as_deref() == Some(g.method.as_str()))
+                    .map(Some)
+                    .collect();
+                if instantiations.is_empty() {
+                    vec![(*impl_, *v, None)]
+                } else {
+                    instantiations.into_iter().map(|g| (*impl_, *v, g)).collect()
+                }
+            })
+            .for_each(|(impl_, v, instantiation)| {
+                if !is_allowed_impl(impl_) {
+                    return;
+                }
+
+                let (decl, generics, header) = match &v.inner {
+                    ItemEnum::Function(f) => (&f.decl, &f.generics, &f.header),
+                    _ => return,
+                };
+
+                let mut errors = Vec::default();
+
+                let name = v.name.as_deref().unwrap();
+
+                // the generic type parameters of the method are substituted with the type arguments of the instantiation,
+                // methods with other kinds of generic parameters or without an instantiation are not supported
+                let type_params: Vec<&str> = generics
+                    .params
+                    .iter()
+                    .filter(|p| matches!(p.kind, GenericParamDefKind::Type { synthetic: false, .. }))
+                    .map(|p| p.name.as_str())
+                    .collect();
+                let mut type_args = Vec::default();
+                match instantiation {
+                    Some(g) if type_params.len() != g.type_args.len() || type_params.len() != generics.params.len() => {
+                        errors.push(format!(
+                            "Generic method instantiated with {} type arguments, but it has {} type parameters and {} generic parameters in total",
+                            g.type_args.len(),
+                            type_params.len(),
+                            generics.params.len()
+                        ));
+                    }
+                    Some(g) => {
+                        for (param, arg) in type_params.iter().zip(&g.type_args) {
+                            match arg.parse::<ArgType>() {
+                                Ok(arg) => type_args.push((*param, arg)),
+                                Err(e) => errors.push(format!("Invalid type argument `{arg}`: {e}")),
+                            }
+                        }
+                    }
+                    // `impl Trait` arguments are checked on their own
+                    None if generics.params.iter().any(|p| !matches!(p.kind, GenericParamDefKind::Type { synthetic: true, .. })) => {
+                        errors.push("Generics on the method".to_owned())
+                    }
+                    None => {}
+                }
+                // paths are resolved by id, wrapped types are known by their name in the config
+                let to_arg_type = |tp: &Type| {
+                    let tp = self.resolve_paths(tp, config);
+                    ArgType::try_from(tp.as_ref()).map(|tp| tp.substitute_generics(&type_args))
+                };
+                let qualified_trait = impl_
+                    .trait_
+                    .as_ref()
+                    .filter(|_| colliding.contains(name))
+                    .map(|trait_| trait_.name.as_str());
+
+                // overrides of colliding trait methods can be keyed by `Trait::method`
+                let method_override = qualified_trait
+                    .and_then(|trait_| self.config.method_overrides.get(&format!("{trait_}::{name}")))
+                    .or_else(|| self.config.method_overrides.get(name));
+                if method_override.map_or(false, |o| o.skip) {
+                    errors.push("Skipped in the config".to_owned());
+                }
+                // crates can mark methods as not scriptable at the source
+                if has_ignore_tag(v.docs.as_deref()) {
+                    errors.push("Marked as ignored in the source".to_owned());
+                }
+                // types with an allowlist only expose the methods listed in it, which can be keyed like overrides
+                let not_included = self.config.include_methods.as_ref().map_or(false, |included| {
+                    !included.iter().any(|m| {
+                        m == name || qualified_trait.map_or(false, |trait_| *m == format!("{trait_}::{name}"))
+                    })
+                });
+                if not_included {
+                    errors.push("Not in the included methods of the config".to_owned());
+                }
+                let script_name = instantiation
+                    .map(|g| g.script_name.clone())
+                    .or_else(|| method_override.and_then(|o| o.rename.clone()))
+                    .or_else(|| qualified_trait.map(|trait_| format!("{}_{name}", to_snake_case(trait_))))
+                    .unwrap_or_else(|| name.to_owned());
+
+                // associated functions returning the type itself are how scripts construct wrapped values
+                let is_constructor = decl.inputs.iter().all(|(arg_name, _)| arg_name != "self")
+                    && decl.output.as_ref().and_then(|tp| to_arg_type(tp).ok()).map_or(false, |tp| match tp {
+                        ArgType::Self_ => true,
+                        ArgType::Base(base) => base == *self.wrapped_type,
+                        _ => false,
+                    });
+
+                let mut inner_writer = PrettyWriter::new();
+
+                // the native fast path documents methods as they are registered, so the docs are also kept on their own
+                let mut head_writer = PrettyWriter::new();
+                self.write_method_docstring(&v.id, config, &mut head_writer, args);
+                self.write_method_version(v, config, &mut head_writer);
+                let head = head_writer.finish();
+                head.lines().for_each(|line| {
+                    inner_writer.write_line(line);
+                });
+                if is_constructor {
+                    inner_writer.write_line("///");
+                    inner_writer.write_line(&format!(
+                        "/// Constructor, called from scripts through the global `{}` as `{}.{script_name}(...)`",
+                        self.wrapped_type, self.wrapped_type
+                    ));
+                }
+                if script_name != name {
+                    inner_writer.write_line(&format!("#[rename(\"{script_name}\")]"));
+                } else {
+                    write_keyword_renames(name, &args.languages, &mut inner_writer);
+                }
+                if let Some(trait_) = qualified_trait {
+                    inner_writer.write_line(&format!("#[qualified({trait_})]"));
+                }
+                if let Some(g) = instantiation {
+                    inner_writer.write_line(&format!("#[type_args({})]", g.type_args.join(", ")));
+                }
+                if let Some(deprecation) = v.deprecation.as_ref().filter(|_| args.warn_deprecated) {
+                    inner_writer.write_line(&format!("#[deprecated({:?})]", deprecation_message(deprecation)));
+                }
+
+                // const functions without arguments can be evaluated at compile time
+                if args.evaluate_const_fns && header.const_ && decl.inputs.is_empty() && decl.output.is_some() {
+                    inner_writer.write_inline("const ");
+                }
+                inner_writer.write_inline(v.name.as_ref().unwrap());
+                inner_writer.write_inline("(");
+                let mut is_global_method = true;
+                let mut native_receiver = None;
+                let mut native_args = Vec::default();
+                let mut native_output = None;
+                let mut has_callback = false;
+                let mut method_manifest = MethodManifest {
+                    name: script_name.clone(),
+                    receiver: None,
+                    args: Vec::default(),
+                    output: None,
+                    constructor: is_constructor,
+                };
+                decl.inputs
+                    .iter()
+                    .enumerate()
+                    .for_each(|(i, (declaration_name, tp))| {
+                        // closures are passed in as script functions
+                        if let Type::ImplTrait(bounds) = tp {
+                            match self.callback_arg(bounds, config) {
+                                Ok((written, described)) => {
+                                    has_callback = true;
+                                    method_manifest.args.push(described);
+                                    inner_writer.write_inline(&written);
+                                    if i + 1 != decl.inputs.len() {
+                                        inner_writer.write_inline(",");
+                                    }
+                                }
+                                Err(e) => errors.push(e),
+                            }
+                            return;
+                        }
+
+                        let arg_type = to_arg_type(tp);
+
+                        if let Ok(arg_type) = arg_type {
+                            let arg_type = arg_type.resolve_generic_self(self.wrapped_type);
+                            // if the underlying ident is self, we shouldn't wrap it when printing it
+                            // if type is unknown no wrapper exists
+                            let wrapper_type: Option<ArgWrapperType> = ArgWrapperType::with_config(self.wrapped_type, &arg_type, config)
+                                .or_else(|| args.reflect_unknown_types.then(|| ArgWrapperType::reflected_fallback(&arg_type)).flatten());
+
+                            let receiver = (declaration_name == "self").then(|| arg_type.receiver()).flatten();
+
+                            match (receiver, wrapper_type) {
+                                // the macro needs to recognize the self receiver,
+                                // by value receivers are called on a clone of the proxied value
+                                (Some(receiver), _) => {
+                                    if receiver == Receiver::Value && !self.implemented_traits.contains("Clone") {
+                                        errors.push("Methods consuming self are only supported on Clone types".to_owned());
+                                    }
+                                    is_global_method = false;
+                                    native_receiver = Some(receiver);
+                                    method_manifest.receiver = Some(receiver.to_string());
+                                    inner_writer.write_inline(&format!("{receiver}:"));
+                                    return;
+                                }
+                                (None, Some(w)) => {
+                                    method_manifest.args.push(arg_type.to_string());
+                                    let mut arg = Arg::new(arg_type, w);
+                                    if args.validate_numeric_args {
+                                        arg = arg.with_validated_name(declaration_name);
+                                    }
+                                    native_args.push(arg.clone());
+                                    inner_writer.write_inline(&arg.to_string())
+                                }
+                                (None, None) if arg_type.is_mut_slice() => {
+                                    inner_writer.write_inline(&format!("<invalid: {arg_type}>"));
+                                    errors.push(format!("Unsupported argument {arg_type}, mutable slices cannot be safely aliased from scripts"));
+                                    return;
+                                }
+                                (None, None) => {
+                                    inner_writer.write_inline(&format!("<invalid: {arg_type}>"));
+                                    errors.push(ArgWrapperType::map_key_error(&arg_type, config)
+                                        .unwrap_or_else(|| format!("Unsupported argument {}, not a wrapped type or primitive", arg_type)));
+                                    return;
+                                }
+                            };
+
+                            if i + 1 != decl.inputs.len() {
+                                inner_writer.write_inline(",");
+                            }
+                        } else {
+                            errors.push(format!("Unsupported argument, Not a simple type: {}.", arg_type.unwrap_err()))
+                        };
+                    });
+
+                if is_global_method {
+                    has_global_methods = true;
+                }
+
+                inner_writer.write_inline(")");
+
+                if let Some(tp) = &decl.output{
+                    let arg_type = to_arg_type(tp);
+                    if let Ok(arg_type) = arg_type {
+                        let is_result = arg_type.is_result();
+                        let arg_type = arg_type.resolve_generic_self(self.wrapped_type).strip_result_error();
+                        // shared references to `Clone` types are returned as owned copies
+                        let arg_type = match arg_type {
+                            ArgType::Ref { is_mut: false, ref_ } if self.is_clone_referent(&ref_, config) => ArgType::Ref {
+                                is_mut: false,
+                                ref_: Box::new(match *ref_ {
+                                    ArgType::Self_ => ArgType::Base(self.wrapped_type.to_owned()),
+                                    a => a,
+                                }),
+                            },
+                            ArgType::Ref { .. } => {
+                                errors.push("references are not supported as return types".to_owned());
+                                methods.record(false);
+                                return;
+                            }
+                            a => a,
+                        };
+
+                        // if the underlying ident is self, we shouldn't wrap it when printing it
+                        // if type is unknown, no wrapper type exists
+                        let wrapper_type: Option<ArgWrapperType> = ArgWrapperType::with_config(self.wrapped_type, &arg_type, config)
+                            .or_else(|| args.reflect_unknown_types.then(|| ArgWrapperType::reflected_fallback(&arg_type)).flatten());
+
+                        match wrapper_type {
+                            Some(w) => {
+                                native_output = Some(Arg::new(arg_type.clone(), w.clone()));
+                                method_manifest.output = Some(arg_type.to_string());
+                                inner_writer.write_inline(" -> ");
+                                inner_writer.write_inline(&Arg::new(arg_type, w).to_string());
+                            }
+                            None if is_result => {
+                                errors.push(format!("Unsupported Ok type of {arg_type}, not a wrapped type or primitive"));
+                                inner_writer.write_inline(&format!("<invalid: {arg_type}>"));
+                            }
+                            None => {
+                                errors.push(ArgWrapperType::map_key_error(&arg_type, config)
+                                    .unwrap_or_else(|| format!("Unsupported argument, not a wrapped type or primitive {arg_type}")));
+                                inner_writer.write_inline(&format!("<invalid: {arg_type}>"));
+                            }
+                        }
+                    } else {
+                        errors.push(format!("Unsupported argument, not a simple type: {}", arg_type.unwrap_err()))
+                    }
+                };
+
+                // the native fast path only covers the common shapes of arguments and outputs, the rest is excluded
+                if self.is_native(args) && errors.is_empty() {
+                    let method = NativeMethod {
+                        name: name.to_owned(),
+                        script_name: escape_keyword(&script_name, Language::Lua),
+                        docs: head.lines().filter_map(|line| line.strip_prefix("///")).map(str::to_owned).collect(),
+                        attrs: head.lines().filter(|line| line.starts_with("#[")).map(str::to_owned).collect(),
+                        qualified_trait: qualified_trait.map(str::to_owned),
+                        type_args: instantiation.map(|g| g.type_args.clone()).unwrap_or_default(),
+                        receiver: native_receiver,
+                        args: native_args,
+                        output: native_output,
+                    };
+                    let support = if has_callback {
+                        Err("callback arguments".to_owned())
+                    } else {
+                        self.native_method_support(&method)
+                    };
+                    match support {
+                        Ok(()) => native.methods.push(method),
+                        Err(e) => errors.push(format!("Not supported by the native fast path, {e}")),
+                    }
+                }
+
+                methods.record(errors.is_empty());
+                method_outcomes
+                    .entry(qualified_trait.map_or_else(|| name.to_owned(), |trait_| format!("{trait_}::{name}")))
+                    .or_default()
+                    .record(&errors);
+                errors.iter().for_each(|e| *exclusions.entry(e.clone()).or_default() += 1);
+
+                if !errors.is_empty() {
+                    if args.verbose {
+                        eprintln!("{}::{name}: excluded, {}", self.wrapped_type, errors.join(","));
+                    }
+                    if args.print_errors {
+                        writer.set_prefix("// ".into());
+                        writer.write_line(&format!("Exclusion reason: {}", errors.join(",")));
+                        writer.extend(inner_writer);
+                        writer.clear_prefix();
+                        writer.newline();
+                    }
+                    // methods skipped on purpose are not meant to be called at all
+                    if config.emit_exclusion_stubs && !not_included && !method_override.map_or(false, |o| o.skip) {
+                        excluded_methods.push(ExcludedMethod {
+                            name: script_name,
+                            is_static: is_global_method,
+                            arity: decl.inputs.iter().filter(|(name, _)| name != "self").count(),
+                            reasons: errors,
+                        });
+                    }
+                } else {
+                    inner_writer.write_inline(",");
+                    let method = inner_writer.finish();
+                    if is_constructor && constructor.is_none() {
+                        // the alias keeps everything but the docs and the name
+                        let signature = method
+                            .lines()
+                            .filter(|line| !line.starts_with("///") && !line.starts_with("#[rename("))
+                            .collect::<Vec<_>>()
+                            .join("\n");
+                        let native_method = self.is_native(args).then(|| native.methods.last().cloned()).flatten();
+                        constructor = Some((script_name.clone(), signature, method_manifest.clone(), native_method));
+                    }
+                    used_method_identifiers.insert(script_name);
+                    manifest.methods.push(method_manifest);
+                    method.lines().for_each(|line| {
+                        writer.write_line(line);
+                    });
+                    writer.newline();
+                }
+            });
+
+        if let Some((script_name, signature, mut method_manifest, native_method)) =
+            constructor.filter(|_| !used_method_identifiers.contains("new"))
+        {
+            let docs = format!(
+                " Constructs a `{}`, same as `{script_name}`",
+                self.wrapped_type
+            );
+            writer.write_line(&format!("///{docs}"));
+            writer.write_line("#[rename(\"new\")]");
+            // `new` is reserved in rhai, where the alias is registered under the name of the constructor instead
+            if args.languages.contains(&Language::Rhai) {
+                writer.write_line(&format!("#[rename(rhai = \"{script_name}\")]"));
+            }
+            signature.lines().for_each(|line| {
+                writer.write_line(line);
+            });
+            writer.newline();
+            used_method_identifiers.insert("new".to_owned());
+            method_manifest.name = "new".to_owned();
+            manifest.methods.push(method_manifest);
+            if let Some(method) = native_method {
+                native.methods.push(NativeMethod {
+                    script_name: "new".to_owned(),
+                    docs: vec![docs],
+                    ..method
+                });
+            }
+        }
+
+        self.has_global_methods = has_global_methods;
+        // overloads which could be wrapped take precedence over the stub, and only one stub is needed per name
+        excluded_methods.retain(|m| used_method_identifiers.insert(m.name.clone()));
+        self.excluded_methods = excluded_methods;
+        self.coverage.methods = methods;
+        self.exclusions = exclusions;
+        self.method_outcomes = method_outcomes;
+        writer.close_paren();
+
+        writer.write_line("+ Fields");
+        writer.open_paren();
+
+        if let ItemEnum::Struct(struct_) = &self.item.inner {
+            // tuple fields are named by their index, private tuple fields are stripped but still count towards the total
+            let (fields, total): (Vec<(String, &Item)>, usize) = match &struct_.kind {
+                StructKind::Plain {
+                    fields,
+                    fields_stripped: _,
+                } => (
+                    fields
+                        .iter()
+                        .map(|field_| self.source.index.get(field_).unwrap())
+                        .map(|field_| (field_.name.clone().unwrap(), field_))
+                        .collect(),
+                    fields.len(),
+                ),
+                StructKind::Tuple(fields) => (
+                    fields
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(idx, field_)| {
+                            Some((idx.to_string(), self.source.index.get(field_.as_ref()?)?))
+                        })
+                        .collect(),
+                    fields.len(),
+                ),
+                StructKind::Unit => (Vec::default(), 0),
+            };
+
+            let wrapped = fields
+                .iter()
+                .filter_map(|(name, field_)| match &field_.inner {
+                    ItemEnum::StructField(type_) => Some((name, type_, field_)),
+                    _ => None,
+                })
+                .filter_map(|(name, type_, field_)| {
+                    // the inner types of newtypes are often wrapped themselves, possibly under another name
+                    let type_ = self.paths_to_wrapped(type_, config);
+                    let arg_type: ArgType = type_.as_ref().try_into().ok()?;
+                    // slices are only supported as method arguments
+                    if arg_type.is_slice() {
+                        return None;
+                    }
+                    let base_ident = arg_type
+                        .base_ident() // resolve self
+                        .unwrap_or(self.wrapped_type.as_str());
+
+                    // if the underlying ident is self, we shouldn't wrap it when printing it
+                    let wrapper: ArgWrapperType = arg_type
+                        .is_self()
+                        .then_some(ArgWrapperType::None)
+                        .or_else(|| ArgWrapperType::for_base_ident(base_ident, config))
+                        // we allow this since we later resolve unknown types to be resolved as ReflectedValues
+                        .unwrap_or(ArgWrapperType::None);
+
+                    // arrays of wrapped types cannot be referenced into, so we go through reflection instead
+                    let wrapper = if arg_type.is_array() && wrapper == ArgWrapperType::Wrapped {
+                        ArgWrapperType::None
+                    } else if arg_type.map_types().is_some() {
+                        // maps are copied into new script tables on every read
+                        ArgWrapperType::with_config(self.wrapped_type, &arg_type, config)
+                            .unwrap_or(ArgWrapperType::None)
+                    } else {
+                        wrapper
+                    };
+
+                    // maps of wrapped values are read only, setting them would require copying each value out of its proxy
+                    let readonly =
+                        arg_type.map_types().is_some() && wrapper == ArgWrapperType::Wrapped;
+
+                    let arg = Arg::new(arg_type, wrapper);
+                    if self.is_native(args) && !Self::native_field_support(&arg) {
+                        if args.verbose {
+                            eprintln!(
+                                "{}::{name}: excluded, not supported by the native fast path",
+                                self.wrapped_type
+                            );
+                        }
+                        return None;
+                    }
+                    let mut reflectable_type = arg.to_string();
+                    let mut manifest_type = arg.type_.to_string();
+
+                    // if we do not have an appropriate wrapper and this is not a primitive or it's not public
+                    // we need to go back to the reflection API
+                    if arg.wrapper == ArgWrapperType::None {
+                        if field_.attrs.iter().any(|attr| attr == "#[reflect(ignore)]") {
+                            return None;
+                        }
+
+                        reflectable_type = "Raw(ReflectedValue)".to_owned();
+                        manifest_type = "ReflectedValue".to_owned();
+                    }
+
+                    if let Some(docs) = &field_.docs {
+                        writer.set_prefix("/// ".into());
+                        docs.lines().for_each(|line| {
+                            writer.write_line(line);
+                        });
+                        writer.clear_prefix();
+                    };
+
+                    // tuple fields are exposed as `_0`, `_1` etc.
+                    let script_name = match &struct_.kind {
+                        StructKind::Tuple(_) => format!("_{name}"),
+                        _ => name.to_owned(),
+                    };
+
+                    // add underscore if a method with same name exists, or in the languages the name is a keyword of
+                    let script_name = if used_method_identifiers.contains(script_name.as_str()) {
+                        writer.write_line(&format!("#[rename(\"_{script_name}\")]"));
+                        format!("_{script_name}")
+                    } else {
+                        write_keyword_renames(&script_name, &args.languages, writer);
+                        script_name
+                    };
+                    manifest.fields.push(FieldManifest {
+                        name: script_name,
+                        type_: manifest_type,
+                        readonly,
+                    });
+                    if self.is_native(args) {
+                        native.fields.push(NativeField {
+                            member: name.to_owned(),
+                            script_name: escape_keyword(
+                                &manifest.fields.last().unwrap().name,
+                                Language::Lua,
+                            ),
+                            docs: field_
+                                .docs
+                                .iter()
+                                .flat_map(|d| d.lines())
+                                .map(|l| format!(" {l}"))
+                                .collect(),
+                            arg: arg.clone(),
+                            readonly,
+                        });
+                    }
+                    if readonly {
+                        writer.write_line("#[readonly]");
+                    }
+                    writer.write_no_newline(name);
+                    writer.write_inline(": ");
+                    writer.write_inline(&reflectable_type);
+                    writer.write_inline(",");
+                    writer.newline();
+
+                    Some(())
+                })
+                .count();
+
+            self.coverage.fields = Count { total, wrapped };
+        };
+
+        if self.config.expose_deref_fields && !self.is_native(args) {
+            self.write_deref_fields(config, &used_method_identifiers, writer);
+        }
+        writer.close_paren();
+
+        static BINARY_OPS: [(&str, &str); 5] = [
+            ("add", "Add"),
+            ("sub", "Sub"),
+            ("div", "Div"),
+            ("mul", "Mul"),
+            ("rem", "Rem"),
+        ];
+        let mut ops = Count::default();
+        writer.write_line("+ BinOps");
+        writer.open_paren();
+        BINARY_OPS.into_iter().for_each(|(op, rep)| {
+            if let Some(items) = self.impl_items.get(op) {
+                // impls for other self types count as not wrapped
+                ops.total += items.len();
+                items
+                    .iter()
+                    .filter_map(|(impl_, item)| Some((impl_, item, (&impl_.for_).try_into().ok()?)))
+                    .filter(|(_, _, self_type): &(&&Impl, &&Item, ArgType)| {
+                        let base_ident =
+                            self_type.base_ident().unwrap_or(self.wrapped_type.as_str());
+                        match ArgWrapperType::for_base_ident(base_ident, config) {
+                            Some(ArgWrapperType::Wrapped) => base_ident == self.wrapped_type,
+                            Some(ArgWrapperType::Raw) => true,
+                            _ => false,
+                        }
+                    })
+                    .for_each(|(impl_, item, _self_type)| {
+                        let result = match &item.inner {
+                            ItemEnum::Function(m) => {
+                                m.decl
+                                    .inputs
+                                    .iter()
+                                    .map(|(_, t)| {
+                                        // check arg is valid
+                                        let arg_type: ArgType = t.try_into()?;
+
+                                        // if the underlying ident is self, we shouldn't wrap it when printing it
+                                        let wrapper_type = ArgWrapperType::with_config(
+                                            self.wrapped_type,
+                                            &arg_type,
+                                            config,
+                                        )
+                                        .ok_or_else(|| arg_type.to_string())?;
+
+                                        Ok(Arg::new(arg_type, wrapper_type))
+                                    })
+                                    .collect::<Result<Vec<_>, _>>()
+                                    .and_then(|op_args| {
+                                        let expr = op_args
+                                            .iter()
+                                            .map(Arg::to_string)
+                                            .collect::<Vec<_>>()
+                                            .join(&format!(" {} ", rep));
+                                        // then provide return type
+                                        // for these traits that's on associated types within the impl
+                                        let out_type = impl_
+                                            .items
+                                            .iter()
+                                            .find_map(|v| {
+                                                let item = self.source.index.get(v).unwrap();
+                                                if let ItemEnum::AssocType { default, .. } =
+                                                    &item.inner
+                                                {
+                                                    if let Some("Output") = item.name.as_deref() {
+                                                        return Some(default.as_ref().unwrap());
+                                                    }
+                                                }
+                                                None
+                                            })
+                                            .ok_or_else(|| expr.clone())?;
+
+                                        let arg_type: ArgType = out_type.try_into()?;
+                                        // if the underlying ident is self, we shouldn't wrap it when printing it
+                                        let wrapper_type: ArgWrapperType =
+                                            ArgWrapperType::with_config(
+                                                self.wrapped_type,
+                                                &arg_type,
+                                                config,
+                                            )
+                                            .ok_or_else(|| arg_type.to_string())?;
+
+                                        if wrapper_type == ArgWrapperType::None {
+                                            return Err(arg_type.to_string());
+                                        }
+
+                                        if self.is_native(args) {
+                                            let op = NativeBinOp {
+                                                trait_: rep,
+                                                lhs: op_args[0].clone(),
+                                                rhs: op_args[1].clone(),
+                                                output: Arg::new(
+                                                    arg_type.clone(),
+                                                    wrapper_type.clone(),
+                                                ),
+                                            };
+                                            if !self.native_bin_op_support(&op) {
+                                                return Err(format!(
+                                                    "{expr}, not supported by the native fast path"
+                                                ));
+                                            }
+                                            native.bin_ops.push(op);
+                                        }
+
+                                        manifest.bin_ops.push(OpManifest {
+                                            op: rep.to_owned(),
+                                            args: op_args
+                                                .iter()
+                                                .map(|a| a.type_.to_string())
+                                                .collect(),
+                                            output: arg_type.to_string(),
+                                        });
+                                        let return_string =
+                                            Arg::new(arg_type, wrapper_type).to_string();
+
+                                        writer.write_no_newline(&expr);
+                                        writer.write_inline(" -> ");
+                                        writer.write_inline(&return_string);
+                                        writer.write_inline(",");
+                                        writer.newline();
+                                        Ok(())
+                                    })
+                            }
+                            _ => panic!("Expected method"),
+                        };
+
+                        match result {
+                            Ok(()) => ops.wrapped += 1,
+                            Err(type_) if args.verbose => eprintln!(
+                                "{}: excluded a `{rep}` operator, unsupported type {type_}",
+                                self.wrapped_type
+                            ),
+                            Err(_) => {}
+                        }
+                    })
+            }
+        });
+        writer.close_paren();
+
+        static UNARY_OPS: [(&str, &str); 1] = [("neg", "Neg")];
+
+        writer.write_line("+ UnaryOps");
+        writer.open_paren();
+        UNARY_OPS.into_iter().for_each(|(op, rep)| {
+            if let Some(items) = self.impl_items.get(op) {
+                items.iter().for_each(|(_, _)| {
+                    ops.record(true);
+                    if self.is_native(args) && !native.unary_ops.contains(&rep) {
+                        native.unary_ops.push(rep);
+                    }
+                    manifest.unary_ops.push(OpManifest {
+                        op: rep.to_owned(),
+                        args: vec!["self".to_owned()],
+                        output: "self".to_owned(),
+                    });
+                    writer.write_line(&format!("{rep} self -> self"));
+                });
+            }
+        });
+        writer.close_paren();
+
+        // scripts index with `[]`, which maps to a single metamethod, so only the first supported key type is exposed
+        let mut index_written = false;
+        for expr in self.find_index_exprs(config) {
+            let result = expr.and_then(|(expr, index)| {
+                if self.is_native(args) {
+                    Err(format!("`{expr}` is not supported by the native fast path"))
+                } else if index_written {
+                    Err(format!(
+                        "`{expr}` is not the only key type, scripts can only index with one"
+                    ))
+                } else {
+                    Ok((expr, index))
+                }
+            });
+            match result {
+                Ok((expr, index)) => {
+                    writer.write_line("+ Index");
+                    writer.open_paren();
+                    writer.write_line(&format!("{expr},"));
+                    writer.close_paren();
+                    manifest.index = Some(index);
+                    index_written = true;
+                    ops.record(true);
+                }
+                Err(reason) => {
+                    if args.verbose {
+                        eprintln!("{}: excluded an `Index` impl, {reason}", self.wrapped_type);
+                    }
+                    ops.record(false);
+                }
+            }
+        }
+        self.coverage.ops = ops;
+        self.manifest = manifest;
+        self.native = native;
+
+        if self
+            .config
+            .derive_flags
+            .iter()
+            .any(|flag| flag == PRIMITIVE_CONVERSIONS_FLAG)
+        {
+            self.primitive_conversions = self.find_primitive_conversions(config);
+        }
+
+        if config.emit_serde_methods {
+            self.serde_methods = self.find_serde_methods();
+        }
+        self.serde_methods.iter().for_each(|m| {
+            self.manifest.methods.push(match m {
+                SerdeMethod::ToJson => MethodManifest {
+                    name: "to_json".to_owned(),
+                    receiver: Some("&self".to_owned()),
+                    args: Vec::default(),
+                    output: Some("String".to_owned()),
+                    constructor: false,
+                },
+                SerdeMethod::FromJson => MethodManifest {
+                    name: "from_json".to_owned(),
+                    receiver: None,
+                    args: vec!["String".to_owned()],
+                    output: Some(self.wrapped_type.to_owned()),
+                    constructor: false,
+                },
+            })
+        });
+
+        self.enum_variants = self.find_enum_variants(config);
+        self.assoc_consts = self.find_assoc_consts(config);
+        self.assoc_consts.iter().for_each(|const_| {
+            self.manifest.fields.push(FieldManifest {
+                name: const_.name.clone(),
+                type_: const_.value.type_.to_string(),
+                readonly: true,
+            })
+        });
+
+        // static functions are only reachable through the global proxy
+        self.has_global_methods |= self
+            .primitive_conversions
+            .iter()
+            .any(|c| matches!(c, PrimitiveConversion::From(_)))
+            || self.serde_methods.contains(&SerdeMethod::FromJson)
+            || self.enum_variants.iter().any(|v| v.payload.is_ok())
+            || !self.assoc_consts.is_empty();
+
+        self.config
+            .derive_flags
+            .iter()
+            .filter(|flag| *flag != PRIMITIVE_CONVERSIONS_FLAG)
+            .for_each(|flag| {
+                writer.write_inline("+ ");
+                flag.lines().for_each(|line| {
+                    writer.write_line(line);
+                });
+            });
+    }
+}
+
+/// Extracts the version out of attributes such as `#[stable(feature = "x", since = "1.0.0")]`
+fn parse_since_attr(attr: &str) -> Option<String> {
+    let mut attr = attr.to_owned();
+    attr.retain(|c| !c.is_whitespace());
+
+    let start = attr.find("since=\"")? + "since=\"".len();
+    let len = attr[start..].find('"')?;
+    Some(attr[start..start + len].to_owned())
+}
+
+/// The doc comment line excluding a method from scripts, `/// <!-- script:ignore -->` ignoring whitespace.
+///
+/// Rustdoc drops unknown attributes, so the tag is an html comment, which doesn't show up in the rendered documentation either
+const IGNORE_DOC_TAG: &str = "<!--script:ignore-->";
+
+/// Returns true if the given docs contain the tag excluding a method from scripts
+fn has_ignore_tag(docs: Option<&str>) -> bool {
+    docs.map_or(false, |docs| {
+        docs.lines().any(|line| {
+            let mut line = line.to_owned();
+            line.retain(|c| !c.is_whitespace());
+            line == IGNORE_DOC_TAG
+        })
+    })
+}
+
+/// Replaces paths to the item with the given id, and references to them, with `Self`.
+///
+/// Renamed types share their name with another type, so paths to them can only be told apart by id
+fn paths_to_self<'t>(tp: &'t Type, id: &Id) -> Cow<'t, Type> {
+    match tp {
+        Type::ResolvedPath(path) if &path.id == id => Cow::Owned(Type::Generic("Self".to_owned())),
+        Type::BorrowedRef {
+            lifetime,
+            mutable,
+            type_,
+        } => match paths_to_self(type_, id) {
+            Cow::Owned(type_) => Cow::Owned(Type::BorrowedRef {
+                lifetime: lifetime.clone(),
+                mutable: *mutable,
+                type_: Box::new(type_),
+            }),
+            Cow::Borrowed(_) => Cow::Borrowed(tp),
+        },
+        _ => Cow::Borrowed(tp),
+    }
+}
+
+/// Writes a link to the given url as the last paragraph of a docstring, expects the `///` prefix to be set
+fn write_docs_rs_link(docs: &str, url: &str, writer: &mut PrettyWriter) {
+    if !docs.is_empty() {
+        writer.write_line("");
+    }
+    writer.write_line(&format!(" [Documentation]({url})"));
+}
+
+/// The reason for the deprecation of an item, i.e. ``since `0.10`, use `from_rotation` instead``
+fn deprecation_message(deprecation: &Deprecation) -> String {
+    let note = deprecation
+        .note
+        .as_deref()
+        .map(|n| n.lines().collect::<Vec<_>>().join(" "));
+    match (&deprecation.since, note) {
+        (Some(since), Some(note)) => format!("since `{since}`, {note}"),
+        (Some(since), None) => format!("since `{since}`"),
+        (None, Some(note)) => note,
+        (None, None) => String::default(),
+    }
+}
+
+fn write_deprecation_note(docs: &str, deprecation: &Deprecation, writer: &mut PrettyWriter) {
+    if !docs.is_empty() {
+        writer.write_line("");
+    }
+    let message = deprecation_message(deprecation);
+    if message.is_empty() {
+        writer.write_line(" **Deprecated**");
+    } else {
+        writer.write_line(&format!(" **Deprecated:** {message}"));
+    }
+}
+
+/// Appends an underscore to identifiers which are keywords of the given language, i.e. `end` -> `end_` in lua
+pub(crate) fn escape_keyword(ident: &str, language: Language) -> String {
+    if language.is_keyword(ident) {
+        format!("{ident}_")
+    } else {
+        ident.to_owned()
+    }
+}
+
+/// Renames the identifier in each of the given languages it is a keyword of, i.e. `#[rename(lua = "end_")]`,
+/// leaving its name in the other languages alone
+fn write_keyword_renames(ident: &str, languages: &[Language], writer: &mut PrettyWriter) {
+    languages
+        .iter()
+        .filter(|l| l.is_keyword(ident))
+        .for_each(|l| {
+            writer.write_line(&format!(
+                "#[rename({} = \"{}\")]",
+                l.name(),
+                escape_keyword(ident, *l)
+            ));
+        });
+}
+
+/// Converts a `CamelCase` identifier to `snake_case`
+fn to_snake_case(ident: &str) -> String {
+    let mut out = String::with_capacity(ident.len());
+    for (idx, c) in ident.char_indices() {
+        if c.is_uppercase() {
+            if idx != 0 {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// The derive flag deciding how scripts convert values of a type to strings:
+/// with `Display` if implemented, with `Debug` otherwise, or else as the type name in angle brackets
+pub(crate) fn to_string_flag(implemented_traits: &IndexSet<String>) -> &'static str {
+    if implemented_traits.contains("Display") {
+        "Display"
+    } else if implemented_traits.contains("Debug") {
+        "Debug"
+    } else {
+        "DefaultToString"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{borrow::Cow, collections::HashMap};
+
+    use clap::Parser;
+    use indexmap::{IndexMap, IndexSet};
+    use rustdoc_types::{
+        Abi, Crate, Deprecation, Enum, FnDecl, Function, GenericArg, GenericArgs, GenericBound,
+        GenericParamDef, GenericParamDefKind, Generics, Header, Id, Impl, Item, ItemEnum, Path,
+        Struct, StructKind, TraitBoundModifier, Type, Variant, Visibility,
+    };
+
+    use crate::{
+        Args, Config, Coverage, IndexManifest, Language, Newtype, OpManifest, PrettyWriter,
+        TypeManifest, WrappedItem,
+    };
+
+    use super::to_string_flag;
+
+    fn item(id: &str, name: &str, inner: ItemEnum) -> Item {
+        Item {
+            id: Id(id.to_owned()),
+            crate_id: 0,
+            name: Some(name.to_owned()),
+            span: None,
+            visibility: Visibility::Public,
+            docs: None,
+            links: HashMap::default(),
+            attrs: Vec::default(),
+            deprecation: None,
+            inner,
+        }
+    }
+
+    fn no_generics() -> Generics {
+        Generics {
+            params: Vec::default(),
+            where_predicates: Vec::default(),
+        }
+    }
+
+    /// A plain struct with the id `0`
+    fn struct_item(name: &str, fields: Vec<Id>, impls: Vec<Id>) -> Item {
+        item(
+            "0",
+            name,
+            ItemEnum::Struct(Struct {
+                kind: StructKind::Plain {
+                    fields,
+                    fields_stripped: false,
+                },
+                generics: no_generics(),
+                impls,
+            }),
+        )
+    }
+
+    /// An enum with the id `0`
+    fn enum_item(name: &str, variants: Vec<Id>) -> Item {
+        item(
+            "0",
+            name,
+            ItemEnum::Enum(Enum {
+                generics: no_generics(),
+                variants_stripped: false,
+                variants,
+                impls: Vec::default(),
+            }),
+        )
+    }
+
+    /// An impl of the given trait for the type named `for_`, with the given generic arguments on the trait
+    fn trait_impl(id: &str, trait_: &str, trait_args: Vec<Type>, for_: &str) -> Item {
+        let path = |name: &str, args: Vec<Type>| Path {
+            name: name.to_owned(),
+            id: Id(format!("{name}_path")),
+            args: Some(Box::new(GenericArgs::AngleBracketed {
+                args: args.into_iter().map(GenericArg::Type).collect(),
+                bindings: Vec::default(),
+            })),
+        };
+        item(
+            id,
+            "",
+            ItemEnum::Impl(Impl {
+                is_unsafe: false,
+                generics: no_generics(),
+                provided_trait_methods: Vec::default(),
+                trait_: Some(path(trait_, trait_args)),
+                for_: Type::ResolvedPath(path(for_, Vec::default())),
+                items: Vec::default(),
+                negative: false,
+                synthetic: false,
+                blanket_impl: None,
+            }),
+        )
+    }
+
+    /// An inherent impl for the type named `for_` containing the given functions
+    fn inherent_impl(id: &str, for_: &str, items: Vec<Id>) -> Item {
+        item(
+            id,
+            "",
+            ItemEnum::Impl(Impl {
+                is_unsafe: false,
+                generics: no_generics(),
+                provided_trait_methods: Vec::default(),
+                trait_: None,
+                for_: Type::ResolvedPath(Path {
+                    name: for_.to_owned(),
+                    id: Id(format!("{for_}_path")),
+                    args: None,
+                }),
+                items,
+                negative: false,
+                synthetic: false,
+                blanket_impl: None,
+            }),
+        )
+    }
+
+    /// A function taking the given arguments, with an optional `self` receiver among them
+    fn function(id: &str, name: &str, inputs: Vec<(&str, Type)>, output: Option<Type>) -> Item {
+        item(
+            id,
+            name,
+            ItemEnum::Function(Function {
+                decl: FnDecl {
+                    inputs: inputs
+                        .into_iter()
+                        .map(|(name, type_)| (name.to_owned(), type_))
+                        .collect(),
+                    output,
+                    c_variadic: false,
+                },
+                generics: no_generics(),
+                header: Header {
+                    const_: false,
+                    unsafe_: false,
+                    async_: false,
+                    abi: Abi::Rust,
+                },
+                has_body: true,
+            }),
+        )
+    }
+
+    /// Writes the derive flags of the struct with id `0` among the given items, with `f32` as the only primitive
+    fn generate(items: Vec<Item>) -> (String, Coverage, TypeManifest) {
+        generate_with_config(items, "", "")
+    }
+
+    /// Like `generate` with additional config entries and entries of the type's config, followed by the body of the Lua impl block
+    /// and the native lua wrapper if the type is on the native fast path
+    fn generate_with_config(
+        items: Vec<Item>,
+        extra_config: &str,
+        newtype_config: &str,
+    ) -> (String, Coverage, TypeManifest) {
+        with_wrapped_item(
+            items,
+            extra_config,
+            newtype_config,
+            &[],
+            |wrapped, config, args| {
+                let mut writer = PrettyWriter::new();
+                wrapped.write_derive_flags_body(config, &mut writer, args);
+                wrapped.write_impl_block_body(Language::Lua, &mut writer, args);
+                if wrapped.is_native(args) {
+                    wrapped.write_native_lua(config, &mut writer, args);
+                }
+                (
+                    writer.finish(),
+                    wrapped.coverage,
+                    std::mem::take(&mut wrapped.manifest),
+                )
+            },
+        )
+    }
+
+    /// Writes the whole macro invocation of the struct with id `0` among the given items, for the given `--language`s
+    fn invocation(items: Vec<Item>, languages: &[&str]) -> String {
+        invocation_with_config(items, "", "", languages)
+    }
+
+    /// Like `invocation` with additional config entries and entries of the type's config
+    fn invocation_with_config(
+        items: Vec<Item>,
+        extra_config: &str,
+        newtype_config: &str,
+        languages: &[&str],
+    ) -> String {
+        with_wrapped_item(
+            items,
+            extra_config,
+            newtype_config,
+            languages,
+            |wrapped, config, args| {
+                let mut writer = PrettyWriter::new();
+                wrapped.write_invocation(config, &args.languages, &mut writer, args);
+                writer.finish()
+            },
+        )
+    }
+
+    /// Sets up the struct with id `0` among the given items like the generator does and passes it to `f`,
+    /// generating for the given `--language`s. Entries starting with `--` are passed as flags instead, i.e. `--warn-deprecated`
+    fn with_wrapped_item<R>(
+        items: Vec<Item>,
+        extra_config: &str,
+        newtype_config: &str,
+        languages: &[&str],
+        f: impl FnOnce(&mut WrappedItem, &Config, &Args) -> R,
+    ) -> R {
+        let struct_ = items.iter().find(|i| i.id.0 == "0").unwrap().clone();
+        let source = Crate {
+            root: struct_.id.clone(),
+            crate_version: None,
+            includes_private: false,
+            index: items.into_iter().map(|i| (i.id.clone(), i)).collect(),
+            paths: HashMap::default(),
+            external_crates: HashMap::default(),
+            format_version: 0,
+        };
+
+        let mut config: Config = toml::from_str(&format!(
+            r#"
+            imports = ""
+            other = ""
+            lua_api_defaults = ""
+            primitives = ["f32"]
+            manual_lua_types = []
+            {types}
+            {extra_config}
+            "#,
+            types = if extra_config.contains("[[types]]") {
+                ""
+            } else {
+                "types = []"
+            },
+        ))
+        .unwrap();
+        // wrapped types are looked up by name, like the generator does
+        config.types = config
+            .types_
+            .drain(..)
+            .map(|t| (t.name().to_owned(), t))
+            .collect();
+        let wrapped_type = struct_.name.clone().unwrap();
+        // items of all impls are gathered by name, like the generator does
+        let mut impl_items: IndexMap<&str, Vec<(&Impl, &Item)>> = IndexMap::default();
+        let mut implemented_traits: IndexSet<String> = IndexSet::default();
+        for impl_ in source.index.values().filter_map(|i| match &i.inner {
+            ItemEnum::Impl(impl_) => Some(impl_),
+            _ => None,
+        }) {
+            if let Some(trait_) = &impl_.trait_ {
+                implemented_traits.insert(trait_.name.clone());
+            }
+            for id in &impl_.items {
+                let item = &source.index[id];
+                impl_items
+                    .entry(item.name.as_deref().unwrap())
+                    .or_default()
+                    .push((impl_, item));
+            }
+        }
+        let newtype: Newtype =
+            toml::from_str(&format!("type = \"{wrapped_type}\"\n{newtype_config}")).unwrap();
+        let wrapped_type = newtype.name().to_owned();
+        let args = Args::parse_from(
+            ["bevy_api_gen", "--config", "config.toml"]
+                .into_iter()
+                .chain(languages.iter().flat_map(|l| match l.starts_with("--") {
+                    true => vec![*l],
+                    false => vec!["--language", *l],
+                })),
+        );
+
+        let mut wrapped = WrappedItem {
+            wrapped_type: &wrapped_type,
+            path_components: Cow::Owned(vec![wrapped_type.clone()]),
+            source: &source,
+            config: &newtype,
+            item: &struct_,
+            impl_items,
+            implemented_traits,
+            self_impl: None,
+            crates: &[],
+            has_global_methods: false,
+            coverage: Default::default(),
+            exclusions: Default::default(),
+            primitive_conversions: Vec::default(),
+            serde_methods: Vec::default(),
+            enum_variants: Vec::default(),
+            assoc_consts: Vec::default(),
+            excluded_methods: Vec::default(),
+            manifest: Default::default(),
+            native: Default::default(),
+            method_outcomes: Default::default(),
+        };
+
+        f(&mut wrapped, &config, &args)
+    }
+
+    #[test]
+    fn array_field() {
+        let field = item(
+            "1",
+            "color",
+            ItemEnum::StructField(Type::Array {
+                type_: Box::new(Type::Primitive("f32".to_owned())),
+                len: "4".to_owned(),
+            }),
+        );
+        let struct_ = struct_item("Tint", vec![field.id.clone()], Vec::default());
+
+        let (output, coverage, manifest) = generate(vec![struct_, field]);
+
+        assert!(output.contains("color: Raw([f32; 4]),"), "{output}");
+        assert_eq!(coverage.fields.wrapped, 1);
+        assert_eq!(manifest.fields[0].type_, "[f32; 4]");
+    }
+
+    #[test]
+    fn comparison_flags() {
+        let eq = trait_impl("1", "PartialEq", Vec::default(), "Vec3");
+        // only comparisons with other `Vec3`s can be exposed
+        let ord = trait_impl(
+            "2",
+            "PartialOrd",
+            vec![Type::Primitive("f32".to_owned())],
+            "Vec3",
+        );
+        let struct_ = struct_item("Vec3", Vec::default(), vec![eq.id.clone(), ord.id.clone()]);
+
+        let (output, _, manifest) = generate(vec![struct_, eq, ord]);
+
+        assert!(output.contains("PartialEq +"), "{output}");
+        assert!(!output.contains("PartialOrd +"), "{output}");
+        assert_eq!(
+            manifest.comparison_ops,
+            [OpManifest {
+                op: "PartialEq".to_owned(),
+                args: vec!["self".to_owned(), "self".to_owned()],
+                output: "bool".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn display_wins_over_debug_for_strings() {
+        let traits = ["Debug", "Display"].map(String::from).into_iter().collect();
+        assert_eq!(to_string_flag(&traits), "Display");
+    }
+
+    #[test]
+    fn debug_is_kept_alongside_display() {
+        let debug = trait_impl("1", "Debug", Vec::default(), "Name");
+        let display = trait_impl("2", "Display", Vec::default(), "Name");
+        let struct_ = struct_item(
+            "Name",
+            Vec::default(),
+            vec![debug.id.clone(), display.id.clone()],
+        );
+
+        let (output, _, _) = generate(vec![struct_, debug, display]);
+
+        assert!(output.contains("Debug +"), "{output}");
+        assert!(output.contains("Display +"), "{output}");
+    }
+
+    #[test]
+    fn display_only_types_convert_to_strings() {
+        let traits = ["Display"].map(String::from).into_iter().collect();
+        assert_eq!(to_string_flag(&traits), "Display");
+    }
+
+    #[test]
+    fn debug_is_the_fallback_for_strings() {
+        let traits = ["Clone", "Debug"].map(String::from).into_iter().collect();
+        assert_eq!(to_string_flag(&traits), "Debug");
+    }
+
+    #[test]
+    fn types_without_formatting_convert_to_their_name() {
+        let struct_ = struct_item("Handle", Vec::default(), Vec::default());
+
+        let (output, _, _) = generate(vec![struct_]);
+
+        assert!(output.contains("DefaultToString +"), "{output}");
+        assert!(!output.contains("Debug +"), "{output}");
+    }
+
+    #[test]
+    fn constructors() {
+        let new = function(
+            "2",
+            "new",
+            vec![("x", Type::Primitive("f32".to_owned()))],
+            Some(Type::Generic("Self".to_owned())),
+        );
+        let length = function(
+            "3",
+            "length",
+            vec![(
+                "self",
+                Type::BorrowedRef {
+                    lifetime: None,
+                    mutable: false,
+                    type_: Box::new(Type::Generic("Self".to_owned())),
+                },
+            )],
+            Some(Type::Primitive("f32".to_owned())),
+        );
+        let impl_ = inherent_impl("1", "Vec1", vec![new.id.clone(), length.id.clone()]);
+        let struct_ = struct_item("Vec1", Vec::default(), vec![impl_.id.clone()]);
+
+        let (output, _, manifest) = generate(vec![struct_, impl_, new, length]);
+
+        assert!(
+            output.contains(
+                "/// Constructor, called from scripts through the global `Vec1` as `Vec1.new(...)`"
+            ),
+            "{output}"
+        );
+        assert!(manifest
+            .methods
+            .iter()
+            .any(|m| m.name == "new" && m.constructor));
+        assert!(manifest
+            .methods
+            .iter()
+            .any(|m| m.name == "length" && !m.constructor));
+        // types with a `new` of their own are not given another one
+        assert_eq!(
+            manifest.methods.iter().filter(|m| m.name == "new").count(),
+            1
+        );
+        assert!(!output.contains("same as"), "{output}");
+    }
+
+    #[test]
+    fn first_constructor_is_aliased_as_new() {
+        let from_x = function(
+            "2",
+            "from_x",
+            vec![("x", Type::Primitive("f32".to_owned()))],
+            Some(Type::Generic("Self".to_owned())),
+        );
+        let impl_ = inherent_impl("1", "Vec1", vec![from_x.id.clone()]);
+        let struct_ = struct_item("Vec1", Vec::default(), vec![impl_.id.clone()]);
+
+        let (output, _, manifest) = generate(vec![struct_, impl_, from_x]);
+
+        assert!(
+            output.contains("/// Constructs a `Vec1`, same as `from_x`"),
+            "{output}"
+        );
+        assert!(output.contains("#[rename(\"new\")]"), "{output}");
+        // the alias is registered with the same signature
+        assert_eq!(
+            output.matches("from_x(Raw(f32)) -> self,").count(),
+            2,
+            "{output}"
+        );
+        assert!(manifest
+            .methods
+            .iter()
+            .any(|m| m.name == "from_x" && m.constructor));
+        assert!(manifest
+            .methods
+            .iter()
+            .any(|m| m.name == "new" && m.constructor));
+    }
+
+    #[test]
+    fn constructor_alias_keeps_its_name_in_rhai() {
+        let from_x = function(
+            "2",
+            "from_x",
+            vec![("x", Type::Primitive("f32".to_owned()))],
+            Some(Type::Generic("Self".to_owned())),
+        );
+        let impl_ = inherent_impl("1", "Vec1", vec![from_x.id.clone()]);
+        let struct_ = struct_item("Vec1", Vec::default(), vec![impl_.id.clone()]);
+
+        let output = with_wrapped_item(
+            vec![struct_, impl_, from_x],
+            "",
+            "",
+            &["lua", "rhai"],
+            |wrapped, config, args| {
+                let mut writer = PrettyWriter::new();
+                wrapped.write_derive_flags_body(config, &mut writer, args);
+                writer.finish()
+            },
+        );
+
+        // `new` is reserved in rhai, so the alias can't be called under that name
+        assert!(output.contains("#[rename(rhai = \"from_x\")]"), "{output}");
+        assert!(!output.contains("new_"), "{output}");
+    }
+
+    #[test]
+    fn ignored_methods() {
+        let method = |id, name: &str, docs: &str| {
+            let mut method = function(
+                id,
+                name,
+                vec![("x", Type::Primitive("f32".to_owned()))],
+                Some(Type::Primitive("f32".to_owned())),
+            );
+            // as emitted by rustdoc for `#[must_use]` methods with doc comments
+            method.attrs = vec!["#[must_use]".to_owned()];
+            method.docs = Some(docs.to_owned());
+            method
+        };
+        let scale = method(
+            "2",
+            "scale",
+            "Scales the curve.\n\nNot <!-- script:ignore --> here.",
+        );
+        let hidden = method("3", "hidden", "Internal helper.\n\n<!-- script:ignore -->");
+        let internal = method("4", "internal", "<!--script:ignore-->\nInternal helper.");
+        let impl_ = inherent_impl(
+            "1",
+            "Curve",
+            vec![scale.id.clone(), hidden.id.clone(), internal.id.clone()],
+        );
+        let struct_ = struct_item("Curve", Vec::default(), vec![impl_.id.clone()]);
+
+        let (output, coverage, manifest) = generate(vec![struct_, impl_, scale, hidden, internal]);
+
+        assert!(output.contains("scale("), "{output}");
+        assert!(!output.contains("hidden("), "{output}");
+        assert!(!output.contains("internal("), "{output}");
+        assert_eq!(coverage.methods.wrapped, 1);
+        assert_eq!(manifest.methods.len(), 1);
+    }
+
+    #[test]
+    fn deprecated_methods() {
+        let mut scale = function(
+            "2",
+            "scale",
+            vec![("x", Type::Primitive("f32".to_owned()))],
+            Some(Type::Primitive("f32".to_owned())),
+        );
+        scale.deprecation = Some(Deprecation {
+            since: Some("0.10".to_owned()),
+            note: Some("use `scale_by` instead".to_owned()),
+        });
+        let impl_ = inherent_impl("1", "Curve", vec![scale.id.clone()]);
+        let mut struct_ = struct_item("Curve", Vec::default(), vec![impl_.id.clone()]);
+        struct_.docs = Some("A curve.".to_owned());
+        struct_.deprecation = Some(Deprecation {
+            since: None,
+            note: None,
+        });
+        let items = vec![struct_, impl_, scale];
+
+        let (output, coverage, _) = generate(items.clone());
+
+        assert!(
+            output.contains("/// **Deprecated:** since `0.10`, use `scale_by` instead"),
+            "{output}"
+        );
+        // scripts are only warned when asked for
+        assert!(!output.contains("#[deprecated("), "{output}");
+        assert_eq!(coverage.methods.wrapped, 1);
+
+        // the note is separated from the docs and left out if there's nothing to say
+        let output = invocation(items.clone(), &["lua", "rhai"]);
+        let lines = output.lines().map(str::trim).collect::<Vec<_>>();
+        assert!(
+            lines
+                .windows(3)
+                .any(|l| l == ["///A curve.", "///", "/// **Deprecated**"]),
+            "{output}"
+        );
+        assert!(!output.contains("#[deprecated("), "{output}");
+
+        // both languages warn scripts calling the method
+        let output = invocation(items, &["lua", "rhai", "--warn-deprecated"]);
+        assert!(
+            output.contains("#[deprecated(\"since `0.10`, use `scale_by` instead\")]"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn unknown_types_are_reflected_if_asked_for() {
+        let unknown = || {
+            Type::ResolvedPath(Path {
+                name: "Knot".to_owned(),
+                id: Id("Knot_path".to_owned()),
+                args: None,
+            })
+        };
+        let insert = function(
+            "2",
+            "insert",
+            vec![
+                (
+                    "self",
+                    Type::BorrowedRef {
+                        lifetime: None,
+                        mutable: false,
+                        type_: Box::new(Type::Generic("Self".to_owned())),
+                    },
+                ),
+                (
+                    "knot",
+                    Type::BorrowedRef {
+                        lifetime: None,
+                        mutable: false,
+                        type_: Box::new(unknown()),
+                    },
+                ),
+            ],
+            Some(unknown()),
+        );
+        let impl_ = inherent_impl("1", "Curve", vec![insert.id.clone()]);
+        let struct_ = struct_item("Curve", Vec::default(), vec![impl_.id.clone()]);
+        let items = vec![struct_, impl_, insert];
+        let wrap = |flags: &[&str]| {
+            with_wrapped_item(items.clone(), "", "", flags, |wrapped, config, args| {
+                let mut writer = PrettyWriter::new();
+                wrapped.write_derive_flags_body(config, &mut writer, args);
+                (writer.finish(), wrapped.coverage.methods.wrapped)
+            })
+        };
+
+        // methods with unknown types are excluded by default
+        let (output, wrapped) = wrap(&[]);
+        assert!(!output.contains("insert("), "{output}");
+        assert_eq!(wrapped, 0);
+
+        let (output, wrapped) = wrap(&["--reflect-unknown-types"]);
+        assert!(
+            output.contains("insert(&self:Reflected(&Knot)) -> Reflected(Knot)"),
+            "{output}"
+        );
+        assert_eq!(wrapped, 1);
+    }
+
+    #[test]
+    fn enum_variants() {
+        let pressed = item("1", "Pressed", ItemEnum::Variant(Variant::Plain(None)));
+        let duration = item(
+            "4",
+            "0",
+            ItemEnum::StructField(Type::Primitive("f32".to_owned())),
+        );
+        let held_for = item(
+            "2",
+            "HeldFor",
+            ItemEnum::Variant(Variant::Tuple(vec![Some(duration.id.clone())])),
+        );
+        let moved = item(
+            "3",
+            "Moved",
+            ItemEnum::Variant(Variant::Struct {
+                fields: Vec::default(),
+                fields_stripped: false,
+            }),
+        );
+        let enum_ = enum_item(
+            "ButtonState",
+            vec![pressed.id.clone(), held_for.id.clone(), moved.id.clone()],
+        );
+
+        let (output, _, _) = generate(vec![enum_, pressed, held_for, moved, duration]);
+
+        assert!(
+            output.contains(
+                "static fn \"Pressed\" => |_,(): ()| {Ok(LuaButtonState::new(ButtonState::Pressed))}"
+            ),
+            "{output}"
+        );
+        assert!(
+            output.contains("static fn \"HeldFor\" => |_,(a_0,): (f32,)| {Ok(LuaButtonState::new(ButtonState::HeldFor(a_0)))}"),
+            "{output}"
+        );
+        // struct variants can't be constructed, but can still be matched
+        assert!(!output.contains("\"Moved\" =>"), "{output}");
+        assert!(output.contains("\"is_moved\" =>"), "{output}");
+        assert!(
+            output.contains(
+                "\"is_held_for\" => |_,s,()| {Ok(s.val(|s| matches!(s, ButtonState::HeldFor{..}))?)}"
+            ),
+            "{output}"
+        );
+        assert!(output.contains("\"variant_name\" =>"), "{output}");
+    }
+
+    #[test]
+    fn colliding_trait_methods_are_qualified() {
+        let self_ref = || Type::BorrowedRef {
+            lifetime: None,
+            mutable: false,
+            type_: Box::new(Type::Generic("Self".to_owned())),
+        };
+        let length = |id: &str| {
+            function(
+                id,
+                "length",
+                vec![("self", self_ref())],
+                Some(Type::Primitive("f32".to_owned())),
+            )
+        };
+        let with_items = |mut impl_: Item, items: Vec<Id>| {
+            if let ItemEnum::Impl(i) = &mut impl_.inner {
+                i.items = items;
+            }
+            impl_
+        };
+        let (inherent, measure, other) = (length("2"), length("4"), length("6"));
+        let inherent_impl_ = inherent_impl("1", "Curve", vec![inherent.id.clone()]);
+        let measure_impl = with_items(
+            trait_impl("3", "Measure", Vec::default(), "Curve"),
+            vec![measure.id.clone()],
+        );
+        // methods of traits which aren't configured are neither wrapped nor collide
+        let other_impl = with_items(
+            trait_impl("5", "Other", Vec::default(), "Curve"),
+            vec![other.id.clone()],
+        );
+        let struct_ = struct_item("Curve", Vec::default(), vec![inherent_impl_.id.clone()]);
+        let items = vec![
+            struct_,
+            inherent_impl_,
+            inherent,
+            measure_impl,
+            measure,
+            other_impl,
+            other,
+        ];
+        let traits = r#"traits = [{name="Measure", import_path="crate::Measure"}]"#;
+
+        let (output, _, manifest) = generate_with_config(items.clone(), "", traits);
+
+        assert!(output.contains("#[qualified(Measure)]"), "{output}");
+        assert!(output.contains("#[rename(\"measure_length\")]"), "{output}");
+        assert!(!output.contains("Other"), "{output}");
+        // the index of the crate isn't ordered
+        let mut names = manifest.methods.iter().map(|m| &m.name).collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["length", "measure_length"]);
+
+        // the trait method can be renamed through its qualified name
+        let (_, _, manifest) = generate_with_config(
+            items,
+            "",
+            &format!(
+                "{traits}\n[method_overrides]\n\"Measure::length\" = {{ rename = \"measure\" }}"
+            ),
+        );
+        let mut names = manifest.methods.iter().map(|m| &m.name).collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["length", "measure"]);
+    }
+
+    #[test]
+    fn method_overrides() {
+        let method = |id, name: &str| {
+            function(
+                id,
+                name,
+                vec![("x", Type::Primitive("f32".to_owned()))],
+                Some(Type::Primitive("f32".to_owned())),
+            )
+        };
+        let (scale, hidden) = (method("2", "scale"), method("3", "hidden"));
+        let impl_ = inherent_impl("1", "Curve", vec![scale.id.clone(), hidden.id.clone()]);
+        let struct_ = struct_item("Curve", Vec::default(), vec![impl_.id.clone()]);
+
+        let (output, coverage, manifest) = generate_with_config(
+            vec![struct_, impl_, scale, hidden],
+            "",
+            "[method_overrides]\nscale = { rename = \"scaled\" }\nhidden = { skip = true }",
+        );
+
+        // renamed methods still call the original one
+        assert!(output.contains("#[rename(\"scaled\")]"), "{output}");
+        assert!(output.contains("scale(Raw(f32)) -> Raw(f32),"), "{output}");
+        assert!(!output.contains("hidden("), "{output}");
+        assert_eq!(coverage.methods.wrapped, 1);
+        assert_eq!(manifest.methods[0].name, "scaled");
+    }
+
+    #[test]
+    fn keywords_are_escaped() {
+        let field = item(
+            "1",
+            "end",
+            ItemEnum::StructField(Type::Primitive("f32".to_owned())),
+        );
+        let function_ = function(
+            "3",
+            "this",
+            vec![("x", Type::Primitive("f32".to_owned()))],
+            Some(Type::Primitive("f32".to_owned())),
+        );
+        let impl_ = inherent_impl("2", "Curve", vec![function_.id.clone()]);
+        let struct_ = struct_item("Curve", vec![field.id.clone()], vec![impl_.id.clone()]);
+
+        let (output, manifest) = with_wrapped_item(
+            vec![struct_, field, impl_, function_],
+            "",
+            "",
+            &["lua", "rhai"],
+            |wrapped, config, args| {
+                let mut writer = PrettyWriter::new();
+                wrapped.write_derive_flags_body(config, &mut writer, args);
+                (writer.finish(), std::mem::take(&mut wrapped.manifest))
+            },
+        );
+
+        // names are only escaped in the languages they are keywords of
+        assert!(output.contains("#[rename(rhai = \"this_\")]"), "{output}");
+        assert!(!output.contains("lua = \"this_\""), "{output}");
+        assert!(output.contains("#[rename(lua = \"end_\")]"), "{output}");
+        assert!(!output.contains("rhai = \"end_\""), "{output}");
+        assert_eq!(manifest.methods[0].name, "this");
+        assert_eq!(manifest.fields[0].name, "end");
+    }
+
+    /// The `Point` struct of `bevy_script_api/tests/generated/rhai_point.rs`
+    fn point() -> Vec<Item> {
+        let x = item(
+            "1",
+            "x",
+            ItemEnum::StructField(Type::Primitive("f32".to_owned())),
+        );
+        let y = item(
+            "2",
+            "y",
+            ItemEnum::StructField(Type::Primitive("f32".to_owned())),
+        );
+        let clone = trait_impl("3", "Clone", Vec::default(), "Point");
+        let debug = trait_impl("4", "Debug", Vec::default(), "Point");
+        let length = function(
+            "6",
+            "length",
+            vec![(
+                "self",
+                Type::BorrowedRef {
+                    lifetime: None,
+                    mutable: false,
+                    type_: Box::new(Type::Generic("Self".to_owned())),
+                },
+            )],
+            Some(Type::Primitive("f32".to_owned())),
+        );
+        let impl_ = inherent_impl("5", "Point", vec![length.id.clone()]);
+        let struct_ = struct_item(
+            "Point",
+            vec![x.id.clone(), y.id.clone()],
+            vec![clone.id.clone(), debug.id.clone(), impl_.id.clone()],
+        );
+        vec![struct_, x, y, clone, debug, impl_, length]
+    }
+
+    /// The code without comment lines and whitespace, fixtures are compared by it
+    fn tokens(code: &str) -> String {
+        code.lines()
+            .filter(|line| !line.trim_start().starts_with("//"))
+            .flat_map(str::split_whitespace)
+            .collect()
+    }
+
+    #[test]
+    fn rhai_invocation_matches_the_compiled_fixture() {
+        // the fixture is compiled and used from scripts by the `rhai_generated` test of `bevy_script_api`
+        let fixture = include_str!("../../bevy_script_api/tests/generated/rhai_point.rs");
+
+        let output = invocation(point(), &["rhai"]);
+
+        assert_eq!(tokens(&output), tokens(fixture), "{output}");
+    }
+
+    /// The `Point` struct of `bevy_script_api/tests/generated/lua_native_point.rs`,
+    /// with methods, documented fields, an associated constant, comparisons and operators
+    fn native_point() -> Vec<Item> {
+        let f32_ = || Type::Primitive("f32".to_owned());
+        let point = || {
+            Type::ResolvedPath(Path {
+                name: "Point".to_owned(),
+                id: Id("0".to_owned()),
+                args: None,
+            })
+        };
+        let self_ref = |mutable| Type::BorrowedRef {
+            lifetime: None,
+            mutable,
+            type_: Box::new(Type::Generic("Self".to_owned())),
+        };
+        let with_items = |mut impl_: Item, items: Vec<Id>| {
+            if let ItemEnum::Impl(i) = &mut impl_.inner {
+                i.items = items;
+            }
+            impl_
+        };
+        let output = |id: &str| {
+            item(
+                id,
+                "Output",
+                ItemEnum::AssocType {
+                    generics: no_generics(),
+                    bounds: Vec::default(),
+                    default: Some(point()),
+                },
+            )
+        };
+
+        let mut x = item("1", "x", ItemEnum::StructField(f32_()));
+        x.docs = Some("The horizontal coordinate.".to_owned());
+        let y = item("2", "y", ItemEnum::StructField(f32_()));
+        let clone = trait_impl("3", "Clone", Vec::default(), "Point");
+        let debug = trait_impl("4", "Debug", Vec::default(), "Point");
+        let partial_eq = trait_impl("5", "PartialEq", Vec::default(), "Point");
+        let new = function(
+            "7",
+            "new",
+            vec![("x", f32_()), ("y", f32_())],
+            Some(Type::Generic("Self".to_owned())),
+        );
+        let mut length = function("8", "length", vec![("self", self_ref(false))], Some(f32_()));
+        length.docs = Some("The distance from the origin.".to_owned());
+        let scale = function(
+            "9",
+            "scale",
+            vec![("self", self_ref(true)), ("factor", f32_())],
+            None,
+        );
+        let mut zero = item(
+            "19",
+            "ZERO",
+            ItemEnum::AssocConst {
+                type_: Type::Generic("Self".to_owned()),
+                default: Some("_".to_owned()),
+            },
+        );
+        zero.docs = Some("The origin.".to_owned());
+        let impl_ = inherent_impl(
+            "6",
+            "Point",
+            vec![
+                new.id.clone(),
+                length.id.clone(),
+                scale.id.clone(),
+                zero.id.clone(),
+            ],
+        );
+        let add = function(
+            "11",
+            "add",
+            vec![("self", Type::Generic("Self".to_owned())), ("rhs", point())],
+            Some(point()),
+        );
+        let add_output = output("12");
+        let add_impl = with_items(
+            trait_impl("10", "Add", Vec::default(), "Point"),
+            vec![add.id.clone(), add_output.id.clone()],
+        );
+        let mul = function(
+            "14",
+            "mul",
+            vec![("self", Type::Generic("Self".to_owned())), ("rhs", f32_())],
+            Some(point()),
+        );
+        let mul_output = output("15");
+        let mul_impl = with_items(
+            trait_impl("13", "Mul", vec![f32_()], "Point"),
+            vec![mul.id.clone(), mul_output.id.clone()],
+        );
+        let neg = function(
+            "17",
+            "neg",
+            vec![("self", Type::Generic("Self".to_owned()))],
+            Some(point()),
+        );
+        let neg_output = output("18");
+        let neg_impl = with_items(
+            trait_impl("16", "Neg", Vec::default(), "Point"),
+            vec![neg.id.clone(), neg_output.id.clone()],
+        );
+        let mut struct_ = struct_item(
+            "Point",
+            vec![x.id.clone(), y.id.clone()],
+            vec![
+                clone.id.clone(),
+                debug.id.clone(),
+                partial_eq.id.clone(),
+                impl_.id.clone(),
+                add_impl.id.clone(),
+                mul_impl.id.clone(),
+                neg_impl.id.clone(),
+            ],
+        );
+        struct_.docs = Some("A point in the plane.".to_owned());
+        vec![
+            struct_, x, y, clone, debug, partial_eq, impl_, new, length, scale, zero, add_impl,
+            add, add_output, mul_impl, mul, mul_output, neg_impl, neg, neg_output,
+        ]
+    }
+
+    #[test]
+    fn native_lua_output_matches_the_compiled_fixture() {
+        // the fixture is compiled and used from scripts by the `lua_native` test of `bevy_script_api`
+        let fixture = include_str!("../../bevy_script_api/tests/generated/lua_native_point.rs");
+
+        // operators need their operands to be wrapped types
+        let output = invocation_with_config(
+            native_point(),
+            "[[types]]\ntype = \"Point\"",
+            "native = true\nderive_flags = [\"Sequence\"]",
+            &["lua"],
+        );
+
+        assert_eq!(tokens(&output), tokens(fixture), "{output}");
+    }
+
+    #[test]
+    fn impl_blocks_are_only_written_for_languages_with_functions() {
+        let output = invocation(point(), &["lua", "rhai"]);
+
+        assert!(
+            output.contains("#[languages(on_feature(lua),on_feature(rhai))]"),
+            "{output}"
+        );
+        assert!(output.contains("lua impl"), "{output}");
+        assert!(!output.contains("rhai impl"), "{output}");
+    }
+
+    #[test]
+    fn rhai_wrappers_need_clone() {
+        let mut items = point();
+        items[3] = trait_impl("3", "Default", Vec::default(), "Point");
+
+        let output = invocation(items, &["lua", "rhai"]);
+
+        assert!(output.contains("#[languages(on_feature(lua))]"), "{output}");
+    }
+
+    #[test]
+    fn exclusion_stubs() {
+        let spawn = function(
+            "2",
+            "spawn",
+            vec![("prefab", Type::Generic("Prefab".to_owned()))],
+            None,
+        );
+        let impl_ = inherent_impl("1", "World", vec![spawn.id.clone()]);
+        let struct_ = struct_item("World", Vec::default(), vec![impl_.id.clone()]);
+
+        let (output, _, _) = generate(vec![struct_.clone(), impl_.clone(), spawn.clone()]);
+        assert!(!output.contains("\"spawn\" =>"), "{output}");
+
+        let (output, coverage, manifest) = generate_with_config(
+            vec![struct_.clone(), impl_.clone(), spawn.clone()],
+            "emit_exclusion_stubs = true",
+            "",
+        );
+        assert!(output.contains("static fn \"spawn\" =>"), "{output}");
+        assert!(
+            output.contains("method `spawn` unavailable: Unsupported argument Prefab"),
+            "{output}"
+        );
+        // stubs don't count as wrapped methods
+        assert_eq!(coverage.methods.wrapped, 0);
+        assert!(manifest.methods.is_empty());
+
+        // rhai stubs take as many arguments as the method, `spawn` is reserved in rhai
+        let clone = trait_impl("3", "Clone", Vec::default(), "World");
+        let output = invocation_with_config(
+            vec![struct_, impl_, spawn, clone],
+            "emit_exclusion_stubs = true",
+            "",
+            &["rhai"],
+        );
+        assert!(
+            output.contains(
+                "static fn \"spawn_\" => |_a_0: bevy_mod_scripting_rhai::rhai::Dynamic| -> Result<"
+            ),
+            "{output}"
+        );
+        assert!(
+            output.contains("method `spawn` unavailable: Unsupported argument Prefab"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn string_arguments() {
+        let str_ref = |mutable| Type::BorrowedRef {
+            lifetime: None,
+            mutable,
+            type_: Box::new(Type::Primitive("str".to_owned())),
+        };
+        let self_ref = |mutable| Type::BorrowedRef {
+            lifetime: None,
+            mutable,
+            type_: Box::new(Type::Generic("Self".to_owned())),
+        };
+        let cow = Type::ResolvedPath(Path {
+            name: "Cow".to_owned(),
+            id: Id("Cow_path".to_owned()),
+            args: Some(Box::new(GenericArgs::AngleBracketed {
+                args: vec![
+                    GenericArg::Lifetime("'static".to_owned()),
+                    GenericArg::Type(Type::Primitive("str".to_owned())),
+                ],
+                bindings: Vec::default(),
+            })),
+        });
+
+        let new = function(
+            "2",
+            "new",
+            vec![("name", str_ref(false))],
+            Some(Type::Generic("Self".to_owned())),
+        );
+        let set = function(
+            "3",
+            "set",
+            vec![("self", self_ref(true)), ("name", cow)],
+            None,
+        );
+        let as_str = function(
+            "4",
+            "as_str",
+            vec![("self", self_ref(false))],
+            Some(str_ref(false)),
+        );
+        let impl_ = inherent_impl(
+            "1",
+            "Name",
+            vec![new.id.clone(), set.id.clone(), as_str.id.clone()],
+        );
+        let struct_ = struct_item("Name", Vec::default(), vec![impl_.id.clone()]);
+
+        let (output, coverage, manifest) = generate(vec![struct_, impl_, new, set, as_str]);
+
+        assert!(output.contains("new(Raw(&str)) -> self,"), "{output}");
+        assert!(output.contains("set(&mut self:Raw(Cow<str>)),"), "{output}");
+        assert!(output.contains("as_str(&self:) -> Raw(&str),"), "{output}");
+        assert_eq!(coverage.methods.wrapped, 3);
+        assert_eq!(coverage.methods.total, 3);
+        assert_eq!(manifest.methods[0].args, vec!["&str".to_owned()]);
+
+        let push = function(
+            "2",
+            "push",
+            vec![("self", self_ref(true)), ("suffix", str_ref(true))],
+            None,
+        );
+        let impl_ = inherent_impl("1", "Name", vec![push.id.clone()]);
+        let struct_ = struct_item("Name", Vec::default(), vec![impl_.id.clone()]);
+
+        let (_, coverage, _) = generate(vec![struct_, impl_, push]);
+        assert_eq!(coverage.methods.wrapped, 0);
+    }
+
+    #[test]
+    fn included_methods() {
+        let methods: Vec<Item> = ["length", "normalize", "lerp"]
+            .into_iter()
+            .zip(2..)
+            .map(|(name, id)| {
+                function(
+                    &id.to_string(),
+                    name,
+                    vec![(
+                        "self",
+                        Type::BorrowedRef {
+                            lifetime: None,
+                            mutable: false,
+                            type_: Box::new(Type::Generic("Self".to_owned())),
+                        },
+                    )],
+                    Some(Type::Primitive("f32".to_owned())),
+                )
+            })
+            .collect();
+        let impl_ = inherent_impl("1", "Vec1", methods.iter().map(|m| m.id.clone()).collect());
+        let struct_ = struct_item("Vec1", Vec::default(), vec![impl_.id.clone()]);
+        let mut items = vec![struct_, impl_];
+        items.extend(methods);
+
+        let (output, coverage, manifest) = generate_with_config(
+            items,
+            "emit_exclusion_stubs = true",
+            r#"include_methods = ["length", "lerp"]"#,
+        );
+
+        assert!(output.contains("length(&self:) -> Raw(f32),"), "{output}");
+        assert!(output.contains("lerp(&self:) -> Raw(f32),"), "{output}");
+        // methods left out on purpose get no stubs either
+        assert!(!output.contains("normalize"), "{output}");
+        assert_eq!(coverage.methods.wrapped, 2);
+        assert_eq!(coverage.methods.total, 3);
+        assert_eq!(manifest.methods.len(), 2);
+    }
+
+    #[test]
+    fn generic_method_instantiations() {
+        let mut get = function(
+            "2",
+            "get",
+            vec![(
+                "self",
+                Type::BorrowedRef {
+                    lifetime: None,
+                    mutable: false,
+                    type_: Box::new(Type::Generic("Self".to_owned())),
+                },
+            )],
+            Some(Type::ResolvedPath(Path {
+                name: "Option".to_owned(),
+                id: Id("Option_path".to_owned()),
+                args: Some(Box::new(GenericArgs::AngleBracketed {
+                    args: vec![GenericArg::Type(Type::Generic("T".to_owned()))],
+                    bindings: Vec::default(),
+                })),
+            })),
+        );
+        if let ItemEnum::Function(f) = &mut get.inner {
+            f.generics.params.push(GenericParamDef {
+                name: "T".to_owned(),
+                kind: GenericParamDefKind::Type {
+                    bounds: Vec::default(),
+                    default: None,
+                    synthetic: false,
+                },
+            });
+        }
+        let impl_ = inherent_impl("1", "Store", vec![get.id.clone()]);
+        let struct_ = struct_item("Store", Vec::default(), vec![impl_.id.clone()]);
+
+        let (_, coverage, _) = generate(vec![struct_.clone(), impl_.clone(), get.clone()]);
+        assert_eq!(coverage.methods.wrapped, 0);
+
+        let (output, coverage, manifest) = generate_with_config(
+            vec![struct_, impl_, get],
+            "",
+            r#"generic_methods = [
+                { method = "get", type_args = ["f32"], script_name = "get_f32" },
+                { method = "get", type_args = ["f32", "f32"], script_name = "get_pair" },
+            ]"#,
+        );
+        assert!(output.contains("#[rename(\"get_f32\")]"), "{output}");
+        assert!(output.contains("#[type_args(f32)]"), "{output}");
+        assert!(
+            output.contains("get(&self:) -> Raw(Option<f32>),"),
+            "{output}"
+        );
+        // the second instantiation has too many type arguments
+        assert!(!output.contains("get_pair"), "{output}");
+        assert_eq!(coverage.methods.wrapped, 1);
+        assert_eq!(manifest.methods[0].name, "get_f32");
+    }
+
+    #[test]
+    fn index_flag() {
+        let usize_ = || Type::Primitive("usize".to_owned());
+        let f32_ = || Type::Primitive("f32".to_owned());
+        let self_ref = |mutable| Type::BorrowedRef {
+            lifetime: None,
+            mutable,
+            type_: Box::new(Type::Generic("Self".to_owned())),
+        };
+        // `&Self::Output` in the source, only the associated type is looked at
+        let output_ref = |mutable| Type::BorrowedRef {
+            lifetime: None,
+            mutable,
+            type_: Box::new(f32_()),
+        };
+        let with_items = |mut impl_: Item, items: Vec<Id>| {
+            if let ItemEnum::Impl(i) = &mut impl_.inner {
+                i.items = items;
+            }
+            impl_
+        };
+
+        let index = function(
+            "2",
+            "index",
+            vec![("self", self_ref(false)), ("index", usize_())],
+            Some(output_ref(false)),
+        );
+        let output = item(
+            "3",
+            "Output",
+            ItemEnum::AssocType {
+                generics: no_generics(),
+                bounds: Vec::default(),
+                default: Some(f32_()),
+            },
+        );
+        let index_impl = with_items(
+            trait_impl("1", "Index", vec![usize_()], "Vec3"),
+            vec![index.id.clone(), output.id.clone()],
+        );
+        let index_mut = function(
+            "5",
+            "index_mut",
+            vec![("self", self_ref(true)), ("index", usize_())],
+            Some(output_ref(true)),
+        );
+        let index_mut_impl = with_items(
+            trait_impl("4", "IndexMut", vec![usize_()], "Vec3"),
+            vec![index_mut.id.clone()],
+        );
+        let struct_ = struct_item("Vec3", Vec::default(), vec![index_impl.id.clone()]);
+
+        let sequence = "derive_flags = [\"Sequence\"]";
+
+        let (output_, coverage, manifest) = generate_with_config(
+            vec![
+                struct_.clone(),
+                index_impl.clone(),
+                index.clone(),
+                output.clone(),
+                index_mut_impl,
+                index_mut,
+            ],
+            "",
+            sequence,
+        );
+        assert!(output_.contains("+ Index"), "{output_}");
+        assert!(output_.contains("mut Raw(usize) -> Raw(f32),"), "{output_}");
+        assert_eq!(coverage.ops.wrapped, 1);
+        assert_eq!(
+            manifest.index,
+            Some(IndexManifest {
+                key: "usize".to_owned(),
+                output: "f32".to_owned(),
+                mutable: true,
+            })
+        );
+
+        // without `IndexMut` the value can only be read
+        let (output_, _, manifest) = generate_with_config(
+            vec![
+                struct_.clone(),
+                index_impl.clone(),
+                index.clone(),
+                output.clone(),
+            ],
+            "",
+            sequence,
+        );
+        assert!(output_.contains("Raw(usize) -> Raw(f32),"), "{output_}");
+        assert!(!output_.contains("mut Raw(usize)"), "{output_}");
+        assert!(!manifest.index.unwrap().mutable);
+
+        // the keys of other types cannot be checked before indexing
+        let (output_, coverage, manifest) = generate(vec![
+            struct_.clone(),
+            index_impl.clone(),
+            index.clone(),
+            output.clone(),
+        ]);
+        assert!(!output_.contains("+ Index"), "{output_}");
+        assert_eq!(coverage.ops.wrapped, 0);
+        assert_eq!(manifest.index, None);
+
+        // indexing written in the config is kept as is
+        let (output_, coverage, _) = generate_with_config(
+            vec![struct_, index_impl, index, output],
+            "",
+            r#"lua_methods = ["(MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])}"]"#,
+        );
+        assert!(!output_.contains("+ Index"), "{output_}");
+        assert_eq!(coverage.ops.wrapped, 0);
+    }
+
+    #[test]
+    fn renamed_type() {
+        // the type is configured as `LegacyColor`, a `Color` from another crate is wrapped as well
+        let color = Type::ResolvedPath(Path {
+            name: "Color".to_owned(),
+            id: Id("0".to_owned()),
+            args: None,
+        });
+        let mix = function(
+            "2",
+            "mix",
+            vec![("self", Type::Generic("Self".to_owned()))],
+            Some(color),
+        );
+        let impl_ = inherent_impl("1", "Color", vec![mix.id.clone()]);
+        let struct_ = struct_item("Color", Vec::default(), vec![impl_.id.clone()]);
+
+        let (output, coverage, _) = generate_with_config(
+            vec![struct_.clone(), impl_.clone(), mix.clone()],
+            "",
+            r#"name = "LegacyColor""#,
+        );
+        assert!(output.contains("mix(self:) -> self,"), "{output}");
+        assert_eq!(coverage.methods.wrapped, 1);
+
+        // without a name paths are resolved by name, and `Color` is not a known type here
+        let (_, coverage, _) = generate(vec![struct_, impl_, mix]);
+        assert_eq!(coverage.methods.wrapped, 0);
+    }
+
+    #[test]
+    fn renamed_types_in_method_signatures() {
+        // `Color` is wrapped as `LegacyColor`, so it's only found by the id of its path
+        let color = || {
+            Type::ResolvedPath(Path {
+                name: "Color".to_owned(),
+                id: Id("3".to_owned()),
+                args: None,
+            })
+        };
+        let option = |type_: Type| {
+            Type::ResolvedPath(Path {
+                name: "Option".to_owned(),
+                id: Id("Option_path".to_owned()),
+                args: Some(Box::new(GenericArgs::AngleBracketed {
+                    args: vec![GenericArg::Type(type_)],
+                    bindings: Vec::default(),
+                })),
+            })
+        };
+        let self_ref = Type::BorrowedRef {
+            lifetime: None,
+            mutable: false,
+            type_: Box::new(Type::Generic("Self".to_owned())),
+        };
+        let color_ref = Type::BorrowedRef {
+            lifetime: None,
+            mutable: false,
+            type_: Box::new(color()),
+        };
+        let blend = function(
+            "2",
+            "blend",
+            vec![("self", self_ref), ("other", color_ref)],
+            Some(option(color())),
+        );
+        let impl_ = inherent_impl("1", "Palette", vec![blend.id.clone()]);
+        let struct_ = struct_item("Palette", Vec::default(), vec![impl_.id.clone()]);
+        let color = item(
+            "3",
+            "Color",
+            ItemEnum::Struct(Struct {
+                kind: StructKind::Unit,
+                generics: no_generics(),
+                impls: Vec::default(),
+            }),
+        );
+
+        let (output, coverage, _) = generate_with_config(
+            vec![struct_, impl_, blend, color],
+            "[[types]]\ntype = \"Color\"\nname = \"LegacyColor\"",
+            "",
+        );
+        assert!(
+            output.contains("blend(&self:Wrapped(&LegacyColor)) -> Wrapped(Option<LegacyColor>),"),
+            "{output}"
+        );
+        assert_eq!(coverage.methods.wrapped, 1);
+    }
+
+    #[test]
+    fn deref_fields() {
+        let f32_ = || Type::Primitive("f32".to_owned());
+        let value = item("1", "value", ItemEnum::StructField(f32_()));
+        let deref = function(
+            "3",
+            "deref",
+            vec![(
+                "self",
+                Type::BorrowedRef {
+                    lifetime: None,
+                    mutable: false,
+                    type_: Box::new(Type::Generic("Self".to_owned())),
+                },
+            )],
+            None,
+        );
+        let target = item(
+            "4",
+            "Target",
+            ItemEnum::AssocType {
+                generics: no_generics(),
+                bounds: Vec::default(),
+                default: Some(Type::ResolvedPath(Path {
+                    name: "Inner".to_owned(),
+                    id: Id("5".to_owned()),
+                    args: None,
+                })),
+            },
+        );
+        let mut deref_impl = trait_impl("2", "Deref", Vec::default(), "Outer");
+        if let ItemEnum::Impl(i) = &mut deref_impl.inner {
+            i.items = vec![deref.id.clone(), target.id.clone()];
+        }
+        let inner = item(
+            "5",
+            "Inner",
+            ItemEnum::Struct(Struct {
+                kind: StructKind::Plain {
+                    fields: vec![Id("6".to_owned()), Id("7".to_owned())],
+                    fields_stripped: false,
+                },
+                generics: no_generics(),
+                impls: Vec::default(),
+            }),
+        );
+        let inner_value = item("6", "value", ItemEnum::StructField(f32_()));
+        let inner_scale = item("7", "scale", ItemEnum::StructField(f32_()));
+        let struct_ = struct_item("Outer", vec![value.id.clone()], vec![deref_impl.id.clone()]);
+
+        let (output, _, _) = generate_with_config(
+            vec![
+                struct_,
+                value,
+                deref_impl,
+                deref,
+                target,
+                inner,
+                inner_value,
+                inner_scale,
+            ],
+            "",
+            "expose_deref_fields = true",
+        );
+        assert!(output.contains("#[rename(\"deref_scale\")]"), "{output}");
+        assert!(output.contains("scale: Raw(f32),"), "{output}");
+        // `s.value` is the field of `Outer` itself
+        assert!(!output.contains("deref_value"), "{output}");
+    }
+
+    #[test]
+    fn assoc_consts() {
+        let const_ = |id: &str, name: &str, type_: Type| {
+            item(
+                id,
+                name,
+                ItemEnum::AssocConst {
+                    type_,
+                    default: Some("_".to_owned()),
+                },
+            )
+        };
+        let mut zero = const_("2", "ZERO", Type::Generic("Self".to_owned()));
+        zero.docs = Some("All zeroes.".to_owned());
+        let epsilon = const_("3", "EPSILON", Type::Primitive("f32".to_owned()));
+        let names = const_(
+            "4",
+            "NAMES",
+            Type::Slice(Box::new(Type::Primitive("str".to_owned()))),
+        );
+        let impl_ = inherent_impl(
+            "1",
+            "Vec3",
+            vec![zero.id.clone(), epsilon.id.clone(), names.id.clone()],
+        );
+        let clone = trait_impl("5", "Clone", Vec::default(), "Vec3");
+        let struct_ = struct_item(
+            "Vec3",
+            Vec::default(),
+            vec![impl_.id.clone(), clone.id.clone()],
+        );
+        let items = vec![struct_, impl_, zero, epsilon, names, clone];
+
+        let (output, _, manifest) = generate(items.clone());
+
+        assert!(output.contains("/// All zeroes."), "{output}");
+        assert!(
+            output.contains("get fn \"ZERO\" => |_,_| {Ok(LuaVec3::new(Vec3::ZERO))};"),
+            "{output}"
+        );
+        assert!(
+            output.contains("get fn \"EPSILON\" => |_,_| {Ok(Vec3::EPSILON)};"),
+            "{output}"
+        );
+        // constants which aren't primitives or wrapped types are left out
+        assert!(!output.contains("NAMES"), "{output}");
+        assert_eq!(
+            manifest
+                .fields
+                .iter()
+                .map(|f| (f.name.as_str(), f.type_.as_str(), f.readonly))
+                .collect::<Vec<_>>(),
+            [("ZERO", "Vec3", true), ("EPSILON", "f32", true)]
+        );
+        assert!(manifest.methods.is_empty());
+
+        // the native fast path registers them as fields too
+        let (output, _, _) = generate_with_config(items, "", "native = true");
+        assert!(
+            output.contains(
+                "fields.add_field_function_get(\"ZERO\", |_,_| {Ok(LuaVec3::new(Vec3::ZERO))});"
+            ),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn serde_methods() {
+        let serialize = trait_impl("1", "Serialize", Vec::default(), "Transform");
+        let deserialize = trait_impl("2", "Deserialize", Vec::default(), "Transform");
+        let struct_ = struct_item(
+            "Transform",
+            Vec::default(),
+            vec![serialize.id.clone(), deserialize.id.clone()],
+        );
+        let items = vec![struct_, serialize, deserialize];
+
+        let (output, _, manifest) =
+            generate_with_config(items.clone(), "emit_serde_methods = true", "");
+        assert!(
+            output.contains("\"to_json\" => |_,s,()| {s.val(|s| bevy_mod_scripting_lua::serde_json::to_string(s))?"),
+            "{output}"
+        );
+        assert!(
+            output.contains("static fn \"from_json\" => |_,json: String| {bevy_mod_scripting_lua::serde_json::from_str::<Transform>(&json).map(LuaTransform::new)"),
+            "{output}"
+        );
+        assert_eq!(manifest.methods.len(), 2);
+
+        // the methods are Lua only, rhai wrappers require `Clone`
+        let clone = trait_impl("3", "Clone", Vec::default(), "Transform");
+        let mut rhai_items = items.clone();
+        rhai_items[0] = struct_item(
+            "Transform",
+            Vec::default(),
+            vec![Id("1".to_owned()), Id("2".to_owned()), clone.id.clone()],
+        );
+        rhai_items.push(clone);
+        let output = invocation_with_config(rhai_items, "emit_serde_methods = true", "", &["rhai"]);
+        assert!(output.contains("impl_script_newtype!"), "{output}");
+        assert!(!output.contains("json"), "{output}");
+
+        // serde_json is only required if the methods are asked for
+        let (output, _, _) = generate(items);
+        assert!(!output.contains("serde_json"), "{output}");
+    }
+
+    #[test]
+    fn callbacks() {
+        let f32_ = || Type::Primitive("f32".to_owned());
+        let self_ref = Type::BorrowedRef {
+            lifetime: None,
+            mutable: true,
+            type_: Box::new(Type::Generic("Self".to_owned())),
+        };
+        let closure = |inputs: Vec<Type>, output: Option<Type>| GenericBound::TraitBound {
+            trait_: Path {
+                name: "FnMut".to_owned(),
+                id: Id("FnMut_path".to_owned()),
+                args: Some(Box::new(GenericArgs::Parenthesized { inputs, output })),
+            },
+            generic_params: Vec::default(),
+            modifier: TraitBoundModifier::None,
+        };
+        let f32_ref = Type::BorrowedRef {
+            lifetime: None,
+            mutable: false,
+            type_: Box::new(f32_()),
+        };
+        let map = function(
+            "2",
+            "map",
+            vec![
+                ("self", self_ref.clone()),
+                (
+                    "f",
+                    Type::ImplTrait(vec![closure(vec![f32_ref, f32_()], Some(f32_()))]),
+                ),
+            ],
+            None,
+        );
+        // `'static` closures may be called after the method returns
+        let on_change = function(
+            "3",
+            "on_change",
+            vec![
+                ("self", self_ref),
+                (
+                    "f",
+                    Type::ImplTrait(vec![
+                        closure(vec![f32_()], None),
+                        GenericBound::Outlives("'static".to_owned()),
+                    ]),
+                ),
+            ],
+            None,
+        );
+        let impl_ = inherent_impl("1", "Curve", vec![map.id.clone(), on_change.id.clone()]);
+        let struct_ = struct_item("Curve", Vec::default(), vec![impl_.id.clone()]);
+
+        let (output, coverage, manifest) = generate(vec![struct_, impl_, map, on_change]);
+
+        assert!(
+            output.contains("map(&mut self:Callback(Raw(&f32),Raw(f32) -> Raw(f32)))"),
+            "{output}"
+        );
+        assert!(!output.contains("on_change("), "{output}");
+        assert_eq!(coverage.methods.wrapped, 1);
+        assert_eq!(manifest.methods[0].args, ["impl FnMut(&f32, f32) -> f32"]);
+    }
+
+    #[test]
+    fn type_name_method() {
+        let struct_ = struct_item("Curve", Vec::default(), Vec::default());
+        let (output, _, manifest) = generate(vec![struct_.clone()]);
+        assert!(
+            output.contains("\"type_name\" => |_,_s,()| {Ok(\"Curve\")};"),
+            "{output}"
+        );
+        // it's available on every wrapper and not part of the wrapped API
+        assert!(manifest.methods.is_empty());
+
+        // manually written methods take precedence
+        let (output, _, _) = generate_with_config(
+            vec![struct_.clone()],
+            "",
+            r#"lua_methods = ["/// Custom\nmut fn \"type_name\" => |_,_s,()| {Ok(\"custom\")}"]"#,
+        );
+        assert!(!output.contains("Ok(\"Curve\")"), "{output}");
+        // other methods merely mentioning it don't
+        let (output, _, _) = generate_with_config(
+            vec![struct_],
+            "",
+            r#"lua_methods = ["\"describe\" => |_,_s,()| {Ok(\"type_name\")}"]"#,
+        );
+        assert!(output.contains("Ok(\"Curve\")"), "{output}");
+
+        // rhai wrappers get it too
+        let output = invocation(point(), &["rhai"]);
+        assert!(
+            output.contains("fn \"type_name\" => |_s: &mut RhaiPoint| -> Result<bevy_mod_scripting_rhai::rhai::Dynamic, Box<bevy_mod_scripting_rhai::rhai::EvalAltResult>> {Ok(\"Point\".into())};"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn lua_method_names() {
+        assert_eq!(lua_method_name("\"a\" => |_,s,()| {Ok(\"b\")}"), Some("a"));
+        assert_eq!(
+            lua_method_name("/// Docs \"b\"\nstatic mut fn \"a\" => |_,()| {Ok(())}"),
+            Some("a")
+        );
+        assert_eq!(
+            lua_method_name("get \"field\" => |_,s| {Ok(1)}"),
+            Some("field")
+        );
+        assert_eq!(
+            lua_method_name("(MetaMethod::Index) => |_,s,idx: usize| {Ok(\"a\")}"),
+            None
+        );
+    }
+
+    #[test]
+    fn native_wrappers() {
+        let x = item(
+            "5",
+            "x",
+            ItemEnum::StructField(Type::Primitive("f32".to_owned())),
+        );
+        let self_ref = || Type::BorrowedRef {
+            lifetime: None,
+            mutable: false,
+            type_: Box::new(Type::Generic("Self".to_owned())),
+        };
+        let new = function(
+            "2",
+            "new",
+            vec![("x", Type::Primitive("f32".to_owned()))],
+            Some(Type::Generic("Self".to_owned())),
+        );
+        let length = function(
+            "3",
+            "length",
+            vec![("self", self_ref())],
+            Some(Type::Primitive("f32".to_owned())),
+        );
+        // collections are only supported through the macro
+        let values = function(
+            "4",
+            "values",
+            vec![("self", self_ref())],
+            Some(Type::ResolvedPath(Path {
+                name: "Vec".to_owned(),
+                id: Id("Vec_path".to_owned()),
+                args: Some(Box::new(GenericArgs::AngleBracketed {
+                    args: vec![GenericArg::Type(Type::Primitive("f32".to_owned()))],
+                    bindings: Vec::default(),
+                })),
+            })),
+        );
+        let impl_ = inherent_impl(
+            "1",
+            "Vec1",
+            vec![new.id.clone(), length.id.clone(), values.id.clone()],
+        );
+        let clone = trait_impl("6", "Clone", Vec::default(), "Vec1");
+        let struct_ = struct_item(
+            "Vec1",
+            vec![x.id.clone()],
+            vec![impl_.id.clone(), clone.id.clone()],
+        );
+        let items = vec![struct_, impl_, new, length, values, clone, x];
+
+        let (output, coverage, _) = generate_with_config(items.clone(), "", "native = true");
+
+        assert!(
+            output.contains("impl bevy_mod_scripting_lua::tealr::mlu::TealData for LuaVec1 {"),
+            "{output}"
+        );
+        assert!(
+            output.contains(
+                "methods.add_function(\"new\", |_, (a_0,): (f32,)| {Ok(LuaVec1::new(Vec1::new(a_0)))});"
+            ),
+            "{output}"
+        );
+        assert!(
+            output.contains(
+                "methods.add_method(\"length\", |_, s, (): ()| {s.val(|s| Ok(s.length()))?});"
+            ),
+            "{output}"
+        );
+        assert!(!output.contains("add_method(\"values\""), "{output}");
+        assert!(
+            output.contains(
+                "fields.add_field_method_get(\"x\", |_, s| Ok(s.val(|s| s.x.clone())?));"
+            ),
+            "{output}"
+        );
+        // entries of the lua impl block are registered as well
+        assert!(
+            output.contains("methods.add_method(\"type_name\", |_,_s,()| {Ok(\"Vec1\")});"),
+            "{output}"
+        );
+        assert_eq!(coverage.methods.wrapped, 2);
+        assert_eq!(coverage.methods.total, 3);
+
+        // sequences are converted from tables as well
+        let (output, _, _) = generate_with_config(
+            items.clone(),
+            "",
+            "native = true\nderive_flags = [\"Sequence\"]",
+        );
+        assert!(
+            output.contains(
+                "self_.set_val(bevy_script_api::lua::table::sequence_from_table::<Vec1>(table)?)?;"
+            ),
+            "{output}"
+        );
+        assert!(
+            output.contains("methods.add_method(\"to_array\""),
+            "{output}"
+        );
+
+        // hand written lua methods are only understood by the macro
+        let (output, _, _) = generate_with_config(
+            items.clone(),
+            "",
+            "native = true\nlua_methods = ['\"one\" => |_,_s,()| Ok(1)']",
+        );
+        assert!(!output.contains("TealData"), "{output}");
+        assert!(output.contains("\"one\" => |_,_s,()| Ok(1);"), "{output}");
+
+        // the macro is used otherwise
+        let (output, coverage, _) = generate_with_config(items, "", "");
+        assert!(!output.contains("TealData"), "{output}");
+        assert_eq!(coverage.methods.wrapped, 3);
+    }
+
+    #[test]
+    fn newtypes_of_wrapped_types() {
+        let tuple_struct = |id: &str, name: &str, field: &str| {
+            item(
+                id,
+                name,
+                ItemEnum::Struct(Struct {
+                    kind: StructKind::Tuple(vec![Some(Id(field.to_owned()))]),
+                    generics: no_generics(),
+                    impls: Vec::default(),
+                }),
+            )
+        };
+        let path = |name: &str, id: &str| {
+            Type::ResolvedPath(Path {
+                name: name.to_owned(),
+                id: Id(id.to_owned()),
+                args: None,
+            })
+        };
+        let health = tuple_struct("0", "Health", "1");
+        let field = item("1", "0", ItemEnum::StructField(path("hp::Hp", "2")));
+        let hp = tuple_struct("2", "Hp", "3");
+        let inner = item(
+            "3",
+            "0",
+            ItemEnum::StructField(Type::Primitive("f32".to_owned())),
+        );
+        let items = vec![health, field, hp, inner];
+
+        let (output, coverage, manifest) = generate_with_config(
+            items.clone(),
+            "[[types]]\ntype = \"Health\"\n[[types]]\ntype = \"Hp\"",
+            "",
+        );
+        assert!(output.contains("0: Wrapped(Hp),"), "{output}");
+        assert_eq!(coverage.fields.wrapped, 1);
+        assert_eq!(manifest.fields[0].name, "_0");
+        assert_eq!(manifest.fields[0].type_, "Hp");
+
+        // renamed types are wrapped under their name
+        let (output, _, manifest) = generate_with_config(
+            items.clone(),
+            "[[types]]\ntype = \"Health\"\n[[types]]\ntype = \"Hp\"\nname = \"HitPoints\"",
+            "",
+        );
+        assert!(output.contains("0: Wrapped(HitPoints),"), "{output}");
+        assert_eq!(manifest.fields[0].type_, "HitPoints");
+
+        // unknown inner types still go through reflection
+        let (output, _, _) = generate_with_config(items, "[[types]]\ntype = \"Health\"", "");
+        assert!(output.contains("0: Raw(ReflectedValue),"), "{output}");
     }
 }
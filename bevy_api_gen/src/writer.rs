@@ -1,4 +1,6 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::HashSet};
+
+use crate::{wrapper::escape_keyword, ApiManifest, ArgType, Language, TypeManifest};
 
 #[derive(Default)]
 struct WriterState {
@@ -178,6 +180,11 @@ impl PrettyWriter {
         self
     }
 
+    /// Returns true if nothing was written yet
+    pub fn is_empty(&self) -> bool {
+        self.output.is_empty()
+    }
+
     /// Consumes self and produces the output string, panics if there is unclosed parenthesis/brackets etc.
     pub fn finish(self) -> String {
         if self.state.open_braces > 0 {
@@ -192,9 +199,252 @@ impl PrettyWriter {
     }
 }
 
+/// The metamethods implementing the operators in the manifest, by operator trait name
+const LUA_METAMETHODS: [(&str, &str); 6] = [
+    ("Add", "__add"),
+    ("Sub", "__sub"),
+    ("Mul", "__mul"),
+    ("Div", "__div"),
+    ("Rem", "__mod"),
+    ("Neg", "__unm"),
+];
+
+/// Writes teal type definitions (a `.d.tl` file) for the lua wrappers described by the manifest.
+///
+/// Each wrapper becomes a global record with its fields, methods and operator metamethods,
+/// types with static functions also get a global instance of the record, i.e. `global Vec3: LuaVec3`.
+/// Types teal can't describe, such as `ReflectedValue`s, are declared as `any`.
+pub fn write_teal_declarations(manifest: &ApiManifest, writer: &mut PrettyWriter) {
+    let wrapped: HashSet<&str> = manifest.types.iter().map(|t| t.name.as_str()).collect();
+
+    for type_ in &manifest.types {
+        let record = lua_wrapper_name(&type_.name);
+        let teal_type = |t: &str| {
+            t.parse::<ArgType>()
+                .map(|t| to_teal_type(&t, type_, &wrapped))
+                .unwrap_or_else(|_| "any".to_owned())
+        };
+
+        writer.write_line(&format!("global record {record}"));
+        writer.indent();
+        writer.write_line("userdata");
+
+        for field in &type_.fields {
+            writer.write_line(&format!(
+                "{}: {}",
+                lua_name(&field.name),
+                teal_type(&field.type_)
+            ));
+        }
+
+        for method in &type_.methods {
+            let args = method
+                .receiver
+                .iter()
+                .map(|_| format!("self: {record}"))
+                .chain(method.args.iter().map(|a| teal_type(a)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let output = method
+                .output
+                .as_ref()
+                .map(|o| format!(": {}", teal_type(o)))
+                .unwrap_or_default();
+            writer.write_line(&format!(
+                "{}: function({args}){output}",
+                lua_name(&method.name)
+            ));
+        }
+
+        for op in type_.bin_ops.iter().chain(&type_.unary_ops) {
+            let metamethod = LUA_METAMETHODS
+                .iter()
+                .find_map(|(name, metamethod)| (*name == op.op).then_some(*metamethod));
+            if let Some(metamethod) = metamethod {
+                let args = op
+                    .args
+                    .iter()
+                    .map(|a| teal_type(a))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                writer.write_line(&format!(
+                    "metamethod {metamethod}: function({args}): {}",
+                    teal_type(&op.output)
+                ));
+            }
+        }
+
+        writer.dedent();
+        writer.write_line("end");
+
+        if type_.methods.iter().any(|m| m.receiver.is_none()) {
+            writer.write_line(&format!("global {}: {record}", type_.name));
+        }
+        writer.newline();
+    }
+}
+
+fn lua_wrapper_name(type_name: &str) -> String {
+    format!("{}{type_name}", Language::Lua.wrapper_prefix())
+}
+
+/// The name a method or field of the manifest is exposed as in lua, lua keywords get an underscore appended
+fn lua_name(name: &str) -> String {
+    escape_keyword(name, Language::Lua)
+}
+
+/// Maps a rust type to the teal type of the lua values it converts to and from
+fn to_teal_type(type_: &ArgType, self_type: &TypeManifest, wrapped: &HashSet<&str>) -> String {
+    match type_ {
+        ArgType::Self_ => lua_wrapper_name(&self_type.name),
+        ArgType::Ref { ref_, .. } => to_teal_type(ref_, self_type, wrapped),
+        ArgType::Slice(inner) | ArgType::Array { type_: inner, .. } => {
+            format!("{{{}}}", to_teal_type(inner, self_type, wrapped))
+        }
+        ArgType::Generic { base, args } => match (base.base_ident(), args.as_slice()) {
+            (Some("Option" | "Result"), [inner, ..]) => to_teal_type(inner, self_type, wrapped),
+            (Some("Vec"), [inner]) => format!("{{{}}}", to_teal_type(inner, self_type, wrapped)),
+            (Some("HashMap"), [key, value]) => format!(
+                "{{{}:{}}}",
+                to_teal_type(key, self_type, wrapped),
+                to_teal_type(value, self_type, wrapped)
+            ),
+            _ => "any".to_owned(),
+        },
+        ArgType::Base(b) => match lua_primitive_type(b) {
+            Some(primitive) => primitive.to_owned(),
+            None if wrapped.contains(b.as_str()) => lua_wrapper_name(b),
+            None => "any".to_owned(),
+        },
+    }
+}
+
+/// The name of the lua type primitives convert to and from, the same in teal and LuaLS annotations
+fn lua_primitive_type(base_ident: &str) -> Option<&'static str> {
+    match base_ident {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => Some("integer"),
+        "f32" | "f64" => Some("number"),
+        "bool" => Some("boolean"),
+        "String" | "str" | "char" => Some("string"),
+        _ => None,
+    }
+}
+
+/// Writes a LuaLS (`lua-language-server`) stub file for the lua wrappers described by the manifest.
+///
+/// Each wrapper becomes a `---@class` with its fields and operators, followed by stubs of its methods annotated with their
+/// parameter and return types, which LuaLS uses for autocompletion. Values without a wrapper are annotated as the `ReflectedValue` class.
+pub fn write_luals_annotations(manifest: &ApiManifest, writer: &mut PrettyWriter) {
+    let wrapped: HashSet<&str> = manifest.types.iter().map(|t| t.name.as_str()).collect();
+
+    writer.write_line("---@meta");
+    writer.newline();
+    writer.write_line("---@class ReflectedValue");
+    writer.newline();
+
+    for type_ in &manifest.types {
+        let class = lua_wrapper_name(&type_.name);
+        let luals_type = |t: &str| {
+            t.parse::<ArgType>()
+                .map(|t| to_luals_type(&t, type_, &wrapped))
+                .unwrap_or_else(|_| "any".to_owned())
+        };
+
+        writer.write_line(&format!("---@class {class}"));
+        for field in &type_.fields {
+            let description = if field.readonly { " read only" } else { "" };
+            writer.write_line(&format!(
+                "---@field {} {}{description}",
+                lua_name(&field.name),
+                luals_type(&field.type_)
+            ));
+        }
+
+        // LuaLS operators always take the class as their first operand
+        for op in type_.bin_ops.iter().chain(&type_.unary_ops) {
+            let metamethod = LUA_METAMETHODS
+                .iter()
+                .find_map(|(name, metamethod)| (*name == op.op).then_some(*metamethod));
+            let operands = op.args.iter().map(|a| luals_type(a)).collect::<Vec<_>>();
+            let operator = match (metamethod, operands.as_slice()) {
+                (Some(metamethod), [lhs]) if *lhs == class => {
+                    Some(metamethod.trim_start_matches("__").to_owned())
+                }
+                (Some(metamethod), [lhs, rhs]) if *lhs == class => {
+                    Some(format!("{}({rhs})", metamethod.trim_start_matches("__")))
+                }
+                _ => None,
+            };
+            if let Some(operator) = operator {
+                writer.write_line(&format!(
+                    "---@operator {operator}: {}",
+                    luals_type(&op.output)
+                ));
+            }
+        }
+        writer.write_line(&format!("local {class} = {{}}"));
+        writer.newline();
+
+        for method in &type_.methods {
+            let params = (1..=method.args.len())
+                .map(|i| format!("arg{i}"))
+                .collect::<Vec<_>>();
+            for (param, arg) in params.iter().zip(&method.args) {
+                writer.write_line(&format!("---@param {param} {}", luals_type(arg)));
+            }
+            if let Some(output) = &method.output {
+                writer.write_line(&format!("---@return {}", luals_type(output)));
+            }
+            let separator = if method.receiver.is_some() { ":" } else { "." };
+            writer.write_line(&format!(
+                "function {class}{separator}{}({}) end",
+                lua_name(&method.name),
+                params.join(", ")
+            ));
+            writer.newline();
+        }
+
+        if type_.methods.iter().any(|m| m.receiver.is_none()) {
+            writer.write_line(&format!("{} = {class}", type_.name));
+            writer.newline();
+        }
+    }
+}
+
+/// Maps a rust type to the LuaLS type of the lua values it converts to and from
+fn to_luals_type(type_: &ArgType, self_type: &TypeManifest, wrapped: &HashSet<&str>) -> String {
+    match type_ {
+        ArgType::Self_ => lua_wrapper_name(&self_type.name),
+        ArgType::Ref { ref_, .. } => to_luals_type(ref_, self_type, wrapped),
+        ArgType::Slice(inner) | ArgType::Array { type_: inner, .. } => {
+            format!("{}[]", to_luals_type(inner, self_type, wrapped))
+        }
+        ArgType::Generic { base, args } => match (base.base_ident(), args.as_slice()) {
+            (Some("Option"), [inner]) => format!("{}?", to_luals_type(inner, self_type, wrapped)),
+            (Some("Result"), [inner, ..]) => to_luals_type(inner, self_type, wrapped),
+            (Some("Vec"), [inner]) => format!("{}[]", to_luals_type(inner, self_type, wrapped)),
+            (Some("HashMap"), [key, value]) => format!(
+                "table<{}, {}>",
+                to_luals_type(key, self_type, wrapped),
+                to_luals_type(value, self_type, wrapped)
+            ),
+            _ => "any".to_owned(),
+        },
+        ArgType::Base(b) => match lua_primitive_type(b) {
+            Some(primitive) => primitive.to_owned(),
+            None if wrapped.contains(b.as_str()) => lua_wrapper_name(b),
+            None if b == "ReflectedValue" => b.to_owned(),
+            None => "any".to_owned(),
+        },
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::PrettyWriter;
+    use crate::{
+        ApiManifest, FieldManifest, MethodManifest, OpManifest, PrettyWriter, TypeManifest,
+    };
 
     #[test]
     fn test_indentation() {
@@ -229,4 +479,118 @@ mod test {
 
         assert_eq!(writer.finish(), "a\n(\n\t(\n\t\ta\n\t)\n)\na\n");
     }
+
+    fn test_manifest() -> ApiManifest {
+        ApiManifest {
+            types: vec![TypeManifest {
+                name: "Vec2".to_owned(),
+                path: "glam::Vec2".to_owned(),
+                methods: vec![
+                    MethodManifest {
+                        name: "new".to_owned(),
+                        receiver: None,
+                        args: vec!["f32".to_owned(), "f32".to_owned()],
+                        output: Some("self".to_owned()),
+                        constructor: true,
+                    },
+                    MethodManifest {
+                        name: "to_array".to_owned(),
+                        receiver: Some("&self".to_owned()),
+                        args: vec![],
+                        output: Some("[f32; 2]".to_owned()),
+                        constructor: false,
+                    },
+                    MethodManifest {
+                        name: "reflect".to_owned(),
+                        receiver: Some("&mut self".to_owned()),
+                        args: vec!["ReflectedValue".to_owned(), "Option<Vec2>".to_owned()],
+                        output: None,
+                        constructor: false,
+                    },
+                ],
+                fields: vec![FieldManifest {
+                    name: "x".to_owned(),
+                    type_: "f32".to_owned(),
+                    readonly: false,
+                }],
+                bin_ops: vec![OpManifest {
+                    op: "Mul".to_owned(),
+                    args: vec!["self".to_owned(), "f32".to_owned()],
+                    output: "Vec2".to_owned(),
+                }],
+                unary_ops: vec![],
+                comparison_ops: vec![],
+                index: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_teal_declarations() {
+        let manifest = test_manifest();
+
+        let mut writer = PrettyWriter::new();
+        super::write_teal_declarations(&manifest, &mut writer);
+
+        assert_eq!(
+            writer.finish(),
+            "global record LuaVec2\n\
+            \tuserdata\n\
+            \tx: number\n\
+            \tnew: function(number, number): LuaVec2\n\
+            \tto_array: function(self: LuaVec2): {number}\n\
+            \treflect: function(self: LuaVec2, any, LuaVec2)\n\
+            \tmetamethod __mul: function(LuaVec2, number): LuaVec2\n\
+            end\n\
+            global Vec2: LuaVec2\n\n"
+        );
+    }
+
+    #[test]
+    fn test_luals_annotations() {
+        let mut writer = PrettyWriter::new();
+        super::write_luals_annotations(&test_manifest(), &mut writer);
+
+        assert_eq!(
+            writer.finish(),
+            "---@meta\n\n\
+            ---@class ReflectedValue\n\n\
+            ---@class LuaVec2\n\
+            ---@field x number\n\
+            ---@operator mul(number): LuaVec2\n\
+            local LuaVec2 = {}\n\n\
+            ---@param arg1 number\n\
+            ---@param arg2 number\n\
+            ---@return LuaVec2\n\
+            function LuaVec2.new(arg1, arg2) end\n\n\
+            ---@return number[]\n\
+            function LuaVec2:to_array() end\n\n\
+            ---@param arg1 ReflectedValue\n\
+            ---@param arg2 LuaVec2?\n\
+            function LuaVec2:reflect(arg1, arg2) end\n\n\
+            Vec2 = LuaVec2\n\n"
+        );
+    }
+
+    #[test]
+    fn lua_keywords_are_escaped() {
+        let mut manifest = test_manifest();
+        manifest.types[0].methods[1].name = "end".to_owned();
+        manifest.types[0].fields[0].name = "function".to_owned();
+
+        let mut writer = PrettyWriter::new();
+        super::write_teal_declarations(&manifest, &mut writer);
+        let teal = writer.finish();
+        assert!(teal.contains("\tfunction_: number\n"), "{teal}");
+        assert!(
+            teal.contains("\tend_: function(self: LuaVec2): {number}\n"),
+            "{teal}"
+        );
+
+        let mut writer = PrettyWriter::new();
+        super::write_luals_annotations(&manifest, &mut writer);
+        let luals = writer.finish();
+        assert!(luals.contains("---@field function_ number\n"), "{luals}");
+        assert!(luals.contains("function LuaVec2:end_() end\n"), "{luals}");
+    }
 }
@@ -3,10 +3,11 @@ use std::{borrow::Cow, result};
 use proc_macro2::TokenStream;
 use quote::ToTokens;
 use syn::{
-    parenthesized,
+    bracketed, parenthesized,
     parse::{Parse, ParseStream},
-    token::Paren,
-    Ident, Token,
+    punctuated::Punctuated,
+    token::{Bracket, Paren},
+    Ident, LitInt, Token,
 };
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
@@ -103,6 +104,41 @@ pub enum SimpleType {
         mut_: Option<Token![mut]>,
         type_: Box<SimpleType>,
     },
+    /// A generic container of a single type, either `Option<T>` (`nil` on the script side maps to `None`),
+    /// `Vec<T>`, `Result<T>` (only valid as a return type, the error type is left out and turned into a script error)
+    /// or `Cow<str>` (received and returned as a script string)
+    Container {
+        container: Ident,
+        lt: Token![<],
+        type_: Box<SimpleType>,
+        gt: Token![>],
+    },
+    /// A slice `[T]`, only valid behind a shared reference as an argument.
+    /// Scripts pass in arrays which are collected into a `Vec<T>` first
+    Slice {
+        bracket: Bracket,
+        type_: Box<SimpleType>,
+    },
+    /// A `HashMap<K, V>` with primitive keys, converted to and from script tables,
+    /// the wrapper applies to the value type.
+    ///
+    /// Scripts exchange maps as `std::collections::HashMap`, the only map type languages convert tables to and from,
+    /// which are collected into whatever map type the wrapped type uses (i.e. `bevy::utils::HashMap`) on the rust side
+    Map {
+        container: Ident,
+        lt: Token![<],
+        key: Box<SimpleType>,
+        comma: Token![,],
+        value: Box<SimpleType>,
+        gt: Token![>],
+    },
+    /// A fixed size array `[T; N]`, scripts pass in and receive arrays whose length is validated at runtime
+    Array {
+        bracket: Bracket,
+        type_: Box<SimpleType>,
+        semi: Token![;],
+        len: LitInt,
+    },
 }
 
 impl SimpleType {
@@ -110,7 +146,11 @@ impl SimpleType {
     pub fn base_ident(&self) -> &Ident {
         match self {
             SimpleType::BaseIdent(b) => b,
-            SimpleType::Ref { type_, .. } => type_.base_ident(),
+            SimpleType::Ref { type_, .. }
+            | SimpleType::Container { type_, .. }
+            | SimpleType::Slice { type_, .. }
+            | SimpleType::Array { type_, .. }
+            | SimpleType::Map { value: type_, .. } => type_.base_ident(),
         }
     }
 
@@ -118,14 +158,22 @@ impl SimpleType {
     pub fn into_base_ident(self) -> Ident {
         match self {
             SimpleType::BaseIdent(b) => b,
-            SimpleType::Ref { type_, .. } => type_.into_base_ident(),
+            SimpleType::Ref { type_, .. }
+            | SimpleType::Container { type_, .. }
+            | SimpleType::Slice { type_, .. }
+            | SimpleType::Array { type_, .. }
+            | SimpleType::Map { value: type_, .. } => type_.into_base_ident(),
         }
     }
 
     pub fn mutate_base_ident<F: FnMut(&mut Ident)>(&mut self, mut f: F) {
         match self {
             SimpleType::BaseIdent(b) => f(b),
-            SimpleType::Ref { type_, .. } => type_.mutate_base_ident(f),
+            SimpleType::Ref { type_, .. }
+            | SimpleType::Container { type_, .. }
+            | SimpleType::Slice { type_, .. }
+            | SimpleType::Array { type_, .. }
+            | SimpleType::Map { value: type_, .. } => type_.mutate_base_ident(f),
         }
     }
 
@@ -133,7 +181,11 @@ impl SimpleType {
     /// e.g. `&MyType` -> `MyType`
     pub fn strip_outer_refs(self) -> Self {
         match self {
-            SimpleType::BaseIdent(_) => self,
+            SimpleType::BaseIdent(_)
+            | SimpleType::Container { .. }
+            | SimpleType::Slice { .. }
+            | SimpleType::Array { .. }
+            | SimpleType::Map { .. } => self,
             SimpleType::Ref { type_, .. } => *type_,
         }
     }
@@ -145,6 +197,70 @@ impl SimpleType {
     pub fn is_mut_ref(&self) -> bool {
         matches!(self, Self::Ref { mut_, .. } if mut_.is_some())
     }
+
+    pub fn is_option(&self) -> bool {
+        matches!(self, Self::Container { container, .. } if container == "Option")
+    }
+
+    pub fn is_vec(&self) -> bool {
+        matches!(self, Self::Container { container, .. } if container == "Vec")
+    }
+
+    pub fn is_result(&self) -> bool {
+        matches!(self, Self::Container { container, .. } if container == "Result")
+    }
+
+    /// Returns true if this is a `String`, `str` or `Cow<str>`, or a reference to one
+    pub fn is_string(&self) -> bool {
+        match self {
+            Self::BaseIdent(b) => b == "String" || b == "str",
+            Self::Container {
+                container, type_, ..
+            } => container == "Cow" && matches!(type_.as_ref(), Self::BaseIdent(b) if b == "str"),
+            Self::Ref { type_, .. } => type_.is_string(),
+            _ => false,
+        }
+    }
+
+    /// Returns true if this is a `Cow`
+    pub fn is_cow(&self) -> bool {
+        matches!(self, Self::Container { container, .. } if container == "Cow")
+    }
+
+    /// Returns true if this is a slice or a reference to one
+    pub fn is_slice(&self) -> bool {
+        match self {
+            Self::Slice { .. } => true,
+            Self::Ref { type_, .. } => type_.is_slice(),
+            _ => false,
+        }
+    }
+
+    /// Returns true if this is a fixed size array
+    pub fn is_array(&self) -> bool {
+        matches!(self, Self::Array { .. })
+    }
+
+    pub fn is_map(&self) -> bool {
+        matches!(self, Self::Map { .. })
+    }
+
+    /// Returns the element type and length of a fixed size array
+    pub fn array_element(&self) -> Option<(&SimpleType, &LitInt)> {
+        match self {
+            Self::Array { type_, len, .. } => Some((type_, len)),
+            _ => None,
+        }
+    }
+
+    /// Returns the element type of a slice or a reference to one
+    pub fn slice_element(&self) -> Option<&SimpleType> {
+        match self {
+            Self::Slice { type_, .. } => Some(type_),
+            Self::Ref { type_, .. } => type_.slice_element(),
+            _ => None,
+        }
+    }
 }
 
 impl Parse for SimpleType {
@@ -155,8 +271,43 @@ impl Parse for SimpleType {
                 mut_: input.parse()?,
                 type_: input.parse()?,
             })
+        } else if input.peek(Bracket) {
+            let f;
+            let bracket = bracketed!(f in input);
+            let type_ = f.parse()?;
+            if f.peek(Token![;]) {
+                Ok(Self::Array {
+                    bracket,
+                    type_,
+                    semi: f.parse()?,
+                    len: f.parse()?,
+                })
+            } else {
+                Ok(Self::Slice { bracket, type_ })
+            }
         } else {
-            Ok(Self::BaseIdent(input.parse()?))
+            let ident: Ident = input.parse()?;
+            if ident == "HashMap" && input.peek(Token![<]) {
+                Ok(Self::Map {
+                    container: ident,
+                    lt: input.parse()?,
+                    key: input.parse()?,
+                    comma: input.parse()?,
+                    value: input.parse()?,
+                    gt: input.parse()?,
+                })
+            } else if (ident == "Option" || ident == "Vec" || ident == "Result" || ident == "Cow")
+                && input.peek(Token![<])
+            {
+                Ok(Self::Container {
+                    container: ident,
+                    lt: input.parse()?,
+                    type_: input.parse()?,
+                    gt: input.parse()?,
+                })
+            } else {
+                Ok(Self::BaseIdent(ident))
+            }
         }
     }
 }
@@ -170,15 +321,90 @@ impl ToTokens for SimpleType {
                 mut_,
                 type_,
             } => tokens.extend(quote::quote!(#ampersand #mut_ #type_)),
+            // `Cow` is not in the prelude, so it's emitted with its full path like maps
+            SimpleType::Container {
+                container,
+                lt,
+                type_,
+                gt,
+            } if container == "Cow" => {
+                tokens.extend(quote::quote!(std::borrow::#container #lt #type_ #gt))
+            }
+            SimpleType::Container {
+                container,
+                lt,
+                type_,
+                gt,
+            } => tokens.extend(quote::quote!(#container #lt #type_ #gt)),
+            SimpleType::Slice { bracket, type_ } => {
+                bracket.surround(tokens, |tokens| type_.to_tokens(tokens))
+            }
+            // this is the map exchanged with scripts, emitted with its full path so that generated code does not need to import it
+            SimpleType::Map {
+                container,
+                lt,
+                key,
+                comma,
+                value,
+                gt,
+            } => tokens
+                .extend(quote::quote!(std::collections::#container #lt #key #comma #value #gt)),
+            SimpleType::Array {
+                bracket,
+                type_,
+                semi,
+                len,
+            } => bracket.surround(tokens, |tokens| {
+                type_.to_tokens(tokens);
+                semi.to_tokens(tokens);
+                len.to_tokens(tokens);
+            }),
         }
     }
 }
 
+/// Primitive types which are validated as numbers when given a parameter name,
+/// shared by the lua derive and the API generator so both agree on which arguments are validated
+pub const NUMERIC_TYPES: [&str; 14] = [
+    "usize", "isize", "f32", "f64", "u128", "u64", "u32", "u16", "u8", "i128", "i64", "i32", "i16",
+    "i8",
+];
+
 #[derive(PartialEq, Eq, Hash, Debug)]
 /// Raw argument expression argument received from the macro invocation
 pub enum ArgType {
-    Raw { paren: Paren, type_: SimpleType },
-    Wrapped { paren: Paren, type_: SimpleType },
+    /// A type which converts to and from script values directly.
+    ///
+    /// If followed by `as <ident>` (i.e. `Raw(f32 as x)`), numeric arguments are validated,
+    /// and conversion errors name the given parameter
+    Raw {
+        paren: Paren,
+        type_: SimpleType,
+        name: Option<(Token![as], Ident)>,
+    },
+    Wrapped {
+        paren: Paren,
+        type_: SimpleType,
+    },
+    /// A type without a proxy, received and returned as a `ReflectedValue`.
+    ///
+    /// Arguments are converted via `FromReflect` when the method is called, so type mismatches are only caught at runtime.
+    /// Only shared references are allowed, as they refer to a temporary copy
+    Reflected {
+        paren: Paren,
+        type_: SimpleType,
+    },
+    /// A non escaping callback, i.e. `Callback(Raw(f32), Wrapped(&Vec3) -> Raw(bool))` for an `impl Fn(f32, &Vec3) -> bool`.
+    ///
+    /// Scripts pass in a function which is called with the arguments of the callback, the output must implement `Default`
+    /// which is returned to the method if the script function fails, the error is raised once the method returns.
+    /// Since the function may access the world, wrappers borrowed by the method are copied for the duration of the call
+    /// and written back afterwards if borrowed mutably, so they must be `Clone`
+    Callback {
+        paren: Paren,
+        args: Punctuated<ArgType, Token![,]>,
+        out: Option<(Token![->], Box<ArgType>)>,
+    },
     Self_(SelfType),
 }
 
@@ -189,9 +415,45 @@ impl Parse for ArgType {
             let ident: Ident = input.parse()?;
             let f;
             match ident.to_string().as_str() {
-                "Raw" => Ok(Self::Raw { paren: parenthesized!(f in input), type_: f.parse()? }),
+                "Raw" => Ok(Self::Raw {
+                    paren: parenthesized!(f in input),
+                    type_: f.parse()?,
+                    name: if f.peek(Token![as]) {
+                        Some((f.parse()?, f.parse()?))
+                    } else {
+                        None
+                    }
+                }),
                 "Wrapped" => Ok(Self::Wrapped { paren: parenthesized!(f in input), type_: f.parse()? }),
-                _ => panic!("Invalid argument, valid arguments are: [Raw(type),Wrapped(type),self,&self,&mut self]"),
+                "Reflected" => {
+                    let paren = parenthesized!(f in input);
+                    let type_: SimpleType = f.parse()?;
+                    if type_.is_mut_ref() {
+                        return Err(syn::Error::new(paren.span, "Reflected arguments cannot be mutable references"));
+                    }
+                    Ok(Self::Reflected { paren, type_ })
+                },
+                "Callback" => {
+                    let paren = parenthesized!(f in input);
+                    let mut args = Punctuated::new();
+                    while !f.is_empty() && !f.peek(Token![->]) {
+                        args.push_value(f.parse()?);
+                        if !f.peek(Token![,]) {
+                            break;
+                        }
+                        args.push_punct(f.parse()?);
+                    }
+                    let out = if f.peek(Token![->]) {
+                        Some((f.parse()?, Box::new(f.parse::<ArgType>()?)))
+                    } else {
+                        None
+                    };
+                    if args.iter().chain(out.as_ref().map(|(_, o)| o.as_ref())).any(|a: &ArgType| !(a.is_raw() || a.is_wrapped()) || a.is_mut_ref()) {
+                        return Err(syn::Error::new(paren.span, "Callbacks can only take and return Raw or Wrapped types, which are not mutable references"));
+                    }
+                    Ok(Self::Callback { paren, args, out })
+                },
+                _ => panic!("Invalid argument, valid arguments are: [Raw(type),Wrapped(type),Reflected(type),Callback(args -> out),self,&self,&mut self]"),
             }
         } else {
             Ok(Self::Self_(input.parse()?))
@@ -202,26 +464,78 @@ impl Parse for ArgType {
 impl ToTokens for ArgType {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self {
-            ArgType::Raw { type_, .. } => tokens.extend(quote::quote!(Raw(#type_))),
+            ArgType::Raw { type_, name, .. } => {
+                let name = name.as_ref().map(|(as_, name)| quote::quote!(#as_ #name));
+                tokens.extend(quote::quote!(Raw(#type_ #name)))
+            }
             ArgType::Wrapped { type_, .. } => tokens.extend(quote::quote!(Wrapped(#type_))),
+            ArgType::Reflected { type_, .. } => tokens.extend(quote::quote!(Reflected(#type_))),
+            ArgType::Callback { args, out, .. } => {
+                let out = out.as_ref().map(|(arrow, out)| quote::quote!(#arrow #out));
+                tokens.extend(quote::quote!(Callback(#args #out)))
+            }
             ArgType::Self_(s) => s.to_tokens(tokens),
         };
     }
 }
 
 impl ArgType {
+    /// Fails if this is a callback, used when parsing positions other than method arguments,
+    /// which callbacks are not allowed in as they have no single type
+    pub fn reject_callback(self, position: &str) -> Result<Self, syn::Error> {
+        if self.is_callback() {
+            Err(syn::Error::new_spanned(
+                &self,
+                format!("Callbacks can only be used as method arguments, not as {position}"),
+            ))
+        } else {
+            Ok(self)
+        }
+    }
+
     /// retrieves the underlying argument type, if it's not a concrete type but a receiver, returns its type
+    ///
+    /// # Panics
+    /// If this is a callback, which has no single type. Callbacks are rejected everywhere but in method arguments when parsing
     pub fn type_(&self) -> result::Result<&SimpleType, SelfType> {
         match self {
-            Self::Raw { type_, .. } | Self::Wrapped { type_, .. } => Ok(type_),
+            Self::Raw { type_, .. }
+            | Self::Wrapped { type_, .. }
+            | Self::Reflected { type_, .. } => Ok(type_),
             Self::Self_(s) => Err(*s),
+            Self::Callback { .. } => panic!("Callbacks have no single type"),
         }
     }
 
+    /// # Panics
+    /// If this is a callback, which has no single type. Callbacks are rejected everywhere but in method arguments when parsing
     pub fn self_(&self) -> result::Result<SelfType, &SimpleType> {
         match self {
-            Self::Raw { type_, .. } | Self::Wrapped { type_, .. } => Err(type_),
+            Self::Raw { type_, .. }
+            | Self::Wrapped { type_, .. }
+            | Self::Reflected { type_, .. } => Err(type_),
             Self::Self_(s) => Ok(*s),
+            Self::Callback { .. } => panic!("Callbacks have no single type"),
+        }
+    }
+
+    /// The underlying type of concrete arguments, receivers and callbacks have none
+    fn simple_type(&self) -> Option<&SimpleType> {
+        match self {
+            Self::Raw { type_, .. }
+            | Self::Wrapped { type_, .. }
+            | Self::Reflected { type_, .. } => Some(type_),
+            Self::Self_(_) | Self::Callback { .. } => None,
+        }
+    }
+
+    /// Returns the arguments and output of a callback
+    pub fn callback(&self) -> Option<(&Punctuated<ArgType, Token![,]>, Option<&ArgType>)> {
+        match self {
+            Self::Callback { args, out, .. } => {
+                Some((args, out.as_ref().map(|(_, out)| out.as_ref())))
+            }
+            _ => None,
         }
     }
 
@@ -236,7 +550,9 @@ impl ArgType {
         match self {
             ArgType::Raw { type_, .. } => type_.is_any_ref(),
             ArgType::Wrapped { type_, .. } => type_.is_any_ref(),
+            ArgType::Reflected { type_, .. } => type_.is_any_ref(),
             ArgType::Self_(s) => s.is_any_ref(),
+            ArgType::Callback { .. } => false,
         }
     }
 
@@ -244,7 +560,9 @@ impl ArgType {
         match self {
             ArgType::Raw { type_, .. } => type_.is_mut_ref(),
             ArgType::Wrapped { type_, .. } => type_.is_mut_ref(),
+            ArgType::Reflected { type_, .. } => type_.is_mut_ref(),
             ArgType::Self_(s) => s.is_mut_ref(),
+            ArgType::Callback { .. } => false,
         }
     }
 
@@ -256,7 +574,66 @@ impl ArgType {
         matches!(self, Self::Raw { .. })
     }
 
+    pub fn is_reflected(&self) -> bool {
+        matches!(self, Self::Reflected { .. })
+    }
+
     pub fn is_self(&self) -> bool {
         matches!(self, Self::Self_ { .. })
     }
+
+    pub fn is_callback(&self) -> bool {
+        matches!(self, Self::Callback { .. })
+    }
+
+    /// Returns true if the underlying type is an `Option`
+    pub fn is_option(&self) -> bool {
+        self.simple_type().map_or(false, SimpleType::is_option)
+    }
+
+    /// Returns true if the underlying type is a `Vec`
+    pub fn is_vec(&self) -> bool {
+        self.simple_type().map_or(false, SimpleType::is_vec)
+    }
+
+    /// Returns true if the underlying type is a slice or a reference to one
+    pub fn is_slice(&self) -> bool {
+        self.simple_type().map_or(false, SimpleType::is_slice)
+    }
+
+    /// Returns true if the underlying type is a `Result`
+    pub fn is_result(&self) -> bool {
+        self.simple_type().map_or(false, SimpleType::is_result)
+    }
+
+    /// Returns true if the underlying type is a fixed size array
+    pub fn is_array(&self) -> bool {
+        self.simple_type().map_or(false, SimpleType::is_array)
+    }
+
+    /// Returns true if the underlying type is a `HashMap`
+    pub fn is_map(&self) -> bool {
+        self.simple_type().map_or(false, SimpleType::is_map)
+    }
+
+    /// Returns true if the underlying type is a `String`, `str` or `Cow<str>`, or a reference to one
+    pub fn is_string(&self) -> bool {
+        self.simple_type().map_or(false, SimpleType::is_string)
+    }
+
+    /// Returns true if the underlying type is a `Cow`
+    pub fn is_cow(&self) -> bool {
+        self.simple_type().map_or(false, SimpleType::is_cow)
+    }
+
+    /// Returns the name of the parameter this argument should be validated as, if any
+    pub fn validated_name(&self) -> Option<&Ident> {
+        match self {
+            ArgType::Raw {
+                name: Some((_, name)),
+                ..
+            } => Some(name),
+            _ => None,
+        }
+    }
 }
@@ -21,8 +21,17 @@ pub enum DeriveFlag {
     Debug => {Ok(Self::Debug{ident})},
     /// Tells the implementors this type supports `Display`
     Display => {Ok(Self::Display{ident})},
+    /// Tells the implementors this type supports neither `Display` nor `Debug`, so it's converted to strings as its type name
+    DefaultToString => {Ok(Self::DefaultToString{ident})},
     /// Tells the implementors this type supports `Clone`
     Clone{} => {Ok(Self::Clone{ident})},
+    /// Tells the implementors this type can be converted from and to a fixed length sequence of numbers,
+    /// via `bevy_script_api::common::sequence::ScriptSequence`
+    Sequence => {Ok(Self::Sequence{ident})},
+    /// Tells the implementors this type can be compared for equality with itself
+    PartialEq => {Ok(Self::PartialEq{ident})},
+    /// Tells the implementors this type can be ordered with respect to itself
+    PartialOrd => {Ok(Self::PartialOrd{ident})},
     /// Tells the implementors what fields are available on this type
     Fields {
         paren: Paren,
@@ -71,6 +80,18 @@ pub enum DeriveFlag {
             paren: parenthesized!(f in input),
             ops: f.parse_terminated(OpExpr::parse)?,
         })
+    },
+    /// Tells the implementors which key this type can be indexed with and what indexing returns, scripts can only index with one key type
+    Index {
+        paren: Paren,
+        exprs: Punctuated<IndexExpr,Token![,]>
+    } => {
+        let f;
+        Ok(Self::Index {
+            ident,
+            paren: parenthesized!(f in input),
+            exprs: f.parse_terminated(IndexExpr::parse)?,
+        })
     }
 }
 );
@@ -100,7 +121,13 @@ pub enum DeriveFlag {
 
 #[derive(PartialEq, Eq, Hash)]
 pub struct AutoMethod {
+    /// Docstrings and any other attributes passed through to the generated method (i.e. `#[cfg(...)]`)
     pub docstring: Vec<Attribute>,
+    /// The `#[rename("new_name")]`, `#[qualified(Trait)]`, `#[type_args(..)]` and `#[deprecated("note")]` attributes if present
+    pub attrs: Vec<Attribute>,
+    pub parsed_attrs: AutoFieldAttributes,
+    /// If present on a function without arguments, the function is evaluated at compile time
+    pub const_: Option<Token![const]>,
     pub ident: Ident,
     pub paren: Paren,
     pub self_: Option<(ArgType, Token![:])>,
@@ -108,16 +135,36 @@ pub struct AutoMethod {
     pub out: Option<ArgType>,
 }
 
+impl AutoMethod {
+    /// The name of the method on the script side, which differs from the rust method if renamed
+    pub fn script_name(&self) -> &Ident {
+        self.parsed_attrs
+            .script_name
+            .as_ref()
+            .unwrap_or(&self.ident)
+    }
+
+    /// The name of the method in the given language, which can be renamed for that language alone
+    pub fn script_name_in(&self, language: &str) -> &Ident {
+        self.parsed_attrs
+            .script_name_in(language)
+            .unwrap_or(&self.ident)
+    }
+}
+
 impl ToTokens for AutoMethod {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let docstring = self.docstring.iter();
+        let attrs = self.attrs.iter();
+        let const_ = &self.const_;
         let id = &self.ident;
         let args = &self.args;
         let self_ = self.self_.as_ref().map(|(a, _)| quote::quote!(#a:));
         let out = self.out.as_ref().map(|t| quote::quote! {-> #t});
         tokens.extend(quote::quote! {
             #(#docstring)*
-            #id(#self_ #args) #out
+            #(#attrs)*
+            #const_ #id(#self_ #args) #out
         })
     }
 }
@@ -126,13 +173,27 @@ impl ToTokens for AutoMethod {
 impl Parse for AutoMethod {
     fn parse(input: ParseStream) -> Result<Self, syn::Error> {
         let f;
+        let (attrs, docstring): (Vec<_>, Vec<_>) = Attribute::parse_outer(input)?
+            .into_iter()
+            .partition(|attr| {
+                attr.path.is_ident("rename")
+                    || attr.path.is_ident("qualified")
+                    || attr.path.is_ident("type_args")
+                    || attr.path.is_ident("deprecated")
+            });
         let o = Ok(Self {
-            docstring: Attribute::parse_outer(input)?,
+            docstring,
+            parsed_attrs: attrs.as_slice().try_into()?,
+            attrs,
+            const_: input.parse()?,
             ident: input.parse()?,
             paren: parenthesized!(f in input),
             self_: {
                 let parser = |p: ParseStream| {
-                    Ok::<_, syn::Error>((p.parse::<ArgType>()?, p.parse::<Token![:]>()?))
+                    Ok::<_, syn::Error>((
+                        p.parse::<ArgType>()?.reject_callback("receivers")?,
+                        p.parse::<Token![:]>()?,
+                    ))
                 };
                 let fork = f.fork();
                 if parser(&fork).is_ok() {
@@ -144,7 +205,11 @@ impl Parse for AutoMethod {
             args: f.parse_terminated(ArgType::parse)?,
             out: if input.peek(Token![->]) {
                 input.parse::<Token![->]>()?;
-                Some(input.parse()?)
+                Some(
+                    input
+                        .parse::<ArgType>()?
+                        .reject_callback("method outputs")?,
+                )
             } else {
                 None
             },
@@ -156,23 +221,91 @@ impl Parse for AutoMethod {
 #[derive(PartialEq, Eq, Hash)]
 pub struct AutoFieldAttributes {
     pub script_name: Option<Ident>,
+    /// set by `#[rename(lua = "end_")]`, the name in the given language only, i.e. when the name is one of its keywords
+    pub language_names: Vec<(Ident, Ident)>,
+    /// set by `#[readonly]`, only a getter is generated
+    pub readonly: bool,
+    /// set by `#[qualified(Trait)]` on methods, the method is called as `<Type as Trait>::method`
+    pub qualified: Option<syn::Path>,
+    /// set by `#[type_args(T, U)]` on generic methods, the method is called as `method::<T, U>`
+    pub type_args: Vec<syn::Type>,
+    /// set by `#[deprecated("note")]` on methods, scripts calling the method are warned once with the note
+    pub deprecated: Option<String>,
+}
+
+impl AutoFieldAttributes {
+    /// The script name given for the given language, or for all languages otherwise
+    pub fn script_name_in(&self, language: &str) -> Option<&Ident> {
+        self.language_names
+            .iter()
+            .find(|(l, _)| l == language)
+            .map(|(_, name)| name)
+            .or(self.script_name.as_ref())
+    }
 }
 
 impl TryFrom<&[Attribute]> for AutoFieldAttributes {
     type Error = syn::Error;
 
     fn try_from(value: &[Attribute]) -> Result<Self, Self::Error> {
-        let mut out = Self { script_name: None };
+        let mut out = Self {
+            script_name: None,
+            language_names: Vec::default(),
+            readonly: false,
+            qualified: None,
+            type_args: Vec::default(),
+            deprecated: None,
+        };
 
         for v in value {
+            // type arguments aren't necessarily valid meta items, i.e. `Option<f32>`
+            if v.path.is_ident("type_args") {
+                out.type_args = v
+                    .parse_args_with(Punctuated::<syn::Type, Token![,]>::parse_terminated)?
+                    .into_iter()
+                    .collect();
+                continue;
+            }
+
             let meta = v.parse_meta()?;
 
             if let Some(ident) = meta.path().get_ident() {
-                if *ident == "rename" {
+                if *ident == "readonly" {
+                    out.readonly = true;
+                } else if *ident == "rename" {
+                    if let syn::Meta::List(l) = &meta {
+                        for nested in &l.nested {
+                            match nested {
+                                syn::NestedMeta::Lit(syn::Lit::Str(s)) => {
+                                    out.script_name = Some(s.parse()?)
+                                }
+                                syn::NestedMeta::Meta(syn::Meta::NameValue(
+                                    syn::MetaNameValue {
+                                        path,
+                                        lit: syn::Lit::Str(s),
+                                        ..
+                                    },
+                                )) if path.get_ident().is_some() => out
+                                    .language_names
+                                    .push((path.get_ident().unwrap().clone(), s.parse()?)),
+                                _ => {}
+                            }
+                        }
+                    }
+                } else if *ident == "qualified" {
+                    if let syn::Meta::List(l) = &meta {
+                        for nested in &l.nested {
+                            if let syn::NestedMeta::Meta(syn::Meta::Path(p)) = nested {
+                                out.qualified = Some(p.clone())
+                            }
+                        }
+                    }
+                } else if *ident == "deprecated" {
+                    out.deprecated = Some(String::default());
                     if let syn::Meta::List(l) = &meta {
                         for nested in &l.nested {
                             if let syn::NestedMeta::Lit(syn::Lit::Str(s)) = nested {
-                                out.script_name = Some(s.parse()?)
+                                out.deprecated = Some(s.value())
                             }
                         }
                     }
@@ -204,7 +337,7 @@ impl Parse for AutoField {
             parsed_attrs: attrs[split_idx..].try_into()?,
             member: input.parse()?,
             colon: input.parse()?,
-            type_: input.parse()?,
+            type_: input.parse::<ArgType>()?.reject_callback("fields")?,
         })
     }
 }
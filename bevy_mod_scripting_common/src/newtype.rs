@@ -43,16 +43,16 @@ impl NewtypeArgs {
         ) = (fields, methods)
         {
             for m in methods {
-                if seen_identifiers.contains(&m.ident) {
+                if seen_identifiers.contains(m.script_name()) {
                     return Err(syn::Error::new_spanned(
                         m,
                         format!(
                             "Method name `{}` clashes with another field or method",
-                            &m.ident
+                            m.script_name()
                         ),
                     ));
                 }
-                seen_identifiers.insert(&m.ident);
+                seen_identifiers.insert(m.script_name());
             }
 
             for f in fields {
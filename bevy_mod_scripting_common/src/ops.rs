@@ -90,12 +90,18 @@ impl Parse for OpExpr {
             left: (input.fork())
                 .parse::<OpName>()
                 .is_err()
-                .then(|| Ok::<_, syn::Error>(Some(input.parse()?)))
+                .then(|| {
+                    Ok::<_, syn::Error>(Some(
+                        input.parse::<ArgType>()?.reject_callback("operands")?,
+                    ))
+                })
                 .unwrap_or(Ok(None))?,
             op: input.parse()?,
-            right: input.parse()?,
+            right: input.parse::<ArgType>()?.reject_callback("operands")?,
             arrow: input.parse()?,
-            return_type: input.parse()?,
+            return_type: input
+                .parse::<ArgType>()?
+                .reject_callback("operator outputs")?,
         };
 
         if s.has_receiver() {
@@ -158,3 +164,36 @@ impl OpExpr {
         )
     }
 }
+
+/// The key and output types of an `Index` impl, prefixed with `mut` if the type also implements `IndexMut` with the same key,
+/// i.e. `mut Raw(usize) -> Raw(f32)`
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct IndexExpr {
+    pub mut_: Option<Token![mut]>,
+    pub key: ArgType,
+    pub arrow: Token![->],
+    pub output: ArgType,
+}
+
+impl Parse for IndexExpr {
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        Ok(Self {
+            mut_: input.parse()?,
+            key: input.parse::<ArgType>()?.reject_callback("index keys")?,
+            arrow: input.parse()?,
+            output: input.parse::<ArgType>()?.reject_callback("index outputs")?,
+        })
+    }
+}
+
+impl ToTokens for IndexExpr {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let mut_ = &self.mut_;
+        let key = &self.key;
+        let output = &self.output;
+
+        tokens.extend(quote::quote! {
+            #mut_ #key -> #output
+        })
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use crate::error::ScriptError;
 
 /// A documentation piece exported by an `APIProvider`
@@ -5,6 +7,20 @@ pub trait DocFragment: 'static {
     fn merge(self, o: Self) -> Self;
     fn gen_docs(self) -> Result<(), ScriptError>;
 
+    /// Renders the documentation into a single file at the given path,
+    /// the supported formats are up to the implementor and are usually picked based on the file extension.
+    ///
+    /// By default exporting is not supported and an error is returned
+    fn export_docs(self, path: &Path) -> Result<(), ScriptError>
+    where
+        Self: Sized,
+    {
+        Err(ScriptError::DocGenError(format!(
+            "Cannot export documentation to `{}`, exporting is not supported for this language",
+            path.display()
+        )))
+    }
+
     /// Retrieves the name of the documentation fragment, most likely the name of your game!
     fn name(&self) -> &'static str;
 }
@@ -1,13 +1,24 @@
+use std::sync::Arc;
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone)]
 pub enum ScriptError {
-    #[error("Runtime error in script `{script}` {msg}")]
-    RuntimeError { script: String, msg: String },
+    #[error("Runtime error in script `{script}`{} {msg}", fmt_line(.line))]
+    RuntimeError {
+        script: String,
+        /// The line the error occurred on, if the language reports it
+        line: Option<usize>,
+        msg: String,
+    },
     #[error("Failed to load script asset for `{script}`")]
     FailedToLoad { script: String },
-    #[error("Syntax error for script `{script}` {msg}")]
-    SyntaxError { script: String, msg: String },
+    #[error("Syntax error for script `{script}`{} {msg}", fmt_line(.line))]
+    SyntaxError {
+        script: String,
+        /// The line the error occurred on, if the language reports it
+        line: Option<usize>,
+        msg: String,
+    },
     #[error("Callback method `{callback}` invalid for script `{script}` {msg}")]
     InvalidCallback {
         script: String,
@@ -16,15 +27,134 @@ pub enum ScriptError {
     },
     #[error("Failed to attach API for script `{script}` {msg}")]
     FailedToAttachAPI { script: String, msg: String },
+    #[error("Failed to convert value to or from `{type_name}` {msg}")]
+    ConversionError { type_name: String, msg: String },
+    #[error("Could not access `{type_name}` in the world {msg}")]
+    WorldAccessError { type_name: String, msg: String },
+    #[error("Script `{script}` exceeded its limit of {limit}")]
+    LimitExceeded {
+        script: String,
+        /// The limit which was exceeded, i.e. `1000 instructions`
+        limit: String,
+    },
+    #[error("Failed to import module `{module}` {msg}")]
+    ModuleError { module: String, msg: String },
     #[error("Failed to generate documentation `{0}`")]
     DocGenError(String),
+    #[error("{language} error{}: {source}{}", fmt_script(.script), fmt_traceback(.traceback))]
+    LanguageError {
+        language: &'static str,
+        /// The script the error originated from, if known
+        script: Option<String>,
+        /// The original error raised by the language
+        source: Arc<dyn std::error::Error + Send + Sync>,
+        /// The traceback reported alongside the error, if the language provides one
+        traceback: Option<String>,
+    },
     #[error("{0}")]
     Other(String),
 }
 
+fn fmt_line(line: &Option<usize>) -> String {
+    line.map(|l| format!(" at line {l}")).unwrap_or_default()
+}
+
+fn fmt_script(script: &Option<String>) -> String {
+    script
+        .as_ref()
+        .map(|s| format!(" in script `{s}`"))
+        .unwrap_or_default()
+}
+
+fn fmt_traceback(traceback: &Option<String>) -> String {
+    traceback
+        .as_ref()
+        .map(|t| format!("\n{t}"))
+        .unwrap_or_default()
+}
+
 impl ScriptError {
     /// Create new `ScriptError::Other` from another error
     pub fn new_other<T: std::error::Error>(other: T) -> Self {
         Self::Other(other.to_string())
     }
+
+    /// Create new `ScriptError::ConversionError` for the given type
+    pub fn new_conversion<T: ToString>(type_name: &str, msg: T) -> Self {
+        Self::ConversionError {
+            type_name: type_name.to_owned(),
+            msg: msg.to_string(),
+        }
+    }
+
+    /// Create new `ScriptError::WorldAccessError` for the given type
+    pub fn new_world_access<T: ToString>(type_name: &str, msg: T) -> Self {
+        Self::WorldAccessError {
+            type_name: type_name.to_owned(),
+            msg: msg.to_string(),
+        }
+    }
+
+    /// Create new `ScriptError::ModuleError` for the given module path
+    pub fn new_module_error<T: ToString>(module: &str, msg: T) -> Self {
+        Self::ModuleError {
+            module: module.to_owned(),
+            msg: msg.to_string(),
+        }
+    }
+
+    /// Create new `ScriptError::LanguageError` keeping the original error raised by a scripting language.
+    /// Language crates wrap this in constructors for their own error types, i.e. `ScriptError::from_lua`
+    pub fn new_language_error<E: std::error::Error + Send + Sync + 'static>(
+        language: &'static str,
+        error: E,
+        traceback: Option<String>,
+    ) -> Self {
+        Self::LanguageError {
+            language,
+            script: None,
+            source: Arc::new(error),
+            traceback,
+        }
+    }
+
+    /// Attach the name of the script to a `ScriptError::LanguageError`, other errors are returned unchanged
+    pub fn with_script<T: ToString>(self, name: T) -> Self {
+        match self {
+            Self::LanguageError {
+                language,
+                source,
+                traceback,
+                ..
+            } => Self::LanguageError {
+                language,
+                script: Some(name.to_string()),
+                source,
+                traceback,
+            },
+            other => other,
+        }
+    }
+
+    /// The script this error originated from, if known
+    pub fn script(&self) -> Option<&str> {
+        match self {
+            Self::RuntimeError { script, .. }
+            | Self::FailedToLoad { script }
+            | Self::SyntaxError { script, .. }
+            | Self::InvalidCallback { script, .. }
+            | Self::FailedToAttachAPI { script, .. }
+            | Self::LimitExceeded { script, .. } => Some(script),
+            Self::LanguageError { script, .. } => script.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The line in the script this error originated from, if known
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::RuntimeError { line, .. } | Self::SyntaxError { line, .. } => *line,
+            _ => None,
+        }
+    }
 }
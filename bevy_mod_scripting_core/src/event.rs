@@ -1,11 +1,44 @@
-use crate::{error::ScriptError, hosts::Recipients};
+use std::{fmt, sync::Arc};
 
-/// An error coming from a script
-#[derive(Debug)]
+use bevy::prelude::{error, Entity, EventReader};
+use parking_lot::Mutex;
+
+use crate::{
+    error::ScriptError,
+    hosts::{Recipients, ScriptData},
+};
+
+/// An error raised by a script while handling an event, callback or timer.
+///
+/// Hosts send one for every failed call and carry on with the remaining scripts and events,
+/// the [`ScriptingPlugin`](crate::ScriptingPlugin) logs them with [`log_script_errors`].
+#[derive(Debug, Clone)]
 pub struct ScriptErrorEvent {
+    /// The entity owning the script, [`Entity::PLACEHOLDER`] for code which doesn't belong to a script instance
+    pub entity: Entity,
+    /// The name of the script
+    pub script: String,
     pub error: ScriptError,
 }
 
+impl ScriptErrorEvent {
+    /// An error raised by the given script
+    pub fn new(script_data: &ScriptData, error: ScriptError) -> Self {
+        Self {
+            entity: script_data.entity,
+            script: script_data.name.to_owned(),
+            error,
+        }
+    }
+}
+
+/// Logs every [`ScriptErrorEvent`] as an error, added to `CoreSet::Last` by the [`ScriptingPlugin`](crate::ScriptingPlugin)
+pub fn log_script_errors(mut errors: EventReader<ScriptErrorEvent>) {
+    for event in errors.iter() {
+        error!("{}", event.error);
+    }
+}
+
 /// An event emitted when a script was loaded or re-loaded (with a hot-reload),
 /// guaranteed to be sent for every script at least once and immediately after it's loaded.
 #[derive(Clone, Debug)]
@@ -18,3 +51,137 @@ pub trait ScriptEvent: Send + Sync + Clone + 'static {
     /// Retrieves the recipient scripts for this event
     fn recipients(&self) -> &Recipients;
 }
+
+/// A hook expecting a value back from every script handling it.
+///
+/// Hosts implementing [`CallbackHost`](crate::hosts::CallbackHost) call the hook with `args`, convert each script's return value to `Out`
+/// and push it into `response`, which the sender keeps a clone of to read the values once the callback handler has run.
+pub struct CallbackEvent<In, Out> {
+    pub hook_name: String,
+    pub args: In,
+    pub recipients: Recipients,
+    pub response: CallbackResponse<Out>,
+}
+
+impl<In: Clone, Out> Clone for CallbackEvent<In, Out> {
+    fn clone(&self) -> Self {
+        Self {
+            hook_name: self.hook_name.clone(),
+            args: self.args.clone(),
+            recipients: self.recipients.clone(),
+            response: self.response.clone(),
+        }
+    }
+}
+
+impl<In, Out> fmt::Debug for CallbackEvent<In, Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackEvent")
+            .field("hook_name", &self.hook_name)
+            .field("recipients", &self.recipients)
+            .finish()
+    }
+}
+
+impl<In: Send + Sync + Clone + 'static, Out: Send + 'static> ScriptEvent
+    for CallbackEvent<In, Out>
+{
+    fn recipients(&self) -> &Recipients {
+        &self.recipients
+    }
+}
+
+/// The value returned by a single script handling a [`CallbackEvent`]
+#[derive(Debug)]
+pub struct ScriptResponse<Out> {
+    pub sid: u32,
+    pub script: String,
+    /// The converted return value, or the error raised while calling the hook or converting its result
+    pub value: Result<Out, ScriptError>,
+}
+
+/// Collects the values returned by scripts handling a [`CallbackEvent`], clones share the same values.
+///
+/// Every recipient defining the hook adds exactly one [`ScriptResponse`], in the order the scripts were called,
+/// recipients which do not define the hook add nothing. Combining the values, i.e. summing them or taking the first one, is up to the sender.
+pub struct CallbackResponse<Out>(Arc<Mutex<Vec<ScriptResponse<Out>>>>);
+
+impl<Out> CallbackResponse<Out> {
+    pub fn new() -> Self {
+        Self(Default::default())
+    }
+
+    /// Records the value returned by a script, called by script hosts
+    pub fn push(&self, response: ScriptResponse<Out>) {
+        self.0.lock().push(response)
+    }
+
+    /// Removes and returns all values returned so far
+    pub fn take(&self) -> Vec<ScriptResponse<Out>> {
+        std::mem::take(&mut *self.0.lock())
+    }
+
+    /// Removes all values returned so far and returns the successful ones, errors have already been sent as [`ScriptErrorEvent`]s
+    pub fn take_values(&self) -> Vec<Out> {
+        self.take()
+            .into_iter()
+            .filter_map(|r| r.value.ok())
+            .collect()
+    }
+}
+
+impl<Out> Default for CallbackResponse<Out> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Out> Clone for CallbackResponse<Out> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<Out> fmt::Debug for CallbackResponse<Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CallbackResponse")
+            .field(&self.0.lock().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::{App, Events};
+
+    use super::*;
+    use crate::ScriptingPlugin;
+
+    #[test]
+    fn errors_carry_the_script_and_entity_they_were_raised_by() {
+        let mut app = App::new();
+        app.add_plugin(ScriptingPlugin);
+        let entity = app.world.spawn_empty().id();
+        app.world.send_event(ScriptErrorEvent::new(
+            &ScriptData {
+                sid: 0,
+                entity,
+                name: "a.lua",
+            },
+            ScriptError::Other("boom".to_owned()),
+        ));
+        // logging the errors leaves them for other readers
+        app.update();
+
+        let events = app.world.resource::<Events<ScriptErrorEvent>>();
+        let errors = events
+            .get_reader()
+            .iter(events)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].entity, entity);
+        assert_eq!(errors[0].script, "a.lua");
+        assert!(errors[0].error.to_string().contains("boom"));
+    }
+}
@@ -1,13 +1,19 @@
 //! All script host related stuff
 use bevy::{
     asset::Asset,
-    ecs::schedule::{BaseSystemSet, FreeSystemSet},
+    ecs::{
+        component::ComponentId,
+        schedule::{BaseSystemSet, FreeSystemSet, ScheduleLabel},
+    },
     prelude::*,
     reflect::FromReflect,
 };
 use std::{
-    collections::HashMap,
+    any::{Any, TypeId},
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
     iter::once,
+    path::Path,
     sync::atomic::{AtomicU32, Ordering},
 };
 
@@ -15,7 +21,8 @@ use crate::{
     asset::CodeAsset,
     docs::DocFragment,
     error::ScriptError,
-    event::{ScriptEvent, ScriptLoaded},
+    event::{CallbackEvent, ScriptEvent, ScriptLoaded},
+    modules::ScriptModules,
     world::WorldPointer,
 };
 
@@ -27,6 +34,8 @@ pub enum Recipients {
     All,
     /// Send only to scripts on the given entity
     Entity(Entity),
+    /// Send only to scripts on entities carrying the given component
+    WithComponent(ComponentId),
     /// Send to script with the given ID
     ScriptID(u32),
     // Send to script with the given name
@@ -41,12 +50,30 @@ pub struct ScriptData<'a> {
     pub name: &'a str,
 }
 
+impl ScriptData<'static> {
+    /// The data of code evaluated with [`EvalHost::eval_oneshot`], which doesn't belong to any script instance or entity.
+    ///
+    /// Each call allocates a new script id, so state kept per script id (timers, systems, random number generators)
+    /// is never shared between evaluations, and is dropped along with that of despawned scripts.
+    pub fn oneshot() -> Self {
+        Self {
+            sid: next_script_id(),
+            entity: Entity::PLACEHOLDER,
+            name: "oneshot",
+        }
+    }
+}
+
 impl Recipients {
-    /// Returns true if the given script is a recipient
-    pub fn is_recipient(&self, c: &ScriptData) -> bool {
+    /// Returns true if the given script is a recipient, component based recipients are resolved against the given world
+    pub fn is_recipient(&self, c: &ScriptData, world: &World) -> bool {
         match self {
             Recipients::All => true,
             Recipients::Entity(e) => e == &c.entity,
+            Recipients::WithComponent(id) => world
+                .get_entity(c.entity)
+                .map(|e| e.contains_id(*id))
+                .unwrap_or(false),
             Recipients::ScriptID(i) => i == &c.sid,
             Recipients::ScriptName(n) => n == c.name,
         }
@@ -59,6 +86,9 @@ impl Default for Recipients {
     }
 }
 
+/// Script state kept across a hot reload, in whatever form the script host saved it in
+pub type ReloadState = Box<dyn Any + Send + Sync>;
+
 /// A script host is the interface between your rust application
 /// and the scripts in some interpreted language.
 pub trait ScriptHost: Send + Sync + 'static + Default + Resource {
@@ -83,6 +113,18 @@ pub trait ScriptHost: Send + Sync + 'static + Default + Resource {
         providers: &mut APIProviders<Self>,
     ) -> Result<Self::ScriptContext, ScriptError>;
 
+    /// Loads a script from its asset, by default this loads the source of the asset with [`ScriptHost::load_script`].
+    ///
+    /// Hosts whose asset loaders compile scripts ahead of time override this to reuse the compiled form kept in the asset.
+    fn load_script_asset(
+        &mut self,
+        script: &Self::ScriptAsset,
+        script_data: &ScriptData,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<Self::ScriptContext, ScriptError> {
+        self.load_script(script.bytes(), script_data, providers)
+    }
+
     /// Perform one-off initialization of scripts (happens for every new or re-loaded script)
     fn setup_script(
         &mut self,
@@ -91,7 +133,36 @@ pub trait ScriptHost: Send + Sync + 'static + Default + Resource {
         providers: &mut APIProviders<Self>,
     ) -> Result<(), ScriptError>;
 
-    /// the main point of contact with the bevy world.
+    /// Called on the old context of a script about to be hot-reloaded, the returned state is passed to
+    /// `load_reload_state` once the new context is set up. Hosts which can keep script state across reloads
+    /// implement this by calling a hook declared by the script, by default nothing is kept.
+    ///
+    /// The world can't be accessed during this call, hosts must hide it from scripts (i.e. the `world` global)
+    fn save_reload_state(
+        &self,
+        _script_data: &ScriptData,
+        _ctx: &mut Self::ScriptContext,
+    ) -> Result<Option<ReloadState>, ScriptError> {
+        Ok(None)
+    }
+
+    /// Restores the state saved by `save_reload_state` into the new context of a hot-reloaded script.
+    ///
+    /// The world can't be accessed during this call, hosts must hide it from scripts (i.e. the `world` global)
+    fn load_reload_state(
+        &self,
+        _script_data: &ScriptData,
+        _ctx: &mut Self::ScriptContext,
+        _state: ReloadState,
+    ) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    /// The modules scripts of this host can import from other files, `None` if the host doesn't support importing modules
+    fn modules(&self) -> Option<&ScriptModules> {
+        None
+    }
+
     /// Scripts are called with appropriate events in the event order
     fn handle_events<'a>(
         &self,
@@ -102,7 +173,7 @@ pub trait ScriptHost: Send + Sync + 'static + Default + Resource {
     );
 
     /// Loads and runs script instantaneously without storing any script data into the world.
-    /// The script id is set to `u32::MAX`.
+    /// The script is given a new script id, like [`ScriptData::oneshot`].
     fn run_one_shot(
         &mut self,
         script: &[u8],
@@ -113,7 +184,7 @@ pub trait ScriptHost: Send + Sync + 'static + Default + Resource {
     ) -> Result<(), ScriptError> {
         let fd = ScriptData {
             name: script_name,
-            sid: u32::MAX,
+            sid: next_script_id(),
             entity,
         };
 
@@ -138,6 +209,39 @@ pub trait ScriptHost: Send + Sync + 'static + Default + Resource {
     ///
     /// Ideally place after any game logic which can spawn/remove/modify scripts to avoid frame lag. (typically `CoreStage::Post_Update`)
     fn register_with_app_in_base_set(app: &mut App, set: impl BaseSystemSet + Clone);
+
+    /// Registers the script host with the given app, and attaches handlers to deal with spawning/removing scripts in the given set of the given schedule.
+    ///
+    /// Use this to run the host in a schedule other than the main one, for example `OnEnter` of some state or `CoreSchedule::FixedUpdate`.
+    fn register_with_app_in_schedule(
+        app: &mut App,
+        schedule: impl ScheduleLabel + Clone,
+        set: impl FreeSystemSet + Clone,
+    );
+}
+
+/// Script hosts which can return values from scripts to rust by handling [`CallbackEvent`]s with inputs `In` and outputs `Out`
+pub trait CallbackHost<In, Out>: ScriptHost {
+    /// Calls the hook of each event on every recipient script defining it,
+    /// pushing each converted return value or error into the event's response in the order the scripts were called
+    fn handle_callbacks<'a>(
+        &self,
+        world: &mut World,
+        events: &[CallbackEvent<In, Out>],
+        ctxs: impl Iterator<Item = (ScriptData<'a>, &'a mut Self::ScriptContext)>,
+        providers: &mut APIProviders<Self>,
+    );
+}
+
+/// Script hosts which can evaluate a code string immediately, without a script asset or entity, returning its value as `Out`
+pub trait EvalHost<Out>: ScriptHost {
+    /// Compiles and runs the given code in a fresh context with the API of every provider attached,
+    /// returning the value the code evaluates to. Useful for in-game consoles and tests.
+    ///
+    /// The code can access the world exactly like a script handling an event, since the providers' runtime setup
+    /// (which is where `GetWorld` gets its world pointer from) runs before the code is evaluated.
+    /// The context is described by [`ScriptData::oneshot`] and is dropped afterwards, so nothing defined by the code persists.
+    fn eval_oneshot(&mut self, code: &str, world: &mut World) -> Result<Out, ScriptError>;
 }
 
 /// Implementors can modify a script context in order to enable
@@ -185,6 +289,58 @@ pub trait APIProvider: 'static + Send + Sync {
     /// Some providers might provide additional types which need to be registered
     /// with the reflection API to work.
     fn register_with_app(&self, _app: &mut App) {}
+
+    /// The types of the providers whose API must be attached before this one's, i.e. because this provider
+    /// refers to globals they register. The providers of a host are sorted accordingly as they are added.
+    fn dependencies(&self) -> Vec<TypeId> {
+        Vec::default()
+    }
+
+    /// The type of this provider, which other providers refer to in their `dependencies`.
+    ///
+    /// There should be no need to override this.
+    fn provider_type_id(&self) -> TypeId {
+        TypeId::of::<Self>()
+    }
+
+    /// The name of this provider used in error messages.
+    fn provider_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+}
+
+/// Returns the index of a node depending on a node which isn't in the given nodes
+fn missing_dependency(nodes: &[(TypeId, Vec<TypeId>)]) -> Option<usize> {
+    nodes.iter().position(|(_, deps)| {
+        deps.iter()
+            .any(|dep| !nodes.iter().any(|(id, _)| id == dep))
+    })
+}
+
+/// Returns the order in which the given nodes should be visited so that every node comes after its dependencies,
+/// nodes without dependencies between them keep their relative order. Dependencies on missing nodes are ignored.
+///
+/// Fails with the index of a node which is part of a cycle.
+fn dependency_order(nodes: &[(TypeId, Vec<TypeId>)]) -> Result<Vec<usize>, usize> {
+    let mut order: Vec<usize> = Vec::with_capacity(nodes.len());
+    while order.len() < nodes.len() {
+        let next = (0..nodes.len()).find(|idx| {
+            !order.contains(idx)
+                && nodes[*idx].1.iter().all(|dep| {
+                    order.iter().any(|placed| nodes[*placed].0 == *dep)
+                        || !nodes.iter().any(|(id, _)| id == dep)
+                })
+        });
+        match next {
+            Some(idx) => order.push(idx),
+            // every remaining node waits on another one
+            None => {
+                let cyclic = (0..nodes.len()).find(|idx| !order.contains(idx)).unwrap();
+                return Err(cyclic);
+            }
+        }
+    }
+    Ok(order)
 }
 
 #[derive(Resource)]
@@ -199,18 +355,57 @@ pub struct APIProviders<T: ScriptHost> {
             >,
         >,
     >,
+    /// Builders of hand written documentation fragments, merged after the fragments of the providers,
+    /// see [`GenDocumentation::add_doc_fragment`](crate::GenDocumentation::add_doc_fragment)
+    pub doc_fragments: Vec<Box<dyn Fn() -> T::DocTarget + Send + Sync>>,
 }
 
 impl<T: ScriptHost> Default for APIProviders<T> {
     fn default() -> Self {
         Self {
             providers: Default::default(),
+            doc_fragments: Default::default(),
         }
     }
 }
 
 impl<T: ScriptHost> APIProviders<T> {
+    /// The type of each provider along with the types of its dependencies
+    fn dependency_nodes(&self) -> Vec<(TypeId, Vec<TypeId>)> {
+        self.providers
+            .iter()
+            .map(|p| (p.provider_type_id(), p.dependencies()))
+            .collect()
+    }
+
+    /// Sorts the providers so that every provider comes after the ones it depends on, fails if the dependencies are cyclic.
+    ///
+    /// This is done by `add_api_provider` whenever a provider is added, providers pushed into `providers` directly are
+    /// attached in the order they were pushed in unless this is called.
+    pub fn sort_by_dependencies(&mut self) -> Result<(), ScriptError> {
+        let order = dependency_order(&self.dependency_nodes()).map_err(|idx| {
+            let name = self.providers[idx].provider_name();
+            ScriptError::Other(format!(
+                "API provider `{name}` is part of a dependency cycle"
+            ))
+        })?;
+
+        let mut providers: Vec<_> = self.providers.drain(..).map(Some).collect();
+        self.providers = order
+            .into_iter()
+            .map(|idx| providers[idx].take().unwrap())
+            .collect();
+        Ok(())
+    }
+
+    /// Attaches the API of all providers in their current order, fails if a provider depends on one which was never added
     pub fn attach_all(&mut self, ctx: &mut T::APITarget) -> Result<(), ScriptError> {
+        if let Some(idx) = missing_dependency(&self.dependency_nodes()) {
+            let name = self.providers[idx].provider_name();
+            return Err(ScriptError::Other(format!(
+                "API provider `{name}` depends on a provider which was not added"
+            )));
+        }
         for p in self.providers.iter_mut() {
             p.attach_api(ctx)?;
         }
@@ -218,6 +413,8 @@ impl<T: ScriptHost> APIProviders<T> {
         Ok(())
     }
 
+    /// Sets up the runtime of the given script with all providers,
+    /// language errors raised by a provider are attached to the script
     pub fn setup_runtime_all(
         &mut self,
         world_ptr: WorldPointer,
@@ -225,7 +422,8 @@ impl<T: ScriptHost> APIProviders<T> {
         ctx: &mut T::ScriptContext,
     ) -> Result<(), ScriptError> {
         for p in self.providers.iter_mut() {
-            p.setup_script_runtime(world_ptr.clone(), script_data, ctx)?;
+            p.setup_script_runtime(world_ptr.clone(), script_data, ctx)
+                .map_err(|e| e.with_script(script_data.name))?;
         }
 
         Ok(())
@@ -237,27 +435,52 @@ impl<T: ScriptHost> APIProviders<T> {
         ctx: &mut T::ScriptContext,
     ) -> Result<(), ScriptError> {
         for p in self.providers.iter_mut() {
-            p.setup_script(script_data, ctx)?;
+            p.setup_script(script_data, ctx)
+                .map_err(|e| e.with_script(script_data.name))?;
         }
 
         Ok(())
     }
 
+    /// Merges the documentation fragments of all providers in their current order,
+    /// followed by the hand written fragments in the order they were added.
+    ///
+    /// Every fragment is merged into the previous ones, so the name of the documentation is that of the first fragment.
+    fn merged_docs(&self) -> Option<T::DocTarget> {
+        self.providers
+            .iter()
+            .filter_map(|p| p.get_doc_fragment())
+            .chain(self.doc_fragments.iter().map(|f| f()))
+            .reduce(|prev, f| prev.merge(f))
+    }
+
     pub fn gen_all(&self) -> Result<(), ScriptError> {
-        let mut d: Option<T::DocTarget> = None;
-        for p in self.providers.iter() {
-            if let Some(f) = p.get_doc_fragment() {
-                if let Some(prev) = d {
-                    d = Some(prev.merge(f))
-                } else {
-                    d = Some(f)
-                }
-            }
-        }
-        d.map(|d| d.gen_docs()).unwrap_or_else(|| Ok(()))
+        self.merged_docs()
+            .map(|d| d.gen_docs())
+            .unwrap_or_else(|| Ok(()))
+    }
+
+    /// Renders the combined documentation of all providers and hand written fragments into a single file at the given path,
+    /// does nothing if no provider documents its API
+    pub fn export_all(&self, path: &Path) -> Result<(), ScriptError> {
+        self.merged_docs()
+            .map(|d| d.export_docs(path))
+            .unwrap_or_else(|| Ok(()))
     }
 }
 
+/// The order in which scripts handle events of the same priority.
+///
+/// Insert this as a resource to change the ordering, the default is `EntityThenName`.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScriptOrdering {
+    /// Scripts run in ascending order of their entity, scripts on the same entity in order of their name
+    #[default]
+    EntityThenName,
+    /// Scripts run in the order their `Script` instances were created
+    Registration,
+}
+
 /// A resource storing the script contexts for each script instance.
 /// The reason we need this is to split the world borrow in our handle event systems, but this
 /// has the added benefit that users don't see the contexts at all, and we can provide
@@ -294,6 +517,11 @@ impl<C> ScriptContexts<C> {
         self.context_entities.remove(&script_id);
     }
 
+    /// Removes the contexts of all scripts attached to the given entity
+    pub fn remove_entity_contexts(&mut self, entity: Entity) {
+        self.context_entities.retain(|_, (e, _, _)| *e != entity);
+    }
+
     pub fn has_context(&self, script_id: u32) -> bool {
         self.context_entities
             .get(&script_id)
@@ -303,6 +531,36 @@ impl<C> ScriptContexts<C> {
     pub fn is_empty(&self) -> bool {
         self.context_entities.is_empty()
     }
+
+    /// The loaded contexts in the order scripts should handle events, see [`ScriptOrdering`]
+    pub fn ordered_contexts(
+        &mut self,
+        ordering: ScriptOrdering,
+    ) -> impl Iterator<Item = (ScriptData<'_>, &mut C)> + '_ {
+        let mut contexts = self
+            .context_entities
+            .iter_mut()
+            .filter_map(|(sid, (entity, ctx, name))| {
+                Some((
+                    ScriptData {
+                        sid: *sid,
+                        entity: *entity,
+                        name,
+                    },
+                    ctx.as_mut()?,
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        match ordering {
+            ScriptOrdering::EntityThenName => contexts.sort_by(|(a, _), (b, _)| {
+                (a.entity, a.name, a.sid).cmp(&(b.entity, b.name, b.sid))
+            }),
+            ScriptOrdering::Registration => contexts.sort_by_key(|(data, _)| data.sid),
+        }
+
+        contexts.into_iter()
+    }
 }
 
 /// A struct defining an instance of a script asset.
@@ -321,6 +579,11 @@ pub struct Script<T: Asset> {
 
 static COUNTER: AtomicU32 = AtomicU32::new(0);
 
+/// Allocates a script id no script instance or other one-shot script has been given
+fn next_script_id() -> u32 {
+    COUNTER.fetch_add(1, Ordering::Relaxed)
+}
+
 impl<T: Asset> Script<T> {
     /// creates a new script instance with the given name and asset handle
     /// automatically gives this script instance a unique ID.
@@ -329,7 +592,7 @@ impl<T: Asset> Script<T> {
         Self {
             handle,
             name,
-            id: COUNTER.fetch_add(1, Ordering::Relaxed),
+            id: next_script_id(),
         }
     }
 
@@ -364,9 +627,24 @@ impl<T: Asset> Script<T> {
         debug!("reloading script {}", script.id);
         // retrieve owning entity
         let entity = contexts.script_owner(script.id()).unwrap();
+        let fd = ScriptData {
+            sid: script.id(),
+            entity,
+            name: script.name(),
+        };
 
-        // remove old context
-        contexts.remove_context(script.id());
+        // remove old context, keeping any state the script wants to carry over
+        let state = contexts
+            .context_entities
+            .remove(&script.id())
+            .and_then(|(_, ctx, _)| ctx)
+            .and_then(|mut ctx| match host.save_reload_state(&fd, &mut ctx) {
+                Ok(state) => state,
+                Err(e) => {
+                    warn! {"Error in saving the state of script {} before reloading:\n{}", &script.name, e}
+                    None
+                }
+            });
 
         // insert new re-loaded context
         Self::insert_new_script_context::<H>(
@@ -378,6 +656,14 @@ impl<T: Asset> Script<T> {
             contexts,
             event_writer,
         );
+
+        if let (Some(state), Some((_, Some(ctx), _))) =
+            (state, contexts.context_entities.get_mut(&script.id()))
+        {
+            if let Err(e) = host.load_reload_state(&fd, ctx, state) {
+                warn! {"Error in restoring the state of script {} after reloading:\n{}", &script.name, e}
+            }
+        }
     }
 
     /// checks if a script has loaded, and if so loads (`ScriptHost::load_script`),
@@ -409,7 +695,7 @@ impl<T: Asset> Script<T> {
         };
         debug!("Inserted script {:?}", fd);
 
-        match host.load_script(script.bytes(), &fd, providers) {
+        match host.load_script_asset(script, &fd, providers) {
             Ok(mut ctx) => {
                 host.setup_script(&fd, &mut ctx, providers)
                     .expect("Failed to setup script");
@@ -428,6 +714,50 @@ impl<T: Asset> Script<T> {
     }
 }
 
+/// Compiled forms of scripts (an AST, bytecode etc.) kept by script hosts which share them between instances of the same script.
+///
+/// Entries are keyed by script name and recompiled once the source of the script changes, i.e. on hot reloads.
+pub struct CompiledScriptCache<T> {
+    entries: HashMap<String, (u64, T)>,
+}
+
+impl<T> Default for CompiledScriptCache<T> {
+    fn default() -> Self {
+        Self {
+            entries: Default::default(),
+        }
+    }
+}
+
+impl<T: Clone> CompiledScriptCache<T> {
+    /// Returns the compiled form of the given script source, compiling it with the given function only if
+    /// no up to date entry exists for the script. Failed compilations are not cached.
+    pub fn get_or_compile<E, F>(&mut self, name: &str, source: &[u8], compile: F) -> Result<T, E>
+    where
+        F: FnOnce(&[u8]) -> Result<T, E>,
+    {
+        let mut hasher = DefaultHasher::new();
+        source.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        if let Some((cached_hash, compiled)) = self.entries.get(name) {
+            if *cached_hash == hash {
+                return Ok(compiled.clone());
+            }
+        }
+
+        let compiled = compile(source)?;
+        self.entries
+            .insert(name.to_owned(), (hash, compiled.clone()));
+        Ok(compiled)
+    }
+
+    /// Removes all entries, the next instance of each script is compiled again
+    pub fn clear(&mut self) {
+        self.entries.clear()
+    }
+}
+
 #[derive(Component, Debug, FromReflect, Reflect)]
 #[reflect(Component, Default)]
 /// The component storing many scripts.
@@ -445,3 +775,223 @@ impl<T: Asset> Default for ScriptCollection<T> {
         }
     }
 }
+
+impl<T: Asset> ScriptCollection<T> {
+    /// Returns the script with the given name, if it is part of this collection
+    pub fn get(&self, name: &str) -> Option<&Script<T>> {
+        self.scripts.iter().find(|s| s.name() == name)
+    }
+
+    /// Returns the script instance with the given id, if it is part of this collection
+    pub fn get_by_id(&self, id: u32) -> Option<&Script<T>> {
+        self.scripts.iter().find(|s| s.id() == id)
+    }
+
+    /// Returns true if a script with the given name is part of this collection
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Adds a script to this collection, replacing the script with the same name if there is one,
+    /// which is returned. A replaced script keeps its position in the collection.
+    ///
+    /// When called on a component of a live entity, the script host picks the change up in its
+    /// `script_add_synchronizer`, initializing the new script and tearing down the context of the replaced one.
+    pub fn add_script(&mut self, script: Script<T>) -> Option<Script<T>> {
+        match self.scripts.iter_mut().find(|s| s.name() == script.name()) {
+            Some(existing) => Some(std::mem::replace(existing, script)),
+            None => {
+                self.scripts.push(script);
+                None
+            }
+        }
+    }
+
+    /// Removes the script with the given name from this collection, returning it if it was present.
+    ///
+    /// When called on a component of a live entity, the script host tears down the script's context
+    /// in its `script_add_synchronizer`.
+    pub fn remove_script(&mut self, name: &str) -> Option<Script<T>> {
+        let idx = self.scripts.iter().position(|s| s.name() == name)?;
+        Some(self.scripts.remove(idx))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::reflect::TypeUuid;
+
+    use super::*;
+
+    fn contexts() -> ScriptContexts<()> {
+        let mut contexts = ScriptContexts::default();
+        for (sid, entity, name) in [(0, 1, "b.lua"), (1, 0, "z.lua"), (2, 1, "a.lua")] {
+            contexts.insert_context(
+                ScriptData {
+                    sid,
+                    entity: Entity::from_raw(entity),
+                    name,
+                },
+                Some(()),
+            );
+        }
+        contexts
+    }
+
+    fn order(ordering: ScriptOrdering) -> Vec<u32> {
+        contexts()
+            .ordered_contexts(ordering)
+            .map(|(data, _)| data.sid)
+            .collect()
+    }
+
+    #[test]
+    fn oneshots_get_their_own_script_ids() {
+        let first = ScriptData::oneshot();
+        let second = ScriptData::oneshot();
+        assert_ne!(first.sid, second.sid);
+    }
+
+    #[test]
+    fn component_recipients_are_resolved_against_the_world() {
+        #[derive(Component)]
+        struct Marker;
+
+        let mut world = World::new();
+        let id = world.init_component::<Marker>();
+        let marked = world.spawn(Marker).id();
+        let unmarked = world.spawn_empty().id();
+        let despawned = world.spawn(Marker).id();
+        world.despawn(despawned);
+
+        let recipients = Recipients::WithComponent(id);
+        let is_recipient = |entity| {
+            recipients.is_recipient(
+                &ScriptData {
+                    sid: 0,
+                    entity,
+                    name: "a.lua",
+                },
+                &world,
+            )
+        };
+        assert!(is_recipient(marked));
+        assert!(!is_recipient(unmarked));
+        assert!(!is_recipient(despawned));
+    }
+
+    #[test]
+    fn entity_then_name_ordering() {
+        assert_eq!(order(ScriptOrdering::EntityThenName), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn registration_ordering() {
+        assert_eq!(order(ScriptOrdering::Registration), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn entity_contexts_are_removed_together() {
+        let mut contexts = contexts();
+        contexts.remove_entity_contexts(Entity::from_raw(1));
+        assert_eq!(
+            contexts
+                .context_entities
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn scripts_with_the_same_name_are_replaced() {
+        #[derive(TypeUuid)]
+        #[uuid = "2b1cf0c3-5e0b-4d46-9a53-9a3f7fb5b6a2"]
+        struct TestAsset;
+
+        let mut collection = ScriptCollection::<TestAsset>::default();
+        let a = Script::new("a.lua".to_owned(), Handle::default());
+        let b = Script::new("b.lua".to_owned(), Handle::default());
+        let new_a = Script::new("a.lua".to_owned(), Handle::default());
+        let (a_id, b_id, new_a_id) = (a.id(), b.id(), new_a.id());
+
+        assert!(collection.add_script(a).is_none());
+        assert!(collection.add_script(b).is_none());
+        assert_eq!(collection.add_script(new_a).map(|s| s.id()), Some(a_id));
+        assert_eq!(
+            collection
+                .scripts
+                .iter()
+                .map(Script::id)
+                .collect::<Vec<_>>(),
+            vec![new_a_id, b_id]
+        );
+        assert_eq!(collection.get("a.lua").map(Script::id), Some(new_a_id));
+        assert!(collection.get_by_id(a_id).is_none());
+
+        assert_eq!(
+            collection.remove_script("b.lua").map(|s| s.id()),
+            Some(b_id)
+        );
+        assert!(collection.remove_script("b.lua").is_none());
+        assert!(!collection.contains("b.lua"));
+    }
+
+    #[test]
+    fn compiled_scripts_are_reused_until_changed() {
+        let mut cache = CompiledScriptCache::default();
+        let mut compilations = 0;
+        let mut compile = |name: &str, source: &str| {
+            cache.get_or_compile(name, source.as_bytes(), |s| {
+                compilations += 1;
+                Ok::<_, ()>(s.len())
+            })
+        };
+
+        assert_eq!(compile("a.lua", "abc"), Ok(3));
+        assert_eq!(compile("a.lua", "abc"), Ok(3));
+        assert_eq!(compile("a.lua", "abcd"), Ok(4));
+        assert_eq!(compile("b.lua", "abcd"), Ok(4));
+        drop(compile);
+        assert_eq!(compilations, 3);
+    }
+
+    #[test]
+    fn providers_follow_their_dependencies() {
+        struct A;
+        struct B;
+        struct C;
+        let (a, b, c) = (TypeId::of::<A>(), TypeId::of::<B>(), TypeId::of::<C>());
+
+        // independent providers keep the order they were added in
+        assert_eq!(
+            dependency_order(&[(b, vec![a]), (c, vec![]), (a, vec![])]),
+            Ok(vec![1, 2, 0])
+        );
+        // dependencies which aren't added yet don't hold up the others
+        assert_eq!(
+            dependency_order(&[(b, vec![c]), (a, vec![b])]),
+            Ok(vec![0, 1])
+        );
+        assert_eq!(
+            dependency_order(&[(a, vec![]), (b, vec![]), (c, vec![])]),
+            Ok(vec![0, 1, 2])
+        );
+    }
+
+    #[test]
+    fn cyclic_or_missing_provider_dependencies_fail() {
+        struct A;
+        struct B;
+        struct C;
+        let (a, b, c) = (TypeId::of::<A>(), TypeId::of::<B>(), TypeId::of::<C>());
+
+        assert_eq!(
+            dependency_order(&[(c, vec![]), (a, vec![b]), (b, vec![a])]),
+            Err(1)
+        );
+        assert_eq!(missing_dependency(&[(b, vec![]), (a, vec![c])]), Some(1));
+        assert_eq!(missing_dependency(&[(b, vec![]), (a, vec![b])]), None);
+    }
+}
@@ -1,19 +1,23 @@
 use crate::{
-    event::ScriptErrorEvent,
-    hosts::{APIProvider, APIProviders, ScriptHost},
+    event::{log_script_errors, CallbackEvent, ScriptErrorEvent},
+    hosts::{APIProvider, APIProviders, CallbackHost, ScriptHost},
 };
 use bevy::{
-    ecs::schedule::{BaseSystemSet, FreeSystemSet},
+    ecs::schedule::{BaseSystemSet, FreeSystemSet, ScheduleLabel},
     prelude::*,
 };
+use bevy_event_priority::AddPriorityEvent;
 use event::ScriptLoaded;
-use systems::script_event_handler;
+use std::path::Path;
+use systems::{script_callback_handler, script_event_handler, CachedCallbackState};
 
 pub mod asset;
 pub mod docs;
 pub mod error;
 pub mod event;
 pub mod hosts;
+pub mod limits;
+pub mod modules;
 pub mod systems;
 pub mod world;
 pub mod prelude {
@@ -22,15 +26,20 @@ pub mod prelude {
         crate::asset::CodeAsset,
         crate::docs::DocFragment,
         crate::error::ScriptError,
-        crate::event::{ScriptErrorEvent, ScriptEvent},
+        crate::event::{
+            CallbackEvent, CallbackResponse, ScriptErrorEvent, ScriptEvent, ScriptResponse,
+        },
         crate::hosts::{
-            APIProvider, APIProviders, Recipients, Script, ScriptCollection, ScriptContexts,
-            ScriptData, ScriptHost,
+            APIProvider, APIProviders, CallbackHost, CompiledScriptCache, EvalHost, Recipients,
+            ReloadState, Script, ScriptCollection, ScriptContexts, ScriptData, ScriptHost,
+            ScriptOrdering,
         },
+        crate::limits::ScriptLimits,
+        crate::modules::ScriptModules,
         crate::systems::script_event_handler,
         crate::{
-            AddScriptApiProvider, AddScriptHost, AddScriptHostHandler, GenDocumentation,
-            ScriptingPlugin,
+            AddScriptApiProvider, AddScriptCallbackHandler, AddScriptHost, AddScriptHostHandler,
+            GenDocumentation, ScriptingPlugin,
         },
         bevy_event_priority::{
             AddPriorityEvent, PriorityEvent, PriorityEventReader, PriorityEventWriter,
@@ -46,12 +55,18 @@ pub struct ScriptingPlugin;
 
 impl Plugin for ScriptingPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_event::<ScriptErrorEvent>();
+        app.add_event::<ScriptErrorEvent>()
+            .add_system(log_script_errors.in_base_set(CoreSet::Last));
     }
 }
 
 pub trait GenDocumentation {
     fn update_documentation<T: ScriptHost>(&mut self) -> &mut Self;
+    fn export_documentation<T: ScriptHost, P: AsRef<Path>>(&mut self, path: P) -> &mut Self;
+    fn add_doc_fragment<T: ScriptHost>(
+        &mut self,
+        fragment: impl Fn() -> T::DocTarget + Send + Sync + 'static,
+    ) -> &mut Self;
 }
 
 impl GenDocumentation for App {
@@ -70,6 +85,33 @@ impl GenDocumentation for App {
 
         self
     }
+
+    /// Renders the complete documentation of the script API into a single file at the given path,
+    /// for example a `.d.tl` declaration file for lua. Unlike `update_documentation` this runs in all builds.
+    fn export_documentation<T: ScriptHost, P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        let path = path.as_ref();
+        let providers: &APIProviders<T> = self.world.resource();
+        match providers.export_all(path) {
+            Ok(_) => info!("Documentation exported to `{}`", path.display()),
+            Err(e) => error!("{}", e),
+        }
+
+        self
+    }
+
+    /// Adds a hand written documentation fragment, i.e. for an API attached without an `APIProvider`,
+    /// to the documentation produced by `update_documentation` and `export_documentation`.
+    ///
+    /// Fragments added this way are merged after those of the providers, in the order they were added.
+    /// This needs to be placed after the `add_script_host_to_base_set` call of the host and before `update_documentation`.
+    fn add_doc_fragment<T: ScriptHost>(
+        &mut self,
+        fragment: impl Fn() -> T::DocTarget + Send + Sync + 'static,
+    ) -> &mut Self {
+        let providers: &mut APIProviders<T> = &mut self.world.resource_mut();
+        providers.doc_fragments.push(Box::new(fragment));
+        self
+    }
 }
 
 /// Trait for app builder notation
@@ -90,6 +132,18 @@ pub trait AddScriptHost {
         &mut self,
         set: S,
     ) -> &mut Self;
+    /// registers the given script host with your app,
+    /// the given set of the given schedule will contain systems handling script loading,re-loading, removal etc.
+    /// Order the set against your own systems with `configure_set` in that schedule.
+    fn add_script_host_to_schedule<
+        T: ScriptHost,
+        L: ScheduleLabel + Clone,
+        S: FreeSystemSet + Clone,
+    >(
+        &mut self,
+        schedule: L,
+        set: S,
+    ) -> &mut Self;
 }
 
 impl AddScriptHost for App {
@@ -113,9 +167,23 @@ impl AddScriptHost for App {
         self.add_event::<ScriptLoaded>();
         self
     }
+    fn add_script_host_to_schedule<T, L, S>(&mut self, schedule: L, set: S) -> &mut Self
+    where
+        T: ScriptHost,
+        L: ScheduleLabel + Clone,
+        S: FreeSystemSet + Clone,
+    {
+        T::register_with_app_in_schedule(self, schedule, set);
+        self.init_resource::<T>();
+        self.add_event::<ScriptLoaded>();
+        self
+    }
 }
 
 pub trait AddScriptApiProvider {
+    /// Adds an API provider to the given host, providers are kept sorted so that each comes after its dependencies.
+    ///
+    /// Panics if the dependencies of the providers added so far are cyclic.
     fn add_api_provider<T: ScriptHost>(
         &mut self,
         provider: Box<
@@ -143,6 +211,9 @@ impl AddScriptApiProvider for App {
         let w = &mut self.world;
         let providers: &mut APIProviders<T> = &mut w.resource_mut();
         providers.providers.push(provider);
+        if let Err(e) = providers.sort_by_dependencies() {
+            panic!("{e}");
+        }
         self
     }
 }
@@ -185,7 +256,20 @@ pub trait AddScriptHostHandler {
         set: S,
     ) -> &mut Self;
 
-    /// Like `add_script_handler_stage` but with additional run criteria
+    /// Like `add_script_handler_to_set` but the handler runs in the given set of the given schedule
+    fn add_script_handler_to_schedule<
+        T: ScriptHost,
+        L: ScheduleLabel,
+        S: FreeSystemSet,
+        const MAX: u32,
+        const MIN: u32,
+    >(
+        &mut self,
+        schedule: L,
+        set: S,
+    ) -> &mut Self;
+
+    /// Like `add_script_handler_to_set` but with additional run criteria
     fn add_script_handler_to_set_with_criteria<
         T: ScriptHost,
         S: FreeSystemSet,
@@ -199,7 +283,7 @@ pub trait AddScriptHostHandler {
         condition: C,
     ) -> &mut Self;
 
-    /// Like `add_script_handler_stage` but with additional run criteria
+    /// Like `add_script_handler_to_base_set` but with additional run criteria
     fn add_script_handler_to_base_set_with_criteria<
         T: ScriptHost,
         S: BaseSystemSet,
@@ -240,6 +324,25 @@ impl AddScriptHostHandler for App {
         self
     }
 
+    fn add_script_handler_to_schedule<
+        T: ScriptHost,
+        L: ScheduleLabel,
+        S: FreeSystemSet,
+        const MAX: u32,
+        const MIN: u32,
+    >(
+        &mut self,
+        schedule: L,
+        set: S,
+    ) -> &mut Self {
+        self.add_system(
+            script_event_handler::<T, MAX, MIN>
+                .in_set(set)
+                .in_schedule(schedule),
+        );
+        self
+    }
+
     fn add_script_handler_to_set_with_criteria<
         T: ScriptHost,
         S: FreeSystemSet,
@@ -279,3 +382,183 @@ impl AddScriptHostHandler for App {
         self
     }
 }
+
+pub trait AddScriptCallbackHandler {
+    /// Enables this script host to handle [`CallbackEvent`](event::CallbackEvent)s with the given inputs and outputs
+    /// and priorities in the range [max,min] (inclusive), during the runtime of the given set.
+    /// Senders can read the returned values from the event's response once this handler has run.
+    fn add_callback_handler_to_set<
+        T: CallbackHost<In, Out>,
+        In: Send + Sync + Clone + 'static,
+        Out: Send + 'static,
+        S: FreeSystemSet,
+        const MAX: u32,
+        const MIN: u32,
+    >(
+        &mut self,
+        set: S,
+    ) -> &mut Self;
+
+    /// Like `add_callback_handler_to_set` but for base sets
+    fn add_callback_handler_to_base_set<
+        T: CallbackHost<In, Out>,
+        In: Send + Sync + Clone + 'static,
+        Out: Send + 'static,
+        S: BaseSystemSet,
+        const MAX: u32,
+        const MIN: u32,
+    >(
+        &mut self,
+        set: S,
+    ) -> &mut Self;
+}
+
+impl AddScriptCallbackHandler for App {
+    fn add_callback_handler_to_set<
+        T: CallbackHost<In, Out>,
+        In: Send + Sync + Clone + 'static,
+        Out: Send + 'static,
+        S: FreeSystemSet,
+        const MAX: u32,
+        const MIN: u32,
+    >(
+        &mut self,
+        set: S,
+    ) -> &mut Self {
+        self.add_priority_event::<CallbackEvent<In, Out>>()
+            .init_resource::<CachedCallbackState<In, Out>>()
+            .add_system(script_callback_handler::<T, In, Out, MAX, MIN>.in_set(set));
+        self
+    }
+
+    fn add_callback_handler_to_base_set<
+        T: CallbackHost<In, Out>,
+        In: Send + Sync + Clone + 'static,
+        Out: Send + 'static,
+        S: BaseSystemSet,
+        const MAX: u32,
+        const MIN: u32,
+    >(
+        &mut self,
+        set: S,
+    ) -> &mut Self {
+        self.add_priority_event::<CallbackEvent<In, Out>>()
+            .init_resource::<CachedCallbackState<In, Out>>()
+            .add_system(script_callback_handler::<T, In, Out, MAX, MIN>.in_base_set(set));
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::reflect::TypeUuid;
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::prelude::*;
+
+    /// The names of the fragments of the last generated documentation
+    static GENERATED: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+    struct Fragment(Vec<&'static str>);
+
+    impl DocFragment for Fragment {
+        fn merge(mut self, o: Self) -> Self {
+            self.0.extend(o.0);
+            self
+        }
+
+        fn gen_docs(self) -> Result<(), ScriptError> {
+            *GENERATED.lock() = self.0;
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            self.0[0]
+        }
+    }
+
+    #[derive(TypeUuid)]
+    #[uuid = "6f3b7c1e-0d4a-4c52-9a4e-3d1b2f8e7a10"]
+    struct Source;
+
+    impl CodeAsset for Source {
+        fn bytes(&self) -> &[u8] {
+            &[]
+        }
+    }
+
+    #[derive(Clone)]
+    struct Event(Recipients);
+
+    impl ScriptEvent for Event {
+        fn recipients(&self) -> &Recipients {
+            &self.0
+        }
+    }
+
+    #[derive(Default, Resource)]
+    struct Host;
+
+    impl ScriptHost for Host {
+        type ScriptContext = ();
+        type ScriptEvent = Event;
+        type ScriptAsset = Source;
+        type APITarget = ();
+        type DocTarget = Fragment;
+
+        fn load_script(
+            &mut self,
+            _: &[u8],
+            _: &ScriptData,
+            _: &mut APIProviders<Self>,
+        ) -> Result<(), ScriptError> {
+            Ok(())
+        }
+
+        fn setup_script(
+            &mut self,
+            _: &ScriptData,
+            _: &mut (),
+            _: &mut APIProviders<Self>,
+        ) -> Result<(), ScriptError> {
+            Ok(())
+        }
+
+        fn handle_events<'a>(
+            &self,
+            _: &mut World,
+            _: &[Event],
+            _: impl Iterator<Item = (ScriptData<'a>, &'a mut ())>,
+            _: &mut APIProviders<Self>,
+        ) {
+        }
+
+        fn register_with_app_in_set(app: &mut App, _: impl FreeSystemSet + Clone) {
+            app.init_resource::<APIProviders<Self>>();
+        }
+
+        fn register_with_app_in_base_set(app: &mut App, _: impl BaseSystemSet + Clone) {
+            app.init_resource::<APIProviders<Self>>();
+        }
+
+        fn register_with_app_in_schedule(
+            app: &mut App,
+            _: impl ScheduleLabel + Clone,
+            _: impl FreeSystemSet + Clone,
+        ) {
+            app.init_resource::<APIProviders<Self>>();
+        }
+    }
+
+    #[test]
+    fn doc_fragments_are_merged_in_the_order_they_were_added() {
+        App::new()
+            .add_script_host_to_base_set::<Host, _>(CoreSet::PostUpdate)
+            .add_doc_fragment::<Host>(|| Fragment(vec!["game"]))
+            .add_doc_fragment::<Host>(|| Fragment(vec!["ui", "input"]))
+            .update_documentation::<Host>();
+
+        assert_eq!(*GENERATED.lock(), ["game", "ui", "input"]);
+    }
+}
@@ -0,0 +1,70 @@
+//! Resource limits applied by script hosts to every script they run.
+//!
+//! Limits are set once on the host resource and apply to every script context it creates, a script exceeding them
+//! is interrupted with a [`ScriptError::LimitExceeded`] which is reported like any other runtime error, leaving the
+//! script loaded and able to handle later events.
+use crate::error::ScriptError;
+
+/// The resources each script of a host may use, `None` leaves a resource unlimited.
+///
+/// Not every language can enforce every limit, each field documents the hosts it applies to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScriptLimits {
+    /// The number of instructions a single call into a script may execute, counting every callback, event handler,
+    /// reload hook and the top level of the script while it is loaded separately.
+    ///
+    /// Lua counts virtual machine instructions in steps of [`ScriptLimits::INSTRUCTION_STEP`], Rhai counts operations.
+    pub max_instructions: Option<u64>,
+    /// The memory in bytes a single script context may allocate in total.
+    ///
+    /// Only enforced by Lua, Rhai doesn't track the memory used by scripts.
+    pub max_memory: Option<usize>,
+    /// The length in bytes of any string a script creates.
+    ///
+    /// Only enforced by Rhai.
+    pub max_string_size: Option<usize>,
+    /// The number of elements in any array or map a script creates.
+    ///
+    /// Only enforced by Rhai.
+    pub max_collection_size: Option<usize>,
+}
+
+impl ScriptLimits {
+    /// The granularity at which hosts which can't count single instructions check the instruction limit
+    pub const INSTRUCTION_STEP: u64 = 1000;
+
+    /// Returns true if no limit is set
+    pub fn is_unlimited(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// The error raised once the given script exceeds the given limit, i.e. `"1000 instructions"`
+    pub fn exceeded<T: ToString>(script: &str, limit: T) -> ScriptError {
+        ScriptError::LimitExceeded {
+            script: script.to_owned(),
+            limit: limit.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn exceeded_limits_name_the_script() {
+        assert!(ScriptLimits::default().is_unlimited());
+        assert!(!ScriptLimits {
+            max_memory: Some(1024),
+            ..Default::default()
+        }
+        .is_unlimited());
+
+        let error = ScriptLimits::exceeded("game.lua", "1000 instructions");
+        assert_eq!(error.script(), Some("game.lua"));
+        assert_eq!(
+            error.to_string(),
+            "Script `game.lua` exceeded its limit of 1000 instructions"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! Modules scripts import from other files, i.e. through lua's `require` or rhai's `import`.
+//!
+//! Modules are script assets like any other, addressed by their asset path. Since scripts are loaded synchronously,
+//! a module must already be loaded when a script imports it. Importing a module which isn't loaded yet fails and
+//! requests the module from the asset server, the importing script is then loaded again as soon as the module is.
+//! Likewise a hot reload of a module reloads every script importing it, directly or through other modules.
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use parking_lot::Mutex;
+
+use crate::error::ScriptError;
+
+#[derive(Default)]
+struct ModuleState {
+    /// the source of every loaded module, by asset path
+    sources: HashMap<String, Arc<[u8]>>,
+    /// modules imported before they were loaded, yet to be requested from the asset server
+    requested: HashSet<String>,
+    /// the scripts importing each module, directly or through other modules
+    importers: HashMap<String, HashSet<String>>,
+    /// the script being loaded followed by the modules it is currently importing, innermost last
+    loading: Vec<String>,
+    /// incremented whenever the source of a module is set
+    generation: u64,
+}
+
+/// The modules imported by the scripts of a script host.
+///
+/// This is shared between the host and the module resolvers it installs in its script engines, clones refer to the same modules.
+#[derive(Clone, Default)]
+pub struct ScriptModules(Arc<Mutex<ModuleState>>);
+
+impl ScriptModules {
+    /// Runs `load` while the given script is being loaded, modules imported meanwhile are recorded as imports of the script
+    pub fn load_script<R>(&self, script: &str, load: impl FnOnce() -> R) -> R {
+        let mut state = self.0.lock();
+        let outer = std::mem::replace(&mut state.loading, vec![script.to_owned()]);
+        drop(state);
+
+        let out = load();
+
+        self.0.lock().loading = outer;
+        out
+    }
+
+    /// Runs `import` with the source of the module at the given asset path, while the module is being loaded.
+    ///
+    /// Fails if the module is not loaded yet, or is already being loaded further up the chain of imports.
+    pub fn import<R>(&self, path: &str, import: impl FnOnce(&[u8]) -> R) -> Result<R, ScriptError> {
+        let mut state = self.0.lock();
+
+        if let Some(start) = state.loading.iter().position(|p| p == path) {
+            let mut chain = state.loading[start..].to_vec();
+            chain.push(path.to_owned());
+            return Err(ScriptError::new_module_error(
+                path,
+                format!("is imported circularly: {}", chain.join(" -> ")),
+            ));
+        }
+
+        let script = state.loading.first().cloned();
+        let importers = state.importers.entry(path.to_owned()).or_default();
+        importers.extend(script);
+
+        let source = match state.sources.get(path).cloned() {
+            Some(source) => source,
+            None => {
+                state.requested.insert(path.to_owned());
+                return Err(ScriptError::new_module_error(
+                    path,
+                    "is not loaded yet, the importing script is loaded again once it is",
+                ));
+            }
+        };
+
+        state.loading.push(path.to_owned());
+        drop(state);
+
+        let out = import(&source);
+
+        self.0.lock().loading.pop();
+        Ok(out)
+    }
+
+    /// Returns true if the given asset path was ever imported as a module
+    pub fn is_module(&self, path: &str) -> bool {
+        self.0.lock().importers.contains_key(path)
+    }
+
+    /// Sets the source of the module at the given asset path, returning the names of the scripts importing it
+    pub fn update_source(&self, path: &str, source: &[u8]) -> Vec<String> {
+        let mut state = self.0.lock();
+        state.sources.insert(path.to_owned(), source.into());
+        state.generation += 1;
+        state
+            .importers
+            .get(path)
+            .map(|scripts| scripts.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// A counter incremented whenever the source of a module is set,
+    /// hosts which compile modules into the scripts importing them use this to tell when compiled scripts are outdated
+    pub fn generation(&self) -> u64 {
+        self.0.lock().generation
+    }
+
+    /// Returns the modules imported since the last call which are not loaded yet
+    pub fn take_requested(&self) -> Vec<String> {
+        self.0.lock().requested.drain().collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn missing_modules_are_requested() {
+        let modules = ScriptModules::default();
+
+        let out = modules.load_script("game.lua", || modules.import("util.lua", |_| ()));
+        assert!(matches!(out, Err(ScriptError::ModuleError { .. })));
+        assert_eq!(modules.take_requested(), vec!["util.lua".to_owned()]);
+        assert!(modules.take_requested().is_empty());
+
+        assert_eq!(
+            modules.update_source("util.lua", b"return 1"),
+            vec!["game.lua".to_owned()]
+        );
+        let out = modules.load_script("game.lua", || {
+            modules.import("util.lua", |source| source.to_vec())
+        });
+        assert_eq!(out.unwrap(), b"return 1".to_vec());
+    }
+
+    #[test]
+    fn nested_imports_are_attributed_to_the_script() {
+        let modules = ScriptModules::default();
+        modules.update_source("a.lua", b"");
+        modules.update_source("b.lua", b"");
+
+        modules
+            .load_script("game.lua", || {
+                modules.import("a.lua", |_| modules.import("b.lua", |_| ()))
+            })
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            modules.update_source("b.lua", b""),
+            vec!["game.lua".to_owned()]
+        );
+    }
+
+    #[test]
+    fn circular_imports_fail() {
+        let modules = ScriptModules::default();
+        modules.update_source("a.lua", b"");
+        modules.update_source("b.lua", b"");
+
+        let out = modules.load_script("game.lua", || {
+            modules.import("a.lua", |_| {
+                modules.import("b.lua", |_| modules.import("a.lua", |_| ()))
+            })
+        });
+
+        match out.unwrap().unwrap() {
+            Err(ScriptError::ModuleError { module, msg }) => {
+                assert_eq!(module, "a.lua");
+                assert!(msg.contains("a.lua -> b.lua -> a.lua"), "{msg}");
+            }
+            other => panic!("expected a circular import error, got {other:?}"),
+        }
+    }
+}
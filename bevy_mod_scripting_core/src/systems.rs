@@ -3,15 +3,18 @@ use std::collections::HashSet;
 use bevy::{
     ecs::system::SystemState,
     prelude::{
-        debug, AssetEvent, Assets, ChangeTrackers, Changed, Entity, EventReader, EventWriter,
-        FromWorld, Query, RemovedComponents, Res, ResMut, Resource, SystemSet, World,
+        debug, AssetEvent, AssetServer, Assets, ChangeTrackers, Changed, Entity, EventReader,
+        EventWriter, FromWorld, Handle, Local, Query, RemovedComponents, Res, ResMut, Resource,
+        SystemSet, World,
     },
 };
 use bevy_event_priority::PriorityEventReader;
 
 use crate::{
-    event::ScriptLoaded,
-    prelude::{APIProviders, Script, ScriptCollection, ScriptContexts, ScriptData, ScriptHost},
+    asset::CodeAsset,
+    event::{CallbackEvent, ScriptLoaded},
+    hosts::{CallbackHost, ScriptOrdering},
+    prelude::{APIProviders, Script, ScriptCollection, ScriptContexts, ScriptHost},
     ScriptErrorEvent,
 };
 
@@ -104,16 +107,21 @@ pub fn script_remove_synchronizer<H: ScriptHost>(
     query.iter().for_each(|v| {
         // we know that this entity used to have a script component
         // ergo a script context must exist in ctxts, remove all scripts on the entity
-        contexts.remove_context(v.index());
+        contexts.remove_entity_contexts(v);
     })
 }
 
-/// Reloads hot-reloaded scripts, or loads missing contexts for scripts which were added but not loaded
+/// Reloads hot-reloaded scripts, or loads missing contexts for scripts which were added but not loaded.
+///
+/// Scripts importing a module are reloaded along with it, and modules imported before they were loaded are requested from the asset server.
+#[allow(clippy::too_many_arguments)]
 pub fn script_hot_reload_handler<H: ScriptHost>(
     mut events: EventReader<AssetEvent<H::ScriptAsset>>,
     mut host: ResMut<H>,
     scripts: Query<&ScriptCollection<H::ScriptAsset>>,
     script_assets: Res<Assets<H::ScriptAsset>>,
+    asset_server: Res<AssetServer>,
+    mut module_handles: Local<Vec<Handle<H::ScriptAsset>>>,
     mut providers: ResMut<APIProviders<H>>,
     mut contexts: ResMut<ScriptContexts<H::ScriptContext>>,
     mut event_writer: EventWriter<ScriptLoaded>,
@@ -125,6 +133,20 @@ pub fn script_hot_reload_handler<H: ScriptHost>(
             _ => continue,
         };
 
+        // scripts importing this asset as a module need to be reloaded too
+        let importers = match (host.modules(), asset_server.get_handle_path(handle)) {
+            (Some(modules), Some(path)) => {
+                let path = path.path().to_string_lossy();
+                match script_assets.get(handle) {
+                    Some(module) if modules.is_module(&path) => {
+                        modules.update_source(&path, module.bytes())
+                    }
+                    _ => Vec::default(),
+                }
+            }
+            _ => Vec::default(),
+        };
+
         // find script using this handle by handle id
         // whether this script was modified or created
         // if a script exists with this handle, we should reload it to load in a new context
@@ -134,7 +156,9 @@ pub fn script_hot_reload_handler<H: ScriptHost>(
             for script in &scripts.scripts {
                 // the script could have well loaded in the same frame that it was added
                 // in that case it will have a context attached and we do not want to reload it
-                if script.handle() == handle && !(contexts.has_context(script.id()) && created) {
+                let modified =
+                    script.handle() == handle && !(contexts.has_context(script.id()) && created);
+                if modified || importers.iter().any(|name| name == script.name()) {
                     Script::<H::ScriptAsset>::reload_script::<H>(
                         &mut host,
                         script,
@@ -147,6 +171,13 @@ pub fn script_hot_reload_handler<H: ScriptHost>(
             }
         }
     }
+
+    if let Some(modules) = host.modules() {
+        for path in modules.take_requested() {
+            debug!("Loading module {}", path);
+            module_handles.push(asset_server.load(path));
+        }
+    }
 }
 
 /// Lets the script host handle all script events
@@ -177,24 +208,11 @@ pub fn script_event_handler<H: ScriptHost, const MAX: u32, const MIN: u32>(world
     // we need a resource scope to be able to simultaneously access the contexts as well
     // as provide world access to scripts
     // afaik there is not really a better way to do this in bevy just now
-    let ctx_iter = ctxts
-        .context_entities
-        .iter_mut()
-        .filter_map(|(sid, (entity, o, name))| {
-            let ctx = match o {
-                Some(v) => v,
-                None => return None,
-            };
-
-            Some((
-                ScriptData {
-                    sid: *sid,
-                    entity: *entity,
-                    name,
-                },
-                ctx,
-            ))
-        });
+    let ordering = world
+        .get_resource::<ScriptOrdering>()
+        .copied()
+        .unwrap_or_default();
+    let ctx_iter = ctxts.ordered_contexts(ordering);
 
     // safety: we have unique access to world, future accesses are protected
     // by the lock in the pointer
@@ -222,3 +240,56 @@ impl<H: ScriptHost> FromWorld for CachedScriptState<H> {
         }
     }
 }
+
+/// Lets the script host handle all callback events with the given inputs and outputs
+pub fn script_callback_handler<H, In, Out, const MAX: u32, const MIN: u32>(world: &mut World)
+where
+    H: CallbackHost<In, Out>,
+    In: Send + Sync + Clone + 'static,
+    Out: Send + 'static,
+{
+    let mut state: CachedCallbackState<In, Out> = world.remove_resource().unwrap();
+
+    let events = state
+        .event_state
+        .get_mut(world)
+        .iter_prio_range(MAX, MIN)
+        .collect::<Vec<CallbackEvent<In, Out>>>();
+
+    world.insert_resource(state);
+
+    if events.is_empty() {
+        return;
+    }
+
+    let mut ctxts: ScriptContexts<H::ScriptContext> = world.remove_resource().unwrap();
+
+    let host: H = world.remove_resource().unwrap();
+    let mut providers: APIProviders<H> = world.remove_resource().unwrap();
+
+    let ordering = world
+        .get_resource::<ScriptOrdering>()
+        .copied()
+        .unwrap_or_default();
+    let ctx_iter = ctxts.ordered_contexts(ordering);
+
+    host.handle_callbacks(world, &events, ctx_iter, &mut providers);
+
+    world.insert_resource(ctxts);
+    world.insert_resource(host);
+    world.insert_resource(providers);
+}
+
+#[derive(Resource)]
+/// system state for exclusive systems dealing with callback events
+pub struct CachedCallbackState<In: Send + Sync + 'static, Out: Send + 'static> {
+    pub event_state: SystemState<PriorityEventReader<'static, 'static, CallbackEvent<In, Out>>>,
+}
+
+impl<In: Send + Sync + 'static, Out: Send + 'static> FromWorld for CachedCallbackState<In, Out> {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            event_state: SystemState::new(world),
+        }
+    }
+}
@@ -29,7 +29,33 @@ use syn::{parse::Parse, parse_macro_input, spanned::Spanned, Attribute};
 /// - other wrapper types generated by this macro surrounded in `Wrapper()`
 /// - Both mutable and immutable references to any of the above (apart from on fields)
 /// - the self type and receiver (self, &self or &mut self), if used in method must be followed by `:` to differentiate it from other self arguments  
-/// Currently more complex types like: Option<T> and LuaWrapper<T> are not yet supported (although they have Proxy implementations which can be manually implemented).
+/// - `Option<T>` and `Vec<T>` of any of the above (without references) inside `Raw()` or `Wrapper()`, i.e. `Wrapped(Option<Vec3>)`.
+///   Vectors of primitives are returned as vec proxies, vectors of wrapper types as tables
+/// - `Result<T>` as a return type, where `T` is any of the above (without references) and the error type is left out, i.e. `Wrapped(Result<Vec3>)`.
+///   The error type must implement `Debug`, errors are raised as script errors
+/// - `&[T]` as an argument, where `T` is any of the above (without references), i.e. `Raw(&[u32])`. Scripts pass in arrays
+/// - `&T` as a return type, where `T` is a primitive or a `Clone` wrapper type, the referenced value is cloned
+/// - `&str`, `&String` and `Cow<str>` inside `Raw()`, i.e. `Raw(&str)`. Script strings are borrowed as `&str` without being copied,
+///   owned strings are only made for methods expecting them. `&str` return values are returned as owned strings
+///
+/// Fields and methods can be exposed under a different name with `#[rename("new_name")]`, or in some languages only with i.e. `#[rename(lua = "end_")]`,
+/// fields marked `#[readonly]` only get a getter.
+/// Methods marked `#[qualified(Trait)]` are called as `<Type as Trait>::method`, for trait methods sharing a name with other methods.
+/// Generic methods marked `#[type_args(T, U)]` are called as `method::<T, U>`, the types of the arguments are given with `T` and `U` substituted.
+///
+/// The `PartialEq` and `PartialOrd` flags expose `==`, `<` and `<=` between two values of the type.
+/// Values are converted to strings with `Display` if the `Display` flag is given, otherwise with `Debug` if the `Debug` flag is given.
+/// Types with neither can be given the `DefaultToString` flag to convert to their type name in angle brackets, i.e. `<Handle>`.
+/// Types implementing `ScriptSequence` can be given the `Sequence` flag alongside `Clone` to also convert from sequences of their elements,
+/// i.e. `{1, 2, 3}` in lua, and back with a `to_array` method.
+/// The `Index(Raw(usize) -> Raw(f32))` flag exposes `[]` through the type's `Index` impl with the given key and output,
+/// prefixing the key with `mut`, i.e. `Index(mut Raw(usize) -> Raw(f32))`, also allows assigning through `IndexMut`. Only one key type is supported.
+/// The type must implement `ScriptIndex` for the key, which checks keys before indexing, sequences are indexed with `usize` keys.
+///
+/// The `#[languages(..)]` attribute selects the languages to implement the type for, `lua` invokes `impl_lua_newtype!`, `rhai` invokes `impl_rhai_newtype!` and so on.
+/// Languages listed inside `on_feature(..)`, i.e. `#[languages(on_feature(lua, rhai))]`, are only implemented when the feature of the same name is enabled.
+///
+/// Currently more complex types like: LuaWrapper<T> are not yet supported (although they have Proxy implementations which can be manually implemented).
 ///  
 /// # Example
 /// ```rust,ignore
@@ -72,46 +98,24 @@ pub fn impl_script_newtype(input: TokenStream) -> TokenStream {
 
             // now create an invocation per language specified
             for language in &list.nested {
-                let mut feature_gate = false;
-                let mut inner_language = None;
-                if let syn::NestedMeta::Meta(syn::Meta::List(sub_list)) = language {
-                    if sub_list.path.is_ident("on_feature") {
-                        if let Some(syn::NestedMeta::Meta(syn::Meta::Path(path))) =
-                            sub_list.nested.first()
-                        {
-                            if let Some(ident) = path.get_ident() {
-                                inner_language = Some(ident);
-                                feature_gate = true;
-                            }
+                let languages = match parse_language(language) {
+                    Ok(v) => v,
+                    Err(e) => return e.to_compile_error().into(),
+                };
+
+                for (inner_language, feature_gate) in languages {
+                    let lang_str = inner_language.to_string();
+                    let macro_ident = format_ident!("impl_{}_newtype", inner_language);
+                    let inner = invocation.inner.clone();
+                    let feature_gate =
+                        feature_gate.then_some(quote::quote!(#[cfg(feature=#lang_str)]));
+                    output.extend(quote_spanned! {language.span()=>
+                        #feature_gate
+                        #macro_ident!{
+                            #inner
                         }
-                    }
-                } else if let syn::NestedMeta::Meta(syn::Meta::Path(path)) = language {
-                    if let Some(ident) = path.get_ident() {
-                        inner_language = Some(ident)
-                    }
+                    });
                 }
-
-                let inner_language =
-                    match inner_language {
-                        Some(v) => v,
-                        None => return syn::Error::new_spanned(
-                            language,
-                            "Expected `on_feature(x)` or `x` attribute where x is a valid language",
-                        )
-                        .to_compile_error()
-                        .into(),
-                    };
-
-                let lang_str = inner_language.to_string();
-                let macro_ident = format_ident!("impl_{}_newtype", inner_language);
-                let inner = invocation.inner.clone();
-                let feature_gate = feature_gate.then_some(quote::quote!(#[cfg(feature=#lang_str)]));
-                output.extend(quote_spanned! {language.span()=>
-                    #feature_gate
-                    #macro_ident!{
-                        #inner
-                    }
-                });
             }
         }
         _ => {
@@ -127,6 +131,40 @@ pub fn impl_script_newtype(input: TokenStream) -> TokenStream {
     output.into()
 }
 
+/// Parses a single entry of the `languages` attribute, either `x` or `on_feature(x,y,..)`,
+/// into the languages it selects and whether they are gated on their features
+fn parse_language(language: &syn::NestedMeta) -> syn::Result<Vec<(&syn::Ident, bool)>> {
+    let error = || {
+        syn::Error::new_spanned(
+            language,
+            "Expected `on_feature(x,..)` or `x` attribute where x is a valid language",
+        )
+    };
+
+    match language {
+        syn::NestedMeta::Meta(syn::Meta::List(sub_list))
+            if sub_list.path.is_ident("on_feature") && !sub_list.nested.is_empty() =>
+        {
+            sub_list
+                .nested
+                .iter()
+                .map(|nested| match nested {
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) => path
+                        .get_ident()
+                        .map(|ident| (ident, true))
+                        .ok_or_else(error),
+                    _ => Err(error()),
+                })
+                .collect()
+        }
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) => path
+            .get_ident()
+            .map(|ident| vec![(ident, false)])
+            .ok_or_else(error),
+        _ => Err(error()),
+    }
+}
+
 pub(crate) struct MacroInvocation {
     pub languages: Attribute,
     pub inner: proc_macro2::TokenStream,
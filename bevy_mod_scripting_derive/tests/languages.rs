@@ -0,0 +1,5 @@
+#[test]
+fn languages_attribute() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/languages_*.rs");
+}
@@ -0,0 +1,31 @@
+use bevy_mod_scripting_derive::impl_script_newtype;
+
+// neither feature is enabled, so none of these may be invoked
+macro_rules! impl_lua_newtype {
+    ($($tt:tt)*) => {
+        compile_error!("lua implementor invoked without the lua feature");
+    };
+}
+
+macro_rules! impl_rhai_newtype {
+    ($($tt:tt)*) => {
+        compile_error!("rhai implementor invoked without the rhai feature");
+    };
+}
+
+impl_script_newtype! {
+    #[languages(on_feature(lua))]
+    MyStruct : Fields()
+}
+
+impl_script_newtype! {
+    #[languages(on_feature(rhai))]
+    MyStruct : Fields()
+}
+
+impl_script_newtype! {
+    #[languages(on_feature(lua, rhai))]
+    MyStruct : Fields()
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+use bevy_mod_scripting_derive::impl_script_newtype;
+
+// stand-ins for the language implementors, each defines a marker type named after the language
+macro_rules! impl_lua_newtype {
+    ($($tt:tt)*) => {
+        struct LuaImplemented;
+    };
+}
+
+macro_rules! impl_rhai_newtype {
+    ($($tt:tt)*) => {
+        struct RhaiImplemented;
+    };
+}
+
+impl_script_newtype! {
+    #[languages(lua, rhai)]
+    MyStruct : Fields()
+}
+
+fn main() {
+    let _ = LuaImplemented;
+    let _ = RhaiImplemented;
+}
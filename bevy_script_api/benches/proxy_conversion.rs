@@ -0,0 +1,100 @@
+//! Measures the conversion of reflected values to and from script values, which happens on every field access from a script.
+//!
+//! Primitive fields take the scalar fast path, the `String` field goes through the type registry and proxies and serves as the baseline.
+//! Run with i.e. `cargo bench -p bevy_script_api --features lua,rhai,bevy_mod_scripting_lua/lua54`
+use bevy::prelude::*;
+use bevy_mod_scripting_core::world::WorldPointer;
+use bevy_mod_scripting_lua::tealr::mlu::mlua::{Lua, ToLua};
+use bevy_mod_scripting_rhai::rhai::{Engine, Scope};
+use bevy_script_api::{
+    lua::{bevy::LuaWorld, RegisterForeignLuaType},
+    rhai::{RegisterForeignRhaiType, ToDynamic},
+    ReflectedValue, ScriptRef,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+#[derive(Reflect, Default)]
+struct Fields {
+    float: f32,
+    int: i32,
+    string: String,
+}
+
+fn setup() -> World {
+    let mut app = App::new();
+    app.register_type::<Fields>()
+        .register_foreign_lua_type::<f32>()
+        .register_foreign_lua_type::<i32>()
+        .register_foreign_lua_type::<String>()
+        .register_foreign_rhai_type::<f32>()
+        .register_foreign_rhai_type::<i32>()
+        .register_foreign_rhai_type::<String>();
+    std::mem::take(&mut app.world)
+}
+
+fn lua_conversions(c: &mut Criterion) {
+    let mut world = setup();
+    // safety: the world outlives every reference to it, all of which are dropped at the end of this function
+    let world_ptr = unsafe { WorldPointer::new(&mut world) };
+    let fields = ReflectedValue::new_owned(Box::<Fields>::default(), world_ptr.clone());
+    let base = ScriptRef::from(fields.clone());
+
+    let lua = Lua::new();
+    lua.globals()
+        .set("world", LuaWorld::new(world_ptr.clone()))
+        .unwrap();
+    lua.globals().set("fields", fields.clone()).unwrap();
+
+    let mut group = c.benchmark_group("lua");
+    for field in ["float", "int", "string"] {
+        let field_ref = base.sub_ref_path(field).unwrap();
+        group.bench_function(format!("to_lua/{field}"), |b| {
+            b.iter(|| black_box(field_ref.clone().to_lua(&lua).unwrap()))
+        });
+    }
+
+    for (field, new_value) in [("float", "1.5"), ("int", "3"), ("string", "'text'")] {
+        let assign = lua
+            .load(&format!("fields.{field} = {new_value}"))
+            .into_function()
+            .unwrap();
+        group.bench_function(format!("assign/{field}"), |b| {
+            b.iter(|| assign.call::<_, ()>(()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn rhai_conversions(c: &mut Criterion) {
+    let mut world = setup();
+    // safety: the world outlives every reference to it, all of which are dropped at the end of this function
+    let world_ptr = unsafe { WorldPointer::new(&mut world) };
+    let fields = ReflectedValue::new_owned(Box::<Fields>::default(), world_ptr);
+    let base = ScriptRef::from(fields.clone());
+
+    let mut engine = Engine::new();
+    engine.build_type::<ReflectedValue>();
+    let mut scope = Scope::new();
+    scope.push("fields", fields.clone());
+
+    let mut group = c.benchmark_group("rhai");
+    for field in ["float", "int", "string"] {
+        let field_ref = base.sub_ref_path(field).unwrap();
+        group.bench_function(format!("to_dynamic/{field}"), |b| {
+            b.iter(|| black_box(field_ref.clone().to_dynamic().unwrap()))
+        });
+    }
+
+    for (field, new_value) in [("float", "1.5"), ("int", "3"), ("string", "\"text\"")] {
+        let assign = engine
+            .compile(format!("fields[\"{field}\"] = {new_value};"))
+            .unwrap();
+        group.bench_function(format!("assign/{field}"), |b| {
+            b.iter(|| engine.run_ast_with_scope(&mut scope, &assign).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, lua_conversions, rhai_conversions);
+criterion_main!(benches);
@@ -1,29 +1,79 @@
 use std::{
+    any::TypeId,
     ops::{Deref, DerefMut},
     sync::Arc,
 };
 
-use crate::ScriptRef;
+use crate::{
+    common::iter::{mark_structural_change, EntitiesWith, ScriptIter},
+    ScriptRef,
+};
 /// Common functionality for all script hosts
 use bevy::{
-    ecs::system::Command,
+    ecs::system::{Command, CommandQueue, Insert},
     prelude::{
-        AppTypeRegistry, BuildWorldChildren, Children, DespawnChildrenRecursive, DespawnRecursive,
-        Entity, Parent, ReflectComponent, ReflectDefault, ReflectResource,
+        App, AppTypeRegistry, BuildWorldChildren, Children, Component, CoreSet,
+        DespawnChildrenRecursive, DespawnRecursive, Entity, IntoSystemConfig, Mut, Parent,
+        ReflectComponent, ReflectDefault, ReflectResource, Resource, Transform, Visibility, World,
     },
     reflect::{
         DynamicArray, DynamicEnum, DynamicList, DynamicMap, DynamicStruct, DynamicTuple,
-        DynamicTupleStruct, TypeRegistration,
+        DynamicTupleStruct, Reflect, TypeInfo, TypeRegistration,
     },
 };
 use bevy_mod_scripting_core::{prelude::ScriptError, world::WorldPointer};
 
+/// An entity matched by [`ScriptWorld::query`] along with references to its queried components
+#[derive(Clone, Debug)]
+pub struct ScriptQueryResult {
+    entity: Entity,
+    components: Vec<ScriptRef>,
+}
+
+impl ScriptQueryResult {
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// The queried components in the order their types were given
+    pub fn components(&self) -> &[ScriptRef] {
+        &self.components
+    }
+}
+
 /// Helper trait for retrieving a world pointer from a script context.
 pub trait GetWorld {
     type Error;
     fn get_world(&self) -> Result<WorldPointer, Self::Error>;
 }
 
+/// Commands queued up by scripts, applied to the world at the end of each frame by [`apply_script_commands`].
+///
+/// Used for structural changes which scripts should not perform while they are running,
+/// such as inserting components.
+#[derive(Resource, Default)]
+pub struct ScriptCommandQueue(CommandQueue);
+
+impl ScriptCommandQueue {
+    pub fn push<C: Command>(&mut self, command: C) {
+        self.0.push(command)
+    }
+}
+
+/// Applies all commands queued up by scripts in the [`ScriptCommandQueue`]
+pub fn apply_script_commands(world: &mut World) {
+    world.resource_scope(|world, mut queue: Mut<ScriptCommandQueue>| queue.0.apply(world));
+}
+
+/// Initializes the [`ScriptCommandQueue`] and adds the system applying it,
+/// does nothing if this was already done (i.e. by another API provider).
+pub fn register_script_commands(app: &mut App) {
+    if !app.world.contains_resource::<ScriptCommandQueue>() {
+        app.init_resource::<ScriptCommandQueue>()
+            .add_system(apply_script_commands.in_base_set(CoreSet::Last));
+    }
+}
+
 #[derive(Clone)]
 pub struct ScriptTypeRegistration(pub(crate) Arc<TypeRegistration>);
 
@@ -41,6 +91,33 @@ impl ScriptTypeRegistration {
     pub fn type_name(&self) -> &'static str {
         self.0.type_name()
     }
+
+    /// A value of this type which components can be inserted with, fields missing from it are filled in with their defaults.
+    /// Value types without reflected fields must implement `ReflectDefault`.
+    pub fn default_value(&self) -> Result<Box<dyn Reflect>, ScriptError> {
+        // this is just a formality
+        // TODO: maybe get an add_default impl added to ReflectComponent
+        // this means that we don't require ReflectDefault for adding components!
+        let value: Box<dyn Reflect> = match self.0.type_info() {
+            TypeInfo::Struct(_) => Box::<DynamicStruct>::default(),
+            TypeInfo::TupleStruct(_) => Box::<DynamicTupleStruct>::default(),
+            TypeInfo::Tuple(_) => Box::<DynamicTuple>::default(),
+            TypeInfo::List(_) => Box::<DynamicList>::default(),
+            TypeInfo::Array(_) => Box::new(DynamicArray::new(Box::new([]))),
+            TypeInfo::Map(_) => Box::<DynamicMap>::default(),
+            TypeInfo::Enum(_) => Box::<DynamicEnum>::default(),
+            TypeInfo::Value(_) | TypeInfo::Dynamic(_) => self
+                .data::<ReflectDefault>()
+                .ok_or_else(|| {
+                    ScriptError::new_conversion(
+                        self.short_name(),
+                        "Value or dynamic type with no `ReflectDefault` type_data, cannot instantiate sensible value",
+                    )
+                })?
+                .default(),
+        };
+        Ok(value)
+    }
 }
 
 impl std::fmt::Debug for ScriptTypeRegistration {
@@ -121,6 +198,7 @@ impl ScriptWorld {
         if let Some(mut entity) = w.get_entity_mut(parent) {
             entity.push_children(&[child]);
         }
+        mark_structural_change(&mut w);
     }
 
     pub fn remove_children(&self, parent: Entity, children: &[Entity]) {
@@ -129,6 +207,7 @@ impl ScriptWorld {
         if let Some(mut entity) = w.get_entity_mut(parent) {
             entity.remove_children(children);
         }
+        mark_structural_change(&mut w);
     }
 
     pub fn insert_children(&self, parent: Entity, index: usize, children: &[Entity]) {
@@ -137,16 +216,19 @@ impl ScriptWorld {
         if let Some(mut entity) = w.get_entity_mut(parent) {
             entity.insert_children(index, children);
         }
+        mark_structural_change(&mut w);
     }
 
     pub fn despawn_children_recursive(&self, entity: Entity) {
         let mut w = self.write();
         DespawnChildrenRecursive { entity }.write(&mut w);
+        mark_structural_change(&mut w);
     }
 
     pub fn despawn_recursive(&self, entity: Entity) {
         let mut w = self.write();
         DespawnRecursive { entity }.write(&mut w);
+        mark_structural_change(&mut w);
     }
 
     pub fn get_type_by_name(&self, type_name: &str) -> Option<ScriptTypeRegistration> {
@@ -170,27 +252,12 @@ impl ScriptWorld {
         let mut w = self.write();
 
         let component_data = comp_type.data::<ReflectComponent>().ok_or_else(|| {
-            ScriptError::Other(format!("Not a component {}", comp_type.short_name()))
+            ScriptError::new_world_access(comp_type.short_name(), "Not a component")
         })?;
 
-        // this is just a formality
-        // TODO: maybe get an add_default impl added to ReflectComponent
-        // this means that we don't require ReflectDefault for adding components!
-        match comp_type.0.type_info(){
-            bevy::reflect::TypeInfo::Struct(_) => component_data.insert(&mut w.entity_mut(entity), &DynamicStruct::default()),
-            bevy::reflect::TypeInfo::TupleStruct(_) => component_data.insert(&mut w.entity_mut(entity), &DynamicTupleStruct::default()),
-            bevy::reflect::TypeInfo::Tuple(_) => component_data.insert(&mut w.entity_mut(entity), &DynamicTuple::default()),
-            bevy::reflect::TypeInfo::List(_) => component_data.insert(&mut w.entity_mut(entity), &DynamicList::default()),
-            bevy::reflect::TypeInfo::Array(_) => component_data.insert(&mut w.entity_mut(entity), &DynamicArray::new(Box::new([]))),
-            bevy::reflect::TypeInfo::Map(_) => component_data.insert(&mut w.entity_mut(entity), &DynamicMap::default()),
-            bevy::reflect::TypeInfo::Value(_) |
-            bevy::reflect::TypeInfo::Dynamic(_) => component_data.insert(&mut w.entity_mut(entity),
-                comp_type.data::<ReflectDefault>().ok_or_else(||
-                    ScriptError::Other(format!("Component {} is a value or dynamic type with no `ReflectDefault` type_data, cannot instantiate sensible value",comp_type.short_name())))?
-                    .default()
-                    .as_ref()),
-            bevy::reflect::TypeInfo::Enum(_) => component_data.insert(&mut w.entity_mut(entity), &DynamicEnum::default())
-        };
+        let component = comp_type.default_value()?;
+        component_data.insert(&mut w.entity_mut(entity), component.as_ref());
+        mark_structural_change(&mut w);
 
         Ok(ScriptRef::new_component_ref(
             component_data.clone(),
@@ -207,7 +274,7 @@ impl ScriptWorld {
         let w = self.read();
 
         let component_data = comp_type.data::<ReflectComponent>().ok_or_else(|| {
-            ScriptError::Other(format!("Not a component {}", comp_type.short_name()))
+            ScriptError::new_world_access(comp_type.short_name(), "Not a component")
         })?;
 
         Ok(component_data.reflect(w.entity(entity)).map(|_component| {
@@ -222,12 +289,84 @@ impl ScriptWorld {
     ) -> Result<bool, ScriptError> {
         let w = self.read();
         let component_data = comp_type.data::<ReflectComponent>().ok_or_else(|| {
-            ScriptError::Other(format!("Not a component {}", comp_type.short_name()))
+            ScriptError::new_world_access(comp_type.short_name(), "Not a component")
         })?;
 
         Ok(component_data.reflect(w.entity(entity)).is_some())
     }
 
+    /// Returns a lazy iterator over all entities which have a component of the given type.
+    ///
+    /// Entities are looked up one at a time as the iterator is advanced,
+    /// spawning or despawning entities, or inserting or removing components, before the iterator is exhausted invalidates it.
+    pub fn iter_entities_with(
+        &self,
+        comp_type: ScriptTypeRegistration,
+    ) -> Result<ScriptIter<Entity>, ScriptError> {
+        comp_type.data::<ReflectComponent>().ok_or_else(|| {
+            ScriptError::new_world_access(comp_type.short_name(), "Not a component")
+        })?;
+
+        let component_id = self.read().components().get_id(comp_type.type_id());
+
+        // components which were never initialized cannot be present on any entity
+        Ok(match component_id {
+            Some(id) => {
+                ScriptIter::new_guarded(EntitiesWith::new(self.0.clone(), vec![id]), self.0.clone())
+            }
+            None => ScriptIter::new(std::iter::empty()),
+        })
+    }
+
+    /// Returns a lazy iterator over all entities which have components of all the given types,
+    /// along with references to those components in the order their types were given.
+    ///
+    /// Components are read and written through the world pointer like any other component reference,
+    /// spawning or despawning entities, or inserting or removing components, before the iterator is exhausted invalidates it.
+    pub fn query(
+        &self,
+        comp_types: Vec<ScriptTypeRegistration>,
+    ) -> Result<ScriptIter<ScriptQueryResult>, ScriptError> {
+        let component_data = comp_types
+            .iter()
+            .map(|comp_type| {
+                comp_type
+                    .data::<ReflectComponent>()
+                    .cloned()
+                    .ok_or_else(|| {
+                        ScriptError::new_world_access(comp_type.short_name(), "Not a component")
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let component_ids = {
+            let w = self.read();
+            comp_types
+                .iter()
+                .map(|comp_type| w.components().get_id(comp_type.type_id()))
+                .collect::<Option<Vec<_>>>()
+        };
+
+        // components which were never initialized cannot be present on any entity
+        let component_ids = match component_ids {
+            Some(ids) => ids,
+            None => return Ok(ScriptIter::new(std::iter::empty())),
+        };
+
+        let world = self.0.clone();
+        Ok(ScriptIter::new_guarded(
+            EntitiesWith::new(self.0.clone(), component_ids),
+            self.0.clone(),
+        )
+        .map(move |entity| ScriptQueryResult {
+            entity,
+            components: component_data
+                .iter()
+                .map(|data| ScriptRef::new_component_ref(data.clone(), entity, world.clone()))
+                .collect(),
+        }))
+    }
+
     pub fn remove_component(
         &mut self,
         entity: Entity,
@@ -235,9 +374,10 @@ impl ScriptWorld {
     ) -> Result<(), ScriptError> {
         let mut w = self.write();
         let component_data = comp_type.data::<ReflectComponent>().ok_or_else(|| {
-            ScriptError::Other(format!("Not a component {}", comp_type.short_name()))
+            ScriptError::new_world_access(comp_type.short_name(), "Not a component")
         })?;
         component_data.remove(&mut w.entity_mut(entity));
+        mark_structural_change(&mut w);
         Ok(())
     }
 
@@ -248,7 +388,7 @@ impl ScriptWorld {
         let w = self.read();
 
         let resource_data = res_type.data::<ReflectResource>().ok_or_else(|| {
-            ScriptError::Other(format!("Not a resource {}", res_type.short_name()))
+            ScriptError::new_world_access(res_type.short_name(), "Not a resource")
         })?;
 
         Ok(resource_data
@@ -260,18 +400,194 @@ impl ScriptWorld {
         let w = self.read();
 
         let resource_data = res_type.data::<ReflectResource>().ok_or_else(|| {
-            ScriptError::Other(format!("Not a resource {}", res_type.short_name()))
+            ScriptError::new_world_access(res_type.short_name(), "Not a resource")
         })?;
 
         Ok(resource_data.reflect(&w).is_some())
     }
+    /// Retrieves a reference to the component of the given type on the entity, if it has one
+    pub fn get_component_of<T: Component>(
+        &self,
+        entity: Entity,
+    ) -> Result<Option<ScriptRef>, ScriptError> {
+        let comp_type = {
+            let w = self.read();
+            let registry = w.resource::<AppTypeRegistry>().read();
+            registry
+                .get(TypeId::of::<T>())
+                .map(|registration| ScriptTypeRegistration::new(Arc::new(registration.clone())))
+                .ok_or_else(|| {
+                    ScriptError::new_world_access(
+                        std::any::type_name::<T>(),
+                        "Component is not registered",
+                    )
+                })?
+        };
+
+        self.get_component(entity, comp_type)
+    }
+
+    /// Overwrites the component on the given entity, if the entity does not have one yet
+    /// the component is inserted at the end of the frame via the [`ScriptCommandQueue`].
+    pub fn set_or_insert_component<T: Component>(
+        &self,
+        entity: Entity,
+        component: T,
+    ) -> Result<(), ScriptError> {
+        let mut w = self.write();
+
+        let mut entity_mut = w.get_entity_mut(entity).ok_or_else(|| {
+            ScriptError::new_world_access("Entity", format!("Entity {entity:?} does not exist"))
+        })?;
+
+        if let Some(mut existing) = entity_mut.get_mut::<T>() {
+            *existing = component;
+            return Ok(());
+        }
+
+        w.get_resource_or_insert_with(ScriptCommandQueue::default)
+            .push(Insert {
+                entity,
+                bundle: component,
+            });
+
+        Ok(())
+    }
+
+    /// Returns `false` if the entity is hidden, either by its own [`Visibility`] or by the closest ancestor
+    /// it inherits its visibility from, like bevy computes it.
+    /// Entities without a [`Visibility`] component are treated as having the default one, inheriting it.
+    pub fn is_visible(&self, entity: Entity) -> Result<bool, ScriptError> {
+        let w = self.read();
+
+        let mut entity_ref = w.get_entity(entity).ok_or_else(|| {
+            ScriptError::new_world_access("Entity", format!("Entity {entity:?} does not exist"))
+        })?;
+
+        loop {
+            match entity_ref.get::<Visibility>().copied().unwrap_or_default() {
+                Visibility::Visible => return Ok(true),
+                Visibility::Hidden => return Ok(false),
+                // roots have nothing to inherit from and are visible
+                Visibility::Inherited => {
+                    match entity_ref
+                        .get::<Parent>()
+                        .and_then(|parent| w.get_entity(parent.get()))
+                    {
+                        Some(parent) => entity_ref = parent,
+                        None => return Ok(true),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Hides the entity, or makes it inherit the visibility of its parent,
+    /// so entities shown under a hidden parent stay hidden until the parent is shown.
+    /// If the entity has no [`Visibility`] component, one is inserted at the end of the frame.
+    pub fn set_visible(&self, entity: Entity, visible: bool) -> Result<(), ScriptError> {
+        self.set_or_insert_component(
+            entity,
+            if visible {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            },
+        )
+    }
+
+    /// Retrieves a reference to the entity's [`Transform`], if it has one
+    pub fn get_transform(&self, entity: Entity) -> Result<Option<ScriptRef>, ScriptError> {
+        self.get_component_of::<Transform>(entity)
+    }
+
+    /// Sets the entity's [`Transform`],
+    /// if the entity has no [`Transform`] component, one is inserted at the end of the frame.
+    pub fn set_transform(&self, entity: Entity, transform: Transform) -> Result<(), ScriptError> {
+        self.set_or_insert_component(entity, transform)
+    }
+
     pub fn remove_resource(&mut self, res_type: ScriptTypeRegistration) -> Result<(), ScriptError> {
         let mut w = self.write();
 
         let resource_data = res_type.data::<ReflectResource>().ok_or_else(|| {
-            ScriptError::Other(format!("Not a resource {}", res_type.short_name()))
+            ScriptError::new_world_access(res_type.short_name(), "Not a resource")
         })?;
         resource_data.remove(&mut w);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn visibility_is_inherited_from_ancestors() {
+        let mut world = World::new();
+        let root = world.spawn(Visibility::Hidden).id();
+        let hidden_child = world.spawn_empty().id();
+        let shown_child = world.spawn(Visibility::Visible).id();
+        let grandchild = world.spawn(Visibility::Inherited).id();
+        let orphan = world.spawn_empty().id();
+        world
+            .entity_mut(root)
+            .push_children(&[hidden_child, shown_child]);
+        world.entity_mut(hidden_child).push_children(&[grandchild]);
+
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+        assert!(!script_world.is_visible(root).unwrap());
+        // entities without a visibility inherit it too
+        assert!(!script_world.is_visible(hidden_child).unwrap());
+        assert!(!script_world.is_visible(grandchild).unwrap());
+        assert!(script_world.is_visible(shown_child).unwrap());
+        assert!(script_world.is_visible(orphan).unwrap());
+    }
+
+    #[test]
+    fn queries_match_entities_with_all_given_components() {
+        #[derive(Component, Reflect, Default)]
+        #[reflect(Component)]
+        struct Health(f32);
+        #[derive(Component, Reflect, Default)]
+        #[reflect(Component)]
+        struct Player;
+        #[derive(Component, Reflect, Default)]
+        #[reflect(Component)]
+        struct Enemy;
+
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        {
+            let registry = world.resource::<AppTypeRegistry>().clone();
+            let mut registry = registry.write();
+            registry.register::<Health>();
+            registry.register::<Player>();
+            registry.register::<Enemy>();
+            registry.register::<f32>();
+        }
+        let player = world.spawn((Health(3.0), Player)).id();
+        world.spawn(Health(1.0));
+        world.spawn(Player);
+
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+        let type_ = |name| script_world.get_type_by_name(name).unwrap();
+
+        let mut results = script_world
+            .query(vec![type_("Player"), type_("Health")])
+            .unwrap();
+        let result = results.next_checked().unwrap().unwrap();
+        assert_eq!(result.entity(), player);
+        // components come in the order their types were given
+        assert_eq!(
+            result.components()[1].get_typed(|h: &Health| h.0).unwrap(),
+            3.0
+        );
+        assert!(results.next_checked().unwrap().is_none());
+
+        // components never added to the world match nothing
+        let mut results = script_world.query(vec![type_("Enemy")]).unwrap();
+        assert!(results.next_checked().unwrap().is_none());
+        assert!(script_world.query(vec![type_("f32")]).is_err());
+    }
+}
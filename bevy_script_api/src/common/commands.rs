@@ -0,0 +1,327 @@
+use std::sync::Arc;
+
+use bevy::{
+    ecs::system::{Command, Despawn},
+    log::warn,
+    prelude::{AppTypeRegistry, DespawnRecursive, Entity, ReflectComponent, ReflectDefault, World},
+    reflect::{Reflect, ReflectFromReflect, TypeRegistration},
+};
+use bevy_mod_scripting_core::{prelude::ScriptError, world::WorldPointer};
+
+use crate::{
+    common::bevy::{ScriptCommandQueue, ScriptTypeRegistration},
+    error::ReflectionError,
+    script_ref::{ScriptOwnedValue, ScriptRef},
+};
+
+/// Structural changes to the world queued up by scripts, exposed to scripts as `commands`.
+///
+/// Unlike the equivalent methods of `world`, which take effect immediately, these are pushed onto the
+/// [`ScriptCommandQueue`] and applied at the end of the frame, once every script handler has run.
+/// Entities spawned this way get their id right away, but only exist in the world once the queue is applied.
+#[derive(Clone, Debug)]
+pub struct ScriptCommands(WorldPointer);
+
+impl ScriptCommands {
+    pub fn new(world: WorldPointer) -> Self {
+        Self(world)
+    }
+
+    fn push<C: Command>(&self, command: C) {
+        let mut w = self.0.write();
+        w.get_resource_or_insert_with(ScriptCommandQueue::default)
+            .push(command);
+    }
+
+    fn component_data(comp_type: &ScriptTypeRegistration) -> Result<ReflectComponent, ScriptError> {
+        comp_type
+            .data::<ReflectComponent>()
+            .cloned()
+            .ok_or_else(|| ScriptError::new_world_access(comp_type.short_name(), "Not a component"))
+    }
+
+    /// Looks up a component type by its short or full name,
+    /// failing with the short names of all registered components if there is no such component
+    pub fn component_type(&self, type_name: &str) -> Result<ScriptTypeRegistration, ScriptError> {
+        let w = self.0.read();
+        let registry = w.resource::<AppTypeRegistry>().read();
+        let is_component = |r: &&TypeRegistration| r.data::<ReflectComponent>().is_some();
+
+        registry
+            .get_with_short_name(type_name)
+            .or_else(|| registry.get_with_name(type_name))
+            .filter(is_component)
+            .map(|r| ScriptTypeRegistration::new(Arc::new(r.clone())))
+            .ok_or_else(|| {
+                let mut names: Vec<_> = registry
+                    .iter()
+                    .filter(is_component)
+                    .map(|r| r.short_name())
+                    .collect();
+                names.sort_unstable();
+                ScriptError::new_world_access(
+                    type_name,
+                    format!(
+                        "Not a registered component, the registered components are: {}",
+                        names.join(", ")
+                    ),
+                )
+            })
+    }
+
+    /// Reserves the id of a new empty entity, which is spawned once the queue is applied
+    pub fn spawn(&self) -> Entity {
+        self.0.read().entities().reserve_entity()
+    }
+
+    /// Despawns the entity once the queue is applied
+    pub fn despawn(&self, entity: Entity) {
+        self.push(Despawn { entity })
+    }
+
+    /// Despawns the entity and its children recursively once the queue is applied
+    pub fn despawn_recursive(&self, entity: Entity) {
+        self.push(DespawnRecursive { entity })
+    }
+
+    /// Inserts the default value of the given component type on the entity once the queue is applied,
+    /// replacing the entity's existing component of that type
+    pub fn insert_default(
+        &self,
+        entity: Entity,
+        comp_type: ScriptTypeRegistration,
+    ) -> Result<(), ScriptError> {
+        let component = comp_type.default_value()?;
+        self.insert(entity, comp_type, component)
+    }
+
+    /// Inserts a component of the type with the given name on the entity once the queue is applied,
+    /// replacing the entity's existing component of that type.
+    ///
+    /// The component starts out as its default value, which `fill` sets the fields given by the script on.
+    /// Types with `ReflectFromReflect` type data are first built from no fields at all, which fills in the fields marked
+    /// `#[reflect(default)]`, otherwise the type must have `ReflectDefault` type data.
+    /// Unknown type names fail with the names of all registered components, see [`ScriptCommands::component_type`]
+    pub fn insert_component<E: From<ReflectionError>>(
+        &self,
+        entity: Entity,
+        type_name: &str,
+        fill: impl FnOnce(&mut ScriptRef) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let to_error = |e: ScriptError| E::from(ReflectionError::Other(e.to_string()));
+
+        let comp_type = self.component_type(type_name).map_err(to_error)?;
+        let from_reflect = comp_type
+            .data::<ReflectFromReflect>()
+            .and_then(|from_reflect| {
+                from_reflect.from_reflect(comp_type.default_value().ok()?.as_ref())
+            });
+        let default = match from_reflect {
+            Some(default) => default,
+            None => comp_type
+                .data::<ReflectDefault>()
+                .ok_or_else(|| {
+                    to_error(ScriptError::new_conversion(
+                        comp_type.short_name(),
+                        "Cannot be built from no fields through `ReflectFromReflect` and has no `ReflectDefault` type data, cannot construct the component from a script value",
+                    ))
+                })?
+                .default(),
+        };
+
+        // the fields are set through a reference like those of any other value owned by the script
+        let owned = ScriptOwnedValue::new(default);
+        let mut component = owned.script_ref(self.0.clone());
+        fill(&mut component)?;
+        let component = component.get(|c| c.clone_value())?;
+
+        self.insert(entity, comp_type, component).map_err(to_error)
+    }
+
+    /// Inserts the given value of the component type on the entity once the queue is applied,
+    /// replacing the entity's existing component of that type.
+    ///
+    /// The value is converted to the component type via `FromReflect`, it can be a dynamic value with all the component's fields
+    pub fn insert(
+        &self,
+        entity: Entity,
+        comp_type: ScriptTypeRegistration,
+        component: Box<dyn Reflect>,
+    ) -> Result<(), ScriptError> {
+        let component_data = Self::component_data(&comp_type)?;
+
+        self.push(move |world: &mut World| {
+            if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+                component_data.insert(&mut entity_mut, component.as_ref());
+            } else {
+                warn!(
+                    "Could not insert `{}` queued up by a script, entity {entity:?} does not exist",
+                    comp_type.short_name()
+                );
+            }
+        });
+        Ok(())
+    }
+
+    /// Removes the component of the given type from the entity once the queue is applied
+    pub fn remove(
+        &self,
+        entity: Entity,
+        comp_type: ScriptTypeRegistration,
+    ) -> Result<(), ScriptError> {
+        let component_data = Self::component_data(&comp_type)?;
+
+        self.push(move |world: &mut World| {
+            if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+                component_data.remove(&mut entity_mut);
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{borrow::Cow, sync::Arc};
+
+    use bevy::{
+        prelude::{AppTypeRegistry, Component, FromReflect, FromWorld, Transform, Vec3},
+        reflect::Reflect,
+    };
+
+    use super::*;
+    use crate::{common::bevy::apply_script_commands, script_ref::ValueIndex};
+
+    fn transform_type(world: &World) -> ScriptTypeRegistration {
+        let registry = world.resource::<AppTypeRegistry>().read();
+        let registration = registry.get_with_short_name("Transform").unwrap().clone();
+        ScriptTypeRegistration::new(Arc::new(registration))
+    }
+
+    #[test]
+    fn commands_apply_with_the_queue() {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        registry.write().register::<Transform>();
+        world.insert_resource(registry);
+        world.init_resource::<ScriptCommandQueue>();
+        let transform = transform_type(&world);
+
+        // safety: the world outlives the pointer, which is dropped before the world is used again
+        let commands = ScriptCommands::new(unsafe { WorldPointer::new(&mut world) });
+        let entity = commands.spawn();
+        commands.insert_default(entity, transform.clone()).unwrap();
+        drop(commands);
+
+        assert!(world.get::<Transform>(entity).is_none());
+        apply_script_commands(&mut world);
+        assert_eq!(world.get::<Transform>(entity), Some(&Transform::default()));
+
+        let commands = ScriptCommands::new(unsafe { WorldPointer::new(&mut world) });
+        commands.remove(entity, transform).unwrap();
+        commands.despawn(entity);
+        drop(commands);
+
+        assert!(world.get_entity(entity).is_some());
+        apply_script_commands(&mut world);
+        assert!(world.get_entity(entity).is_none());
+    }
+
+    #[test]
+    fn components_are_built_from_their_default() {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        registry.write().register::<Transform>();
+        world.insert_resource(registry);
+        world.init_resource::<ScriptCommandQueue>();
+        let entity = world.spawn_empty().id();
+
+        let commands = ScriptCommands::new(unsafe { WorldPointer::new(&mut world) });
+        commands
+            .insert_component(entity, "Transform", |component| {
+                component
+                    .index(Cow::Borrowed("scale"))
+                    .set_val(Vec3::splat(2.0))
+                    .map_err(ScriptError::from)
+            })
+            .unwrap();
+        let unknown = commands
+            .insert_component(entity, "Transfrom", |_| Ok::<_, ScriptError>(()))
+            .unwrap_err();
+        drop(commands);
+
+        assert!(unknown.to_string().contains("Transform"), "{unknown}");
+        apply_script_commands(&mut world);
+        assert_eq!(
+            world.get::<Transform>(entity),
+            Some(&Transform::from_scale(Vec3::splat(2.0)))
+        );
+    }
+
+    #[derive(Component, Reflect, FromReflect, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Health {
+        #[reflect(default)]
+        current: f32,
+        #[reflect(default = "full")]
+        max: f32,
+    }
+
+    fn full() -> f32 {
+        100.0
+    }
+
+    // `ReflectComponent` requires `FromWorld`, which does not make the type `ReflectDefault`
+    impl FromWorld for Health {
+        fn from_world(_: &mut World) -> Self {
+            Self {
+                current: 0.0,
+                max: full(),
+            }
+        }
+    }
+
+    #[test]
+    fn components_without_default_are_built_through_from_reflect() {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        registry.write().register::<Health>();
+        world.insert_resource(registry);
+        world.init_resource::<ScriptCommandQueue>();
+        let entity = world.spawn_empty().id();
+
+        let commands = ScriptCommands::new(unsafe { WorldPointer::new(&mut world) });
+        let missing_from_reflect = commands
+            .insert_component(entity, "Health", |_| Ok::<_, ScriptError>(()))
+            .unwrap_err();
+        assert!(
+            missing_from_reflect.to_string().contains("ReflectDefault"),
+            "{missing_from_reflect}"
+        );
+        drop(commands);
+
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register_type_data::<Health, ReflectFromReflect>();
+        let commands = ScriptCommands::new(unsafe { WorldPointer::new(&mut world) });
+        commands
+            .insert_component(entity, "Health", |component| {
+                component
+                    .index(Cow::Borrowed("current"))
+                    .set_val(50.0f32)
+                    .map_err(ScriptError::from)
+            })
+            .unwrap();
+        drop(commands);
+
+        apply_script_commands(&mut world);
+        assert_eq!(
+            world.get::<Health>(entity),
+            Some(&Health {
+                current: 50.0,
+                max: 100.0
+            })
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use bevy::{
+    ecs::{
+        event::{Event, ManualEventReader},
+        system::SystemState,
+    },
+    prelude::*,
+    utils::get_short_name,
+};
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+
+use crate::ReflectedValue;
+
+/// Script events which can be constructed from a bevy event, i.e. a call to a hook with the reflected event as its argument
+pub trait ReflectEvent: ScriptEvent {
+    /// Creates an event calling the given hook on every script with the given reflected event
+    fn reflect_event(hook_name: String, event: ReflectedValue) -> Self;
+}
+
+/// Script events which call a hook on a single script with default arguments, as sent by timers and per frame systems
+pub trait HookEvent: ScriptEvent {
+    /// Creates an event calling the given hook on the script with the given id
+    fn hook_event(hook_name: String, sid: u32) -> Self;
+}
+
+/// The hook scripts define to receive bevy events of type `T`, `on_` followed by the short type name in snake case.
+///
+/// i.e. `bevy::app::AppExit` is received by `on_app_exit` and `my_game::PlayerDied<Boss>` by `on_player_died`
+pub fn scriptable_event_hook<T>() -> String {
+    let name = get_short_name(std::any::type_name::<T>());
+    let chars: Vec<char> = name.chars().take_while(|c| *c != '<').collect();
+
+    let mut hook = String::from("on_");
+    for (i, c) in chars.iter().enumerate() {
+        // a word starts at an uppercase letter following a lowercase one, or preceding one in acronyms
+        let prev_upper = i > 0 && chars[i - 1].is_uppercase();
+        let next_lower = chars.get(i + 1).map_or(false, |c| c.is_lowercase());
+        if i > 0 && c.is_uppercase() && (!prev_upper || next_lower) && chars[i - 1] != '_' {
+            hook.push('_');
+        }
+        hook.extend(c.to_lowercase());
+    }
+    hook
+}
+
+/// Forwards every bevy event of type `T` to the scripts of host `H`, as script events with priority `PRIO`
+pub fn scriptable_event_system<H: ScriptHost, T: Event + Reflect + Clone, const PRIO: u32>(
+    world: &mut World,
+    mut reader: Local<ManualEventReader<T>>,
+    writer: &mut SystemState<PriorityEventWriter<H::ScriptEvent>>,
+) where
+    H::ScriptEvent: ReflectEvent,
+{
+    let events: Vec<T> = reader
+        .iter(world.resource::<Events<T>>())
+        .cloned()
+        .collect();
+    if events.is_empty() {
+        return;
+    }
+
+    // safety:
+    // - the world outlives the events, scripts only access them while a host has &mut World access
+    // - world_ptr is not used to access the world in this function
+    let world_ptr = unsafe { WorldPointer::new(world) };
+    let hook_name = scriptable_event_hook::<T>();
+    let script_events = events.into_iter().map(|event| {
+        H::ScriptEvent::reflect_event(
+            hook_name.clone(),
+            ReflectedValue::new_owned(Box::new(event), world_ptr.clone()),
+        )
+    });
+
+    writer.get_mut(world).send_batch(script_events, PRIO);
+}
+
+/// Forwarding bevy events to scripts
+pub trait AddScriptableEvent {
+    /// Forwards every event of type `T` to the scripts of host `H` as a call to the hook named by [`scriptable_event_hook`],
+    /// with the event as its only argument. Scripts which don't define the hook ignore the event.
+    ///
+    /// The events are sent with priority `PRIO` in `CoreSet::PreUpdate`,
+    /// so they are handled by whichever handler set covers that priority, in the same order as any other event.
+    fn add_scriptable_event<H: ScriptHost, T: Event + Reflect + Clone, const PRIO: u32>(
+        &mut self,
+    ) -> &mut Self
+    where
+        H::ScriptEvent: ReflectEvent;
+}
+
+impl AddScriptableEvent for App {
+    fn add_scriptable_event<H: ScriptHost, T: Event + Reflect + Clone, const PRIO: u32>(
+        &mut self,
+    ) -> &mut Self
+    where
+        H::ScriptEvent: ReflectEvent,
+    {
+        self.add_event::<T>()
+            .add_system(scriptable_event_system::<H, T, PRIO>.in_base_set(CoreSet::PreUpdate))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hooks_are_named_after_the_type() {
+        assert_eq!(scriptable_event_hook::<bevy::app::AppExit>(), "on_app_exit");
+        assert_eq!(scriptable_event_hook::<Vec<u8>>(), "on_vec");
+        assert_eq!(scriptable_event_hook::<std::ffi::CString>(), "on_c_string");
+    }
+}
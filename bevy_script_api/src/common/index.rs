@@ -0,0 +1,50 @@
+use std::fmt::Debug;
+
+use super::sequence::ScriptSequence;
+
+/// Types scripts can index into through their `Index` implementation.
+///
+/// `Index` panics on invalid keys, so keys are checked up front to turn them into errors,
+/// which keeps scripts from bringing down the host
+pub trait ScriptIndex<K> {
+    /// Returns true if the value can be indexed with the given key
+    fn contains_key(&self, key: &K) -> bool;
+}
+
+/// Sequences are indexed by the position of their elements
+impl<T: ScriptSequence> ScriptIndex<usize> for T {
+    fn contains_key(&self, key: &usize) -> bool {
+        *key < T::LEN
+    }
+}
+
+/// Checks the key of an indexing operation run on behalf of a script, i.e. before `s[key].clone()`
+pub fn check_key<T: ScriptIndex<K> + ?Sized, K: Debug>(
+    type_name: &str,
+    value: &T,
+    key: &K,
+) -> Result<(), String> {
+    if value.contains_key(key) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Cannot index `{type_name}` with the invalid key {key:?}"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::math::Vec3;
+
+    use super::check_key;
+
+    #[test]
+    fn invalid_keys_are_errors() {
+        let v = Vec3::new(1.0, 2.0, 3.0);
+        assert_eq!(check_key("Vec3", &v, &2), Ok(()));
+
+        let err = check_key("Vec3", &v, &3).unwrap_err();
+        assert_eq!(err, "Cannot index `Vec3` with the invalid key 3");
+    }
+}
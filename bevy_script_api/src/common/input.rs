@@ -0,0 +1,150 @@
+use std::hash::Hash;
+
+use bevy::{
+    ecs::query::With,
+    input::{keyboard::KeyCode, mouse::MouseButton, Input},
+    math::Vec2,
+    reflect::{DynamicEnum, DynamicVariant, FromReflect, TypeInfo, Typed, VariantInfo},
+    window::{PrimaryWindow, Window},
+};
+use bevy_mod_scripting_core::{prelude::ScriptError, world::WorldPointer};
+
+/// Looks up the unit variant of `T` with the given name, i.e. `KeyCode::Space` from `"Space"`,
+/// failing with the names of all unit variants if there is no such variant
+fn unit_variant<T: FromReflect + Typed>(name: &str, kind: &str) -> Result<T, ScriptError> {
+    let variants = match T::type_info() {
+        TypeInfo::Enum(info) => info
+            .iter()
+            .filter(|v| matches!(v, VariantInfo::Unit(_)))
+            .map(VariantInfo::name)
+            .collect::<Vec<_>>(),
+        _ => Vec::default(),
+    };
+
+    variants
+        .contains(&name)
+        .then(|| {
+            T::from_reflect(&DynamicEnum::new(
+                std::any::type_name::<T>(),
+                name,
+                DynamicVariant::Unit,
+            ))
+        })
+        .flatten()
+        .ok_or_else(|| ScriptError::ConversionError {
+            type_name: std::any::type_name::<T>().to_owned(),
+            msg: format!(
+                "unknown {kind} `{name}`, the known {kind}s are: {}",
+                variants.join(", ")
+            ),
+        })
+}
+
+/// Read only access to the keyboard and mouse state, exposed to scripts as `input`.
+///
+/// Keys and mouse buttons are named after their `KeyCode` and `MouseButton` variants, i.e. `"Space"`, `"A"` or `"Left"`.
+/// The state is that of the `Input` resources, updated by bevy's `InputPlugin` at the start of each frame.
+#[derive(Clone, Debug)]
+pub struct ScriptInput(WorldPointer);
+
+impl ScriptInput {
+    pub fn new(world: WorldPointer) -> Self {
+        Self(world)
+    }
+
+    /// The key with the given name, i.e. `KeyCode::Space` for `"Space"`
+    pub fn key_code(name: &str) -> Result<KeyCode, ScriptError> {
+        unit_variant(name, "key")
+    }
+
+    /// The mouse button with the given name, i.e. `MouseButton::Left` for `"Left"`
+    pub fn mouse_button(name: &str) -> Result<MouseButton, ScriptError> {
+        unit_variant(name, "mouse button")
+    }
+
+    fn with_input<T, O>(&self, f: impl FnOnce(&Input<T>) -> O) -> Result<O, ScriptError>
+    where
+        T: Copy + Eq + Hash + Send + Sync + 'static,
+    {
+        let w = self.0.read();
+        w.get_resource::<Input<T>>().map(f).ok_or_else(|| {
+            ScriptError::new_world_access(
+                std::any::type_name::<Input<T>>(),
+                "Resource is missing, is the `InputPlugin` added?",
+            )
+        })
+    }
+
+    /// Whether the key with the given name is being held down
+    pub fn is_key_pressed(&self, key: &str) -> Result<bool, ScriptError> {
+        let key = Self::key_code(key)?;
+        self.with_input(|input| input.pressed(key))
+    }
+
+    /// Whether the key with the given name was pressed during the current frame
+    pub fn is_key_just_pressed(&self, key: &str) -> Result<bool, ScriptError> {
+        let key = Self::key_code(key)?;
+        self.with_input(|input| input.just_pressed(key))
+    }
+
+    /// Whether the key with the given name was released during the current frame
+    pub fn is_key_just_released(&self, key: &str) -> Result<bool, ScriptError> {
+        let key = Self::key_code(key)?;
+        self.with_input(|input| input.just_released(key))
+    }
+
+    /// Whether the mouse button with the given name is being held down
+    pub fn is_mouse_pressed(&self, button: &str) -> Result<bool, ScriptError> {
+        let button = Self::mouse_button(button)?;
+        self.with_input(|input| input.pressed(button))
+    }
+
+    /// Whether the mouse button with the given name was pressed during the current frame
+    pub fn is_mouse_just_pressed(&self, button: &str) -> Result<bool, ScriptError> {
+        let button = Self::mouse_button(button)?;
+        self.with_input(|input| input.just_pressed(button))
+    }
+
+    /// Whether the mouse button with the given name was released during the current frame
+    pub fn is_mouse_just_released(&self, button: &str) -> Result<bool, ScriptError> {
+        let button = Self::mouse_button(button)?;
+        self.with_input(|input| input.just_released(button))
+    }
+
+    /// The position of the cursor in the primary window in logical pixels from the bottom left corner,
+    /// `None` if the cursor is outside the window or there is no primary window
+    pub fn mouse_position(&self) -> Option<Vec2> {
+        let mut w = self.0.write();
+        let mut windows = w.query_filtered::<&Window, With<PrimaryWindow>>();
+        windows
+            .get_single(&w)
+            .ok()
+            .and_then(Window::cursor_position)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::input::{keyboard::KeyCode, mouse::MouseButton};
+
+    use super::ScriptInput;
+
+    #[test]
+    fn key_names() {
+        assert_eq!(ScriptInput::key_code("Space").unwrap(), KeyCode::Space);
+        assert_eq!(ScriptInput::key_code("A").unwrap(), KeyCode::A);
+        let err = ScriptInput::key_code("Spacebar").unwrap_err().to_string();
+        assert!(err.contains("unknown key `Spacebar`"), "{err}");
+        assert!(err.contains("Space"), "{err}");
+    }
+
+    #[test]
+    fn mouse_button_names() {
+        assert_eq!(
+            ScriptInput::mouse_button("Left").unwrap(),
+            MouseButton::Left
+        );
+        // buttons with data can't be named
+        assert!(ScriptInput::mouse_button("Other").is_err());
+    }
+}
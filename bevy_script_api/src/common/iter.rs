@@ -0,0 +1,186 @@
+use bevy::{
+    ecs::{archetype::ArchetypeGeneration, component::ComponentId},
+    prelude::{Entity, Resource, World},
+};
+use bevy_mod_scripting_core::world::WorldPointer;
+
+use crate::error::ReflectionError;
+
+/// A type erased iterator, lazily producing values for scripts one at a time.
+///
+/// Unlike returning a collection, nothing is produced until the script asks for the next element,
+/// which makes it suitable for exposing large datasets such as queries over many entities.
+pub struct ScriptIter<T> {
+    inner: Box<dyn Iterator<Item = T> + Send + Sync>,
+    guard: Option<WorldGuard>,
+}
+
+impl<T> ScriptIter<T> {
+    /// Wraps an iterator which does not depend on the state of the world
+    pub fn new<I: Iterator<Item = T> + Send + Sync + 'static>(iter: I) -> Self {
+        Self {
+            inner: Box::new(iter),
+            guard: None,
+        }
+    }
+
+    /// Wraps an iterator which reads from the world as it is advanced.
+    ///
+    /// If the world changes structurally while iterating (entities are spawned or despawned, components are inserted or removed,
+    /// or a system runs in between) advancing the iterator any further results in an error.
+    pub fn new_guarded<I: Iterator<Item = T> + Send + Sync + 'static>(
+        iter: I,
+        world: WorldPointer,
+    ) -> Self {
+        let stamp = WorldGuard::stamp(&world.read());
+        Self {
+            inner: Box::new(iter),
+            guard: Some(WorldGuard { world, stamp }),
+        }
+    }
+
+    /// Produces the next element, or `None` if the iterator is exhausted
+    pub fn next_checked(&mut self) -> Result<Option<T>, ReflectionError> {
+        if let Some(guard) = &self.guard {
+            guard.check()?;
+        }
+        Ok(self.inner.next())
+    }
+
+    /// Maps the elements of this iterator lazily, keeping the world guard if there is one
+    pub fn map<U, F: FnMut(T) -> U + Send + Sync + 'static>(self, f: F) -> ScriptIter<U>
+    where
+        T: 'static,
+    {
+        ScriptIter {
+            inner: Box::new(self.inner.map(f)),
+            guard: self.guard,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for ScriptIter<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptIter")
+            .field("guarded", &self.guard.is_some())
+            .finish()
+    }
+}
+
+/// The number of structural changes scripts made to the world directly
+#[derive(Resource, Default)]
+struct StructuralChanges(u32);
+
+/// Marks the world as structurally modified, invalidating all guarded iterators alive at the time.
+///
+/// Moving an entity between existing archetypes changes neither the number of archetypes nor of entities,
+/// so scripts modifying the world directly count their changes through this, leaving change detection untouched.
+pub(crate) fn mark_structural_change(world: &mut World) {
+    world
+        .get_resource_or_insert_with(StructuralChanges::default)
+        .0 += 1;
+}
+
+/// Detects structural changes to the world made while an iterator is alive
+struct WorldGuard {
+    world: WorldPointer,
+    stamp: (ArchetypeGeneration, u32, u32, u32),
+}
+
+impl WorldGuard {
+    /// Systems advance the change tick of the world as they run, so running one in between is detected as well
+    fn stamp(world: &World) -> (ArchetypeGeneration, u32, u32, u32) {
+        (
+            world.archetypes().generation(),
+            world.entities().len(),
+            world.read_change_tick(),
+            world.get_resource::<StructuralChanges>().map_or(0, |c| c.0),
+        )
+    }
+
+    fn check(&self) -> Result<(), ReflectionError> {
+        if Self::stamp(&self.world.read()) != self.stamp {
+            return Err(ReflectionError::Other(
+                "Iterator invalidated, the world was structurally modified during iteration"
+                    .to_owned(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Lazily walks all entities with all the given components, one archetype at a time
+pub(crate) struct EntitiesWith {
+    world: WorldPointer,
+    components: Vec<ComponentId>,
+    archetype: usize,
+    row: usize,
+}
+
+impl EntitiesWith {
+    pub(crate) fn new(world: WorldPointer, components: Vec<ComponentId>) -> Self {
+        Self {
+            world,
+            components,
+            archetype: 0,
+            row: 0,
+        }
+    }
+}
+
+impl Iterator for EntitiesWith {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let w = self.world.read();
+
+        loop {
+            let archetype = w.archetypes().iter().nth(self.archetype)?;
+
+            if self.components.iter().all(|c| archetype.contains(*c)) {
+                if let Some(entity) = archetype.entities().get(self.row) {
+                    self.row += 1;
+                    return Some(entity.entity());
+                }
+            }
+
+            self.archetype += 1;
+            self.row = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::{AppTypeRegistry, GlobalTransform, Transform};
+
+    use super::*;
+    use crate::common::bevy::ScriptWorld;
+
+    #[test]
+    fn moving_entities_between_archetypes_invalidates_iterators() {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        registry.write().register::<Transform>();
+        registry.write().register::<GlobalTransform>();
+        world.insert_resource(registry);
+        let moved = world.spawn(Transform::default()).id();
+        world.spawn((Transform::default(), GlobalTransform::default()));
+
+        // safety: the world outlives the pointer, which is dropped before the world is used again
+        let script_world = ScriptWorld::new(unsafe { WorldPointer::new(&mut world) });
+        let transform = script_world.get_type_by_name("Transform").unwrap();
+        let global_transform = script_world.get_type_by_name("GlobalTransform").unwrap();
+        let mut entities = script_world.iter_entities_with(transform).unwrap();
+        assert!(entities.next_checked().unwrap().is_some());
+
+        // the entity moves into an archetype which already exists, the number of entities stays the same
+        let change_tick = script_world.read().read_change_tick();
+        script_world
+            .add_default_component(moved, global_transform)
+            .unwrap();
+        assert!(entities.next_checked().is_err());
+        // change detection is not affected
+        assert_eq!(script_world.read().read_change_tick(), change_tick);
+    }
+}
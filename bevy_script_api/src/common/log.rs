@@ -0,0 +1,153 @@
+use bevy::log::{debug, error, error_span, info, warn, Level};
+
+/// The target of all events logged by scripts, i.e. `RUST_LOG=script=warn` only shows warnings and errors coming from scripts
+pub const SCRIPT_LOG_TARGET: &str = "script";
+
+/// Warns that a script called a deprecated method, the generated wrappers call this once per method
+pub fn warn_deprecated(method: &str, note: &str) {
+    if note.is_empty() {
+        warn!(target: SCRIPT_LOG_TARGET, "`{method}` is deprecated");
+    } else {
+        warn!(target: SCRIPT_LOG_TARGET, "`{method}` is deprecated: {note}");
+    }
+}
+
+/// Logs messages on behalf of a single script through `tracing`, exposed to scripts as `log`.
+///
+/// Every event is emitted inside a `script` span carrying the name of the script in its `name` field,
+/// so script output can be filtered and formatted alongside engine logs.
+#[derive(Clone, Debug)]
+pub struct ScriptLogger {
+    script_name: String,
+}
+
+impl ScriptLogger {
+    pub fn new(script_name: impl Into<String>) -> Self {
+        Self {
+            script_name: script_name.into(),
+        }
+    }
+
+    pub fn script_name(&self) -> &str {
+        &self.script_name
+    }
+
+    /// Logs the message at the given level, trace messages are logged as debug messages
+    pub fn log(&self, level: Level, msg: &str) {
+        // error spans are enabled whenever any script event is
+        let span = error_span!(target: SCRIPT_LOG_TARGET, "script", name = %self.script_name);
+        let _guard = span.enter();
+
+        match level {
+            Level::ERROR => error!(target: SCRIPT_LOG_TARGET, "{msg}"),
+            Level::WARN => warn!(target: SCRIPT_LOG_TARGET, "{msg}"),
+            Level::INFO => info!(target: SCRIPT_LOG_TARGET, "{msg}"),
+            _ => debug!(target: SCRIPT_LOG_TARGET, "{msg}"),
+        }
+    }
+
+    pub fn error(&self, msg: &str) {
+        self.log(Level::ERROR, msg)
+    }
+
+    pub fn warn(&self, msg: &str) {
+        self.log(Level::WARN, msg)
+    }
+
+    pub fn info(&self, msg: &str) {
+        self.log(Level::INFO, msg)
+    }
+
+    pub fn debug(&self, msg: &str) {
+        self.log(Level::DEBUG, msg)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use bevy::utils::tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id},
+        subscriber::with_default,
+        Event, Subscriber,
+    };
+    use tracing_subscriber::{
+        layer::{Context, SubscriberExt},
+        registry::LookupSpan,
+        Layer, Registry,
+    };
+
+    use super::*;
+
+    /// The value of a single field of a span or event
+    struct FieldVisitor(&'static str, Option<String>);
+
+    impl Visit for FieldVisitor {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            if field.name() == self.0 {
+                self.1 = Some(format!("{value:?}"));
+            }
+        }
+    }
+
+    struct ScriptName(String);
+
+    /// Records the level, message and script name of every event
+    #[derive(Clone, Default)]
+    struct CaptureLayer(Arc<Mutex<Vec<(Level, String, Option<String>)>>>);
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for CaptureLayer {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+            let mut visitor = FieldVisitor("name", None);
+            attrs.record(&mut visitor);
+            if let (Some(name), Some(span)) = (visitor.1, ctx.span(id)) {
+                span.extensions_mut().insert(ScriptName(name));
+            }
+        }
+
+        fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+            let mut visitor = FieldVisitor("message", None);
+            event.record(&mut visitor);
+            let script = ctx.event_span(event).and_then(|span| {
+                span.extensions()
+                    .get::<ScriptName>()
+                    .map(|name| name.0.clone())
+            });
+            self.0.lock().unwrap().push((
+                *event.metadata().level(),
+                visitor.1.unwrap_or_default(),
+                script,
+            ));
+        }
+    }
+
+    #[test]
+    fn events_carry_the_script_name() {
+        let layer = CaptureLayer::default();
+        let subscriber = Registry::default().with(layer.clone());
+
+        with_default(subscriber, || {
+            let logger = ScriptLogger::new("scripts/player.lua");
+            logger.info("spawned");
+            logger.error("out of health");
+        });
+
+        assert_eq!(
+            *layer.0.lock().unwrap(),
+            vec![
+                (
+                    Level::INFO,
+                    "spawned".to_owned(),
+                    Some("scripts/player.lua".to_owned())
+                ),
+                (
+                    Level::ERROR,
+                    "out of health".to_owned(),
+                    Some("scripts/player.lua".to_owned())
+                ),
+            ]
+        );
+    }
+}
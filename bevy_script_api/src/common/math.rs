@@ -0,0 +1,154 @@
+use bevy::math::{Mat3, Mat4, Quat, Vec2, Vec3, Vec4};
+
+/// A value accepted by the math helpers which work on several types, such as `lerp` or `normalize`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MathValue {
+    Number(f64),
+    Vec2(Vec2),
+    Vec3(Vec3),
+    Vec4(Vec4),
+    Quat(Quat),
+    Mat3(Mat3),
+    Mat4(Mat4),
+}
+
+impl MathValue {
+    /// The name of the type of this value as seen by scripts
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            MathValue::Number(_) => "number",
+            MathValue::Vec2(_) => "Vec2",
+            MathValue::Vec3(_) => "Vec3",
+            MathValue::Vec4(_) => "Vec4",
+            MathValue::Quat(_) => "Quat",
+            MathValue::Mat3(_) => "Mat3",
+            MathValue::Mat4(_) => "Mat4",
+        }
+    }
+
+    fn unsupported(op: &str, values: &[&MathValue]) -> String {
+        let types = values
+            .iter()
+            .map(|v| v.type_name())
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("`{op}` is not supported for ({types})")
+    }
+
+    /// Linearly interpolates between two values of the same type, quaternions are interpolated spherically
+    pub fn lerp(&self, other: &MathValue, t: f32) -> Result<MathValue, String> {
+        Ok(match (self, other) {
+            (MathValue::Number(a), MathValue::Number(b)) => {
+                MathValue::Number(a + (b - a) * t as f64)
+            }
+            (MathValue::Vec2(a), MathValue::Vec2(b)) => MathValue::Vec2(a.lerp(*b, t)),
+            (MathValue::Vec3(a), MathValue::Vec3(b)) => MathValue::Vec3(a.lerp(*b, t)),
+            (MathValue::Vec4(a), MathValue::Vec4(b)) => MathValue::Vec4(a.lerp(*b, t)),
+            (MathValue::Quat(a), MathValue::Quat(b)) => MathValue::Quat(a.slerp(*b, t)),
+            _ => return Err(Self::unsupported("lerp", &[self, other])),
+        })
+    }
+
+    /// The dot product of two vectors or quaternions of the same type
+    pub fn dot(&self, other: &MathValue) -> Result<f32, String> {
+        Ok(match (self, other) {
+            (MathValue::Vec2(a), MathValue::Vec2(b)) => a.dot(*b),
+            (MathValue::Vec3(a), MathValue::Vec3(b)) => a.dot(*b),
+            (MathValue::Vec4(a), MathValue::Vec4(b)) => a.dot(*b),
+            (MathValue::Quat(a), MathValue::Quat(b)) => a.dot(*b),
+            _ => return Err(Self::unsupported("dot", &[self, other])),
+        })
+    }
+
+    /// Scales a vector or quaternion to unit length, fails if its length is zero or not finite
+    pub fn normalize(&self) -> Result<MathValue, String> {
+        let normalized = match self {
+            MathValue::Vec2(v) => v.try_normalize().map(MathValue::Vec2),
+            MathValue::Vec3(v) => v.try_normalize().map(MathValue::Vec3),
+            MathValue::Vec4(v) => v.try_normalize().map(MathValue::Vec4),
+            MathValue::Quat(q) => Vec4::from(*q)
+                .try_normalize()
+                .map(|v| MathValue::Quat(Quat::from_vec4(v))),
+            _ => return Err(Self::unsupported("normalize", &[self])),
+        };
+        normalized.ok_or_else(|| format!("Cannot normalize a zero length `{}`", self.type_name()))
+    }
+
+    /// The length of a vector or quaternion
+    pub fn length(&self) -> Result<f32, String> {
+        Ok(match self {
+            MathValue::Vec2(v) => v.length(),
+            MathValue::Vec3(v) => v.length(),
+            MathValue::Vec4(v) => v.length(),
+            MathValue::Quat(q) => q.length(),
+            _ => return Err(Self::unsupported("length", &[self])),
+        })
+    }
+
+    /// The angle in radians between two vectors, or the angle of the rotation between two quaternions
+    pub fn angle(&self, other: &MathValue) -> Result<f32, String> {
+        Ok(match (self, other) {
+            (MathValue::Vec2(a), MathValue::Vec2(b)) => a.angle_between(*b),
+            (MathValue::Vec3(a), MathValue::Vec3(b)) => a.angle_between(*b),
+            (MathValue::Quat(a), MathValue::Quat(b)) => a.angle_between(*b),
+            _ => return Err(Self::unsupported("angle", &[self, other])),
+        })
+    }
+
+    /// The inverse of a quaternion or matrix
+    pub fn inverse(&self) -> Result<MathValue, String> {
+        Ok(match self {
+            MathValue::Quat(q) => MathValue::Quat(q.inverse()),
+            MathValue::Mat3(m) => MathValue::Mat3(m.inverse()),
+            MathValue::Mat4(m) => MathValue::Mat4(m.inverse()),
+            _ => return Err(Self::unsupported("inverse", &[self])),
+        })
+    }
+
+    /// The transpose of a matrix
+    pub fn transpose(&self) -> Result<MathValue, String> {
+        Ok(match self {
+            MathValue::Mat3(m) => MathValue::Mat3(m.transpose()),
+            MathValue::Mat4(m) => MathValue::Mat4(m.transpose()),
+            _ => return Err(Self::unsupported("transpose", &[self])),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lerp_matching_types() {
+        assert_eq!(
+            MathValue::Number(1.0).lerp(&MathValue::Number(3.0), 0.5),
+            Ok(MathValue::Number(2.0))
+        );
+        assert_eq!(
+            MathValue::Vec3(Vec3::ZERO).lerp(&MathValue::Vec3(Vec3::ONE), 0.25),
+            Ok(MathValue::Vec3(Vec3::splat(0.25)))
+        );
+        assert_eq!(
+            MathValue::Vec2(Vec2::ZERO).lerp(&MathValue::Vec3(Vec3::ONE), 0.25),
+            Err("`lerp` is not supported for (Vec2, Vec3)".to_owned())
+        );
+    }
+
+    #[test]
+    fn normalize_rejects_zero_length() {
+        assert_eq!(
+            MathValue::Vec2(Vec2::new(3.0, 0.0)).normalize(),
+            Ok(MathValue::Vec2(Vec2::X))
+        );
+        assert!(MathValue::Vec3(Vec3::ZERO).normalize().is_err());
+    }
+
+    #[test]
+    fn angle_between_vectors() {
+        let angle = MathValue::Vec3(Vec3::X)
+            .angle(&MathValue::Vec3(Vec3::Y))
+            .unwrap();
+        assert!((angle - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+    }
+}
@@ -1,2 +1,16 @@
 pub mod bevy;
+pub mod commands;
+pub mod events;
+pub mod index;
+pub mod input;
+pub mod iter;
+pub mod log;
+#[cfg(feature = "math")]
+pub mod math;
+pub mod rng;
+pub mod scalar;
+pub mod sequence;
 pub mod std;
+pub mod systems;
+pub mod time;
+pub mod timers;
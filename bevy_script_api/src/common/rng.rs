@@ -0,0 +1,210 @@
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::Mutex;
+
+/// A small, seedable pseudo random number generator (SplitMix64).
+///
+/// Not cryptographically secure, meant for gameplay and reproducible test runs or replays.
+#[derive(Clone, Debug)]
+pub struct ScriptRng {
+    state: u64,
+}
+
+impl ScriptRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Resets the generator, the same seed always produces the same sequence of numbers
+    pub fn seed(&mut self, seed: u64) {
+        self.state = seed;
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a float in the range `[0,1)`
+    pub fn float(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns an integer in the range `[min,max]`, or `None` if `min > max`
+    pub fn range(&mut self, min: i64, max: i64) -> Option<i64> {
+        if min > max {
+            return None;
+        }
+
+        // the number of possible values minus one, covers the full i64 range
+        let span = max.wrapping_sub(min) as u64;
+        if span == u64::MAX {
+            return Some(self.next_u64() as i64);
+        }
+
+        // reject values from the incomplete last bucket to avoid modulo bias
+        let n = span + 1;
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let v = self.next_u64();
+            if v >= threshold {
+                return Some(min.wrapping_add((v % n) as i64));
+            }
+        }
+    }
+}
+
+/// A handle to a random number generator exposed to scripts as `rng`.
+///
+/// The generator sits behind a mutex so handles can be used by scripts running in parallel,
+/// however if the generator is shared, the numbers each script observes then depend on the order the scripts ran in.
+#[derive(Clone, Debug)]
+pub struct ScriptRngHandle(Arc<Mutex<ScriptRng>>);
+
+impl ScriptRngHandle {
+    pub fn new(rng: ScriptRng) -> Self {
+        Self(Arc::new(Mutex::new(rng)))
+    }
+
+    pub fn seed(&self, seed: u64) {
+        self.0.lock().seed(seed)
+    }
+
+    pub fn float(&self) -> f64 {
+        self.0.lock().float()
+    }
+
+    pub fn range(&self, min: i64, max: i64) -> Option<i64> {
+        self.0.lock().range(min, max)
+    }
+}
+
+/// How random number generators are handed out to scripts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScriptRngMode {
+    /// Each script gets its own generator seeded with the base seed combined with the script's id.
+    /// Results are reproducible regardless of the order in which scripts run,
+    /// as long as scripts are loaded in the same order.
+    #[default]
+    PerScript,
+    /// All scripts share a single generator seeded with the base seed
+    Shared,
+}
+
+/// Hands out random number generators to scripts, used by the rng API providers of each language.
+#[derive(Clone, Debug)]
+pub struct ScriptRngSource {
+    mode: ScriptRngMode,
+    seed: u64,
+    shared: ScriptRngHandle,
+}
+
+impl Default for ScriptRngSource {
+    /// A per script source seeded from the system time, i.e. not reproducible
+    fn default() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Self::new(ScriptRngMode::PerScript, seed)
+    }
+}
+
+impl ScriptRngSource {
+    pub fn new(mode: ScriptRngMode, seed: u64) -> Self {
+        Self {
+            mode,
+            seed,
+            shared: ScriptRngHandle::new(ScriptRng::new(seed)),
+        }
+    }
+
+    /// Returns the generator the script with the given id should use
+    pub fn for_script(&self, sid: u32) -> ScriptRngHandle {
+        match self.mode {
+            ScriptRngMode::PerScript => ScriptRngHandle::new(ScriptRng::new(
+                self.seed ^ (sid as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15),
+            )),
+            ScriptRngMode::Shared => self.shared.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn floats(handle: &ScriptRngHandle, n: usize) -> Vec<f64> {
+        (0..n).map(|_| handle.float()).collect()
+    }
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = ScriptRng::new(42);
+        let mut b = ScriptRng::new(42);
+        let seq_a = (0..16).map(|_| a.next_u64()).collect::<Vec<_>>();
+        let seq_b = (0..16).map(|_| b.next_u64()).collect::<Vec<_>>();
+        assert_eq!(seq_a, seq_b);
+
+        let mut c = ScriptRng::new(43);
+        assert_ne!(seq_a, (0..16).map(|_| c.next_u64()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn reseeding_restarts_the_sequence() {
+        let handle = ScriptRngHandle::new(ScriptRng::new(7));
+        let first = floats(&handle, 8);
+        let ranges = (0..8).map(|_| handle.range(-5, 5)).collect::<Vec<_>>();
+
+        handle.seed(7);
+        assert_eq!(floats(&handle, 8), first);
+        assert_eq!(
+            (0..8).map(|_| handle.range(-5, 5)).collect::<Vec<_>>(),
+            ranges
+        );
+    }
+
+    #[test]
+    fn values_stay_in_range() {
+        let mut rng = ScriptRng::new(1);
+        for _ in 0..1000 {
+            let f = rng.float();
+            assert!((0.0..1.0).contains(&f));
+            assert!((-3..=3).contains(&rng.range(-3, 3).unwrap()));
+        }
+        assert_eq!(rng.range(5, 5), Some(5));
+        assert_eq!(rng.range(1, 0), None);
+        assert!(rng.range(i64::MIN, i64::MAX).is_some());
+    }
+
+    #[test]
+    fn per_script_generators_do_not_depend_on_run_order() {
+        let source = ScriptRngSource::new(ScriptRngMode::PerScript, 42);
+        let (a, b) = (source.for_script(0), source.for_script(1));
+        let a_first = floats(&a, 4);
+        let b_second = floats(&b, 4);
+        assert_ne!(a_first, b_second);
+
+        // running the scripts in the opposite order yields the same numbers for each script
+        let source = ScriptRngSource::new(ScriptRngMode::PerScript, 42);
+        let (a, b) = (source.for_script(0), source.for_script(1));
+        assert_eq!(floats(&b, 4), b_second);
+        assert_eq!(floats(&a, 4), a_first);
+    }
+
+    #[test]
+    fn shared_generator_is_seeded_once() {
+        let source = ScriptRngSource::new(ScriptRngMode::Shared, 42);
+        let (a, b) = (source.for_script(0), source.for_script(1));
+        let interleaved = [a.float(), b.float(), a.float(), b.float()];
+
+        let single = ScriptRngHandle::new(ScriptRng::new(42));
+        assert_eq!(floats(&single, 4), interleaved);
+    }
+}
@@ -0,0 +1,133 @@
+use bevy::reflect::Reflect;
+
+/// A primitive value passed between scripts and reflected values directly, skipping the type registry and proxy conversions.
+///
+/// Reading and writing primitive fields is by far the most common conversion, so script references check for these
+/// before looking up proxies (see `ScriptRef::to_lua` and `ScriptRef::apply_lua`, or their rhai equivalents).
+/// Values which aren't primitives, or which don't fit the target exactly, go through the proxies as usual.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptScalar {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+}
+
+macro_rules! read_int {
+    ($value:ident, $($ty:ty),*) => {
+        $(
+            if let Some(v) = $value.downcast_ref::<$ty>() {
+                return i64::try_from(*v).ok().map(ScriptScalar::Int);
+            }
+        )*
+    };
+}
+
+macro_rules! write_int {
+    ($value:ident, $int:ident, $($ty:ty),*) => {
+        $(
+            if let Some(v) = $value.downcast_mut::<$ty>() {
+                return <$ty>::try_from($int).map(|i| *v = i).is_ok();
+            }
+        )*
+    };
+}
+
+impl ScriptScalar {
+    /// Reads the given value if it's a primitive which a script scalar can hold exactly
+    #[inline]
+    pub fn from_reflect(value: &dyn Reflect) -> Option<Self> {
+        if let Some(v) = value.downcast_ref::<f32>() {
+            return Some(Self::Float(*v as f64));
+        }
+        if let Some(v) = value.downcast_ref::<f64>() {
+            return Some(Self::Float(*v));
+        }
+        if let Some(v) = value.downcast_ref::<bool>() {
+            return Some(Self::Bool(*v));
+        }
+        read_int!(value, i32, u32, i64, u64, usize, isize, i16, u16, i8, u8);
+        None
+    }
+
+    /// Writes the scalar into the given value if it's a primitive which can hold it exactly,
+    /// returns false without touching the value otherwise.
+    ///
+    /// Integers are written to floats, and floats with no fractional part to integers, like lua's own conversions
+    #[inline]
+    pub fn apply_to(self, value: &mut dyn Reflect) -> bool {
+        match self {
+            Self::Bool(b) => match value.downcast_mut::<bool>() {
+                Some(v) => {
+                    *v = b;
+                    true
+                }
+                None => false,
+            },
+            Self::Float(f) => {
+                if let Some(v) = value.downcast_mut::<f32>() {
+                    *v = f as f32;
+                    return true;
+                }
+                if let Some(v) = value.downcast_mut::<f64>() {
+                    *v = f;
+                    return true;
+                }
+                // casting saturates, so values out of range never compare equal to the original
+                let int = f as i64;
+                int as f64 == f && Self::Int(int).apply_to(value)
+            }
+            Self::Int(int) => {
+                if let Some(v) = value.downcast_mut::<f32>() {
+                    *v = int as f32;
+                    return true;
+                }
+                if let Some(v) = value.downcast_mut::<f64>() {
+                    *v = int as f64;
+                    return true;
+                }
+                write_int!(value, int, i32, u32, i64, u64, usize, isize, i16, u16, i8, u8);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn primitives_are_read_exactly() {
+        assert_eq!(
+            ScriptScalar::from_reflect(&1.5f32),
+            Some(ScriptScalar::Float(1.5))
+        );
+        assert_eq!(
+            ScriptScalar::from_reflect(&-3i8),
+            Some(ScriptScalar::Int(-3))
+        );
+        assert_eq!(
+            ScriptScalar::from_reflect(&true),
+            Some(ScriptScalar::Bool(true))
+        );
+        assert_eq!(ScriptScalar::from_reflect(&u64::MAX), None);
+        assert_eq!(ScriptScalar::from_reflect(&String::new()), None);
+    }
+
+    #[test]
+    fn scalars_are_written_if_they_fit() {
+        let mut v = 0u8;
+        assert!(ScriptScalar::Int(200).apply_to(&mut v));
+        assert_eq!(v, 200);
+        assert!(!ScriptScalar::Int(300).apply_to(&mut v));
+        assert!(!ScriptScalar::Int(-1).apply_to(&mut v));
+        assert!(ScriptScalar::Float(7.0).apply_to(&mut v));
+        assert!(!ScriptScalar::Float(7.5).apply_to(&mut v));
+        assert!(!ScriptScalar::Bool(true).apply_to(&mut v));
+        assert_eq!(v, 7);
+
+        let mut f = 0f32;
+        assert!(ScriptScalar::Int(2).apply_to(&mut f));
+        assert_eq!(f, 2.0);
+    }
+}
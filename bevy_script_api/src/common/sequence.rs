@@ -0,0 +1,89 @@
+use bevy::math::{
+    DQuat, DVec2, DVec3, DVec4, IVec2, IVec3, IVec4, Quat, UVec2, UVec3, UVec4, Vec2, Vec3, Vec3A,
+    Vec4,
+};
+
+/// Math types which scripts can build from, and convert to, a fixed length sequence of numbers,
+/// i.e. a `Vec3` from the lua table `{1, 2, 3}` or the rhai array `[1.0, 2.0, 3.0]`.
+///
+/// Elements are in the order of the named fields, quaternions are sequences of `x, y, z, w`.
+pub trait ScriptSequence: Sized {
+    /// The type of every element
+    type Element: Copy;
+    /// The number of elements
+    const LEN: usize;
+
+    /// Builds the value from exactly [`Self::LEN`] elements, the length is checked by [`ScriptSequence::from_sequence`]
+    fn from_elements(elements: &[Self::Element]) -> Self;
+
+    /// The elements of this value
+    fn to_elements(&self) -> Vec<Self::Element>;
+
+    /// Builds the value from the given elements, failing if there aren't exactly [`Self::LEN`] of them
+    fn from_sequence(elements: &[Self::Element]) -> Result<Self, String> {
+        if elements.len() == Self::LEN {
+            Ok(Self::from_elements(elements))
+        } else {
+            Err(format!(
+                "expected a sequence of {} elements, got {}",
+                Self::LEN,
+                elements.len()
+            ))
+        }
+    }
+}
+
+macro_rules! impl_script_sequence {
+    ($element:ty, $len:literal, $($type:ty),*) => {
+        $(
+            impl ScriptSequence for $type {
+                type Element = $element;
+                const LEN: usize = $len;
+
+                fn from_elements(elements: &[Self::Element]) -> Self {
+                    <$type>::from_slice(elements)
+                }
+
+                fn to_elements(&self) -> Vec<Self::Element> {
+                    self.to_array().to_vec()
+                }
+            }
+        )*
+    };
+}
+
+impl_script_sequence!(f32, 2, Vec2);
+impl_script_sequence!(f32, 3, Vec3, Vec3A);
+impl_script_sequence!(f32, 4, Vec4, Quat);
+impl_script_sequence!(f64, 2, DVec2);
+impl_script_sequence!(f64, 3, DVec3);
+impl_script_sequence!(f64, 4, DVec4, DQuat);
+impl_script_sequence!(i32, 2, IVec2);
+impl_script_sequence!(i32, 3, IVec3);
+impl_script_sequence!(i32, 4, IVec4);
+impl_script_sequence!(u32, 2, UVec2);
+impl_script_sequence!(u32, 3, UVec3);
+impl_script_sequence!(u32, 4, UVec4);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sequences_must_match_the_length() {
+        assert_eq!(
+            Vec3::from_sequence(&[1.0, 2.0, 3.0]),
+            Ok(Vec3::new(1.0, 2.0, 3.0))
+        );
+        assert_eq!(
+            Quat::from_sequence(&[0.0, 0.0, 0.0, 1.0]),
+            Ok(Quat::IDENTITY)
+        );
+        assert_eq!(Quat::IDENTITY.to_elements(), vec![0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(
+            Vec2::from_sequence(&[1.0, 2.0, 3.0]),
+            Err("expected a sequence of 2 elements, got 3".to_owned())
+        );
+        assert!(IVec4::from_sequence(&[1]).is_err());
+    }
+}
@@ -1,11 +1,14 @@
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
 use bevy::reflect::FromReflect;
+use bevy_mod_scripting_core::world::WorldPointer;
 
-use crate::{error::ReflectionError, ScriptRef, ValueIndex};
+use crate::{error::ReflectionError, script_ref::ScriptOwnedValue, ScriptRef, ValueIndex};
 
 pub struct ScriptVec<T> {
     pub(crate) ref_: ScriptRef,
+    /// Keeps the vector alive if it's owned by the script
+    owner: Option<Arc<ScriptOwnedValue>>,
     _ph: PhantomData<T>,
 }
 
@@ -13,6 +16,7 @@ impl<T> Clone for ScriptVec<T> {
     fn clone(&self) -> Self {
         Self {
             ref_: self.ref_.clone(),
+            owner: self.owner.clone(),
             _ph: PhantomData,
         }
     }
@@ -43,6 +47,17 @@ impl<T: FromReflect> ScriptVec<T> {
     pub fn new_ref(ref_: ScriptRef) -> Self {
         Self {
             ref_,
+            owner: None,
+            _ph: PhantomData,
+        }
+    }
+
+    /// Creates a vec proxy which owns the given vector, i.e. one returned from a rust function
+    pub fn new_owned(vec: Vec<T>, world_ptr: WorldPointer) -> Self {
+        let owner = Arc::new(ScriptOwnedValue::new(Box::new(vec)));
+        Self {
+            ref_: owner.script_ref(world_ptr),
+            owner: Some(owner),
             _ph: PhantomData,
         }
     }
@@ -84,6 +99,42 @@ impl<T: FromReflect> ScriptVec<T> {
         self.ref_
             .get_mut_typed(|s: &mut Vec<T>| Ok(s.remove(idx)))?
     }
+
+    /// Retains only the elements for which the predicate returns `true`.
+    ///
+    /// The predicate receives copies of the elements and runs without any locks held on the vector,
+    /// so it may access the world (as script callbacks do). If the length of the vector changes while the predicates run
+    /// nothing is removed and an error is returned.
+    pub fn retain<E: From<ReflectionError>>(
+        &mut self,
+        mut predicate: impl FnMut(T) -> Result<bool, E>,
+    ) -> Result<(), E> {
+        let len = self.len()?;
+        let mut keep = Vec::with_capacity(len);
+
+        for idx in 0..len {
+            let elem = self
+                .ref_
+                .get_typed(|s: &Vec<T>| s.get(idx).and_then(|v| T::from_reflect(v)))?
+                .ok_or_else(|| {
+                    ReflectionError::Other(format!("Could not copy element at index {idx}"))
+                })?;
+            keep.push(predicate(elem)?);
+        }
+
+        self.ref_.get_mut_typed(|s: &mut Vec<T>| {
+            if s.len() != len {
+                return Err(ReflectionError::Other(
+                    "Vec was modified while evaluating the retain predicate".to_owned(),
+                ));
+            }
+            let mut keep = keep.into_iter();
+            s.retain(|_| keep.next().unwrap_or(true));
+            Ok(())
+        })??;
+
+        Ok(())
+    }
 }
 
 impl<T> ValueIndex<usize> for ScriptVec<T> {
@@ -0,0 +1,184 @@
+use std::{collections::BTreeMap, marker::PhantomData, sync::Arc};
+
+use bevy::prelude::*;
+use bevy_mod_scripting_core::prelude::*;
+use parking_lot::Mutex;
+
+use crate::common::events::HookEvent;
+
+/// The hooks registered as per frame systems by scripts of a single script host, whose events are of type `E`.
+///
+/// Every frame, [`script_system_system`] sends an event with the configured priority for each registered hook,
+/// so they are handled by whichever handler set covers that priority, in the same order as any other event.
+#[derive(Resource)]
+pub struct ScriptSystems<E> {
+    hooks: Arc<Mutex<BTreeMap<u32, Vec<String>>>>,
+    priority: u32,
+    _ph: PhantomData<fn() -> E>,
+}
+
+impl<E> Clone for ScriptSystems<E> {
+    fn clone(&self) -> Self {
+        Self {
+            hooks: self.hooks.clone(),
+            priority: self.priority,
+            _ph: PhantomData,
+        }
+    }
+}
+
+impl<E> Default for ScriptSystems<E> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<E> ScriptSystems<E> {
+    /// Creates a new set of systems whose events are sent with the given priority
+    pub fn new(priority: u32) -> Self {
+        Self {
+            hooks: Default::default(),
+            priority,
+            _ph: PhantomData,
+        }
+    }
+
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    /// Returns the handle the script with the given id uses to register its systems
+    pub fn for_script(&self, sid: u32) -> ScriptSystemHandle {
+        ScriptSystemHandle {
+            hooks: self.hooks.clone(),
+            sid,
+        }
+    }
+
+    /// Removes all systems of the script with the given id
+    pub fn clear_script(&self, sid: u32) {
+        self.hooks.lock().remove(&sid);
+    }
+
+    /// Removes all systems for which the predicate on their script id returns false
+    pub fn retain_scripts<F: FnMut(u32) -> bool>(&self, mut f: F) {
+        self.hooks.lock().retain(|sid, _| f(*sid))
+    }
+
+    /// Returns the script ids and hooks of all registered systems, in order of script id and then registration
+    pub fn hooks(&self) -> Vec<(u32, String)> {
+        self.hooks
+            .lock()
+            .iter()
+            .flat_map(|(sid, hooks)| hooks.iter().map(|hook| (*sid, hook.clone())))
+            .collect()
+    }
+}
+
+/// A handle to the systems of a single script, used by the `register_system` family of script functions.
+///
+/// Systems call a hook on the script by name every frame, if the script doesn't define the hook nothing happens.
+#[derive(Clone)]
+pub struct ScriptSystemHandle {
+    hooks: Arc<Mutex<BTreeMap<u32, Vec<String>>>>,
+    sid: u32,
+}
+
+impl std::fmt::Debug for ScriptSystemHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptSystemHandle")
+            .field("sid", &self.sid)
+            .finish()
+    }
+}
+
+impl ScriptSystemHandle {
+    /// Calls the given hook every frame, returns false if it was already registered
+    pub fn register(&self, hook_name: String) -> bool {
+        let mut hooks = self.hooks.lock();
+        let hooks = hooks.entry(self.sid).or_default();
+        if hooks.contains(&hook_name) {
+            return false;
+        }
+        hooks.push(hook_name);
+        true
+    }
+
+    /// Stops calling the given hook every frame, returns true if it was registered
+    pub fn unregister(&self, hook_name: &str) -> bool {
+        let mut hooks = self.hooks.lock();
+        let Some(script_hooks) = hooks.get_mut(&self.sid) else {
+            return false;
+        };
+        let len = script_hooks.len();
+        script_hooks.retain(|hook| hook != hook_name);
+        let removed = script_hooks.len() != len;
+        if script_hooks.is_empty() {
+            hooks.remove(&self.sid);
+        }
+        removed
+    }
+
+    /// Returns true if the given hook is called every frame
+    pub fn is_registered(&self, hook_name: &str) -> bool {
+        self.hooks
+            .lock()
+            .get(&self.sid)
+            .map_or(false, |hooks| hooks.iter().any(|hook| hook == hook_name))
+    }
+}
+
+/// Sends an event for every system registered by scripts of the given script host.
+///
+/// Systems belonging to scripts which no longer exist are dropped.
+pub fn script_system_system<H: ScriptHost>(
+    systems: Res<ScriptSystems<H::ScriptEvent>>,
+    contexts: Res<ScriptContexts<H::ScriptContext>>,
+    mut events: PriorityEventWriter<H::ScriptEvent>,
+) where
+    H::ScriptEvent: HookEvent,
+{
+    systems.retain_scripts(|sid| contexts.script_owner(sid).is_some());
+
+    events.send_batch(
+        systems
+            .hooks()
+            .into_iter()
+            .map(|(sid, hook_name)| H::ScriptEvent::hook_event(hook_name, sid)),
+        systems.priority(),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn systems_are_registered_once_per_script() {
+        let systems = ScriptSystems::<()>::default();
+        assert!(systems.for_script(1).register("on_tick".to_owned()));
+        assert!(!systems.for_script(1).register("on_tick".to_owned()));
+        assert!(systems.for_script(0).register("on_tick".to_owned()));
+        assert!(systems.for_script(0).register("on_late_tick".to_owned()));
+
+        assert_eq!(
+            systems.hooks(),
+            vec![
+                (0, "on_tick".to_owned()),
+                (0, "on_late_tick".to_owned()),
+                (1, "on_tick".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn scripts_only_unregister_their_own_systems() {
+        let systems = ScriptSystems::<()>::default();
+        systems.for_script(0).register("on_tick".to_owned());
+
+        assert!(!systems.for_script(1).unregister("on_tick"));
+        assert!(systems.for_script(0).is_registered("on_tick"));
+        assert!(systems.for_script(0).unregister("on_tick"));
+        assert!(systems.hooks().is_empty());
+    }
+}
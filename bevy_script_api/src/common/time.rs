@@ -0,0 +1,80 @@
+use bevy::{core::FrameCount, ecs::system::Resource, time::Time};
+use bevy_mod_scripting_core::{prelude::ScriptError, world::WorldPointer};
+
+/// Read only access to the frame timing, exposed to scripts as `time`.
+///
+/// The values are those of the `Time` and `FrameCount` resources, updated by bevy's `TimePlugin` and `FrameCountPlugin` once per frame.
+#[derive(Clone, Debug)]
+pub struct ScriptTime(WorldPointer);
+
+impl ScriptTime {
+    pub fn new(world: WorldPointer) -> Self {
+        Self(world)
+    }
+
+    fn with_resource<R: Resource, O>(
+        &self,
+        plugin: &str,
+        f: impl FnOnce(&R) -> O,
+    ) -> Result<O, ScriptError> {
+        let w = self.0.read();
+        w.get_resource::<R>().map(f).ok_or_else(|| {
+            ScriptError::new_world_access(
+                std::any::type_name::<R>(),
+                format!("Resource is missing, is the `{plugin}` added?"),
+            )
+        })
+    }
+
+    /// The time in seconds elapsed between the previous frame and the current one
+    pub fn delta_seconds(&self) -> Result<f64, ScriptError> {
+        self.with_resource("TimePlugin", Time::delta_seconds_f64)
+    }
+
+    /// The time in seconds elapsed since the app started
+    pub fn elapsed_seconds(&self) -> Result<f64, ScriptError> {
+        self.with_resource("TimePlugin", Time::elapsed_seconds_f64)
+    }
+
+    /// The number of frames since the app started, wrapping around on overflow
+    pub fn frame_count(&self) -> Result<u32, ScriptError> {
+        self.with_resource("FrameCountPlugin", |count: &FrameCount| count.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use bevy::{core::FrameCount, ecs::world::World, time::Time};
+    use bevy_mod_scripting_core::world::WorldPointer;
+
+    use super::ScriptTime;
+
+    #[test]
+    fn values_are_read_from_the_resources() {
+        let mut world = World::new();
+        let mut time = Time::default();
+        let startup = time.startup();
+        time.update_with_instant(startup + Duration::from_secs(1));
+        time.update_with_instant(startup + Duration::from_millis(1500));
+        world.insert_resource(time);
+        world.insert_resource(FrameCount(3));
+
+        // safety: the world outlives the pointer
+        let script_time = ScriptTime::new(unsafe { WorldPointer::new(&mut world) });
+        assert_eq!(script_time.delta_seconds().unwrap(), 0.5);
+        assert_eq!(script_time.elapsed_seconds().unwrap(), 1.5);
+        assert_eq!(script_time.frame_count().unwrap(), 3);
+    }
+
+    #[test]
+    fn missing_resources() {
+        let mut world = World::new();
+        let script_time = ScriptTime::new(unsafe { WorldPointer::new(&mut world) });
+        let err = script_time.delta_seconds().unwrap_err().to_string();
+        assert!(err.contains("TimePlugin"), "{err}");
+        let err = script_time.frame_count().unwrap_err().to_string();
+        assert!(err.contains("FrameCountPlugin"), "{err}");
+    }
+}
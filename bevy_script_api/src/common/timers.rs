@@ -0,0 +1,253 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use bevy::prelude::*;
+use bevy_mod_scripting_core::prelude::*;
+use parking_lot::Mutex;
+
+use crate::common::events::HookEvent;
+
+#[derive(Debug)]
+struct ScriptTimer {
+    id: u64,
+    sid: u32,
+    hook_name: String,
+    remaining: f64,
+    /// `Some` for repeating timers
+    interval: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct TimerQueue {
+    next_id: u64,
+    timers: Vec<ScriptTimer>,
+}
+
+impl TimerQueue {
+    /// Adds a timer calling the hook named by `hook_name` given the id of the new timer
+    fn insert(
+        &mut self,
+        sid: u32,
+        hook_name: impl FnOnce(u64) -> String,
+        delay: f64,
+        interval: Option<f64>,
+    ) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.timers.push(ScriptTimer {
+            id,
+            sid,
+            hook_name: hook_name(id),
+            remaining: delay,
+            interval,
+        });
+        id
+    }
+
+    /// Advances all timers by the given amount of seconds, returning the script ids and hooks which are due.
+    ///
+    /// One shot timers which are due are removed, repeating timers fire at most once per call.
+    fn advance(&mut self, delta: f64) -> Vec<(u32, String)> {
+        let mut due = Vec::default();
+        self.timers.retain_mut(|timer| {
+            timer.remaining -= delta;
+            if timer.remaining > 0.0 {
+                return true;
+            }
+
+            due.push((timer.sid, timer.hook_name.clone()));
+            match timer.interval {
+                Some(interval) => {
+                    timer.remaining = (timer.remaining + interval).max(0.0);
+                    true
+                }
+                None => false,
+            }
+        });
+        due
+    }
+}
+
+/// The timers scheduled by scripts of a single script host, whose events are of type `E`.
+///
+/// Due timers are sent as events with the configured priority by [`script_timer_system`],
+/// so they are handled by whichever handler set covers that priority, in the same order as any other event.
+#[derive(Resource)]
+pub struct ScriptTimers<E> {
+    queue: Arc<Mutex<TimerQueue>>,
+    priority: u32,
+    _ph: PhantomData<fn() -> E>,
+}
+
+impl<E> Clone for ScriptTimers<E> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: self.queue.clone(),
+            priority: self.priority,
+            _ph: PhantomData,
+        }
+    }
+}
+
+impl<E> Default for ScriptTimers<E> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<E> ScriptTimers<E> {
+    /// Creates a new set of timers whose events are sent with the given priority
+    pub fn new(priority: u32) -> Self {
+        Self {
+            queue: Default::default(),
+            priority,
+            _ph: PhantomData,
+        }
+    }
+
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    /// Returns the handle the script with the given id uses to schedule its timers
+    pub fn for_script(&self, sid: u32) -> ScriptTimerHandle {
+        ScriptTimerHandle {
+            queue: self.queue.clone(),
+            sid,
+        }
+    }
+
+    /// Removes all timers for which the predicate on their script id returns false
+    pub fn retain_scripts<F: FnMut(u32) -> bool>(&self, mut f: F) {
+        self.queue.lock().timers.retain(|timer| f(timer.sid))
+    }
+
+    /// Advances all timers by the given amount of seconds, returning the script ids and hooks which are due
+    pub fn advance(&self, delta: f64) -> Vec<(u32, String)> {
+        self.queue.lock().advance(delta)
+    }
+}
+
+/// A handle to the timers of a single script, exposed to scripts as `timers`.
+///
+/// Timers call a hook on the script by name once they are due, if the script doesn't define the hook nothing happens.
+#[derive(Clone)]
+pub struct ScriptTimerHandle {
+    queue: Arc<Mutex<TimerQueue>>,
+    sid: u32,
+}
+
+impl std::fmt::Debug for ScriptTimerHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptTimerHandle")
+            .field("sid", &self.sid)
+            .finish()
+    }
+}
+
+impl ScriptTimerHandle {
+    /// Calls the given hook once after `delay` seconds, returns the id of the timer
+    pub fn after(&self, delay: f64, hook_name: String) -> u64 {
+        self.after_with(delay, |_| hook_name)
+    }
+
+    /// Like [`Self::after`] with the hook named after the id of the timer,
+    /// i.e. for hooks defined by the script host to call functions scheduled by scripts
+    pub fn after_with(&self, delay: f64, hook_name: impl FnOnce(u64) -> String) -> u64 {
+        self.queue.lock().insert(self.sid, hook_name, delay, None)
+    }
+
+    /// Calls the given hook every `interval` seconds, returns the id of the timer
+    /// or `None` if the interval is not positive
+    pub fn every(&self, interval: f64, hook_name: String) -> Option<u64> {
+        self.every_with(interval, |_| hook_name)
+    }
+
+    /// Like [`Self::every`] with the hook named after the id of the timer
+    pub fn every_with(&self, interval: f64, hook_name: impl FnOnce(u64) -> String) -> Option<u64> {
+        if interval <= 0.0 {
+            return None;
+        }
+        Some(
+            self.queue
+                .lock()
+                .insert(self.sid, hook_name, interval, Some(interval)),
+        )
+    }
+
+    /// Cancels one of this script's timers, returns true if it was still pending
+    pub fn cancel(&self, id: u64) -> bool {
+        let mut queue = self.queue.lock();
+        let len = queue.timers.len();
+        queue
+            .timers
+            .retain(|timer| timer.id != id || timer.sid != self.sid);
+        queue.timers.len() != len
+    }
+}
+
+/// Ticks the timers of the given script host against [`Time`] and sends an event for every timer which is due.
+///
+/// Timers belonging to scripts which no longer exist are dropped.
+pub fn script_timer_system<H: ScriptHost>(
+    time: Res<Time>,
+    timers: Res<ScriptTimers<H::ScriptEvent>>,
+    contexts: Res<ScriptContexts<H::ScriptContext>>,
+    mut events: PriorityEventWriter<H::ScriptEvent>,
+) where
+    H::ScriptEvent: HookEvent,
+{
+    timers.retain_scripts(|sid| contexts.script_owner(sid).is_some());
+
+    let due = timers.advance(time.delta_seconds_f64());
+    events.send_batch(
+        due.into_iter()
+            .map(|(sid, hook_name)| H::ScriptEvent::hook_event(hook_name, sid)),
+        timers.priority(),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn one_shot_timers_fire_once() {
+        let timers = ScriptTimers::<()>::default();
+        timers.for_script(0).after(1.0, "on_timer".to_owned());
+
+        assert!(timers.advance(0.5).is_empty());
+        assert_eq!(timers.advance(0.5), vec![(0, "on_timer".to_owned())]);
+        assert!(timers.advance(1.0).is_empty());
+    }
+
+    #[test]
+    fn repeating_timers_fire_every_interval() {
+        let timers = ScriptTimers::<()>::default();
+        timers.for_script(1).every(1.0, "on_tick".to_owned());
+
+        assert!(timers.advance(0.75).is_empty());
+        assert_eq!(timers.advance(0.5).len(), 1);
+        assert!(timers.advance(0.5).is_empty());
+        assert_eq!(timers.advance(0.25).len(), 1);
+    }
+
+    #[test]
+    fn hooks_can_be_named_after_their_timer() {
+        let timers = ScriptTimers::<()>::default();
+        let id = timers
+            .for_script(0)
+            .after_with(1.0, |id| format!("timer_{id}"));
+
+        assert_eq!(timers.advance(1.0), vec![(0, format!("timer_{id}"))]);
+    }
+
+    #[test]
+    fn scripts_only_cancel_their_own_timers() {
+        let timers = ScriptTimers::<()>::default();
+        let id = timers.for_script(0).after(1.0, "on_timer".to_owned());
+
+        assert!(!timers.for_script(1).cancel(id));
+        assert!(timers.for_script(0).cancel(id));
+        assert!(timers.advance(1.0).is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 
+use bevy_mod_scripting_core::prelude::ScriptError;
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone)]
@@ -19,6 +20,17 @@ pub enum ReflectionError {
     Other(String),
 }
 
+impl From<ReflectionError> for ScriptError {
+    fn from(e: ReflectionError) -> Self {
+        match e {
+            ReflectionError::CannotDowncast { ref to, .. } => {
+                ScriptError::new_conversion(to, e.to_string())
+            }
+            e => ScriptError::new_other(e),
+        }
+    }
+}
+
 #[cfg(feature = "lua")]
 impl From<ReflectionError> for bevy_mod_scripting_lua::tealr::mlu::mlua::Error {
     fn from(e: ReflectionError) -> Self {
@@ -1398,6 +1398,14 @@ impl_script_newtype! {
     )
     lua impl
     {
+        /// Returns `false` if the entity or the ancestor it inherits its visibility from is hidden, entities without a `Visibility` component inherit it.
+        "is_visible" => |ctx,s,()| {crate::common::bevy::ScriptWorld::new(ctx.get_world()?).is_visible(s.inner()?).map_err(|e| bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(e.to_string()))};
+        /// Hides the entity or makes it inherit its parent's visibility, inserts a `Visibility` component at the end of the frame if it's missing.
+        "set_visible" => |ctx,s,visible: bool| {crate::common::bevy::ScriptWorld::new(ctx.get_world()?).set_visible(s.inner()?,visible).map_err(|e| bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(e.to_string()))};
+        /// Retrieves a reference to the entity's `Transform`, or `nil` if it has none.
+        "get_transform" => |ctx,s,()| {crate::common::bevy::ScriptWorld::new(ctx.get_world()?).get_transform(s.inner()?).map(|t| t.map(LuaTransform::new_ref)).map_err(|e| bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(e.to_string()))};
+        /// Sets the entity's `Transform`, inserts it at the end of the frame if it's missing.
+        "set_transform" => |ctx,s,transform: LuaTransform| {crate::common::bevy::ScriptWorld::new(ctx.get_world()?).set_transform(s.inner()?,transform.inner()?).map_err(|e| bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(e.to_string()))};
     }
 }
 impl_script_newtype! {
@@ -2119,6 +2127,7 @@ impl_script_newtype! {
     )
     + Fields
     (
+        0: Wrapped(Color),
     )
     + BinOps
     (
@@ -2235,6 +2244,7 @@ impl_script_newtype! {
     )
     + Fields
     (
+        0: Raw(ReflectedValue),
     )
     + BinOps
     (
@@ -3356,6 +3366,14 @@ impl_script_newtype! {
         ///Will panic if `self` is zero length when `glam_assert` is enabled.
         normalize(self:) -> self,
 
+        ///Returns `self` normalized to length 1.0 if possible, else returns `None`.
+        ///
+        ///In particular, if the input is zero (or very close to zero), or non-finite,
+        ///the result of this operation will be `None`.
+        ///
+        ///See also [`Self::normalize_or_zero`].
+        try_normalize(self:) -> Wrapped(Option<Vec2>),
+
         ///Returns `self` normalized to length 1.0 if possible, else returns zero.
         ///
         ///In particular, if the input is zero (or very close to zero), or non-finite,
@@ -3538,10 +3556,13 @@ impl_script_newtype! {
     (
         Neg self -> self
     )
+    + Index
+    (
+        mut Raw(usize) -> Raw(f32),
+    )
+    + Sequence
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f32)| {s.val_mut(|s| Ok(s[idx] = val))?};
     }
 }
 impl_script_newtype! {
@@ -3714,6 +3735,14 @@ impl_script_newtype! {
         ///Will panic if `self` is zero length when `glam_assert` is enabled.
         normalize(self:) -> self,
 
+        ///Returns `self` normalized to length 1.0 if possible, else returns `None`.
+        ///
+        ///In particular, if the input is zero (or very close to zero), or non-finite,
+        ///the result of this operation will be `None`.
+        ///
+        ///See also [`Self::normalize_or_zero`].
+        try_normalize(self:) -> Wrapped(Option<Vec3>),
+
         ///Returns `self` normalized to length 1.0 if possible, else returns zero.
         ///
         ///In particular, if the input is zero (or very close to zero), or non-finite,
@@ -3896,10 +3925,13 @@ impl_script_newtype! {
     (
         Neg self -> self
     )
+    + Index
+    (
+        mut Raw(usize) -> Raw(f32),
+    )
+    + Sequence
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f32)| {s.val_mut(|s| Ok(s[idx] = val))?};
     }
 }
 impl_script_newtype! {
@@ -4077,6 +4109,14 @@ impl_script_newtype! {
         ///Will panic if `self` is zero length when `glam_assert` is enabled.
         normalize(self:) -> self,
 
+        ///Returns `self` normalized to length 1.0 if possible, else returns `None`.
+        ///
+        ///In particular, if the input is zero (or very close to zero), or non-finite,
+        ///the result of this operation will be `None`.
+        ///
+        ///See also [`Self::normalize_or_zero`].
+        try_normalize(self:) -> Wrapped(Option<Vec3A>),
+
         ///Returns `self` normalized to length 1.0 if possible, else returns zero.
         ///
         ///In particular, if the input is zero (or very close to zero), or non-finite,
@@ -4256,10 +4296,13 @@ impl_script_newtype! {
     (
         Neg self -> self
     )
+    + Index
+    (
+        mut Raw(usize) -> Raw(f32),
+    )
+    + Sequence
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f32)| {s.val_mut(|s| Ok(s[idx] = val))?};
     }
 }
 impl_script_newtype! {
@@ -4430,6 +4473,14 @@ impl_script_newtype! {
         ///Will panic if `self` is zero length when `glam_assert` is enabled.
         normalize(self:) -> self,
 
+        ///Returns `self` normalized to length 1.0 if possible, else returns `None`.
+        ///
+        ///In particular, if the input is zero (or very close to zero), or non-finite,
+        ///the result of this operation will be `None`.
+        ///
+        ///See also [`Self::normalize_or_zero`].
+        try_normalize(self:) -> Wrapped(Option<Vec4>),
+
         ///Returns `self` normalized to length 1.0 if possible, else returns zero.
         ///
         ///In particular, if the input is zero (or very close to zero), or non-finite,
@@ -4588,10 +4639,13 @@ impl_script_newtype! {
     (
         Neg self -> self
     )
+    + Index
+    (
+        mut Raw(usize) -> Raw(f32),
+    )
+    + Sequence
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f32)| {s.val_mut(|s| Ok(s[idx] = val))?};
     }
 }
 impl_script_newtype! {
@@ -4971,6 +5025,14 @@ impl_script_newtype! {
         ///Will panic if `self` is zero length when `glam_assert` is enabled.
         normalize(self:) -> self,
 
+        ///Returns `self` normalized to length 1.0 if possible, else returns `None`.
+        ///
+        ///In particular, if the input is zero (or very close to zero), or non-finite,
+        ///the result of this operation will be `None`.
+        ///
+        ///See also [`Self::normalize_or_zero`].
+        try_normalize(self:) -> Wrapped(Option<DVec2>),
+
         ///Returns `self` normalized to length 1.0 if possible, else returns zero.
         ///
         ///In particular, if the input is zero (or very close to zero), or non-finite,
@@ -5153,10 +5215,13 @@ impl_script_newtype! {
     (
         Neg self -> self
     )
+    + Index
+    (
+        mut Raw(usize) -> Raw(f64),
+    )
+    + Sequence
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f64)| {s.val_mut(|s| Ok(s[idx] = val))?};
     }
 }
 impl_script_newtype! {
@@ -5329,6 +5394,14 @@ impl_script_newtype! {
         ///Will panic if `self` is zero length when `glam_assert` is enabled.
         normalize(self:) -> self,
 
+        ///Returns `self` normalized to length 1.0 if possible, else returns `None`.
+        ///
+        ///In particular, if the input is zero (or very close to zero), or non-finite,
+        ///the result of this operation will be `None`.
+        ///
+        ///See also [`Self::normalize_or_zero`].
+        try_normalize(self:) -> Wrapped(Option<DVec3>),
+
         ///Returns `self` normalized to length 1.0 if possible, else returns zero.
         ///
         ///In particular, if the input is zero (or very close to zero), or non-finite,
@@ -5514,10 +5587,13 @@ impl_script_newtype! {
     (
         Neg self -> self
     )
+    + Index
+    (
+        mut Raw(usize) -> Raw(f64),
+    )
+    + Sequence
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f64)| {s.val_mut(|s| Ok(s[idx] = val))?};
     }
 }
 impl_script_newtype! {
@@ -5684,6 +5760,14 @@ impl_script_newtype! {
         ///Will panic if `self` is zero length when `glam_assert` is enabled.
         normalize(self:) -> self,
 
+        ///Returns `self` normalized to length 1.0 if possible, else returns `None`.
+        ///
+        ///In particular, if the input is zero (or very close to zero), or non-finite,
+        ///the result of this operation will be `None`.
+        ///
+        ///See also [`Self::normalize_or_zero`].
+        try_normalize(self:) -> Wrapped(Option<DVec4>),
+
         ///Returns `self` normalized to length 1.0 if possible, else returns zero.
         ///
         ///In particular, if the input is zero (or very close to zero), or non-finite,
@@ -5846,10 +5930,13 @@ impl_script_newtype! {
     (
         Neg self -> self
     )
+    + Index
+    (
+        mut Raw(usize) -> Raw(f64),
+    )
+    + Sequence
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,f64)| {s.val_mut(|s| Ok(s[idx] = val))?};
     }
 }
 impl_script_newtype! {
@@ -6021,10 +6108,13 @@ impl_script_newtype! {
     (
         Neg self -> self
     )
+    + Index
+    (
+        mut Raw(usize) -> Raw(i32),
+    )
+    + Sequence
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,i32)| {s.val_mut(|s| Ok(s[idx] = val))?};
     }
 }
 impl_script_newtype! {
@@ -6196,10 +6286,13 @@ impl_script_newtype! {
     (
         Neg self -> self
     )
+    + Index
+    (
+        mut Raw(usize) -> Raw(i32),
+    )
+    + Sequence
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,i32)| {s.val_mut(|s| Ok(s[idx] = val))?};
     }
 }
 impl_script_newtype! {
@@ -6363,10 +6456,13 @@ impl_script_newtype! {
     (
         Neg self -> self
     )
+    + Index
+    (
+        mut Raw(usize) -> Raw(i32),
+    )
+    + Sequence
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,i32)| {s.val_mut(|s| Ok(s[idx] = val))?};
     }
 }
 impl_script_newtype! {
@@ -6506,10 +6602,13 @@ impl_script_newtype! {
     + UnaryOps
     (
     )
+    + Index
+    (
+        mut Raw(usize) -> Raw(u32),
+    )
+    + Sequence
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,u32)| {s.val_mut(|s| Ok(s[idx] = val))?};
     }
 }
 impl_script_newtype! {
@@ -6661,10 +6760,13 @@ impl_script_newtype! {
     + UnaryOps
     (
     )
+    + Index
+    (
+        mut Raw(usize) -> Raw(u32),
+    )
+    + Sequence
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,u32)| {s.val_mut(|s| Ok(s[idx] = val))?};
     }
 }
 impl_script_newtype! {
@@ -6808,10 +6910,13 @@ impl_script_newtype! {
     + UnaryOps
     (
     )
+    + Index
+    (
+        mut Raw(usize) -> Raw(u32),
+    )
+    + Sequence
     lua impl
     {
-        (MetaMethod::Index) => |_,s,idx: usize| {Ok(s.inner()?[idx])};
-        mut (MetaMethod::NewIndex) => |_,s,(idx,val): (usize,u32)| {s.val_mut(|s| Ok(s[idx] = val))?};
     }
 }
 impl_script_newtype! {
@@ -9325,6 +9430,7 @@ impl_script_newtype! {
     (
         Neg self -> self
     )
+    + Sequence
     lua impl
     {
     }
@@ -9585,6 +9691,7 @@ impl_script_newtype! {
     (
         Neg self -> self
     )
+    + Sequence
     lua impl
     {
     }
@@ -10083,7 +10190,7 @@ impl APIProvider for LuaBevyAPIProvider {
             .get_mut()
             .expect("Unable to acquire lock on Lua context");
         bevy_mod_scripting_lua::tealr::mlu::set_global_env(BevyAPIGlobals, ctx)
-            .map_err(|e| ScriptError::Other(e.to_string()))
+            .map_err(<ScriptError as bevy_mod_scripting_lua::LuaScriptError>::from_lua)
     }
     fn get_doc_fragment(&self) -> Option<Self::DocTarget> {
         Some(LuaDocFragment::new("BevyAPI", |tw| {
@@ -10252,6 +10359,8 @@ impl APIProvider for LuaBevyAPIProvider {
 			.process_type::<bevy_mod_scripting_lua::tealr::mlu::UserDataProxy<crate::lua::bevy::LuaScriptData>>()
 			.process_type::<crate::lua::bevy::LuaTypeRegistration>()
 			.process_type::<crate::lua::std::LuaVec<T>>()
+			.process_type::<crate::lua::iter::LuaIter>()
+			.process_type::<crate::lua::bevy::LuaQueryResult>()
         }))
     }
     fn setup_script(
@@ -10287,6 +10396,7 @@ impl APIProvider for LuaBevyAPIProvider {
             .map_err(ScriptError::new_other)
     }
     fn register_with_app(&self, app: &mut App) {
+        crate::common::bevy::register_script_commands(app);
         app.register_foreign_lua_type::<AlignContent>();
         app.register_foreign_lua_type::<AlignItems>();
         app.register_foreign_lua_type::<AlignSelf>();
@@ -15,29 +15,65 @@ pub mod wrappers;
 pub use {script_ref::*, sub_reflect::*};
 
 pub mod prelude {
+    #[cfg(all(feature = "lua", feature = "math"))]
+    pub use crate::lua::math::LuaMathAPIProvider;
     #[cfg(feature = "lua")]
     pub use crate::{
         impl_lua_newtype,
         lua::{
-            bevy::LuaBevyAPIProvider, std::LuaVec, FromLuaProxy, LuaProxyable, ReflectLuaProxyable,
-            ToLuaProxy,
+            bevy::LuaBevyAPIProvider, commands::LuaCommandsAPIProvider, input::LuaInputAPIProvider,
+            iter::LuaIter, log::LuaLogAPIProvider, rng::LuaRngAPIProvider, std::LuaVec,
+            systems::LuaSystemAPIProvider, time::LuaTimeAPIProvider, timers::LuaTimerAPIProvider,
+            FromLuaProxy, LuaProxyable, ReflectLuaProxyable, ToLuaProxy,
         },
+        LuaProxyable,
     };
 
+    #[cfg(all(feature = "rhai", feature = "math"))]
+    pub use crate::rhai::math::RhaiMathAPIProvider;
     #[cfg(feature = "rhai")]
-    pub use crate::rhai::{
-        bevy::RhaiBevyAPIProvider,
-        std::{RhaiCopy, RhaiVec},
-        FromRhaiProxy, ReflectRhaiProxyable, RhaiProxyable, ToRhaiProxy,
+    pub use crate::{
+        impl_rhai_newtype,
+        rhai::{
+            bevy::RhaiBevyAPIProvider,
+            commands::RhaiCommandsAPIProvider,
+            input::RhaiInputAPIProvider,
+            iter::RhaiIter,
+            log::RhaiLogAPIProvider,
+            rng::RhaiRngAPIProvider,
+            std::{RhaiCopy, RhaiVec},
+            systems::RhaiSystemAPIProvider,
+            time::RhaiTimeAPIProvider,
+            timers::RhaiTimerAPIProvider,
+            FromRhaiProxy, ReflectRhaiProxyable, RegisterRhaiWrapper, RhaiProxyable, RhaiWrapper,
+            ToRhaiProxy,
+        },
     };
 
-    pub use crate::{common::bevy::GetWorld, impl_script_newtype, ValueIndex};
+    pub use crate::{
+        common::{
+            bevy::GetWorld,
+            commands::ScriptCommands,
+            events::{AddScriptableEvent, ReflectEvent},
+            input::ScriptInput,
+            iter::ScriptIter,
+            log::ScriptLogger,
+            rng::{ScriptRngMode, ScriptRngSource},
+            sequence::ScriptSequence,
+            systems::ScriptSystems,
+            time::ScriptTime,
+            timers::ScriptTimers,
+        },
+        impl_script_newtype, ValueIndex,
+    };
 }
 
 // re-export derive macros from other langs
 pub use bevy_mod_scripting_derive::impl_script_newtype;
 #[cfg(feature = "lua")]
-pub use bevy_mod_scripting_lua_derive::impl_lua_newtype; //LuaProxy};
+pub use bevy_mod_scripting_lua_derive::{impl_lua_newtype, LuaProxyable};
+#[cfg(feature = "rhai")]
+pub use bevy_mod_scripting_rhai_derive::impl_rhai_newtype;
 
 pub(crate) mod generated;
 
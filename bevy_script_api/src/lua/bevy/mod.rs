@@ -1,5 +1,9 @@
-use crate::common::bevy::{ScriptTypeRegistration, ScriptWorld};
+use crate::common::bevy::{ScriptQueryResult, ScriptTypeRegistration, ScriptWorld};
+use crate::common::iter::mark_structural_change;
 use crate::impl_tealr_type;
+use crate::lua::iter::LuaIter;
+use crate::lua::table::{reflect_to_table, table_to_reflect};
+use crate::script_ref::ReflectedValue;
 
 use std::sync::Arc;
 
@@ -36,6 +40,25 @@ impl TealData for LuaTypeRegistration {
     }
 }
 
+pub type LuaQueryResult = ScriptQueryResult;
+impl_tealr_type!(LuaQueryResult);
+
+impl TealData for LuaQueryResult {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type(
+            "An entity matched by [`LuaWorld::query`] along with its queried components.",
+        );
+
+        methods.document("Returns the queried components as a list, in the order their types were given to the query.");
+        methods.add_method("components", |_, s, ()| Ok(s.components().to_vec()));
+    }
+
+    fn add_fields<'lua, F: tealr::mlu::TealDataFields<'lua, Self>>(fields: &mut F) {
+        fields.document("The matched entity");
+        fields.add_field_method_get("entity", |_, s| Ok(LuaEntity::new(s.entity())));
+    }
+}
+
 #[derive(Debug)]
 pub struct LuaScriptData {
     sid: u32,
@@ -94,6 +117,29 @@ impl TealData for LuaWorld {
                 .map(|registration| LuaTypeRegistration::new(Arc::new(registration.clone()))))
         });
 
+        methods.document("Constructs a value of the type with the given name from a table.");
+        methods.document("Nested tables are converted into nested structs, tuples, lists and arrays, fields missing from the table keep their default values.");
+        methods.document("The type must be registered and reflect `Default`, conversion errors name the offending field.");
+        methods.add_method(
+            "from_table",
+            |ctx, world, (type_name, table): (String, mlua::Table)| {
+                let registration = world.get_type_by_name(&type_name).ok_or_else(|| {
+                    mlua::Error::RuntimeError(format!(
+                        "No type with name `{type_name}` is registered"
+                    ))
+                })?;
+
+                table_to_reflect(ctx, world.clone().into(), &registration, table)
+            },
+        );
+
+        methods.document(
+            "Converts the given reflected value into a plain table, the inverse of `from_table`.",
+        );
+        methods.add_method("to_table", |ctx, _, value: ReflectedValue| {
+            reflect_to_table(ctx, value.ref_)
+        });
+
         methods.document("Inserts a component of the given type to the given entity by instantiating a default version of it.");
         methods.document("The component can then be modified using field access.");
         methods.add_method(
@@ -127,6 +173,32 @@ impl TealData for LuaWorld {
             },
         );
 
+        methods.document(
+            "Returns a lazy iterator over all entities with a component of the given type.",
+        );
+        methods
+            .document("Spawning or despawning entities, or inserting or removing components, while iterating invalidates the iterator.");
+        methods.add_method(
+            "iter_entities_with",
+            |_, world, comp_type: LuaTypeRegistration| {
+                world
+                    .iter_entities_with(comp_type)
+                    .map(|iter| LuaIter::new(iter.map(LuaEntity::new)))
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+            },
+        );
+
+        methods.document("Returns a lazy iterator over all entities with components of all the given types, i.e. `world:query({transform_type, velocity_type})`.");
+        methods.document("Each element holds the matched `entity` and its components, which can be read and modified like those returned by `get_component`.");
+        methods
+            .document("Spawning or despawning entities, or inserting or removing components, while iterating invalidates the iterator.");
+        methods.add_method("query", |_, world, comp_types: Vec<LuaTypeRegistration>| {
+            world
+                .query(comp_types)
+                .map(LuaIter::new)
+                .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+        });
+
         methods.document("Removes the given component from the given entity, does nothing if it doesn't exist on the entity.");
         methods.add_method_mut(
             "remove_component",
@@ -199,6 +271,7 @@ impl TealData for LuaWorld {
                 if let Some(mut entity) = w.get_entity_mut(parent.inner()?) {
                     entity.push_children(&children);
                 }
+                mark_structural_change(&mut w);
 
                 Ok(())
             },
@@ -288,8 +361,10 @@ impl TealData for LuaWorld {
         methods.document("Spawns a new entity and returns its Entity ID");
         methods.add_method("spawn", |_, world, ()| {
             let mut w = world.write();
+            let entity = w.spawn(()).id();
+            mark_structural_change(&mut w);
 
-            Ok(LuaEntity::new(w.spawn(()).id()))
+            Ok(LuaEntity::new(entity))
         });
 
         methods.document(
@@ -297,8 +372,10 @@ impl TealData for LuaWorld {
         );
         methods.add_method("despawn", |_, world, entity: LuaEntity| {
             let mut w = world.write();
+            let despawned = w.despawn(entity.inner()?);
+            mark_structural_change(&mut w);
 
-            Ok(w.despawn(entity.inner()?))
+            Ok(despawned)
         });
     }
 }
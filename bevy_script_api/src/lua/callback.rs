@@ -0,0 +1,72 @@
+use std::{cell::Cell, marker::PhantomData};
+
+use bevy_mod_scripting_lua::tealr;
+
+use tealr::mlu::mlua::{self, FromLua, Function, Lua, Value};
+
+use super::{FromLuaProxy, ToLuaProxy};
+
+/// A lua function passed to rust as a callback, arguments and return values are converted via the proxy layer
+/// (see [`ToLuaProxy`] and [`FromLuaProxy`]).
+///
+/// The callback borrows the lua context it came from, so it can only be used for the duration of the host call it was passed to.
+/// The callback runs inside that host call, so the rust side must not hold any locks on world data (i.e. inside [`ScriptRef::get_mut`](crate::ScriptRef::get_mut))
+/// while invoking it, as the script is free to access the world. Invoking the callback again from within itself results in an error.
+pub struct LuaCallback<'lua, A, R> {
+    func: Function<'lua>,
+    lua: &'lua Lua,
+    running: Cell<bool>,
+    _ph: PhantomData<fn(A) -> R>,
+}
+
+impl<'lua, A, R> LuaCallback<'lua, A, R> {
+    pub fn new(lua: &'lua Lua, func: Function<'lua>) -> Self {
+        Self {
+            func,
+            lua,
+            running: Cell::new(false),
+            _ph: PhantomData,
+        }
+    }
+}
+
+impl<'lua, A: ToLuaProxy<'lua>, R: FromLuaProxy<'lua>> LuaCallback<'lua, A, R> {
+    /// Invokes the lua function with the given argument
+    pub fn call(&self, arg: A) -> mlua::Result<R> {
+        if self.running.replace(true) {
+            return Err(mlua::Error::RuntimeError(
+                "Callback was invoked from within itself".to_owned(),
+            ));
+        }
+
+        let result = arg
+            .to_lua_proxy(self.lua)
+            .and_then(|arg| self.func.call::<_, Value>(arg))
+            .and_then(|out| R::from_lua_proxy(out, self.lua));
+
+        self.running.set(false);
+        result
+    }
+
+    /// Converts the callback into a boxed rust closure
+    pub fn boxed(self) -> Box<dyn FnMut(A) -> mlua::Result<R> + 'lua>
+    where
+        A: 'lua,
+        R: 'lua,
+    {
+        Box::new(move |arg| self.call(arg))
+    }
+}
+
+impl<'lua, A, R> FromLua<'lua> for LuaCallback<'lua, A, R> {
+    fn from_lua(value: Value<'lua>, lua: &'lua Lua) -> mlua::Result<Self> {
+        match value {
+            Value::Function(func) => Ok(Self::new(lua, func)),
+            value => Err(mlua::Error::FromLuaConversionError {
+                from: value.type_name(),
+                to: "function",
+                message: Some("Expected a callback".to_owned()),
+            }),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::sync::Mutex;
+
+use bevy::prelude::App;
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_lua::{docs::LuaDocFragment, tealr};
+
+use tealr::mlu::{
+    mlua::{self, Lua, Table, Value},
+    TealData, TealDataMethods,
+};
+
+use crate::common::{bevy::register_script_commands, commands::ScriptCommands};
+use crate::impl_tealr_type;
+use crate::lua::{
+    bevy::{LuaEntity, LuaTypeRegistration},
+    ApplyLua,
+};
+use crate::script_ref::ValueIndex;
+
+pub type LuaScriptCommands = ScriptCommands;
+
+impl_tealr_type!(LuaScriptCommands);
+
+impl TealData for LuaScriptCommands {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type(
+            "Queues up structural changes to the world, available to scripts as `commands`.",
+        );
+        methods.document_type("Changes are applied at the end of the frame, after all scripts have run, unlike the equivalent methods of [`LuaWorld`].");
+
+        methods.document(
+            "Spawns a new empty entity at the end of the frame, returns its Entity ID right away.",
+        );
+        methods.add_method("spawn", |_, commands, ()| {
+            Ok(LuaEntity::new(commands.spawn()))
+        });
+
+        methods.document("Despawns the given entity at the end of the frame.");
+        methods.add_method("despawn", |_, commands, entity: LuaEntity| {
+            commands.despawn(entity.inner()?);
+            Ok(())
+        });
+
+        methods.document("Despawns the given entity and the entity's children recursively at the end of the frame.");
+        methods.add_method("despawn_recursive", |_, commands, entity: LuaEntity| {
+            commands.despawn_recursive(entity.inner()?);
+            Ok(())
+        });
+
+        methods.document("Inserts a component of the given type with its default value on the given entity at the end of the frame.");
+        methods.add_method(
+            "insert",
+            |_, commands, (entity, comp_type): (LuaEntity, LuaTypeRegistration)| {
+                commands
+                    .insert_default(entity.inner()?, comp_type)
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+            },
+        );
+
+        methods.document("Inserts a component of the type with the given name on the given entity at the end of the frame, built from its default value and the fields of the given table.");
+        methods.document("Tuple struct fields are given by their index starting at 0. Fails with the names of all registered components if the type is not one of them.");
+        methods.add_method(
+            "insert_component",
+            |ctx, commands, (entity, type_name, fields): (LuaEntity, String, Table)| {
+                commands.insert_component(entity.inner()?, &type_name, |component| {
+                    for pair in fields.pairs::<Value, Value>() {
+                        let (field, value) = pair?;
+                        component.index(field)?.apply_lua(ctx, value)?;
+                    }
+                    Ok(())
+                })
+            },
+        );
+
+        methods.document("Removes the component of the given type from the given entity at the end of the frame.");
+        methods.add_method(
+            "remove",
+            |_, commands, (entity, comp_type): (LuaEntity, LuaTypeRegistration)| {
+                commands
+                    .remove(entity.inner()?, comp_type)
+                    .map_err(|e| mlua::Error::RuntimeError(e.to_string()))
+            },
+        );
+    }
+}
+
+/// Provides scripts with the `commands` global, which spawns and despawns entities and inserts and removes components
+/// at the end of the frame rather than while scripts are running.
+///
+/// See [`ScriptCommands`] for when the commands are applied.
+#[derive(Default)]
+pub struct LuaCommandsAPIProvider;
+
+impl APIProvider for LuaCommandsAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        _script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        let ctx = ctx.get_mut().expect("Could not get context");
+        ctx.globals()
+            .set("commands", LuaScriptCommands::new(world_ptr))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn get_doc_fragment(&self) -> Option<Self::DocTarget> {
+        Some(LuaDocFragment::new("CommandsAPI", |tw| {
+            tw.process_type::<LuaScriptCommands>()
+        }))
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_commands(app);
+    }
+}
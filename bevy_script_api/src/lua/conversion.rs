@@ -0,0 +1,164 @@
+//! Errors raised when converting lua values to rust values, naming the part of the value which failed to convert.
+//!
+//! Conversions of nested values attach the field, index or argument being converted to errors raised by the conversions
+//! nested in them (see [`ConversionContext`]). The path is built up as the error propagates outwards, so the innermost failure
+//! of i.e. a table assigned to a component reads "field `transform.translation`: expected number, got string".
+use std::fmt;
+
+use bevy_mod_scripting_lua::tealr::mlu::mlua;
+
+/// An element of the path to a converted value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConversionPathElem {
+    /// A field of a struct or table
+    Field(String),
+    /// An element of a sequence, counted from 1 as in lua
+    Index(usize),
+    /// A named argument of a function
+    Argument(String),
+}
+
+/// An error raised by a conversion nested inside other conversions, along with the path to the value it was converting
+#[derive(Debug, Clone)]
+pub struct ConversionError {
+    path: Vec<ConversionPathElem>,
+    cause: mlua::Error,
+}
+
+impl ConversionError {
+    /// The path to the value which failed to convert, outermost element first
+    pub fn path(&self) -> &[ConversionPathElem] {
+        &self.path
+    }
+
+    /// The error raised by the conversion of the value itself
+    pub fn cause(&self) -> &mlua::Error {
+        &self.cause
+    }
+
+    /// Attaches the given path element to the error, prepending it to the path if the error already carries one
+    pub fn wrap(error: mlua::Error, elem: ConversionPathElem) -> mlua::Error {
+        if let mlua::Error::ExternalError(e) = &error {
+            if let Some(e) = e.downcast_ref::<ConversionError>() {
+                let mut e = e.clone();
+                e.path.insert(0, elem);
+                return mlua::Error::external(e);
+            }
+        }
+
+        mlua::Error::external(ConversionError {
+            path: vec![elem],
+            cause: error,
+        })
+    }
+
+    fn fmt_cause(cause: &mlua::Error, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match cause {
+            mlua::Error::FromLuaConversionError { from, to, message } => {
+                write!(f, "expected {to}, got {from}")?;
+                match message {
+                    Some(message) => write!(f, " ({message})"),
+                    None => Ok(()),
+                }
+            }
+            mlua::Error::CallbackError { cause, .. } => Self::fmt_cause(cause, f),
+            e => write!(f, "{e}"),
+        }
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.path.first() {
+            Some(ConversionPathElem::Argument(_)) => "argument",
+            Some(ConversionPathElem::Index(_)) => "element",
+            _ => "field",
+        };
+
+        let mut path = String::new();
+        for elem in &self.path {
+            match elem {
+                ConversionPathElem::Field(name) | ConversionPathElem::Argument(name) => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(name);
+                }
+                ConversionPathElem::Index(index) => path.push_str(&format!("[{index}]")),
+            }
+        }
+
+        write!(f, "{kind} `{path}`: ")?;
+        Self::fmt_cause(&self.cause, f)
+    }
+}
+
+impl std::error::Error for ConversionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.cause)
+    }
+}
+
+/// Attaches the field, index or argument being converted to the errors of a conversion, see [`ConversionError`]
+pub trait ConversionContext<T> {
+    fn in_field(self, name: &str) -> mlua::Result<T>;
+
+    /// the index is the lua index of the element, i.e. counted from 1
+    fn at_index(self, index: usize) -> mlua::Result<T>;
+
+    fn in_argument(self, name: &str) -> mlua::Result<T>;
+}
+
+impl<T> ConversionContext<T> for mlua::Result<T> {
+    fn in_field(self, name: &str) -> mlua::Result<T> {
+        self.map_err(|e| ConversionError::wrap(e, ConversionPathElem::Field(name.to_owned())))
+    }
+
+    fn at_index(self, index: usize) -> mlua::Result<T> {
+        self.map_err(|e| ConversionError::wrap(e, ConversionPathElem::Index(index)))
+    }
+
+    fn in_argument(self, name: &str) -> mlua::Result<T> {
+        self.map_err(|e| ConversionError::wrap(e, ConversionPathElem::Argument(name.to_owned())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn expected_number() -> mlua::Result<f32> {
+        Err(mlua::Error::FromLuaConversionError {
+            from: "string",
+            to: "number",
+            message: None,
+        })
+    }
+
+    #[test]
+    fn nested_fields_are_joined() {
+        let err = expected_number()
+            .in_field("translation")
+            .in_field("transform")
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "field `transform.translation`: expected number, got string"
+        );
+    }
+
+    #[test]
+    fn indices_and_arguments_are_named() {
+        let err = expected_number()
+            .in_field("x")
+            .at_index(2)
+            .in_argument("points")
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "argument `points[2].x`: expected number, got string"
+        );
+    }
+}
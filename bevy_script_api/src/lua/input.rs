@@ -0,0 +1,105 @@
+use std::sync::Mutex;
+
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_lua::{docs::LuaDocFragment, tealr};
+
+use tealr::mlu::{
+    mlua::{self, Lua},
+    TealData, TealDataMethods,
+};
+
+use crate::common::input::ScriptInput;
+use crate::impl_tealr_type;
+use crate::lua::bevy::LuaVec2;
+
+pub type LuaScriptInput = ScriptInput;
+
+impl_tealr_type!(LuaScriptInput);
+
+fn to_lua_error(e: ScriptError) -> mlua::Error {
+    mlua::Error::RuntimeError(e.to_string())
+}
+
+impl TealData for LuaScriptInput {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type(
+            "Reads the state of the keyboard and mouse, available to scripts as `input`.",
+        );
+        methods.document_type("Keys and mouse buttons are named after the variants of bevy's `KeyCode` and `MouseButton`, i.e. `\"Space\"`, `\"A\"` or `\"Left\"`.");
+
+        methods.document("Returns true if the key with the given name is being held down.");
+        methods.add_method("is_key_pressed", |_, input, key: String| {
+            input.is_key_pressed(&key).map_err(to_lua_error)
+        });
+
+        methods.document("Returns true if the key with the given name was pressed this frame.");
+        methods.add_method("is_key_just_pressed", |_, input, key: String| {
+            input.is_key_just_pressed(&key).map_err(to_lua_error)
+        });
+
+        methods.document("Returns true if the key with the given name was released this frame.");
+        methods.add_method("is_key_just_released", |_, input, key: String| {
+            input.is_key_just_released(&key).map_err(to_lua_error)
+        });
+
+        methods
+            .document("Returns true if the mouse button with the given name is being held down.");
+        methods.add_method("is_mouse_pressed", |_, input, button: String| {
+            input.is_mouse_pressed(&button).map_err(to_lua_error)
+        });
+
+        methods.document(
+            "Returns true if the mouse button with the given name was pressed this frame.",
+        );
+        methods.add_method("is_mouse_just_pressed", |_, input, button: String| {
+            input.is_mouse_just_pressed(&button).map_err(to_lua_error)
+        });
+
+        methods.document(
+            "Returns true if the mouse button with the given name was released this frame.",
+        );
+        methods.add_method("is_mouse_just_released", |_, input, button: String| {
+            input.is_mouse_just_released(&button).map_err(to_lua_error)
+        });
+
+        methods.document("Returns the position of the cursor in the primary window in logical pixels from the bottom left corner, or nil if the cursor is outside the window.");
+        methods.add_method("mouse_position", |_, input, ()| {
+            Ok(input.mouse_position().map(LuaVec2::new))
+        });
+    }
+}
+
+/// Provides scripts with the `input` global, which reads the state of the keyboard and mouse,
+/// i.e. `input:is_key_pressed("Space")` or `input:mouse_position()`.
+///
+/// See [`ScriptInput`] for how keys and mouse buttons are named.
+#[derive(Default)]
+pub struct LuaInputAPIProvider;
+
+impl APIProvider for LuaInputAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        _script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        let ctx = ctx.get_mut().expect("Could not get context");
+        ctx.globals()
+            .set("input", LuaScriptInput::new(world_ptr))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn get_doc_fragment(&self) -> Option<Self::DocTarget> {
+        Some(LuaDocFragment::new("InputAPI", |tw| {
+            tw.process_type::<LuaScriptInput>()
+        }))
+    }
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use bevy_mod_scripting_lua::tealr;
+use parking_lot::Mutex;
+
+use tealr::mlu::{
+    mlua::{self, Lua, MetaMethod, ToLua, Value},
+    TealData, TealDataMethods, TypedFunction,
+};
+
+use crate::{common::iter::ScriptIter, impl_tealr_type};
+
+/// Type erases the element type of a [`ScriptIter`]
+trait LuaNext: Send {
+    fn next_lua<'lua>(&mut self, lua: &'lua Lua) -> mlua::Result<Value<'lua>>;
+}
+
+impl<T: for<'lua> ToLua<'lua>> LuaNext for ScriptIter<T> {
+    fn next_lua<'lua>(&mut self, lua: &'lua Lua) -> mlua::Result<Value<'lua>> {
+        match self.next_checked()? {
+            Some(v) => v.to_lua(lua),
+            None => Ok(Value::Nil),
+        }
+    }
+}
+
+/// A lazy iterator proxy, elements are converted to lua values only once the script asks for them.
+///
+/// Can be used directly in generic for loops (`for v in iter do`) as well as with `pairs`,
+/// iteration stops at the first `nil` element.
+#[derive(Clone)]
+pub struct LuaIter(Arc<Mutex<Box<dyn LuaNext>>>);
+
+impl_tealr_type!(LuaIter);
+
+impl LuaIter {
+    pub fn new<T: for<'lua> ToLua<'lua> + 'static>(iter: ScriptIter<T>) -> Self {
+        Self(Arc::new(Mutex::new(Box::new(iter))))
+    }
+
+    /// Produces the next element, or `nil` if the iterator is exhausted
+    pub fn next<'lua>(&self, lua: &'lua Lua) -> mlua::Result<Value<'lua>> {
+        self.0.lock().next_lua(lua)
+    }
+}
+
+impl<T: for<'lua> ToLua<'lua> + 'static> From<ScriptIter<T>> for LuaIter {
+    fn from(iter: ScriptIter<T>) -> Self {
+        Self::new(iter)
+    }
+}
+
+impl std::fmt::Debug for LuaIter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LuaIter")
+    }
+}
+
+impl TealData for LuaIter {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type(
+            "A lazy iterator, elements are produced one at a time as the iterator is advanced.",
+        );
+        methods.document_type(
+            "Errors if the world is structurally modified while iterating over world data.",
+        );
+
+        methods.document("Returns the next element or `nil` if the iterator is exhausted.");
+        methods.add_method("next", |ctx, s, ()| s.next(ctx));
+
+        methods.add_meta_method(MetaMethod::ToString, |_, s, ()| Ok(format!("{s:?}")));
+
+        methods.add_meta_method(MetaMethod::Call, |ctx, s, ()| s.next(ctx));
+
+        methods.add_meta_method(MetaMethod::Pairs, |ctx, s, ()| {
+            let iter = s.clone();
+            let mut idx = 0;
+            TypedFunction::from_rust_mut(
+                move |ctx, ()| match iter.next(ctx)? {
+                    Value::Nil => Ok((Value::Nil, Value::Nil)),
+                    v => {
+                        idx += 1;
+                        Ok((idx.to_lua(ctx)?, v))
+                    }
+                },
+                ctx,
+            )
+        });
+    }
+}
@@ -0,0 +1,66 @@
+use std::sync::Mutex;
+
+use bevy::log::Level;
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{docs::LuaDocFragment, tealr};
+
+use tealr::mlu::mlua::{self, Lua, Value, Variadic};
+
+use crate::common::log::ScriptLogger;
+
+/// Converts the arguments to strings the same way `print` does and joins them with tabs
+fn join_args(lua: &Lua, args: Variadic<Value>) -> mlua::Result<String> {
+    let tostring: mlua::Function = lua.globals().get("tostring")?;
+    let parts = args
+        .into_iter()
+        .map(|v| tostring.call::<_, String>(v))
+        .collect::<mlua::Result<Vec<_>>>()?;
+    Ok(parts.join("\t"))
+}
+
+/// Provides scripts with the `log` table, whose `error`, `warn`, `info` and `debug` functions
+/// log through `tracing` on behalf of the script instead of printing to stdout.
+///
+/// See [`ScriptLogger`] for how script output can be filtered.
+#[derive(Default)]
+pub struct LuaLogAPIProvider;
+
+impl APIProvider for LuaLogAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        let ctx = ctx.get_mut().expect("Could not get context");
+        let logger = ScriptLogger::new(script_data.name);
+
+        let table = ctx.create_table().map_err(ScriptError::new_other)?;
+        for (name, level) in [
+            ("error", Level::ERROR),
+            ("warn", Level::WARN),
+            ("info", Level::INFO),
+            ("debug", Level::DEBUG),
+        ] {
+            let logger = logger.clone();
+            let f = ctx
+                .create_function(move |lua, args: Variadic<Value>| {
+                    logger.log(level, &join_args(lua, args)?);
+                    Ok(())
+                })
+                .map_err(ScriptError::new_other)?;
+            table.set(name, f).map_err(ScriptError::new_other)?;
+        }
+
+        ctx.globals()
+            .set("log", table)
+            .map_err(ScriptError::new_other)
+    }
+}
@@ -0,0 +1,210 @@
+use std::sync::Mutex;
+
+use bevy::math::{Mat3, Mat4, Quat, Vec2, Vec3, Vec4};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{docs::LuaDocFragment, tealr};
+
+use tealr::mlu::mlua::{self, Lua, Table, ToLua, Value};
+
+use crate::common::math::MathValue;
+use crate::lua::bevy::{LuaMat3, LuaMat4, LuaQuat, LuaVec2, LuaVec3, LuaVec4};
+
+fn to_math_value(value: Value) -> mlua::Result<MathValue> {
+    match value {
+        Value::Integer(i) => Ok(MathValue::Number(i as f64)),
+        Value::Number(n) => Ok(MathValue::Number(n)),
+        Value::UserData(ud) => {
+            if let Ok(v) = ud.borrow::<LuaVec2>() {
+                Ok(MathValue::Vec2(v.inner()?))
+            } else if let Ok(v) = ud.borrow::<LuaVec3>() {
+                Ok(MathValue::Vec3(v.inner()?))
+            } else if let Ok(v) = ud.borrow::<LuaVec4>() {
+                Ok(MathValue::Vec4(v.inner()?))
+            } else if let Ok(v) = ud.borrow::<LuaQuat>() {
+                Ok(MathValue::Quat(v.inner()?))
+            } else if let Ok(v) = ud.borrow::<LuaMat3>() {
+                Ok(MathValue::Mat3(v.inner()?))
+            } else if let Ok(v) = ud.borrow::<LuaMat4>() {
+                Ok(MathValue::Mat4(v.inner()?))
+            } else {
+                Err(mlua::Error::RuntimeError(
+                    "Expected a number, vector, quaternion or matrix".to_owned(),
+                ))
+            }
+        }
+        v => Err(mlua::Error::RuntimeError(format!(
+            "Expected a number, vector, quaternion or matrix, got a {}",
+            v.type_name()
+        ))),
+    }
+}
+
+fn from_math_value(lua: &Lua, value: MathValue) -> mlua::Result<Value> {
+    match value {
+        MathValue::Number(n) => Ok(Value::Number(n)),
+        MathValue::Vec2(v) => LuaVec2::new(v).to_lua(lua),
+        MathValue::Vec3(v) => LuaVec3::new(v).to_lua(lua),
+        MathValue::Vec4(v) => LuaVec4::new(v).to_lua(lua),
+        MathValue::Quat(q) => LuaQuat::new(q).to_lua(lua),
+        MathValue::Mat3(m) => LuaMat3::new(m).to_lua(lua),
+        MathValue::Mat4(m) => LuaMat4::new(m).to_lua(lua),
+    }
+}
+
+fn attach_math_api(lua: &Lua) -> mlua::Result<()> {
+    let math: Table = lua.globals().get("math")?;
+
+    // constructors
+    math.set(
+        "vec2",
+        lua.create_function(|_, (x, y): (f32, f32)| Ok(LuaVec2::new(Vec2::new(x, y))))?,
+    )?;
+    math.set(
+        "vec3",
+        lua.create_function(|_, (x, y, z): (f32, f32, f32)| Ok(LuaVec3::new(Vec3::new(x, y, z))))?,
+    )?;
+    math.set(
+        "vec4",
+        lua.create_function(|_, (x, y, z, w): (f32, f32, f32, f32)| {
+            Ok(LuaVec4::new(Vec4::new(x, y, z, w)))
+        })?,
+    )?;
+    math.set(
+        "quat",
+        lua.create_function(|_, (x, y, z, w): (f32, f32, f32, f32)| {
+            Ok(LuaQuat::new(Quat::from_xyzw(x, y, z, w)))
+        })?,
+    )?;
+    math.set(
+        "quat_from_axis_angle",
+        lua.create_function(|_, (axis, angle): (LuaVec3, f32)| {
+            Ok(LuaQuat::new(Quat::from_axis_angle(
+                axis.inner()?.normalize(),
+                angle,
+            )))
+        })?,
+    )?;
+    math.set(
+        "quat_from_euler",
+        lua.create_function(|_, (x, y, z): (f32, f32, f32)| {
+            Ok(LuaQuat::new(Quat::from_euler(
+                bevy::math::EulerRot::XYZ,
+                x,
+                y,
+                z,
+            )))
+        })?,
+    )?;
+    math.set(
+        "mat3_identity",
+        lua.create_function(|_, ()| Ok(LuaMat3::new(Mat3::IDENTITY)))?,
+    )?;
+    math.set(
+        "mat3_from_quat",
+        lua.create_function(|_, q: LuaQuat| Ok(LuaMat3::new(Mat3::from_quat(q.inner()?))))?,
+    )?;
+    math.set(
+        "mat4_identity",
+        lua.create_function(|_, ()| Ok(LuaMat4::new(Mat4::IDENTITY)))?,
+    )?;
+    math.set(
+        "mat4_from_srt",
+        lua.create_function(
+            |_, (scale, rotation, translation): (LuaVec3, LuaQuat, LuaVec3)| {
+                Ok(LuaMat4::new(Mat4::from_scale_rotation_translation(
+                    scale.inner()?,
+                    rotation.inner()?,
+                    translation.inner()?,
+                )))
+            },
+        )?,
+    )?;
+
+    // operations working on several types
+    math.set(
+        "lerp",
+        lua.create_function(|lua, (a, b, t): (Value, Value, f32)| {
+            let v = to_math_value(a)?
+                .lerp(&to_math_value(b)?, t)
+                .map_err(mlua::Error::RuntimeError)?;
+            from_math_value(lua, v)
+        })?,
+    )?;
+    math.set(
+        "dot",
+        lua.create_function(|_, (a, b): (Value, Value)| {
+            to_math_value(a)?
+                .dot(&to_math_value(b)?)
+                .map_err(mlua::Error::RuntimeError)
+        })?,
+    )?;
+    math.set(
+        "cross",
+        lua.create_function(|_, (a, b): (LuaVec3, LuaVec3)| {
+            Ok(LuaVec3::new(a.inner()?.cross(b.inner()?)))
+        })?,
+    )?;
+    math.set(
+        "normalize",
+        lua.create_function(|lua, v: Value| {
+            let v = to_math_value(v)?
+                .normalize()
+                .map_err(mlua::Error::RuntimeError)?;
+            from_math_value(lua, v)
+        })?,
+    )?;
+    math.set(
+        "length",
+        lua.create_function(|_, v: Value| {
+            to_math_value(v)?
+                .length()
+                .map_err(mlua::Error::RuntimeError)
+        })?,
+    )?;
+    math.set(
+        "angle",
+        lua.create_function(|_, (a, b): (Value, Value)| {
+            to_math_value(a)?
+                .angle(&to_math_value(b)?)
+                .map_err(mlua::Error::RuntimeError)
+        })?,
+    )?;
+    math.set(
+        "inverse",
+        lua.create_function(|lua, v: Value| {
+            let v = to_math_value(v)?
+                .inverse()
+                .map_err(mlua::Error::RuntimeError)?;
+            from_math_value(lua, v)
+        })?,
+    )?;
+    math.set(
+        "transpose",
+        lua.create_function(|lua, v: Value| {
+            let v = to_math_value(v)?
+                .transpose()
+                .map_err(mlua::Error::RuntimeError)?;
+            from_math_value(lua, v)
+        })?,
+    )?;
+
+    Ok(())
+}
+
+/// Extends the standard `math` table with constructors and common operations for the `Vec2`, `Vec3`, `Vec4`,
+/// `Quat`, `Mat3` and `Mat4` wrappers, i.e. `math.vec3(1, 0, 0)`, `math.lerp(a, b, 0.5)` or `math.angle(a, b)`.
+///
+/// `lerp` also works on plain numbers and spherically interpolates quaternions.
+#[derive(Default)]
+pub struct LuaMathAPIProvider;
+
+impl APIProvider for LuaMathAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, ctx: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let ctx = ctx.get_mut().expect("Could not get context");
+        attach_math_api(ctx).map_err(ScriptError::new_other)
+    }
+}
@@ -2,13 +2,15 @@ use ::std::any::TypeId;
 use ::std::borrow::Cow;
 
 use crate::common::bevy::GetWorld;
+use crate::common::events::{HookEvent, ReflectEvent};
+use crate::common::scalar::ScriptScalar;
 use crate::impl_tealr_type;
 use ::bevy::prelude::{App, AppTypeRegistry};
 
 use ::bevy::reflect::{FromType, GetTypeRegistration, Reflect};
 
-use bevy_mod_scripting_core::world::WorldPointer;
-use bevy_mod_scripting_lua::tealr;
+use bevy_mod_scripting_core::{hosts::Recipients, world::WorldPointer};
+use bevy_mod_scripting_lua::{tealr, LuaArg, LuaEvent};
 
 use tealr::mlu::mlua::MetaMethod;
 use tealr::mlu::{
@@ -22,7 +24,20 @@ use crate::script_ref::{ReflectedValue, ScriptRef, ValueIndex};
 use self::bevy::LuaWorld;
 
 pub mod bevy;
+pub mod callback;
+pub mod commands;
+pub mod conversion;
+pub mod input;
+pub mod iter;
+pub mod log;
+#[cfg(feature = "math")]
+pub mod math;
+pub mod rng;
 pub mod std;
+pub mod systems;
+pub mod table;
+pub mod time;
+pub mod timers;
 pub mod util;
 
 /// A trait allowing to register the [`LuaProxyable`] trait with the type registry for foreign types
@@ -83,6 +98,28 @@ impl ValueIndex<Value<'_>> for ScriptRef {
     }
 }
 
+impl<'lua> ToLua<'lua> for ScriptScalar {
+    fn to_lua(self, lua: &'lua Lua) -> mlua::Result<Value<'lua>> {
+        match self {
+            ScriptScalar::Bool(b) => b.to_lua(lua),
+            ScriptScalar::Int(i) => i.to_lua(lua),
+            ScriptScalar::Float(f) => f.to_lua(lua),
+        }
+    }
+}
+
+/// The scalar held by the given lua value, if it's a primitive
+// the integer type of lua depends on the lua version
+#[allow(clippy::unnecessary_cast)]
+fn lua_scalar(v: &Value) -> Option<ScriptScalar> {
+    match v {
+        Value::Boolean(b) => Some(ScriptScalar::Bool(*b)),
+        Value::Integer(i) => Some(ScriptScalar::Int(*i as i64)),
+        Value::Number(n) => Some(ScriptScalar::Float(*n)),
+        _ => None,
+    }
+}
+
 /// For internal use only.
 ///
 /// Mainly necessary for separation of concerns on the [`ScriptRef`] type, but might have other uses potentially.
@@ -95,6 +132,13 @@ pub(crate) trait ApplyLua {
 impl ApplyLua for ScriptRef {
     /// Applies the given lua value to the proxied reflect type. Semantically equivalent to `Reflect::apply`
     fn apply_lua<'lua>(&mut self, ctx: &'lua Lua, v: Value<'lua>) -> Result<(), mlua::Error> {
+        // primitives are written directly if the target can hold them
+        if let Some(scalar) = lua_scalar(&v) {
+            if self.get_mut(|s| scalar.apply_to(s))? {
+                return Ok(());
+            }
+        }
+
         let luaworld = ctx.globals().get::<_, LuaWorld>("world").unwrap();
 
         // remove typedata from the world to be able to manipulate world
@@ -106,16 +150,20 @@ impl ApplyLua for ScriptRef {
                 .cloned()
         };
 
-        if let Some(ud) = proxyable {
-            return ud.apply_lua(self, ctx, v);
-        } else if let Value::UserData(v) = &v {
+        // reflected values can always be applied via reflection,
+        // even if the target type has a proxy
+        if let Value::UserData(v) = &v {
             if v.is::<ReflectedValue>() {
-                let b = v.take::<ReflectedValue>().unwrap();
-                self.apply(&b.into())?;
+                let b = v.borrow::<ReflectedValue>()?;
+                self.apply(&b.ref_)?;
                 return Ok(());
             }
         }
 
+        if let Some(ud) = proxyable {
+            return ud.apply_lua(self, ctx, v);
+        }
+
         Err(mlua::Error::RuntimeError(self.get(|s|
             format!("Attempted to assign `{}` = {v:?}. Did you forget to call `app.register_foreign_lua_type::<{}>`?",
                 self.path,
@@ -128,21 +176,26 @@ impl ApplyLua for ScriptRef {
 impl<'lua> ToLua<'lua> for ScriptRef {
     /// Converts the LuaRef to the most convenient representation
     /// checking conversions in this order:
+    /// - A primitive which fits a lua number or boolean is converted directly, see [`ScriptScalar`]
     /// - A primitive or bevy type which has a reflect interface is converted to a custom UserData exposing its API to lua conveniently
     /// - A type implementing CustomUserData is converted with its `ref_to_lua` method
     /// - Finally the method is represented as a `ReflectedValue` which exposes the Reflect interface
     fn to_lua(self, ctx: &'lua Lua) -> mlua::Result<Value<'lua>> {
+        let (type_id, scalar) = self.get(|s| (s.type_id(), ScriptScalar::from_reflect(s)))?;
+        if let Some(scalar) = scalar {
+            return scalar.to_lua(ctx);
+        }
+
         let world = self.world_ptr.clone();
         let world = world.read();
 
         let typedata = &world.resource::<AppTypeRegistry>();
         let g = typedata.read();
 
-        let type_id = self.get(|s| s.type_id())?;
         if let Some(v) = g.get_type_data::<ReflectLuaProxyable>(type_id) {
             v.ref_to_lua(self, ctx)
         } else {
-            ReflectedValue { ref_: self }.to_lua(ctx)
+            ReflectedValue::new(self).to_lua(ctx)
         }
     }
 }
@@ -183,6 +236,16 @@ impl TealData for ReflectedValue {
                 Ok(())
             },
         );
+
+        methods.document("Returns the value at the given reflect path, for example `\"translation.x\"` or `\"items[0].name\"`.");
+        methods.add_method("get_path", |_, val, path: String| {
+            Ok(val.ref_.sub_ref_path(&path)?)
+        });
+
+        methods.document("Sets the value at the given reflect path.");
+        methods.add_method_mut("set_path", |ctx, val, (path, new_val): (String, Value)| {
+            val.ref_.sub_ref_path(&path)?.apply_lua(ctx, new_val)
+        });
     }
 }
 /// A higher level trait for allowing types to be interpreted as custom lua proxy types (or just normal types, this interface is flexible).
@@ -208,12 +271,15 @@ pub trait LuaProxyable {
     ) -> mlua::Result<()>;
 }
 
-/// Exactly alike to [`mlua::ToLua`]
+/// Exactly alike to [`mlua::FromLua`]
+///
+/// Implementations converting nested values should attach the field or index being converted to the errors of the nested conversions
+/// with [`ConversionContext`](conversion::ConversionContext), so that errors name the exact part of the value which failed to convert.
 pub trait FromLuaProxy<'lua>: Sized {
     fn from_lua_proxy(new_val: Value<'lua>, lua: &'lua Lua) -> mlua::Result<Self>;
 }
 
-/// Exactly alike to [`mlua::FromLua`]
+/// Exactly alike to [`mlua::ToLua`]
 pub trait ToLuaProxy<'lua> {
     fn to_lua_proxy(self, lua: &'lua Lua) -> mlua::Result<Value<'lua>>;
 }
@@ -305,3 +371,24 @@ impl GetWorld for Lua {
         self.globals().get::<_, LuaWorld>("world").map(Into::into)
     }
 }
+
+/// Bevy events are received as [`ReflectedValue`]s, i.e. by a host with `LuaEvent<ReflectedValue>` events
+impl<A: LuaArg + From<ReflectedValue>> ReflectEvent for LuaEvent<A> {
+    fn reflect_event(hook_name: String, event: ReflectedValue) -> Self {
+        LuaEvent {
+            hook_name,
+            args: event.into(),
+            recipients: Recipients::All,
+        }
+    }
+}
+
+impl<A: LuaArg + Default> HookEvent for LuaEvent<A> {
+    fn hook_event(hook_name: String, sid: u32) -> Self {
+        LuaEvent {
+            hook_name,
+            args: Default::default(),
+            recipients: Recipients::ScriptID(sid),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::Mutex;
+
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{docs::LuaDocFragment, tealr};
+
+use tealr::mlu::{
+    mlua::{self, Lua},
+    TealData, TealDataMethods,
+};
+
+use crate::common::rng::{ScriptRngHandle, ScriptRngSource};
+use crate::impl_tealr_type;
+
+pub type LuaScriptRng = ScriptRngHandle;
+
+impl_tealr_type!(LuaScriptRng);
+
+impl TealData for LuaScriptRng {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type("A seedable random number generator, available to scripts as `rng`.");
+
+        methods.document("Returns a random integer between `min` and `max` inclusive.");
+        methods.add_method("range", |_, rng, (min, max): (i64, i64)| {
+            rng.range(min, max).ok_or_else(|| {
+                mlua::Error::RuntimeError(format!("Invalid range, `{min}` is greater than `{max}`"))
+            })
+        });
+
+        methods.document("Returns a random float between 0 (inclusive) and 1 (exclusive).");
+        methods.add_method("float", |_, rng, ()| Ok(rng.float()));
+
+        methods.document(
+            "Reseeds the generator, the same seed always produces the same sequence of numbers.",
+        );
+        methods.add_method("seed", |_, rng, seed: i64| {
+            rng.seed(seed as u64);
+            Ok(())
+        });
+    }
+}
+
+/// Provides scripts with the `rng` global, a seedable random number generator.
+///
+/// See [`ScriptRngSource`] for the ways generators can be shared between scripts.
+#[derive(Default)]
+pub struct LuaRngAPIProvider {
+    pub source: ScriptRngSource,
+}
+
+impl LuaRngAPIProvider {
+    pub fn new(source: ScriptRngSource) -> Self {
+        Self { source }
+    }
+}
+
+impl APIProvider for LuaRngAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        let ctx = ctx.get_mut().expect("Could not get context");
+        ctx.globals()
+            .set("rng", self.source.for_script(script_data.sid))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn get_doc_fragment(&self) -> Option<Self::DocTarget> {
+        Some(LuaDocFragment::new("RngAPI", |tw| {
+            tw.process_type::<LuaScriptRng>()
+        }))
+    }
+}
@@ -8,7 +8,7 @@ use bevy_mod_scripting_lua::tealr;
 use tealr::mlu::mlua::MetaMethod;
 use tealr::mlu::TypedFunction;
 use tealr::mlu::{
-    mlua::{self, FromLua, Lua, ToLua, UserData, Value},
+    mlua::{self, prelude::LuaInteger, FromLua, Function, Lua, ToLua, UserData, Value},
     TealData, TealDataMethods,
 };
 use tealr::TypeBody;
@@ -23,6 +23,8 @@ use crate::{
     sub_reflect::ReflectPathElem,
 };
 
+use super::callback::LuaCallback;
+use super::conversion::ConversionContext;
 use super::ApplyLua;
 use super::FromLuaProxy;
 use super::LuaProxyable;
@@ -230,6 +232,27 @@ impl<'lua, T: for<'a> ToLuaProxy<'a>> ToLuaProxy<'lua> for Option<T> {
 /// lua can natively represent lists of things
 pub type LuaVec<T> = ScriptVec<T>;
 
+/// Translates a 1-based lua index into an index into a vec of the given length,
+/// returns `None` if the index is 0 or past the end and an error if it's negative
+fn to_vec_index(index: LuaInteger, len: usize) -> mlua::Result<Option<usize>> {
+    if index < 0 {
+        return Err(mlua::Error::RuntimeError(format!(
+            "Negative index `{index}` into LuaVec, indexing begins at 1"
+        )));
+    }
+
+    match usize::try_from(index) {
+        Ok(index) if index >= 1 && index <= len => Ok(Some(index - 1)),
+        _ => Ok(None),
+    }
+}
+
+fn out_of_range(index: LuaInteger, len: usize) -> mlua::Error {
+    mlua::Error::RuntimeError(format!(
+        "Index `{index}` is out of range for a LuaVec of length {len}, indexing begins at 1"
+    ))
+}
+
 impl<
         T: TypeName
             + FromReflect
@@ -293,13 +316,20 @@ impl<
 
         methods.add_meta_method(MetaMethod::ToString, |_, s, ()| Ok(format!("{s:?}")));
 
-        methods.add_meta_method(MetaMethod::Index, |_, s, index: usize| {
-            Ok(s.index(index - 1))
+        methods.document("Returns nil for indices past the end, negative indices are an error.");
+        methods.add_meta_method(MetaMethod::Index, |_, s, index: LuaInteger| {
+            Ok(to_vec_index(index, s.len()?)?.map(|idx| s.index(idx)))
         });
 
         methods.add_meta_method_mut(
             MetaMethod::NewIndex,
-            |ctx, s, (index, value): (usize, Value)| s.index(index - 1).apply_lua(ctx, value),
+            |ctx, s, (index, value): (LuaInteger, Value)| {
+                let len = s.len()?;
+                match to_vec_index(index, len)? {
+                    Some(idx) => s.index(idx).apply_lua(ctx, value),
+                    None => Err(out_of_range(index, len)),
+                }
+            },
         );
 
         methods.add_meta_method(MetaMethod::Pairs, |ctx, s, _: ()| {
@@ -308,8 +338,8 @@ impl<
             let ref_: ScriptRef = s.clone().into();
             TypedFunction::from_rust_mut(
                 move |ctx, ()| {
-                    let o = if curr_idx < len {
-                        (curr_idx.to_lua(ctx)?, ref_.index(curr_idx).to_lua(ctx)?)
+                    let o = if curr_idx <= len {
+                        (curr_idx.to_lua(ctx)?, ref_.index(curr_idx - 1).to_lua(ctx)?)
                     } else {
                         (Value::Nil, Value::Nil)
                     };
@@ -323,7 +353,7 @@ impl<
         methods.add_meta_method(MetaMethod::Len, |_, s, ()| Ok(s.len()?));
 
         methods.add_method_mut("push", |ctx, s, v: Value| {
-            let new_val = T::from_lua_proxy(v, ctx)?;
+            let new_val = T::from_lua_proxy(v, ctx).in_argument("value")?;
             s.push(new_val)?;
             Ok(())
         });
@@ -335,15 +365,26 @@ impl<
             Ok(())
         });
 
-        methods.add_method_mut("insert", |ctx, s, (idx, v): (usize, Value<'lua>)| {
-            s.insert(idx - 1, T::from_lua_proxy(v, ctx)?)?;
+        methods.add_method_mut("insert", |ctx, s, (index, v): (LuaInteger, Value<'lua>)| {
+            // inserting right after the last element is allowed
+            let len = s.len()?;
+            let idx = to_vec_index(index, len + 1)?.ok_or_else(|| out_of_range(index, len))?;
+            s.insert(idx, T::from_lua_proxy(v, ctx).in_argument("value")?)?;
             Ok(())
         });
 
-        methods.add_method_mut("remove", |ctx, s, idx: usize| {
+        methods.add_method_mut("remove", |ctx, s, index: LuaInteger| {
+            let len = s.len()?;
+            let idx = to_vec_index(index, len)?.ok_or_else(|| out_of_range(index, len))?;
             let removed = s.remove(idx)?;
             removed.to_lua_proxy(ctx)
         });
+
+        methods.document("Retains only the elements for which the given predicate returns `true`.");
+        methods.add_method_mut("retain", |ctx, s, predicate: Function| {
+            let predicate = LuaCallback::<T, bool>::new(ctx, predicate);
+            s.retain(|v| predicate.call(v))
+        });
     }
 }
 
@@ -379,12 +420,12 @@ impl<
                     let idx = lua_idx - 1;
                     if idx > last_target_idx {
                         // here we don't need to do anything special just use LuaProxyable impl
-                        T::apply_lua(&mut self_.index(idx), lua, v)?;
+                        T::apply_lua(&mut self_.index(idx), lua, v).at_index(lua_idx)?;
                     } else {
                         // here we don't have anything to apply this to
                         // use FromLua impl
                         self_.get_mut_typed(|s: &mut Vec<T>| {
-                            s[idx] = T::from_lua_proxy(v, lua)?;
+                            s[idx] = T::from_lua_proxy(v, lua).at_index(lua_idx)?;
                             Ok::<_, mlua::Error>(())
                         })??;
                     }
@@ -425,7 +466,7 @@ impl<
                 // if we receive one of those, we should clone it one by one
                 table
                     .pairs::<usize, Value>()
-                    .map(|v| v.and_then(|(_, v)| T::from_lua_proxy(v, lua)))
+                    .map(|v| v.and_then(|(idx, v)| T::from_lua_proxy(v, lua).at_index(idx)))
                     .collect::<Result<Vec<_>, _>>()
             }
             _ => {
@@ -451,3 +492,27 @@ impl<'lua, T: for<'a> ToLuaProxy<'a> + Clone + FromReflect + LuaProxyable> ToLua
         proxies.to_lua(lua)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn vec_index_is_one_based() {
+        assert_eq!(to_vec_index(1, 3).unwrap(), Some(0));
+        assert_eq!(to_vec_index(3, 3).unwrap(), Some(2));
+    }
+
+    #[test]
+    fn vec_index_out_of_range_is_none() {
+        assert_eq!(to_vec_index(0, 3).unwrap(), None);
+        assert_eq!(to_vec_index(4, 3).unwrap(), None);
+        assert_eq!(to_vec_index(1, 0).unwrap(), None);
+    }
+
+    #[test]
+    fn vec_index_negative_is_error() {
+        let err = to_vec_index(-1, 3).unwrap_err().to_string();
+        assert!(err.contains("Negative index `-1`"), "{err}");
+    }
+}
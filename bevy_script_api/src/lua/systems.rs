@@ -0,0 +1,80 @@
+use std::sync::Mutex;
+
+use bevy::prelude::{App, CoreSet, IntoSystemConfig};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{docs::LuaDocFragment, tealr, LuaArg, LuaEvent, LuaScriptHost};
+
+use tealr::mlu::mlua::{self, Lua};
+
+use crate::common::systems::{script_system_system, ScriptSystemHandle, ScriptSystems};
+
+/// Exposes the given systems as the `register_system`, `unregister_system` and `is_system_registered` globals
+fn attach_system_globals(lua: &Lua, systems: ScriptSystemHandle) -> mlua::Result<()> {
+    let globals = lua.globals();
+
+    let s = systems.clone();
+    globals.set(
+        "register_system",
+        lua.create_function(move |_, hook: String| Ok(s.register(hook)))?,
+    )?;
+
+    let s = systems.clone();
+    globals.set(
+        "unregister_system",
+        lua.create_function(move |_, hook: String| Ok(s.unregister(&hook)))?,
+    )?;
+
+    globals.set(
+        "is_system_registered",
+        lua.create_function(move |_, hook: String| Ok(systems.is_registered(&hook)))?,
+    )
+}
+
+/// Provides scripts with the `register_system`, `unregister_system` and `is_system_registered` globals,
+/// used to have the script's hooks called every frame, i.e. `register_system("on_tick")`.
+///
+/// Registered hooks are sent as [`LuaEvent`]s with default arguments and the priority given in [`ScriptSystems::new`]
+/// every frame in `CoreSet::PreUpdate`. A script's systems are cleared when it is reloaded.
+pub struct LuaSystemAPIProvider<A: LuaArg> {
+    pub systems: ScriptSystems<LuaEvent<A>>,
+}
+
+impl<A: LuaArg> Default for LuaSystemAPIProvider<A> {
+    fn default() -> Self {
+        Self {
+            systems: Default::default(),
+        }
+    }
+}
+
+impl<A: LuaArg> LuaSystemAPIProvider<A> {
+    pub fn new(systems: ScriptSystems<LuaEvent<A>>) -> Self {
+        Self { systems }
+    }
+}
+
+impl<A: LuaArg + Default> APIProvider for LuaSystemAPIProvider<A> {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        self.systems.clear_script(script_data.sid);
+        let ctx = ctx.get_mut().expect("Could not get context");
+        attach_system_globals(ctx, self.systems.for_script(script_data.sid))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        app.insert_resource(self.systems.clone())
+            .add_system(script_system_system::<LuaScriptHost<A>>.in_base_set(CoreSet::PreUpdate));
+    }
+}
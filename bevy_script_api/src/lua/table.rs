@@ -0,0 +1,281 @@
+use ::std::any::type_name;
+
+use ::bevy::prelude::{AppTypeRegistry, ReflectDefault};
+use ::bevy::reflect::{ReflectMut, ReflectRef, TypeInfo, TypeRegistration};
+
+use bevy_mod_scripting_core::world::WorldPointer;
+use bevy_mod_scripting_lua::tealr::mlu::mlua::{self, FromLua, Lua, Table, ToLua, Value};
+
+use crate::{
+    common::sequence::ScriptSequence,
+    error::ReflectionError,
+    script_ref::{ReflectedValue, ScriptRef},
+    sub_reflect::ReflectPathElem,
+};
+
+use super::{conversion::ConversionContext, ApplyLua};
+
+/// The layout of a reflected value as far as table conversions are concerned
+enum Layout {
+    /// A struct with the given field names
+    Struct(Vec<String>),
+    /// A tuple, tuple struct, array or list with the given length,
+    /// only lists can be resized
+    Sequence { len: usize, resizable: bool },
+    /// Anything else, converted via the usual proxy layer
+    Value,
+}
+
+fn layout_of(ref_: &ScriptRef) -> Result<Layout, ReflectionError> {
+    ref_.get(|s| match s.reflect_ref() {
+        ReflectRef::Struct(s) => Layout::Struct(
+            (0..s.field_len())
+                .filter_map(|i| s.name_at(i))
+                .map(ToOwned::to_owned)
+                .collect(),
+        ),
+        ReflectRef::TupleStruct(s) => Layout::Sequence {
+            len: s.field_len(),
+            resizable: false,
+        },
+        ReflectRef::Tuple(s) => Layout::Sequence {
+            len: s.field_len(),
+            resizable: false,
+        },
+        ReflectRef::Array(s) => Layout::Sequence {
+            len: s.len(),
+            resizable: false,
+        },
+        ReflectRef::List(s) => Layout::Sequence {
+            len: s.len(),
+            resizable: true,
+        },
+        _ => Layout::Value,
+    })
+}
+
+fn field_error(path: &str, msg: impl ToString) -> mlua::Error {
+    ReflectionError::InvalidReflectionPath {
+        path: path.to_owned(),
+        msg: msg.to_string(),
+    }
+    .into()
+}
+
+/// Constructs a new script owned value of the given type from a lua table.
+///
+/// The value starts off as the default value of the type (which must reflect `Default`) and the table is applied over it,
+/// fields missing from the table keep their default values.
+/// Nested tables are converted recursively into structs, tuples, tuple structs, lists and arrays,
+/// all other values are converted with the usual proxy layer (see [`LuaProxyable`](super::LuaProxyable)).
+pub fn table_to_reflect<'lua>(
+    lua: &'lua Lua,
+    world_ptr: WorldPointer,
+    registration: &TypeRegistration,
+    table: Table<'lua>,
+) -> mlua::Result<ReflectedValue> {
+    let default = registration.data::<ReflectDefault>().ok_or_else(|| {
+        mlua::Error::RuntimeError(format!(
+            "Type `{}` does not reflect `Default`, cannot construct it from a table",
+            registration.type_name()
+        ))
+    })?;
+
+    let value = ReflectedValue::new_owned(default.default(), world_ptr);
+    let mut ref_ = value.ref_.clone();
+    apply_table_value(
+        lua,
+        &mut ref_,
+        Value::Table(table),
+        registration.short_name(),
+    )?;
+
+    Ok(value)
+}
+
+/// Applies the given lua value to the reflected value, recursing into tables.
+///
+/// Errors name the path (starting from `path`) of the field which failed to convert.
+pub fn apply_table_value<'lua>(
+    lua: &'lua Lua,
+    ref_: &mut ScriptRef,
+    value: Value<'lua>,
+    path: &str,
+) -> mlua::Result<()> {
+    if let Value::Table(table) = &value {
+        match layout_of(ref_)? {
+            Layout::Struct(fields) => return apply_struct(lua, ref_, table.clone(), &fields, path),
+            Layout::Sequence { len, resizable } => {
+                return apply_sequence(lua, ref_, table.clone(), len, resizable, path)
+            }
+            Layout::Value => {}
+        }
+    }
+
+    ref_.apply_lua(lua, value).map_err(|e| field_error(path, e))
+}
+
+fn apply_struct<'lua>(
+    lua: &'lua Lua,
+    ref_: &mut ScriptRef,
+    table: Table<'lua>,
+    fields: &[String],
+    path: &str,
+) -> mlua::Result<()> {
+    for pair in table.pairs::<Value, Value>() {
+        let (key, value) = pair?;
+
+        let field = match key {
+            Value::String(s) => s.to_str()?.to_owned(),
+            key => {
+                return Err(field_error(
+                    path,
+                    format!("Expected a field name, found `{key:?}`"),
+                ))
+            }
+        };
+
+        let field_path = format!("{path}.{field}");
+
+        if !fields.contains(&field) {
+            return Err(field_error(&field_path, "No such field"));
+        }
+
+        let mut field_ref = ref_.sub_ref(ReflectPathElem::FieldAccess(field.into()));
+        apply_table_value(lua, &mut field_ref, value, &field_path)?;
+    }
+
+    Ok(())
+}
+
+fn apply_sequence<'lua>(
+    lua: &'lua Lua,
+    ref_: &mut ScriptRef,
+    table: Table<'lua>,
+    len: usize,
+    resizable: bool,
+    path: &str,
+) -> mlua::Result<()> {
+    let new_len = table.raw_len() as usize;
+
+    if resizable {
+        resize_list(ref_, len, new_len, path)?;
+    } else if new_len != len {
+        return Err(field_error(
+            path,
+            format!("Expected {len} elements, found {new_len}"),
+        ));
+    }
+
+    for idx in 0..new_len {
+        // lua tables are 1-indexed
+        let value = table.raw_get::<_, Value>(idx + 1)?;
+        let mut elem_ref = ref_.sub_ref(ReflectPathElem::IndexAccess(idx));
+
+        apply_table_value(lua, &mut elem_ref, value, &format!("{path}[{}]", idx + 1))?;
+    }
+
+    Ok(())
+}
+
+/// Truncates or grows the list behind the reference, new elements are default initialized
+fn resize_list(ref_: &mut ScriptRef, len: usize, new_len: usize, path: &str) -> mlua::Result<()> {
+    let default = if new_len > len {
+        let item_type = ref_.get(|s| match s.get_type_info() {
+            TypeInfo::List(info) => Some((info.item_type_id(), info.item_type_name())),
+            _ => None,
+        })?;
+
+        let (item_type_id, item_type_name) =
+            item_type.ok_or_else(|| field_error(path, "Not a list"))?;
+
+        let world = ref_.world_ptr.read();
+        let registry = world.resource::<AppTypeRegistry>().read();
+
+        Some(
+            registry
+                .get_type_data::<ReflectDefault>(item_type_id)
+                .cloned()
+                .ok_or_else(|| {
+                    field_error(
+                        path,
+                        format!("Element type `{item_type_name}` does not reflect `Default`, cannot grow the list"),
+                    )
+                })?,
+        )
+    } else {
+        None
+    };
+
+    ref_.get_mut(|s| {
+        if let ReflectMut::List(list) = s.reflect_mut() {
+            while list.len() > new_len {
+                list.pop();
+            }
+
+            if let Some(default) = default {
+                while list.len() < new_len {
+                    list.push(default.default());
+                }
+            }
+        }
+    })?;
+
+    Ok(())
+}
+
+/// Converts the value behind the reference into a lua table, the inverse of [`table_to_reflect`].
+///
+/// Structs become tables keyed by field names, tuples, tuple structs, lists and arrays become sequences.
+/// All other values are converted to their usual lua representation.
+pub fn reflect_to_table<'lua>(lua: &'lua Lua, ref_: ScriptRef) -> mlua::Result<Value<'lua>> {
+    match layout_of(&ref_)? {
+        Layout::Struct(fields) => {
+            let table = lua.create_table()?;
+            for field in fields {
+                let field_ref = ref_.sub_ref(ReflectPathElem::FieldAccess(field.clone().into()));
+                table.raw_set(field, reflect_to_table(lua, field_ref)?)?;
+            }
+            Ok(Value::Table(table))
+        }
+        Layout::Sequence { len, .. } => {
+            let table = lua.create_table()?;
+            for idx in 0..len {
+                let elem_ref = ref_.sub_ref(ReflectPathElem::IndexAccess(idx));
+                table.raw_set(idx + 1, reflect_to_table(lua, elem_ref)?)?;
+            }
+            Ok(Value::Table(table))
+        }
+        Layout::Value => ref_.to_lua(lua),
+    }
+}
+
+/// Converts a lua sequence such as `{1, 2, 3}` into a math type, see [`ScriptSequence`].
+///
+/// Fails if the table doesn't hold exactly as many elements as the type, or if any of them fails to convert.
+pub fn sequence_from_table<'lua, T>(table: Table<'lua>) -> mlua::Result<T>
+where
+    T: ScriptSequence,
+    T::Element: FromLua<'lua>,
+{
+    let elements = table
+        .sequence_values::<T::Element>()
+        .enumerate()
+        .map(|(idx, elem)| elem.at_index(idx + 1))
+        .collect::<mlua::Result<Vec<_>>>()?;
+
+    T::from_sequence(&elements).map_err(|message| mlua::Error::FromLuaConversionError {
+        from: "table",
+        to: type_name::<T>(),
+        message: Some(message),
+    })
+}
+
+/// Converts a math type into a lua sequence of its elements, the inverse of [`sequence_from_table`]
+pub fn sequence_to_table<'lua, T>(lua: &'lua Lua, value: &T) -> mlua::Result<Table<'lua>>
+where
+    T: ScriptSequence,
+    T::Element: ToLua<'lua>,
+{
+    lua.create_sequence_from(value.to_elements())
+}
@@ -0,0 +1,76 @@
+use std::sync::Mutex;
+
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+use bevy_mod_scripting_lua::{docs::LuaDocFragment, tealr};
+
+use tealr::mlu::{
+    mlua::{self, Lua},
+    TealData, TealDataMethods,
+};
+
+use crate::common::time::ScriptTime;
+use crate::impl_tealr_type;
+
+pub type LuaScriptTime = ScriptTime;
+
+impl_tealr_type!(LuaScriptTime);
+
+fn to_lua_error(e: ScriptError) -> mlua::Error {
+    mlua::Error::RuntimeError(e.to_string())
+}
+
+impl TealData for LuaScriptTime {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type("Reads the frame timing, available to scripts as `time`.");
+
+        methods.document(
+            "Returns the time in seconds elapsed between the previous frame and the current one.",
+        );
+        methods.add_method("delta_seconds", |_, time, ()| {
+            time.delta_seconds().map_err(to_lua_error)
+        });
+
+        methods.document("Returns the time in seconds elapsed since the app started.");
+        methods.add_method("elapsed_seconds", |_, time, ()| {
+            time.elapsed_seconds().map_err(to_lua_error)
+        });
+
+        methods.document("Returns the number of frames since the app started.");
+        methods.add_method("frame_count", |_, time, ()| {
+            time.frame_count().map_err(to_lua_error)
+        });
+    }
+}
+
+/// Provides scripts with the `time` global, which reads the frame timing,
+/// i.e. `time:delta_seconds()`, `time:elapsed_seconds()` or `time:frame_count()`.
+#[derive(Default)]
+pub struct LuaTimeAPIProvider;
+
+impl APIProvider for LuaTimeAPIProvider {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        _script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        let ctx = ctx.get_mut().expect("Could not get context");
+        ctx.globals()
+            .set("time", LuaScriptTime::new(world_ptr))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn get_doc_fragment(&self) -> Option<Self::DocTarget> {
+        Some(LuaDocFragment::new("TimeAPI", |tw| {
+            tw.process_type::<LuaScriptTime>()
+        }))
+    }
+}
@@ -0,0 +1,203 @@
+use std::sync::Mutex;
+
+use bevy::prelude::{App, CoreSet, IntoSystemConfig};
+use bevy_mod_scripting_core::prelude::*;
+use bevy_mod_scripting_lua::{docs::LuaDocFragment, tealr, LuaArg, LuaEvent, LuaScriptHost};
+
+use tealr::mlu::{
+    mlua::{self, Function, Lua, MultiValue, Value},
+    TealData, TealDataMethods,
+};
+
+use crate::common::timers::{script_timer_system, ScriptTimerHandle, ScriptTimers};
+use crate::impl_tealr_type;
+
+pub type LuaScriptTimers = ScriptTimerHandle;
+
+/// The prefix of the hooks calling the functions scheduled by scripts, followed by the id of the timer.
+///
+/// The functions are kept in the registry under the same name
+const TIMER_HOOK_PREFIX: &str = "__bevy_mod_scripting_timer_";
+
+fn timer_hook_name(id: u64) -> String {
+    format!("{TIMER_HOOK_PREFIX}{id}")
+}
+
+/// Stores the scheduled function in the registry and defines the hook the timer with the given id calls,
+/// which forgets the function again after its only call for one shot timers
+fn register_timer_function(
+    lua: &Lua,
+    id: u64,
+    callback: Function,
+    repeating: bool,
+) -> mlua::Result<()> {
+    let hook_name = timer_hook_name(id);
+    lua.set_named_registry_value(&hook_name, callback)?;
+    let name = hook_name.clone();
+    let hook = lua.create_function(move |lua, _: MultiValue| {
+        let callback: Function = lua.named_registry_value(&name)?;
+        if !repeating {
+            forget_timer_function(lua, &name)?;
+        }
+        callback.call::<_, ()>(())
+    })?;
+    lua.globals().raw_set(hook_name, hook)
+}
+
+fn forget_timer_function(lua: &Lua, hook_name: &str) -> mlua::Result<()> {
+    lua.unset_named_registry_value(hook_name)?;
+    lua.globals().raw_set(hook_name, Value::Nil)
+}
+
+/// The name of the hook given to `after` or `every` instead of a function
+fn hook_name(hook: Value) -> mlua::Result<String> {
+    match hook {
+        Value::String(name) => Ok(name.to_str()?.to_owned()),
+        other => Err(mlua::Error::FromLuaConversionError {
+            from: other.type_name(),
+            to: "timer hook",
+            message: Some("expected a function or the name of a hook".to_owned()),
+        }),
+    }
+}
+
+/// Schedules a call to the given function or hook once after `delay` seconds
+fn after(lua: &Lua, timers: &LuaScriptTimers, delay: f64, hook: Value) -> mlua::Result<u64> {
+    match hook {
+        Value::Function(callback) => {
+            let id = timers.after_with(delay, timer_hook_name);
+            register_timer_function(lua, id, callback, false)?;
+            Ok(id)
+        }
+        hook => Ok(timers.after(delay, hook_name(hook)?)),
+    }
+}
+
+/// Schedules calls to the given function or hook every `interval` seconds
+fn every(lua: &Lua, timers: &LuaScriptTimers, interval: f64, hook: Value) -> mlua::Result<u64> {
+    let id = match hook {
+        Value::Function(callback) => {
+            let id = timers.every_with(interval, timer_hook_name);
+            if let Some(id) = id {
+                register_timer_function(lua, id, callback, true)?;
+            }
+            id
+        }
+        hook => timers.every(interval, hook_name(hook)?),
+    };
+    id.ok_or_else(|| {
+        mlua::Error::RuntimeError(format!("Timer interval must be positive, got `{interval}`"))
+    })
+}
+
+fn cancel(lua: &Lua, timers: &LuaScriptTimers, id: u64) -> mlua::Result<bool> {
+    let cancelled = timers.cancel(id);
+    if cancelled {
+        forget_timer_function(lua, &timer_hook_name(id))?;
+    }
+    Ok(cancelled)
+}
+
+impl_tealr_type!(LuaScriptTimers);
+
+impl TealData for LuaScriptTimers {
+    fn add_methods<'lua, T: TealDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.document_type(
+            "Schedules calls to this script's hooks, available to scripts as `timers`.",
+        );
+
+        methods.document("Calls the given function, or the hook with the given name, once after `delay` seconds, returns the id of the timer.");
+        methods.add_method("after", |lua, timers, (delay, hook): (f64, Value)| {
+            after(lua, timers, delay, hook)
+        });
+
+        methods.document("Calls the given function, or the hook with the given name, every `interval` seconds, returns the id of the timer.");
+        methods.add_method("every", |lua, timers, (interval, hook): (f64, Value)| {
+            every(lua, timers, interval, hook)
+        });
+
+        methods
+            .document("Cancels the timer with the given id, returns true if it was still pending.");
+        methods.add_method("cancel", |lua, timers, id: u64| cancel(lua, timers, id));
+    }
+}
+
+/// Exposes the methods of the given timers as the `after`, `every` and `cancel_timer` globals
+fn attach_timer_globals(lua: &Lua, timers: LuaScriptTimers) -> mlua::Result<()> {
+    let globals = lua.globals();
+
+    let t = timers.clone();
+    globals.set(
+        "after",
+        lua.create_function(move |lua, (delay, hook): (f64, Value)| after(lua, &t, delay, hook))?,
+    )?;
+
+    let t = timers.clone();
+    globals.set(
+        "every",
+        lua.create_function(move |lua, (interval, hook): (f64, Value)| {
+            every(lua, &t, interval, hook)
+        })?,
+    )?;
+
+    globals.set(
+        "cancel_timer",
+        lua.create_function(move |lua, id: u64| cancel(lua, &timers, id))?,
+    )?;
+
+    globals.set("timers", timers)
+}
+
+/// Provides scripts with the `timers` global, used to call the script's hooks after a delay or repeatedly.
+/// Its methods are also available as the `after`, `every` and `cancel_timer` globals.
+///
+/// Timers are ticked against `Time` in `CoreSet::PreUpdate` and due hooks are sent as [`LuaEvent`]s
+/// with default arguments and the priority given in [`ScriptTimers::new`].
+pub struct LuaTimerAPIProvider<A: LuaArg> {
+    pub timers: ScriptTimers<LuaEvent<A>>,
+}
+
+impl<A: LuaArg> Default for LuaTimerAPIProvider<A> {
+    fn default() -> Self {
+        Self {
+            timers: Default::default(),
+        }
+    }
+}
+
+impl<A: LuaArg> LuaTimerAPIProvider<A> {
+    pub fn new(timers: ScriptTimers<LuaEvent<A>>) -> Self {
+        Self { timers }
+    }
+}
+
+impl<A: LuaArg + Default> APIProvider for LuaTimerAPIProvider<A> {
+    type APITarget = Mutex<Lua>;
+    type ScriptContext = Mutex<Lua>;
+    type DocTarget = LuaDocFragment;
+
+    fn attach_api(&mut self, _: &mut Self::APITarget) -> Result<(), ScriptError> {
+        Ok(())
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        let ctx = ctx.get_mut().expect("Could not get context");
+        attach_timer_globals(ctx, self.timers.for_script(script_data.sid))
+            .map_err(ScriptError::new_other)
+    }
+
+    fn get_doc_fragment(&self) -> Option<Self::DocTarget> {
+        Some(LuaDocFragment::new("TimerAPI", |tw| {
+            tw.process_type::<LuaScriptTimers>()
+        }))
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        app.insert_resource(self.timers.clone())
+            .add_system(script_timer_system::<LuaScriptHost<A>>.in_base_set(CoreSet::PreUpdate));
+    }
+}
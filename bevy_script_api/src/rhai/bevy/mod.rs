@@ -1,4 +1,10 @@
-use bevy::prelude::Entity;
+use bevy::{
+    math::{
+        DQuat, DVec2, DVec3, DVec4, IVec2, IVec3, IVec4, Quat, UVec2, UVec3, UVec4, Vec2, Vec3,
+        Vec3A, Vec4,
+    },
+    prelude::Entity,
+};
 use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
 
 #[allow(deprecated)]
@@ -9,11 +15,18 @@ use bevy_mod_scripting_rhai::{
 use rhai::plugin::*;
 
 use crate::{
-    common::bevy::{ScriptTypeRegistration, ScriptWorld},
+    common::{
+        bevy::{ScriptQueryResult, ScriptTypeRegistration, ScriptWorld},
+        iter::mark_structural_change,
+    },
     ReflectedValue,
 };
 
-use super::{RegisterForeignRhaiType, ToDynamic};
+use super::{
+    iter::RhaiIter,
+    std::{register_sequence_types, RhaiDynVec},
+    RegisterForeignRhaiType, ToDynamic,
+};
 
 #[allow(deprecated)]
 impl CustomType for ScriptTypeRegistration {
@@ -29,6 +42,23 @@ impl CustomType for ScriptTypeRegistration {
     }
 }
 
+#[allow(deprecated)]
+impl CustomType for ScriptQueryResult {
+    fn build(mut builder: rhai::TypeBuilder<Self>) {
+        builder
+            .with_name("QueryResult")
+            .with_fn("entity", |self_: &mut Self| self_.entity())
+            .with_fn("components", |self_: &mut Self| {
+                self_
+                    .components()
+                    .iter()
+                    .map(|c| c.clone().to_dynamic())
+                    .collect::<Result<rhai::Array, _>>()
+            })
+            .with_fn("to_debug", |self_: &mut Self| format!("{:?}", self_));
+    }
+}
+
 #[allow(deprecated)]
 impl CustomType for ScriptWorld {
     fn build(mut builder: rhai::TypeBuilder<Self>) {
@@ -88,6 +118,42 @@ impl CustomType for ScriptWorld {
                     })
                 },
             )
+            .with_fn(
+                "iter_entities_with",
+                |self_: ScriptWorld, comp_type: ScriptTypeRegistration| {
+                    self_
+                        .iter_entities_with(comp_type)
+                        .map(RhaiIter::new)
+                        .map_err(|e| {
+                            Box::new(EvalAltResult::ErrorRuntime(
+                                e.to_string().into(),
+                                Position::NONE,
+                            ))
+                        })
+                },
+            )
+            .with_fn("query", |self_: ScriptWorld, comp_types: rhai::Array| {
+                let comp_types = comp_types
+                    .into_iter()
+                    .map(|t| {
+                        let type_name = t.type_name();
+                        t.try_cast::<ScriptTypeRegistration>().ok_or_else(|| {
+                            Box::new(EvalAltResult::ErrorMismatchDataType(
+                                "TypeRegistration".to_owned(),
+                                type_name.to_owned(),
+                                Position::NONE,
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                self_.query(comp_types).map(RhaiIter::new).map_err(|e| {
+                    Box::new(EvalAltResult::ErrorRuntime(
+                        e.to_string().into(),
+                        Position::NONE,
+                    ))
+                })
+            })
             .with_fn(
                 "remove_component",
                 |mut self_: ScriptWorld, entity: Entity, comp_type: ScriptTypeRegistration| {
@@ -205,12 +271,15 @@ impl CustomType for ScriptWorld {
             )
             .with_fn("spawn", |self_: &mut ScriptWorld| {
                 let mut w = self_.write();
-                w.spawn(()).id()
+                let entity = w.spawn(()).id();
+                mark_structural_change(&mut w);
+                entity
             })
             .with_fn("despawn", |self_: &mut ScriptWorld, entity: Entity| {
                 let mut w = self_.write();
-
-                w.despawn(entity)
+                let despawned = w.despawn(entity);
+                mark_structural_change(&mut w);
+                despawned
             })
             .with_fn("to_string", |self_: &mut ScriptWorld| self_.to_string())
             .with_fn("to_debug", |self_: &mut ScriptWorld| format!("{:?}", self_));
@@ -228,6 +297,12 @@ impl APIProvider for RhaiBevyAPIProvider {
         engine.build_type::<ReflectedValue>();
         engine.build_type::<ScriptTypeRegistration>();
         engine.build_type::<ScriptWorld>();
+        engine.build_type::<ScriptQueryResult>();
+        engine.build_type::<RhaiIter>();
+        engine.register_iterator_result::<RhaiIter, _>();
+        engine.build_type::<RhaiDynVec>();
+        engine.register_iterator_result::<RhaiDynVec, _>();
+        register_sequence_types(engine);
         Ok(())
     }
 
@@ -271,5 +346,20 @@ impl APIProvider for RhaiBevyAPIProvider {
         app.register_foreign_rhai_type::<u128>();
         app.register_foreign_rhai_type::<usize>();
         app.register_foreign_rhai_type::<String>();
+        app.register_foreign_rhai_type::<Vec2>();
+        app.register_foreign_rhai_type::<Vec3>();
+        app.register_foreign_rhai_type::<Vec3A>();
+        app.register_foreign_rhai_type::<Vec4>();
+        app.register_foreign_rhai_type::<Quat>();
+        app.register_foreign_rhai_type::<DVec2>();
+        app.register_foreign_rhai_type::<DVec3>();
+        app.register_foreign_rhai_type::<DVec4>();
+        app.register_foreign_rhai_type::<DQuat>();
+        app.register_foreign_rhai_type::<IVec2>();
+        app.register_foreign_rhai_type::<IVec3>();
+        app.register_foreign_rhai_type::<IVec4>();
+        app.register_foreign_rhai_type::<UVec2>();
+        app.register_foreign_rhai_type::<UVec3>();
+        app.register_foreign_rhai_type::<UVec4>();
     }
 }
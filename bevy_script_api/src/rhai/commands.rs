@@ -0,0 +1,105 @@
+use std::borrow::Cow;
+
+use bevy::prelude::{App, Entity};
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+
+#[allow(deprecated)]
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{self, CustomType, EvalAltResult, Position},
+};
+
+use crate::{
+    common::{
+        bevy::{register_script_commands, ScriptTypeRegistration},
+        commands::ScriptCommands,
+    },
+    rhai::ApplyRhai,
+    script_ref::ValueIndex,
+};
+
+fn to_rhai_error(e: ScriptError) -> Box<EvalAltResult> {
+    Box::new(EvalAltResult::ErrorRuntime(
+        e.to_string().into(),
+        Position::NONE,
+    ))
+}
+
+#[allow(deprecated)]
+impl CustomType for ScriptCommands {
+    fn build(mut builder: rhai::TypeBuilder<Self>) {
+        builder
+            .with_name("Commands")
+            .with_fn("spawn", |self_: &mut Self| self_.spawn())
+            .with_fn("despawn", |self_: &mut Self, entity: Entity| {
+                self_.despawn(entity)
+            })
+            .with_fn("despawn_recursive", |self_: &mut Self, entity: Entity| {
+                self_.despawn_recursive(entity)
+            })
+            .with_result_fn(
+                "insert",
+                |self_: &mut Self, entity: Entity, comp_type: ScriptTypeRegistration| {
+                    self_
+                        .insert_default(entity, comp_type)
+                        .map_err(to_rhai_error)
+                },
+            )
+            .with_result_fn(
+                "insert_component",
+                |self_: &mut Self, entity: Entity, type_name: &str, fields: rhai::Map| {
+                    self_.insert_component(entity, type_name, |component| {
+                        for (field, value) in fields {
+                            component
+                                .index(Cow::Owned(field.to_string()))
+                                .apply_rhai(value)?;
+                        }
+                        Ok(())
+                    })
+                },
+            )
+            .with_result_fn(
+                "remove",
+                |self_: &mut Self, entity: Entity, comp_type: ScriptTypeRegistration| {
+                    self_.remove(entity, comp_type).map_err(to_rhai_error)
+                },
+            )
+            .with_fn("to_debug", |self_: &mut Self| format!("{:?}", self_));
+    }
+}
+
+/// Provides scripts with the `commands` variable, which spawns and despawns entities and inserts and removes components
+/// at the end of the frame rather than while scripts are running, i.e. `commands.insert(commands.spawn(), world.get_type_by_name("Transform"))`.
+///
+/// Components can be built from their default value and an object map of fields,
+/// i.e. `commands.insert_component(commands.spawn(), "Transform", #{ scale: vec3(2.0, 2.0, 2.0) })`.
+///
+/// See [`ScriptCommands`] for when the commands are applied.
+#[derive(Default)]
+pub struct RhaiCommandsAPIProvider;
+
+impl APIProvider for RhaiCommandsAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine.build_type::<ScriptCommands>();
+        Ok(())
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        _script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        ctx.scope
+            .set_value("commands", ScriptCommands::new(world_ptr));
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        register_script_commands(app);
+    }
+}
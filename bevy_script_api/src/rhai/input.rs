@@ -0,0 +1,80 @@
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+
+#[allow(deprecated)]
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{self, CustomType, Dynamic, EvalAltResult, Position},
+};
+
+use crate::common::input::ScriptInput;
+
+fn to_rhai_error(e: ScriptError) -> Box<EvalAltResult> {
+    Box::new(EvalAltResult::ErrorRuntime(
+        e.to_string().into(),
+        Position::NONE,
+    ))
+}
+
+#[allow(deprecated)]
+impl CustomType for ScriptInput {
+    fn build(mut builder: rhai::TypeBuilder<Self>) {
+        builder
+            .with_name("Input")
+            .with_result_fn("is_key_pressed", |self_: &mut Self, key: &str| {
+                self_.is_key_pressed(key).map_err(to_rhai_error)
+            })
+            .with_result_fn("is_key_just_pressed", |self_: &mut Self, key: &str| {
+                self_.is_key_just_pressed(key).map_err(to_rhai_error)
+            })
+            .with_result_fn("is_key_just_released", |self_: &mut Self, key: &str| {
+                self_.is_key_just_released(key).map_err(to_rhai_error)
+            })
+            .with_result_fn("is_mouse_pressed", |self_: &mut Self, button: &str| {
+                self_.is_mouse_pressed(button).map_err(to_rhai_error)
+            })
+            .with_result_fn("is_mouse_just_pressed", |self_: &mut Self, button: &str| {
+                self_.is_mouse_just_pressed(button).map_err(to_rhai_error)
+            })
+            .with_result_fn(
+                "is_mouse_just_released",
+                |self_: &mut Self, button: &str| {
+                    self_.is_mouse_just_released(button).map_err(to_rhai_error)
+                },
+            )
+            .with_fn("mouse_position", |self_: &mut Self| {
+                self_
+                    .mouse_position()
+                    .map(Dynamic::from)
+                    .unwrap_or(Dynamic::UNIT)
+            })
+            .with_fn("to_debug", |self_: &mut Self| format!("{:?}", self_));
+    }
+}
+
+/// Provides scripts with the `input` variable, which reads the state of the keyboard and mouse,
+/// i.e. `input.is_key_pressed("Space")` or `input.mouse_position()`, which is `()` if the cursor is outside the window.
+///
+/// See [`ScriptInput`] for how keys and mouse buttons are named.
+#[derive(Default)]
+pub struct RhaiInputAPIProvider;
+
+impl APIProvider for RhaiInputAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine.build_type::<ScriptInput>();
+        Ok(())
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        _script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        ctx.scope.set_value("input", ScriptInput::new(world_ptr));
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+#[allow(deprecated)]
+use bevy_mod_scripting_rhai::rhai::{self, CustomType, Dynamic, EvalAltResult};
+
+use crate::common::iter::ScriptIter;
+
+type RhaiNextFn = dyn FnMut() -> Result<Option<Dynamic>, Box<EvalAltResult>> + Send;
+
+/// A lazy iterator proxy, elements are converted to rhai values only once the script asks for them.
+///
+/// Can be used directly in for loops (`for v in iter {}`).
+#[derive(Clone)]
+pub struct RhaiIter(Arc<Mutex<Box<RhaiNextFn>>>);
+
+impl RhaiIter {
+    pub fn new<T: Clone + Send + Sync + 'static>(mut iter: ScriptIter<T>) -> Self {
+        Self(Arc::new(Mutex::new(Box::new(move || {
+            Ok(iter.next_checked()?.map(Dynamic::from))
+        }))))
+    }
+
+    /// Produces the next element, or `None` if the iterator is exhausted
+    pub fn next(&self) -> Result<Option<Dynamic>, Box<EvalAltResult>> {
+        (self.0.lock())()
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> From<ScriptIter<T>> for RhaiIter {
+    fn from(iter: ScriptIter<T>) -> Self {
+        Self::new(iter)
+    }
+}
+
+impl std::fmt::Debug for RhaiIter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Iter")
+    }
+}
+
+impl IntoIterator for RhaiIter {
+    type Item = Result<Dynamic, Box<EvalAltResult>>;
+
+    type IntoIter = RhaiIterIntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        RhaiIterIntoIter(self)
+    }
+}
+
+pub struct RhaiIterIntoIter(RhaiIter);
+
+impl Iterator for RhaiIterIntoIter {
+    type Item = Result<Dynamic, Box<EvalAltResult>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().transpose()
+    }
+}
+
+#[allow(deprecated)]
+impl CustomType for RhaiIter {
+    fn build(mut builder: rhai::TypeBuilder<Self>) {
+        builder
+            .with_name("Iter")
+            .with_fn("next", |self_: &mut Self| {
+                self_.next().map(Option::unwrap_or_default)
+            })
+            .with_fn("to_debug", |self_: &mut Self| format!("{:?}", self_));
+    }
+}
@@ -0,0 +1,59 @@
+use bevy::log::Level;
+use bevy_mod_scripting_core::prelude::*;
+
+#[allow(deprecated)]
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{self, CustomType, Dynamic},
+};
+
+use crate::common::log::ScriptLogger;
+
+#[allow(deprecated)]
+impl CustomType for ScriptLogger {
+    fn build(mut builder: rhai::TypeBuilder<Self>) {
+        builder
+            .with_name("Log")
+            .with_fn("error", |self_: &mut Self, msg: Dynamic| {
+                self_.log(Level::ERROR, &msg.to_string())
+            })
+            .with_fn("warn", |self_: &mut Self, msg: Dynamic| {
+                self_.log(Level::WARN, &msg.to_string())
+            })
+            .with_fn("info", |self_: &mut Self, msg: Dynamic| {
+                self_.log(Level::INFO, &msg.to_string())
+            })
+            .with_fn("debug", |self_: &mut Self, msg: Dynamic| {
+                self_.log(Level::DEBUG, &msg.to_string())
+            })
+            .with_fn("to_debug", |self_: &mut Self| format!("{:?}", self_));
+    }
+}
+
+/// Provides scripts with the `log` variable, whose `error`, `warn`, `info` and `debug` methods
+/// log through `tracing` on behalf of the script instead of printing to stdout.
+///
+/// See [`ScriptLogger`] for how script output can be filtered.
+#[derive(Default)]
+pub struct RhaiLogAPIProvider;
+
+impl APIProvider for RhaiLogAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine.build_type::<ScriptLogger>();
+        Ok(())
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        ctx.scope
+            .set_value("log", ScriptLogger::new(script_data.name));
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use bevy::math::{EulerRot, Mat3, Mat4, Quat, Vec2, Vec3, Vec4};
+use bevy_mod_scripting_core::prelude::*;
+
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{Dynamic, EvalAltResult, Position, FLOAT},
+};
+
+use crate::rhai::std::sequence_from_array;
+
+fn math_error(msg: String) -> Box<EvalAltResult> {
+    Box::new(EvalAltResult::ErrorRuntime(
+        Dynamic::from(msg),
+        Position::NONE,
+    ))
+}
+
+/// Registers the component accessors, arithmetic and the operations shared by all vector types
+macro_rules! register_vec {
+    ($engine:ident, $type:ident, $name:literal, [$($field:ident),*]) => {
+        $engine.register_type_with_name::<$type>($name);
+        $(
+            $engine.register_get_set(
+                stringify!($field),
+                |v: &mut $type| v.$field as FLOAT,
+                |v: &mut $type, val: FLOAT| v.$field = val as f32,
+            );
+        )*
+        $engine
+            .register_fn("+", |a: $type, b: $type| a + b)
+            .register_fn("-", |a: $type, b: $type| a - b)
+            .register_fn("-", |a: $type| -a)
+            .register_fn("*", |a: $type, b: $type| a * b)
+            .register_fn("*", |a: $type, b: FLOAT| a * b as f32)
+            .register_fn("*", |a: FLOAT, b: $type| a as f32 * b)
+            .register_fn("/", |a: $type, b: FLOAT| a / b as f32)
+            .register_fn("==", |a: $type, b: $type| a == b)
+            .register_fn("!=", |a: $type, b: $type| a != b)
+            .register_fn("lerp", |a: $type, b: $type, t: FLOAT| a.lerp(b, t as f32))
+            .register_fn("dot", |a: $type, b: $type| a.dot(b) as FLOAT)
+            .register_fn("length", |v: $type| v.length() as FLOAT)
+            .register_fn("normalize", |v: $type| {
+                v.try_normalize()
+                    .ok_or_else(|| math_error(format!("Cannot normalize a zero length `{}`", $name)))
+            })
+            .register_fn("to_string", |v: &mut $type| v.to_string())
+            .register_fn("to_debug", |v: &mut $type| format!("{v:?}"));
+    };
+}
+
+/// Registers the arithmetic and the operations shared by all matrix types
+macro_rules! register_mat {
+    ($engine:ident, $type:ident, $name:literal, $vec:ident) => {
+        $engine
+            .register_type_with_name::<$type>($name)
+            .register_fn("*", |a: $type, b: $type| a * b)
+            .register_fn("*", |a: $type, b: $vec| a * b)
+            .register_fn("+", |a: $type, b: $type| a + b)
+            .register_fn("-", |a: $type, b: $type| a - b)
+            .register_fn("==", |a: $type, b: $type| a == b)
+            .register_fn("!=", |a: $type, b: $type| a != b)
+            .register_fn("inverse", |m: $type| m.inverse())
+            .register_fn("transpose", |m: $type| m.transpose())
+            .register_fn("determinant", |m: $type| m.determinant() as FLOAT)
+            .register_fn("to_string", |m: &mut $type| m.to_string())
+            .register_fn("to_debug", |m: &mut $type| format!("{m:?}"));
+    };
+}
+
+/// Provides scripts with the `Vec2`, `Vec3`, `Vec4`, `Quat`, `Mat3` and `Mat4` types, their constructors
+/// (i.e. `vec3(1.0, 0.0, 0.0)` or `quat_from_axis_angle(vec3(0.0, 1.0, 0.0), PI / 2.0)`), arithmetic operators
+/// and common operations such as `lerp`, `dot`, `cross`, `normalize` and `angle`.
+///
+/// Vectors and quaternions can also be constructed from arrays (i.e. `vec3([1.0, 0.0, 0.0])`)
+/// and converted back into them with `to_array`, which is registered by `RhaiBevyAPIProvider`.
+///
+/// `lerp` also works on plain numbers and spherically interpolates quaternions.
+#[derive(Default)]
+pub struct RhaiMathAPIProvider;
+
+impl APIProvider for RhaiMathAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        register_vec!(engine, Vec2, "Vec2", [x, y]);
+        register_vec!(engine, Vec3, "Vec3", [x, y, z]);
+        register_vec!(engine, Vec4, "Vec4", [x, y, z, w]);
+        register_mat!(engine, Mat3, "Mat3", Vec3);
+        register_mat!(engine, Mat4, "Mat4", Vec4);
+
+        engine
+            .register_type_with_name::<Quat>("Quat")
+            .register_fn("*", |a: Quat, b: Quat| a * b)
+            .register_fn("*", |a: Quat, b: Vec3| a * b)
+            .register_fn("==", |a: Quat, b: Quat| a == b)
+            .register_fn("!=", |a: Quat, b: Quat| a != b)
+            .register_fn("lerp", |a: Quat, b: Quat, t: FLOAT| a.slerp(b, t as f32))
+            .register_fn("dot", |a: Quat, b: Quat| a.dot(b) as FLOAT)
+            .register_fn("length", |q: Quat| q.length() as FLOAT)
+            .register_fn("normalize", |q: Quat| {
+                Vec4::from(q)
+                    .try_normalize()
+                    .map(Quat::from_vec4)
+                    .ok_or_else(|| math_error("Cannot normalize a zero length `Quat`".to_owned()))
+            })
+            .register_fn("angle", |a: Quat, b: Quat| a.angle_between(b) as FLOAT)
+            .register_fn("inverse", |q: Quat| q.inverse())
+            .register_fn("to_string", |q: &mut Quat| q.to_string())
+            .register_fn("to_debug", |q: &mut Quat| format!("{q:?}"));
+
+        // constructors
+        engine
+            .register_fn("vec2", |x: FLOAT, y: FLOAT| Vec2::new(x as f32, y as f32))
+            .register_fn("vec3", |x: FLOAT, y: FLOAT, z: FLOAT| {
+                Vec3::new(x as f32, y as f32, z as f32)
+            })
+            .register_fn("vec4", |x: FLOAT, y: FLOAT, z: FLOAT, w: FLOAT| {
+                Vec4::new(x as f32, y as f32, z as f32, w as f32)
+            })
+            .register_fn("quat", |x: FLOAT, y: FLOAT, z: FLOAT, w: FLOAT| {
+                Quat::from_xyzw(x as f32, y as f32, z as f32, w as f32)
+            })
+            .register_fn("vec2", sequence_from_array::<Vec2>)
+            .register_fn("vec3", sequence_from_array::<Vec3>)
+            .register_fn("vec4", sequence_from_array::<Vec4>)
+            .register_fn("quat", sequence_from_array::<Quat>)
+            .register_fn("quat_from_axis_angle", |axis: Vec3, angle: FLOAT| {
+                Quat::from_axis_angle(axis.normalize(), angle as f32)
+            })
+            .register_fn("quat_from_euler", |x: FLOAT, y: FLOAT, z: FLOAT| {
+                Quat::from_euler(EulerRot::XYZ, x as f32, y as f32, z as f32)
+            })
+            .register_fn("mat3_identity", || Mat3::IDENTITY)
+            .register_fn("mat3_from_quat", Mat3::from_quat)
+            .register_fn("mat4_identity", || Mat4::IDENTITY)
+            .register_fn(
+                "mat4_from_srt",
+                |scale: Vec3, rotation: Quat, translation: Vec3| {
+                    Mat4::from_scale_rotation_translation(scale, rotation, translation)
+                },
+            );
+
+        // operations specific to some types
+        engine
+            .register_fn("lerp", |a: FLOAT, b: FLOAT, t: FLOAT| a + (b - a) * t)
+            .register_fn("cross", |a: Vec3, b: Vec3| a.cross(b))
+            .register_fn("angle", |a: Vec2, b: Vec2| a.angle_between(b) as FLOAT)
+            .register_fn("angle", |a: Vec3, b: Vec3| a.angle_between(b) as FLOAT);
+
+        Ok(())
+    }
+}
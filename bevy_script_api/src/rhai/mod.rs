@@ -5,12 +5,33 @@ use ::bevy::{
     reflect::{FromType, GetTypeRegistration, Reflect},
 };
 #[allow(deprecated)]
-use bevy_mod_scripting_rhai::rhai::{CustomType, Dynamic, EvalAltResult, INT};
+use bevy_mod_scripting_rhai::rhai::{
+    CustomType, Dynamic, Engine, EvalAltResult, Module, FLOAT, INT,
+};
+
+use bevy_mod_scripting_core::hosts::Recipients;
+use bevy_mod_scripting_rhai::{rhai::FuncArgs, RhaiEvent};
 
-use crate::{ReflectedValue, ScriptRef, ValueIndex};
+use crate::{
+    common::{
+        events::{HookEvent, ReflectEvent},
+        scalar::ScriptScalar,
+    },
+    ReflectedValue, ScriptRef, ValueIndex,
+};
 
 pub mod bevy;
+pub mod commands;
+pub mod input;
+pub mod iter;
+pub mod log;
+#[cfg(feature = "math")]
+pub mod math;
+pub mod rng;
 pub mod std;
+pub mod systems;
+pub mod time;
+pub mod timers;
 
 /// A trait allowing the registration of the [`RhaiProxyable`] trait with the type registry for foreign types
 ///
@@ -87,6 +108,45 @@ impl<T: RhaiProxyable + Reflect> FromType<T> for ReflectRhaiProxyable {
     }
 }
 
+/// A rhai wrapper generated by `impl_rhai_newtype!`, i.e. `RhaiVec3` for `Vec3`.
+///
+/// Methods, fields and operators are registered with the type itself, while static functions and constants
+/// live in a module named after the wrapped type, i.e. `Vec3::new(1.0, 2.0, 3.0)` or `Vec3::ZERO`
+pub trait RhaiWrapper: CustomType {
+    /// The name of the wrapped type, which scripts refer to it by
+    const NAME: &'static str;
+
+    /// Builds the module holding the static functions and constants of the wrapped type
+    fn static_module() -> Result<Module, Box<EvalAltResult>>;
+}
+
+pub trait RegisterRhaiWrapper {
+    /// Registers the given wrapper type along with the static module of the wrapped type
+    fn register_rhai_wrapper<T: RhaiWrapper>(&mut self) -> Result<&mut Self, Box<EvalAltResult>>;
+}
+
+impl RegisterRhaiWrapper for Engine {
+    fn register_rhai_wrapper<T: RhaiWrapper>(&mut self) -> Result<&mut Self, Box<EvalAltResult>> {
+        self.build_type::<T>();
+        self.register_static_module(T::NAME, T::static_module()?.into());
+        Ok(self)
+    }
+}
+
+/// Casts a rhai value to the given proxy type, i.e. the rhai wrapper of an argument received by reference
+pub fn cast_proxy<T: Clone + Send + Sync + 'static>(
+    value: Dynamic,
+) -> Result<T, Box<EvalAltResult>> {
+    let type_name = value.type_name();
+    value.try_cast::<T>().ok_or_else(|| {
+        Box::new(EvalAltResult::ErrorMismatchDataType(
+            ::std::any::type_name::<T>().to_owned(),
+            type_name.to_owned(),
+            Position::NONE,
+        ))
+    })
+}
+
 pub trait ToDynamic {
     fn to_dynamic(self) -> Result<Dynamic, Box<EvalAltResult>>;
 }
@@ -97,8 +157,69 @@ impl ToDynamic for ReflectedValue {
     }
 }
 
+/// Lets hosts with `RhaiEvent<(ReflectedValue,)>` events receive bevy events
+impl From<ReflectedValue> for (ReflectedValue,) {
+    fn from(value: ReflectedValue) -> Self {
+        (value,)
+    }
+}
+
+/// Bevy events are received as [`ReflectedValue`]s, i.e. by a host with `RhaiEvent<(ReflectedValue,)>` events
+impl<A> ReflectEvent for RhaiEvent<A>
+where
+    A: FuncArgs + Send + Clone + Sync + 'static + From<ReflectedValue>,
+{
+    fn reflect_event(hook_name: String, event: ReflectedValue) -> Self {
+        RhaiEvent {
+            hook_name,
+            args: event.into(),
+            recipients: Recipients::All,
+        }
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + Default + 'static> HookEvent for RhaiEvent<A> {
+    fn hook_event(hook_name: String, sid: u32) -> Self {
+        RhaiEvent {
+            hook_name,
+            args: Default::default(),
+            recipients: Recipients::ScriptID(sid),
+        }
+    }
+}
+
+// the integer and float types of rhai depend on its features
+#[allow(clippy::unnecessary_cast)]
+impl ToDynamic for ScriptScalar {
+    fn to_dynamic(self) -> Result<Dynamic, Box<EvalAltResult>> {
+        Ok(match self {
+            ScriptScalar::Bool(b) => Dynamic::from(b),
+            ScriptScalar::Int(i) => Dynamic::from(i as INT),
+            ScriptScalar::Float(f) => Dynamic::from(f as FLOAT),
+        })
+    }
+}
+
+/// The scalar held by the given rhai value, if it's a primitive
+#[allow(clippy::unnecessary_cast)]
+fn rhai_scalar(value: &Dynamic) -> Option<ScriptScalar> {
+    if let Ok(b) = value.as_bool() {
+        Some(ScriptScalar::Bool(b))
+    } else if let Ok(i) = value.as_int() {
+        Some(ScriptScalar::Int(i as i64))
+    } else {
+        value.as_float().ok().map(|f| ScriptScalar::Float(f as f64))
+    }
+}
+
 impl ToDynamic for ScriptRef {
     fn to_dynamic(self) -> Result<Dynamic, Box<EvalAltResult>> {
+        // primitives are converted directly, skipping the type registry
+        let (type_id, scalar) = self.get(|s| (s.type_id(), ScriptScalar::from_reflect(s)))?;
+        if let Some(scalar) = scalar {
+            return scalar.to_dynamic();
+        }
+
         // clone since it's cheap and we don't want to clone self later
         let world = self.world_ptr.clone();
         let world = world.read();
@@ -106,12 +227,10 @@ impl ToDynamic for ScriptRef {
         let type_data = world.resource::<AppTypeRegistry>();
         let g = type_data.read();
 
-        let type_id = self.get(|s| s.type_id())?;
-
         if let Some(v) = g.get_type_data::<ReflectRhaiProxyable>(type_id) {
             v.ref_to_rhai(self)
         } else {
-            ReflectedValue { ref_: self }.to_dynamic()
+            ReflectedValue::new(self).to_dynamic()
         }
     }
 }
@@ -122,6 +241,13 @@ pub trait ApplyRhai {
 
 impl ApplyRhai for ScriptRef {
     fn apply_rhai(&mut self, value: Dynamic) -> Result<(), Box<EvalAltResult>> {
+        // primitives are written directly if the target can hold them
+        if let Some(scalar) = rhai_scalar(&value) {
+            if self.get_mut(|s| scalar.apply_to(s))? {
+                return Ok(());
+            }
+        }
+
         let world_ptr = self.world_ptr.clone();
 
         // remove typedata from the world to be able to manipulate world
@@ -180,6 +306,15 @@ impl CustomType for ReflectedValue {
             .with_indexer_set_result(|obj: &mut ReflectedValue, index: Dynamic, value: Dynamic| {
                 obj.ref_.index(index)?.apply_rhai(value)
             })
+            .with_result_fn("get_path", |obj: &mut ReflectedValue, path: &str| {
+                obj.ref_.sub_ref_path(path)?.to_dynamic()
+            })
+            .with_result_fn(
+                "set_path",
+                |obj: &mut ReflectedValue, path: &str, value: Dynamic| {
+                    obj.ref_.sub_ref_path(path)?.apply_rhai(value)
+                },
+            )
             .with_fn("to_debug", |self_: &mut ReflectedValue| {
                 format!("{self_:?}")
             });
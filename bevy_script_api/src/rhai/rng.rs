@@ -0,0 +1,70 @@
+use bevy_mod_scripting_core::prelude::*;
+
+#[allow(deprecated)]
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{self, CustomType, Dynamic, EvalAltResult, Position, FLOAT, INT},
+};
+
+use crate::common::rng::{ScriptRngHandle, ScriptRngSource};
+
+#[allow(deprecated)]
+impl CustomType for ScriptRngHandle {
+    fn build(mut builder: rhai::TypeBuilder<Self>) {
+        builder
+            .with_name("Rng")
+            .with_fn("range", |self_: &mut Self, min: INT, max: INT| {
+                self_
+                    .range(min as i64, max as i64)
+                    .map(|v| v as INT)
+                    .ok_or_else(|| {
+                        Box::new(EvalAltResult::ErrorRuntime(
+                            Dynamic::from(format!(
+                                "Invalid range, `{min}` is greater than `{max}`"
+                            )),
+                            Position::NONE,
+                        ))
+                    })
+            })
+            .with_fn("float", |self_: &mut Self| self_.float() as FLOAT)
+            .with_fn("seed", |self_: &mut Self, seed: INT| {
+                self_.seed(seed as u64)
+            })
+            .with_fn("to_debug", |self_: &mut Self| format!("{:?}", self_));
+    }
+}
+
+/// Provides scripts with the `rng` variable, a seedable random number generator.
+///
+/// See [`ScriptRngSource`] for the ways generators can be shared between scripts.
+#[derive(Default)]
+pub struct RhaiRngAPIProvider {
+    pub source: ScriptRngSource,
+}
+
+impl RhaiRngAPIProvider {
+    pub fn new(source: ScriptRngSource) -> Self {
+        Self { source }
+    }
+}
+
+impl APIProvider for RhaiRngAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine.build_type::<ScriptRngHandle>();
+        Ok(())
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        ctx.scope
+            .set_value("rng", self.source.for_script(script_data.sid));
+        Ok(())
+    }
+}
@@ -1,11 +1,20 @@
 use std::{any::type_name, iter::Map};
 
-use bevy::reflect::{FromReflect, Reflect};
+use bevy::{
+    math::{
+        DQuat, DVec2, DVec3, DVec4, IVec2, IVec3, IVec4, Quat, UVec2, UVec3, UVec4, Vec2, Vec3,
+        Vec3A, Vec4,
+    },
+    reflect::{FromReflect, Reflect},
+};
+use bevy_mod_scripting_core::world::WorldPointer;
 #[allow(deprecated)]
 use bevy_mod_scripting_rhai::rhai::{CustomType, Dynamic, Engine, EvalAltResult, Position};
 
 use crate::{
-    common::std::ScriptVec, error::ReflectionError, ReflectPathElem, ScriptRef, ValueIndex,
+    common::{sequence::ScriptSequence, std::ScriptVec},
+    error::ReflectionError,
+    ReflectPathElem, ScriptRef, ValueIndex,
 };
 
 use super::{ApplyRhai, FromRhaiProxy, RhaiProxyable, ToDynamic, ToRhaiProxy};
@@ -38,6 +47,12 @@ impl<T: Clone + RhaiCopy + Reflect> RhaiProxyable for T {
 /// This means the proxy for this type is the type itself, and is created by cloning the original reference.
 pub trait RhaiCopy {}
 
+impl<T: Clone + RhaiCopy + Reflect> ToRhaiProxy for T {
+    fn to_rhai_proxy(self) -> Result<Dynamic, Box<EvalAltResult>> {
+        Ok(Dynamic::from(self))
+    }
+}
+
 /// Implements RhaiProxyabel for a numeric type via another proxy type by coercing the type
 macro_rules! impl_rhai_proxy {
     // i.e. impl_rhai_proxy!(String as Into)
@@ -48,6 +63,44 @@ macro_rules! impl_rhai_proxy {
     ($type:ty as $proxy_type:ty) => {
         impl_rhai_proxy!($type, $proxy_type,self:{(self as $proxy_type).into()}, s:{(*s as $proxy_type).into()});
     };
+    // i.e. impl_rhai_proxy!(u128 as i64, checked), for integers wider than the proxy type whose values out of its range are rejected
+    ($type:ty as $proxy_type:ty, checked) => {
+        impl RhaiProxyable for $type {
+            fn ref_to_rhai(
+                self_: crate::ScriptRef,
+            ) -> Result<Dynamic, Box<EvalAltResult>> {
+                self_.get_typed(|s: &$type| s.to_rhai_proxy())?
+            }
+
+            fn apply_rhai(
+                self_: &mut crate::ScriptRef,
+                new_val: Dynamic,
+            ) -> Result<(), Box<EvalAltResult>> {
+                self_.set_val(Self::from_rhai_proxy(new_val)?)?;
+                Ok(())
+            }
+        }
+
+        impl FromRhaiProxy for $type {
+            fn from_rhai_proxy(self_: Dynamic) -> Result<Self, Box<EvalAltResult>> {
+                if self_.is::<$proxy_type>() {
+                    checked_int(self_.cast::<$proxy_type>(), stringify!($type))
+                } else {
+                    Err(Box::new(EvalAltResult::ErrorMismatchDataType(
+                        stringify!($type).to_owned(),
+                        self_.type_name().to_owned(),
+                        Position::NONE,
+                    )))
+                }
+            }
+        }
+
+        impl ToRhaiProxy for $type {
+            fn to_rhai_proxy(self) -> Result<Dynamic, Box<EvalAltResult>> {
+                checked_int::<$proxy_type, _>(self, "script integers").map(Dynamic::from)
+            }
+        }
+    };
     // i.e. impl_rhai_proxy!(ident, u32, i64, (*ident as i64).into()) expression is used in ref_to_rhai
     ($type:ty, $proxy_type:ty,$self:ident: {$($proxy_expr:tt)*}, $self_to_rhai:ident : {$($proxy_expr_to_rhai:tt)*} ) => {
         impl RhaiProxyable for $type {
@@ -92,23 +145,135 @@ macro_rules! impl_rhai_proxy {
 }
 use bevy_mod_scripting_rhai::rhai::{FLOAT, INT};
 
+/// Converts between integer types, failing for values out of range of the target type rather than truncating them
+fn checked_int<T: TryFrom<S>, S: Copy + std::fmt::Display>(
+    value: S,
+    target: &str,
+) -> Result<T, Box<EvalAltResult>> {
+    T::try_from(value).map_err(|_| {
+        Box::new(EvalAltResult::ErrorArithmetic(
+            format!("{value} is out of range of {target}"),
+            Position::NONE,
+        ))
+    })
+}
+
 impl_rhai_proxy!(i8 as INT);
 impl_rhai_proxy!(i16 as INT);
 impl_rhai_proxy!(i32 as INT);
 impl_rhai_proxy!(i64 as INT);
-impl_rhai_proxy!(i128 as INT);
+impl_rhai_proxy!(i128 as INT, checked);
 impl_rhai_proxy!(isize as INT);
 impl_rhai_proxy!(u8 as INT);
 impl_rhai_proxy!(u16 as INT);
 impl_rhai_proxy!(u32 as INT);
+// 64 bit unsigned integers above `INT::MAX` wrap around into negative integers and back
 impl_rhai_proxy!(u64 as INT);
-impl_rhai_proxy!(u128 as INT);
+impl_rhai_proxy!(u128 as INT, checked);
 impl_rhai_proxy!(usize as INT);
 impl_rhai_proxy!(f32 as FLOAT);
 impl_rhai_proxy!(f64 as FLOAT);
 impl_rhai_proxy!(bool as bool);
 impl_rhai_proxy!(String as Into);
 
+/// Converts an array such as `[1.0, 2.0, 3.0]` into a math type, see [`ScriptSequence`].
+///
+/// Fails if the array doesn't hold exactly as many elements as the type, or if any of them fails to convert.
+pub fn sequence_from_array<T>(array: Vec<Dynamic>) -> Result<T, Box<EvalAltResult>>
+where
+    T: ScriptSequence,
+    T::Element: FromRhaiProxy,
+{
+    let elements = array
+        .into_iter()
+        .map(<T::Element as FromRhaiProxy>::from_rhai_proxy)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    T::from_sequence(&elements).map_err(|msg| {
+        Box::new(EvalAltResult::ErrorRuntime(
+            format!("Cannot convert array to `{}`: {msg}", type_name::<T>()).into(),
+            Position::NONE,
+        ))
+    })
+}
+
+/// Converts a math type into an array of its elements, the inverse of [`sequence_from_array`]
+pub fn sequence_to_array<T>(value: &T) -> Result<Vec<Dynamic>, Box<EvalAltResult>>
+where
+    T: ScriptSequence,
+    T::Element: ToRhaiProxy,
+{
+    value
+        .to_elements()
+        .into_iter()
+        .map(ToRhaiProxy::to_rhai_proxy)
+        .collect()
+}
+
+/// Implements the proxies of math types, which are passed to scripts as themselves
+/// and can be converted from either themselves or an array of their elements
+macro_rules! impl_rhai_sequence_proxy {
+    ($($type:ty),*) => {
+        $(
+            impl RhaiProxyable for $type {
+                fn ref_to_rhai(self_: crate::ScriptRef) -> Result<Dynamic, Box<EvalAltResult>> {
+                    self_.get_typed(|self_: &$type| Ok(Dynamic::from(*self_)))?
+                }
+
+                fn apply_rhai(
+                    self_: &mut crate::ScriptRef,
+                    new_val: Dynamic,
+                ) -> Result<(), Box<EvalAltResult>> {
+                    self_.set_val(Self::from_rhai_proxy(new_val)?)?;
+                    Ok(())
+                }
+            }
+
+            impl FromRhaiProxy for $type {
+                fn from_rhai_proxy(self_: Dynamic) -> Result<Self, Box<EvalAltResult>> {
+                    if self_.is::<$type>() {
+                        Ok(self_.cast::<$type>())
+                    } else if self_.is::<Vec<Dynamic>>() {
+                        sequence_from_array(self_.cast::<Vec<Dynamic>>())
+                    } else {
+                        Err(Box::new(EvalAltResult::ErrorMismatchDataType(
+                            stringify!($type).to_owned(),
+                            self_.type_name().to_owned(),
+                            Position::NONE,
+                        )))
+                    }
+                }
+            }
+
+            impl ToRhaiProxy for $type {
+                fn to_rhai_proxy(self) -> Result<Dynamic, Box<EvalAltResult>> {
+                    Ok(Dynamic::from(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_rhai_sequence_proxy!(Vec2, Vec3, Vec3A, Vec4, Quat);
+impl_rhai_sequence_proxy!(DVec2, DVec3, DVec4, DQuat);
+impl_rhai_sequence_proxy!(IVec2, IVec3, IVec4, UVec2, UVec3, UVec4);
+
+/// Registers `to_array` for each of the math types converted to and from arrays
+macro_rules! register_sequence_fns {
+    ($engine:ident, $($type:ty),*) => {
+        $(
+            $engine.register_fn("to_array", |v: &mut $type| sequence_to_array(v));
+        )*
+    };
+}
+
+/// Registers the functions shared by the math types which convert to and from arrays, i.e. `v.to_array()`
+pub fn register_sequence_types(engine: &mut Engine) {
+    register_sequence_fns!(engine, Vec2, Vec3, Vec3A, Vec4, Quat);
+    register_sequence_fns!(engine, DVec2, DVec3, DVec4, DQuat);
+    register_sequence_fns!(engine, IVec2, IVec3, IVec4, UVec2, UVec3, UVec4);
+}
+
 impl<T: RhaiProxyable + Reflect + FromReflect + Clone + FromRhaiProxy> RhaiProxyable for Option<T> {
     fn ref_to_rhai(self_: crate::ScriptRef) -> Result<Dynamic, Box<EvalAltResult>> {
         self_.get_typed(|s: &Option<T>| match s {
@@ -234,8 +399,18 @@ impl<T: ToRhaiProxy> ToRhaiProxy for Option<T> {
 }
 
 /// Composite trait composing the various traits required for a type `T` to be used as part of a RhaiVec<T>
-pub trait RhaiVecElem: FromReflect + RhaiProxyable + FromRhaiProxy + Clone {}
-impl<T: FromReflect + RhaiProxyable + FromRhaiProxy + Clone> RhaiVecElem for T {}
+pub trait RhaiVecElem: FromReflect + RhaiProxyable + FromRhaiProxy + ToRhaiProxy + Clone {}
+impl<T: FromReflect + RhaiProxyable + FromRhaiProxy + ToRhaiProxy + Clone> RhaiVecElem for T {}
+
+/// Converts an index received from a script into an index into a vector of the given length.
+///
+/// Negative indices and indices past the last element are rejected, apart from the length itself when inserting
+fn vec_index(idx: INT, len: usize, inserting: bool) -> Result<usize, Box<EvalAltResult>> {
+    usize::try_from(idx)
+        .ok()
+        .filter(|idx| *idx < len || (inserting && *idx == len))
+        .ok_or_else(|| Box::new(EvalAltResult::ErrorArrayBounds(len, idx, Position::NONE)))
+}
 
 /// A ScriptVec wrapper which implements a custom iterator ontop of ScriptVec's
 pub struct RhaiVec<T: RhaiVecElem>(pub ScriptVec<T>);
@@ -249,6 +424,10 @@ impl<T: RhaiVecElem> RhaiVec<T> {
     pub fn new_ref(self_: crate::ScriptRef) -> Self {
         Self(ScriptVec::<T>::new_ref(self_))
     }
+
+    pub fn new_owned(vec: Vec<T>, world_ptr: WorldPointer) -> Self {
+        Self(ScriptVec::<T>::new_owned(vec, world_ptr))
+    }
 }
 
 impl<T: RhaiVecElem> std::ops::Deref for RhaiVec<T> {
@@ -275,9 +454,156 @@ impl<T: RhaiVecElem> IntoIterator for RhaiVec<T> {
     }
 }
 
+/// Operations on a vector reference with the element type erased, see [`RhaiDynVec`]
+trait DynVecOps: Send + Sync {
+    fn script_ref(&self) -> &ScriptRef;
+    fn len(&self) -> Result<usize, ReflectionError>;
+    fn push(&mut self, val: Dynamic) -> Result<(), Box<EvalAltResult>>;
+    fn pop(&mut self) -> Result<Dynamic, Box<EvalAltResult>>;
+    fn clear(&mut self) -> Result<(), ReflectionError>;
+    fn insert(&mut self, idx: INT, val: Dynamic) -> Result<(), Box<EvalAltResult>>;
+    fn remove(&mut self, idx: INT) -> Result<Dynamic, Box<EvalAltResult>>;
+    fn get(&self, idx: INT) -> Result<Dynamic, Box<EvalAltResult>>;
+    fn set(&self, idx: INT, val: Dynamic) -> Result<(), Box<EvalAltResult>>;
+    fn boxed_clone(&self) -> Box<dyn DynVecOps>;
+}
+
+impl<T: RhaiVecElem> DynVecOps for ScriptVec<T> {
+    fn script_ref(&self) -> &ScriptRef {
+        &self.ref_
+    }
+
+    fn len(&self) -> Result<usize, ReflectionError> {
+        ScriptVec::len(self)
+    }
+
+    fn push(&mut self, val: Dynamic) -> Result<(), Box<EvalAltResult>> {
+        ScriptVec::push(self, T::from_rhai_proxy(val)?).map_err(Into::into)
+    }
+
+    fn pop(&mut self) -> Result<Dynamic, Box<EvalAltResult>> {
+        ScriptVec::pop(self)?.to_rhai_proxy()
+    }
+
+    fn clear(&mut self) -> Result<(), ReflectionError> {
+        ScriptVec::clear(self)
+    }
+
+    fn insert(&mut self, idx: INT, val: Dynamic) -> Result<(), Box<EvalAltResult>> {
+        let idx = vec_index(idx, ScriptVec::len(self)?, true)?;
+        ScriptVec::insert(self, idx, T::from_rhai_proxy(val)?).map_err(Into::into)
+    }
+
+    fn remove(&mut self, idx: INT) -> Result<Dynamic, Box<EvalAltResult>> {
+        let idx = vec_index(idx, ScriptVec::len(self)?, false)?;
+        ScriptVec::remove(self, idx)?.to_rhai_proxy()
+    }
+
+    fn get(&self, idx: INT) -> Result<Dynamic, Box<EvalAltResult>> {
+        let idx = vec_index(idx, ScriptVec::len(self)?, false)?;
+        self.index(idx).to_dynamic()
+    }
+
+    fn set(&self, idx: INT, val: Dynamic) -> Result<(), Box<EvalAltResult>> {
+        let idx = vec_index(idx, ScriptVec::len(self)?, false)?;
+        self.index(idx).apply_rhai(val)
+    }
+
+    fn boxed_clone(&self) -> Box<dyn DynVecOps> {
+        Box::new(self.clone())
+    }
+}
+
+/// A reference to a `Vec<T>` of any registered element type.
+///
+/// `Vec<T>` values are handed to scripts as this type, which only needs to be registered with the engine once
+/// (the bevy API provider does so), unlike [`RhaiVec`] which requires [`RegisterVecType::register_vec_functions`] for every `T`.
+pub struct RhaiDynVec(Box<dyn DynVecOps>);
+
+impl RhaiDynVec {
+    pub fn new_ref<T: RhaiVecElem>(ref_: ScriptRef) -> Self {
+        Self(Box::new(ScriptVec::<T>::new_ref(ref_)))
+    }
+}
+
+impl Clone for RhaiDynVec {
+    fn clone(&self) -> Self {
+        Self(self.0.boxed_clone())
+    }
+}
+
+impl std::fmt::Debug for RhaiDynVec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("RhaiDynVec")
+            .field(self.0.script_ref())
+            .finish()
+    }
+}
+
+impl IntoIterator for RhaiDynVec {
+    type Item = Result<Dynamic, Box<EvalAltResult>>;
+
+    type IntoIter = RhaiDynVecIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        RhaiDynVecIter { vec: self, idx: 0 }
+    }
+}
+
+/// Iterates over the elements of a [`RhaiDynVec`], looking each one up as it is reached
+pub struct RhaiDynVecIter {
+    vec: RhaiDynVec,
+    idx: usize,
+}
+
+impl Iterator for RhaiDynVecIter {
+    type Item = Result<Dynamic, Box<EvalAltResult>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.vec.0.len() {
+            Ok(len) if self.idx < len => {
+                self.idx += 1;
+                Some(self.vec.0.get((self.idx - 1) as INT))
+            }
+            Ok(_) => None,
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl CustomType for RhaiDynVec {
+    fn build(mut builder: bevy_mod_scripting_rhai::rhai::TypeBuilder<Self>) {
+        builder
+            .with_name("Vec")
+            .with_result_fn("is_empty", |vec: &mut RhaiDynVec| {
+                vec.0.len().map(|len| len == 0).map_err(Into::into)
+            })
+            .with_result_fn("len", |vec: &mut RhaiDynVec| {
+                vec.0.len().map(|v| v as INT).map_err(Into::into)
+            })
+            .with_result_fn("push", |vec: &mut RhaiDynVec, val: Dynamic| vec.0.push(val))
+            .with_result_fn("pop", |vec: &mut RhaiDynVec| vec.0.pop())
+            .with_result_fn("clear", |vec: &mut RhaiDynVec| {
+                vec.0.clear().map_err(Into::into)
+            })
+            .with_result_fn("insert", |vec: &mut RhaiDynVec, idx: INT, val: Dynamic| {
+                vec.0.insert(idx, val)
+            })
+            .with_result_fn("remove", |vec: &mut RhaiDynVec, idx: INT| vec.0.remove(idx))
+            .with_result_fn("index$get$", |vec: &mut RhaiDynVec, idx: INT| {
+                vec.0.get(idx)
+            })
+            .with_result_fn(
+                "index$set$",
+                |vec: &mut RhaiDynVec, idx: INT, value: Dynamic| vec.0.set(idx, value),
+            );
+    }
+}
+
 impl<T: RhaiVecElem> RhaiProxyable for Vec<T> {
     fn ref_to_rhai(self_: crate::ScriptRef) -> Result<Dynamic, Box<EvalAltResult>> {
-        Ok(Dynamic::from(RhaiVec::<T>::new_ref(self_)))
+        Ok(Dynamic::from(RhaiDynVec::new_ref::<T>(self_)))
     }
 
     fn apply_rhai(
@@ -306,6 +632,10 @@ impl<T: RhaiVecElem> RhaiProxyable for Vec<T> {
             let vec = new_val.cast::<RhaiVec<T>>();
             self_.apply(&vec.ref_)?;
             Ok(())
+        } else if new_val.is::<RhaiDynVec>() {
+            let vec = new_val.cast::<RhaiDynVec>();
+            self_.apply(vec.0.script_ref())?;
+            Ok(())
         } else {
             Err(Box::new(EvalAltResult::ErrorMismatchDataType(
                 "Array or Vec".to_owned(),
@@ -321,6 +651,9 @@ impl<T: RhaiVecElem> FromRhaiProxy for Vec<T> {
         if self_.is::<RhaiVec<T>>() {
             let vec = self_.cast::<RhaiVec<T>>();
             vec.ref_.get_typed(|s: &Vec<T>| Ok(s.clone()))?
+        } else if self_.is::<RhaiDynVec>() {
+            let vec = self_.cast::<RhaiDynVec>();
+            vec.0.script_ref().get_typed(|s: &Vec<T>| Ok(s.clone()))?
         } else if self_.is::<Vec<Dynamic>>() {
             self_
                 .cast::<Vec<Dynamic>>()
@@ -360,24 +693,28 @@ impl<T: RhaiVecElem> CustomType for RhaiVec<T> {
             .with_result_fn("push", |vec: &mut RhaiVec<T>, val: Dynamic| {
                 vec.push(T::from_rhai_proxy(val)?).map_err(Into::into)
             })
-            .with_result_fn("pop", |vec: &mut RhaiVec<T>| vec.pop().map_err(Into::into))
+            .with_result_fn("pop", |vec: &mut RhaiVec<T>| vec.pop()?.to_rhai_proxy())
             .with_result_fn("clear", |vec: &mut RhaiVec<T>| {
                 vec.clear().map_err(Into::into)
             })
             .with_result_fn("insert", |vec: &mut RhaiVec<T>, idx: INT, val: Dynamic| {
-                vec.insert(idx as usize, T::from_rhai_proxy(val)?)
+                let idx = vec_index(idx, vec.len()?, true)?;
+                vec.insert(idx, T::from_rhai_proxy(val)?)
                     .map_err(Into::into)
             })
             .with_result_fn("remove", |vec: &mut RhaiVec<T>, idx: INT| {
-                vec.remove(idx as usize).map_err(Into::into)
+                let idx = vec_index(idx, vec.len()?, false)?;
+                vec.remove(idx)?.to_rhai_proxy()
             })
             .with_result_fn("index$get$", |vec: &mut RhaiVec<T>, idx: INT| {
-                vec.index(idx as usize).to_dynamic()
+                let idx = vec_index(idx, vec.len()?, false)?;
+                vec.index(idx).to_dynamic()
             })
             .with_result_fn(
                 "index$set$",
                 |vec: &mut RhaiVec<T>, idx: INT, value: Dynamic| {
-                    vec.index(idx as usize).apply_rhai(value)
+                    let idx = vec_index(idx, vec.len()?, false)?;
+                    vec.index(idx).apply_rhai(value)
                 },
             );
     }
@@ -387,6 +724,8 @@ impl<T: RhaiVecElem> CustomType for RhaiVec<T> {
 ///
 /// Rhai does not support the idea of generic types, instead every function is a standalone thing, and hence
 /// generics must be monomorphized manually (registered for every type you want to use them with).
+///
+/// This is only needed for [`RhaiVec`]s created on the rust side, `Vec<T>` values obtained through reflection are [`RhaiDynVec`]s.
 pub trait RegisterVecType {
     fn register_vec_functions<T: RhaiVecElem>(&mut self) -> &mut Self;
 }
@@ -398,3 +737,89 @@ impl RegisterVecType for Engine {
         self
     }
 }
+
+#[cfg(test)]
+mod test {
+    use bevy::prelude::World;
+
+    use super::*;
+    use crate::ReflectedValue;
+
+    #[test]
+    fn math_types_are_assigned_from_arrays() {
+        let mut world = World::new();
+        let value = ReflectedValue::new_owned(Box::new(IVec3::ZERO), unsafe {
+            WorldPointer::new(&mut world)
+        });
+        let mut ref_ = value.ref_.clone();
+
+        let array: Vec<Dynamic> = vec![1.into(), 2.into(), 3.into()];
+        IVec3::apply_rhai(&mut ref_, Dynamic::from(array)).unwrap();
+        assert_eq!(ref_.get_typed(|v: &IVec3| *v).unwrap(), IVec3::new(1, 2, 3));
+
+        let copy = IVec3::ref_to_rhai(ref_.clone()).unwrap();
+        assert_eq!(copy.cast::<IVec3>(), IVec3::new(1, 2, 3));
+
+        assert!(IVec3::apply_rhai(&mut ref_, Dynamic::from(1.0 as FLOAT)).is_err());
+    }
+
+    #[test]
+    fn all_math_types_convert_to_arrays() {
+        let mut engine = Engine::new();
+        register_sequence_types(&mut engine);
+        let mut scope = bevy_mod_scripting_rhai::rhai::Scope::new();
+        scope.push("v", UVec2::new(1, 2));
+        scope.push("q", DQuat::IDENTITY);
+
+        let v = engine
+            .eval_with_scope::<Vec<Dynamic>>(&mut scope, "v.to_array()")
+            .unwrap();
+        assert_eq!(
+            v.into_iter()
+                .map(|e| e.as_int().unwrap())
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        let q = engine
+            .eval_with_scope::<Vec<Dynamic>>(&mut scope, "q.to_array()")
+            .unwrap();
+        assert_eq!(q.len(), 4);
+    }
+
+    #[test]
+    fn vectors_return_proxies_and_reject_invalid_indices() {
+        let mut world = World::new();
+        let value = ReflectedValue::new_owned(Box::new(vec![1u8, 2, 3]), unsafe {
+            WorldPointer::new(&mut world)
+        });
+        let mut engine = Engine::new();
+        engine.build_type::<RhaiDynVec>();
+        let mut scope = bevy_mod_scripting_rhai::rhai::Scope::new();
+        scope.push_dynamic("v", Vec::<u8>::ref_to_rhai(value.ref_.clone()).unwrap());
+
+        // removed elements are converted like any other `u8`
+        let popped = engine.eval_with_scope::<INT>(&mut scope, "v.pop() + 1");
+        assert_eq!(popped.unwrap(), 4);
+        let removed = engine.eval_with_scope::<INT>(&mut scope, "v.remove(0) * 10");
+        assert_eq!(removed.unwrap(), 10);
+
+        for code in [
+            "v.remove(-1)",
+            "v.remove(1)",
+            "v.insert(-1, 5)",
+            "v[-1]",
+            "v[1] = 5",
+        ] {
+            let error = engine.run_with_scope(&mut scope, code).unwrap_err();
+            assert!(
+                matches!(*error, EvalAltResult::ErrorArrayBounds(1, _, _)),
+                "{code}: {error}"
+            );
+        }
+        engine.run_with_scope(&mut scope, "v.insert(1, 7)").unwrap();
+        assert_eq!(
+            value.ref_.get_typed(|v: &Vec<u8>| v.clone()).unwrap(),
+            vec![2, 7]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use bevy::prelude::{App, CoreSet, IntoSystemConfig};
+use bevy_mod_scripting_core::prelude::*;
+
+use bevy_mod_scripting_rhai::{
+    calling_script,
+    prelude::*,
+    rhai::{EvalAltResult, FnPtr, ImmutableString, NativeCallContext},
+};
+
+use crate::common::systems::{script_system_system, ScriptSystemHandle, ScriptSystems};
+
+/// Runs the given function on the systems of the script which called the native function the given context belongs to
+fn with_script_systems<E, T>(
+    systems: &ScriptSystems<E>,
+    context: &NativeCallContext,
+    f: impl FnOnce(ScriptSystemHandle) -> T,
+) -> Result<T, Box<EvalAltResult>> {
+    calling_script(context)
+        .map(|sid| f(systems.for_script(sid)))
+        .ok_or_else(|| "Systems can only be registered by scripts attached to entities".into())
+}
+
+/// Provides scripts with the `register_system`, `unregister_system` and `is_system_registered` functions,
+/// used to have the script's functions called every frame.
+/// Hooks are given either by name or as function pointers, i.e. `register_system("on_tick")` or `register_system(Fn("on_tick"))`.
+///
+/// Registered hooks are sent as [`RhaiEvent`]s with default arguments and the priority given in [`ScriptSystems::new`]
+/// every frame in `CoreSet::PreUpdate`. A script's systems are cleared when it is reloaded.
+pub struct RhaiSystemAPIProvider<A: FuncArgs + Clone + Send + Sync + 'static> {
+    pub systems: ScriptSystems<RhaiEvent<A>>,
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + 'static> Default for RhaiSystemAPIProvider<A> {
+    fn default() -> Self {
+        Self {
+            systems: Default::default(),
+        }
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + 'static> RhaiSystemAPIProvider<A> {
+    pub fn new(systems: ScriptSystems<RhaiEvent<A>>) -> Self {
+        Self { systems }
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + Default + 'static> APIProvider
+    for RhaiSystemAPIProvider<A>
+{
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        let systems = self.systems.clone();
+        engine.register_fn(
+            "register_system",
+            move |ctx: NativeCallContext, hook: ImmutableString| {
+                with_script_systems(&systems, &ctx, |s| s.register(hook.to_string()))
+            },
+        );
+        let systems = self.systems.clone();
+        engine.register_fn(
+            "register_system",
+            move |ctx: NativeCallContext, hook: FnPtr| {
+                with_script_systems(&systems, &ctx, |s| s.register(hook.fn_name().to_owned()))
+            },
+        );
+        let systems = self.systems.clone();
+        engine.register_fn(
+            "unregister_system",
+            move |ctx: NativeCallContext, hook: ImmutableString| {
+                with_script_systems(&systems, &ctx, |s| s.unregister(&hook))
+            },
+        );
+        let systems = self.systems.clone();
+        engine.register_fn(
+            "unregister_system",
+            move |ctx: NativeCallContext, hook: FnPtr| {
+                with_script_systems(&systems, &ctx, |s| s.unregister(hook.fn_name()))
+            },
+        );
+        let systems = self.systems.clone();
+        engine.register_fn(
+            "is_system_registered",
+            move |ctx: NativeCallContext, hook: ImmutableString| {
+                with_script_systems(&systems, &ctx, |s| s.is_registered(&hook))
+            },
+        );
+        Ok(())
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        _: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        self.systems.clear_script(script_data.sid);
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        app.insert_resource(self.systems.clone())
+            .add_system(script_system_system::<RhaiScriptHost<A>>.in_base_set(CoreSet::PreUpdate));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use bevy::{ecs::system::SystemState, prelude::*};
+    use bevy_mod_scripting_core::prelude::*;
+
+    use super::*;
+
+    type Host = RhaiScriptHost<()>;
+
+    #[test]
+    fn registered_hooks_are_called_every_frame() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin::default())
+            .add_plugin(ScriptingPlugin)
+            .add_script_host_to_base_set::<Host, _>(CoreSet::PostUpdate)
+            .add_api_provider::<Host>(Box::<RhaiSystemAPIProvider<()>>::default())
+            .add_script_handler_to_base_set::<Host, _, 0, 0>(CoreSet::PostUpdate);
+
+        let ticks = Arc::<AtomicUsize>::default();
+        let counter = ticks.clone();
+        app.world
+            .resource_mut::<Host>()
+            .engine
+            .register_fn("tick", move || {
+                counter.fetch_add(1, Ordering::Relaxed);
+            });
+
+        let handle = app.world.resource_mut::<Assets<RhaiFile>>().add(RhaiFile {
+            bytes: br#"
+                fn on_init() { register_system("on_tick"); }
+                fn on_tick() { tick(); }
+            "#
+            .as_slice()
+            .into(),
+            ast: None,
+        });
+        app.world.spawn(ScriptCollection::<RhaiFile> {
+            scripts: vec![Script::new("systems.rhai".to_owned(), handle)],
+        });
+        app.update();
+        let mut events = SystemState::<PriorityEventWriter<RhaiEvent<()>>>::new(&mut app.world);
+        events.get_mut(&mut app.world).send(
+            RhaiEvent {
+                hook_name: "on_init".to_owned(),
+                args: (),
+                recipients: Recipients::All,
+            },
+            0,
+        );
+        app.update();
+        assert_eq!(ticks.load(Ordering::Relaxed), 0);
+
+        for frame in 1..=3 {
+            app.update();
+            assert_eq!(ticks.load(Ordering::Relaxed), frame);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use bevy_mod_scripting_core::{prelude::*, world::WorldPointer};
+
+#[allow(deprecated)]
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{self, CustomType, EvalAltResult, Position, FLOAT, INT},
+};
+
+use crate::common::time::ScriptTime;
+
+fn to_rhai_error(e: ScriptError) -> Box<EvalAltResult> {
+    Box::new(EvalAltResult::ErrorRuntime(
+        e.to_string().into(),
+        Position::NONE,
+    ))
+}
+
+#[allow(deprecated)]
+impl CustomType for ScriptTime {
+    fn build(mut builder: rhai::TypeBuilder<Self>) {
+        builder
+            .with_name("Time")
+            .with_result_fn("delta_seconds", |self_: &mut Self| {
+                self_
+                    .delta_seconds()
+                    .map(|v| v as FLOAT)
+                    .map_err(to_rhai_error)
+            })
+            .with_result_fn("elapsed_seconds", |self_: &mut Self| {
+                self_
+                    .elapsed_seconds()
+                    .map(|v| v as FLOAT)
+                    .map_err(to_rhai_error)
+            })
+            .with_result_fn("frame_count", |self_: &mut Self| {
+                self_.frame_count().map(|v| v as INT).map_err(to_rhai_error)
+            })
+            .with_fn("to_debug", |self_: &mut Self| format!("{:?}", self_));
+    }
+}
+
+/// Provides scripts with the `time` variable, which reads the frame timing,
+/// i.e. `time.delta_seconds()`, `time.elapsed_seconds()` or `time.frame_count()`.
+#[derive(Default)]
+pub struct RhaiTimeAPIProvider;
+
+impl APIProvider for RhaiTimeAPIProvider {
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine.build_type::<ScriptTime>();
+        Ok(())
+    }
+
+    fn setup_script_runtime(
+        &mut self,
+        world_ptr: WorldPointer,
+        _script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        ctx.scope.set_value("time", ScriptTime::new(world_ptr));
+        Ok(())
+    }
+}
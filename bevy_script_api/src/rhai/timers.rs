@@ -0,0 +1,148 @@
+use bevy::prelude::{App, CoreSet, IntoSystemConfig};
+use bevy_mod_scripting_core::prelude::*;
+
+#[allow(deprecated)]
+use bevy_mod_scripting_rhai::{
+    prelude::*,
+    rhai::{
+        self, CustomType, Dynamic, EvalAltResult, FnPtr, ImmutableString, Position, FLOAT, INT,
+    },
+};
+
+use crate::common::timers::{script_timer_system, ScriptTimerHandle, ScriptTimers};
+
+fn every(
+    timers: &mut ScriptTimerHandle,
+    interval: FLOAT,
+    hook: String,
+) -> Result<INT, Box<EvalAltResult>> {
+    timers
+        .every(interval as f64, hook)
+        .map(|id| id as INT)
+        .ok_or_else(|| {
+            Box::new(EvalAltResult::ErrorRuntime(
+                Dynamic::from(format!("Timer interval must be positive, got `{interval}`")),
+                Position::NONE,
+            ))
+        })
+}
+
+/// The name of the hook the given function pointer refers to.
+///
+/// Timers call hooks by name, so function pointers with curried arguments, such as closures capturing variables, are rejected
+fn fn_ptr_hook(hook: FnPtr) -> Result<String, Box<EvalAltResult>> {
+    if hook.is_curried() {
+        return Err(Box::new(EvalAltResult::ErrorRuntime(
+            Dynamic::from(format!(
+                "Timers cannot call `{}` with curried arguments, schedule a hook by name instead",
+                hook.fn_name()
+            )),
+            Position::NONE,
+        )));
+    }
+    Ok(hook.fn_name().to_owned())
+}
+
+#[allow(deprecated)]
+impl CustomType for ScriptTimerHandle {
+    fn build(mut builder: rhai::TypeBuilder<Self>) {
+        builder
+            .with_name("Timers")
+            .with_fn(
+                "after",
+                |self_: &mut Self, delay: FLOAT, hook: ImmutableString| {
+                    self_.after(delay as f64, hook.to_string()) as INT
+                },
+            )
+            .with_result_fn("after", |self_: &mut Self, delay: FLOAT, hook: FnPtr| {
+                Ok(self_.after(delay as f64, fn_ptr_hook(hook)?) as INT)
+            })
+            .with_result_fn(
+                "every",
+                |self_: &mut Self, interval: FLOAT, hook: ImmutableString| {
+                    every(self_, interval, hook.to_string())
+                },
+            )
+            .with_result_fn("every", |self_: &mut Self, interval: FLOAT, hook: FnPtr| {
+                every(self_, interval, fn_ptr_hook(hook)?)
+            })
+            .with_fn("cancel", |self_: &mut Self, id: INT| {
+                self_.cancel(id as u64)
+            })
+            .with_fn("to_debug", |self_: &mut Self| format!("{:?}", self_));
+    }
+}
+
+/// Provides scripts with the `timers` variable, used to call the script's functions after a delay or repeatedly.
+/// Hooks are given either by name or as function pointers without curried arguments, i.e. `timers.after(1.0, "on_timer")` or `timers.after(1.0, Fn("on_timer"))`.
+///
+/// Timers are ticked against `Time` in `CoreSet::PreUpdate` and due hooks are sent as [`RhaiEvent`]s
+/// with default arguments and the priority given in [`ScriptTimers::new`].
+pub struct RhaiTimerAPIProvider<A: FuncArgs + Clone + Send + Sync + 'static> {
+    pub timers: ScriptTimers<RhaiEvent<A>>,
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + 'static> Default for RhaiTimerAPIProvider<A> {
+    fn default() -> Self {
+        Self {
+            timers: Default::default(),
+        }
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + 'static> RhaiTimerAPIProvider<A> {
+    pub fn new(timers: ScriptTimers<RhaiEvent<A>>) -> Self {
+        Self { timers }
+    }
+}
+
+impl<A: FuncArgs + Clone + Send + Sync + Default + 'static> APIProvider
+    for RhaiTimerAPIProvider<A>
+{
+    type APITarget = Engine;
+    type ScriptContext = RhaiContext;
+    type DocTarget = RhaiDocFragment;
+
+    fn attach_api(&mut self, engine: &mut Self::APITarget) -> Result<(), ScriptError> {
+        engine.build_type::<ScriptTimerHandle>();
+        Ok(())
+    }
+
+    fn setup_script(
+        &mut self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<(), ScriptError> {
+        ctx.scope
+            .set_value("timers", self.timers.for_script(script_data.sid));
+        Ok(())
+    }
+
+    fn register_with_app(&self, app: &mut App) {
+        app.insert_resource(self.timers.clone())
+            .add_system(script_timer_system::<RhaiScriptHost<A>>.in_base_set(CoreSet::PreUpdate));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy_mod_scripting_rhai::rhai::Scope;
+
+    #[test]
+    fn function_pointers_with_curried_arguments_are_rejected() {
+        let mut engine = Engine::new();
+        engine.build_type::<ScriptTimerHandle>();
+        let timers = ScriptTimers::<()>::default();
+        let mut scope = Scope::new();
+        scope.push("timers", timers.for_script(0));
+
+        engine
+            .run_with_scope(&mut scope, r#"timers.after(1.0, Fn("on_timer"))"#)
+            .unwrap();
+        assert!(engine
+            .run_with_scope(&mut scope, r#"timers.every(1.0, Fn("on_tick").curry(1))"#)
+            .is_err());
+        assert_eq!(timers.advance(1.0), vec![(0, "on_timer".to_owned())]);
+    }
+}
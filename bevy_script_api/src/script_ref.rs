@@ -1,7 +1,11 @@
 use bevy::prelude::*;
 use parking_lot::RwLock;
+use std::cell::UnsafeCell;
 use std::fmt::Debug;
-use std::{borrow::Cow, sync::Weak};
+use std::{
+    borrow::Cow,
+    sync::{Arc, Weak},
+};
 
 use bevy_mod_scripting_core::world::WorldPointer;
 
@@ -73,6 +77,17 @@ impl ScriptRef {
         }
     }
 
+    /// Creates a script reference to the value at the given reflect path relative to this one, see [`ReflectPathElem::parse_path`].
+    /// Fails if the path is malformed or does not lead to a value.
+    pub fn sub_ref_path(&self, path: &str) -> Result<ScriptRef, ReflectionError> {
+        let mut ref_ = self.clone();
+        for elem in ReflectPathElem::parse_path(path)? {
+            ref_.path.push(elem);
+        }
+        ref_.get(|_| ())?;
+        Ok(ref_)
+    }
+
     /// Retrieves the underlying `dyn Reflect` reference and applies function which can retrieve a value.
     /// Panics if the reference is invalid or world is already borrowed mutably.
     #[inline(always)]
@@ -241,11 +256,90 @@ unsafe impl Send for ReflectPtr {}
 /// safe since Reflect values have to be Sync
 unsafe impl Sync for ReflectPtr {}
 
+/// A type erased reflect value created and owned by a script.
+///
+/// References to the value are handed out as [`ScriptRef`]s with a script owned base,
+/// those become invalid as soon as the owner is dropped.
+pub struct ScriptOwnedValue {
+    value: UnsafeCell<Box<dyn Reflect>>,
+    valid: Arc<RwLock<()>>,
+}
+
+/// Safety: we make this sync via RwLock<()> assuming invariants are upheld
+unsafe impl Sync for ScriptOwnedValue {}
+
+impl ScriptOwnedValue {
+    pub fn new(value: Box<dyn Reflect>) -> Self {
+        Self {
+            value: UnsafeCell::new(value),
+            valid: Arc::new(RwLock::new(())),
+        }
+    }
+
+    /// Creates a script reference pointing to the owned value
+    pub fn script_ref(&self, world_ptr: WorldPointer) -> ScriptRef {
+        unsafe {
+            // safety:
+            // - valid is dropped when the value goes out of scope, so won't be dangling
+            // - using the valid lock means no incorrect aliasing may occur
+            // - the pointer points to base of the reference
+            ScriptRef::new_script_ref(
+                (&mut **self.value.get() as *mut dyn Reflect).into(),
+                Arc::downgrade(&self.valid),
+                world_ptr,
+            )
+        }
+    }
+}
+
+impl Debug for ScriptOwnedValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptOwnedValue").finish_non_exhaustive()
+    }
+}
+
+impl Drop for ScriptOwnedValue {
+    fn drop(&mut self) {
+        if self.valid.is_locked() {
+            panic!(
+                "Something is referencing a script owned value and it's about to go out of scope!"
+            );
+        }
+    }
+}
+
 /// A value representing a type which has no special UserData implementation,
 /// It exposes the much less convenient reflect interface of the underlying type.
 #[derive(Clone, Debug)]
 pub struct ReflectedValue {
     pub(crate) ref_: ScriptRef,
+    /// Keeps the pointed to value alive if it's owned by the script
+    pub(crate) owner: Option<Arc<ScriptOwnedValue>>,
+}
+
+impl ReflectedValue {
+    pub fn new(ref_: ScriptRef) -> Self {
+        Self { ref_, owner: None }
+    }
+
+    /// Creates a reflected value which owns the given value
+    pub fn new_owned(value: Box<dyn Reflect>, world_ptr: WorldPointer) -> Self {
+        let owner = Arc::new(ScriptOwnedValue::new(value));
+        Self {
+            ref_: owner.script_ref(world_ptr),
+            owner: Some(owner),
+        }
+    }
+
+    /// Copies the underlying value out via [`FromReflect`], failing if it is not of type `T`
+    pub fn to_value<T: FromReflect>(&self) -> Result<T, ReflectionError> {
+        self.ref_.get(|r| {
+            T::from_reflect(r).ok_or_else(|| ReflectionError::CannotDowncast {
+                from: r.type_name().to_owned().into(),
+                to: std::any::type_name::<T>().into(),
+            })
+        })?
+    }
 }
 
 impl From<ReflectedValue> for ScriptRef {
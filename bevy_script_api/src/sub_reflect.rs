@@ -141,6 +141,57 @@ impl Display for ReflectPathElem {
 }
 
 impl ReflectPathElem {
+    /// Parses a reflect path in bevy's syntax into its elements, for example `"translation.x"` or `"items[0].name"`.
+    ///
+    /// Numeric field names such as `.0` access tuple fields, the leading dot of the first field is optional.
+    pub fn parse_path(path: &str) -> Result<Vec<Self>, ReflectionError> {
+        let error = |msg: &str| ReflectionError::InvalidReflectionPath {
+            path: path.to_owned(),
+            msg: msg.to_owned(),
+        };
+
+        let mut elems = Vec::default();
+        let mut field = !path.starts_with('[');
+        let mut rest = if field {
+            path.strip_prefix('.').unwrap_or(path)
+        } else {
+            path
+        };
+
+        loop {
+            if field {
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                let name = &rest[..end];
+                if name.is_empty() {
+                    return Err(error("Expected a field name"));
+                }
+                elems.push(match name.parse::<usize>() {
+                    Ok(index) => Self::IndexAccess(index),
+                    Err(_) => Self::FieldAccess(Cow::Owned(name.to_owned())),
+                });
+                rest = &rest[end..];
+            } else {
+                let end = rest.find(']').ok_or_else(|| error("Unclosed `[`"))?;
+                let index = rest[1..end]
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| error("Expected an index between `[` and `]`"))?;
+                elems.push(Self::IndexAccess(index));
+                rest = &rest[end + 1..];
+            }
+
+            match rest.chars().next() {
+                None => return Ok(elems),
+                Some('.') => {
+                    field = true;
+                    rest = &rest[1..];
+                }
+                Some('[') => field = false,
+                Some(_) => return Err(error("Expected `.` or `[` after `]`")),
+            }
+        }
+    }
+
     pub fn sub_ref<'a>(&self, base: &'a dyn Reflect) -> Result<&'a dyn Reflect, ReflectionError> {
         match self {
             ReflectPathElem::SubReflection { get, .. } => get(base),
@@ -445,3 +496,34 @@ impl ReflectPath {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(path: &str) -> Vec<String> {
+        ReflectPathElem::parse_path(path)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn parses_fields_and_indices() {
+        assert_eq!(parse("translation.x"), vec![".translation", ".x"]);
+        assert_eq!(parse(".items[2].name"), vec![".items", "[2]", ".name"]);
+        assert_eq!(parse("[0][1]"), vec!["[0]", "[1]"]);
+        assert_eq!(parse("pair.1"), vec![".pair", "[1]"]);
+    }
+
+    #[test]
+    fn rejects_malformed_paths() {
+        for path in ["", "a..b", "a.", "a[", "a[x]", "a[0]b"] {
+            assert!(
+                ReflectPathElem::parse_path(path).is_err(),
+                "`{path}` should not parse"
+            );
+        }
+    }
+}
@@ -0,0 +1,101 @@
+// The native lua wrapper `bevy_api_gen --language lua` writes for the `Point` fixture of its tests with `native = true`,
+// which check that they still match (ignoring whitespace), see `tests/lua_native.rs`
+#[cfg(feature="lua")]
+bevy_script_api::make_script_wrapper!(Point as LuaPoint with Clone);
+#[cfg(feature="lua")]
+bevy_script_api::impl_tealr_type!(LuaPoint);
+#[cfg(feature="lua")]
+impl std::fmt::Debug for LuaPoint {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		self.val(|s| std::fmt::Debug::fmt(s, f)).unwrap_or_else(|_| f.write_str("Error while retrieving reference in `std::fmt::Debug`."))
+	}
+}
+#[cfg(feature="lua")]
+#[allow(unused_parens, unused_variables, clippy::all)]
+impl bevy_mod_scripting_lua::tealr::mlu::TealData for LuaPoint {
+	fn add_methods<'lua, T: bevy_mod_scripting_lua::tealr::mlu::TealDataMethods<'lua, Self>>(methods: &mut T) {
+		methods.document_type("A point in the plane.");
+		methods.add_meta_method(bevy_mod_scripting_lua::tealr::mlu::mlua::MetaMethod::ToString, |_, s, ()| Ok(format!("{:?}", s)));
+		methods.add_meta_method(bevy_mod_scripting_lua::tealr::mlu::mlua::MetaMethod::Eq, |_, s, o: LuaPoint| Ok(s.val(|s| o.val(|o| s == o))??));
+		{
+			methods.add_function("new", |_, (a_0,a_1,): (f32,f32,)| {Ok(LuaPoint::new(Point::new(a_0, a_1)))});
+		}
+		{
+			methods.document("The distance from the origin.");
+			methods.add_method("length", |_, s, (): ()| {s.val(|s| Ok(s.length()))?});
+		}
+		{
+			methods.add_method_mut("scale", |_, s, (a_0,): (f32,)| {s.val_mut(|s| Ok(s.scale(a_0)))?});
+		}
+		methods.add_meta_function(bevy_mod_scripting_lua::tealr::mlu::mlua::MetaMethod::Add, |lua, (lhs, rhs): (bevy_mod_scripting_lua::tealr::mlu::mlua::Value, bevy_mod_scripting_lua::tealr::mlu::mlua::Value)| {
+			if let (Ok(l), Ok(r)) = (<LuaPoint as bevy_mod_scripting_lua::tealr::mlu::mlua::FromLua>::from_lua(lhs.clone(), lua), <LuaPoint as bevy_mod_scripting_lua::tealr::mlu::mlua::FromLua>::from_lua(rhs.clone(), lua)) {
+				return bevy_mod_scripting_lua::tealr::mlu::mlua::ToLua::to_lua(LuaPoint::new(l.inner()? + r.inner()?), lua);
+			}
+			Err(bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(format!("unsupported operands for `Add` on `Point`: {} and {}", lhs.type_name(), rhs.type_name())))
+		});
+		methods.add_meta_function(bevy_mod_scripting_lua::tealr::mlu::mlua::MetaMethod::Mul, |lua, (lhs, rhs): (bevy_mod_scripting_lua::tealr::mlu::mlua::Value, bevy_mod_scripting_lua::tealr::mlu::mlua::Value)| {
+			if let (Ok(l), Ok(r)) = (<LuaPoint as bevy_mod_scripting_lua::tealr::mlu::mlua::FromLua>::from_lua(lhs.clone(), lua), <f32 as bevy_mod_scripting_lua::tealr::mlu::mlua::FromLua>::from_lua(rhs.clone(), lua)) {
+				return bevy_mod_scripting_lua::tealr::mlu::mlua::ToLua::to_lua(LuaPoint::new(l.inner()? * r), lua);
+			}
+			Err(bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(format!("unsupported operands for `Mul` on `Point`: {} and {}", lhs.type_name(), rhs.type_name())))
+		});
+		methods.add_meta_method(bevy_mod_scripting_lua::tealr::mlu::mlua::MetaMethod::Unm, |_, s, ()| Ok(LuaPoint::new(-s.inner()?)));
+		{
+			methods.document(" Converts this value into a sequence of its elements, i.e. `{x, y, z}`");
+			methods.add_method("to_array", |lua, s, ()| bevy_script_api::lua::table::sequence_to_table(lua, &s.inner()?));
+		}
+		{
+			methods.document(" Returns the fully qualified name of this type, as given by `Reflect::type_name`");
+			methods.add_method("type_name", |_,_s,()| {Ok("Point")});
+		}
+	}
+	fn add_fields<'lua, T: bevy_mod_scripting_lua::tealr::mlu::TealDataFields<'lua, Self>>(fields: &mut T) {
+		{
+			fields.document(" The horizontal coordinate.");
+			fields.add_field_method_get("x", |_, s| Ok(s.val(|s| s.x.clone())?));
+			fields.add_field_method_set("x", |_, s, o: f32| Ok(s.val_mut(|s| s.x = o)?));
+		}
+		{
+			fields.add_field_method_get("y", |_, s| Ok(s.val(|s| s.y.clone())?));
+			fields.add_field_method_set("y", |_, s, o: f32| Ok(s.val_mut(|s| s.y = o)?));
+		}
+		{
+			fields.document(" The origin.");
+			fields.add_field_function_get("ZERO", |_,_| {Ok(LuaPoint::new(Point::ZERO))});
+		}
+	}
+}
+#[cfg(feature="lua")]
+impl bevy_script_api::lua::LuaProxyable for Point {
+	fn ref_to_lua<'lua>(self_: bevy_script_api::script_ref::ScriptRef, lua: &'lua bevy_mod_scripting_lua::tealr::mlu::mlua::Lua) -> bevy_mod_scripting_lua::tealr::mlu::mlua::Result<bevy_mod_scripting_lua::tealr::mlu::mlua::Value<'lua>> {
+		<LuaPoint as bevy_mod_scripting_lua::tealr::mlu::mlua::ToLua>::to_lua(LuaPoint::new_ref(self_), lua)
+	}
+	fn apply_lua<'lua>(self_: &mut bevy_script_api::script_ref::ScriptRef, _: &'lua bevy_mod_scripting_lua::tealr::mlu::mlua::Lua, new_val: bevy_mod_scripting_lua::tealr::mlu::mlua::Value<'lua>) -> bevy_mod_scripting_lua::tealr::mlu::mlua::Result<()> {
+		if let bevy_mod_scripting_lua::tealr::mlu::mlua::Value::UserData(v) = new_val {
+			Ok(v.borrow::<LuaPoint>()?.apply_self_to_base(self_)?)
+		} else if let bevy_mod_scripting_lua::tealr::mlu::mlua::Value::Table(table) = new_val {
+			self_.set_val(bevy_script_api::lua::table::sequence_from_table::<Point>(table)?)?;
+			Ok(())
+		} else {
+			Err(bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError("Error in assigning to custom user data".to_owned()))
+		}
+	}
+}
+#[cfg(feature="lua")]
+impl bevy_script_api::lua::ToLuaProxy<'_> for Point {
+	fn to_lua_proxy<'lua>(self, lua: &'lua bevy_mod_scripting_lua::tealr::mlu::mlua::Lua) -> bevy_mod_scripting_lua::tealr::mlu::mlua::Result<bevy_mod_scripting_lua::tealr::mlu::mlua::Value<'lua>> {
+		<LuaPoint as bevy_mod_scripting_lua::tealr::mlu::mlua::ToLua>::to_lua(LuaPoint::new(self), lua)
+	}
+}
+#[cfg(feature="lua")]
+impl bevy_script_api::lua::FromLuaProxy<'_> for Point {
+	fn from_lua_proxy<'lua>(lua_value: bevy_mod_scripting_lua::tealr::mlu::mlua::Value<'lua>, _: &'lua bevy_mod_scripting_lua::tealr::mlu::mlua::Lua) -> bevy_mod_scripting_lua::tealr::mlu::mlua::Result<Self> {
+		if let bevy_mod_scripting_lua::tealr::mlu::mlua::Value::UserData(ud) = lua_value {
+			Ok(ud.borrow::<LuaPoint>()?.inner()?)
+		} else if let bevy_mod_scripting_lua::tealr::mlu::mlua::Value::Table(table) = lua_value {
+			bevy_script_api::lua::table::sequence_from_table(table)
+		} else {
+			Err(bevy_mod_scripting_lua::tealr::mlu::mlua::Error::FromLuaConversionError { from: lua_value.type_name(), to: "Point", message: None })
+		}
+	}
+}
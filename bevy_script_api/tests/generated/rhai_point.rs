@@ -0,0 +1,28 @@
+// The invocation `bevy_api_gen --language rhai` writes for the `Point` fixture of its tests,
+// which check that they still match (ignoring whitespace), see `tests/rhai_generated.rs`
+impl_script_newtype!{
+	#[languages(on_feature(rhai))]
+	Point : 
+	Clone +
+	Debug +
+	Methods
+	(
+		length(&self:) -> Raw(f32),
+	)
+	+ Fields
+	(
+		x: Raw(f32),
+		y: Raw(f32),
+	)
+	+ BinOps
+	(
+	)
+	+ UnaryOps
+	(
+	)
+	rhai impl
+	{
+		/// Returns the fully qualified name of this type, as given by `Reflect::type_name`
+		fn "type_name" => |_s: &mut RhaiPoint| -> Result<bevy_mod_scripting_rhai::rhai::Dynamic, Box<bevy_mod_scripting_rhai::rhai::EvalAltResult>> {Ok("Point".into())};
+	}
+}
@@ -0,0 +1,127 @@
+//! Compiles the native lua wrapper generated by `bevy_api_gen` for the `Point` fixture of its tests and drives it from scripts.
+//!
+//! Run with i.e. `cargo test -p bevy_script_api --features lua,bevy_mod_scripting_lua/lua54 --test lua_native`
+use std::ops::{Add, Mul, Neg};
+
+use bevy::reflect::{FromReflect, Reflect};
+use bevy_mod_scripting_lua::tealr::mlu::{
+    mlua::{Lua, Value},
+    UserDataProxy,
+};
+use bevy_script_api::{common::sequence::ScriptSequence, lua::FromLuaProxy};
+
+/// A point in the plane.
+#[derive(Reflect, FromReflect, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Point {
+    /// The horizontal coordinate.
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    /// The origin.
+    pub const ZERO: Point = Point { x: 0.0, y: 0.0 };
+
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    /// The distance from the origin.
+    pub fn length(&self) -> f32 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+
+    pub fn scale(&mut self, factor: f32) {
+        self.x *= factor;
+        self.y *= factor;
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Mul<f32> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: f32) -> Point {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl ScriptSequence for Point {
+    type Element = f32;
+    const LEN: usize = 2;
+
+    fn from_elements(elements: &[f32]) -> Self {
+        Point::new(elements[0], elements[1])
+    }
+
+    fn to_elements(&self) -> Vec<f32> {
+        vec![self.x, self.y]
+    }
+}
+
+include!("generated/lua_native_point.rs");
+
+#[test]
+fn generated_wrapper() {
+    let lua = Lua::new();
+    lua.globals()
+        .set("point", LuaPoint::new(Point::new(3.0, 4.0)))
+        .unwrap();
+
+    let eval = |code: &str| lua.load(code).eval::<f32>().unwrap();
+
+    // methods
+    assert_eq!(eval("point:length()"), 5.0);
+    assert_eq!(eval("point:scale(2) return point.x"), 6.0);
+    assert_eq!(eval("return point.new(1, 2).y"), 2.0);
+    // fields
+    assert_eq!(eval("point.y = 1 return point.y"), 1.0);
+    // associated constants are static fields of the global proxy of the type
+    lua.globals()
+        .set("Point", UserDataProxy::<LuaPoint>::new(&lua).unwrap())
+        .unwrap();
+    assert_eq!(eval("return Point.ZERO.y"), 0.0);
+    assert_eq!(eval("Point.ZERO.y = 2 return Point.ZERO.y"), 0.0);
+    // operators
+    assert_eq!(eval("return (point + point).x"), 12.0);
+    assert_eq!(eval("return (point * 0.5).x"), 3.0);
+    assert_eq!(eval("return (-point).y"), -1.0);
+    assert!(lua.load("return point * 'x'").exec().is_err());
+    assert!(lua.load("return point == point").eval::<bool>().unwrap());
+    assert!(lua
+        .load("return tostring(point)")
+        .eval::<String>()
+        .unwrap()
+        .contains("Point"));
+    assert_eq!(
+        lua.load("return point:type_name()")
+            .eval::<String>()
+            .unwrap(),
+        "Point"
+    );
+
+    // sequences
+    assert_eq!(eval("return point:to_array()[2]"), 1.0);
+    let table = lua.load("return {7, 8}").eval::<Value>().unwrap();
+    assert_eq!(
+        Point::from_lua_proxy(table, &lua).unwrap(),
+        Point::new(7.0, 8.0)
+    );
+    let table = lua.load("return {7}").eval::<Value>().unwrap();
+    assert!(Point::from_lua_proxy(table, &lua).is_err());
+}
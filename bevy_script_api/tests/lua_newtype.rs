@@ -0,0 +1,172 @@
+//! Exposes local types to lua through `impl_script_newtype!` and drives the generated wrappers from scripts.
+//!
+//! Run with i.e. `cargo test -p bevy_script_api --features lua,bevy_mod_scripting_lua/lua54 --test lua_newtype`
+use bevy::{
+    prelude::{AppTypeRegistry, Component, ReflectComponent, World},
+    reflect::{FromReflect, Reflect},
+    utils::HashMap,
+};
+use bevy_mod_scripting_core::world::WorldPointer;
+use bevy_mod_scripting_lua::tealr::mlu::mlua::Lua;
+use bevy_script_api::{
+    impl_lua_newtype, impl_script_newtype, lua::bevy::LuaWorld, script_ref::ScriptRef,
+};
+
+#[derive(Component, Reflect, FromReflect, Clone, Copy, Debug, Default, PartialEq)]
+#[reflect(Component)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub fn map(&mut self, f: impl Fn(f32) -> f32) {
+        self.x = f(self.x);
+        self.y = f(self.y);
+    }
+
+    pub fn weight(&self) -> Weight {
+        Weight(self.x + self.y)
+    }
+
+    pub fn scale_by(&mut self, weight: &Weight) {
+        self.x *= weight.0;
+        self.y *= weight.0;
+    }
+}
+
+/// Has no proxy, so it's passed to scripts as a `ReflectedValue`
+#[derive(Reflect, FromReflect, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Weight(pub f32);
+
+impl_script_newtype!(
+    #[languages(lua)]
+    Point: Debug + Clone
+        + Fields(
+            x: Raw(f32),
+            y: Raw(f32),
+        )
+        + Methods(
+            map(&mut self:Callback(Raw(f32) -> Raw(f32))),
+            weight(&self:) -> Reflected(Weight),
+            scale_by(&mut self:Reflected(&Weight)),
+        )
+);
+
+/// Uses bevy's `HashMap` rather than the one maps are exchanged with scripts as
+#[derive(Reflect, FromReflect, Clone, Debug, Default)]
+pub struct Inventory {
+    pub counts: HashMap<String, f32>,
+}
+
+impl Inventory {
+    pub fn restocked(&self, extra: HashMap<String, f32>) -> HashMap<String, f32> {
+        let mut counts = self.counts.clone();
+        for (item, count) in extra {
+            *counts.entry(item).or_default() += count;
+        }
+        counts
+    }
+}
+
+impl_script_newtype!(
+    #[languages(lua)]
+    Inventory: Debug + Clone
+        + Fields(
+            counts: Raw(HashMap<String, f32>),
+        )
+        + Methods(
+            restocked(&self:Raw(HashMap<String, f32>)) -> Raw(HashMap<String, f32>),
+        )
+);
+
+#[test]
+fn maps_are_converted_to_the_map_type_of_the_wrapped_type() {
+    let mut world = World::new();
+    let world_ptr = unsafe { WorldPointer::new(&mut world) };
+
+    let lua = Lua::new();
+    lua.globals()
+        .set("world", LuaWorld::new(world_ptr))
+        .unwrap();
+    lua.globals()
+        .set("inventory", LuaInventory::new(Inventory::default()))
+        .unwrap();
+    let eval = |code: &str| lua.load(code).eval::<f32>().unwrap();
+
+    assert_eq!(
+        eval("inventory.counts = {apples = 2} return inventory.counts.apples"),
+        2.0
+    );
+    assert_eq!(
+        eval("return inventory:restocked({apples = 1, pears = 3}).apples"),
+        3.0
+    );
+}
+
+#[test]
+fn callbacks_can_access_the_world() {
+    let mut world = World::new();
+    world.init_resource::<AppTypeRegistry>();
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    registry.write().register::<Point>();
+    let reflect_component = registry
+        .read()
+        .get(std::any::TypeId::of::<Point>())
+        .and_then(|r| r.data::<ReflectComponent>())
+        .unwrap()
+        .clone();
+    let entity = world.spawn(Point { x: 1.0, y: 2.0 }).id();
+
+    {
+        let world_ptr = unsafe { WorldPointer::new(&mut world) };
+        let point = LuaPoint::new_ref(ScriptRef::new_component_ref(
+            reflect_component,
+            entity,
+            world_ptr.clone(),
+        ));
+
+        let lua = Lua::new();
+        let entity_count = lua
+            .create_function(move |_, ()| Ok(world_ptr.read().entities().len() as f32))
+            .unwrap();
+        lua.globals().set("entity_count", entity_count).unwrap();
+        lua.globals().set("point", point).unwrap();
+
+        lua.load("point:map(function(v) return v + entity_count() end)")
+            .exec()
+            .unwrap();
+        // nothing is written back if a callback fails
+        assert!(lua
+            .load("point:map(function(v) error(entity_count()) end)")
+            .exec()
+            .is_err());
+    }
+
+    assert_eq!(
+        *world.get::<Point>(entity).unwrap(),
+        Point { x: 2.0, y: 3.0 }
+    );
+}
+
+#[test]
+fn unknown_types_are_passed_as_reflected_values() {
+    let mut world = World::new();
+    let world_ptr = unsafe { WorldPointer::new(&mut world) };
+
+    let lua = Lua::new();
+    lua.globals()
+        .set("world", LuaWorld::new(world_ptr))
+        .unwrap();
+    lua.globals()
+        .set("point", LuaPoint::new(Point { x: 1.0, y: 2.0 }))
+        .unwrap();
+
+    lua.load("point:scale_by(point:weight())").exec().unwrap();
+    assert_eq!(
+        lua.load("return point.x + point.y").eval::<f32>().unwrap(),
+        9.0
+    );
+    // anything but a reflected value is rejected
+    assert!(lua.load("point:scale_by(2)").exec().is_err());
+}
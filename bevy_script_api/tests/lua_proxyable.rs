@@ -0,0 +1,88 @@
+//! Converts a struct deriving `LuaProxyable` to and from lua through a reference to a component.
+//!
+//! Run with i.e. `cargo test -p bevy_script_api --features lua,bevy_mod_scripting_lua/lua54 --test lua_proxyable`
+use bevy::{
+    prelude::{AppTypeRegistry, Component, ReflectComponent, World},
+    reflect::{FromReflect, Reflect},
+};
+use bevy_mod_scripting_core::world::WorldPointer;
+use bevy_mod_scripting_lua::tealr::mlu::mlua::{Lua, Value};
+use bevy_script_api::{script_ref::ScriptRef, LuaProxyable};
+
+#[derive(Component, Reflect, FromReflect, LuaProxyable, Clone, Debug, Default, PartialEq)]
+#[reflect(Component)]
+pub struct Settings {
+    pub volume: f32,
+    pub name: String,
+}
+
+#[test]
+fn references_are_copied_and_written_back_on_assignment() {
+    let mut world = World::new();
+    world.init_resource::<AppTypeRegistry>();
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    registry.write().register::<Settings>();
+    let reflect_component = registry
+        .read()
+        .get(std::any::TypeId::of::<Settings>())
+        .and_then(|r| r.data::<ReflectComponent>())
+        .unwrap()
+        .clone();
+    let entity = world
+        .spawn(Settings {
+            volume: 0.5,
+            name: "music".to_owned(),
+        })
+        .id();
+
+    {
+        let world_ptr = unsafe { WorldPointer::new(&mut world) };
+        let mut settings = ScriptRef::new_component_ref(reflect_component, entity, world_ptr);
+        let volume = |settings: &ScriptRef| {
+            settings
+                .get(|s| s.downcast_ref::<Settings>().unwrap().volume)
+                .unwrap()
+        };
+
+        let lua = Lua::new();
+        let table =
+            <Settings as bevy_script_api::lua::LuaProxyable>::ref_to_lua(settings.clone(), &lua)
+                .unwrap();
+        lua.globals().set("settings", table).unwrap();
+
+        // reads see the referenced value
+        assert_eq!(
+            lua.load("return settings.volume").eval::<f32>().unwrap(),
+            0.5
+        );
+        assert_eq!(
+            lua.load("return settings.name").eval::<String>().unwrap(),
+            "music"
+        );
+
+        // writes only change the copy
+        lua.load("settings.volume = 1 settings.name = 'sfx'")
+            .exec()
+            .unwrap();
+        assert_eq!(volume(&settings), 0.5);
+
+        // until it is assigned back
+        let table = lua.load("return settings").eval::<Value>().unwrap();
+        <Settings as bevy_script_api::lua::LuaProxyable>::apply_lua(&mut settings, &lua, table)
+            .unwrap();
+        assert_eq!(volume(&settings), 1.0);
+
+        // fields missing from the table are left alone
+        let table = lua.load("return { volume = 0 }").eval::<Value>().unwrap();
+        <Settings as bevy_script_api::lua::LuaProxyable>::apply_lua(&mut settings, &lua, table)
+            .unwrap();
+    }
+
+    assert_eq!(
+        *world.get::<Settings>(entity).unwrap(),
+        Settings {
+            volume: 0.0,
+            name: "sfx".to_owned(),
+        }
+    );
+}
@@ -0,0 +1,170 @@
+//! Converts random values to their script proxies and back, checking nothing is lost on the way,
+//! such as the precision of floats or the high bits of integers.
+//!
+//! Script integers are 64 bit signed integers, so wider integer types are only expected to round trip values in that range,
+//! what happens to unsigned integers above `i64::MAX` is checked separately.
+//! Run with i.e. `cargo test -p bevy_script_api --features lua,rhai,bevy_mod_scripting_lua/lua54 --test proxy_round_trip`
+use bevy::math::{DVec3, IVec3, Quat, UVec3, Vec2, Vec3, Vec4};
+use bevy_mod_scripting_lua::tealr::mlu::mlua::{self, Lua, ToLua, Value};
+use bevy_mod_scripting_rhai::rhai::{Dynamic, INT};
+use bevy_script_api::{
+    common::sequence::ScriptSequence,
+    lua::{table::sequence_to_table, FromLuaProxy, ToLuaProxy},
+    rhai::{std::sequence_to_array, FromRhaiProxy, ToRhaiProxy},
+};
+use proptest::prelude::*;
+
+fn lua_round_trip<T>(value: T) -> T
+where
+    T: for<'lua> ToLuaProxy<'lua> + for<'lua> FromLuaProxy<'lua>,
+{
+    let lua = Lua::new();
+    let proxy = value.to_lua_proxy(&lua).unwrap();
+    T::from_lua_proxy(proxy, &lua).unwrap()
+}
+
+fn rhai_round_trip<T: ToRhaiProxy + FromRhaiProxy>(value: T) -> T {
+    T::from_rhai_proxy(value.to_rhai_proxy().unwrap()).unwrap()
+}
+
+/// Converts a value which lua represents as a float, rather than an integer, to lua and back
+fn lua_float_round_trip<T>(value: T) -> (f64, mlua::Result<T>)
+where
+    T: for<'lua> ToLuaProxy<'lua> + for<'lua> FromLuaProxy<'lua>,
+{
+    let lua = Lua::new();
+    let proxy = value.to_lua_proxy(&lua).unwrap();
+    let Value::Number(float) = proxy else {
+        panic!("expected a float, got {proxy:?}");
+    };
+    (float, T::from_lua_proxy(proxy, &lua))
+}
+
+/// Round trips through lua tables and rhai arrays of the elements
+fn sequence_round_trips<T>(value: T) -> (T, T)
+where
+    T: ScriptSequence + for<'lua> FromLuaProxy<'lua> + FromRhaiProxy,
+    T::Element: for<'lua> ToLua<'lua> + ToRhaiProxy,
+{
+    let lua = Lua::new();
+    let table = sequence_to_table(&lua, &value).unwrap();
+    let from_lua = T::from_lua_proxy(Value::Table(table), &lua).unwrap();
+
+    let array = sequence_to_array(&value).unwrap();
+    let from_rhai = T::from_rhai_proxy(Dynamic::from(array)).unwrap();
+
+    (from_lua, from_rhai)
+}
+
+/// NaN is never equal to itself, every other float is
+fn f32_value() -> impl Strategy<Value = f32> {
+    any::<f32>().prop_filter("NaN", |f| !f.is_nan())
+}
+
+fn f64_value() -> impl Strategy<Value = f64> {
+    any::<f64>().prop_filter("NaN", |f| !f.is_nan())
+}
+
+macro_rules! round_trip_tests {
+    ($($name:ident: $type:ty = $strategy:expr;)*) => {
+        proptest! {
+            $(
+                #[test]
+                fn $name(value in $strategy) {
+                    let value: $type = value;
+                    prop_assert_eq!(lua_round_trip(value.clone()), value.clone());
+                    prop_assert_eq!(rhai_round_trip(value.clone()), value);
+                }
+            )*
+        }
+    };
+}
+
+round_trip_tests! {
+    bool_: bool = any::<bool>();
+    i8_: i8 = any::<i8>();
+    i16_: i16 = any::<i16>();
+    i32_: i32 = any::<i32>();
+    i64_: i64 = any::<i64>();
+    isize_: isize = any::<isize>();
+    i128_: i128 = i64::MIN as i128..=i64::MAX as i128;
+    u8_: u8 = any::<u8>();
+    u16_: u16 = any::<u16>();
+    u32_: u32 = any::<u32>();
+    u64_: u64 = 0..=i64::MAX as u64;
+    usize_: usize = 0..=i64::MAX as usize;
+    u128_: u128 = 0..=i64::MAX as u128;
+    f32_: f32 = f32_value();
+    f64_: f64 = f64_value();
+    string: String = any::<String>();
+    vec2: Vec2 = (f32_value(), f32_value()).prop_map(|(x, y)| Vec2::new(x, y));
+    vec3: Vec3 = (f32_value(), f32_value(), f32_value()).prop_map(|(x, y, z)| Vec3::new(x, y, z));
+    vec4: Vec4 = (f32_value(), f32_value(), f32_value(), f32_value())
+        .prop_map(|(x, y, z, w)| Vec4::new(x, y, z, w));
+    quat: Quat = (f32_value(), f32_value(), f32_value(), f32_value())
+        .prop_map(|(x, y, z, w)| Quat::from_xyzw(x, y, z, w));
+    dvec3: DVec3 = (f64_value(), f64_value(), f64_value()).prop_map(|(x, y, z)| DVec3::new(x, y, z));
+    ivec3: IVec3 = any::<[i32; 3]>().prop_map(IVec3::from_array);
+    uvec3: UVec3 = any::<[u32; 3]>().prop_map(UVec3::from_array);
+}
+
+proptest! {
+    #[test]
+    fn vec3_sequence(value in (f32_value(), f32_value(), f32_value()).prop_map(|(x, y, z)| Vec3::new(x, y, z))) {
+        prop_assert_eq!(sequence_round_trips(value), (value, value));
+    }
+
+    #[test]
+    fn quat_sequence(value in (f32_value(), f32_value(), f32_value(), f32_value()).prop_map(|(x, y, z, w)| Quat::from_xyzw(x, y, z, w))) {
+        prop_assert_eq!(sequence_round_trips(value), (value, value));
+    }
+
+    #[test]
+    fn dvec3_sequence(value in (f64_value(), f64_value(), f64_value()).prop_map(|(x, y, z)| DVec3::new(x, y, z))) {
+        prop_assert_eq!(sequence_round_trips(value), (value, value));
+    }
+
+    #[test]
+    fn uvec3_sequence(value in any::<[u32; 3]>().prop_map(UVec3::from_array)) {
+        prop_assert_eq!(sequence_round_trips(value), (value, value));
+    }
+}
+
+// Lua turns unsigned integers above `i64::MAX` into floats, which are converted back if they're still in range of the type,
+// rhai wraps 64 bit integers around into negative integers and back, and rejects wider ones.
+proptest! {
+    #[test]
+    fn u64_above_i64_max(value in i64::MAX as u64 + 1..=u64::MAX) {
+        let (float, back) = lua_float_round_trip(value);
+        prop_assert_eq!(float, value as f64);
+        prop_assert_eq!(back.ok(), (float < u64::MAX as f64).then_some(float as u64));
+
+        prop_assert_eq!(value.to_rhai_proxy().unwrap().as_int(), Ok(value as INT));
+        prop_assert_eq!(rhai_round_trip(value), value);
+    }
+
+    #[test]
+    fn usize_above_i64_max(value in i64::MAX as usize + 1..=usize::MAX) {
+        let (float, back) = lua_float_round_trip(value);
+        prop_assert_eq!(float, value as f64);
+        prop_assert_eq!(back.ok(), (float < usize::MAX as f64).then_some(float as usize));
+
+        prop_assert_eq!(value.to_rhai_proxy().unwrap().as_int(), Ok(value as INT));
+        prop_assert_eq!(rhai_round_trip(value), value);
+    }
+
+    #[test]
+    fn u128_above_i64_max(value in i64::MAX as u128 + 1..=u128::MAX) {
+        let (float, back) = lua_float_round_trip(value);
+        prop_assert_eq!(float, value as f64);
+        prop_assert_eq!(back.ok(), (float < u128::MAX as f64).then_some(float as u128));
+
+        prop_assert!(value.to_rhai_proxy().is_err());
+    }
+
+    #[test]
+    fn negative_rhai_integers(value in i64::MIN..0) {
+        prop_assert_eq!(u64::from_rhai_proxy(Dynamic::from(value)).unwrap(), value as u64);
+        prop_assert!(u128::from_rhai_proxy(Dynamic::from(value)).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+//! Compiles the rhai wrapper generated by `bevy_api_gen` for the `Point` fixture of its tests and drives it from scripts.
+//!
+//! Run with i.e. `cargo test -p bevy_script_api --features rhai --test rhai_generated`
+use bevy::reflect::{FromReflect, Reflect};
+use bevy_mod_scripting_rhai::rhai::{Dynamic, Engine, Scope};
+use bevy_script_api::{impl_rhai_newtype, impl_script_newtype, rhai::RegisterRhaiWrapper};
+
+#[derive(Reflect, FromReflect, Clone, Debug, Default)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub fn length(&self) -> f32 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+}
+
+include!("generated/rhai_point.rs");
+
+#[test]
+fn generated_wrapper() {
+    let mut engine = Engine::new();
+    engine.register_rhai_wrapper::<RhaiPoint>().unwrap();
+
+    let mut scope = Scope::new();
+    scope.push("point", RhaiPoint::new(Point { x: 3.0, y: 4.0 }));
+
+    assert_eq!(
+        engine
+            .eval_with_scope::<f64>(&mut scope, "point.length()")
+            .unwrap(),
+        5.0
+    );
+    assert_eq!(
+        engine
+            .eval_with_scope::<f64>(&mut scope, "point.y = 0.0; point.length()")
+            .unwrap(),
+        3.0
+    );
+    assert!(engine
+        .eval_with_scope::<Dynamic>(&mut scope, "point.to_debug()")
+        .unwrap()
+        .to_string()
+        .contains("Point"));
+    assert_eq!(
+        engine
+            .eval_with_scope::<String>(&mut scope, "point.type_name()")
+            .unwrap(),
+        "Point"
+    );
+}
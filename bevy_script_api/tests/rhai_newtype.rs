@@ -0,0 +1,349 @@
+//! Exposes local types to rhai through `impl_script_newtype!` and drives the generated wrappers from scripts.
+//!
+//! Run with i.e. `cargo test -p bevy_script_api --features rhai --test rhai_newtype`
+use std::{
+    io::Write,
+    ops::{Add, Neg},
+    sync::{Arc, Mutex},
+};
+
+use bevy::{
+    prelude::{AppTypeRegistry, Component, ReflectComponent, World},
+    reflect::{FromReflect, Reflect},
+    utils::tracing::subscriber::with_default,
+};
+use bevy_mod_scripting_core::world::WorldPointer;
+use bevy_mod_scripting_rhai::rhai::{Dynamic, Engine, EvalAltResult, Scope};
+use bevy_script_api::{
+    impl_rhai_newtype, impl_script_newtype,
+    rhai::{RegisterRhaiWrapper, ToRhaiProxy},
+    script_ref::ScriptRef,
+};
+
+#[derive(Component, Reflect, FromReflect, Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[reflect(Component)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0.0, y: 0.0 };
+
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub const fn origin() -> Self {
+        Self::ORIGIN
+    }
+
+    pub fn dot(&self, other: Point) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn this(&self) -> f32 {
+        self.x
+    }
+
+    pub fn length_squared(&self) -> f32 {
+        self.dot(*self)
+    }
+
+    pub fn scale(&mut self, by: f32) {
+        self.x *= by;
+        self.y *= by;
+    }
+
+    pub fn map(&mut self, f: impl Fn(f32) -> f32) {
+        self.x = f(self.x);
+        self.y = f(self.y);
+    }
+
+    pub fn normalize(self) -> Result<Point, String> {
+        let length = self.dot(self).sqrt();
+        if length == 0.0 {
+            Err("cannot normalize the origin".to_owned())
+        } else {
+            Ok(Point::new(self.x / length, self.y / length))
+        }
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Add<f32> for Point {
+    type Output = Point;
+
+    fn add(self, rhs: f32) -> Self::Output {
+        Point::new(self.x + rhs, self.y + rhs)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Self::Output {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+#[derive(Reflect, FromReflect, Clone, Debug, Default)]
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+}
+
+impl_script_newtype!(
+    #[languages(rhai)]
+    Point: Debug + Clone + PartialEq + PartialOrd
+        + Fields(
+            x: Raw(f32),
+            #[readonly]
+            y: Raw(f32),
+        )
+        + Methods(
+            // `new` is reserved in rhai
+            #[rename("new_")]
+            new(Raw(f32), Raw(f32)) -> self,
+            const origin() -> self,
+            dot(&self:self) -> Raw(f32),
+            // renamed in rhai alone, where `this` is a keyword
+            #[rename(lua = "this", rhai = "this_")]
+            this(&self:) -> Raw(f32),
+            #[deprecated("use `dot` instead")]
+            length_squared(&self:) -> Raw(f32),
+            scale(&mut self:Raw(f32)),
+            map(&mut self:Callback(Raw(f32) -> Raw(f32))),
+            normalize(self:) -> Wrapped(Result<Point>),
+        )
+        + BinOps(
+            self Add self -> self,
+            self Add Raw(f32) -> self,
+        )
+        + UnaryOps(
+            Neg self -> self
+        )
+    rhai impl {
+        /// Swaps the coordinates
+        fn "swapped" => |s: &mut RhaiPoint| -> Result<Dynamic, Box<EvalAltResult>> {
+            s.val(|s| Point::new(s.y, s.x).to_rhai_proxy())?
+        };
+        // the stubs `bevy_api_gen` writes for excluded methods with `emit_exclusion_stubs`
+        /// Unavailable: Unsupported argument Prefab, not a wrapped type or primitive
+        static fn "instantiate" => |_a_0: bevy_mod_scripting_rhai::rhai::Dynamic| -> Result<bevy_mod_scripting_rhai::rhai::Dynamic, Box<bevy_mod_scripting_rhai::rhai::EvalAltResult>> {Err(Box::new(bevy_mod_scripting_rhai::rhai::EvalAltResult::ErrorRuntime("method `instantiate` unavailable: Unsupported argument Prefab, not a wrapped type or primitive".into(), bevy_mod_scripting_rhai::rhai::Position::NONE)))};
+        /// Unavailable: Unsupported argument Vec<Prefab>, not a wrapped type or primitive
+        fn "fill" => |_s: &mut RhaiPoint, _a_0: bevy_mod_scripting_rhai::rhai::Dynamic, _a_1: bevy_mod_scripting_rhai::rhai::Dynamic| -> Result<bevy_mod_scripting_rhai::rhai::Dynamic, Box<bevy_mod_scripting_rhai::rhai::EvalAltResult>> {Err(Box::new(bevy_mod_scripting_rhai::rhai::EvalAltResult::ErrorRuntime("method `fill` unavailable: Unsupported argument Vec<Prefab>, not a wrapped type or primitive".into(), bevy_mod_scripting_rhai::rhai::Position::NONE)))};
+    }
+);
+
+impl_script_newtype!(
+    #[languages(rhai)]
+    Segment: Debug + Clone
+        + Fields(
+            start: Wrapped(Point),
+            end: Wrapped(Point),
+        )
+);
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .register_rhai_wrapper::<RhaiPoint>()
+        .unwrap()
+        .register_rhai_wrapper::<RhaiSegment>()
+        .unwrap();
+    engine
+}
+
+fn eval<T: Clone + Send + Sync + 'static>(script: &str) -> Result<T, Box<EvalAltResult>> {
+    let mut scope = Scope::new();
+    scope.push(
+        "segment",
+        RhaiSegment::new(Segment {
+            start: Point::new(1.0, 2.0),
+            end: Point::new(3.0, 4.0),
+        }),
+    );
+    engine().eval_with_scope::<T>(&mut scope, script)
+}
+
+#[test]
+fn static_functions_and_constants() {
+    assert_eq!(eval::<f64>("Point::new_(1.0, 2.0).x").unwrap(), 1.0);
+    assert_eq!(eval::<f64>("Point::origin.y").unwrap(), 0.0);
+}
+
+#[test]
+fn methods() {
+    assert_eq!(
+        eval::<f64>("Point::new_(1.0, 2.0).dot(Point::new_(3.0, 4.0))").unwrap(),
+        11.0
+    );
+    assert_eq!(
+        eval::<f64>("let p = Point::new_(1.0, 2.0); p.scale(2.0); p.y").unwrap(),
+        4.0
+    );
+    assert_eq!(
+        eval::<f64>("Point::new_(0.0, 2.0).normalize().y").unwrap(),
+        1.0
+    );
+    assert!(eval::<Dynamic>("Point::origin.normalize()")
+        .unwrap_err()
+        .to_string()
+        .contains("cannot normalize the origin"));
+    assert_eq!(
+        eval::<f64>("Point::new_(1.0, 2.0).swapped().x").unwrap(),
+        2.0
+    );
+    assert_eq!(eval::<f64>("Point::new_(1.0, 2.0).this_()").unwrap(), 1.0);
+}
+
+#[test]
+fn exclusion_stubs_explain_why_methods_are_unavailable() {
+    let error = |script| eval::<Dynamic>(script).unwrap_err().to_string();
+    assert!(error("Point::instantiate(1)")
+        .contains("method `instantiate` unavailable: Unsupported argument Prefab"));
+    assert!(error("Point::origin.fill(1, [])").contains("method `fill` unavailable"));
+}
+
+/// Collects everything logged by a `tracing_subscriber::fmt` subscriber
+#[derive(Clone, Default)]
+struct LogOutput(Arc<Mutex<Vec<u8>>>);
+
+impl Write for LogOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn deprecated_methods_warn_once() {
+    let output = LogOutput::default();
+    let writer = output.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+
+    with_default(subscriber, || {
+        for _ in 0..2 {
+            assert_eq!(
+                eval::<f64>("Point::new_(1.0, 2.0).length_squared()").unwrap(),
+                5.0
+            );
+        }
+    });
+
+    let logs = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+    assert_eq!(
+        logs.matches("`Point.length_squared` is deprecated: use `dot` instead")
+            .count(),
+        1,
+        "{logs}"
+    );
+}
+
+#[test]
+fn fields() {
+    assert_eq!(
+        eval::<f64>("let p = Point::new_(1.0, 2.0); p.x = 5.0; p.x").unwrap(),
+        5.0
+    );
+    // readonly fields have no setter
+    assert!(eval::<Dynamic>("let p = Point::new_(1.0, 2.0); p.y = 5.0;").is_err());
+    // wrapped fields are written back to the value they were read from
+    assert_eq!(
+        eval::<f64>("segment.end.x = 10.0; segment.end.x").unwrap(),
+        10.0
+    );
+    assert!(eval::<Dynamic>("segment.start = 1.0;").is_err());
+}
+
+#[test]
+fn operators() {
+    assert_eq!(
+        eval::<f64>("(Point::new_(1.0, 2.0) + Point::new_(3.0, 4.0)).y").unwrap(),
+        6.0
+    );
+    assert_eq!(eval::<f64>("(Point::new_(1.0, 2.0) + 1.0).x").unwrap(), 2.0);
+    assert_eq!(eval::<f64>("(-Point::new_(1.0, 2.0)).x").unwrap(), -1.0);
+    assert!(eval::<bool>("Point::new_(1.0, 2.0) == Point::new_(1.0, 2.0)").unwrap());
+    assert!(eval::<bool>("Point::new_(1.0, 2.0) < Point::new_(3.0, 2.0)").unwrap());
+    assert_eq!(
+        eval::<String>("Point::new_(1.0, 2.0).to_string()").unwrap(),
+        "Point { x: 1.0, y: 2.0 }"
+    );
+}
+
+#[test]
+fn callbacks() {
+    assert_eq!(
+        eval::<f64>("let p = Point::new_(1.0, 2.0); p.map(|v| v * 3.0); p.y").unwrap(),
+        6.0
+    );
+    // curried arguments are passed on
+    assert_eq!(
+        eval::<f64>(
+            "fn add(a, b) { a + b } let p = Point::new_(1.0, 2.0); p.map(Fn(\"add\").curry(0.5)); p.x"
+        )
+        .unwrap(),
+        1.5
+    );
+    assert!(eval::<Dynamic>("Point::origin.map(|v| throw \"no\")")
+        .unwrap_err()
+        .to_string()
+        .contains("no"));
+}
+
+#[test]
+fn callbacks_can_access_the_world() {
+    let mut world = World::new();
+    world.init_resource::<AppTypeRegistry>();
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    registry.write().register::<Point>();
+    let reflect_component = registry
+        .read()
+        .get(std::any::TypeId::of::<Point>())
+        .and_then(|r| r.data::<ReflectComponent>())
+        .unwrap()
+        .clone();
+    let entity = world.spawn(Point::new(1.0, 2.0)).id();
+
+    {
+        let world_ptr = unsafe { WorldPointer::new(&mut world) };
+        let point = RhaiPoint::new_ref(ScriptRef::new_component_ref(
+            reflect_component,
+            entity,
+            world_ptr.clone(),
+        ));
+
+        let mut engine = engine();
+        engine.register_fn("entity_count", move || {
+            world_ptr.read().entities().len() as f64
+        });
+        let mut scope = Scope::new();
+        scope.push("point", point);
+
+        engine
+            .eval_with_scope::<()>(&mut scope, "point.map(|v| v + entity_count());")
+            .unwrap();
+        // nothing is written back if a callback fails
+        assert!(engine
+            .eval_with_scope::<()>(&mut scope, "point.map(|v| throw entity_count());")
+            .is_err());
+    }
+
+    assert_eq!(*world.get::<Point>(entity).unwrap(), Point::new(2.0, 3.0));
+}
@@ -179,6 +179,10 @@ fn main() -> std::io::Result<()> {
                                 print(string.format("%s:%s",k,v))
                             end
                     
+                            -- script functions can be passed to rust as callbacks, here to filter the vector in place
+                            comp.vec_of_option_bools:retain(function(v) return v ~= nil end)
+                            print(#comp.vec_of_option_bools)
+
                             comp.vec_of_option_bools:clear()
 
                             print(#comp.vec_of_option_bools)
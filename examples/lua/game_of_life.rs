@@ -242,11 +242,10 @@ fn main() -> std::io::Result<()> {
                 .after(update_rendered_state)
                 .in_schedule(CoreSchedule::FixedUpdate),
         )
-        .configure_set(
-            LifeStages::Scripts
-                .after(CoreSet::UpdateFlush)
-                .before(CoreSet::PostUpdate),
-        )
+        // the scripts run in the fixed update schedule, right after the update event is sent
+        .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+            schedule.configure_set(LifeStages::Scripts.after(send_on_update));
+        })
         .add_system(
             script_event_handler::<LuaScriptHost<()>, 0, 1>
                 .in_set(LifeStages::Scripts)
@@ -0,0 +1,81 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy_mod_scripting::prelude::*;
+
+/// A component present on a large number of entities, we only want to look at a handful of them
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub struct Enemy {
+    health: f32,
+}
+
+fn main() -> std::io::Result<()> {
+    let mut app = App::new();
+
+    app.add_plugins(DefaultPlugins)
+        .add_plugin(ScriptingPlugin)
+        .register_type::<Enemy>()
+        .add_script_host_to_base_set::<LuaScriptHost<()>, _>(CoreSet::PostUpdate)
+        .add_api_provider::<LuaScriptHost<()>>(Box::new(LuaBevyAPIProvider))
+        .add_system(|world: &mut World| {
+            world.spawn_batch((0..100_000).map(|i| Enemy { health: i as f32 }));
+
+            let entity = world.spawn(()).id();
+
+            world.resource_scope(|world, mut host: Mut<LuaScriptHost<()>>| {
+                host.run_one_shot(
+                    r#"
+                    function once()
+                        local enemy_type = world:get_type_by_name("Enemy")
+
+                        -- entities are looked up one at a time as the loop advances,
+                        -- breaking out early means the rest of the entities are never visited
+                        local found = 0
+                        for enemy in world:iter_entities_with(enemy_type) do
+                            local e = world:get_component(enemy, enemy_type)
+                            if e.health > 50000 then
+                                print(string.format("%s has %s health", enemy, e.health))
+                                found = found + 1
+                                if found == 5 then
+                                    break
+                                end
+                            end
+                        end
+
+                        -- pairs works too, and yields the index alongside each element
+                        for i, enemy in pairs(world:iter_entities_with(enemy_type)) do
+                            if i > 3 then
+                                break
+                            end
+                            print(string.format("%s: %s", i, enemy))
+                        end
+
+                        -- spawning or despawning entities mid iteration invalidates the iterator
+                        local ok, err = pcall(function()
+                            for enemy in world:iter_entities_with(enemy_type) do
+                                world:despawn(enemy)
+                            end
+                        end)
+                        print(string.format("iteration failed as expected: %s", err))
+                    end
+                    "#
+                    .as_bytes(),
+                    "script.lua",
+                    entity,
+                    world,
+                    LuaEvent {
+                        hook_name: "once".to_owned(),
+                        args: (),
+                        recipients: Recipients::All,
+                    },
+                )
+                .expect("Something went wrong in the script!");
+            });
+
+            world.send_event(AppExit)
+        });
+
+    app.run();
+
+    Ok(())
+}
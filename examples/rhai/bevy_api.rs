@@ -3,7 +3,7 @@ use bevy::math::DQuat;
 use bevy::prelude::*;
 use bevy_mod_scripting::{api::rhai::bevy::RhaiBevyAPIProvider, prelude::*};
 use bevy_mod_scripting_rhai::rhai::Engine;
-use bevy_script_api::rhai::{std::RegisterVecType, RegisterForeignRhaiType};
+use bevy_script_api::rhai::RegisterForeignRhaiType;
 
 /// Let's define a resource, we want it to be "assignable" via lua so we derive `ReflectLuaProxyable`
 /// This allows us to reach this value when it's a field under any other Reflectable type
@@ -36,9 +36,8 @@ pub struct MyComponent {
 }
 
 pub struct MyAPIProvider;
-// unlike mlua, rhai does not have the concept of generic types, all functionality is based around
-// registering monomorphized functions, therefore we must register functions of generic types for every type we want
-// to use them with, it's less convenient and is what the compiler would do anyway but hey it works!
+// `Vec<T>` and `Option<T>` fields are handled generically by the bevy API provider,
+// registering them as foreign rhai types below is all that is needed
 impl APIProvider for MyAPIProvider {
     type APITarget = Engine;
 
@@ -48,8 +47,6 @@ impl APIProvider for MyAPIProvider {
 
     fn attach_api(&mut self, api: &mut Self::APITarget) -> Result<(), ScriptError> {
         api.set_max_expr_depths(999, 999);
-        api.register_vec_functions::<Option<bool>>();
-        api.register_vec_functions::<bool>();
         Ok(())
     }
 }
@@ -66,8 +63,8 @@ fn main() -> std::io::Result<()> {
         .register_foreign_rhai_type::<Option<Vec<bool>>>()
         // note the implementation for Option is there, but we must register `LuaProxyable` for it
         .init_resource::<MyResource>()
-        // this stage handles addition and removal of script contexts, we can safely use `CoreStage::PostUpdate`
-        .add_script_host::<RhaiScriptHost<()>, _>(CoreStage::PostUpdate)
+        // this stage handles addition and removal of script contexts, we can safely use `CoreSet::PostUpdate`
+        .add_script_host_to_base_set::<RhaiScriptHost<()>, _>(CoreSet::PostUpdate)
         .add_api_provider::<RhaiScriptHost<()>>(Box::new(RhaiBevyAPIProvider))
         .add_api_provider::<RhaiScriptHost<()>>(Box::new(MyAPIProvider))
         .add_system(|world: &mut World| {
@@ -163,10 +163,10 @@ fn main() -> std::io::Result<()> {
         .add_console_command::<RunScriptCmd, _>(run_script_cmd)
         .add_console_command::<DeleteScriptCmd, _>(delete_script_cmd)
         // choose and register the script hosts you want to use
-        .add_script_host::<RhaiScriptHost<()>, _>(CoreStage::PostUpdate)
+        .add_script_host_to_base_set::<RhaiScriptHost<()>, _>(CoreSet::PostUpdate)
         .add_api_provider::<RhaiScriptHost<()>>(Box::new(RhaiAPI))
         .add_api_provider::<RhaiScriptHost<()>>(Box::new(RhaiBevyAPIProvider))
-        .add_script_handler_stage::<RhaiScriptHost<()>, _, 0, 0>(CoreStage::PostUpdate)
+        .add_script_handler_to_base_set::<RhaiScriptHost<()>, _, 0, 0>(CoreSet::PostUpdate)
         // add your systems
         .add_system(trigger_on_update_rhai)
         .add_system(forward_script_err_to_console);
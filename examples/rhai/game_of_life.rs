@@ -6,13 +6,12 @@ use bevy::{
         render_resource::{Extent3d, TextureDimension, TextureFormat},
         texture::ImageSampler,
     },
-    time::FixedTimestep,
     window::WindowResized,
 };
 
 use bevy_mod_scripting::prelude::*;
 use bevy_mod_scripting_rhai::rhai::packages::Package;
-use bevy_script_api::rhai::{std::RegisterVecType, RegisterForeignRhaiType};
+use bevy_script_api::rhai::RegisterForeignRhaiType;
 use rhai_rand::RandomPackage;
 
 #[derive(Clone, Debug, Default, Reflect, Component)]
@@ -30,7 +29,6 @@ impl APIProvider for LifeAPI {
     type DocTarget = RhaiDocFragment;
 
     fn attach_api(&mut self, api: &mut Self::APITarget) -> Result<(), ScriptError> {
-        api.register_vec_functions::<u8>();
         let random = RandomPackage::new();
         api.set_max_expr_depths(999, 999);
 
@@ -196,44 +194,48 @@ pub fn send_init(mut events: PriorityEventWriter<RhaiEvent<()>>) {
     )
 }
 
-#[derive(SystemSet)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
 pub enum LifeStages {
     Scripts,
 }
 
 /// how often to step the simulation
-const UPDATE_FREQUENCY: f64 = 1.0 / 30.0;
+const UPDATE_FREQUENCY: f32 = 1.0 / 30.0;
 
 fn main() -> std::io::Result<()> {
     let mut app = App::new();
 
     app.add_plugins(DefaultPlugins)
+        .insert_resource(FixedTime::new_from_secs(UPDATE_FREQUENCY))
         .add_plugin(LogDiagnosticsPlugin::default())
         .add_plugin(FrameTimeDiagnosticsPlugin::default())
         .add_plugin(ScriptingPlugin)
         .init_resource::<Settings>()
         .add_startup_system(setup)
         .add_startup_system(send_init)
-        .add_system(sync_window_size.before(update_rendered_state))
+        .add_system(sync_window_size)
         .add_startup_system(|asset_server: ResMut<AssetServer>| {
             asset_server.asset_io().watch_for_changes().unwrap()
         })
-        .add_system_set(
-            SystemSet::new()
-                .with_run_criteria(FixedTimestep::step(UPDATE_FREQUENCY))
-                .with_system(update_rendered_state)
-                .with_system(send_on_update),
+        .add_system(
+            update_rendered_state
+                .after(sync_window_size)
+                .in_schedule(CoreSchedule::FixedUpdate),
         )
-        .add_stage_after(
-            CoreStage::Update,
-            LifeStages::Scripts,
-            SystemStage::single_threaded(),
+        .add_system(
+            send_on_update
+                .after(update_rendered_state)
+                .in_schedule(CoreSchedule::FixedUpdate),
         )
-        .add_script_handler_stage_with_criteria::<RhaiScriptHost<()>, _, _, _, 0, 1>(
+        // the scripts run in the fixed update schedule, right after the update event is sent
+        .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+            schedule.configure_set(LifeStages::Scripts.after(send_on_update));
+        })
+        .add_script_handler_to_schedule::<RhaiScriptHost<()>, _, _, 0, 1>(
+            CoreSchedule::FixedUpdate,
             LifeStages::Scripts,
-            FixedTimestep::step(UPDATE_FREQUENCY),
         )
-        .add_script_host::<RhaiScriptHost<()>, _>(CoreStage::PostUpdate)
+        .add_script_host_to_base_set::<RhaiScriptHost<()>, _>(CoreSet::PostUpdate)
         .add_api_provider::<RhaiScriptHost<()>>(Box::new(RhaiBevyAPIProvider))
         .add_api_provider::<RhaiScriptHost<()>>(Box::new(LifeAPI))
         .update_documentation::<RhaiScriptHost<()>>();
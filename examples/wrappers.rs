@@ -85,7 +85,7 @@ fn main() -> std::io::Result<()> {
 
     app.add_plugins(DefaultPlugins)
         .add_plugin(ScriptingPlugin)
-        .add_script_host::<LuaScriptHost<LuaMyThing>, _>(CoreStage::PostUpdate)
+        .add_script_host_to_base_set::<LuaScriptHost<LuaMyThing>, _>(CoreSet::PostUpdate)
         .register_type::<MyThing>()
         .init_resource::<MyThing>()
         .add_system(|world: &mut World| {
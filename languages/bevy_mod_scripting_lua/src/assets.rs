@@ -3,14 +3,17 @@ use bevy::{
     reflect::TypeUuid,
 };
 use bevy_mod_scripting_core::asset::CodeAsset;
+use tealr::mlu::mlua::{prelude::*, Lua};
 
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 #[derive(Debug, TypeUuid)]
 #[uuid = "39cadc56-aa9c-4543-8640-a018b74b5052"]
 /// A lua code file in bytes
 pub struct LuaFile {
     pub bytes: Arc<[u8]>,
+    /// The script compiled to bytecode, if it was loaded by a [`LuaLoader`] which compiles scripts ahead of time
+    pub bytecode: Option<Arc<[u8]>>,
 }
 
 impl CodeAsset for LuaFile {
@@ -21,7 +24,42 @@ impl CodeAsset for LuaFile {
 
 #[derive(Default)]
 /// Asset loader for lua scripts
-pub struct LuaLoader;
+pub struct LuaLoader {
+    /// When enabled scripts are compiled to bytecode while their asset is loaded, so syntax errors fail the asset load.
+    ///
+    /// Safe lua states refuse to load bytecode, so hosts only reuse the bytecode with the `unsafe_lua_modules` feature enabled.
+    /// Replace the default loader with `app.add_asset_loader(LuaLoader { precompile: true })` after adding the host.
+    pub precompile: bool,
+}
+
+impl LuaLoader {
+    /// Creates the asset for the given lua source, compiling it if enabled
+    pub(crate) fn lua_file(&self, path: &Path, source: &[u8]) -> Result<LuaFile, Error> {
+        let bytecode = if self.precompile {
+            let lua = Lua::new();
+            let name = path.to_string_lossy();
+            let bytecode = lua
+                .load(source)
+                .set_name(&*name)
+                .and_then(|c| c.into_function())
+                .map(|f| f.dump(false))
+                .map_err(|e| match e {
+                    LuaError::SyntaxError { message, .. } => {
+                        Error::msg(format!("Syntax error in `{}`: {message}", path.display()))
+                    }
+                    e => Error::msg(format!("Failed to compile `{}`: {e}", path.display())),
+                })?;
+            Some(bytecode.into())
+        } else {
+            None
+        };
+
+        Ok(LuaFile {
+            bytes: source.into(),
+            bytecode,
+        })
+    }
+}
 
 impl AssetLoader for LuaLoader {
     fn load<'a>(
@@ -116,18 +154,20 @@ impl AssetLoader for LuaLoader {
                     fs::read_to_string(temp_file_path).expect("Could not find output lua file");
                 fs::remove_file(temp_file_path).unwrap();
 
-                load_context.set_default_asset(LoadedAsset::new(LuaFile {
-                    bytes: lua_code.as_bytes().into(),
-                }));
+                let file = self.lua_file(load_context.path(), lua_code.as_bytes());
+                Box::pin(async move {
+                    load_context.set_default_asset(LoadedAsset::new(file?));
+                    Ok(())
+                })
             }
             _ => {
-                load_context.set_default_asset(LoadedAsset::new(LuaFile {
-                    bytes: bytes.into(),
-                }));
+                let file = self.lua_file(load_context.path(), bytes);
+                Box::pin(async move {
+                    load_context.set_default_asset(LoadedAsset::new(file?));
+                    Ok(())
+                })
             }
         }
-
-        Box::pin(async move { Ok(()) })
     }
 
     #[cfg(feature = "teal")]
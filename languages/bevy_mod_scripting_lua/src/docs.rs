@@ -2,6 +2,7 @@ use std::{
     env,
     fs::{self, File},
     io::Write,
+    path::Path,
     process::Command,
 };
 
@@ -73,6 +74,49 @@ impl DocFragment for LuaDocFragment {
         self
     }
 
+    /// Exports either a teal declaration file (`.d.tl`) or the raw `tealr` json (`.json`) depending on the extension
+    fn export_docs(self, path: &Path) -> Result<(), ScriptError> {
+        let docs_name = self.name().to_owned();
+        let file_name = path
+            .file_name()
+            .map(|f| f.to_string_lossy())
+            .unwrap_or_default();
+
+        let tw = self
+            .walker
+            .into_iter()
+            .fold(TypeWalker::new(), |a, v| (v.builder)(a));
+
+        let contents = if file_name.ends_with(".d.tl") {
+            tw.generate_global(&docs_name)
+                .map_err(|e| ScriptError::DocGenError(e.to_string()))?
+        } else if file_name.ends_with(".json") {
+            serde_json::to_string_pretty(&tw)
+                .map_err(|e| ScriptError::DocGenError(e.to_string()))?
+        } else {
+            return Err(ScriptError::DocGenError(format!(
+                "Cannot export to `{}`, expected a `.d.tl` or `.json` file",
+                path.display()
+            )));
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                ScriptError::DocGenError(format!(
+                    "Could not create `{}` directories: {e}",
+                    parent.display()
+                ))
+            })?;
+        }
+
+        File::create(path)
+            .and_then(|mut file| {
+                file.write_all(contents.as_bytes())?;
+                file.flush()
+            })
+            .map_err(|e| ScriptError::DocGenError(e.to_string()))
+    }
+
     fn gen_docs(self) -> Result<(), ScriptError> {
         let script_asset_path = &FileAssetIo::get_base_path().join("assets").join("scripts");
 
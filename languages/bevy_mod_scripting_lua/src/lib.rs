@@ -3,11 +3,12 @@ use crate::{
     docs::LuaDocFragment,
 };
 use bevy::{
-    ecs::schedule::{BaseSystemSet, FreeSystemSet},
+    ecs::schedule::{BaseSystemSet, FreeSystemSet, ScheduleLabel},
     prelude::*,
 };
 use bevy_mod_scripting_core::{prelude::*, systems::*, world::WorldPointer};
 
+use std::borrow::Cow;
 use std::fmt;
 use std::marker::PhantomData;
 use std::sync::Mutex;
@@ -15,7 +16,12 @@ use tealr::mlu::mlua::{prelude::*, Function};
 
 pub mod assets;
 pub mod docs;
+pub mod limits;
+pub mod modules;
+pub mod reload;
 pub mod util;
+/// Used by the `to_json` and `from_json` methods `bevy_api_gen` generates with `emit_serde_methods`
+pub use serde_json;
 pub use tealr;
 pub mod prelude {
     pub use crate::{
@@ -28,7 +34,7 @@ pub mod prelude {
                 TealData,
             },
         },
-        LuaEvent, LuaScriptHost,
+        LuaEvent, LuaScriptError, LuaScriptHost,
     };
 }
 
@@ -63,17 +69,103 @@ impl<A: LuaArg> ScriptEvent for LuaEvent<A> {
 #[derive(Resource)]
 /// Lua script host, enables Lua scripting.
 pub struct LuaScriptHost<A: LuaArg> {
+    /// When enabled each script is compiled to bytecode once, which is then loaded by every instance of the script.
+    /// Each instance still runs in its own lua state.
+    ///
+    /// Safe lua states refuse to load bytecode, so this only has an effect with the `unsafe_lua_modules` feature enabled.
+    pub share_compiled_scripts: bool,
+    /// The resource limits applied to every lua state created by the host, changes apply to scripts loaded afterwards.
+    pub limits: ScriptLimits,
+    #[cfg_attr(not(feature = "unsafe_lua_modules"), allow(dead_code))]
+    compiled: CompiledScriptCache<Vec<u8>>,
+    modules: ScriptModules,
     _ph: PhantomData<A>,
 }
 
 impl<A: LuaArg> Default for LuaScriptHost<A> {
     fn default() -> Self {
         Self {
+            share_compiled_scripts: false,
+            limits: Default::default(),
+            compiled: Default::default(),
+            modules: Default::default(),
             _ph: Default::default(),
         }
     }
 }
 
+impl<A: LuaArg> LuaScriptHost<A> {
+    /// Loads a script into a new lua state, from the given bytecode compiled ahead of time if available
+    fn load_lua(
+        &mut self,
+        script: &[u8],
+        bytecode: Option<&[u8]>,
+        script_data: &ScriptData,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<Mutex<Lua>, ScriptError> {
+        #[cfg(feature = "unsafe_lua_modules")]
+        let lua = unsafe { Lua::unsafe_new() };
+        #[cfg(not(feature = "unsafe_lua_modules"))]
+        let lua = Lua::new();
+
+        limits::apply_limits(&lua, script_data.name, &self.limits)
+            .and_then(|_| modules::attach_require_api(&lua, self.modules.clone()))
+            .map_err(|e| ScriptError::FailedToAttachAPI {
+                script: script_data.name.to_owned(),
+                msg: e.to_string(),
+            })?;
+
+        // with bytecode compiled by the asset loader or shared compiled scripts,
+        // the script is loaded from its bytecode instead of its source
+        #[cfg(feature = "unsafe_lua_modules")]
+        let source = match bytecode {
+            Some(bytecode) => Ok(Cow::Borrowed(bytecode)),
+            None if self.share_compiled_scripts => self
+                .compiled
+                .get_or_compile(script_data.name, script, |script| {
+                    lua.load(script)
+                        .set_name(script_data.name)
+                        .and_then(|c| c.into_function())
+                        .map(|f| f.dump(false))
+                })
+                .map(Cow::Owned),
+            None => Ok(Cow::Borrowed(script)),
+        };
+        // safe lua states refuse to load bytecode
+        #[cfg(not(feature = "unsafe_lua_modules"))]
+        let source: LuaResult<Cow<[u8]>> = {
+            let _ = bytecode;
+            Ok(Cow::Borrowed(script))
+        };
+
+        source
+            .and_then(|source| {
+                self.modules.load_script(script_data.name, || {
+                    lua.load(source.as_ref())
+                        .set_name(script_data.name)
+                        .and_then(|c| c.exec())
+                })
+            })
+            .map_err(|e| match &e {
+                LuaError::SyntaxError { message, .. } => ScriptError::SyntaxError {
+                    script: script_data.name.to_owned(),
+                    line: error_line(&e, script_data.name),
+                    msg: message.clone(),
+                },
+                e => limits::limit_error(script_data.name, &self.limits, &e)
+                    .or_else(|| modules::import_error(&e))
+                    .unwrap_or_else(|| ScriptError::FailedToLoad {
+                        script: script_data.name.to_owned(),
+                    }),
+            })?;
+
+        let mut lua = Mutex::new(lua);
+
+        providers.attach_all(&mut lua)?;
+        Ok(lua)
+    }
+}
+
 impl<A: LuaArg> ScriptHost for LuaScriptHost<A> {
     type ScriptContext = Mutex<Lua>;
     type APITarget = Mutex<Lua>;
@@ -131,28 +223,66 @@ impl<A: LuaArg> ScriptHost for LuaScriptHost<A> {
             .add_system(script_hot_reload_handler::<Self>.in_base_set(set));
     }
 
+    fn register_with_app_in_schedule(
+        app: &mut App,
+        schedule: impl ScheduleLabel + Clone,
+        set: impl FreeSystemSet + Clone,
+    ) {
+        app.add_priority_event::<Self::ScriptEvent>()
+            .add_asset::<LuaFile>()
+            .init_asset_loader::<LuaLoader>()
+            .init_resource::<CachedScriptState<Self>>()
+            .init_resource::<ScriptContexts<Self::ScriptContext>>()
+            .init_resource::<APIProviders<Self>>()
+            .register_type::<ScriptCollection<Self::ScriptAsset>>()
+            .register_type::<Script<Self::ScriptAsset>>()
+            .register_type::<Handle<LuaFile>>()
+            // handle script insertions removal first
+            // then update their contexts later on script asset changes
+            .add_system(
+                script_add_synchronizer::<Self>
+                    .before(script_remove_synchronizer::<Self>)
+                    .in_set(set.clone())
+                    .in_schedule(schedule.clone()),
+            )
+            .add_system(
+                script_remove_synchronizer::<Self>
+                    .before(script_hot_reload_handler::<Self>)
+                    .in_set(set.clone())
+                    .in_schedule(schedule.clone()),
+            )
+            .add_system(
+                script_hot_reload_handler::<Self>
+                    .in_set(set)
+                    .in_schedule(schedule),
+            );
+    }
+
     fn load_script(
         &mut self,
         script: &[u8],
         script_data: &ScriptData,
         providers: &mut APIProviders<Self>,
     ) -> Result<Self::ScriptContext, ScriptError> {
-        #[cfg(feature = "unsafe_lua_modules")]
-        let lua = unsafe { Lua::unsafe_new() };
-        #[cfg(not(feature = "unsafe_lua_modules"))]
-        let lua = Lua::new();
-
-        lua.load(script)
-            .set_name(script_data.name)
-            .and_then(|c| c.exec())
-            .map_err(|_e| ScriptError::FailedToLoad {
-                script: script_data.name.to_owned(),
-            })?;
+        self.load_lua(script, None, script_data, providers)
+    }
 
-        let mut lua = Mutex::new(lua);
+    fn load_script_asset(
+        &mut self,
+        script: &Self::ScriptAsset,
+        script_data: &ScriptData,
+        providers: &mut APIProviders<Self>,
+    ) -> Result<Self::ScriptContext, ScriptError> {
+        self.load_lua(
+            &script.bytes,
+            script.bytecode.as_deref(),
+            script_data,
+            providers,
+        )
+    }
 
-        providers.attach_all(&mut lua)?;
-        Ok(lua)
+    fn modules(&self) -> Option<&ScriptModules> {
+        Some(&self.modules)
     }
 
     fn setup_script(
@@ -165,6 +295,33 @@ impl<A: LuaArg> ScriptHost for LuaScriptHost<A> {
         providers.setup_all(script_data, ctx)
     }
 
+    fn save_reload_state(
+        &self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<Option<ReloadState>, ScriptError> {
+        let ctx = ctx.get_mut().expect("Poison error in context");
+        limits::reset_instructions(ctx);
+        reload::save_state(ctx)
+            .map(|state| state.map(|s| Box::new(s) as ReloadState))
+            .map_err(|e| runtime_error(script_data, &self.limits, e))
+    }
+
+    fn load_reload_state(
+        &self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        state: ReloadState,
+    ) -> Result<(), ScriptError> {
+        let state = match state.downcast::<reload::ReloadValue>() {
+            Ok(state) => state,
+            Err(_) => return Ok(()),
+        };
+        let ctx = ctx.get_mut().expect("Poison error in context");
+        limits::reset_instructions(ctx);
+        reload::load_state(ctx, *state).map_err(|e| runtime_error(script_data, &self.limits, e))
+    }
+
     fn handle_events<'a>(
         &self,
         world: &mut World,
@@ -178,9 +335,13 @@ impl<A: LuaArg> ScriptHost for LuaScriptHost<A> {
         let world_ptr = unsafe { WorldPointer::new(world) };
 
         ctxs.for_each(|(script_data, ctx)| {
-            providers
-                .setup_runtime_all(world_ptr.clone(), &script_data, ctx)
-                .expect("Could not setup script runtime");
+            // a script which can't be set up is reported and skipped, the others still handle the events
+            if let Err(error) = providers.setup_runtime_all(world_ptr.clone(), &script_data, ctx) {
+                world_ptr
+                    .write()
+                    .send_event(ScriptErrorEvent::new(&script_data, error));
+                return;
+            }
 
             let ctx = ctx.get_mut().expect("Poison error in context");
 
@@ -191,7 +352,10 @@ impl<A: LuaArg> ScriptHost for LuaScriptHost<A> {
 
             for event in events {
                 // check if this script should handle this event
-                if !event.recipients().is_recipient(&script_data) {
+                if !event
+                    .recipients()
+                    .is_recipient(&script_data, &world_ptr.read())
+                {
                     continue;
                 }
 
@@ -200,22 +364,448 @@ impl<A: LuaArg> ScriptHost for LuaScriptHost<A> {
                     Err(_) => continue, // not subscribed to this event
                 };
 
+                limits::reset_instructions(ctx);
                 if let Err(error) = f.call::<_, ()>(event.args.clone()) {
                     let mut world = world_ptr.write();
                     let mut state: CachedScriptState<Self> = world.remove_resource().unwrap();
 
                     let (_, mut error_wrt, _) = state.event_state.get_mut(&mut world);
 
-                    let error = ScriptError::RuntimeError {
-                        script: script_data.name.to_owned(),
-                        msg: error.to_string(),
-                    };
-
-                    error!("{}", error);
-                    error_wrt.send(ScriptErrorEvent { error });
+                    let error = runtime_error(&script_data, &self.limits, error);
+                    error_wrt.send(ScriptErrorEvent::new(&script_data, error));
                     world.insert_resource(state);
                 }
             }
         });
     }
 }
+
+impl<A: LuaArg, In: LuaArg, Out: for<'lua> FromLuaMulti<'lua> + Send + 'static>
+    CallbackHost<In, Out> for LuaScriptHost<A>
+{
+    fn handle_callbacks<'a>(
+        &self,
+        world: &mut World,
+        events: &[CallbackEvent<In, Out>],
+        ctxs: impl Iterator<Item = (ScriptData<'a>, &'a mut Self::ScriptContext)>,
+        providers: &mut APIProviders<Self>,
+    ) {
+        // safety:
+        // - we have &mut World access
+        // - we do not use world_ptr after using the world reference which it's derived from
+        let world_ptr = unsafe { WorldPointer::new(world) };
+
+        ctxs.for_each(|(script_data, ctx)| {
+            // a script which can't be set up is reported and skipped, the others still handle the events
+            if let Err(error) = providers.setup_runtime_all(world_ptr.clone(), &script_data, ctx) {
+                world_ptr
+                    .write()
+                    .send_event(ScriptErrorEvent::new(&script_data, error));
+                return;
+            }
+
+            let ctx = ctx.get_mut().expect("Poison error in context");
+            let globals = ctx.globals();
+
+            for event in events {
+                if !event
+                    .recipients()
+                    .is_recipient(&script_data, &world_ptr.read())
+                {
+                    continue;
+                }
+
+                let f: Function = match globals.raw_get(event.hook_name.clone()) {
+                    Ok(f) => f,
+                    Err(_) => continue, // not subscribed to this event
+                };
+
+                limits::reset_instructions(ctx);
+                let value = f.call::<_, Out>(event.args.clone()).map_err(|error| {
+                    let error = runtime_error(&script_data, &self.limits, error);
+                    world_ptr
+                        .write()
+                        .send_event(ScriptErrorEvent::new(&script_data, error.clone()));
+                    error
+                });
+
+                event.response.push(ScriptResponse {
+                    sid: script_data.sid,
+                    script: script_data.name.to_owned(),
+                    value,
+                });
+            }
+        });
+    }
+}
+
+impl<A: LuaArg, Out: for<'lua> FromLuaMulti<'lua>> EvalHost<Out> for LuaScriptHost<A> {
+    fn eval_oneshot(&mut self, code: &str, world: &mut World) -> Result<Out, ScriptError> {
+        let script_data = ScriptData::oneshot();
+
+        world.resource_scope(|world, mut providers: Mut<APIProviders<Self>>| {
+            let mut ctx = self.load_script(&[], &script_data, &mut providers)?;
+            self.setup_script(&script_data, &mut ctx, &mut providers)?;
+
+            // safety:
+            // - we have &mut World access
+            // - we do not use world after creating the world pointer
+            let world_ptr = unsafe { WorldPointer::new(world) };
+            providers.setup_runtime_all(world_ptr, &script_data, &mut ctx)?;
+
+            let lua = ctx.get_mut().expect("Poison error in context");
+            limits::reset_instructions(lua);
+            lua.load(code)
+                .set_name(script_data.name)
+                .and_then(|c| c.eval::<Out>())
+                .map_err(|e| match &e {
+                    LuaError::SyntaxError { message, .. } => ScriptError::SyntaxError {
+                        script: script_data.name.to_owned(),
+                        line: error_line(&e, script_data.name),
+                        msg: message.clone(),
+                    },
+                    e => runtime_error(&script_data, &self.limits, e),
+                })
+        })
+    }
+}
+
+/// Converts an error raised while running a script into a `ScriptError::RuntimeError`,
+/// or a `ScriptError::LimitExceeded` if the script exceeded one of the given limits
+fn runtime_error(script_data: &ScriptData, limits: &ScriptLimits, error: LuaError) -> ScriptError {
+    if let Some(error) = limits::limit_error(script_data.name, limits, &error) {
+        return error;
+    }
+
+    ScriptError::RuntimeError {
+        script: script_data.name.to_owned(),
+        line: error_line(&error, script_data.name),
+        msg: error.to_string(),
+    }
+}
+
+/// The line of the chunk with the given name an error was raised at.
+///
+/// Lua errors carry no position of their own, so it is read from the position lua prefixes syntax and runtime errors
+/// with, or from the traceback of failed callbacks, only ever taking a position in the given chunk.
+/// Positions of other chunks, i.e. of modules or of errors nested in the message, are skipped
+fn error_line(error: &LuaError, chunk: &str) -> Option<usize> {
+    match error {
+        LuaError::SyntaxError { message, .. } | LuaError::RuntimeError(message) => {
+            chunk_line(message, chunk)
+        }
+        LuaError::CallbackError { traceback, cause } => {
+            chunk_line(traceback, chunk).or_else(|| error_line(cause, chunk))
+        }
+        _ => None,
+    }
+}
+
+/// Finds the first position `chunk:line:` in the given text, where lua writes the chunk as `[string "name"]`
+fn chunk_line(text: &str, chunk: &str) -> Option<usize> {
+    text.match_indices(chunk).find_map(|(start, _)| {
+        let position = &text[start + chunk.len()..];
+        let position = position.strip_prefix("\"]").unwrap_or(position);
+        let (line, _) = position.strip_prefix(':')?.split_once(':')?;
+        line.parse().ok()
+    })
+}
+
+/// Constructs [`ScriptError`]s from lua errors, keeping the original error and the traceback of failed callbacks
+pub trait LuaScriptError {
+    fn from_lua(error: LuaError) -> Self;
+}
+
+impl LuaScriptError for ScriptError {
+    fn from_lua(error: LuaError) -> Self {
+        let traceback = match &error {
+            LuaError::CallbackError { traceback, .. } => Some(traceback.clone()),
+            _ => None,
+        };
+        ScriptError::new_language_error("Lua", error, traceback)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::ecs::system::SystemState;
+    use std::path::Path;
+
+    type Host = LuaScriptHost<()>;
+
+    /// Sets up an app handling `damage(integer) -> integer` callbacks, with the given scripts attached to a single entity
+    fn app_with_scripts(scripts: &[(&str, &str)]) -> App {
+        app_with_limits(ScriptLimits::default(), scripts)
+    }
+
+    /// Like [`app_with_scripts`], with the given limits set on the host before loading the scripts
+    fn app_with_limits(limits: ScriptLimits, scripts: &[(&str, &str)]) -> App {
+        let mut app = App::new();
+        app.add_plugin(ScriptingPlugin)
+            .init_resource::<Host>()
+            .init_resource::<APIProviders<Host>>()
+            .init_resource::<ScriptContexts<Mutex<Lua>>>()
+            .add_callback_handler_to_base_set::<Host, (i64,), i64, _, 0, 0>(CoreSet::Update);
+
+        app.world.resource_mut::<Host>().limits = limits;
+        let entity = app.world.spawn_empty().id();
+        app.world.resource_scope(|world, mut host: Mut<Host>| {
+            world.resource_scope(|world, mut providers: Mut<APIProviders<Host>>| {
+                let mut contexts = world.resource_mut::<ScriptContexts<Mutex<Lua>>>();
+                for (sid, (name, code)) in scripts.iter().enumerate() {
+                    let data = ScriptData {
+                        sid: sid as u32,
+                        entity,
+                        name,
+                    };
+                    let ctx = host
+                        .load_script(code.as_bytes(), &data, &mut providers)
+                        .unwrap();
+                    contexts.insert_context(data, Some(ctx));
+                }
+            })
+        });
+        app
+    }
+
+    /// Exposes the id of the running script to it as `sid`
+    struct ScriptIdAPI;
+
+    impl APIProvider for ScriptIdAPI {
+        type APITarget = Mutex<Lua>;
+        type ScriptContext = Mutex<Lua>;
+        type DocTarget = LuaDocFragment;
+
+        fn attach_api(&mut self, _: &mut Mutex<Lua>) -> Result<(), ScriptError> {
+            Ok(())
+        }
+
+        fn setup_script_runtime(
+            &mut self,
+            _: WorldPointer,
+            script_data: &ScriptData,
+            ctx: &mut Mutex<Lua>,
+        ) -> Result<(), ScriptError> {
+            ctx.get_mut()
+                .expect("Poison error in context")
+                .globals()
+                .set("sid", script_data.sid)
+                .map_err(ScriptError::from_lua)
+        }
+    }
+
+    fn send_callback(app: &mut App, args: (i64,)) -> CallbackResponse<i64> {
+        let response = CallbackResponse::new();
+        let mut state =
+            SystemState::<PriorityEventWriter<CallbackEvent<(i64,), i64>>>::new(&mut app.world);
+        state.get_mut(&mut app.world).send(
+            CallbackEvent {
+                hook_name: "damage".to_owned(),
+                args,
+                recipients: Recipients::All,
+                response: response.clone(),
+            },
+            0,
+        );
+        response
+    }
+
+    #[test]
+    fn callbacks_return_values_of_each_script() {
+        let mut app = app_with_scripts(&[
+            ("a.lua", "function damage(x) return x * 2 end"),
+            ("b.lua", "function heal(x) return x end"),
+            ("c.lua", r#"function damage(x) error("no damage") end"#),
+            ("d.lua", "function damage(x) return x + 1 end"),
+        ]);
+
+        let response = send_callback(&mut app, (2,));
+        assert!(response.take().is_empty());
+        app.update();
+
+        let responses = response.take();
+        assert_eq!(
+            responses
+                .iter()
+                .map(|r| r.script.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a.lua", "c.lua", "d.lua"]
+        );
+        assert_eq!(responses[0].value.as_ref().unwrap(), &4);
+        assert!(matches!(
+            responses[1].value,
+            Err(ScriptError::RuntimeError { .. })
+        ));
+        assert_eq!(responses[2].value.as_ref().unwrap(), &3);
+        assert_eq!(app.world.resource::<Events<ScriptErrorEvent>>().len(), 1);
+
+        // values are only collected once
+        assert!(response.take().is_empty());
+        let response = send_callback(&mut app, (5,));
+        app.update();
+        assert_eq!(response.take_values(), vec![10, 6]);
+    }
+
+    #[test]
+    fn precompiled_scripts_are_loaded() {
+        let loader = LuaLoader { precompile: true };
+        let file = loader
+            .lua_file(Path::new("a.lua"), b"function damage(x) return x * 3 end")
+            .unwrap();
+        assert!(file.bytecode.is_some());
+        // syntax errors fail the asset load
+        let error = loader
+            .lua_file(Path::new("b.lua"), b"function damage(x)")
+            .unwrap_err();
+        assert!(error.to_string().contains("b.lua"), "{error}");
+
+        let mut host = Host::default();
+        let data = ScriptData {
+            sid: 0,
+            entity: Entity::PLACEHOLDER,
+            name: "a.lua",
+        };
+        let ctx = host
+            .load_script_asset(&file, &data, &mut APIProviders::default())
+            .unwrap();
+        let damage: i64 = ctx.lock().unwrap().load("return damage(2)").eval().unwrap();
+        assert_eq!(damage, 6);
+    }
+
+    #[test]
+    fn scripts_exceeding_their_limits_are_interrupted() {
+        let limits = ScriptLimits {
+            max_instructions: Some(1_000_000),
+            max_memory: Some(1 << 20),
+            ..Default::default()
+        };
+        let mut app = app_with_limits(
+            limits,
+            &[
+                ("a.lua", "function damage(x) while true do end end"),
+                (
+                    "b.lua",
+                    "function damage(x) local t = {} for i = 1, x do t[i] = string.rep('a', 1024) .. i end return x end",
+                ),
+                ("c.lua", "function damage(x) return x end"),
+            ],
+        );
+
+        let response = send_callback(&mut app, (2000,));
+        app.update();
+        let responses = response.take();
+        let exceeded = responses
+            .iter()
+            .map(|r| match &r.value {
+                Err(ScriptError::LimitExceeded { script, limit }) => {
+                    assert_eq!(script, &r.script);
+                    Some(limit.as_str())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            exceeded,
+            vec![
+                Some("1000000 instructions"),
+                Some("1048576 bytes of memory"),
+                None
+            ]
+        );
+        assert_eq!(responses[2].value.as_ref().unwrap(), &2000);
+
+        // the scripts stay loaded and handle later events within their limits
+        let response = send_callback(&mut app, (2,));
+        app.update();
+        assert_eq!(response.take_values(), vec![2, 2]);
+    }
+
+    #[test]
+    fn reload_hooks_cannot_access_the_world() {
+        let mut host = Host::default();
+        let data = ScriptData {
+            sid: 0,
+            entity: Entity::PLACEHOLDER,
+            name: "a.lua",
+        };
+        let mut old = host
+            .load_script(
+                b"function on_reload_save() return { world = world } end",
+                &data,
+                &mut APIProviders::default(),
+            )
+            .unwrap();
+        let mut new = host
+            .load_script(
+                b"function on_reload_load(state) seen = world or state.world or 'nothing' end",
+                &data,
+                &mut APIProviders::default(),
+            )
+            .unwrap();
+        for ctx in [&mut old, &mut new] {
+            let lua = ctx.get_mut().unwrap();
+            lua.globals().set("world", "the world").unwrap();
+        }
+
+        let state = host.save_reload_state(&data, &mut old).unwrap().unwrap();
+        host.load_reload_state(&data, &mut new, state).unwrap();
+        let globals = new.get_mut().unwrap().globals();
+        assert_eq!(globals.get::<_, String>("seen").unwrap(), "nothing");
+        // the world is available again afterwards
+        assert_eq!(globals.get::<_, String>("world").unwrap(), "the world");
+        let globals = old.get_mut().unwrap().globals();
+        assert_eq!(globals.get::<_, String>("world").unwrap(), "the world");
+    }
+
+    #[test]
+    fn oneshots_get_their_own_script_ids() {
+        let mut world = World::new();
+        let mut providers = APIProviders::<Host>::default();
+        providers.providers.push(Box::new(ScriptIdAPI));
+        world.insert_resource(providers);
+
+        let mut host = Host::default();
+        let first: u32 = host.eval_oneshot("return sid", &mut world).unwrap();
+        let second: u32 = host.eval_oneshot("return sid", &mut world).unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn errors_report_the_line_of_the_script_they_were_raised_at() {
+        let lua = Lua::new();
+        lua.globals()
+            .set(
+                "fail",
+                lua.create_function(|_, ()| {
+                    Err::<(), _>(LuaError::RuntimeError("other.lua:7: failed".to_owned()))
+                })
+                .unwrap(),
+            )
+            .unwrap();
+        let line = |code: &str| {
+            let error = lua
+                .load(code)
+                .set_name("script.lua")
+                .and_then(|c| c.exec())
+                .unwrap_err();
+            error_line(&error, "script.lua")
+        };
+
+        // syntax errors
+        assert_eq!(line("local a = 1\nlocal = 2"), Some(2));
+        // positions of other chunks in the message are skipped
+        assert_eq!(line("\n\nerror('other.lua:7: failed')"), Some(3));
+        assert_eq!(line("error('multi\\nline 5: error')"), Some(1));
+        // failed callbacks are positioned by their traceback
+        assert_eq!(line("\n\n\nfail()"), Some(4));
+        // errors raised outside of the script have no line in it
+        assert_eq!(
+            error_line(
+                &LuaError::RuntimeError("script.lua".to_owned()),
+                "script.lua"
+            ),
+            None
+        );
+    }
+}
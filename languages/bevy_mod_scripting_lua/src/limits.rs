@@ -0,0 +1,73 @@
+//! Enforcing the [`ScriptLimits`] of the host in lua states.
+//!
+//! The memory limit covers everything allocated by a lua state. Instructions are counted by a debug hook running every
+//! [`ScriptLimits::INSTRUCTION_STEP`] instructions, the count is reset before each call into the state. Once exceeded
+//! the hook keeps raising the error on every step, so scripts can't recover from it with `pcall`.
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use bevy_mod_scripting_core::prelude::*;
+use tealr::mlu::mlua::{prelude::*, HookTriggers};
+
+/// The instructions executed by the current call into a lua state, stored as app data on the state
+struct InstructionCount(Arc<AtomicU64>);
+
+/// Applies the given limits to the lua state of the given script
+pub(crate) fn apply_limits(lua: &Lua, script: &str, limits: &ScriptLimits) -> LuaResult<()> {
+    if let Some(max_memory) = limits.max_memory {
+        lua.set_memory_limit(max_memory)?;
+    }
+
+    if let Some(max_instructions) = limits.max_instructions {
+        let count = Arc::new(AtomicU64::new(0));
+        lua.set_app_data(InstructionCount(count.clone()));
+
+        let step = ScriptLimits::INSTRUCTION_STEP.min(max_instructions).max(1);
+        let script = script.to_owned();
+        lua.set_hook(
+            HookTriggers {
+                every_nth_instruction: Some(step as u32),
+                ..Default::default()
+            },
+            move |_, _| {
+                if count.fetch_add(step, Ordering::Relaxed) + step > max_instructions {
+                    return Err(LuaError::external(ScriptLimits::exceeded(
+                        &script,
+                        format!("{max_instructions} instructions"),
+                    )));
+                }
+                Ok(())
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Resets the instructions counted against the limit, called before each call into the lua state
+pub(crate) fn reset_instructions(lua: &Lua) {
+    if let Some(count) = lua.app_data_ref::<InstructionCount>() {
+        count.0.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Finds the limit the given script exceeded, if the given error was caused by one
+pub(crate) fn limit_error(
+    script: &str,
+    limits: &ScriptLimits,
+    error: &LuaError,
+) -> Option<ScriptError> {
+    match error {
+        LuaError::MemoryError(_) => limits.max_memory.map(|max_memory| {
+            ScriptLimits::exceeded(script, format!("{max_memory} bytes of memory"))
+        }),
+        LuaError::ExternalError(e) => e
+            .downcast_ref::<ScriptError>()
+            .filter(|e| matches!(e, ScriptError::LimitExceeded { .. }))
+            .cloned(),
+        LuaError::CallbackError { cause, .. } => limit_error(script, limits, cause),
+        _ => None,
+    }
+}
@@ -0,0 +1,114 @@
+//! A sandboxed `require`, loading modules from script assets instead of the file system.
+//!
+//! `require("scripts.util")` runs the module at the asset path `scripts/util.lua` once per lua state and returns
+//! whatever it returned. Modules should be required at the top level of a script, so that the script is reloaded
+//! whenever one of its modules is (see [`ScriptModules`]).
+//!
+//! Modules lua knows about itself, i.e. the standard libraries or modules added to `package.preload` by API providers,
+//! are still required through lua's own `require`.
+use bevy_mod_scripting_core::prelude::*;
+use tealr::mlu::mlua::{prelude::*, Function, Table, Value};
+
+use crate::error_line;
+
+/// The registry key of the table holding the value of every module already required in a lua state
+const LOADED_MODULES: &str = "bevy_mod_scripting.loaded_modules";
+
+/// The registry key of lua's own `require`, replaced by the one resolving script assets
+const LUA_REQUIRE: &str = "bevy_mod_scripting.lua_require";
+
+/// The asset path of the module with the given name, i.e. `scripts.util` -> `scripts/util.lua`
+fn module_path(name: &str) -> String {
+    format!("{}.lua", name.replace('.', "/"))
+}
+
+/// Finds the script error raised by a failed import, if the given error was caused by one
+pub(crate) fn import_error(error: &LuaError) -> Option<ScriptError> {
+    match error {
+        LuaError::ExternalError(e) => e.downcast_ref::<ScriptError>().cloned(),
+        LuaError::CallbackError { cause, .. } => import_error(cause),
+        _ => None,
+    }
+}
+
+/// Returns true if lua's own `require` knows the module with the given name, being loaded or preloaded already
+fn is_lua_module(lua: &Lua, name: &str) -> LuaResult<bool> {
+    let package: Option<Table> = lua.globals().get("package")?;
+    for table in ["loaded", "preload"] {
+        let modules: Option<Table> = match &package {
+            Some(package) => package.get(table)?,
+            None => None,
+        };
+        if let Some(modules) = modules {
+            if !matches!(modules.raw_get::<_, Value>(name)?, Value::Nil) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Converts an error raised while running the module at the given path into a `ScriptError`,
+/// keeping the errors raised by modules it imports itself
+fn module_error(path: &str, error: LuaError) -> ScriptError {
+    if let Some(e) = import_error(&error) {
+        return e;
+    }
+
+    match &error {
+        LuaError::SyntaxError { message, .. } => ScriptError::SyntaxError {
+            script: path.to_owned(),
+            line: error_line(&error, path),
+            msg: message.clone(),
+        },
+        _ => ScriptError::RuntimeError {
+            script: path.to_owned(),
+            line: error_line(&error, path),
+            msg: error.to_string(),
+        },
+    }
+}
+
+/// Replaces the global `require` with one resolving modules against the given script modules,
+/// falling back to the original `require` for the modules lua knows about
+pub(crate) fn attach_require_api(lua: &Lua, modules: ScriptModules) -> LuaResult<()> {
+    lua.set_named_registry_value(LOADED_MODULES, lua.create_table()?)?;
+    let lua_require: Option<Function> = lua.globals().get("require")?;
+    lua.set_named_registry_value(LUA_REQUIRE, lua_require)?;
+
+    let require = lua.create_function(move |lua, name: String| {
+        let lua_require: Option<Function> = lua.named_registry_value(LUA_REQUIRE)?;
+        if let Some(lua_require) = lua_require {
+            if is_lua_module(lua, &name)? {
+                return lua_require.call::<_, Value>(name);
+            }
+        }
+
+        let path = module_path(&name);
+        let loaded: Table = lua.named_registry_value(LOADED_MODULES)?;
+
+        let value: Value = loaded.raw_get(path.as_str())?;
+        if !matches!(value, Value::Nil) {
+            return Ok(value);
+        }
+
+        let value = modules
+            .import(&path, |source| {
+                lua.load(source)
+                    .set_name(&path)
+                    .and_then(|c| c.call::<_, Value>(()))
+            })
+            .map_err(LuaError::external)?
+            .map_err(|e| LuaError::external(module_error(&path, e)))?;
+
+        // like lua's own require, modules returning nothing are marked as loaded with `true`
+        let value = match value {
+            Value::Nil => Value::Boolean(true),
+            v => v,
+        };
+        loaded.raw_set(path.as_str(), value.clone())?;
+        Ok(value)
+    })?;
+
+    lua.globals().set("require", require)
+}
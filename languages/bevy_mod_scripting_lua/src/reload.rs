@@ -0,0 +1,119 @@
+//! Keeping script state across hot reloads.
+//!
+//! Before a script is reloaded its `on_reload_save()` function is called, the returned value is copied
+//! out of the old lua state and passed to `on_reload_load(state)` in the new one.
+//! Only nil, booleans, numbers, strings and tables of those can be kept, cyclic or too deeply nested tables are refused.
+//! Both functions run with the `world` global set to nil, since the world can't be accessed while scripts are reloaded.
+use tealr::mlu::mlua::{prelude::*, FromLuaMulti, Function, ToLuaMulti, Value};
+
+/// How deeply tables can be nested in the kept state, deeper tables are most likely cyclic
+const MAX_DEPTH: usize = 64;
+
+/// A lua value copied out of a lua state so it can outlive it
+#[derive(Debug, Clone)]
+pub(crate) enum ReloadValue {
+    Nil,
+    Boolean(bool),
+    Integer(LuaInteger),
+    Number(LuaNumber),
+    String(Vec<u8>),
+    Table(Vec<(ReloadValue, ReloadValue)>),
+}
+
+impl ReloadValue {
+    /// Copies the value out of its lua state, failing on tables nested deeper than [`MAX_DEPTH`]
+    fn from_value(value: Value) -> LuaResult<Self> {
+        Self::from_value_at(value, 0)
+    }
+
+    fn from_value_at(value: Value, depth: usize) -> LuaResult<Self> {
+        Ok(match value {
+            Value::Nil => Self::Nil,
+            Value::Boolean(b) => Self::Boolean(b),
+            Value::Integer(i) => Self::Integer(i),
+            Value::Number(n) => Self::Number(n),
+            Value::String(s) => Self::String(s.as_bytes().to_vec()),
+            Value::Table(_) if depth >= MAX_DEPTH => {
+                return Err(LuaError::FromLuaConversionError {
+                    from: "table",
+                    to: "reload state",
+                    message: Some(format!(
+                        "tables nested deeper than {MAX_DEPTH} levels cannot be kept across reloads, the table may contain a cycle"
+                    )),
+                })
+            }
+            Value::Table(t) => Self::Table(
+                t.pairs::<Value, Value>()
+                    .map(|pair| {
+                        let (k, v) = pair?;
+                        Ok((
+                            Self::from_value_at(k, depth + 1)?,
+                            Self::from_value_at(v, depth + 1)?,
+                        ))
+                    })
+                    .collect::<LuaResult<_>>()?,
+            ),
+            other => return Err(LuaError::FromLuaConversionError {
+                from: other.type_name(),
+                to: "reload state",
+                message: Some(
+                    "only nil, booleans, numbers, strings and tables can be kept across reloads"
+                        .to_owned(),
+                ),
+            }),
+        })
+    }
+
+    fn into_value(self, lua: &Lua) -> LuaResult<Value> {
+        Ok(match self {
+            Self::Nil => Value::Nil,
+            Self::Boolean(b) => Value::Boolean(b),
+            Self::Integer(i) => Value::Integer(i),
+            Self::Number(n) => Value::Number(n),
+            Self::String(s) => Value::String(lua.create_string(&s)?),
+            Self::Table(pairs) => {
+                let table = lua.create_table()?;
+                for (k, v) in pairs {
+                    table.raw_set(k.into_value(lua)?, v.into_value(lua)?)?;
+                }
+                Value::Table(table)
+            }
+        })
+    }
+}
+
+/// Calls `on_reload_save` if the script defines it and copies out the returned state
+pub(crate) fn save_state(lua: &Lua) -> LuaResult<Option<ReloadValue>> {
+    let f: Function = match lua.globals().raw_get("on_reload_save") {
+        Ok(f) => f,
+        Err(_) => return Ok(None),
+    };
+
+    call_without_world::<_, Value>(lua, f, ())
+        .and_then(ReloadValue::from_value)
+        .map(Some)
+}
+
+/// Calls `on_reload_load` with the saved state if the script defines it
+pub(crate) fn load_state(lua: &Lua, state: ReloadValue) -> LuaResult<()> {
+    let f: Function = match lua.globals().raw_get("on_reload_load") {
+        Ok(f) => f,
+        Err(_) => return Ok(()),
+    };
+
+    call_without_world(lua, f, state.into_value(lua)?)
+}
+
+/// Calls the given function with the `world` global hidden from it
+fn call_without_world<'lua, A: ToLuaMulti<'lua>, R: FromLuaMulti<'lua>>(
+    lua: &'lua Lua,
+    f: Function<'lua>,
+    args: A,
+) -> LuaResult<R> {
+    let globals = lua.globals();
+    let world: Value = globals.raw_get("world")?;
+    globals.raw_set("world", Value::Nil)?;
+    let result = f.call(args);
+    globals.raw_set("world", world)?;
+    result
+}
@@ -1,10 +1,13 @@
 use std::iter::once;
 
 use bevy_mod_scripting_common::{
-    arg::SimpleType, derive_flag::DeriveFlag, newtype::Newtype, utils::EmptyToken,
+    arg::{SimpleType, NUMERIC_TYPES},
+    derive_flag::DeriveFlag,
+    newtype::Newtype,
+    utils::EmptyToken,
 };
 use proc_macro2::Span;
-use quote::{format_ident, quote_spanned};
+use quote::{format_ident, quote_spanned, ToTokens};
 use syn::{parse_quote_spanned, punctuated::Punctuated, spanned::Spanned, LitInt, Token};
 
 use crate::lua_method::LuaMethod;
@@ -23,6 +26,10 @@ pub(crate) fn make_methods<'a>(flag: &DeriveFlag, new_type: &'a Newtype, out: &m
         // first go through each parameter and remember identifiers + types of each
         let mut parameter_identifiers = Vec::default();
         let mut parameter_types = Vec::default();
+        // statements validating named numeric parameters before the call
+        let mut validators = Vec::default();
+        // statements raising the errors of script callbacks after the call
+        let mut callback_errors = Vec::default();
 
         let parameters : Punctuated<proc_macro2::TokenStream,Token![,]> = m.args.iter()
             .enumerate()
@@ -33,19 +40,226 @@ pub(crate) fn make_methods<'a>(flag: &DeriveFlag, new_type: &'a Newtype, out: &m
                 // store the identifier and type
                 parameter_identifiers.push(lit.clone());
 
+                // conversion errors name the parameter, or give its position if it has no name
+                let arg_name = arg_type.validated_name().map_or_else(|| (idx + 1).to_string(), ToString::to_string);
+
+                // callbacks are received as lua functions and adapted into closures, which can only be called during the method call.
+                // Once a call fails the closure returns defaults without calling the script again, and the error is raised after the method returns
+                if let Some((callback_args, callback_out)) = arg_type.callback() {
+                    parameter_types.push(quote_spanned!{m.span()=>
+                        bevy_mod_scripting_lua::tealr::mlu::mlua::Function
+                    });
+                    let error = format_ident!("{lit}_error");
+                    let callback_params: Vec<_> = (0..callback_args.len()).map(|i| format_ident!("{lit}_{i}")).collect();
+                    let callback_param_types = callback_args.iter()
+                        .map(|a| a.type_or_resolve(|| SimpleType::BaseIdent(wrapped_type.clone())).into_owned());
+                    let callback_values = callback_args.iter().zip(&callback_params).map(|(a, p)| {
+                        let resolved_type = a.type_or_resolve(|| SimpleType::BaseIdent(wrapped_type.clone()));
+                        match (a.is_wrapped(), a.is_any_ref()) {
+                            (true, true) => {
+                                let wrapper = format_ident!("Lua{}", resolved_type.base_ident());
+                                quote_spanned!(m.span()=>#wrapper::new(#p.clone()))
+                            }
+                            (true, false) => {
+                                let wrapper = format_ident!("Lua{}", resolved_type.base_ident());
+                                quote_spanned!(m.span()=>#wrapper::new(#p))
+                            }
+                            (false, true) => quote_spanned!(m.span()=>#p.to_owned()),
+                            (false, false) => quote_spanned!(m.span()=>#p),
+                        }
+                    });
+                    let (out_type, lua_out_type, from_lua) = match callback_out {
+                        Some(out) => {
+                            let out_type = out.type_or_resolve(|| SimpleType::BaseIdent(wrapped_type.clone())).into_owned();
+                            if out.is_wrapped() {
+                                let wrapper = format_ident!("Lua{}", out_type.base_ident());
+                                (out_type.into_token_stream(), quote_spanned!(m.span()=>#wrapper), quote_spanned!(m.span()=>.and_then(|v| Ok(v.inner()?))))
+                            } else {
+                                (out_type.to_token_stream(), out_type.into_token_stream(), Default::default())
+                            }
+                        }
+                        None => (quote_spanned!(m.span()=>()), quote_spanned!(m.span()=>()), Default::default()),
+                    };
+                    validators.push(quote_spanned!{m.span()=>
+                        let #error = std::cell::RefCell::new(None);
+                        let #lit = |#(#callback_params: #callback_param_types),*| -> #out_type {
+                            if #error.borrow().is_some() {
+                                return Default::default();
+                            }
+                            match #lit.call::<_, #lua_out_type>((#(#callback_values,)*))#from_lua {
+                                Ok(v) => v,
+                                Err(e) => {
+                                    *#error.borrow_mut() = Some(e);
+                                    Default::default()
+                                }
+                            }
+                        };
+                    });
+                    callback_errors.push(quote_spanned!{m.span()=>
+                        if let Some(e) = #error.into_inner() {
+                            return Err(e);
+                        }
+                    });
+                    return quote_spanned!{m.span()=>
+                        #lit
+                    };
+                }
+
                 // the paramter type must be stripped of outermost references
                 // and also a prefix for wrapper types must be addded
                 let mut resolved_parameter_type = arg_type.type_or_resolve(|| SimpleType::BaseIdent(wrapped_type.clone())).into_owned();
                 if arg_type.is_wrapped() || arg_type.is_self() {
                     resolved_parameter_type.mutate_base_ident(|ident| *ident = format_ident!("Lua{ident}"));
                 }
-                parameter_types.push(resolved_parameter_type.strip_outer_refs());
+                let resolved_parameter_type = resolved_parameter_type.strip_outer_refs();
+
+                // reflected parameters are received as reflected values and copied out into the expected type,
+                // which is inferred from the method call
+                if arg_type.is_reflected() {
+                    parameter_types.push(quote_spanned!{m.span()=>
+                        bevy_script_api::script_ref::ReflectedValue
+                    });
+                    validators.push(quote_spanned!{m.span()=>
+                        let #lit = bevy_script_api::lua::conversion::ConversionContext::in_argument(
+                            #lit.to_value().map_err(bevy_mod_scripting_lua::tealr::mlu::mlua::Error::from),
+                            #arg_name,
+                        )?;
+                    });
+                    return if arg_type.is_any_ref() {
+                        quote_spanned!{m.span()=>
+                            &#lit
+                        }
+                    } else {
+                        quote_spanned!{m.span()=>
+                            #lit
+                        }
+                    };
+                }
+
+                // strings are received as lua strings and borrowed as `&str` without copying them,
+                // a copy is only made for methods expecting an owned string
+                if arg_type.is_raw() && arg_type.is_string() {
+                    parameter_types.push(quote_spanned!{m.span()=>
+                        bevy_mod_scripting_lua::tealr::mlu::mlua::String
+                    });
+                    let borrowed = quote_spanned!{m.span()=>
+                        bevy_script_api::lua::conversion::ConversionContext::in_argument(#lit.to_str(), #arg_name)?
+                    };
+                    return if resolved_parameter_type.is_cow() {
+                        quote_spanned!{m.span()=>
+                            std::borrow::Cow::Owned(#borrowed.to_owned())
+                        }
+                    } else if resolved_parameter_type.base_ident() == "str" {
+                        borrowed
+                    } else if arg_type.is_any_ref() {
+                        quote_spanned!{m.span()=>
+                            &#borrowed.to_owned()
+                        }
+                    } else {
+                        quote_spanned!{m.span()=>
+                            #borrowed.to_owned()
+                        }
+                    };
+                }
+
+                // named numeric parameters are received as any lua value and converted by hand,
+                // so that errors can name the parameter
+                match arg_type.validated_name() {
+                    Some(_) if !resolved_parameter_type.is_option() && NUMERIC_TYPES.contains(&resolved_parameter_type.base_ident().to_string().as_str()) => {
+                        validators.push(quote_spanned!{m.span()=>
+                            let #lit : #resolved_parameter_type = bevy_script_api::lua::conversion::ConversionContext::in_argument(
+                                match #lit {
+                                    bevy_mod_scripting_lua::tealr::mlu::mlua::Value::Integer(_)
+                                    | bevy_mod_scripting_lua::tealr::mlu::mlua::Value::Number(_) => {
+                                        bevy_mod_scripting_lua::tealr::mlu::mlua::FromLua::from_lua(#lit, lua)
+                                    },
+                                    v => Err(bevy_mod_scripting_lua::tealr::mlu::mlua::Error::FromLuaConversionError {
+                                        from: v.type_name(),
+                                        to: "number",
+                                        message: None,
+                                    }),
+                                },
+                                #arg_name,
+                            )?;
+                        });
+                        parameter_types.push(quote_spanned!{m.span()=>
+                            bevy_mod_scripting_lua::tealr::mlu::mlua::Value
+                        });
+                    }
+                    // slices are received as vectors, collected from lua tables
+                    _ if resolved_parameter_type.is_slice() => {
+                        let element_type = resolved_parameter_type.slice_element().unwrap();
+                        parameter_types.push(quote_spanned!{m.span()=>
+                            Vec<#element_type>
+                        });
+                    }
+                    // arrays are received as vectors too, and converted after their length is checked
+                    _ if resolved_parameter_type.is_array() => {
+                        let (element_type, len) = resolved_parameter_type.array_element().unwrap();
+                        parameter_types.push(quote_spanned!{m.span()=>
+                            Vec<#element_type>
+                        });
+                        let elements = if arg_type.is_wrapped() {
+                            quote_spanned!{m.span()=>
+                                #lit.into_iter().map(|v| v.inner()).collect::<Result<Vec<_>,_>>()?
+                            }
+                        } else {
+                            quote_spanned!{m.span()=>
+                                #lit
+                            }
+                        };
+                        validators.push(quote_spanned!{m.span()=>
+                            let #lit : [_; #len] = bevy_script_api::lua::conversion::ConversionContext::in_argument(
+                                #elements.try_into().map_err(|v: Vec<_>|
+                                    bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(
+                                        format!("expected an array of length {}, got {}", #len, v.len())
+                                    )
+                                ),
+                                #arg_name,
+                            )?;
+                        });
+                    }
+                    _ => parameter_types.push(resolved_parameter_type.into_token_stream()),
+                }
 
                 // finally produce an expression to be used as parameter to the method/function call
-                if (arg_type.is_wrapped() || arg_type.is_self()) && !arg_type.is_any_ref(){
+                if arg_type.is_wrapped() && arg_type.is_slice() {
+                    quote_spanned!{m.span()=>
+                        &#lit.into_iter().map(|v| v.inner()).collect::<Result<Vec<_>,_>>()?
+                    }
+                } else if arg_type.is_slice() {
+                    quote_spanned!{m.span()=>
+                        &#lit
+                    }
+                } else if arg_type.is_array() {
+                    quote_spanned!{m.span()=>
+                        #lit
+                    }
+                } else if arg_type.is_wrapped() && arg_type.is_option() {
+                    quote_spanned!{m.span()=>
+                        #lit.map(|v| v.inner()).transpose()?
+                    }
+                } else if arg_type.is_wrapped() && arg_type.is_vec() {
+                    quote_spanned!{m.span()=>
+                        #lit.into_iter().map(|v| v.inner()).collect::<Result<Vec<_>,_>>()?
+                    }
+                } else if arg_type.is_wrapped() && arg_type.is_map() {
+                    quote_spanned!{m.span()=>
+                        #lit.into_iter().map(|(k, v)| v.inner().map(|v| (k, v))).collect::<Result<_,_>>()?
+                    }
+                } else if arg_type.is_map() {
+                    // collected into the map type of the method, which need not be the one exchanged with scripts
+                    quote_spanned!{m.span()=>
+                        #lit.into_iter().collect()
+                    }
+                } else if (arg_type.is_wrapped() || arg_type.is_self()) && !arg_type.is_any_ref(){
                     quote_spanned!{m.span()=>
                         #lit.inner()?
                     }
+                } else if arg_type.is_raw() && arg_type.is_any_ref() {
+                    quote_spanned!{m.span()=>
+                        &#lit
+                    }
                 } else {
                     quote_spanned!{m.span()=>
                         #lit
@@ -57,29 +271,54 @@ pub(crate) fn make_methods<'a>(flag: &DeriveFlag, new_type: &'a Newtype, out: &m
         // create the function call, either static or from the receiver.
         let method_identifier = &m.ident;
         let base_ident = &new_type.args.base_type_ident;
+        // generic methods are called with the type arguments given by `#[type_args(..)]`
+        let type_args = &m.parsed_attrs.type_args;
+        let method_identifier = if type_args.is_empty() {
+            quote_spanned!(m.span()=>#method_identifier)
+        } else {
+            quote_spanned!(m.span()=>#method_identifier::<#(#type_args),*>)
+        };
 
         let receiver_argument_identifier = format_ident!("s");
         let static_;
         let fn_;
         let mut_;
         let mut body;
+        // trait methods which clash with other methods of the same name are called through the trait
+        let qualified = m.parsed_attrs.qualified.as_ref();
         if let Some((self_,_)) = &m.self_ {
-            if self_.is_any_ref(){
-                body = quote_spanned!(m.span()=>#receiver_argument_identifier.#method_identifier(#parameters));
-                if self_.is_mut_ref(){
-                    mut_ = Some(Token![mut](Span::call_site()));
-                } else {
-                    mut_ = None;
-                }
-            } else{
-                body = quote_spanned!(m.span()=>#receiver_argument_identifier.inner()?.#method_identifier(#parameters));
+            let receiver = if self_.is_any_ref() {
+                quote_spanned!(m.span()=>#receiver_argument_identifier)
+            } else {
+                quote_spanned!(m.span()=>#receiver_argument_identifier.inner()?)
+            };
+            body = match qualified {
+                Some(trait_) => quote_spanned!(m.span()=><#base_ident as #trait_>::#method_identifier(#receiver, #parameters)),
+                None => quote_spanned!(m.span()=>#receiver.#method_identifier(#parameters)),
+            };
+            if self_.is_mut_ref(){
+                mut_ = Some(Token![mut](Span::call_site()));
+            } else {
                 mut_ = None;
             }
 
             static_ = None;
             fn_ = None;
         } else {
-            body = quote_spanned!(m.span()=>#base_ident::#method_identifier(#parameters));
+            body = match (&m.const_, &m.out) {
+                // argument-less const functions are evaluated at compile time
+                (Some(_), Some(out_type)) if m.args.is_empty() => {
+                    let const_type = out_type.type_or_resolve(|| SimpleType::BaseIdent(wrapped_type.clone())).into_owned().strip_outer_refs();
+                    quote_spanned!(m.span()=>{
+                        const VALUE: #const_type = #base_ident::#method_identifier();
+                        VALUE
+                    })
+                }
+                _ => match qualified {
+                    Some(trait_) => quote_spanned!(m.span()=><#base_ident as #trait_>::#method_identifier(#parameters)),
+                    None => quote_spanned!(m.span()=>#base_ident::#method_identifier(#parameters)),
+                },
+            };
             static_ = Some(Token![static](Span::call_site()));
             fn_ = Some(Token![fn](Span::call_site()));
             mut_ = None;
@@ -87,11 +326,77 @@ pub(crate) fn make_methods<'a>(flag: &DeriveFlag, new_type: &'a Newtype, out: &m
 
         // call wrapper constructor on produced value if necessary (if output is also wrapped)
         if let Some(out_type) = &m.out{
-            if out_type.is_wrapped() || out_type.is_self(){
+            if out_type.is_result() {
+                // errors are raised as script errors, the `Ok` value is then wrapped like any other output
+                body = quote_spanned!{m.span()=>
+                    (#body).map_err(|e| bevy_mod_scripting_lua::tealr::mlu::mlua::Error::external(
+                        bevy_mod_scripting_core::prelude::ScriptError::Other(format!("{e:?}"))
+                    ))?
+                };
+            }
+
+            if out_type.is_any_ref() {
+                // references are returned as owned copies of the referenced value, i.e. `&str` as a `String`
+                body = quote_spanned!{m.span()=>
+                    (#body).to_owned()
+                };
+            } else if out_type.is_cow() {
+                body = quote_spanned!{m.span()=>
+                    (#body).into_owned()
+                };
+            }
+
+            if out_type.is_vec() && out_type.is_raw() {
+                // vectors of primitives are returned as vec proxies owning the vector
+                let elem_type = out_type.type_or_resolve(|| SimpleType::BaseIdent(wrapped_type.clone())).base_ident().clone();
+                body = quote_spanned!{m.span()=>
+                    bevy_script_api::lua::std::LuaVec::<#elem_type>::new_owned(
+                        #body,
+                        <bevy_mod_scripting_lua::tealr::mlu::mlua::Lua as bevy_script_api::common::bevy::GetWorld>::get_world(lua)?
+                    )
+                };
+            } else if out_type.is_array() && out_type.is_raw() {
+                // arrays of primitives are returned as plain tables
+                body = quote_spanned!{m.span()=>
+                    (#body).to_vec()
+                };
+            } else if out_type.is_map() && out_type.is_raw() {
+                body = quote_spanned!{m.span()=>
+                    (#body).into_iter().collect::<std::collections::HashMap<_,_>>()
+                };
+            } else if out_type.is_map() && out_type.is_wrapped() {
+                // maps of wrapped types become tables of wrappers with the same keys
                 let resolved_out_type = out_type.type_or_resolve(|| SimpleType::BaseIdent(wrapped_type.clone()));
                 let wrapper_out_type = format_ident!("Lua{}",resolved_out_type.base_ident());
                 body = quote_spanned!{m.span()=>
-                    #wrapper_out_type::new(#body)
+                    (#body).into_iter().map(|(k, v)| (k, #wrapper_out_type::new(v))).collect::<std::collections::HashMap<_,_>>()
+                };
+            } else if out_type.is_vec() || out_type.is_array() {
+                // vectors and arrays of wrapped types become tables of wrappers, since `LuaVec` needs type names for its elements
+                // which only the wrappers have
+                let resolved_out_type = out_type.type_or_resolve(|| SimpleType::BaseIdent(wrapped_type.clone()));
+                let wrapper_out_type = format_ident!("Lua{}",resolved_out_type.base_ident());
+                body = quote_spanned!{m.span()=>
+                    (#body).into_iter().map(#wrapper_out_type::new).collect::<Vec<_>>()
+                };
+            } else if out_type.is_wrapped() || out_type.is_self(){
+                let resolved_out_type = out_type.type_or_resolve(|| SimpleType::BaseIdent(wrapped_type.clone()));
+                let wrapper_out_type = format_ident!("Lua{}",resolved_out_type.base_ident());
+                body = if out_type.is_option() {
+                    quote_spanned!{m.span()=>
+                        (#body).map(#wrapper_out_type::new)
+                    }
+                } else {
+                    quote_spanned!{m.span()=>
+                        #wrapper_out_type::new(#body)
+                    }
+                };
+            } else if out_type.is_reflected() {
+                body = quote_spanned!{m.span()=>
+                    bevy_script_api::script_ref::ReflectedValue::new_owned(
+                        Box::new(#body),
+                        <bevy_mod_scripting_lua::tealr::mlu::mlua::Lua as bevy_script_api::common::bevy::GetWorld>::get_world(lua)?
+                    )
                 };
             }
         };
@@ -100,15 +405,50 @@ pub(crate) fn make_methods<'a>(flag: &DeriveFlag, new_type: &'a Newtype, out: &m
         body = quote_spanned!(m.span()=>Ok(#body));
 
         // for every wrapper involved as a parameter (and possibly the `self` receiver) which is a reference,
-        // wrap the expression in a val/val_mut call, to allow references as parameters
+        // wrap the expression in a val/val_mut call, to allow references as parameters.
+        // references to primitives are taken of the received value directly.
+        // Callbacks may access the world, which val/val_mut can hold a lock on for the duration of the call,
+        // so with callbacks the wrappers are copied out instead and copies of mutable references are written back after the call
+        let has_callbacks = !callback_errors.is_empty();
+        let mut copies = Vec::default();
+        let mut borrows = Vec::default();
+        let mut write_backs = Vec::default();
         m.args
             .iter()
             .zip(parameter_identifiers.iter())
-            .map(|(a,b)| (a.is_any_ref().then_some(a),b))
+            .map(|(a,b)| ((a.is_any_ref() && !a.is_reflected() && !a.is_slice() && !a.is_raw()).then_some(a),b))
             .chain(once((m.self_.as_ref().map(|(v,_)|v),&receiver_argument_identifier)))
             .filter_map(|(a,b)| Some((a?,b)))
             .for_each(|(arg,arg_ident)| {
-                if arg.is_any_ref() {
+                if !arg.is_any_ref() {
+                    return;
+                }
+                if has_callbacks {
+                    let copy = format_ident!("{arg_ident}_copy", span=arg.span());
+                    if arg.is_mut_ref() {
+                        copies.push(quote_spanned!{m.span()=>
+                            let mut #copy = #arg_ident.inner()?;
+                        });
+                        borrows.push(quote_spanned!{m.span()=>
+                            let #arg_ident = &mut #copy;
+                        });
+                        // the receiver is already a mutable reference, arguments are received by value
+                        let rebind = (arg_ident != &receiver_argument_identifier).then(|| quote_spanned!{m.span()=>
+                            let mut #arg_ident = #arg_ident;
+                        });
+                        write_backs.push(quote_spanned!{m.span()=>
+                            #rebind
+                            #arg_ident.val_mut(|#arg_ident| *#arg_ident = #copy)?;
+                        });
+                    } else {
+                        copies.push(quote_spanned!{m.span()=>
+                            let #copy = #arg_ident.inner()?;
+                        });
+                        borrows.push(quote_spanned!{m.span()=>
+                            let #arg_ident = &#copy;
+                        });
+                    }
+                } else {
                     let method_call = arg.is_mut_ref()
                     .then(|| format_ident!("val_mut",span=arg.span()))
                     .unwrap_or_else(|| format_ident!("val",span=arg.span()));
@@ -119,16 +459,124 @@ pub(crate) fn make_methods<'a>(flag: &DeriveFlag, new_type: &'a Newtype, out: &m
             });
 
 
+        // errors of script callbacks take precedence over the result computed with their defaults,
+        // they are only checked once the callbacks are dropped after the call, nothing is written back if one failed
+        if has_callbacks {
+            body = quote_spanned!{m.span()=>{
+                #(#copies)*
+                let result = {
+                    #(#borrows)*
+                    #body
+                };
+                #(#callback_errors)*
+                #(#write_backs)*
+                result
+            }};
+        }
+
+        // deprecated methods warn the first time any script calls them
+        let deprecation_warning = m.parsed_attrs.deprecated.as_ref().map(|note| {
+            let method_name = format!("{base_ident}.{}", m.script_name_in("lua"));
+            quote_spanned!{m.span()=>
+                static WARNED: std::sync::Once = std::sync::Once::new();
+                WARNED.call_once(|| bevy_script_api::common::log::warn_deprecated(#method_name, #note));
+            }
+        });
+
         // finally generate the full method definition
 
         let docstrings = m.docstring.iter().collect::<Punctuated<_,EmptyToken>>();
-        let method_identifier_string = method_identifier.to_string();
+        let method_identifier_string = m.script_name_in("lua").to_string();
         let self_ident = m.self_.as_ref()
             .map(|_| quote_spanned!(m.span()=>#receiver_argument_identifier,))
             .unwrap_or_else(Default::default);
+        let needs_lua = !validators.is_empty() || m.out.as_ref().map_or(false, |out| out.is_reflected() || (out.is_vec() && out.is_raw()));
+        let lua_ident = if !needs_lua {
+            quote_spanned!(m.span()=>_)
+        } else {
+            quote_spanned!(m.span()=>lua)
+        };
         parse_quote_spanned!{m.span()=>
             #docstrings
-            #static_ #mut_ #fn_ #method_identifier_string =>|_,#self_ident (#(#parameter_identifiers),*):(#(#parameter_types),*)| #body
+            #static_ #mut_ #fn_ #method_identifier_string =>|#lua_ident,#self_ident (#(#parameter_identifiers),*):(#(#parameter_types),*)| {
+                #deprecation_warning
+                #(#validators)*
+                #body
+            }
         }
     }).collect::<Vec<_>>())
 }
+
+#[cfg(test)]
+mod test {
+    use bevy_mod_scripting_common::newtype::Newtype;
+    use quote::ToTokens;
+
+    use super::make_methods;
+
+    #[test]
+    fn callbacks_are_adapted_from_lua_functions() {
+        let new_type: Newtype = syn::parse_str(
+            "Vec3 : Methods(any(&self: Callback(Raw(f32), Wrapped(&Vec3) -> Raw(bool))) -> Raw(bool))",
+        )
+        .unwrap();
+        let mut methods = Vec::default();
+        make_methods(
+            new_type.args.flags.first().unwrap(),
+            &new_type,
+            &mut methods,
+        );
+
+        let method = methods[0].to_token_stream().to_string();
+        assert!(
+            method.contains("(a_0) : (bevy_mod_scripting_lua :: tealr :: mlu :: mlua :: Function)")
+        );
+        assert!(method.contains("let a_0 = | a_0_0 : f32 , a_0_1 : & Vec3 | -> bool"));
+        assert!(method.contains(
+            "a_0 . call :: < _ , bool > ((a_0_0 , LuaVec3 :: new (a_0_1 . clone ()) ,))"
+        ));
+        assert!(method.contains("if let Some (e) = a_0_error . into_inner () { return Err (e) ; }"));
+        // the receiver is copied out for the duration of the call, since the callback may access the world
+        assert!(method.contains("let s_copy = s . inner () ? ;"));
+        assert!(method.contains("let s = & s_copy ;"));
+        assert!(!method.contains("s . val (| s |"));
+    }
+
+    #[test]
+    fn callbacks_write_back_mutable_receivers() {
+        let new_type: Newtype =
+            syn::parse_str("Vec3 : Methods(map(&mut self: Callback(Raw(f32) -> Raw(f32))))")
+                .unwrap();
+        let mut methods = Vec::default();
+        make_methods(
+            new_type.args.flags.first().unwrap(),
+            &new_type,
+            &mut methods,
+        );
+
+        let method = methods[0].to_token_stream().to_string();
+        assert!(method.contains("let mut s_copy = s . inner () ? ;"));
+        assert!(method.contains("let s = & mut s_copy ;"));
+        // only once the callbacks succeeded
+        assert!(method.contains(
+            "if let Some (e) = a_0_error . into_inner () { return Err (e) ; } s . val_mut (| s | * s = s_copy) ? ;"
+        ));
+    }
+
+    #[test]
+    fn callbacks_are_rejected_outside_of_arguments() {
+        for invalid in [
+            "Vec3 : Methods(any(&self) -> Callback(Raw(f32)))",
+            "Vec3 : Fields(x: Callback(Raw(f32)))",
+            "Vec3 : BinOps(self Add Callback(Raw(f32)) -> Wrapped(Vec3))",
+            "Vec3 : Index(Raw(usize) -> Callback(Raw(f32)))",
+        ] {
+            let err = syn::parse_str::<Newtype>(invalid).err().unwrap();
+            assert!(
+                err.to_string()
+                    .starts_with("Callbacks can only be used as method arguments"),
+                "{invalid}: {err}"
+            );
+        }
+    }
+}
@@ -31,17 +31,34 @@ pub(crate) fn make_fields<'a>(
             .type_or_resolve(|| SimpleType::BaseIdent(new_type.args.base_type_ident.clone()))
             .into_owned();
 
+        if f.type_.is_wrapped() && f.type_.is_map() && !f.parsed_attrs.readonly {
+            return Err(syn::Error::new_spanned(
+                f,
+                "Maps of wrapped types must be #[readonly]",
+            ));
+        }
+
         if f.type_.is_wrapped() || f.type_.is_self() {
             resolved_field_type.mutate_base_ident(|ident| *ident = format_ident!("Lua{ident}"));
         }
 
         let id = &f.member;
-        let (mut lua_id_string, rust_id_string) = match id {
-            syn::Member::Named(string_id) => (string_id.to_string(), string_id.to_string()),
-            syn::Member::Unnamed(index) => (format!("_{}", index.index), index.index.to_string()),
+        // named fields are accessed via their name and tuple fields via their index
+        let (mut lua_id_string, reflect_index) = match id {
+            syn::Member::Named(string_id) => {
+                let rust_id_string = string_id.to_string();
+                (
+                    rust_id_string.clone(),
+                    quote_spanned! {f.span()=>std::borrow::Cow::Borrowed(#rust_id_string)},
+                )
+            }
+            syn::Member::Unnamed(index) => {
+                let index = index.index as usize;
+                (format!("_{index}"), quote_spanned! {f.span()=>#index})
+            }
         };
 
-        if let Some(new_name) = &f.parsed_attrs.script_name {
+        if let Some(new_name) = f.parsed_attrs.script_name_in("lua") {
             lua_id_string = new_name.to_string();
         }
 
@@ -50,15 +67,32 @@ pub(crate) fn make_fields<'a>(
         let field_type_string = field_type_ident.to_string();
 
         // make the getter method
-        let expr_getter = f.type_.is_wrapped()
+        let expr_getter = (f.type_.is_wrapped() && !f.type_.is_map())
             .then(|| {
                 quote_spanned!{f.span()=>
-                    Ok(#field_type_ident::new_ref(s.script_ref(world_ptr).index(std::borrow::Cow::Borrowed(#rust_id_string))))
+                    Ok(#field_type_ident::new_ref(s.script_ref(world_ptr).index(#reflect_index)))
                 }
             }).unwrap_or_else(|| {
                 if field_type_string == "ReflectedValue" {
                     return quote_spanned!{f.span()=>
-                        Ok(s.script_ref(world_ptr).index(std::borrow::Cow::Borrowed(#rust_id_string)))
+                        Ok(s.script_ref(world_ptr).index(#reflect_index))
+                    }
+                }
+                // maps are copied into new tables, wrapping each value
+                if f.type_.is_wrapped() && f.type_.is_map() {
+                    return quote_spanned!{f.span()=>
+                        s.val(|s| Ok(s.#id.iter().map(|(k, v)| (k.clone(), #field_type_ident::new(v.clone()))).collect::<std::collections::HashMap<_,_>>()))?
+                    }
+                }
+                if f.type_.is_map() {
+                    return quote_spanned!{f.span()=>
+                        s.val(|s| Ok(s.#id.iter().map(|(k, v)| (k.clone(), v.clone())).collect::<std::collections::HashMap<_,_>>()))?
+                    }
+                }
+                // arrays are handed out as plain tables
+                if resolved_field_type.is_array() {
+                    return quote_spanned!{f.span()=>
+                        s.val(|s| Ok(s.#id.to_vec()))?
                     }
                 }
                 quote_spanned!{f.span()=>{}
@@ -74,25 +108,61 @@ pub(crate) fn make_fields<'a>(
             }
         });
 
+        if f.parsed_attrs.readonly {
+            continue;
+        }
+
         // make the setter method
-        let expr_setter = f.type_.is_wrapped()
-            .then(|| {quote_spanned!{f.span()=>
-                Ok(o.apply_self_to_base(&mut s.script_ref(world_ptr).index(std::borrow::Cow::Borrowed(#rust_id_string)))?)
-            }}).unwrap_or_else(|| {
+        let expr_setter = f
+            .type_
+            .is_wrapped()
+            .then(|| {
+                quote_spanned! {f.span()=>
+                    Ok(o.apply_self_to_base(&mut s.script_ref(world_ptr).index(#reflect_index))?)
+                }
+            })
+            .unwrap_or_else(|| {
                 if field_type_string == "ReflectedValue" {
-                    return quote_spanned!{f.span()=>
-                        Ok(s.script_ref(world_ptr).index(std::borrow::Cow::Borrowed(#rust_id_string)).apply(&o.ref_)?)
-                    }
+                    return quote_spanned! {f.span()=>
+                        Ok(s.script_ref(world_ptr).index(#reflect_index).apply(&o.ref_)?)
+                    };
                 }
-                quote_spanned!{f.span()=>
+                // arrays are set from tables of the exact same length
+                if let Some((_, len)) = resolved_field_type.array_element() {
+                    return quote_spanned! {f.span()=>
+                        let o : [_; #len] = o.try_into().map_err(|v: Vec<_>|
+                            bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError(
+                                format!("expected an array of length {}, got {}", #len, v.len())
+                            )
+                        )?;
+                        s.val_mut(|s| Ok(s.#id = o))?
+                    };
+                }
+                // tables are collected into the map type of the field
+                if resolved_field_type.is_map() {
+                    return quote_spanned! {f.span()=>
+                        s.val_mut(|s| Ok(s.#id = o.into_iter().collect()))?
+                    };
+                }
+                quote_spanned! {f.span()=>
                     s.val_mut(|s| Ok(s.#id = o))?
                 }
             });
 
+        let setter_type = match resolved_field_type.array_element() {
+            Some((element_type, _)) => quote_spanned! {f.span()=>Vec<#element_type>},
+            None if resolved_field_type.is_map() => quote_spanned! {f.span()=>#resolved_field_type},
+            None => quote_spanned! {f.span()=>#field_type_ident},
+        };
+
         out.push(parse_quote_spanned! {f.span()=>
-            set #lua_id_string => |ctx,s: &mut #newtype_name, o: #field_type_ident| {
+            set #lua_id_string => |ctx,s: &mut #newtype_name, o: #setter_type| {
                 let world_ptr = <bevy_mod_scripting_lua::tealr::mlu::mlua::Lua as bevy_script_api::common::bevy::GetWorld>::get_world(ctx)?;
-                #expr_setter
+                // errors applying the value name the field, nested proxy conversions prepend it to their own path
+                bevy_script_api::lua::conversion::ConversionContext::in_field(
+                    (|| -> bevy_mod_scripting_lua::tealr::mlu::mlua::Result<()> { #expr_setter })(),
+                    #lua_id_string,
+                )
             }
         });
     }
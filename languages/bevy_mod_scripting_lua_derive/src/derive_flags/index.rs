@@ -0,0 +1,89 @@
+use bevy_mod_scripting_common::{
+    arg::{ArgType, SimpleType},
+    derive_flag::DeriveFlag,
+    newtype::Newtype,
+};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote_spanned};
+use syn::{parse_quote_spanned, spanned::Spanned};
+
+use crate::lua_method::LuaMethod;
+
+pub(crate) fn make_index<'a>(
+    flag: &DeriveFlag,
+    new_type: &'a Newtype,
+    out: &mut Vec<LuaMethod>,
+) -> Result<(), syn::Error> {
+    let wrapped_type = &new_type.args.base_type_ident;
+
+    let (ident, exprs) = match flag {
+        DeriveFlag::Index { ident, exprs, .. } => (ident, exprs),
+        _ => panic!("Expected Index flag"),
+    };
+
+    // lua has a single index metamethod, so there is no way to dispatch on the type of the key
+    if exprs.len() > 1 {
+        return Err(syn::Error::new_spanned(
+            &exprs[1],
+            "Only a single key type can be indexed with",
+        ));
+    }
+
+    let expr = match exprs.first() {
+        Some(expr) => expr,
+        None => return Ok(()),
+    };
+
+    // wrapped types are passed in and out of lua as their lua wrappers
+    let resolve = |arg_type: &ArgType| {
+        let mut resolved_type = arg_type
+            .type_or_resolve(|| SimpleType::BaseIdent(wrapped_type.clone()))
+            .into_owned();
+        if arg_type.is_wrapped() || arg_type.is_self() {
+            resolved_type.mutate_base_ident(|ident| *ident = format_ident!("Lua{ident}"));
+        }
+        resolved_type
+    };
+    let unwrap = |arg_type: &ArgType, value: TokenStream| {
+        if arg_type.is_wrapped() || arg_type.is_self() {
+            quote_spanned! {expr.span()=>#value.inner()?}
+        } else {
+            value
+        }
+    };
+
+    let key_type = resolve(&expr.key);
+    let output_type = resolve(&expr.output);
+    let key = unwrap(&expr.key, quote_spanned! {expr.span()=>key});
+    let value = unwrap(&expr.output, quote_spanned! {expr.span()=>value});
+
+    // keys are checked before indexing, since invalid keys panic
+    let type_name = wrapped_type.to_string();
+    let mut output = quote_spanned! {expr.span()=>
+        s.val(|s| bevy_script_api::common::index::check_key(#type_name, s, &key).map(|()| s[key].clone()))?
+            .map_err(bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError)?
+    };
+    if expr.output.is_wrapped() || expr.output.is_self() {
+        output = quote_spanned! {expr.span()=>#output_type::new(#output)};
+    }
+
+    out.push(parse_quote_spanned! {ident.span()=>
+        (bevy_mod_scripting_lua::tealr::mlu::mlua::MetaMethod::Index) => |_,s,key: #key_type| {
+            let key = #key;
+            Ok(#output)
+        }
+    });
+
+    if expr.mut_.is_some() {
+        out.push(parse_quote_spanned! {ident.span()=>
+            mut (bevy_mod_scripting_lua::tealr::mlu::mlua::MetaMethod::NewIndex) => |_,s,(key,value): (#key_type,#output_type)| {
+                let key = #key;
+                let value = #value;
+                Ok(s.val_mut(|s| bevy_script_api::common::index::check_key(#type_name, &*s, &key).map(|()| s[key] = value))?
+                    .map_err(bevy_mod_scripting_lua::tealr::mlu::mlua::Error::RuntimeError)?)
+            }
+        });
+    }
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 pub mod auto_methods;
 pub mod bin_ops;
 pub mod fields;
+pub mod index;
 pub mod unary_ops;
 
-pub(crate) use {auto_methods::*, bin_ops::*, fields::*, unary_ops::*};
+pub(crate) use {auto_methods::*, bin_ops::*, fields::*, index::*, unary_ops::*};
@@ -11,7 +11,7 @@ use bevy_mod_scripting_common::{
 use quote::{format_ident, quote, quote_spanned};
 
 use crate::{
-    derive_flags::{make_bin_ops, make_fields, make_methods, make_unary_ops},
+    derive_flags::{make_bin_ops, make_fields, make_index, make_methods, make_unary_ops},
     lua_method::LuaMethod,
 };
 
@@ -93,6 +93,19 @@ impl WrapperImplementor for LuaImplementor {
             }
         }
 
+        if newtype.args.flags.contains(&DeriveFlag::Display {
+            ident: Ident::new("Display", Span::call_site()),
+        }) {
+            definition = quote_spanned! {newtype.span()=>
+                #definition
+                impl std::fmt::Display for #newtype_name {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+                        self.val(|s| std::fmt::Display::fmt(s, f)).unwrap_or_else(|_| f.write_str("Error while retrieving reference in `std::fmt::Display`."))
+                    }
+                }
+            }
+        }
+
         Ok(definition)
     }
 
@@ -105,6 +118,18 @@ impl WrapperImplementor for LuaImplementor {
         let wrapped_type = &newtype.args.base_type_ident;
         let tealr = quote::quote!(bevy_mod_scripting_lua::tealr);
 
+        // sequences of numbers can be assigned directly, i.e. `transform.translation = {1, 2, 3}`
+        let apply_sequence = if is_sequence(newtype) {
+            quote_spanned! {newtype.span()=>
+                else if let #tealr::mlu::mlua::Value::Table(table) = new_val {
+                    self_.set_val(bevy_script_api::lua::table::sequence_from_table::<#wrapped_type>(table)?)?;
+                    Ok(())
+                }
+            }
+        } else {
+            Default::default()
+        };
+
         // provide documentation generation implementations
         let tealr_implementations = quote_spanned! {newtype.span()=>
             bevy_script_api::impl_tealr_type!(#wrapper_type);
@@ -154,7 +179,7 @@ impl WrapperImplementor for LuaImplementor {
 
                         other.apply_self_to_base(self_)?;
                         Ok(())
-                    } else {
+                    } #apply_sequence else {
                         Err(#tealr::mlu::mlua::Error::RuntimeError(
                             "Error in assigning to custom user data".to_owned(),
                         ))
@@ -189,15 +214,49 @@ impl WrapperImplementor for LuaImplementor {
         let wrapped_type = &new_type.args.base_type_ident;
         let tealr = quote::quote!(bevy_mod_scripting_lua::tealr);
 
+        // `Display` output is preferred for `tostring` over `Debug` output
+        let has_display = new_type.args.flags.contains(&DeriveFlag::Display {
+            ident: Ident::new("Display", Span::call_site()),
+        });
+
         derive_flags.try_for_each(|v| {
             match v {
-                DeriveFlag::Debug{ident} => out.push(parse_quote_spanned!{ident.span()=>
-                    (#tealr::mlu::mlua::MetaMethod::ToString) => |_,s,()| Ok(format!("{:?}",s))
-                }),
+                DeriveFlag::Debug{ident} => if !has_display {
+                    out.push(parse_quote_spanned!{ident.span()=>
+                        (#tealr::mlu::mlua::MetaMethod::ToString) => |_,s,()| Ok(format!("{:?}",s))
+                    })
+                },
                 DeriveFlag::Display{ident} => out.push(parse_quote_spanned!{ident.span()=>
                     (#tealr::mlu::mlua::MetaMethod::ToString) => |_,s,()| Ok(format!("{}",s))
                 }),
+                DeriveFlag::DefaultToString{ident} => {
+                    let name = format!("<{wrapped_type}>");
+                    out.push(parse_quote_spanned!{ident.span()=>
+                        (#tealr::mlu::mlua::MetaMethod::ToString) => |_,_,()| Ok(#name)
+                    })
+                },
+                DeriveFlag::PartialEq{ident} => out.push(parse_quote_spanned!{ident.span()=>
+                    (#tealr::mlu::mlua::MetaMethod::Eq) => |_,s,o: #wrapper_type| Ok(s.val(|s| o.val(|o| s == o))??)
+                }),
+                DeriveFlag::PartialOrd{ident} => {
+                    out.push(parse_quote_spanned!{ident.span()=>
+                        (#tealr::mlu::mlua::MetaMethod::Lt) => |_,s,o: #wrapper_type| Ok(s.val(|s| o.val(|o| s < o))??)
+                    });
+                    out.push(parse_quote_spanned!{ident.span()=>
+                        (#tealr::mlu::mlua::MetaMethod::Le) => |_,s,o: #wrapper_type| Ok(s.val(|s| o.val(|o| s <= o))??)
+                    });
+                },
                 DeriveFlag::Clone{ident} => {
+                    let from_sequence = if is_sequence(new_type) {
+                        quote_spanned!{ident.span()=>
+                            else if let #tealr::mlu::mlua::Value::Table(table) = lua_value {
+                                bevy_script_api::lua::table::sequence_from_table(table)
+                            }
+                        }
+                    } else {
+                        Default::default()
+                    };
+
                     self.additional_globals.extend(
                         quote_spanned!{ident.span()=>
                             impl bevy_script_api::lua::FromLuaProxy<'_> for #wrapped_type {
@@ -205,10 +264,10 @@ impl WrapperImplementor for LuaImplementor {
                                     if let #tealr::mlu::mlua::Value::UserData(ud) = lua_value{
                                         let wrapper = ud.borrow::<#wrapper_type>()?;
                                         Ok(std::ops::Deref::deref(&wrapper).inner()?)
-                                    } else {
+                                    } #from_sequence else {
                                         Err(#tealr::mlu::mlua::Error::FromLuaConversionError{
-                                            from: "",
-                                            to: "",
+                                            from: lua_value.type_name(),
+                                            to: stringify!(#wrapped_type),
                                             message: None
                                         })
                                     }
@@ -218,6 +277,10 @@ impl WrapperImplementor for LuaImplementor {
                     );
 
                 }
+                DeriveFlag::Sequence{ident} => out.push(parse_quote_spanned!{ident.span()=>
+                    /// Converts this value into a sequence of its elements, i.e. `{x, y, z}`
+                    "to_array" => |lua,s,()| bevy_script_api::lua::table::sequence_to_table(lua, &s.inner()?)
+                }),
                 flag @ DeriveFlag::Methods {..} => {
                     make_methods(flag,new_type,&mut out);
                 },
@@ -230,6 +293,9 @@ impl WrapperImplementor for LuaImplementor {
                 flag @ DeriveFlag::Fields {..} => {
                     make_fields(flag,new_type,&mut out)?;
                 },
+                flag @ DeriveFlag::Index {..} => {
+                    make_index(flag,new_type,&mut out)?;
+                },
             };
             Ok::<(),syn::Error>(())
         })?;
@@ -257,3 +323,10 @@ impl WrapperImplementor for LuaImplementor {
         }
     }
 }
+
+/// Returns true if the newtype has the `Sequence` flag
+fn is_sequence(new_type: &Newtype) -> bool {
+    new_type.args.flags.contains(&DeriveFlag::Sequence {
+        ident: Ident::new("Sequence", Span::call_site()),
+    })
+}
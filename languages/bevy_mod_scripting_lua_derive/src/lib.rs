@@ -2,12 +2,13 @@ use bevy_mod_scripting_common::{implementor::WrapperImplementor, newtype::Newtyp
 use implementor::LuaImplementor;
 // use impls::{impl_enum, impl_struct};
 use proc_macro::TokenStream;
-use syn::parse_macro_input;
+use syn::{parse_macro_input, DeriveInput};
 
 pub(crate) mod derive_flags;
 pub(crate) mod implementor;
 pub(crate) mod impls;
 pub(crate) mod lua_method;
+pub(crate) mod proxyable;
 
 #[proc_macro]
 pub fn impl_lua_newtype(tokens: TokenStream) -> TokenStream {
@@ -21,6 +22,22 @@ pub fn impl_lua_newtype(tokens: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Implements `LuaProxyable`, `FromLuaProxy` and `ToLuaProxy` for a struct with named fields,
+/// representing it in lua as a table whose fields are converted through their own proxies.
+///
+/// Fields marked with `#[lua(skip)]` are left out, they must implement `Default`.
+///
+/// Values accessed by reference are copied into a new table, changes to the table are written back only when
+/// it is assigned back, i.e. `local s = c.settings; s.volume = 1; c.settings = s`.
+#[proc_macro_derive(LuaProxyable, attributes(lua))]
+pub fn derive_lua_proxyable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    proxyable::derive_lua_proxyable(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
 // #[proc_macro_derive(LuaProxy, attributes(lua, scripting))]
 // pub fn lua_derive(input: TokenStream) -> TokenStream {
 //     let input = parse_macro_input!(input as DeriveInput);
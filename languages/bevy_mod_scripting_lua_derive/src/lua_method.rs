@@ -211,14 +211,21 @@ impl LuaMethod {
     /// UserData method which implements the given method or field
     ///
     /// For field setters and getters teh receiver must be an instance of
-    /// [`UserDataFields`] and [`UserDataMethods`] otherwise
+    /// [`UserDataFields`] and [`UserDataMethods`] otherwise.
+    /// Field getters and setters marked `fn` receive the userdata instead of `Self`,
+    /// which makes them static fields readable from the global proxy of the type, i.e. `get fn "ZERO" => |_,_| ...`
     pub fn to_call_expr(&self, receiver: &'static str) -> TokenStream {
         let closure = &self.closure.to_applied_closure();
         let receiver = Ident::new(receiver, Span::call_site());
 
-        let ds: TokenStream = self
+        // attributes other than docs (i.e. `#[cfg(...)]`) apply to the whole registration
+        let (docstring, attrs): (Vec<_>, Vec<_>) = self
             .docstring
             .iter()
+            .partition(|attr| attr.path.is_ident("doc"));
+
+        let ds: TokenStream = docstring
+            .into_iter()
             .map(|v| {
                 let ts: TokenStream = attribute_to_string_lit(v);
                 if ts.is_empty() {
@@ -232,7 +239,12 @@ impl LuaMethod {
             .collect();
         let call_ident = if self.method_type.is_field_getter || self.method_type.is_field_setter {
             format_ident!(
-                "add_field_method_{}",
+                "add_field_{}_{}",
+                if self.method_type.is_function {
+                    "function"
+                } else {
+                    "method"
+                },
                 self.method_type
                     .is_field_getter
                     .then_some("get")
@@ -259,8 +271,11 @@ impl LuaMethod {
         let inner_tokens = self.method_type.get_inner_tokens();
 
         quote_spanned! {self.span()=>
-            #ds
-            #receiver.#call_ident(#inner_tokens,#closure);
+            #(#attrs)*
+            {
+                #ds
+                #receiver.#call_ident(#inner_tokens,#closure);
+            }
         }
     }
 }
@@ -0,0 +1,165 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Field, Fields, Meta, NestedMeta};
+
+/// Returns true if the field is marked with `#[lua(skip)]`
+fn is_skipped(field: &Field) -> syn::Result<bool> {
+    let mut skipped = false;
+    for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("lua")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => {
+                for nested in list.nested {
+                    match nested {
+                        NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+                            skipped = true
+                        }
+                        other => {
+                            return Err(syn::Error::new_spanned(
+                                other,
+                                "Unknown lua attribute, expected `skip`",
+                            ))
+                        }
+                    }
+                }
+            }
+            other => return Err(syn::Error::new_spanned(other, "Expected `#[lua(skip)]`")),
+        }
+    }
+    Ok(skipped)
+}
+
+/// Generates `LuaProxyable`, `FromLuaProxy` and `ToLuaProxy` implementations for a struct with named fields.
+///
+/// The struct is represented in lua as a table, each field is converted through the proxy implementations of its own type,
+/// conversion errors are prefixed with the name of the field which failed to convert.
+/// Fields marked with `#[lua(skip)]` are not visible to lua and are set to their default values when converting from lua.
+///
+/// References (i.e. components accessed from scripts) are handed out as copies: `ref_to_lua` fills a new table from the
+/// referenced value, and the table is only written back once it is assigned to the reference, which goes through `apply_lua`.
+/// Fields whose own proxies are references, such as wrapped types, still point into the original value.
+pub(crate) fn derive_lua_proxyable(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "LuaProxyable can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "LuaProxyable can only be derived for structs",
+            ))
+        }
+    };
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "LuaProxyable cannot be derived for generic structs",
+        ));
+    }
+
+    let mut proxied = Vec::default();
+    let mut skipped = Vec::default();
+    for field in fields {
+        if is_skipped(field)? {
+            skipped.push(field.ident.as_ref().unwrap());
+        } else {
+            proxied.push(field);
+        }
+    }
+
+    let ident = &input.ident;
+    let type_name = ident.to_string();
+    let names = proxied
+        .iter()
+        .map(|f| f.ident.as_ref().unwrap())
+        .collect::<Vec<_>>();
+    let labels = names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    let types = proxied.iter().map(|f| &f.ty).collect::<Vec<_>>();
+
+    let tealr = quote!(bevy_mod_scripting_lua::tealr);
+    let mlua = quote!(#tealr::mlu::mlua);
+    let script_ref = quote!(bevy_script_api::script_ref::ScriptRef);
+    let context = quote!(bevy_script_api::lua::conversion::ConversionContext);
+
+    let expect_table = quote! {
+        match new_val {
+            #mlua::Value::Table(table) => table,
+            other => {
+                return Err(#mlua::Error::FromLuaConversionError {
+                    from: other.type_name(),
+                    to: #type_name,
+                    message: Some("expected a table".to_owned()),
+                })
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl bevy_script_api::lua::LuaProxyable for #ident {
+            fn ref_to_lua<'lua>(self_: #script_ref, lua: &'lua #mlua::Lua) -> #mlua::Result<#mlua::Value<'lua>> {
+                let table = lua.create_table()?;
+                #(
+                    table.set(
+                        #labels,
+                        <#types as bevy_script_api::lua::LuaProxyable>::ref_to_lua(
+                            self_.sub_ref(bevy_script_api::ReflectPathElem::FieldAccess(#labels.into())),
+                            lua,
+                        )?,
+                    )?;
+                )*
+                Ok(#mlua::Value::Table(table))
+            }
+
+            fn apply_lua<'lua>(self_: &mut #script_ref, lua: &'lua #mlua::Lua, new_val: #mlua::Value<'lua>) -> #mlua::Result<()> {
+                let table = #expect_table;
+                #(
+                    match table.get::<_, #mlua::Value>(#labels)? {
+                        #mlua::Value::Nil => {}
+                        value => #context::in_field(
+                            <#types as bevy_script_api::lua::LuaProxyable>::apply_lua(
+                                &mut self_.sub_ref(bevy_script_api::ReflectPathElem::FieldAccess(#labels.into())),
+                                lua,
+                                value,
+                            ),
+                            #labels,
+                        )?,
+                    }
+                )*
+                Ok(())
+            }
+        }
+
+        impl<'lua> bevy_script_api::lua::FromLuaProxy<'lua> for #ident {
+            fn from_lua_proxy(new_val: #mlua::Value<'lua>, lua: &'lua #mlua::Lua) -> #mlua::Result<Self> {
+                let table = #expect_table;
+                Ok(Self {
+                    #(
+                        #names: #context::in_field(
+                            <#types as bevy_script_api::lua::FromLuaProxy>::from_lua_proxy(table.get(#labels)?, lua),
+                            #labels,
+                        )?,
+                    )*
+                    #(
+                        #skipped: Default::default(),
+                    )*
+                })
+            }
+        }
+
+        impl<'lua> bevy_script_api::lua::ToLuaProxy<'lua> for #ident {
+            fn to_lua_proxy(self, lua: &'lua #mlua::Lua) -> #mlua::Result<#mlua::Value<'lua>> {
+                let table = lua.create_table()?;
+                #(
+                    table.set(#labels, bevy_script_api::lua::ToLuaProxy::to_lua_proxy(self.#names, lua)?)?;
+                )*
+                Ok(#mlua::Value::Table(table))
+            }
+        }
+    })
+}
@@ -4,13 +4,17 @@ use bevy::{
     reflect::TypeUuid,
 };
 use bevy_mod_scripting_core::prelude::*;
-use std::sync::Arc;
+use rhai::{module_resolvers::StaticModuleResolver, Engine, EvalAltResult, Scope, AST};
+use std::{path::Path, sync::Arc};
 
 #[derive(Debug, TypeUuid)]
 #[uuid = "e4f7d00d-5acd-45fb-a29c-5a44c5447f5c"]
 /// A rhai code file in bytes
 pub struct RhaiFile {
     pub bytes: Arc<[u8]>,
+    /// The compiled script, if it was loaded by a [`RhaiLoader`] which compiles scripts ahead of time.
+    /// Scripts importing modules are still compiled by the host, which embeds the modules they import.
+    pub ast: Option<AST>,
 }
 
 impl CodeAsset for RhaiFile {
@@ -20,8 +24,44 @@ impl CodeAsset for RhaiFile {
 }
 
 #[derive(Default)]
-/// Asset loader for lua scripts
-pub struct RhaiLoader;
+/// Asset loader for rhai scripts
+pub struct RhaiLoader {
+    /// When enabled scripts are compiled while their asset is loaded, so syntax errors fail the asset load.
+    ///
+    /// Scripts are compiled by a default engine, so leave this disabled if the host engine registers custom syntax or operators.
+    /// Replace the default loader with `app.add_asset_loader(RhaiLoader { precompile: true })` after adding the host.
+    pub precompile: bool,
+}
+
+impl RhaiLoader {
+    /// Creates the asset for the given rhai source, compiling it if enabled
+    pub(crate) fn rhai_file(&self, path: &Path, source: &[u8]) -> Result<RhaiFile, Error> {
+        let ast = if self.precompile {
+            let script = std::str::from_utf8(source)?;
+
+            // imports fail to resolve, leaving scripts importing modules to the host
+            let mut engine = Engine::new();
+            engine.set_module_resolver(StaticModuleResolver::new());
+            match engine.compile_into_self_contained(&Scope::new(), script) {
+                Ok(ast) => Some(ast),
+                Err(e) if matches!(*e, EvalAltResult::ErrorModuleNotFound(..)) => None,
+                Err(e) => {
+                    return Err(Error::msg(format!(
+                        "Syntax error in `{}`: {e}",
+                        path.display()
+                    )))
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(RhaiFile {
+            bytes: source.into(),
+            ast,
+        })
+    }
+}
 
 impl AssetLoader for RhaiLoader {
     fn load<'a>(
@@ -29,10 +69,11 @@ impl AssetLoader for RhaiLoader {
         bytes: &'a [u8],
         load_context: &'a mut bevy::asset::LoadContext,
     ) -> bevy::asset::BoxedFuture<'a, Result<(), Error>> {
-        load_context.set_default_asset(LoadedAsset::new(RhaiFile {
-            bytes: bytes.into(),
-        }));
-        Box::pin(async move { Ok(()) })
+        let file = self.rhai_file(load_context.path(), bytes);
+        Box::pin(async move {
+            load_context.set_default_asset(LoadedAsset::new(file?));
+            Ok(())
+        })
     }
 
     fn extensions(&self) -> &[&str] {
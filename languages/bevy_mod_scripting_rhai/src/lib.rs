@@ -1,20 +1,25 @@
 use crate::{
     assets::{RhaiFile, RhaiLoader},
     docs::RhaiDocFragment,
+    modules::RhaiModuleResolver,
+};
+use bevy::{
+    ecs::schedule::{BaseSystemSet, FreeSystemSet, ScheduleLabel},
+    prelude::*,
 };
-use bevy::prelude::*;
 use bevy_mod_scripting_core::{prelude::*, systems::*, world::WorldPointer};
 use rhai::*;
 use std::marker::PhantomData;
 
 pub mod assets;
 pub mod docs;
+pub mod modules;
 pub use rhai;
 pub mod prelude {
     pub use crate::{
         assets::{RhaiFile, RhaiLoader},
         docs::RhaiDocFragment,
-        RhaiContext, RhaiEvent, RhaiScriptHost,
+        RhaiContext, RhaiEvent, RhaiScriptError, RhaiScriptHost,
     };
     pub use rhai;
     pub use rhai::{Engine, FuncArgs};
@@ -23,6 +28,16 @@ pub mod prelude {
 #[derive(Resource)]
 pub struct RhaiScriptHost<A: FuncArgs + Send> {
     pub engine: Engine,
+    /// When enabled each script is compiled once and its AST is shared by every instance of the script.
+    /// Each instance still keeps its own scope.
+    pub share_compiled_scripts: bool,
+    /// The resource limits of every script run by the engine, applied to the engine whenever a script is loaded.
+    /// Operations are counted per call into a script.
+    pub limits: ScriptLimits,
+    compiled: CompiledScriptCache<AST>,
+    modules: ScriptModules,
+    /// the generation of the modules compiled into the cached scripts
+    modules_generation: u64,
     _ph: PhantomData<A>,
 }
 
@@ -35,8 +50,16 @@ impl<A: FuncArgs + Send> Default for RhaiScriptHost<A> {
             Ok(info.name != "state" && info.name != "world" && info.name != "entity")
         });
 
+        let modules = ScriptModules::default();
+        e.set_module_resolver(RhaiModuleResolver(modules.clone()));
+
         Self {
             engine: e,
+            share_compiled_scripts: false,
+            limits: Default::default(),
+            compiled: Default::default(),
+            modules,
+            modules_generation: 0,
             _ph: Default::default(),
         }
     }
@@ -47,6 +70,49 @@ pub struct RhaiContext {
     pub scope: Scope<'static>,
 }
 
+impl RhaiContext {
+    /// Creates the context of a new instance of the given script
+    fn new(mut ast: AST, script_data: &ScriptData) -> Self {
+        ast.set_source(script_data.name);
+
+        // persistent state for scripts
+        let mut scope = Scope::new();
+        scope.push("state", Map::new());
+
+        Self { ast, scope }
+    }
+
+    /// Returns true if the script defines a function with the given name
+    fn defines(&self, name: &str) -> bool {
+        self.ast.iter_functions().any(|f| f.name == name)
+    }
+
+    /// Calls the given function of the script, with the id of the script as the tag of the call, see [`calling_script`]
+    fn call_fn<T: Clone + Send + Sync + 'static>(
+        &mut self,
+        engine: &Engine,
+        script_data: &ScriptData,
+        name: &str,
+        args: impl FuncArgs,
+    ) -> Result<T, Box<EvalAltResult>> {
+        let options = CallFnOptions::new().with_tag(script_data.sid as INT);
+        engine.call_fn_with_options(options, &mut self.scope, &self.ast, name, args)
+    }
+
+    /// Runs the given call with the `world` variable hidden from the script, used while the world can't be accessed
+    fn without_world<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let world = self.scope.get_value::<Dynamic>("world");
+        if world.is_some() {
+            self.scope.set_value("world", ());
+        }
+        let result = f(self);
+        if let Some(world) = world {
+            self.scope.set_value("world", world);
+        }
+        result
+    }
+}
+
 #[derive(Clone)]
 /// A Rhai Hook. The result of creating this event will be
 /// a call to the lua script with the hook_name and the given arguments
@@ -62,35 +128,18 @@ impl<A: FuncArgs + Clone + Send + Sync + 'static> ScriptEvent for RhaiEvent<A> {
     }
 }
 
-impl<A: FuncArgs + Send + Clone + Sync + 'static> ScriptHost for RhaiScriptHost<A> {
-    type ScriptContext = RhaiContext;
-    type ScriptEvent = RhaiEvent<A>;
-    type ScriptAsset = RhaiFile;
-    type APITarget = Engine;
-    type DocTarget = RhaiDocFragment;
-
-    fn register_with_app(app: &mut bevy::prelude::App, set: impl bevy::prelude::SystemSet) {
-        app.add_priority_event::<Self::ScriptEvent>()
+impl<A: FuncArgs + Send + Clone + Sync + 'static> RhaiScriptHost<A> {
+    /// Registers everything the host needs apart from its systems, and attaches the API providers to the engine on startup
+    fn register_resources(app: &mut App) -> &mut App {
+        app.add_priority_event::<RhaiEvent<A>>()
             .add_asset::<RhaiFile>()
             .init_asset_loader::<RhaiLoader>()
             .init_resource::<CachedScriptState<Self>>()
-            .init_resource::<ScriptContexts<Self::ScriptContext>>()
+            .init_resource::<ScriptContexts<RhaiContext>>()
             .init_resource::<APIProviders<Self>>()
-            .register_type::<ScriptCollection<Self::ScriptAsset>>()
-            .register_type::<Script<Self::ScriptAsset>>()
+            .register_type::<ScriptCollection<RhaiFile>>()
+            .register_type::<Script<RhaiFile>>()
             .register_type::<Handle<RhaiFile>>()
-            .add_system_set_to_stage(
-                stage,
-                SystemSet::new()
-                    .with_system(
-                        script_add_synchronizer::<Self>.before(script_remove_synchronizer::<Self>),
-                    )
-                    .with_system(
-                        script_remove_synchronizer::<Self>
-                            .before(script_hot_reload_handler::<Self>),
-                    )
-                    .with_system(script_hot_reload_handler::<Self>),
-            )
             // setup engine
             .add_startup_system(
                 |mut providers: ResMut<APIProviders<Self>>, mut host: ResMut<Self>| {
@@ -98,9 +147,130 @@ impl<A: FuncArgs + Send + Clone + Sync + 'static> ScriptHost for RhaiScriptHost<
                         .attach_all(&mut host.engine)
                         .expect("Error in adding api's for rhai");
                 },
+            )
+    }
+}
+
+impl<A: FuncArgs + Send + Clone + Sync + 'static> RhaiScriptHost<A> {
+    /// Loads a script into a new context, using the AST compiled ahead of time by the asset loader if available
+    fn load_rhai(
+        &mut self,
+        script: &[u8],
+        precompiled: Option<&AST>,
+        script_data: &ScriptData,
+    ) -> Result<RhaiContext, ScriptError> {
+        apply_limits(&mut self.engine, &self.limits);
+
+        let compile = |script: &[u8]| {
+            let script = std::str::from_utf8(script).map_err(|_| ScriptError::FailedToLoad {
+                script: script_data.name.to_owned(),
+            })?;
+
+            // imports are resolved and embedded while compiling
+            self.modules
+                .load_script(script_data.name, || match precompiled {
+                    Some(ast) => Ok(ast.clone()),
+                    None => self
+                        .engine
+                        .compile_into_self_contained(&Scope::new(), script),
+                })
+                .map_err(|e| {
+                    if matches!(*e, EvalAltResult::ErrorParsing(..)) {
+                        ScriptError::SyntaxError {
+                            script: script_data.name.to_owned(),
+                            line: e.position().line(),
+                            msg: e.to_string(),
+                        }
+                    } else {
+                        runtime_error(script_data, &self.limits, e)
+                    }
+                })
+        };
+
+        // cached scripts embed outdated modules once any module changes
+        let generation = self.modules.generation();
+        if generation != self.modules_generation {
+            self.compiled.clear();
+            self.modules_generation = generation;
+        }
+
+        let ast = if self.share_compiled_scripts {
+            self.compiled
+                .get_or_compile(script_data.name, script, compile)?
+        } else {
+            compile(script)?
+        };
+
+        Ok(RhaiContext::new(ast, script_data))
+    }
+}
+
+impl<A: FuncArgs + Send + Clone + Sync + 'static> ScriptHost for RhaiScriptHost<A> {
+    type ScriptContext = RhaiContext;
+    type ScriptEvent = RhaiEvent<A>;
+    type ScriptAsset = RhaiFile;
+    type APITarget = Engine;
+    type DocTarget = RhaiDocFragment;
+
+    fn register_with_app_in_set(app: &mut App, set: impl FreeSystemSet + Clone) {
+        Self::register_resources(app)
+            .add_system(
+                script_add_synchronizer::<Self>
+                    .before(script_remove_synchronizer::<Self>)
+                    .in_set(set.clone()),
+            )
+            .add_system(
+                script_remove_synchronizer::<Self>
+                    .before(script_hot_reload_handler::<Self>)
+                    .in_set(set.clone()),
+            )
+            .add_system(script_hot_reload_handler::<Self>.in_set(set));
+    }
+
+    fn register_with_app_in_base_set(app: &mut App, set: impl BaseSystemSet + Clone) {
+        Self::register_resources(app)
+            .add_system(
+                script_add_synchronizer::<Self>
+                    .before(script_remove_synchronizer::<Self>)
+                    .in_base_set(set.clone()),
+            )
+            .add_system(
+                script_remove_synchronizer::<Self>
+                    .before(script_hot_reload_handler::<Self>)
+                    .in_base_set(set.clone()),
+            )
+            .add_system(script_hot_reload_handler::<Self>.in_base_set(set));
+    }
+
+    fn register_with_app_in_schedule(
+        app: &mut App,
+        schedule: impl ScheduleLabel + Clone,
+        set: impl FreeSystemSet + Clone,
+    ) {
+        Self::register_resources(app)
+            .add_system(
+                script_add_synchronizer::<Self>
+                    .before(script_remove_synchronizer::<Self>)
+                    .in_set(set.clone())
+                    .in_schedule(schedule.clone()),
+            )
+            .add_system(
+                script_remove_synchronizer::<Self>
+                    .before(script_hot_reload_handler::<Self>)
+                    .in_set(set.clone())
+                    .in_schedule(schedule.clone()),
+            )
+            .add_system(
+                script_hot_reload_handler::<Self>
+                    .in_set(set)
+                    .in_schedule(schedule),
             );
     }
 
+    fn modules(&self) -> Option<&ScriptModules> {
+        Some(&self.modules)
+    }
+
     fn setup_script(
         &mut self,
         script_data: &ScriptData,
@@ -110,31 +280,63 @@ impl<A: FuncArgs + Send + Clone + Sync + 'static> ScriptHost for RhaiScriptHost<
         providers.setup_all(script_data, ctx)
     }
 
+    /// Calls `on_reload_save()` if the script defines it, the returned value is passed to `on_reload_load(state)` after the reload
+    ///
+    /// Both functions run with the `world` variable set to `()`, since the world can't be accessed while scripts are reloaded
+    fn save_reload_state(
+        &self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+    ) -> Result<Option<ReloadState>, ScriptError> {
+        if !ctx.defines("on_reload_save") {
+            return Ok(None);
+        }
+
+        ctx.without_world(|ctx| {
+            ctx.call_fn::<Dynamic>(&self.engine, script_data, "on_reload_save", ())
+        })
+        .map(|state| Some(Box::new(state) as ReloadState))
+        .map_err(|e| runtime_error(script_data, &self.limits, e))
+    }
+
+    fn load_reload_state(
+        &self,
+        script_data: &ScriptData,
+        ctx: &mut Self::ScriptContext,
+        state: ReloadState,
+    ) -> Result<(), ScriptError> {
+        let state = match state.downcast::<Dynamic>() {
+            Ok(state) => *state,
+            Err(_) => return Ok(()),
+        };
+
+        if !ctx.defines("on_reload_load") {
+            return Ok(());
+        }
+
+        ctx.without_world(|ctx| {
+            ctx.call_fn::<()>(&self.engine, script_data, "on_reload_load", (state,))
+        })
+        .map_err(|e| runtime_error(script_data, &self.limits, e))
+    }
+
     fn load_script(
         &mut self,
         script: &[u8],
         script_data: &ScriptData,
         _: &mut APIProviders<Self>,
     ) -> Result<Self::ScriptContext, ScriptError> {
-        let mut scope = Scope::new();
-        let mut ast = self
-            .engine
-            .compile(
-                std::str::from_utf8(script).map_err(|_| ScriptError::FailedToLoad {
-                    script: script_data.name.to_owned(),
-                })?,
-            )
-            .map_err(|e| ScriptError::SyntaxError {
-                script: script_data.name.to_owned(),
-                msg: e.to_string(),
-            })?;
-
-        ast.set_source(script_data.name);
-
-        // persistent state for scripts
-        scope.push("state", Map::new());
+        self.load_rhai(script, None, script_data)
+    }
 
-        Ok(RhaiContext { ast, scope })
+    /// Uses the AST compiled by the asset loader if available, otherwise compiles the script like [`ScriptHost::load_script`]
+    fn load_script_asset(
+        &mut self,
+        script: &Self::ScriptAsset,
+        script_data: &ScriptData,
+        _: &mut APIProviders<Self>,
+    ) -> Result<Self::ScriptContext, ScriptError> {
+        self.load_rhai(&script.bytes, script.ast.as_ref(), script_data)
     }
 
     fn handle_events<'a>(
@@ -149,22 +351,21 @@ impl<A: FuncArgs + Send + Clone + Sync + 'static> ScriptHost for RhaiScriptHost<
             // - we have &mut World access
             // - we do not use world_ptr after we use the original reference again anywhere in this function
             let world_ptr = unsafe { WorldPointer::new(world) };
-            providers
-                .setup_runtime_all(world_ptr.clone(), &fd, ctx)
-                .expect("Failed to setup script runtime");
+            // a script which can't be set up is reported and skipped, the others still handle the events
+            if let Err(error) = providers.setup_runtime_all(world_ptr.clone(), &fd, ctx) {
+                world_ptr
+                    .write()
+                    .send_event(ScriptErrorEvent::new(&fd, error));
+                return;
+            }
 
             for event in events.iter() {
                 // check if this script should handle this event
-                if !event.recipients().is_recipient(&fd) {
-                    return;
+                if !event.recipients().is_recipient(&fd, &world_ptr.read()) {
+                    continue;
                 };
 
-                match self.engine.call_fn(
-                    &mut ctx.scope,
-                    &ctx.ast,
-                    &event.hook_name,
-                    event.args.clone(),
-                ) {
+                match ctx.call_fn::<()>(&self.engine, &fd, &event.hook_name, event.args.clone()) {
                     Ok(v) => v,
                     Err(e) => {
                         let mut world = world_ptr.write();
@@ -172,12 +373,8 @@ impl<A: FuncArgs + Send + Clone + Sync + 'static> ScriptHost for RhaiScriptHost<
 
                         let (_, mut error_wrt, _) = state.event_state.get_mut(&mut world);
 
-                        let error = ScriptError::RuntimeError {
-                            script: fd.name.to_string(),
-                            msg: e.to_string(),
-                        };
-                        error!("{}", error);
-                        error_wrt.send(ScriptErrorEvent { error });
+                        let error = runtime_error(&fd, &self.limits, e);
+                        error_wrt.send(ScriptErrorEvent::new(&fd, error));
 
                         world.insert_resource(state);
                     }
@@ -190,3 +387,522 @@ impl<A: FuncArgs + Send + Clone + Sync + 'static> ScriptHost for RhaiScriptHost<
         });
     }
 }
+
+impl<A, In, Out> CallbackHost<In, Out> for RhaiScriptHost<A>
+where
+    A: FuncArgs + Send + Clone + Sync + 'static,
+    In: FuncArgs + Send + Clone + Sync + 'static,
+    Out: Clone + Send + Sync + 'static,
+{
+    fn handle_callbacks<'a>(
+        &self,
+        world: &mut World,
+        events: &[CallbackEvent<In, Out>],
+        ctxs: impl Iterator<Item = (ScriptData<'a>, &'a mut Self::ScriptContext)>,
+        providers: &mut APIProviders<Self>,
+    ) {
+        ctxs.for_each(|(fd, ctx)| {
+            // safety:
+            // - we have &mut World access
+            // - we do not use world_ptr after we use the original reference again anywhere in this function
+            let world_ptr = unsafe { WorldPointer::new(world) };
+            // a script which can't be set up is reported and skipped, the others still handle the events
+            if let Err(error) = providers.setup_runtime_all(world_ptr.clone(), &fd, ctx) {
+                world_ptr
+                    .write()
+                    .send_event(ScriptErrorEvent::new(&fd, error));
+                return;
+            }
+
+            for event in events {
+                if !event.recipients().is_recipient(&fd, &world_ptr.read()) {
+                    continue;
+                }
+
+                // not subscribed to this event
+                if !ctx.defines(&event.hook_name) {
+                    continue;
+                }
+
+                let value = ctx
+                    .call_fn::<Out>(&self.engine, &fd, &event.hook_name, event.args.clone())
+                    .map_err(|e| {
+                        let error = runtime_error(&fd, &self.limits, e);
+                        world_ptr
+                            .write()
+                            .send_event(ScriptErrorEvent::new(&fd, error.clone()));
+                        error
+                    });
+
+                event.response.push(ScriptResponse {
+                    sid: fd.sid,
+                    script: fd.name.to_string(),
+                    value,
+                });
+            }
+        });
+    }
+}
+
+impl<A, Out> EvalHost<Out> for RhaiScriptHost<A>
+where
+    A: FuncArgs + Send + Clone + Sync + 'static,
+    Out: Clone + Send + Sync + 'static,
+{
+    fn eval_oneshot(&mut self, code: &str, world: &mut World) -> Result<Out, ScriptError> {
+        let script_data = ScriptData::oneshot();
+
+        world.resource_scope(|world, mut providers: Mut<APIProviders<Self>>| {
+            let mut ctx = self.load_script(&[], &script_data, &mut providers)?;
+            self.setup_script(&script_data, &mut ctx, &mut providers)?;
+
+            // safety:
+            // - we have &mut World access
+            // - we do not use world after creating the world pointer
+            let world_ptr = unsafe { WorldPointer::new(world) };
+            providers.setup_runtime_all(world_ptr, &script_data, &mut ctx)?;
+
+            self.engine
+                .eval_with_scope::<Out>(&mut ctx.scope, code)
+                .map_err(|e| runtime_error(&script_data, &self.limits, e))
+        })
+    }
+}
+
+/// Applies the given limits to the engine, unset limits are lifted
+/// Returns the id of the script which called the native function the given context belongs to.
+///
+/// The host tags its calls into scripts with the id of the script, so functions registered on the engine can tell which script
+/// called them. Returns `None` for calls not made on behalf of a script, i.e. while evaluating oneshot scripts.
+pub fn calling_script(context: &NativeCallContext) -> Option<u32> {
+    context.tag()?.as_int().ok().map(|sid| sid as u32)
+}
+
+fn apply_limits(engine: &mut Engine, limits: &ScriptLimits) {
+    let max_collection_size = limits.max_collection_size.unwrap_or(0);
+    engine
+        .set_max_operations(limits.max_instructions.unwrap_or(0))
+        .set_max_string_size(limits.max_string_size.unwrap_or(0))
+        .set_max_array_size(max_collection_size)
+        .set_max_map_size(max_collection_size);
+}
+
+/// Describes the limit exceeded by a script, if the given error was caused by one
+fn exceeded_limit(limits: &ScriptLimits, error: &EvalAltResult) -> Option<String> {
+    match error {
+        EvalAltResult::ErrorInFunctionCall(_, _, inner, _)
+        | EvalAltResult::ErrorInModule(_, inner, _) => exceeded_limit(limits, inner),
+        EvalAltResult::ErrorTooManyOperations(_) => limits
+            .max_instructions
+            .map(|max| format!("{max} operations")),
+        EvalAltResult::ErrorDataTooLarge(what, _) if what.contains("string") => limits
+            .max_string_size
+            .map(|max| format!("{max} bytes per string")),
+        EvalAltResult::ErrorDataTooLarge(..) => limits
+            .max_collection_size
+            .map(|max| format!("{max} elements per array or map")),
+        _ => None,
+    }
+}
+
+/// Converts an error raised while running a script into a `ScriptError::RuntimeError`,
+/// or a `ScriptError::LimitExceeded` if the script exceeded one of the given limits
+fn runtime_error(
+    script_data: &ScriptData,
+    limits: &ScriptLimits,
+    error: Box<EvalAltResult>,
+) -> ScriptError {
+    if let Some(limit) = exceeded_limit(limits, &error) {
+        return ScriptLimits::exceeded(script_data.name, limit);
+    }
+
+    ScriptError::RuntimeError {
+        script: script_data.name.to_owned(),
+        line: error.position().line(),
+        msg: error.to_string(),
+    }
+}
+
+/// Constructs [`ScriptError`]s from rhai errors, keeping the original error and the position it was raised at
+pub trait RhaiScriptError {
+    fn from_rhai(error: Box<EvalAltResult>) -> Self;
+}
+
+impl RhaiScriptError for ScriptError {
+    fn from_rhai(error: Box<EvalAltResult>) -> Self {
+        let position = error.position();
+        let traceback = (!position.is_none()).then(|| format!("at {position}"));
+        ScriptError::new_language_error("Rhai", *error, traceback)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::ecs::system::SystemState;
+    use std::path::Path;
+
+    type Host = RhaiScriptHost<()>;
+
+    /// Sets up an app handling `damage(INT) -> INT` callbacks, with the given scripts attached to a single entity
+    fn app_with_scripts(scripts: &[(&str, &str)]) -> App {
+        app_with_limits(ScriptLimits::default(), scripts)
+    }
+
+    /// Like [`app_with_scripts`], with the given limits set on the host before loading the scripts
+    fn app_with_limits(limits: ScriptLimits, scripts: &[(&str, &str)]) -> App {
+        let mut app = App::new();
+        app.add_plugin(ScriptingPlugin)
+            .init_resource::<Host>()
+            .init_resource::<APIProviders<Host>>()
+            .init_resource::<ScriptContexts<RhaiContext>>()
+            .add_callback_handler_to_base_set::<Host, (INT,), INT, _, 0, 0>(CoreSet::Update);
+
+        app.world.resource_mut::<Host>().limits = limits;
+        let entity = app.world.spawn_empty().id();
+        app.world.resource_scope(|world, mut host: Mut<Host>| {
+            world.resource_scope(|world, mut providers: Mut<APIProviders<Host>>| {
+                let mut contexts = world.resource_mut::<ScriptContexts<RhaiContext>>();
+                for (sid, (name, code)) in scripts.iter().enumerate() {
+                    let data = ScriptData {
+                        sid: sid as u32,
+                        entity,
+                        name,
+                    };
+                    let ctx = host
+                        .load_script(code.as_bytes(), &data, &mut providers)
+                        .unwrap();
+                    contexts.insert_context(data, Some(ctx));
+                }
+            })
+        });
+        app
+    }
+
+    /// Exposes the id of the running script to it as `sid`
+    struct ScriptIdAPI;
+
+    impl APIProvider for ScriptIdAPI {
+        type APITarget = Engine;
+        type ScriptContext = RhaiContext;
+        type DocTarget = RhaiDocFragment;
+
+        fn attach_api(&mut self, _: &mut Engine) -> Result<(), ScriptError> {
+            Ok(())
+        }
+
+        fn setup_script_runtime(
+            &mut self,
+            _: WorldPointer,
+            script_data: &ScriptData,
+            ctx: &mut RhaiContext,
+        ) -> Result<(), ScriptError> {
+            ctx.scope.set_value("sid", script_data.sid as INT);
+            Ok(())
+        }
+    }
+
+    /// Fails to set up the runtime of every script with a rhai error
+    struct FailingAPI;
+
+    impl APIProvider for FailingAPI {
+        type APITarget = Engine;
+        type ScriptContext = RhaiContext;
+        type DocTarget = RhaiDocFragment;
+
+        fn attach_api(&mut self, _: &mut Engine) -> Result<(), ScriptError> {
+            Ok(())
+        }
+
+        fn setup_script_runtime(
+            &mut self,
+            _: WorldPointer,
+            _: &ScriptData,
+            _: &mut RhaiContext,
+        ) -> Result<(), ScriptError> {
+            Err(ScriptError::from_rhai(
+                EvalAltResult::ErrorRuntime("no runtime".into(), Position::new(1, 2)).into(),
+            ))
+        }
+    }
+
+    fn send_callback(app: &mut App, args: (INT,)) -> CallbackResponse<INT> {
+        let response = CallbackResponse::new();
+        let mut state =
+            SystemState::<PriorityEventWriter<CallbackEvent<(INT,), INT>>>::new(&mut app.world);
+        state.get_mut(&mut app.world).send(
+            CallbackEvent {
+                hook_name: "damage".to_owned(),
+                args,
+                recipients: Recipients::All,
+                response: response.clone(),
+            },
+            0,
+        );
+        response
+    }
+
+    #[test]
+    fn callbacks_return_values_of_each_script() {
+        let mut app = app_with_scripts(&[
+            ("a.rhai", "fn damage(x) { x * 2 }"),
+            ("b.rhai", "fn heal(x) { x }"),
+            ("c.rhai", r#"fn damage(x) { throw "no damage" }"#),
+            ("d.rhai", "fn damage(x) { x + 1 }"),
+        ]);
+
+        let response = send_callback(&mut app, (2,));
+        assert!(response.take().is_empty());
+        app.update();
+
+        let responses = response.take();
+        assert_eq!(
+            responses
+                .iter()
+                .map(|r| r.script.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a.rhai", "c.rhai", "d.rhai"]
+        );
+        assert_eq!(responses[0].value.as_ref().unwrap(), &4);
+        assert!(matches!(
+            responses[1].value,
+            Err(ScriptError::RuntimeError { .. })
+        ));
+        assert_eq!(responses[2].value.as_ref().unwrap(), &3);
+        assert_eq!(app.world.resource::<Events<ScriptErrorEvent>>().len(), 1);
+
+        // values are only collected once
+        assert!(response.take().is_empty());
+        let response = send_callback(&mut app, (5,));
+        app.update();
+        assert_eq!(response.take_values(), vec![10, 6]);
+    }
+
+    #[test]
+    fn scripts_exceeding_their_limits_are_interrupted() {
+        let limits = ScriptLimits {
+            max_instructions: Some(1000),
+            max_string_size: Some(16),
+            max_collection_size: Some(8),
+            ..Default::default()
+        };
+        let mut app = app_with_limits(
+            limits,
+            &[
+                ("a.rhai", "fn damage(x) { loop {} }"),
+                (
+                    "b.rhai",
+                    r#"fn damage(x) { let s = ""; for i in 0..x { s += "abcd"; } x }"#,
+                ),
+                (
+                    "c.rhai",
+                    "fn damage(x) { let a = []; for i in 0..x { a.push(i); } x }",
+                ),
+                ("d.rhai", "fn damage(x) { x }"),
+            ],
+        );
+
+        let response = send_callback(&mut app, (10,));
+        app.update();
+        let responses = response.take();
+        let exceeded = responses
+            .iter()
+            .map(|r| match &r.value {
+                Err(ScriptError::LimitExceeded { script, limit }) => {
+                    assert_eq!(script, &r.script);
+                    Some(limit.as_str())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            exceeded,
+            vec![
+                Some("1000 operations"),
+                Some("16 bytes per string"),
+                Some("8 elements per array or map"),
+                None
+            ]
+        );
+        assert_eq!(responses[3].value.as_ref().unwrap(), &10);
+
+        // the scripts stay loaded and handle later events within their limits
+        let response = send_callback(&mut app, (2,));
+        app.update();
+        assert_eq!(response.take_values(), vec![2, 2, 2]);
+    }
+
+    #[test]
+    fn oneshots_get_their_own_script_ids() {
+        let mut world = World::new();
+        let mut providers = APIProviders::<Host>::default();
+        providers.providers.push(Box::new(ScriptIdAPI));
+        world.insert_resource(providers);
+
+        let mut host = Host::default();
+        let first: INT = host.eval_oneshot("sid", &mut world).unwrap();
+        let second: INT = host.eval_oneshot("sid", &mut world).unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn precompiled_scripts_are_loaded() {
+        let loader = RhaiLoader { precompile: true };
+        let file = loader
+            .rhai_file(Path::new("a.rhai"), b"fn damage(x) { x * 3 }")
+            .unwrap();
+        assert!(file.ast.is_some());
+        // syntax errors fail the asset load
+        let error = loader
+            .rhai_file(Path::new("b.rhai"), b"fn damage(x) {")
+            .unwrap_err();
+        assert!(error.to_string().contains("b.rhai"), "{error}");
+
+        let mut host = Host::default();
+        let data = ScriptData {
+            sid: 0,
+            entity: Entity::PLACEHOLDER,
+            name: "a.rhai",
+        };
+        let mut ctx = host
+            .load_script_asset(&file, &data, &mut APIProviders::default())
+            .unwrap();
+        let damage: INT = host
+            .engine
+            .call_fn(&mut ctx.scope, &ctx.ast, "damage", (2 as INT,))
+            .unwrap();
+        assert_eq!(damage, 6);
+    }
+
+    #[test]
+    fn reload_hooks_cannot_access_the_world() {
+        let mut host = Host::default();
+        let data = ScriptData {
+            sid: 0,
+            entity: Entity::PLACEHOLDER,
+            name: "a.rhai",
+        };
+        let mut old = host
+            .load_script(
+                b"fn on_reload_save() { world }",
+                &data,
+                &mut APIProviders::default(),
+            )
+            .unwrap();
+        old.scope.set_value("world", "the world");
+        let state = host.save_reload_state(&data, &mut old).unwrap().unwrap();
+        assert!(state.downcast_ref::<Dynamic>().unwrap().is::<()>());
+        assert_eq!(
+            old.scope.get_value::<ImmutableString>("world").unwrap(),
+            "the world"
+        );
+
+        let mut new = host
+            .load_script(
+                b"fn on_reload_load(state) { if world != () { throw world; } }",
+                &data,
+                &mut APIProviders::default(),
+            )
+            .unwrap();
+        new.scope.set_value("world", "the world");
+        host.load_reload_state(&data, &mut new, state).unwrap();
+        assert_eq!(
+            new.scope.get_value::<ImmutableString>("world").unwrap(),
+            "the world"
+        );
+    }
+
+    #[test]
+    fn provider_errors_name_their_script() {
+        let mut world = World::new();
+        let mut providers = APIProviders::<Host>::default();
+        providers.providers.push(Box::new(FailingAPI));
+        world.insert_resource(providers);
+
+        let result: Result<INT, _> = Host::default().eval_oneshot("1", &mut world);
+        let error = result.unwrap_err();
+        assert_eq!(error.script(), Some("oneshot"));
+        assert!(matches!(
+            error,
+            ScriptError::LanguageError {
+                language: "Rhai",
+                traceback: Some(_),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn scripts_can_be_added_replaced_and_removed_at_runtime() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin::default())
+            .add_plugin(ScriptingPlugin)
+            .add_script_host_to_base_set::<Host, _>(CoreSet::PostUpdate)
+            .add_callback_handler_to_base_set::<Host, (INT,), INT, _, 0, 0>(CoreSet::Update);
+
+        let mut assets = app.world.resource_mut::<Assets<RhaiFile>>();
+        let mut script = |code: &str| {
+            let handle = assets.add(RhaiFile {
+                bytes: code.as_bytes().into(),
+                ast: None,
+            });
+            Script::new("a.rhai".to_owned(), handle)
+        };
+        let (first, second) = (
+            script("fn damage(x) { x }"),
+            script("fn damage(x) { x * 2 }"),
+        );
+        let (first_id, second_id) = (first.id(), second.id());
+        let entity = app
+            .world
+            .spawn(ScriptCollection::<RhaiFile>::default())
+            .id();
+        app.update();
+
+        fn damage(app: &mut App) -> Vec<INT> {
+            let response = send_callback(app, (3,));
+            app.update();
+            response.take_values()
+        }
+        fn collection(app: &mut App, entity: Entity) -> Mut<'_, ScriptCollection<RhaiFile>> {
+            app.world.get_mut(entity).unwrap()
+        }
+
+        // added scripts are initialized
+        assert!(collection(&mut app, entity).add_script(first).is_none());
+        app.update();
+        assert!(app
+            .world
+            .resource::<ScriptContexts<RhaiContext>>()
+            .has_context(first_id));
+        assert_eq!(damage(&mut app), vec![3]);
+
+        // the context of a replaced script is torn down and the new script takes its place
+        let replaced = collection(&mut app, entity).add_script(second).unwrap();
+        assert_eq!(replaced.id(), first_id);
+        app.update();
+        let contexts = app.world.resource::<ScriptContexts<RhaiContext>>();
+        assert!(!contexts.has_context(first_id));
+        assert!(contexts.has_context(second_id));
+        assert_eq!(damage(&mut app), vec![6]);
+
+        // removed scripts no longer have a context
+        assert_eq!(
+            collection(&mut app, entity)
+                .remove_script("a.rhai")
+                .unwrap()
+                .id(),
+            second_id
+        );
+        assert!(collection(&mut app, entity)
+            .remove_script("a.rhai")
+            .is_none());
+        app.update();
+        assert!(app
+            .world
+            .resource::<ScriptContexts<RhaiContext>>()
+            .is_empty());
+        assert!(damage(&mut app).is_empty());
+    }
+}
@@ -0,0 +1,41 @@
+//! Resolves `import` statements against script assets instead of the file system.
+//!
+//! `import "scripts/util" as util;` imports the module at the asset path `scripts/util.rhai`. Imports are resolved
+//! when a script is compiled and embedded into it, so the script is reloaded whenever one of its modules is (see [`ScriptModules`]).
+use bevy_mod_scripting_core::prelude::*;
+use rhai::{Engine, EvalAltResult, Module, ModuleResolver, Position, Scope, Shared};
+
+/// Compiles and runs the source of a module, imports made by the module itself are resolved as it runs
+fn eval_module(
+    engine: &Engine,
+    asset_path: &str,
+    source: &[u8],
+) -> Result<Module, Box<EvalAltResult>> {
+    let source = std::str::from_utf8(source).map_err(|e| e.to_string())?;
+    let mut ast = engine.compile(source)?;
+    ast.set_source(asset_path);
+    Module::eval_ast_as_new(Scope::new(), &ast, engine)
+}
+
+/// The module resolver installed in the engine of the rhai script host
+pub(crate) struct RhaiModuleResolver(pub(crate) ScriptModules);
+
+impl ModuleResolver for RhaiModuleResolver {
+    fn resolve(
+        &self,
+        engine: &Engine,
+        _: Option<&str>,
+        path: &str,
+        pos: Position,
+    ) -> Result<Shared<Module>, Box<EvalAltResult>> {
+        let asset_path = format!("{path}.rhai");
+
+        self.0
+            .import(&asset_path, |source| {
+                eval_module(engine, &asset_path, source)
+            })
+            .map_err(|e| EvalAltResult::ErrorRuntime(e.to_string().into(), pos))?
+            .map(Into::into)
+            .map_err(|e| EvalAltResult::ErrorInModule(path.to_owned(), e, pos).into())
+    }
+}
@@ -0,0 +1,348 @@
+use bevy_mod_scripting_common::{
+    arg::{ArgType, SimpleType},
+    derive_flag::DeriveFlag,
+    newtype::Newtype,
+    utils::EmptyToken,
+};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote_spanned};
+use syn::{parse_quote_spanned, punctuated::Punctuated, spanned::Spanned};
+
+use crate::{derive_flags::resolve_type, implementor::wrapper_type, rhai_method::RhaiMethod};
+
+/// Returns true if rhai values can be converted to and from the given argument or output
+fn is_supported(arg_type: &ArgType) -> bool {
+    match arg_type.callback() {
+        Some((args, out)) => args.iter().chain(out).all(is_supported),
+        None => {
+            !(arg_type.is_reflected()
+                || arg_type.is_slice()
+                || arg_type.is_array()
+                || arg_type.is_map())
+        }
+    }
+}
+
+pub(crate) fn make_methods(flag: &DeriveFlag, new_type: &Newtype, out: &mut Vec<RhaiMethod>) {
+    let wrapped_type = &new_type.args.base_type_ident;
+    let wrapper_type = wrapper_type(new_type);
+    let rhai = quote::quote!(bevy_mod_scripting_rhai::rhai);
+
+    let methods = match flag {
+        DeriveFlag::Methods { methods, .. } => methods,
+        _ => panic!("Expected Methods flag"),
+    };
+
+    out.extend(methods.iter()
+    .filter(|m| m.args.iter().chain(m.out.as_ref()).all(is_supported))
+    .map(|m| {
+        // arguments are received as dynamic values and converted before the call
+        let mut parameter_identifiers = Vec::default();
+        let mut conversions = Vec::default();
+        // wrappers received by mutable reference are borrowed for the duration of the call
+        let mut borrowed = Vec::default();
+        // statements raising the errors of script callbacks after the call
+        let mut callback_errors = Vec::default();
+
+        let parameters : Punctuated<TokenStream, syn::Token![,]> = m.args.iter()
+            .enumerate()
+            .map(|(idx, arg_type)| {
+                let lit = format_ident!("a_{idx}", span = m.span());
+                parameter_identifiers.push(lit.clone());
+
+                // callbacks are received as function pointers and adapted into closures calling them in the context of the method call.
+                // Once a call fails the closure returns defaults without calling the script again, and the error is raised after the method returns
+                if let Some((callback_args, callback_out)) = arg_type.callback() {
+                    let error = format_ident!("{lit}_error");
+                    let callback_params: Vec<_> = (0..callback_args.len()).map(|i| format_ident!("{lit}_{i}")).collect();
+                    let callback_param_types = callback_args.iter()
+                        .map(|a| a.type_or_resolve(|| SimpleType::BaseIdent(wrapped_type.clone())).into_owned());
+                    let callback_values = callback_args.iter().zip(&callback_params).map(|(a, p)| {
+                        let value = match (a.is_wrapped(), a.is_any_ref()) {
+                            (true, true) => quote_spanned!(m.span()=>#p.clone()),
+                            (false, true) => quote_spanned!(m.span()=>#p.to_owned()),
+                            (_, false) => quote_spanned!(m.span()=>#p),
+                        };
+                        quote_spanned!(m.span()=>bevy_script_api::rhai::ToRhaiProxy::to_rhai_proxy(#value)?)
+                    });
+                    let call = quote_spanned!(m.span()=>#lit.call_within_context::<#rhai::Dynamic>(&ctx, args)?);
+                    let (out_type, call) = match callback_out {
+                        Some(out) => {
+                            let out_type = resolve_type(out, wrapped_type);
+                            let call = quote_spanned!(m.span()=><#out_type as bevy_script_api::rhai::FromRhaiProxy>::from_rhai_proxy(#call));
+                            (quote_spanned!(m.span()=>#out_type), call)
+                        }
+                        None => (quote_spanned!(m.span()=>()), quote_spanned!(m.span()=>{
+                            #call;
+                            Ok(())
+                        })),
+                    };
+                    conversions.push(quote_spanned!{m.span()=>
+                        let #lit = bevy_script_api::rhai::cast_proxy::<#rhai::FnPtr>(#lit)?;
+                        let #error = std::cell::RefCell::new(None);
+                        let #lit = |#(#callback_params: #callback_param_types),*| -> #out_type {
+                            if #error.borrow().is_some() {
+                                return Default::default();
+                            }
+                            let call = || -> Result<#out_type, Box<#rhai::EvalAltResult>> {
+                                let args: Vec<#rhai::Dynamic> = vec![#(#callback_values),*];
+                                #call
+                            };
+                            match call() {
+                                Ok(v) => v,
+                                Err(e) => {
+                                    *#error.borrow_mut() = Some(e);
+                                    Default::default()
+                                }
+                            }
+                        };
+                    });
+                    callback_errors.push(quote_spanned!{m.span()=>
+                        if let Some(e) = #error.into_inner() {
+                            return Err(e);
+                        }
+                    });
+                    return quote_spanned!(m.span()=>#lit);
+                }
+
+                let resolved_type = resolve_type(arg_type, wrapped_type);
+                if (arg_type.is_wrapped() || arg_type.is_self()) && arg_type.is_mut_ref() {
+                    let wrapper = format_ident!("Rhai{}", resolved_type.base_ident());
+                    conversions.push(quote_spanned!{m.span()=>
+                        let mut #lit = bevy_script_api::rhai::cast_proxy::<#wrapper>(#lit)?;
+                    });
+                    borrowed.push(lit.clone());
+                    return quote_spanned!(m.span()=>#lit);
+                }
+
+                let mut_ = arg_type.is_mut_ref().then(|| quote_spanned!(m.span()=>mut));
+                conversions.push(quote_spanned!{m.span()=>
+                    let #mut_ #lit = <#resolved_type as bevy_script_api::rhai::FromRhaiProxy>::from_rhai_proxy(#lit)?;
+                });
+                if arg_type.is_mut_ref() {
+                    quote_spanned!(m.span()=>&mut #lit)
+                } else if arg_type.is_any_ref() {
+                    quote_spanned!(m.span()=>&#lit)
+                } else if arg_type.is_cow() {
+                    quote_spanned!(m.span()=>std::borrow::Cow::Owned(#lit))
+                } else {
+                    quote_spanned!(m.span()=>#lit)
+                }
+            }).collect();
+
+        let method_identifier = &m.ident;
+        // generic methods are called with the type arguments given by `#[type_args(..)]`
+        let type_args = &m.parsed_attrs.type_args;
+        let method_identifier = if type_args.is_empty() {
+            quote_spanned!(m.span()=>#method_identifier)
+        } else {
+            quote_spanned!(m.span()=>#method_identifier::<#(#type_args),*>)
+        };
+
+        // trait methods which clash with other methods of the same name are called through the trait
+        let qualified = m.parsed_attrs.qualified.as_ref();
+        let mut body = match &m.self_ {
+            Some((self_, _)) => {
+                let receiver = if self_.is_any_ref() {
+                    quote_spanned!(m.span()=>s)
+                } else {
+                    quote_spanned!(m.span()=>s.inner()?)
+                };
+                match qualified {
+                    Some(trait_) => quote_spanned!(m.span()=><#wrapped_type as #trait_>::#method_identifier(#receiver, #parameters)),
+                    None => quote_spanned!(m.span()=>#receiver.#method_identifier(#parameters)),
+                }
+            }
+            None => match qualified {
+                Some(trait_) => quote_spanned!(m.span()=><#wrapped_type as #trait_>::#method_identifier(#parameters)),
+                None => quote_spanned!(m.span()=>#wrapped_type::#method_identifier(#parameters)),
+            },
+        };
+
+        // convert the output into a rhai value, references and borrowed strings are copied
+        body = match &m.out {
+            Some(out_type) => {
+                if out_type.is_result() {
+                    // errors are raised as script errors, the `Ok` value is then converted like any other output
+                    body = quote_spanned!{m.span()=>
+                        (#body).map_err(|e| Box::new(#rhai::EvalAltResult::ErrorRuntime(
+                            format!("{e:?}").into(),
+                            #rhai::Position::NONE,
+                        )))?
+                    };
+                }
+                if out_type.is_any_ref() {
+                    body = quote_spanned!(m.span()=>(#body).to_owned());
+                } else if out_type.is_cow() {
+                    body = quote_spanned!(m.span()=>(#body).into_owned());
+                }
+                quote_spanned!{m.span()=>
+                    bevy_script_api::rhai::ToRhaiProxy::to_rhai_proxy(#body)
+                }
+            }
+            None => quote_spanned!{m.span()=>{
+                #body;
+                Ok::<_, Box<#rhai::EvalAltResult>>(#rhai::Dynamic::UNIT)
+            }},
+        };
+
+        // wrappers received by reference, including the receiver, are accessed for the duration of the call.
+        // Callbacks may access the world, which val/val_mut can hold a lock on for the duration of the call,
+        // so with callbacks the wrappers are copied out instead and copies of mutable references are written back after the call
+        let has_callbacks = !callback_errors.is_empty();
+        let mut copies = Vec::default();
+        let mut borrows = Vec::default();
+        let mut write_backs = Vec::default();
+        let receiver = m.self_.as_ref()
+            .filter(|(self_, _)| self_.is_any_ref())
+            .map(|(self_, _)| (self_.is_mut_ref(), format_ident!("s")));
+        for (mut_, ident) in borrowed.into_iter().map(|b| (true, b)).chain(receiver) {
+            if has_callbacks {
+                let copy = format_ident!("{ident}_copy");
+                if mut_ {
+                    copies.push(quote_spanned!(m.span()=>let mut #copy = #ident.inner()?;));
+                    borrows.push(quote_spanned!(m.span()=>let #ident = &mut #copy;));
+                    write_backs.push(quote_spanned!(m.span()=>#ident.val_mut(|#ident| *#ident = #copy)?;));
+                } else {
+                    copies.push(quote_spanned!(m.span()=>let #copy = #ident.inner()?;));
+                    borrows.push(quote_spanned!(m.span()=>let #ident = &#copy;));
+                }
+                continue;
+            }
+            let method_call = if mut_ {
+                format_ident!("val_mut")
+            } else {
+                format_ident!("val")
+            };
+            body = quote_spanned!{m.span()=>
+                #ident.#method_call(|#ident| #body)?
+            };
+        }
+
+        // errors of script callbacks take precedence over the result computed with their defaults,
+        // they are only checked once the callbacks are dropped after the call, nothing is written back if one failed
+        if has_callbacks {
+            body = quote_spanned!{m.span()=>{
+                #(#copies)*
+                let result = {
+                    #(#borrows)*
+                    #body
+                };
+                #(#callback_errors)*
+                #(#write_backs)*
+                result
+            }};
+        }
+
+        // deprecated methods warn the first time any script calls them
+        let deprecation_warning = m.parsed_attrs.deprecated.as_ref().map(|note| {
+            let method_name = format!("{wrapped_type}.{}", m.script_name_in("rhai"));
+            quote_spanned!{m.span()=>
+                static WARNED: std::sync::Once = std::sync::Once::new();
+                WARNED.call_once(|| bevy_script_api::common::log::warn_deprecated(#method_name, #note));
+            }
+        });
+
+        let docstrings = m.docstring.iter().collect::<Punctuated<_,EmptyToken>>();
+        let method_identifier_string = m.script_name_in("rhai").to_string();
+        let parameter_types = parameter_identifiers.iter().map(|_| quote_spanned!(m.span()=>#rhai::Dynamic));
+        // callbacks are called within the context of the method call
+        let context = has_callbacks.then(|| quote_spanned!(m.span()=>ctx: #rhai::NativeCallContext,));
+
+        match &m.self_ {
+            Some(_) => parse_quote_spanned!{m.span()=>
+                #docstrings
+                fn #method_identifier_string => |#context s: &mut #wrapper_type, #(#parameter_identifiers: #parameter_types),*| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> {
+                    #deprecation_warning
+                    #(#conversions)*
+                    #body
+                }
+            },
+            // argument-less const functions are constants of the static module
+            None if m.const_.is_some() && m.args.is_empty() => parse_quote_spanned!{m.span()=>
+                #docstrings
+                const #method_identifier_string => || -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> {
+                    #body
+                }
+            },
+            None => parse_quote_spanned!{m.span()=>
+                #docstrings
+                static fn #method_identifier_string => |#context #(#parameter_identifiers: #parameter_types),*| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> {
+                    #deprecation_warning
+                    #(#conversions)*
+                    #body
+                }
+            },
+        }
+    }).collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod test {
+    use bevy_mod_scripting_common::newtype::Newtype;
+    use quote::ToTokens;
+
+    use super::make_methods;
+    use crate::rhai_method::RhaiMethodKind;
+
+    #[test]
+    fn methods_are_registered_by_kind() {
+        let new_type: Newtype = syn::parse_str(
+            "Vec3 : Methods(dot(&self: Wrapped(Vec3)) -> Raw(f32), new(Raw(f32), Raw(f32), Raw(f32)) -> self, const zero() -> self, any(&self: Callback(Raw(f32) -> Raw(bool))) -> Raw(bool))",
+        )
+        .unwrap();
+        let mut methods = Vec::default();
+        make_methods(
+            new_type.args.flags.first().unwrap(),
+            &new_type,
+            &mut methods,
+        );
+
+        assert_eq!(
+            methods.iter().map(|m| m.kind).collect::<Vec<_>>(),
+            vec![
+                RhaiMethodKind::Fn,
+                RhaiMethodKind::Static,
+                RhaiMethodKind::Const,
+                RhaiMethodKind::Fn
+            ]
+        );
+
+        let dot = methods[0].to_token_stream().to_string();
+        assert!(dot
+            .contains("| s : & mut RhaiVec3 , a_0 : bevy_mod_scripting_rhai :: rhai :: Dynamic |"));
+        assert!(dot.contains(
+            "let a_0 = < Vec3 as bevy_script_api :: rhai :: FromRhaiProxy > :: from_rhai_proxy (a_0) ? ;"
+        ));
+        assert!(dot.contains("s . val (| s | bevy_script_api :: rhai :: ToRhaiProxy :: to_rhai_proxy (s . dot (a_0))) ?"));
+
+        let zero = methods[2].to_token_stream().to_string();
+        assert!(zero.contains("const \"zero\" => | |"));
+        assert!(zero.contains("to_rhai_proxy (Vec3 :: zero ())"));
+    }
+
+    #[test]
+    fn callbacks_are_adapted_from_function_pointers() {
+        let new_type: Newtype = syn::parse_str(
+            "Vec3 : Methods(any(&mut self: Callback(Raw(f32), Wrapped(&Vec3) -> Raw(bool))) -> Raw(bool))",
+        )
+        .unwrap();
+        let mut methods = Vec::default();
+        make_methods(
+            new_type.args.flags.first().unwrap(),
+            &new_type,
+            &mut methods,
+        );
+
+        let method = methods[0].to_token_stream().to_string();
+        assert!(method.contains(
+            "| ctx : bevy_mod_scripting_rhai :: rhai :: NativeCallContext , s : & mut RhaiVec3 ,"
+        ));
+        assert!(method.contains("let a_0 = | a_0_0 : f32 , a_0_1 : & Vec3 | -> bool"));
+        assert!(method.contains("a_0 . call_within_context :: < bevy_mod_scripting_rhai :: rhai :: Dynamic > (& ctx , args) ?"));
+        // the receiver is copied out for the duration of the call, since the callback may access the world
+        assert!(method.contains("let mut s_copy = s . inner () ? ;"));
+        assert!(!method.contains("s . val_mut (| s | bevy_script_api"));
+        assert!(method.contains("if let Some (e) = a_0_error . into_inner () { return Err (e) ; } s . val_mut (| s | * s = s_copy) ? ;"));
+    }
+}
@@ -0,0 +1,87 @@
+use bevy_mod_scripting_common::{
+    arg::ArgType,
+    derive_flag::DeriveFlag,
+    newtype::Newtype,
+    ops::{OpName, Side},
+};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote_spanned};
+use syn::{parse_quote_spanned, spanned::Spanned};
+
+use crate::{derive_flags::resolve_type, rhai_method::RhaiMethod};
+
+/// The name of the rhai operator calling the given rust operator
+pub(crate) fn rhai_operator(op: &OpName) -> &'static str {
+    match op {
+        OpName::Add { .. } => "+",
+        OpName::Sub { .. } | OpName::Neg { .. } => "-",
+        OpName::Mul { .. } => "*",
+        OpName::Div { .. } => "/",
+        OpName::Rem { .. } => "%",
+    }
+}
+
+/// Produces the parameter type of an operand along with the expression converting it to the operand of the rust operator.
+///
+/// Wrappers are received as themselves so that rhai can pick the operator by their type,
+/// other values are received as dynamic values and converted
+pub(crate) fn make_operand(
+    arg_type: &ArgType,
+    ident: &syn::Ident,
+    new_type: &Newtype,
+) -> (TokenStream, TokenStream) {
+    let resolved_type = resolve_type(arg_type, &new_type.args.base_type_ident);
+    let ampersand = arg_type
+        .is_any_ref()
+        .then(|| quote_spanned!(arg_type.span()=>&));
+    if arg_type.is_wrapped() || arg_type.is_self() {
+        let wrapper = format_ident!("Rhai{}", resolved_type.base_ident());
+        (
+            quote_spanned!(arg_type.span()=>#wrapper),
+            quote_spanned!(arg_type.span()=>#ampersand #ident.inner()?),
+        )
+    } else {
+        (
+            quote_spanned!(arg_type.span()=>bevy_mod_scripting_rhai::rhai::Dynamic),
+            quote_spanned!(arg_type.span()=>#ampersand <#resolved_type as bevy_script_api::rhai::FromRhaiProxy>::from_rhai_proxy(#ident)?),
+        )
+    }
+}
+
+pub(crate) fn make_bin_ops(
+    flag: &DeriveFlag,
+    new_type: &Newtype,
+    out: &mut Vec<RhaiMethod>,
+) -> Result<(), syn::Error> {
+    let rhai = quote::quote!(bevy_mod_scripting_rhai::rhai);
+
+    let (ident, ops) = match flag {
+        DeriveFlag::BinOps { ident, ops, .. } => (ident, ops),
+        _ => panic!("Expected BinOps flag"),
+    };
+
+    // rhai dispatches operators on the types of both operands, so each expression is registered on its own
+    for op in ops {
+        let operator = rhai_operator(&op.op);
+        let (l, r) = op.map_both(|arg_type, side| {
+            let ident = match side {
+                Side::Left => format_ident!("l"),
+                Side::Right => format_ident!("r"),
+            };
+            let (param_type, value) = make_operand(arg_type, &ident, new_type);
+            (quote_spanned!(op.span()=>#ident: #param_type), value)
+        });
+        let (l_param, l_value) = l.expect("Expected binary expression");
+        let (r_param, r_value) = r;
+        let trait_ = format_ident!("{}", op.op.to_str());
+        let method = op.op.to_rust_method_ident();
+
+        out.push(parse_quote_spanned! {ident.span()=>
+            fn #operator => |#l_param, #r_param| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> {
+                bevy_script_api::rhai::ToRhaiProxy::to_rhai_proxy(std::ops::#trait_::#method(#l_value, #r_value))
+            }
+        });
+    }
+
+    Ok(())
+}
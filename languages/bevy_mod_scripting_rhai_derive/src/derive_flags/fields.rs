@@ -0,0 +1,94 @@
+use bevy_mod_scripting_common::{derive_flag::DeriveFlag, newtype::Newtype};
+use quote::quote_spanned;
+use syn::{parse_quote_spanned, spanned::Spanned};
+
+use crate::{derive_flags::resolve_type, implementor::wrapper_type, rhai_method::RhaiMethod};
+
+pub(crate) fn make_fields(
+    flag: &DeriveFlag,
+    new_type: &Newtype,
+    out: &mut Vec<RhaiMethod>,
+) -> Result<(), syn::Error> {
+    let newtype_name = wrapper_type(new_type);
+    let rhai = quote::quote!(bevy_mod_scripting_rhai::rhai);
+
+    let fields = match flag {
+        DeriveFlag::Fields { fields, .. } => fields,
+        _ => panic!("Expected Fields flag"),
+    };
+
+    // each field is mapped to a getter + setter function
+    for f in fields {
+        if f.type_.is_any_ref() {
+            return Err(syn::Error::new_spanned(
+                f,
+                "Reference fields are not supported",
+            ));
+        }
+
+        // maps, arrays and reflected values have no rhai conversions
+        if f.type_.is_map() || f.type_.is_array() || f.type_.is_reflected() {
+            continue;
+        }
+
+        let field_type = resolve_type(&f.type_, &new_type.args.base_type_ident);
+
+        let id = &f.member;
+        // named fields are accessed via their name and tuple fields via their index
+        let (mut rhai_id_string, reflect_index) = match id {
+            syn::Member::Named(string_id) => {
+                let rust_id_string = string_id.to_string();
+                (
+                    rust_id_string.clone(),
+                    quote_spanned! {f.span()=>std::borrow::Cow::Borrowed(#rust_id_string)},
+                )
+            }
+            syn::Member::Unnamed(index) => {
+                let index = index.index as usize;
+                (format!("_{index}"), quote_spanned! {f.span()=>#index})
+            }
+        };
+
+        if let Some(new_name) = f.parsed_attrs.script_name_in("rhai") {
+            rhai_id_string = new_name.to_string();
+        }
+
+        let docstring = f.docstring.iter();
+
+        // wrapped fields of references are references themselves, so that i.e. `transform.translation.x = 2.0` modifies the transform
+        let expr_getter = if f.type_.is_wrapped() || f.type_.is_self() {
+            quote_spanned! {f.span()=>
+                match &*s {
+                    #newtype_name::Ref(r) => <#field_type as bevy_script_api::rhai::RhaiProxyable>::ref_to_rhai(
+                        bevy_script_api::ValueIndex::index(r, #reflect_index)
+                    ),
+                    _ => bevy_script_api::rhai::ToRhaiProxy::to_rhai_proxy(s.val(|s| s.#id.clone())?),
+                }
+            }
+        } else {
+            quote_spanned! {f.span()=>
+                bevy_script_api::rhai::ToRhaiProxy::to_rhai_proxy(s.val(|s| s.#id.clone())?)
+            }
+        };
+
+        out.push(parse_quote_spanned! {f.span()=>
+            #(#docstring)*
+            get #rhai_id_string => |s: &mut #newtype_name| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> {
+                #expr_getter
+            }
+        });
+
+        if f.parsed_attrs.readonly {
+            continue;
+        }
+
+        out.push(parse_quote_spanned! {f.span()=>
+            set #rhai_id_string => |s: &mut #newtype_name, o: #rhai::Dynamic| -> Result<(), Box<#rhai::EvalAltResult>> {
+                let o = <#field_type as bevy_script_api::rhai::FromRhaiProxy>::from_rhai_proxy(o)?;
+                Ok(s.val_mut(|s| s.#id = o)?)
+            }
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use bevy_mod_scripting_common::{derive_flag::DeriveFlag, newtype::Newtype};
+use syn::parse_quote_spanned;
+
+use crate::{derive_flags::resolve_type, implementor::wrapper_type, rhai_method::RhaiMethod};
+
+pub(crate) fn make_index(
+    flag: &DeriveFlag,
+    new_type: &Newtype,
+    out: &mut Vec<RhaiMethod>,
+) -> Result<(), syn::Error> {
+    let wrapped_type = &new_type.args.base_type_ident;
+    let wrapper_type = wrapper_type(new_type);
+    let rhai = quote::quote!(bevy_mod_scripting_rhai::rhai);
+
+    let (ident, exprs) = match flag {
+        DeriveFlag::Index { ident, exprs, .. } => (ident, exprs),
+        _ => panic!("Expected Index flag"),
+    };
+
+    // keys are received as dynamic values, so there is no way to dispatch on their type
+    if exprs.len() > 1 {
+        return Err(syn::Error::new_spanned(
+            &exprs[1],
+            "Only a single key type can be indexed with",
+        ));
+    }
+
+    let expr = match exprs.first() {
+        Some(expr) => expr,
+        None => return Ok(()),
+    };
+
+    let key_type = resolve_type(&expr.key, wrapped_type);
+    let output_type = resolve_type(&expr.output, wrapped_type);
+
+    // keys are checked before indexing, since invalid keys panic
+    let type_name = wrapped_type.to_string();
+    out.push(parse_quote_spanned! {ident.span()=>
+        index get => |s: &mut #wrapper_type, key: #rhai::Dynamic| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> {
+            let key = <#key_type as bevy_script_api::rhai::FromRhaiProxy>::from_rhai_proxy(key)?;
+            let value = s.val(|s| bevy_script_api::common::index::check_key(#type_name, s, &key).map(|()| s[key].clone()))?
+                .map_err(|e| Box::new(#rhai::EvalAltResult::ErrorRuntime(e.into(), #rhai::Position::NONE)))?;
+            bevy_script_api::rhai::ToRhaiProxy::to_rhai_proxy(value)
+        }
+    });
+
+    if expr.mut_.is_some() {
+        out.push(parse_quote_spanned! {ident.span()=>
+            index set => |s: &mut #wrapper_type, key: #rhai::Dynamic, value: #rhai::Dynamic| -> Result<(), Box<#rhai::EvalAltResult>> {
+                let key = <#key_type as bevy_script_api::rhai::FromRhaiProxy>::from_rhai_proxy(key)?;
+                let value = <#output_type as bevy_script_api::rhai::FromRhaiProxy>::from_rhai_proxy(value)?;
+                s.val_mut(|s| bevy_script_api::common::index::check_key(#type_name, &*s, &key).map(|()| s[key] = value))?
+                    .map_err(|e| Box::new(#rhai::EvalAltResult::ErrorRuntime(e.into(), #rhai::Position::NONE)))
+            }
+        });
+    }
+
+    Ok(())
+}
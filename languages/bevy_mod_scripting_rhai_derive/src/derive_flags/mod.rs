@@ -0,0 +1,24 @@
+use bevy_mod_scripting_common::arg::{ArgType, SimpleType};
+use proc_macro2::Ident;
+
+pub mod auto_methods;
+pub mod bin_ops;
+pub mod fields;
+pub mod index;
+pub mod unary_ops;
+
+pub(crate) use {auto_methods::*, bin_ops::*, fields::*, index::*, unary_ops::*};
+
+/// Resolves the type values of the given argument are converted from and to by rhai, without outer references.
+/// Strings are received as owned strings, i.e. `Raw(&str)` resolves to `String`
+pub(crate) fn resolve_type(arg_type: &ArgType, wrapped_type: &Ident) -> SimpleType {
+    let resolved_type = arg_type
+        .type_or_resolve(|| SimpleType::BaseIdent(wrapped_type.clone()))
+        .into_owned()
+        .strip_outer_refs();
+    if resolved_type.is_string() {
+        SimpleType::BaseIdent(Ident::new("String", wrapped_type.span()))
+    } else {
+        resolved_type
+    }
+}
@@ -0,0 +1,36 @@
+use bevy_mod_scripting_common::{derive_flag::DeriveFlag, newtype::Newtype};
+use quote::format_ident;
+use syn::{parse_quote_spanned, spanned::Spanned};
+
+use crate::{
+    derive_flags::{make_operand, rhai_operator},
+    rhai_method::RhaiMethod,
+};
+
+pub(crate) fn make_unary_ops(
+    flag: &DeriveFlag,
+    new_type: &Newtype,
+    out: &mut Vec<RhaiMethod>,
+) -> Result<(), syn::Error> {
+    let rhai = quote::quote!(bevy_mod_scripting_rhai::rhai);
+
+    let ops = match flag {
+        DeriveFlag::UnaryOps { ops, .. } => ops,
+        _ => panic!("Expected UnaryOps flag"),
+    };
+
+    for op in ops {
+        let operator = rhai_operator(&op.op);
+        let (param_type, value) = make_operand(&op.right, &format_ident!("r"), new_type);
+        let trait_ = format_ident!("{}", op.op.to_str());
+        let method = op.op.to_rust_method_ident();
+
+        out.push(parse_quote_spanned! {op.span()=>
+            fn #operator => |r: #param_type| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> {
+                bevy_script_api::rhai::ToRhaiProxy::to_rhai_proxy(std::ops::#trait_::#method(#value))
+            }
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,282 @@
+use proc_macro2::{Ident, TokenStream};
+use syn::{parse_quote_spanned, punctuated::Punctuated, spanned::Spanned, Token};
+
+use bevy_mod_scripting_common::{
+    derive_flag::DeriveFlag, implementor::WrapperImplementor, newtype::Newtype,
+};
+use quote::{format_ident, quote_spanned};
+
+use crate::{
+    derive_flags::{make_bin_ops, make_fields, make_index, make_methods, make_unary_ops},
+    rhai_method::RhaiMethod,
+};
+
+/// Generates rhai wrappers, named after the wrapped type with a `Rhai` prefix, i.e. `RhaiVec3` for `Vec3`.
+///
+/// Rhai values are cloned freely, so the wrapped type must have the `Clone` flag.
+/// Methods, fields and operators are registered with the wrapper via `CustomType`, static functions and constants
+/// with the static module of `RhaiWrapper`, both are registered with `Engine::register_rhai_wrapper`.
+///
+/// Arguments are converted with `FromRhaiProxy` and outputs with `ToRhaiProxy`, callbacks are received as function pointers.
+/// Methods taking or returning reflected values, slices, arrays or maps are not exposed to rhai
+pub(crate) struct RhaiImplementor;
+
+impl WrapperImplementor for RhaiImplementor {
+    type Function = RhaiMethod;
+
+    fn module_name() -> &'static str {
+        "rhai"
+    }
+
+    fn generate_newtype_definition(
+        &mut self,
+        newtype: &Newtype,
+    ) -> std::result::Result<TokenStream, syn::Error> {
+        let newtype_name = wrapper_type(newtype);
+        let base_type = &newtype.args.base_type_ident;
+
+        if !has_flag(newtype, "Clone") {
+            return Err(syn::Error::new_spanned(
+                &newtype.args.base_type,
+                "Rhai values are cloned, types wrapped for rhai need the `Clone` flag",
+            ));
+        }
+
+        let mut definition = quote_spanned! {newtype.span()=>
+            bevy_script_api::make_script_wrapper!(#base_type as #newtype_name with Clone);
+        };
+
+        if has_flag(newtype, "Debug") {
+            definition = quote_spanned! {newtype.span()=>
+                #definition
+                impl std::fmt::Debug for #newtype_name {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+                        self.val(|s| s.fmt(f)).unwrap_or_else(|_| f.write_str("Error while retrieving reference in `std::fmt::Debug`."))
+                    }
+                }
+            }
+        }
+
+        if has_flag(newtype, "Display") {
+            definition = quote_spanned! {newtype.span()=>
+                #definition
+                impl std::fmt::Display for #newtype_name {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+                        self.val(|s| std::fmt::Display::fmt(s, f)).unwrap_or_else(|_| f.write_str("Error while retrieving reference in `std::fmt::Display`."))
+                    }
+                }
+            }
+        }
+
+        Ok(definition)
+    }
+
+    fn generate_newtype_implementation<'a, I: Iterator<Item = &'a Self::Function>>(
+        &mut self,
+        newtype: &'a Newtype,
+        functions: I,
+    ) -> std::result::Result<TokenStream, syn::Error> {
+        let wrapper_type = wrapper_type(newtype);
+        let wrapped_type = &newtype.args.base_type_ident;
+        let wrapped_type_string = wrapped_type.to_string();
+        let rhai = quote::quote!(bevy_mod_scripting_rhai::rhai);
+
+        // sequences of numbers can be assigned directly, i.e. `transform.translation = [1.0, 2.0, 3.0]`
+        let (apply_sequence, from_sequence) = if has_flag(newtype, "Sequence") {
+            (
+                quote_spanned! {newtype.span()=>
+                    else if new_val.is::<#rhai::Array>() {
+                        self_.set_val(bevy_script_api::rhai::std::sequence_from_array::<#wrapped_type>(new_val.cast::<#rhai::Array>())?)?;
+                        Ok(())
+                    }
+                },
+                quote_spanned! {newtype.span()=>
+                    else if self_.is::<#rhai::Array>() {
+                        bevy_script_api::rhai::std::sequence_from_array(self_.cast::<#rhai::Array>())
+                    }
+                },
+            )
+        } else {
+            Default::default()
+        };
+
+        let (statics, methods) = functions.partition::<Vec<_>, _>(|f| f.is_static());
+        let methods = methods.iter().map(|f| f.to_call_expr("builder"));
+        let statics = statics.iter().map(|f| f.to_call_expr("module"));
+
+        Ok(quote_spanned! {newtype.span()=>
+            #[allow(deprecated,unused_parens,unused_variables,clippy::all)]
+            impl #rhai::CustomType for #wrapper_type {
+                fn build(mut builder: #rhai::TypeBuilder<Self>) {
+                    builder.with_name(#wrapped_type_string);
+                    #(#methods)*
+                }
+            }
+
+            #[allow(deprecated,unused_parens,unused_variables,clippy::all)]
+            impl bevy_script_api::rhai::RhaiWrapper for #wrapper_type {
+                const NAME: &'static str = #wrapped_type_string;
+
+                fn static_module() -> Result<#rhai::Module, Box<#rhai::EvalAltResult>> {
+                    let mut module = #rhai::Module::new();
+                    #(#statics)*
+                    Ok(module)
+                }
+            }
+
+            impl bevy_script_api::rhai::RhaiProxyable for #wrapped_type {
+                fn ref_to_rhai(self_: bevy_script_api::script_ref::ScriptRef) -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> {
+                    Ok(#rhai::Dynamic::from(#wrapper_type::new_ref(self_)))
+                }
+
+                fn apply_rhai(self_: &mut bevy_script_api::script_ref::ScriptRef, new_val: #rhai::Dynamic) -> Result<(), Box<#rhai::EvalAltResult>> {
+                    if new_val.is::<#wrapper_type>() {
+                        let other = new_val.cast::<#wrapper_type>();
+                        other.apply_self_to_base(self_)?;
+                        Ok(())
+                    } #apply_sequence else {
+                        Err(Box::new(#rhai::EvalAltResult::ErrorMismatchDataType(
+                            #wrapped_type_string.to_owned(),
+                            new_val.type_name().to_owned(),
+                            #rhai::Position::NONE,
+                        )))
+                    }
+                }
+            }
+
+            impl bevy_script_api::rhai::FromRhaiProxy for #wrapped_type {
+                fn from_rhai_proxy(self_: #rhai::Dynamic) -> Result<Self, Box<#rhai::EvalAltResult>> {
+                    if self_.is::<#wrapper_type>() {
+                        Ok(self_.cast::<#wrapper_type>().inner()?)
+                    } #from_sequence else {
+                        Err(Box::new(#rhai::EvalAltResult::ErrorMismatchDataType(
+                            #wrapped_type_string.to_owned(),
+                            self_.type_name().to_owned(),
+                            #rhai::Position::NONE,
+                        )))
+                    }
+                }
+            }
+
+            impl bevy_script_api::rhai::ToRhaiProxy for #wrapped_type {
+                fn to_rhai_proxy(self) -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> {
+                    Ok(#rhai::Dynamic::from(#wrapper_type::new(self)))
+                }
+            }
+        })
+    }
+
+    fn generate_derive_flag_functions<'a, I: Iterator<Item = &'a DeriveFlag>>(
+        &mut self,
+        new_type: &'a Newtype,
+        mut derive_flags: I,
+    ) -> Result<Vec<RhaiMethod>, syn::Error> {
+        let mut out: Vec<Self::Function> = Default::default();
+        let wrapper_type = wrapper_type(new_type);
+        let wrapped_type = &new_type.args.base_type_ident;
+        let rhai = quote::quote!(bevy_mod_scripting_rhai::rhai);
+
+        // `Display` output is preferred for `to_string` over `Debug` output
+        let has_display = has_flag(new_type, "Display");
+
+        derive_flags.try_for_each(|v| {
+            match v {
+                DeriveFlag::Debug{ident} => {
+                    out.push(parse_quote_spanned!{ident.span()=>
+                        fn "to_debug" => |s: &mut #wrapper_type| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> { Ok(format!("{:?}",s).into()) }
+                    });
+                    if !has_display {
+                        out.push(parse_quote_spanned!{ident.span()=>
+                            fn "to_string" => |s: &mut #wrapper_type| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> { Ok(format!("{:?}",s).into()) }
+                        });
+                    }
+                },
+                DeriveFlag::Display{ident} => out.push(parse_quote_spanned!{ident.span()=>
+                    fn "to_string" => |s: &mut #wrapper_type| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> { Ok(format!("{}",s).into()) }
+                }),
+                DeriveFlag::DefaultToString{ident} => {
+                    let name = format!("<{wrapped_type}>");
+                    out.push(parse_quote_spanned!{ident.span()=>
+                        fn "to_string" => |s: &mut #wrapper_type| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> { Ok(#name.into()) }
+                    })
+                },
+                DeriveFlag::PartialEq{ident} => {
+                    out.push(parse_quote_spanned!{ident.span()=>
+                        fn "==" => |s: &mut #wrapper_type, o: #wrapper_type| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> { Ok(s.val(|s| o.val(|o| s == o))??.into()) }
+                    });
+                    out.push(parse_quote_spanned!{ident.span()=>
+                        fn "!=" => |s: &mut #wrapper_type, o: #wrapper_type| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> { Ok(s.val(|s| o.val(|o| s != o))??.into()) }
+                    });
+                },
+                DeriveFlag::PartialOrd{ident} => {
+                    for op in ["<", "<=", ">", ">="] {
+                        let operator: syn::BinOp = syn::parse_str(op)?;
+                        out.push(parse_quote_spanned!{ident.span()=>
+                            fn #op => |s: &mut #wrapper_type, o: #wrapper_type| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> { Ok(s.val(|s| o.val(|o| s #operator o))??.into()) }
+                        });
+                    }
+                },
+                // the `FromRhaiProxy` implementation is always generated, since the flag is required
+                DeriveFlag::Clone{..} => {},
+                DeriveFlag::Sequence{ident} => out.push(parse_quote_spanned!{ident.span()=>
+                    /// Converts this value into an array of its elements, i.e. `[x, y, z]`
+                    fn "to_array" => |s: &mut #wrapper_type| -> Result<#rhai::Dynamic, Box<#rhai::EvalAltResult>> {
+                        Ok(bevy_script_api::rhai::std::sequence_to_array(&s.inner()?)?.into())
+                    }
+                }),
+                flag @ DeriveFlag::Methods {..} => {
+                    make_methods(flag, new_type, &mut out);
+                },
+                flag @ DeriveFlag::BinOps {..} =>  {
+                    make_bin_ops(flag, new_type, &mut out)?;
+                },
+                flag @ DeriveFlag::UnaryOps {..} => {
+                    make_unary_ops(flag, new_type, &mut out)?;
+                },
+                flag @ DeriveFlag::Fields {..} => {
+                    make_fields(flag, new_type, &mut out)?;
+                },
+                flag @ DeriveFlag::Index {..} => {
+                    make_index(flag, new_type, &mut out)?;
+                },
+            };
+            Ok::<(),syn::Error>(())
+        })?;
+
+        Ok(out)
+    }
+
+    fn generate_newtype_functions(
+        &mut self,
+        new_type: &Newtype,
+    ) -> Result<Vec<RhaiMethod>, syn::Error> {
+        let rhai_block = new_type
+            .impl_blocks
+            .iter()
+            .find(|block| block.label == "rhai");
+
+        if let Some(block) = rhai_block {
+            let functions = &block.functions;
+            let tokens: Punctuated<RhaiMethod, Token![;]> =
+                parse_quote_spanned!(block.span()=>#functions);
+
+            Ok(tokens.into_iter().collect())
+        } else {
+            Ok(Default::default())
+        }
+    }
+}
+
+/// The name of the rhai wrapper of the given newtype, i.e. `RhaiVec3`
+pub(crate) fn wrapper_type(new_type: &Newtype) -> Ident {
+    format_ident!(
+        "Rhai{}",
+        new_type.args.base_type_ident,
+        span = new_type.args.base_type_ident.span()
+    )
+}
+
+/// Returns true if the newtype has the flag of the given name
+fn has_flag(new_type: &Newtype, flag: &str) -> bool {
+    new_type.args.flags.iter().any(|f| f.to_str() == flag)
+}
@@ -1,29 +1,165 @@
-use bevy_mod_scripting_common::implementor::WrapperFunction;
+use bevy_mod_scripting_common::{implementor::WrapperFunction, utils::EmptyToken};
 use proc_macro2::TokenStream;
-use quote::ToTokens;
+use quote::{format_ident, quote_spanned, ToTokens};
 use syn::{
     parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
     *,
 };
+
 impl WrapperFunction for RhaiMethod {}
 
+/// How a function is exposed to rhai
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum RhaiMethodKind {
+    /// A method or operator taking the receiver as its first argument, i.e. `fn "dot"` or `fn "+"`
+    Fn,
+    /// A function of the static module named after the wrapped type, i.e. `static fn "new"` called as `Vec3::new(..)`
+    Static,
+    /// A constant of the static module named after the wrapped type, i.e. `const "ZERO"` read as `Vec3::ZERO`.
+    /// The closure takes no arguments and is evaluated once when the module is built
+    Const,
+    /// A property getter, i.e. `get "x"`
+    Get,
+    /// A property setter, i.e. `set "x"`
+    Set,
+    /// An indexer getter, `index get`
+    IndexGet,
+    /// An indexer setter, `index set`
+    IndexSet,
+}
+
+/// A function registered with rhai, in the syntax of `rhai impl {..}` blocks:
+///
+/// ```rust,ignore
+/// /// Docs
+/// fn "name" => |s: &mut RhaiMyType, a: Dynamic| -> Result<Dynamic, Box<EvalAltResult>> { .. };
+/// static fn "name" => |a: Dynamic| -> Result<Dynamic, Box<EvalAltResult>> { .. };
+/// const "NAME" => || -> Result<Dynamic, Box<EvalAltResult>> { .. };
+/// get "field" => |s: &mut RhaiMyType| -> Result<Dynamic, Box<EvalAltResult>> { .. };
+/// set "field" => |s: &mut RhaiMyType, v: Dynamic| -> Result<(), Box<EvalAltResult>> { .. };
+/// index get => |s: &mut RhaiMyType, key: Dynamic| -> Result<Dynamic, Box<EvalAltResult>> { .. };
+/// index set => |s: &mut RhaiMyType, key: Dynamic, v: Dynamic| -> Result<(), Box<EvalAltResult>> { .. }
+/// ```
+///
+/// All closures are fallible and must have their argument and return types spelled out,
+/// `fn` and `static fn` closures may take a `NativeCallContext` before their other arguments
 pub(crate) struct RhaiMethod {
-    pub method: ItemFn,
+    pub docstring: Vec<Attribute>,
+    pub kind: RhaiMethodKind,
+    pub kind_span: proc_macro2::Span,
+    pub name: Option<LitStr>,
+    pub arrow: Token![=>],
+    pub closure: ExprClosure,
+}
+
+impl RhaiMethod {
+    /// Returns true if this function belongs to the static module rather than the type
+    pub fn is_static(&self) -> bool {
+        matches!(self.kind, RhaiMethodKind::Static | RhaiMethodKind::Const)
+    }
+
+    /// Produces the statement registering this function, with either the `TypeBuilder` or the static `Module` of the given name
+    pub fn to_call_expr(&self, receiver: &'static str) -> TokenStream {
+        let receiver = format_ident!("{receiver}", span = self.span());
+        let name = &self.name;
+        let closure = &self.closure;
+        match self.kind {
+            RhaiMethodKind::Fn => quote_spanned! {self.span()=>
+                #receiver.with_result_fn(#name, #closure);
+            },
+            RhaiMethodKind::Static => quote_spanned! {self.span()=>
+                #receiver.set_native_fn(#name, #closure);
+            },
+            RhaiMethodKind::Const => quote_spanned! {self.span()=>
+                #receiver.set_var(#name, (#closure)()?);
+            },
+            RhaiMethodKind::Get => quote_spanned! {self.span()=>
+                #receiver.with_get_result(#name, #closure);
+            },
+            RhaiMethodKind::Set => quote_spanned! {self.span()=>
+                #receiver.with_set_result(#name, #closure);
+            },
+            RhaiMethodKind::IndexGet => quote_spanned! {self.span()=>
+                #receiver.with_indexer_get_result(#closure);
+            },
+            RhaiMethodKind::IndexSet => quote_spanned! {self.span()=>
+                #receiver.with_indexer_set_result(#closure);
+            },
+        }
+    }
 }
 
 impl Parse for RhaiMethod {
     fn parse(input: ParseStream) -> Result<Self> {
+        let docstring = Attribute::parse_outer(input)?;
+        let kind_span = input.span();
+        let kind = if input.peek(Token![static]) {
+            input.parse::<Token![static]>()?;
+            input.parse::<Token![fn]>()?;
+            RhaiMethodKind::Static
+        } else if input.peek(Token![fn]) {
+            input.parse::<Token![fn]>()?;
+            RhaiMethodKind::Fn
+        } else if input.peek(Token![const]) {
+            input.parse::<Token![const]>()?;
+            RhaiMethodKind::Const
+        } else {
+            let ident: Ident = input.parse()?;
+            match ident.to_string().as_str() {
+                "get" => RhaiMethodKind::Get,
+                "set" => RhaiMethodKind::Set,
+                "index" => {
+                    let accessor: Ident = input.parse()?;
+                    match accessor.to_string().as_str() {
+                        "get" => RhaiMethodKind::IndexGet,
+                        "set" => RhaiMethodKind::IndexSet,
+                        _ => return Err(Error::new_spanned(accessor, "Expected `get` or `set`")),
+                    }
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        ident,
+                        "Expected one of: [fn,static fn,const,get,set,index get,index set]",
+                    ))
+                }
+            }
+        };
+
         Ok(Self {
-            method: input.parse()?,
+            docstring,
+            name: if matches!(kind, RhaiMethodKind::IndexGet | RhaiMethodKind::IndexSet) {
+                None
+            } else {
+                Some(input.parse()?)
+            },
+            kind,
+            kind_span,
+            arrow: input.parse()?,
+            closure: input.parse()?,
         })
     }
 }
 
 impl ToTokens for RhaiMethod {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let method = &self.method;
+        let docstring: Punctuated<Attribute, EmptyToken> = self.docstring.iter().cloned().collect();
+        let kind = match self.kind {
+            RhaiMethodKind::Fn => quote_spanned!(self.kind_span=>fn),
+            RhaiMethodKind::Static => quote_spanned!(self.kind_span=>static fn),
+            RhaiMethodKind::Const => quote_spanned!(self.kind_span=>const),
+            RhaiMethodKind::Get => quote_spanned!(self.kind_span=>get),
+            RhaiMethodKind::Set => quote_spanned!(self.kind_span=>set),
+            RhaiMethodKind::IndexGet => quote_spanned!(self.kind_span=>index get),
+            RhaiMethodKind::IndexSet => quote_spanned!(self.kind_span=>index set),
+        };
+        let name = &self.name;
+        let arrow = &self.arrow;
+        let closure = &self.closure;
         tokens.extend(quote::quote! {
-            #method
+            #docstring
+            #kind #name #arrow #closure
         })
     }
 }